@@ -16,6 +16,10 @@ fn bench_html(c: &mut Criterion) {
     ("medium", "<div>Hello</div>".repeat(100)),
     ("large", "<div>Hello</div>".repeat(1000)),
     ("superlarge", "<div>Hello</div>".repeat(2000)),
+    (
+      "attributes",
+      r#"<input type="text" name=a data-id='1' class="x y" disabled>"#.repeat(1000),
+    ),
     (
       "wikipedia",
       rt.block_on(async {
@@ -0,0 +1,101 @@
+use oxc_diagnostics::LabeledSpan;
+use serde_json::{Map, Value, json};
+
+use crate::{FileDiagnostics, LineIndex, code, primary_label, severity};
+
+/// Render diagnostics to a JSON array, one object per diagnostic.
+///
+/// ```json
+/// {
+///   "path": "index.html",
+///   "code": "html-parser(orphan-end-tag)",
+///   "severity": "error",
+///   "message": "Unexpected closing tag: </span>",
+///   "line": 2,
+///   "column": 1,
+///   "labels": [
+///     {
+///       "start": 4, "end": 11,
+///       "line": 2, "column": 1, "endLine": 2, "endColumn": 8,
+///       "primary": true,
+///       "message": "no matching opening tag"
+///     }
+///   ]
+/// }
+/// ```
+///
+/// `line` and `column` are the start of the primary label, and are missing for a diagnostic
+/// without labels, like `code`, `help` and `url` when the diagnostic has none. Offsets are in
+/// bytes, lines and columns are 1-based and count characters.
+pub fn to_json(files: &[FileDiagnostics]) -> Value {
+  let mut output = Vec::new();
+
+  for file in files {
+    let index = LineIndex::new(file.source_text);
+    for diagnostic in file.diagnostics {
+      let mut object = Map::new();
+      object.insert("path".into(), file.path.into());
+      if let Some(code) = code(diagnostic) {
+        object.insert("code".into(), code.into());
+      }
+      object.insert("severity".into(), severity(diagnostic).into());
+      object.insert("message".into(), diagnostic.message.as_ref().into());
+      if let Some(help) = &diagnostic.help {
+        object.insert("help".into(), help.as_ref().into());
+      }
+      if let Some(url) = &diagnostic.url {
+        object.insert("url".into(), url.as_ref().into());
+      }
+      if let Some(label) = primary_label(diagnostic) {
+        let position = index.position(label.offset());
+        object.insert("line".into(), position.line.into());
+        object.insert("column".into(), position.column.into());
+      }
+      let labels = diagnostic.labels.iter().flatten();
+      object.insert(
+        "labels".into(),
+        labels.map(|label| label_to_json(&index, label)).collect(),
+      );
+      output.push(Value::Object(object));
+    }
+  }
+
+  Value::Array(output)
+}
+
+fn label_to_json(index: &LineIndex, label: &LabeledSpan) -> Value {
+  let start = index.position(label.offset());
+  let end = index.position(label.offset() + label.len());
+  let mut object = json!({
+    "start": label.offset(),
+    "end": label.offset() + label.len(),
+    "line": start.line,
+    "column": start.column,
+    "endLine": end.line,
+    "endColumn": end.column,
+    "primary": label.primary(),
+  });
+  if let Some(message) = label.label() {
+    object["message"] = message.into();
+  }
+  object
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+  use oxc_allocator::Allocator;
+
+  use crate::{FileDiagnostics, test::diagnostics, to_json};
+
+  #[test]
+  fn json() {
+    const HTML: &str = "<section>\n  <p>café</section>\n</span>";
+
+    let allocator = Allocator::default();
+    let diagnostics = diagnostics(&allocator, HTML);
+    let json = to_json(&[FileDiagnostics::new("index.html", HTML, &diagnostics)]);
+
+    assert_snapshot!(serde_json::to_string_pretty(&json).unwrap());
+  }
+}
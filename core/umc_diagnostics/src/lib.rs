@@ -0,0 +1,102 @@
+//! Machine-readable output of diagnostics.
+//!
+//! Diagnostics only carry byte offsets, so they are rendered together with the source they
+//! refer to, as [`FileDiagnostics`]:
+//!
+//! - [`to_json`] renders a flat array of diagnostics, for editor problem matchers and scripts
+//! - [`to_sarif`] renders a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//!   log, for GitHub code scanning
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_diagnostics::{FileDiagnostics, to_json};
+//! use umc_html_parser::CreateHtml;
+//! use umc_parser::Parser;
+//!
+//! let allocator = Allocator::default();
+//! let source_text = "<p>\n</span>";
+//! let parser = Parser::html(&allocator, source_text);
+//! let result = parser.parse();
+//!
+//! let json = to_json(&[FileDiagnostics::new("index.html", source_text, &result.errors)]);
+//! assert_eq!(json[0]["code"], "html-parser(orphan-end-tag)");
+//! assert_eq!(json[0]["line"], 2);
+//! assert_eq!(json[0]["column"], 1);
+//! ```
+
+mod json;
+mod position;
+mod sarif;
+
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic, Severity};
+
+pub use json::to_json;
+pub use position::{LineIndex, Position};
+pub use sarif::{Tool, to_sarif};
+
+/// The diagnostics of a file, with its source text to locate them.
+#[derive(Debug, Clone, Copy)]
+pub struct FileDiagnostics<'a> {
+  /// Path of the file, as shown to the user, e.g. relative to the repository root
+  pub path: &'a str,
+  /// Source text the offsets of the diagnostics refer to
+  pub source_text: &'a str,
+  pub diagnostics: &'a [OxcDiagnostic],
+}
+
+impl<'a> FileDiagnostics<'a> {
+  pub const fn new(path: &'a str, source_text: &'a str, diagnostics: &'a [OxcDiagnostic]) -> Self {
+    Self {
+      path,
+      source_text,
+      diagnostics,
+    }
+  }
+}
+
+/// The error code of a diagnostic, like `html-parser(orphan-end-tag)`.
+fn code(diagnostic: &OxcDiagnostic) -> Option<String> {
+  diagnostic
+    .code
+    .is_some()
+    .then(|| diagnostic.code.to_string())
+}
+
+fn severity(diagnostic: &OxcDiagnostic) -> &'static str {
+  match diagnostic.severity {
+    Severity::Error => "error",
+    Severity::Warning => "warning",
+    Severity::Advice => "advice",
+  }
+}
+
+/// The label a diagnostic is located at: the primary one, or the first one.
+fn primary_label(diagnostic: &OxcDiagnostic) -> Option<&LabeledSpan> {
+  let labels = diagnostic.labels.as_deref()?;
+  labels
+    .iter()
+    .find(|label| label.primary())
+    .or_else(|| labels.first())
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use oxc_diagnostics::OxcDiagnostic;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  /// Diagnostics of the parser, and one without code nor labels
+  pub fn diagnostics(allocator: &Allocator, source_text: &'static str) -> Vec<OxcDiagnostic> {
+    let parser = Parser::html(allocator, source_text);
+    let mut errors = parser.parse().errors;
+    errors.push(
+      OxcDiagnostic::warn("Something is off")
+        .with_help("Have a look")
+        .with_url("https://example.com/off"),
+    );
+    errors
+  }
+}
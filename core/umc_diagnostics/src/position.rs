@@ -0,0 +1,70 @@
+/// A 1-based line and column in a source text.
+///
+/// Columns are counted in characters, so that a position is the same whatever the encoding
+/// the source is shown in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+  pub line: usize,
+  pub column: usize,
+}
+
+/// Start offsets of the lines of a source text, to convert byte offsets into [`Position`]s.
+///
+/// Lines end with `\n`, a `\r` before it belongs to the line it ends.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+  source_text: &'a str,
+  line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+  pub fn new(source_text: &'a str) -> Self {
+    let line_starts = std::iter::once(0)
+      .chain(source_text.match_indices('\n').map(|(index, _)| index + 1))
+      .collect();
+    Self {
+      source_text,
+      line_starts,
+    }
+  }
+
+  /// The position of a byte offset.
+  ///
+  /// Offsets past the end of the source are clamped to it, and offsets inside a character
+  /// count as that character.
+  pub fn position(&self, offset: usize) -> Position {
+    let mut offset = offset.min(self.source_text.len());
+    while !self.source_text.is_char_boundary(offset) {
+      offset -= 1;
+    }
+    let line = self.line_starts.partition_point(|start| *start <= offset);
+    let start = self.line_starts[line - 1];
+    Position {
+      line,
+      column: self.source_text[start..offset].chars().count() + 1,
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{LineIndex, Position};
+
+  #[test]
+  fn position() {
+    let index = LineIndex::new("ab\r\ncé\nd");
+    let position = |offset| {
+      let Position { line, column } = index.position(offset);
+      (line, column)
+    };
+
+    assert_eq!(position(0), (1, 1));
+    assert_eq!(position(2), (1, 3));
+    assert_eq!(position(4), (2, 1));
+    // é is two bytes
+    assert_eq!(position(7), (2, 3));
+    assert_eq!(position(6), (2, 2));
+    assert_eq!(position(8), (3, 1));
+    assert_eq!(position(100), (3, 2));
+  }
+}
@@ -0,0 +1,172 @@
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic, Severity};
+use serde_json::{Value, json};
+
+use crate::{FileDiagnostics, LineIndex, code, primary_label};
+
+/// The tool reporting the diagnostics of a SARIF log.
+#[derive(Debug, Clone, Copy)]
+pub struct Tool<'a> {
+  pub name: &'a str,
+  pub version: &'a str,
+  /// Home page of the tool
+  pub information_uri: Option<&'a str>,
+}
+
+impl<'a> Tool<'a> {
+  pub const fn new(name: &'a str, version: &'a str) -> Self {
+    Self {
+      name,
+      version,
+      information_uri: None,
+    }
+  }
+}
+
+/// Render diagnostics to a SARIF 2.1.0 log with a single run.
+///
+/// Every error code becomes a rule of the tool, and every diagnostic a result located at
+/// its primary label, the other labels being related locations. Paths are used as is for
+/// the artifact URIs, so they should be relative to the repository root for GitHub code
+/// scanning. Columns are counted in characters (`unicodeCodePoints`).
+pub fn to_sarif(tool: &Tool, files: &[FileDiagnostics]) -> Value {
+  let mut rules: Vec<(String, Option<&str>)> = Vec::new();
+  let mut results = Vec::new();
+
+  for file in files {
+    let index = LineIndex::new(file.source_text);
+    for diagnostic in file.diagnostics {
+      let mut result = result_to_sarif(file.path, &index, diagnostic);
+      if let Some(code) = code(diagnostic) {
+        let rule_index = rules
+          .iter()
+          .position(|(id, _)| *id == code)
+          .unwrap_or_else(|| {
+            rules.push((code.clone(), diagnostic.url.as_deref()));
+            rules.len() - 1
+          });
+        result["ruleId"] = code.into();
+        result["ruleIndex"] = rule_index.into();
+      }
+      results.push(result);
+    }
+  }
+
+  let rules: Vec<Value> = rules
+    .into_iter()
+    .map(|(id, url)| {
+      let mut rule = json!({ "id": id });
+      if let Some(url) = url {
+        rule["helpUri"] = url.into();
+      }
+      rule
+    })
+    .collect();
+  let mut driver = json!({
+    "name": tool.name,
+    "version": tool.version,
+    "rules": rules,
+  });
+  if let Some(uri) = tool.information_uri {
+    driver["informationUri"] = uri.into();
+  }
+
+  json!({
+    "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+    "version": "2.1.0",
+    "runs": [{
+      "tool": { "driver": driver },
+      "columnKind": "unicodeCodePoints",
+      "results": results,
+    }],
+  })
+}
+
+fn result_to_sarif(path: &str, index: &LineIndex, diagnostic: &OxcDiagnostic) -> Value {
+  let level = match diagnostic.severity {
+    Severity::Error => "error",
+    Severity::Warning => "warning",
+    Severity::Advice => "note",
+  };
+  let mut text = diagnostic.message.to_string();
+  if let Some(help) = &diagnostic.help {
+    text = format!("{text}\n{help}");
+  }
+
+  let primary = primary_label(diagnostic);
+  let location = json!({ "physicalLocation": physical_location(path, index, primary) });
+  let related: Vec<Value> = diagnostic
+    .labels
+    .iter()
+    .flatten()
+    .filter(|label| !primary.is_some_and(|primary| std::ptr::eq(*label, primary)))
+    .enumerate()
+    .map(|(id, label)| {
+      let mut location = json!({
+        "id": id,
+        "physicalLocation": physical_location(path, index, Some(label)),
+      });
+      if let Some(message) = label.label() {
+        location["message"] = json!({ "text": message });
+      }
+      location
+    })
+    .collect();
+
+  let mut result = json!({
+    "level": level,
+    "message": { "text": text },
+    "locations": [location],
+  });
+  if !related.is_empty() {
+    result["relatedLocations"] = related.into();
+  }
+  result
+}
+
+fn physical_location(path: &str, index: &LineIndex, label: Option<&LabeledSpan>) -> Value {
+  let mut location = json!({ "artifactLocation": { "uri": path } });
+  if let Some(label) = label {
+    let start = index.position(label.offset());
+    let end = index.position(label.offset() + label.len());
+    location["region"] = json!({
+      "startLine": start.line,
+      "startColumn": start.column,
+      "endLine": end.line,
+      "endColumn": end.column,
+      "byteOffset": label.offset(),
+      "byteLength": label.len(),
+    });
+  }
+  location
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+  use oxc_allocator::Allocator;
+
+  use crate::{FileDiagnostics, Tool, test::diagnostics, to_sarif};
+
+  #[test]
+  fn sarif() {
+    const A: &str = "<section>\n  <p>café</section>";
+    const B: &str = "</span>";
+
+    let allocator = Allocator::default();
+    let a = diagnostics(&allocator, A);
+    let b = diagnostics(&allocator, B);
+    let tool = Tool {
+      information_uri: Some("https://github.com/liangmiQwQ/umc"),
+      ..Tool::new("umc", "0.0.0")
+    };
+    let sarif = to_sarif(
+      &tool,
+      &[
+        FileDiagnostics::new("a.html", A, &a),
+        FileDiagnostics::new("src/b.html", B, &b),
+      ],
+    );
+
+    assert_snapshot!(serde_json::to_string_pretty(&sarif).unwrap());
+  }
+}
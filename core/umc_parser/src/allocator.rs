@@ -0,0 +1,92 @@
+//! Reuse of an allocator across sequential parses.
+//!
+//! Every parse allocates its AST in an [`Allocator`]. Creating one per parse pays for the
+//! setup and teardown of the arena each time, which dominates short parses, e.g. one per
+//! request of a server. A [`ReusableAllocator`] keeps the memory of the arena, and resets
+//! it before each parse.
+//!
+//! # What invalidates a result
+//!
+//! A result borrows the allocator it was parsed into, so resetting the allocator invalidates
+//! every result parsed before, along with anything else allocated in it, e.g. the source
+//! text decoded by a parser. [`ReusableAllocator::reset`] takes `&mut self`, so the compiler
+//! rejects any use of a previous result after it: the guarantee needs no runtime check.
+//! Move what must outlive the next parse out of the arena first, e.g. by serializing it.
+
+use std::{borrow::Cow, ops::Deref};
+
+use oxc_allocator::Allocator;
+
+use crate::{LanguageParser, ParseResult, parse_source};
+
+/// An [`Allocator`] reset before each parse, keeping its memory.
+///
+/// # Example
+///
+/// ```ignore
+/// use umc_html_parser::Html;
+/// use umc_parser::allocator::ReusableAllocator;
+///
+/// let mut allocator = ReusableAllocator::new();
+/// let options = Default::default();
+/// for source_text in ["<p>a</p>", "<p>b</p>"] {
+///   let result = allocator.parse::<Html>(source_text, &options);
+///   println!("{} nodes", result.program.len());
+///   // `result` cannot be used after the next parse
+/// }
+/// ```
+#[derive(Default)]
+pub struct ReusableAllocator {
+  allocator: Allocator,
+}
+
+impl ReusableAllocator {
+  /// Create an empty allocator, which grows with the first parses.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Create an allocator with room for `capacity` bytes.
+  pub fn with_capacity(capacity: usize) -> Self {
+    Self {
+      allocator: Allocator::with_capacity(capacity),
+    }
+  }
+
+  /// Reset the allocator for a new parse, and borrow it.
+  ///
+  /// Results of the previous parses are invalidated, see the [module](self) documentation.
+  pub fn reset(&mut self) -> &Allocator {
+    self.allocator.reset();
+    &self.allocator
+  }
+
+  /// Reset the allocator, then parse the source text into it.
+  pub fn parse<'a, L: LanguageParser>(
+    &'a mut self,
+    source_text: &'a str,
+    options: &'a L::Option,
+  ) -> ParseResult<L::Result<'a>> {
+    parse_source::<L>(self.reset(), source_text, Cow::Borrowed(options))
+  }
+
+  /// Take the allocator back.
+  pub fn into_inner(self) -> Allocator {
+    self.allocator
+  }
+}
+
+impl From<Allocator> for ReusableAllocator {
+  fn from(allocator: Allocator) -> Self {
+    Self { allocator }
+  }
+}
+
+/// Read-only access, e.g. to the [capacity](Allocator::capacity) kept between parses.
+impl Deref for ReusableAllocator {
+  type Target = Allocator;
+
+  fn deref(&self) -> &Allocator {
+    &self.allocator
+  }
+}
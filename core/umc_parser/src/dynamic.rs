@@ -0,0 +1,170 @@
+//! Parsers of any language behind a trait object.
+//!
+//! Every [`LanguageParser`] is a distinct generic instantiation of [`Parser`](crate::Parser),
+//! which cannot be stored next to another. A [`DynLanguageParser`] erases the language: its
+//! result is an [`AnyProgram`], downcast back to the [`LanguageParser::Result`] of the
+//! language. [`DynParser`] implements it for any language, and plugins may implement it
+//! for parsers of their own.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxc_allocator::Allocator;
+//! use umc_html_parser::Html;
+//! use umc_parser::dynamic::{DynLanguageParser, DynParser};
+//!
+//! let parsers: Vec<Box<dyn DynLanguageParser>> = vec![Box::new(DynParser::<Html>::default())];
+//!
+//! let allocator = Allocator::default();
+//! let result = parsers[0].parse(&allocator, "<p>Hello</p>");
+//! let program = result.program.downcast_ref::<Html>().unwrap();
+//! ```
+
+use std::{any::TypeId, borrow::Cow};
+
+use oxc_allocator::Allocator;
+
+use crate::{LanguageParser, ParseResult, parse_source};
+
+/// A parser of a language, with the type of its result erased.
+///
+/// Parsers are `Send + Sync`, to be shared by the threads of a tool.
+pub trait DynLanguageParser: Send + Sync {
+  /// The [name](LanguageParser::NAME) of the language.
+  fn name(&self) -> &'static str;
+
+  /// The [file extensions](LanguageParser::EXTENSIONS) of the language.
+  fn extensions(&self) -> &'static [&'static str] {
+    &[]
+  }
+
+  /// The [MIME types](LanguageParser::MIME_TYPES) of the language.
+  fn mime_types(&self) -> &'static [&'static str] {
+    &[]
+  }
+
+  /// Parse the source text.
+  fn parse<'a>(
+    &'a self,
+    allocator: &'a Allocator,
+    source_text: &'a str,
+  ) -> ParseResult<AnyProgram<'a>>;
+}
+
+/// The [`DynLanguageParser`] of a [`LanguageParser`], with its options.
+pub struct DynParser<L: LanguageParser> {
+  /// Options of every parse
+  pub options: L::Option,
+}
+
+impl<L: LanguageParser> DynParser<L> {
+  /// Create a parser with options.
+  pub const fn new(options: L::Option) -> Self {
+    Self { options }
+  }
+}
+
+impl<L: LanguageParser> Default for DynParser<L> {
+  fn default() -> Self {
+    Self::new(L::Option::default())
+  }
+}
+
+impl<L> DynLanguageParser for DynParser<L>
+where
+  L: LanguageParser + 'static,
+  L::Option: Send + Sync,
+{
+  fn name(&self) -> &'static str {
+    L::NAME
+  }
+
+  fn extensions(&self) -> &'static [&'static str] {
+    L::EXTENSIONS
+  }
+
+  fn mime_types(&self) -> &'static [&'static str] {
+    L::MIME_TYPES
+  }
+
+  fn parse<'a>(
+    &'a self,
+    allocator: &'a Allocator,
+    source_text: &'a str,
+  ) -> ParseResult<AnyProgram<'a>> {
+    let result = parse_source::<L>(allocator, source_text, Cow::Borrowed(&self.options));
+    ParseResult {
+      program: AnyProgram::new::<L>(result.program),
+      errors: result.errors,
+      metrics: result.metrics,
+    }
+  }
+}
+
+/// The [`LanguageParser::Result`] of any language.
+///
+/// Results borrow the allocator, so they cannot be [`Any`](std::any::Any): they are
+/// identified by the type of their language instead.
+pub struct AnyProgram<'a>(Box<dyn TypedProgram<'a> + 'a>);
+
+impl<'a> AnyProgram<'a> {
+  /// Erase the type of the result of a language.
+  pub fn new<L: LanguageParser + 'static>(program: L::Result<'a>) -> Self {
+    Self(Box::new(Typed::<L>(program)))
+  }
+
+  /// The name of the language of the result.
+  pub fn language_name(&self) -> &'static str {
+    self.0.language_name()
+  }
+
+  /// Whether the result was produced by the language `L`.
+  pub fn is<L: LanguageParser + 'static>(&self) -> bool {
+    self.0.language() == TypeId::of::<L>()
+  }
+
+  /// Get the result, if it was produced by the language `L`.
+  pub fn downcast_ref<L: LanguageParser + 'static>(&self) -> Option<&L::Result<'a>> {
+    if !self.is::<L>() {
+      return None;
+    }
+    let typed =
+      std::ptr::from_ref::<dyn TypedProgram<'a> + 'a>(self.0.as_ref()).cast::<Typed<'a, L>>();
+    // SAFETY: `Typed<L>` is the only implementation of the private `TypedProgram` trait
+    // whose language is `L`.
+    #[allow(unsafe_code)]
+    Some(unsafe { &(*typed).0 })
+  }
+
+  /// Take the result, or get `self` back if it was not produced by the language `L`.
+  ///
+  /// # Errors
+  ///
+  /// Returns `self` if the result was produced by another language.
+  pub fn downcast<L: LanguageParser + 'static>(self) -> Result<L::Result<'a>, Self> {
+    if !self.is::<L>() {
+      return Err(self);
+    }
+    let typed = Box::into_raw(self.0).cast::<Typed<'a, L>>();
+    // SAFETY: see `downcast_ref`, the pointer comes from a `Box` of the same type.
+    #[allow(unsafe_code)]
+    Ok(unsafe { Box::from_raw(typed) }.0)
+  }
+}
+
+trait TypedProgram<'a> {
+  fn language(&self) -> TypeId;
+  fn language_name(&self) -> &'static str;
+}
+
+struct Typed<'a, L: LanguageParser>(L::Result<'a>);
+
+impl<'a, L: LanguageParser + 'static> TypedProgram<'a> for Typed<'a, L> {
+  fn language(&self) -> TypeId {
+    TypeId::of::<L>()
+  }
+
+  fn language_name(&self) -> &'static str {
+    L::NAME
+  }
+}
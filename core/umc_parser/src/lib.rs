@@ -16,9 +16,22 @@
 //! let result = parser.parse();
 //! ```
 
+use std::borrow::Cow;
+
 use oxc_allocator::Allocator;
-use oxc_diagnostics::OxcDiagnostic;
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use umc_span::Span;
+
+use crate::metrics::ParseMetrics;
 
+/// Reuse of an allocator across sequential parses.
+pub mod allocator;
+/// Parsers of any language behind a trait object.
+pub mod dynamic;
+/// Cost of a parse.
+pub mod metrics;
+/// Dispatch to a language by file extension or MIME type.
+pub mod registry;
 /// Source text tracking and navigation.
 pub mod source;
 /// Token types and utilities.
@@ -39,17 +52,30 @@ pub mod token;
 /// struct Html;
 ///
 /// impl LanguageParser for Html {
+///   const NAME: &'static str = "html";
+///   const EXTENSIONS: &'static [&'static str] = &["html", "htm"];
+///   const MIME_TYPES: &'static [&'static str] = &["text/html"];
+///
 ///   type Result<'a> = Program<'a>;
 ///   type Option = HtmlParserOption;
 ///   type Parser<'a> = HtmlParserImpl<'a>;
 /// }
 /// ```
 pub trait LanguageParser: Sized {
+  /// Name of the language, e.g. `html`.
+  const NAME: &'static str;
+  /// File extensions of the language, without the dot, used by the
+  /// [`LanguageRegistry`](registry::LanguageRegistry).
+  const EXTENSIONS: &'static [&'static str] = &[];
+  /// MIME types of the language, used by the [`LanguageRegistry`](registry::LanguageRegistry).
+  const MIME_TYPES: &'static [&'static str] = &[];
+
   /// The type of the parsed result (e.g., AST root node or node collection).
   /// Uses a lifetime parameter to support arena-allocated data.
   type Result<'a>;
-  /// Parser configuration options, must have a default implementation
-  type Option: Default;
+  /// Parser configuration options, must have a default implementation.
+  /// Parsers own their options or borrow them, see [`Parser::with_shared_options`].
+  type Option: Default + Clone;
   /// The concrete parser implementation for this language
   type Parser<'a>: ParserImpl<'a, Self>;
 }
@@ -64,8 +90,8 @@ pub trait ParserImpl<'a, T: LanguageParser> {
   /// # Parameters
   /// - `allocator`: Memory arena for allocating AST nodes
   /// - `source_text`: Source code to parse
-  /// - `options`: Language-specific parser options
-  fn new(allocator: &'a Allocator, source_text: &'a str, options: &'a T::Option) -> Self;
+  /// - `options`: Language-specific parser options, owned or shared with other parsers
+  fn new(allocator: &'a Allocator, source_text: &'a str, options: Cow<'a, T::Option>) -> Self;
 
   /// Parse the source text and return the result.
   ///
@@ -83,8 +109,8 @@ pub struct Parser<'a, T: LanguageParser> {
   pub allocator: &'a Allocator,
   /// The source code text to be parsed
   pub source_text: &'a str,
-  /// Language-specific parser configuration options
-  pub options: T::Option,
+  /// Language-specific parser configuration options, owned or shared with other parsers
+  pub options: Cow<'a, T::Option>,
 }
 
 /// Result of a parsing operation.
@@ -97,6 +123,34 @@ pub struct ParseResult<T> {
   pub program: T,
   /// Diagnostic errors encountered during parsing
   pub errors: Vec<OxcDiagnostic>,
+  /// Cost of the parse, `None` unless the parser was asked to collect it
+  pub metrics: Option<ParseMetrics>,
+}
+
+impl<T> ParseResult<T> {
+  /// Whether a diagnostic has the error severity, warnings aside.
+  pub fn has_errors(&self) -> bool {
+    self
+      .errors
+      .iter()
+      .any(|error| error.severity == Severity::Error)
+  }
+
+  /// Get the program, or the diagnostics if any of them is an error.
+  ///
+  /// Meant for validation, where a recovered program is of no use once the source is
+  /// known to be invalid.
+  ///
+  /// # Errors
+  ///
+  /// Returns every diagnostic, warnings included, if any of them is an error.
+  pub fn into_result(self) -> Result<T, Vec<OxcDiagnostic>> {
+    if self.has_errors() {
+      Err(self.errors)
+    } else {
+      Ok(self.program)
+    }
+  }
 }
 
 impl<'a, T: LanguageParser> Parser<'a, T> {
@@ -109,24 +163,129 @@ impl<'a, T: LanguageParser> Parser<'a, T> {
     Self {
       allocator,
       source_text,
-      options: T::Option::default(),
+      options: Cow::Owned(T::Option::default()),
     }
   }
 
   /// Override the parser option
   #[must_use]
   pub fn with_options(mut self, options: T::Option) -> Self {
-    self.options = options;
+    self.options = Cow::Owned(options);
+    self
+  }
+
+  /// Override the parser option with options borrowed for the lifetime of the result,
+  /// e.g. to share them between the parses of many threads without cloning them.
+  #[must_use]
+  pub fn with_shared_options(mut self, options: &'a T::Option) -> Self {
+    self.options = Cow::Borrowed(options);
     self
   }
 
-  /// Get the parse result.
+  /// Get the parse result, consuming the parser.
   ///
-  /// Takes `&'a self` to ensure the options reference has the same lifetime
-  /// as the allocator and source text, which is required for arena allocation.
-  pub fn parse(&'a self) -> ParseResult<T::Result<'a>> {
-    let parser = T::Parser::new(self.allocator, self.source_text, &self.options);
+  /// The result borrows the allocator and the source text, not the parser, so a parser can
+  /// be created and consumed in one expression.
+  ///
+  /// The result borrows the allocator: resetting it to parse again invalidates the result,
+  /// see [`ReusableAllocator`](allocator::ReusableAllocator).
+  ///
+  /// Source texts longer than [`MAX_SOURCE_LEN`] are parsed up to the limit, with an error.
+  pub fn parse(self) -> ParseResult<T::Result<'a>> {
+    parse_source::<T>(self.allocator, self.source_text, self.options)
+  }
+}
+
+/// The longest source text parsed, in bytes: offsets in the source are `u32`.
+pub const MAX_SOURCE_LEN: usize = u32::MAX as usize;
+
+/// Parse a source text with a language, up to [`MAX_SOURCE_LEN`] bytes.
+pub(crate) fn parse_source<'a, T: LanguageParser>(
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  options: Cow<'a, T::Option>,
+) -> ParseResult<T::Result<'a>> {
+  parse_within::<T>(allocator, source_text, options, MAX_SOURCE_LEN)
+}
+
+/// Parse the first `max_len` bytes of a source text, rounded down to a character, and
+/// report the rest as too long.
+fn parse_within<'a, T: LanguageParser>(
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  options: Cow<'a, T::Option>,
+  max_len: usize,
+) -> ParseResult<T::Result<'a>> {
+  if source_text.len() <= max_len {
+    return T::Parser::new(allocator, source_text, options).parse();
+  }
+  let mut end = max_len;
+  while !source_text.is_char_boundary(end) {
+    end -= 1;
+  }
+  let mut result = T::Parser::new(allocator, &source_text[..end], options).parse();
+  result
+    .errors
+    .insert(0, source_too_long(source_text.len(), max_len, end));
+  result
+}
+
+/// The error for a source text of `len` bytes over the limit of `max_len` bytes, parsed up
+/// to `end`, for parsers which read the source text themselves, like streaming ones.
+pub fn source_too_long(len: usize, max_len: usize, end: usize) -> OxcDiagnostic {
+  OxcDiagnostic::error(format!(
+    "Source text too long: {len} bytes, over the limit of {max_len} bytes"
+  ))
+  .with_label(Span::empty(u32::try_from(end).unwrap_or(u32::MAX)).label("not parsed from here"))
+  .with_help("Split the source text into smaller documents")
+}
+
+#[cfg(test)]
+mod test {
+  use std::borrow::Cow;
+
+  use oxc_allocator::Allocator;
+
+  use super::{LanguageParser, ParseResult, ParserImpl, parse_within};
+
+  /// A language whose program is the source text parsed.
+  struct Echo;
+
+  impl LanguageParser for Echo {
+    const NAME: &'static str = "echo";
+
+    type Result<'a> = &'a str;
+    type Option = ();
+    type Parser<'a> = EchoParser<'a>;
+  }
+
+  struct EchoParser<'a>(&'a str);
+
+  impl<'a> ParserImpl<'a, Echo> for EchoParser<'a> {
+    fn new(_: &'a Allocator, source_text: &'a str, _: Cow<'a, ()>) -> Self {
+      Self(source_text)
+    }
+
+    fn parse(self) -> ParseResult<&'a str> {
+      ParseResult {
+        program: self.0,
+        errors: Vec::new(),
+        metrics: None,
+      }
+    }
+  }
+
+  #[test]
+  fn source_too_long() {
+    let allocator = Allocator::default();
+    let result = parse_within::<Echo>(&allocator, "abcd", Cow::Owned(()), 4);
+    assert_eq!(result.program, "abcd");
+    assert!(result.errors.is_empty());
 
-    parser.parse()
+    // cut before the character over the limit
+    let result = parse_within::<Echo>(&allocator, "ab\u{e9}cd", Cow::Owned(()), 3);
+    assert_eq!(result.program, "ab");
+    assert_eq!(result.errors.len(), 1);
+    assert!(result.errors[0].message.contains("6 bytes"));
   }
 }
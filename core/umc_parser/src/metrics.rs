@@ -0,0 +1,41 @@
+//! Cost of a parse, to monitor parsing in production.
+//!
+//! Collecting metrics is opt-in, as timing a parse has a cost of its own: a parser asked to
+//! collect them sets [`ParseResult::metrics`](crate::ParseResult::metrics), e.g. with the
+//! `collect_metrics` option of the HTML parser.
+
+use std::{collections::BTreeMap, time::Duration};
+
+/// Measurements of a parse.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ParseMetrics {
+  /// Length of the parsed source, in bytes
+  pub bytes: usize,
+  /// Time spent parsing, embedded languages included
+  pub duration: Duration,
+  /// Number of tokens read from the lexer, the end of file aside
+  pub tokens: usize,
+  /// Number of nodes of the AST by kind, e.g. `element`
+  pub nodes: BTreeMap<&'static str, usize>,
+  /// Bytes allocated in the arena by the parse, rounded to the chunks of the allocator
+  pub arena_bytes: usize,
+  /// Time spent parsing embedded languages, like the JavaScript of scripts
+  pub embedded_duration: Duration,
+}
+
+impl ParseMetrics {
+  /// Parsing speed, in bytes per second.
+  #[allow(clippy::cast_precision_loss)]
+  pub fn bytes_per_second(&self) -> f64 {
+    let seconds = self.duration.as_secs_f64();
+    if seconds == 0.0 {
+      return 0.0;
+    }
+    self.bytes as f64 / seconds
+  }
+
+  /// Total number of nodes of the AST.
+  pub fn node_count(&self) -> usize {
+    self.nodes.values().sum()
+  }
+}
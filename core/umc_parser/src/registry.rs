@@ -0,0 +1,129 @@
+//! Dispatch to a language by file extension, MIME type or name.
+//!
+//! A [`LanguageRegistry`] holds parsers of different languages, each with its own options,
+//! so tools handling several languages can look one up for a file and parse it without a
+//! match arm per extension. Languages are [`DynLanguageParser`]s, so the program of the
+//! result is an [`AnyProgram`](crate::dynamic::AnyProgram), downcast back to the
+//! [`LanguageParser::Result`] of the language.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use oxc_allocator::Allocator;
+//! use umc_html_parser::Html;
+//! use umc_parser::registry::LanguageRegistry;
+//!
+//! let registry = LanguageRegistry::new().with::<Html>();
+//! let language = registry.for_path("index.html".as_ref()).unwrap();
+//!
+//! let allocator = Allocator::default();
+//! let result = language.parse(&allocator, "<p>Hello</p>");
+//! let program = result.program.downcast_ref::<Html>().unwrap();
+//! ```
+
+use std::path::Path;
+
+use crate::{
+  LanguageParser,
+  dynamic::{DynLanguageParser, DynParser},
+};
+
+/// Languages registered with their options.
+///
+/// When several languages claim the same extension or MIME type, the one registered last
+/// is used.
+#[derive(Default)]
+pub struct LanguageRegistry {
+  languages: Vec<Box<dyn DynLanguageParser>>,
+}
+
+impl LanguageRegistry {
+  /// Create an empty registry.
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Register a language with its default options.
+  #[must_use]
+  pub fn with<L>(self) -> Self
+  where
+    L: LanguageParser + 'static,
+    L::Option: Send + Sync,
+  {
+    self.with_options::<L>(L::Option::default())
+  }
+
+  /// Register a language with options.
+  #[must_use]
+  pub fn with_options<L>(mut self, options: L::Option) -> Self
+  where
+    L: LanguageParser + 'static,
+    L::Option: Send + Sync,
+  {
+    self.register::<L>(options);
+    self
+  }
+
+  /// Register a language with options.
+  pub fn register<L>(&mut self, options: L::Option)
+  where
+    L: LanguageParser + 'static,
+    L::Option: Send + Sync,
+  {
+    self.register_dyn(Box::new(DynParser::<L>::new(options)));
+  }
+
+  /// Register a parser of any language, e.g. of a plugin.
+  pub fn register_dyn(&mut self, parser: Box<dyn DynLanguageParser>) {
+    self.languages.push(parser);
+  }
+
+  /// Every registered language, in registration order.
+  pub fn languages(&self) -> impl Iterator<Item = &dyn DynLanguageParser> {
+    self.languages.iter().map(AsRef::as_ref)
+  }
+
+  /// Get a language by its [name](LanguageParser::NAME).
+  pub fn for_name(&self, name: &str) -> Option<&dyn DynLanguageParser> {
+    self.find(|language| language.name() == name)
+  }
+
+  /// Get a language by file extension, without the dot and ignoring case, e.g. `html`.
+  pub fn for_extension(&self, extension: &str) -> Option<&dyn DynLanguageParser> {
+    self.find(|language| {
+      language
+        .extensions()
+        .iter()
+        .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+    })
+  }
+
+  /// Get a language by MIME type, ignoring case and parameters, e.g.
+  /// `text/html; charset=utf-8`.
+  pub fn for_mime_type(&self, mime_type: &str) -> Option<&dyn DynLanguageParser> {
+    let essence = mime_type.split(';').next().unwrap_or_default().trim();
+    self.find(|language| {
+      language
+        .mime_types()
+        .iter()
+        .any(|candidate| candidate.eq_ignore_ascii_case(essence))
+    })
+  }
+
+  /// Get a language by the extension of a path.
+  pub fn for_path(&self, path: &Path) -> Option<&dyn DynLanguageParser> {
+    self.for_extension(path.extension()?.to_str()?)
+  }
+
+  fn find(
+    &self,
+    predicate: impl Fn(&dyn DynLanguageParser) -> bool,
+  ) -> Option<&dyn DynLanguageParser> {
+    self
+      .languages
+      .iter()
+      .rev()
+      .map(AsRef::as_ref)
+      .find(|language| predicate(*language))
+  }
+}
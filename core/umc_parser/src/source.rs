@@ -1,3 +1,5 @@
+use memchr::memchr2_iter;
+
 /// Tracks the current position in source text during parsing.
 ///
 /// This struct maintains a pointer into the source text and provides methods
@@ -62,8 +64,9 @@ impl Source<'_> {
     self.source_text[self.pointer as usize..].starts_with(bytes)
   }
 
-  /// Check if the remaining source text starts with the given bytes
-  /// Ignore case, but you need to pass in the bytes in lowercase
+  /// Check if the remaining source text starts with the given bytes, ignoring ASCII case
+  ///
+  /// Only the length of `bytes` is compared, without allocating.
   ///
   /// ## Example
   ///
@@ -76,9 +79,27 @@ impl Source<'_> {
   /// ```
   #[inline]
   pub fn starts_with_lowercase(&self, bytes: &[u8]) -> bool {
-    self.source_text[self.pointer as usize..]
-      .to_ascii_lowercase()
-      .starts_with(bytes)
+    self
+      .rest()
+      .get(..bytes.len())
+      .is_some_and(|prefix| prefix.eq_ignore_ascii_case(bytes))
+  }
+
+  /// Find the given bytes in the remaining source text, ignoring ASCII case
+  ///
+  /// Returns the offset from the current pointer location.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_parser::source::Source;
+  ///
+  /// let source = Source::new("let a; </SCRIPT>");
+  /// assert_eq!(source.find_ignore_case(b"</script"), Some(7));
+  /// assert_eq!(source.find_ignore_case(b"</style"), None);
+  /// ```
+  pub fn find_ignore_case(&self, bytes: &[u8]) -> Option<usize> {
+    find_ignore_case(self.rest(), bytes)
   }
 
   /// Get the remaining source text which is after the current pointer location
@@ -132,3 +153,21 @@ impl Source<'_> {
     self.pointer += diff;
   }
 }
+
+/// Find `needle` in `haystack`, ignoring ASCII case.
+///
+/// Candidates are found with `memchr` on both cases of the first byte, so the search stays
+/// linear and vectorized like a case-sensitive one.
+pub fn find_ignore_case(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+  let Some((&first, rest)) = needle.split_first() else {
+    return Some(0);
+  };
+  let last_start = haystack.len().checked_sub(needle.len())?;
+
+  memchr2_iter(
+    first.to_ascii_lowercase(),
+    first.to_ascii_uppercase(),
+    &haystack[..=last_start],
+  )
+  .find(|&start| haystack[start + 1..start + needle.len()].eq_ignore_ascii_case(rest))
+}
@@ -602,6 +602,19 @@ impl Debug for Span {
   }
 }
 
+// Skip `_align` field in serialized output
+#[cfg(feature = "serialize")]
+impl serde::Serialize for Span {
+  fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::SerializeStruct;
+
+    let mut map = serializer.serialize_struct("Span", 2)?;
+    map.serialize_field("start", &self.start)?;
+    map.serialize_field("end", &self.end)?;
+    map.end()
+  }
+}
+
 /// Get the span for an AST node.
 pub trait GetSpan {
   /// Get the [`Span`] for an AST node.
@@ -0,0 +1,180 @@
+use std::{
+  collections::{HashMap, HashSet, VecDeque},
+  path::{Path, PathBuf},
+};
+
+/// Tracks which files depend on which other files.
+///
+/// Edges are recorded from a file to the files it includes (its dependencies).
+/// A reverse index is kept so that a change to a partial can be propagated
+/// to every file which includes it, directly or transitively.
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+  /// file -> files it depends on
+  dependencies: HashMap<PathBuf, HashSet<PathBuf>>,
+  /// file -> files depending on it
+  dependents: HashMap<PathBuf, HashSet<PathBuf>>,
+}
+
+impl DependencyGraph {
+  /// Replace the dependencies of `path` with `dependencies`.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use std::path::Path;
+  /// use umc_watch::DependencyGraph;
+  ///
+  /// let mut graph = DependencyGraph::default();
+  /// graph.set_dependencies(Path::new("index.html"), [Path::new("header.html").to_path_buf()]);
+  /// assert_eq!(graph.dependents(Path::new("header.html")).count(), 1);
+  /// ```
+  pub fn set_dependencies(&mut self, path: &Path, dependencies: impl IntoIterator<Item = PathBuf>) {
+    self.remove_dependencies(path);
+
+    let dependencies: HashSet<PathBuf> = dependencies.into_iter().collect();
+    for dependency in &dependencies {
+      self
+        .dependents
+        .entry(dependency.clone())
+        .or_default()
+        .insert(path.to_path_buf());
+    }
+
+    if !dependencies.is_empty() {
+      self.dependencies.insert(path.to_path_buf(), dependencies);
+    }
+  }
+
+  /// Forget the outgoing edges of `path`.
+  ///
+  /// Files which depend on `path` keep their edges, so they are still invalidated
+  /// when `path` is created again.
+  pub fn remove_dependencies(&mut self, path: &Path) {
+    if let Some(old) = self.dependencies.remove(path) {
+      for dependency in old {
+        if let Some(dependents) = self.dependents.get_mut(&dependency) {
+          dependents.remove(path);
+          if dependents.is_empty() {
+            self.dependents.remove(&dependency);
+          }
+        }
+      }
+    }
+  }
+
+  /// Get the files `path` depends on directly.
+  pub fn dependencies(&self, path: &Path) -> impl Iterator<Item = &Path> {
+    self
+      .dependencies
+      .get(path)
+      .into_iter()
+      .flatten()
+      .map(PathBuf::as_path)
+  }
+
+  /// Get the files depending on `path` directly.
+  pub fn dependents(&self, path: &Path) -> impl Iterator<Item = &Path> {
+    self
+      .dependents
+      .get(path)
+      .into_iter()
+      .flatten()
+      .map(PathBuf::as_path)
+  }
+
+  /// Get every file affected by a change of `changed`.
+  ///
+  /// The result contains the changed files themselves followed by their transitive
+  /// dependents in breadth-first order. Each file appears once, even with cycles.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use std::path::{Path, PathBuf};
+  /// use umc_watch::DependencyGraph;
+  ///
+  /// let mut graph = DependencyGraph::default();
+  /// graph.set_dependencies(Path::new("page.html"), [PathBuf::from("layout.html")]);
+  /// graph.set_dependencies(Path::new("layout.html"), [PathBuf::from("nav.html")]);
+  ///
+  /// let affected = graph.invalidate([PathBuf::from("nav.html")]);
+  /// assert_eq!(
+  ///   affected,
+  ///   [PathBuf::from("nav.html"), PathBuf::from("layout.html"), PathBuf::from("page.html")]
+  /// );
+  /// ```
+  pub fn invalidate(&self, changed: impl IntoIterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    let mut queue: VecDeque<PathBuf> = VecDeque::new();
+
+    for path in changed {
+      if seen.insert(path.clone()) {
+        queue.push_back(path);
+      }
+    }
+
+    while let Some(path) = queue.pop_front() {
+      // sort to keep the output deterministic
+      let mut dependents: Vec<&Path> = self.dependents(&path).collect();
+      dependents.sort();
+
+      for dependent in dependents {
+        if seen.insert(dependent.to_path_buf()) {
+          queue.push_back(dependent.to_path_buf());
+        }
+      }
+
+      result.push(path);
+    }
+
+    result
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::{Path, PathBuf};
+
+  use super::DependencyGraph;
+
+  #[test]
+  fn replace_dependencies() {
+    let mut graph = DependencyGraph::default();
+    graph.set_dependencies(Path::new("a"), [PathBuf::from("b"), PathBuf::from("c")]);
+    graph.set_dependencies(Path::new("a"), [PathBuf::from("c")]);
+
+    assert_eq!(graph.dependents(Path::new("b")).count(), 0);
+    assert_eq!(
+      graph.dependents(Path::new("c")).collect::<Vec<_>>(),
+      [Path::new("a")]
+    );
+  }
+
+  #[test]
+  fn invalidate_cycle() {
+    let mut graph = DependencyGraph::default();
+    graph.set_dependencies(Path::new("a"), [PathBuf::from("b")]);
+    graph.set_dependencies(Path::new("b"), [PathBuf::from("a")]);
+
+    assert_eq!(
+      graph.invalidate([PathBuf::from("a")]),
+      [PathBuf::from("a"), PathBuf::from("b")]
+    );
+  }
+
+  #[test]
+  fn removed_file_keeps_dependents() {
+    let mut graph = DependencyGraph::default();
+    graph.set_dependencies(Path::new("page"), [PathBuf::from("partial")]);
+    graph.set_dependencies(Path::new("partial"), [PathBuf::from("nested")]);
+    graph.remove_dependencies(Path::new("partial"));
+
+    assert_eq!(graph.dependents(Path::new("nested")).count(), 0);
+    assert_eq!(
+      graph.invalidate([PathBuf::from("partial")]),
+      [PathBuf::from("partial"), PathBuf::from("page")]
+    );
+  }
+}
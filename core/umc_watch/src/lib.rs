@@ -0,0 +1,316 @@
+//! File watching with dependency-aware invalidation for the Universal Markup-language Compiler.
+//!
+//! This crate re-parses files as they change on disk. When a file is included by other
+//! files (partials, layouts, ...), every file depending on it is re-parsed as well, so
+//! build tools receive a complete set of incremental results for each change.
+//!
+//! Dependencies are discovered through a resolver callback, which is called with the
+//! source of every parsed file. Languages without includes can keep the default resolver,
+//! which reports no dependencies.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use umc_html_parser::Html;
+//! use umc_watch::{WatchEvent, Watcher};
+//!
+//! let mut watcher = Watcher::<Html>::new()?;
+//! watcher.watch("src".as_ref())?;
+//!
+//! while watcher.wait(|event| {
+//!   if let WatchEvent::Parsed { path, result, .. } = event {
+//!     println!("{}: {} errors", path.display(), result.errors.len());
+//!   }
+//! }) {}
+//! ```
+
+use std::{
+  fs, io,
+  path::{Path, PathBuf},
+  sync::mpsc::{Receiver, RecvTimeoutError, channel},
+  time::Duration,
+};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher as _};
+use oxc_allocator::Allocator;
+use umc_parser::{LanguageParser, ParseResult, Parser};
+
+mod graph;
+
+pub use graph::DependencyGraph;
+
+/// An incremental result produced by the [`Watcher`].
+///
+/// The lifetime `'a` is tied to the allocator and source text owned by the watcher,
+/// so events are only valid inside the handler they are passed to.
+pub enum WatchEvent<'a, T: LanguageParser> {
+  /// A file was parsed because it or one of its dependencies changed
+  Parsed {
+    /// Path of the parsed file
+    path: &'a Path,
+    /// Source text of the parsed file
+    source_text: &'a str,
+    /// The parse result
+    result: ParseResult<T::Result<'a>>,
+  },
+  /// A file was removed from disk
+  Removed {
+    /// Path of the removed file
+    path: &'a Path,
+  },
+  /// A file could not be read, or the file system watcher failed
+  Error {
+    /// Path of the file related to the error, if any
+    path: Option<&'a Path>,
+    /// The underlying error
+    error: notify::Error,
+  },
+}
+
+/// Resolver for the dependencies of a file, called with the path and source text of the file.
+type Resolver = Box<dyn Fn(&Path, &str) -> Vec<PathBuf>>;
+
+/// Watches files and re-parses them, and their dependents, on change.
+pub struct Watcher<T: LanguageParser> {
+  backend: RecommendedWatcher,
+  receiver: Receiver<notify::Result<notify::Event>>,
+  graph: DependencyGraph,
+  resolver: Resolver,
+  filter: Box<dyn Fn(&Path) -> bool>,
+  options: Box<dyn Fn() -> T::Option>,
+  debounce: Duration,
+  allocator: Allocator,
+}
+
+impl<T: LanguageParser> Watcher<T>
+where
+  T::Option: 'static,
+{
+  /// Create a watcher using the platform recommended file system backend.
+  pub fn new() -> notify::Result<Self> {
+    let (sender, receiver) = channel();
+    let backend = notify::recommended_watcher(sender)?;
+
+    Ok(Self {
+      backend,
+      receiver,
+      graph: DependencyGraph::default(),
+      resolver: Box::new(|_, _| Vec::new()),
+      filter: Box::new(|_| true),
+      options: Box::new(T::Option::default),
+      debounce: Duration::from_millis(50),
+      allocator: Allocator::default(),
+    })
+  }
+
+  /// Set the dependency resolver.
+  ///
+  /// The resolver gets the path and source text of each parsed file and returns the
+  /// files it includes. Paths reported by the file system are absolute, so resolvers
+  /// should return absolute paths as well.
+  #[must_use]
+  pub fn with_resolver(mut self, resolver: impl Fn(&Path, &str) -> Vec<PathBuf> + 'static) -> Self {
+    self.resolver = Box::new(resolver);
+    self
+  }
+
+  /// Only parse changed files for which `filter` returns true, e.g. by extension.
+  ///
+  /// Dependents of a filtered out file are still invalidated.
+  #[must_use]
+  pub fn with_filter(mut self, filter: impl Fn(&Path) -> bool + 'static) -> Self {
+    self.filter = Box::new(filter);
+    self
+  }
+
+  /// Set the factory for the parser options used for every parse.
+  #[must_use]
+  pub fn with_options(mut self, options: impl Fn() -> T::Option + 'static) -> Self {
+    self.options = Box::new(options);
+    self
+  }
+
+  /// Set how long to wait for further file system events before processing a batch.
+  #[must_use]
+  pub const fn with_debounce(mut self, debounce: Duration) -> Self {
+    self.debounce = debounce;
+    self
+  }
+
+  /// Start watching a file or a directory (recursively).
+  pub fn watch(&mut self, path: &Path) -> notify::Result<()> {
+    self.backend.watch(path, RecursiveMode::Recursive)
+  }
+
+  /// Stop watching a file or a directory.
+  pub fn unwatch(&mut self, path: &Path) -> notify::Result<()> {
+    self.backend.unwatch(path)
+  }
+
+  /// Get the dependency graph collected so far.
+  pub const fn graph(&self) -> &DependencyGraph {
+    &self.graph
+  }
+
+  /// Block until files change, then process the changes as one batch.
+  ///
+  /// Returns `false` once the underlying watcher is gone and no more events will come.
+  pub fn wait(&mut self, handler: impl for<'a> FnMut(WatchEvent<'a, T>)) -> bool {
+    let Ok(first) = self.receiver.recv() else {
+      return false;
+    };
+
+    let mut events = vec![first];
+    loop {
+      match self.receiver.recv_timeout(self.debounce) {
+        Ok(event) => events.push(event),
+        Err(RecvTimeoutError::Timeout) => break,
+        Err(RecvTimeoutError::Disconnected) => {
+          self.process_events(events, handler);
+          return false;
+        }
+      }
+    }
+
+    self.process_events(events, handler);
+    true
+  }
+
+  fn process_events(
+    &mut self,
+    events: Vec<notify::Result<notify::Event>>,
+    mut handler: impl for<'a> FnMut(WatchEvent<'a, T>),
+  ) {
+    let mut changed: Vec<PathBuf> = Vec::new();
+
+    for event in events {
+      match event {
+        Ok(event) => {
+          if matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+          ) {
+            for path in event.paths {
+              if !changed.contains(&path) {
+                changed.push(path);
+              }
+            }
+          }
+        }
+        Err(error) => handler(WatchEvent::Error { path: None, error }),
+      }
+    }
+
+    self.process(changed, handler);
+  }
+
+  /// Re-parse `changed` and every file depending on them.
+  ///
+  /// This is what [`wait`](Self::wait) does for each batch of file system events. It can also
+  /// be called directly, e.g. for the initial build.
+  pub fn process(
+    &mut self,
+    changed: impl IntoIterator<Item = PathBuf>,
+    mut handler: impl for<'a> FnMut(WatchEvent<'a, T>),
+  ) {
+    for path in self.graph.invalidate(changed) {
+      if path.is_dir() || !(self.filter)(&path) {
+        continue;
+      }
+
+      let source_text = match fs::read_to_string(&path) {
+        Ok(source_text) => source_text,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => {
+          self.graph.remove_dependencies(&path);
+          handler(WatchEvent::Removed { path: &path });
+          continue;
+        }
+        Err(error) => {
+          handler(WatchEvent::Error {
+            path: Some(&path),
+            error: error.into(),
+          });
+          continue;
+        }
+      };
+
+      self
+        .graph
+        .set_dependencies(&path, (self.resolver)(&path, &source_text));
+
+      {
+        let parser = Parser::<T>::new(&self.allocator, &source_text).with_options((self.options)());
+        handler(WatchEvent::Parsed {
+          path: &path,
+          source_text: &source_text,
+          result: parser.parse(),
+        });
+      }
+
+      self.allocator.reset();
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::{
+    fs,
+    path::{Path, PathBuf},
+  };
+
+  use umc_html_parser::Html;
+
+  use crate::{WatchEvent, Watcher};
+
+  /// Treat every `<include src="...">` as a dependency on a sibling file
+  fn resolve_includes(path: &Path, source_text: &str) -> Vec<PathBuf> {
+    source_text
+      .split("<include src=\"")
+      .skip(1)
+      .filter_map(|rest| rest.split('"').next())
+      .map(|name| path.with_file_name(name))
+      .collect()
+  }
+
+  #[test]
+  fn process_dependents() {
+    let dir = std::env::temp_dir().join(format!("umc_watch_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let page = dir.join("page.html");
+    let partial = dir.join("partial.html");
+    fs::write(&page, r#"<main><include src="partial.html"></main>"#).unwrap();
+    fs::write(&partial, "<p>Hello</p>").unwrap();
+
+    let mut watcher = Watcher::<Html>::new()
+      .unwrap()
+      .with_resolver(resolve_includes);
+
+    let mut parsed = Vec::new();
+    let mut collect = |event: WatchEvent<'_, Html>| match event {
+      WatchEvent::Parsed { path, .. } => parsed.push(path.to_path_buf()),
+      WatchEvent::Removed { path } => parsed.push(path.with_extension("removed")),
+      WatchEvent::Error { error, .. } => panic!("{error}"),
+    };
+
+    watcher.process([page.clone(), partial.clone()], &mut collect);
+    watcher.process([partial.clone()], &mut collect);
+    fs::remove_file(&partial).unwrap();
+    watcher.process([partial.clone()], &mut collect);
+
+    assert_eq!(
+      parsed,
+      [
+        page.clone(),
+        partial.clone(),
+        partial.clone(),
+        page.clone(),
+        partial.with_extension("removed"),
+        page
+      ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+  }
+}
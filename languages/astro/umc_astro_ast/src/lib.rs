@@ -0,0 +1,47 @@
+//! Astro Abstract Syntax Tree (AST) node definitions.
+//!
+//! An Astro component is a frontmatter of TypeScript between `---` fences, followed by its
+//! markup: HTML with `{...}` expressions, see [`Component`].
+//!
+//! The markup is made of the [`umc_html_ast`] nodes, so the tools working on HTML, like
+//! visitors of `umc_html_traverse`, work on the markup of components unchanged.
+
+use oxc_ast::ast::Program as JsProgram;
+use umc_html_ast::Program;
+use umc_span::{GetSpan, Span};
+
+/// A parsed `.astro` component.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+pub struct Component<'a> {
+  /// The frontmatter at the start of the component, `None` without one
+  pub frontmatter: Option<Frontmatter<'a>>,
+  /// The markup after the frontmatter.
+  ///
+  /// Expressions like `{title}` are [`TemplateTag`](umc_html_ast::TemplateTag) nodes, and
+  /// attributes like `class={name}` have the expression, braces included, as their value.
+  /// Spread and shorthand attributes like `{...props}` are attributes without value.
+  pub markup: Program<'a>,
+}
+
+/// The frontmatter of a component, TypeScript code between `---` fences.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+pub struct Frontmatter<'a> {
+  /// Source location of the frontmatter, fences included
+  pub span: Span,
+  /// Source location of the code between the fences
+  pub content_span: Span,
+  /// The parsed code. Its spans are relative to the code, which starts at
+  /// `content_span.start` in the component.
+  pub program: JsProgram<'a>,
+}
+
+impl GetSpan for Frontmatter<'_> {
+  #[inline]
+  fn span(&self) -> Span {
+    self.span
+  }
+}
@@ -0,0 +1,126 @@
+//! Astro parser implementation for the Universal Markup-language Compiler.
+//!
+//! This crate parses `.astro` components into the AST defined in [`umc_astro_ast`]: the
+//! frontmatter is parsed as TypeScript by `oxc_parser`, and the markup by the HTML parser,
+//! with `{...}` expressions kept whole as template tags, see
+//! [`TemplateDelimiters::JSX`](umc_html_parser::option::TemplateDelimiters::JSX).
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_astro_parser::CreateAstro;
+//! use umc_html_ast::Node;
+//! use umc_parser::Parser;
+//!
+//! let allocator = Allocator::default();
+//! let source_text = "---\nconst { title } = Astro.props;\n---\n<h1 class={title}>{title}</h1>";
+//! let result = Parser::astro(&allocator, source_text).parse();
+//!
+//! assert!(result.errors.is_empty());
+//! assert_eq!(result.program.frontmatter.unwrap().program.body.len(), 1);
+//! let Node::Element(h1) = &result.program.markup[1] else { unreachable!() };
+//! assert!(matches!(&h1.children[0], Node::TemplateTag(tag) if tag.value == "title"));
+//! ```
+
+use oxc_allocator::Allocator;
+use oxc_ast::ast::Expression;
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_parser::Parser as JsParser;
+use oxc_span::SourceType;
+use umc_astro_ast::Component;
+use umc_parser::{LanguageParser, Parser};
+
+use crate::{option::AstroParserOption, parse::AstroParserImpl};
+
+mod parse;
+
+/// Astro language parser marker type.
+///
+/// This zero-sized type implements [`LanguageParser`] for Astro parsing.
+/// Use [`Parser::astro()`](CreateAstro::astro) to create an Astro parser instance.
+pub struct Astro;
+
+impl LanguageParser for Astro {
+  const NAME: &'static str = "astro";
+  const EXTENSIONS: &'static [&'static str] = &["astro"];
+
+  type Result<'a> = Component<'a>;
+  type Option = AstroParserOption;
+  type Parser<'a> = AstroParserImpl<'a>;
+}
+
+/// Convenience trait for creating Astro parsers.
+pub trait CreateAstro<'a> {
+  /// Create a parser for Astro parsing.
+  ///
+  /// # Parameters
+  /// - `allocator`: Memory arena for allocating AST nodes
+  /// - `source_text`: Astro component to parse
+  fn astro(allocator: &'a Allocator, source_text: &'a str) -> Self;
+}
+
+impl<'a> CreateAstro<'a> for Parser<'a, Astro> {
+  fn astro(allocator: &'a Allocator, source_text: &'a str) -> Self {
+    Parser::<Astro>::new(allocator, source_text)
+  }
+}
+
+/// Parse the code of an expression of the markup, like `title` in `{title}`, as TSX.
+///
+/// The code is the [`value`](umc_html_ast::TemplateTag::value) of a template tag, or the
+/// value of an attribute without its braces. The spans of the expression are relative to
+/// the code.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use oxc_ast::ast::Expression;
+/// use umc_astro_parser::parse_expression;
+///
+/// let allocator = Allocator::default();
+/// let expression = parse_expression(&allocator, "items.map((item) => <li>{item}</li>)");
+/// assert!(matches!(expression, Ok(Expression::CallExpression(_))));
+/// ```
+///
+/// # Errors
+///
+/// Returns the syntax errors of the code.
+pub fn parse_expression<'a>(
+  allocator: &'a Allocator,
+  code: &'a str,
+) -> Result<Expression<'a>, Vec<OxcDiagnostic>> {
+  JsParser::new(allocator, code, SourceType::tsx()).parse_expression()
+}
+
+/// Astro parser configuration options.
+pub mod option {
+  use oxc_parser::ParseOptions;
+  use umc_html_parser::option::{HtmlParserOption, TemplateDelimiters};
+
+  /// Astro parser configuration options.
+  #[derive(Debug, Clone)]
+  pub struct AstroParserOption {
+    /// The options of the HTML parser, for the markup.
+    ///
+    /// By default, `{...}` expressions are [template
+    /// tags](HtmlParserOption::template_delimiters), and names keep their case, as
+    /// components like `<Card>` are told apart from elements by it.
+    pub html: HtmlParserOption,
+    /// The oxc_parser options for the frontmatter.
+    pub frontmatter: ParseOptions,
+  }
+
+  impl Default for AstroParserOption {
+    fn default() -> Self {
+      Self {
+        html: HtmlParserOption::builder()
+          .template_delimiters(vec![TemplateDelimiters::JSX])
+          .normalize_case(false)
+          .build(),
+        frontmatter: ParseOptions::default(),
+      }
+    }
+  }
+}
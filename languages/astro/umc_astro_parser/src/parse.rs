@@ -0,0 +1,228 @@
+use std::borrow::Cow;
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use oxc_parser::Parser as JsParser;
+use oxc_span::SourceType;
+use umc_astro_ast::{Component, Frontmatter};
+use umc_html_parser::Html;
+use umc_parser::{LanguageParser, ParseResult, ParserImpl};
+use umc_span::Span;
+
+use crate::{Astro, option::AstroParserOption};
+
+/// The fences around the frontmatter.
+const FENCE: &str = "---";
+
+/// Astro parser implementation.
+///
+/// Parses the frontmatter with `oxc_parser`, then the markup after it with the HTML parser,
+/// in the same allocator.
+pub struct AstroParserImpl<'a> {
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  options: Cow<'a, AstroParserOption>,
+}
+
+impl<'a> ParserImpl<'a, Astro> for AstroParserImpl<'a> {
+  fn new(
+    allocator: &'a Allocator,
+    source_text: &'a str,
+    options: Cow<'a, <Astro as LanguageParser>::Option>,
+  ) -> Self {
+    AstroParserImpl {
+      allocator,
+      source_text,
+      options,
+    }
+  }
+
+  fn parse(self) -> ParseResult<Component<'a>> {
+    let mut errors = Vec::new();
+    let frontmatter = self.parse_frontmatter(&mut errors);
+    let markup_start = frontmatter
+      .as_ref()
+      .map_or(0, |frontmatter| frontmatter.span.end);
+
+    let html_options = match self.options {
+      Cow::Borrowed(options) => Cow::Borrowed(&options.html),
+      Cow::Owned(options) => Cow::Owned(options.html),
+    };
+    let markup =
+      <Html as LanguageParser>::Parser::new(self.allocator, self.source_text, html_options)
+        .starting_at(markup_start)
+        .parse();
+    errors.extend(markup.errors);
+
+    ParseResult {
+      program: Component {
+        frontmatter,
+        markup: markup.program,
+      },
+      errors,
+      metrics: markup.metrics,
+    }
+  }
+}
+
+impl<'a> AstroParserImpl<'a> {
+  /// Parse the frontmatter at the start of the component, if any.
+  fn parse_frontmatter(&self, errors: &mut Vec<OxcDiagnostic>) -> Option<Frontmatter<'a>> {
+    let (span, content_span) = find_frontmatter(self.source_text, errors)?;
+    let code = content_span.source_text(self.source_text);
+    let ret = JsParser::new(self.allocator, code, SourceType::ts())
+      .with_options(self.options.frontmatter)
+      .parse();
+    errors.extend(
+      ret
+        .errors
+        .into_iter()
+        .map(|error| shift_diagnostic(error, content_span.start)),
+    );
+
+    Some(Frontmatter {
+      span,
+      content_span,
+      program: ret.program,
+    })
+  }
+}
+
+/// Find the frontmatter at the start of `source_text`, after whitespace: its span, fences
+/// included, and the span of its code.
+///
+/// The fences are lines made of `---`. A frontmatter without closing fence runs to the end
+/// of the component, with an error.
+fn find_frontmatter(source_text: &str, errors: &mut Vec<OxcDiagnostic>) -> Option<(Span, Span)> {
+  let start = source_text.len() - source_text.trim_start().len();
+  let opening = source_text[start..].split_inclusive('\n').next()?;
+  if opening.trim_end() != FENCE {
+    return None;
+  }
+  let content_start = start + opening.len();
+
+  let mut line_start = content_start;
+  for line in source_text[content_start..].split_inclusive('\n') {
+    if line.trim_end() == FENCE {
+      let span = Span::new(start as u32, (line_start + FENCE.len()) as u32);
+      return Some((span, Span::new(content_start as u32, line_start as u32)));
+    }
+    line_start += line.len();
+  }
+
+  let end = source_text.len() as u32;
+  errors.push(
+    OxcDiagnostic::error(format!("Expected a closing `{FENCE}` fence, but found EOF")).with_labels(
+      [
+        Span::empty(end).primary_label("frontmatter ends here"),
+        Span::new(start as u32, (start + FENCE.len()) as u32).label("frontmatter starts here"),
+      ],
+    ),
+  );
+  Some((
+    Span::new(start as u32, end),
+    Span::new(content_start as u32, end),
+  ))
+}
+
+/// Move the labels of a diagnostic of the frontmatter to their offset in the component.
+fn shift_diagnostic(mut error: OxcDiagnostic, offset: u32) -> OxcDiagnostic {
+  if let Some(labels) = &mut error.labels {
+    for label in labels {
+      let span = (label.offset() + offset as usize, label.len());
+      let text = label.label().map(ToString::to_string);
+      *label = if label.primary() {
+        LabeledSpan::new_primary_with_span(text, span)
+      } else {
+        LabeledSpan::new_with_span(text, span)
+      };
+    }
+  }
+  error
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_ast::Node;
+  use umc_parser::Parser;
+  use umc_span::Span;
+
+  use crate::CreateAstro;
+
+  #[test]
+  fn component() {
+    const ASTRO: &str = r#"---
+import Card from "./Card.astro";
+const items: string[] = ["a", "b"];
+---
+<style>h1 { color: red; }</style>
+<Card {...props} title={`${items.length} items`}>
+  <ul>{items.map((item) => <li>{item}</li>)}</ul>
+</Card>"#;
+
+    let allocator = Allocator::default();
+    let result = Parser::astro(&allocator, ASTRO).parse();
+    assert!(result.errors.is_empty());
+
+    let frontmatter = result.program.frontmatter.as_ref().unwrap();
+    assert_eq!(frontmatter.span, Span::new(0, 76));
+    assert_eq!(frontmatter.content_span, Span::new(4, 73));
+    assert_eq!(frontmatter.program.body.len(), 2);
+
+    let markup = &result.program.markup;
+    assert!(matches!(&markup[1], Node::Element(style) if style.name() == "style"));
+    let Node::Element(card) = &markup[3] else {
+      unreachable!()
+    };
+    assert_eq!(card.name(), "Card");
+    let attributes: Vec<_> = card
+      .attributes
+      .iter()
+      .map(|attribute| {
+        (
+          attribute.key.value,
+          attribute.value.as_ref().map(|v| v.value),
+        )
+      })
+      .collect();
+    assert_eq!(
+      attributes,
+      [
+        ("{...props}", None),
+        ("title", Some("{`${items.length} items`}"))
+      ]
+    );
+    let Node::Element(ul) = &card.children[1] else {
+      unreachable!()
+    };
+    assert!(matches!(
+      &ul.children[..],
+      [Node::TemplateTag(tag)] if tag.value == "items.map((item) => <li>{item}</li>)"
+    ));
+  }
+
+  #[test]
+  fn without_frontmatter() {
+    let allocator = Allocator::default();
+    let result = Parser::astro(&allocator, "<p>--- {a}</p>").parse();
+    assert!(result.errors.is_empty());
+    assert!(result.program.frontmatter.is_none());
+    assert_eq!(result.program.markup.len(), 1);
+  }
+
+  #[test]
+  fn frontmatter_errors() {
+    let allocator = Allocator::default();
+    let result = Parser::astro(&allocator, "---\nconst = 1;\n---\n<p></p>").parse();
+    assert_eq!(result.errors.len(), 1);
+    let label = result.errors[0].labels.as_ref().unwrap()[0].offset();
+    assert!((4..14).contains(&label));
+
+    let result = Parser::astro(&allocator, "---\nconst a = 1;\n<p></p>").parse();
+    assert!(result.errors[0].message.contains("closing `---` fence"));
+    let frontmatter = result.program.frontmatter.unwrap();
+    assert!(result.program.markup.is_empty());
+    assert_eq!(frontmatter.content_span, Span::new(4, 24));
+  }
+}
@@ -0,0 +1,425 @@
+//! Validation of [AMP](https://amp.dev/documentation/guides-and-tutorials/learn/spec/amphtml)
+//! documents.
+//!
+//! The core rules of the AMP validator are checked on the tree: tags and attributes AMP
+//! forbids, the required boilerplate of the document and the size limits of its CSS.
+//! Extension components, whose rules come from their own specifications, are not checked.
+//!
+//! Every diagnostic has the code of its [`AmpErrorKind`], e.g. `amp(forbidden-tag)`.
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Element, Node, Program};
+use umc_span::Span;
+
+/// Scope of the error code of every AMP diagnostic.
+pub const ERROR_CODE_SCOPE: &str = "amp";
+
+/// Maximum size in bytes of the CSS of a document, in `<style amp-custom>` and `style`
+/// attributes.
+pub const MAX_CSS_BYTES: usize = 75_000;
+
+/// Maximum size in bytes of a `style` attribute.
+pub const MAX_STYLE_ATTRIBUTE_BYTES: usize = 1_000;
+
+/// URL of the AMP runtime, required in the `<head>` of every document.
+pub const RUNTIME_URL: &str = "https://cdn.ampproject.org/v0.js";
+
+/// Tags forbidden anywhere in AMP documents.
+const FORBIDDEN_TAGS: &[&str] = &[
+  "applet", "base", "embed", "frame", "frameset", "object", "param",
+];
+
+/// Tags replaced by AMP components, only allowed inside `<noscript>`.
+const REPLACED_TAGS: &[(&str, &str)] = &[
+  ("img", "amp-img"),
+  ("video", "amp-video"),
+  ("audio", "amp-audio"),
+  ("iframe", "amp-iframe"),
+];
+
+/// Kind of an AMP validation error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmpErrorKind {
+  /// A tag AMP forbids, like `<object>`, or replaces with a component, like `<img>`
+  ForbiddenTag,
+  /// An attribute AMP forbids, like event handlers or `javascript:` URLs
+  ForbiddenAttribute,
+  /// A script other than the AMP runtime, its components and JSON data
+  ForbiddenScript,
+  /// A stylesheet other than `<style amp-custom>` and the AMP boilerplate
+  ForbiddenStyle,
+  /// A part of the boilerplate is missing, like the runtime script or `<html ⚡>`
+  MissingBoilerplate,
+  /// The CSS of the document, or a `style` attribute, is over its size limit
+  CssTooLarge,
+}
+
+impl AmpErrorKind {
+  /// The code of the kind, e.g. `forbidden-tag`.
+  pub const fn code(self) -> &'static str {
+    match self {
+      Self::ForbiddenTag => "forbidden-tag",
+      Self::ForbiddenAttribute => "forbidden-attribute",
+      Self::ForbiddenScript => "forbidden-script",
+      Self::ForbiddenStyle => "forbidden-style",
+      Self::MissingBoilerplate => "missing-boilerplate",
+      Self::CssTooLarge => "css-too-large",
+    }
+  }
+
+  fn error(self, message: impl Into<String>, span: Span) -> OxcDiagnostic {
+    OxcDiagnostic::error(message.into())
+      .with_label(span)
+      .with_error_code(ERROR_CODE_SCOPE, self.code())
+  }
+}
+
+/// Validate an AMP document, getting its errors in document order, the missing
+/// boilerplate last.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::amp::validate;
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, r#"<html amp><body><img src="a.png"></body></html>"#);
+/// let program = parser.parse().program;
+///
+/// let errors = validate(&program);
+/// assert_eq!(errors[0].to_string(), "<img> is not allowed in AMP, use <amp-img>");
+/// ```
+pub fn validate(program: &Program) -> Vec<OxcDiagnostic> {
+  let mut validator = Validator::default();
+  validator.walk(program, false);
+  validator.finish(program)
+}
+
+#[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
+struct Validator {
+  errors: Vec<OxcDiagnostic>,
+  /// Spans of the `<html>` and `<head>` elements, once found
+  html: Option<Span>,
+  head: Option<Span>,
+  has_body: bool,
+  has_doctype: bool,
+  has_runtime: bool,
+  has_charset: bool,
+  has_viewport: bool,
+  has_boilerplate: bool,
+  has_noscript_boilerplate: bool,
+  /// Bytes of CSS in `<style amp-custom>` and `style` attributes
+  css_bytes: usize,
+  /// Where the CSS size limit was exceeded
+  css_exceeded_at: Option<Span>,
+}
+
+impl Validator {
+  fn walk(&mut self, nodes: &[Node], in_noscript: bool) {
+    for node in nodes {
+      match node {
+        Node::Doctype(_) => self.has_doctype = true,
+        Node::Element(element) => self.element(element, in_noscript),
+        Node::Script(script) => self.errors.push(
+          AmpErrorKind::ForbiddenScript.error("Inline scripts are not allowed in AMP", script.span),
+        ),
+        _ => {}
+      }
+    }
+  }
+
+  fn element(&mut self, element: &Element, in_noscript: bool) {
+    let name = element.name();
+    let in_head = self
+      .head
+      .is_some_and(|head| head.contains_inclusive(element.span));
+
+    match name {
+      "html" => {
+        self.html.get_or_insert(element.span);
+        if element.attribute("amp").is_none() && element.attribute("⚡").is_none() {
+          self.errors.push(
+            AmpErrorKind::MissingBoilerplate
+              .error("<html> must have the `amp` or `⚡` attribute", element.span),
+          );
+        }
+      }
+      "head" => {
+        self.head.get_or_insert(element.span);
+        self.head_children(element);
+      }
+      "body" => self.has_body = true,
+      "script" => self.script(element, in_head),
+      "style" => self.style(element, in_head, in_noscript),
+      "meta" if in_head => {
+        if element
+          .attribute("name")
+          .is_some_and(|name| name.eq_ignore_ascii_case("viewport"))
+        {
+          self.has_viewport = true;
+        }
+      }
+      _ if FORBIDDEN_TAGS.contains(&name) => self.errors.push(
+        AmpErrorKind::ForbiddenTag.error(format!("<{name}> is not allowed in AMP"), element.span),
+      ),
+      _ => {
+        if !in_noscript
+          && let Some((_, component)) = REPLACED_TAGS.iter().find(|(tag, _)| *tag == name)
+        {
+          self.errors.push(AmpErrorKind::ForbiddenTag.error(
+            format!("<{name}> is not allowed in AMP, use <{component}>"),
+            element.span,
+          ));
+        }
+      }
+    }
+
+    self.attributes(element);
+    self.walk(&element.children, in_noscript || name == "noscript");
+  }
+
+  /// Check that `<meta charset="utf-8">` is the first child of `<head>`.
+  fn head_children(&mut self, head: &Element) {
+    let first = head.children.iter().find_map(|child| match child {
+      Node::Element(element) => Some(element),
+      _ => None,
+    });
+    self.has_charset = first.is_some_and(|element| {
+      element.name() == "meta"
+        && element
+          .attribute("charset")
+          .is_some_and(|charset| charset.eq_ignore_ascii_case("utf-8"))
+    });
+  }
+
+  fn script(&mut self, script: &Element, in_head: bool) {
+    let src = script.attribute("src");
+    if src == Some(RUNTIME_URL) {
+      self.has_runtime = in_head;
+      return;
+    }
+    let is_json = script.attribute("type").is_some_and(|kind| {
+      kind.eq_ignore_ascii_case("application/json")
+        || kind.eq_ignore_ascii_case("application/ld+json")
+    });
+    let is_component = src.is_some_and(|src| src.starts_with("https://cdn.ampproject.org/"))
+      && (script.attribute("custom-element").is_some()
+        || script.attribute("custom-template").is_some());
+    if !is_json && !is_component {
+      self.errors.push(AmpErrorKind::ForbiddenScript.error(
+        "Only the AMP runtime, AMP components and JSON scripts are allowed in AMP",
+        script.span,
+      ));
+    }
+  }
+
+  fn style(&mut self, style: &Element, in_head: bool, in_noscript: bool) {
+    if style.attribute("amp-boilerplate").is_some() {
+      if in_noscript {
+        self.has_noscript_boilerplate = true;
+      } else {
+        self.has_boilerplate = true;
+      }
+    } else if in_head && style.attribute("amp-custom").is_some() {
+      let bytes = style
+        .children
+        .iter()
+        .map(|child| match child {
+          Node::Text(text) => text.value.len(),
+          _ => 0,
+        })
+        .sum();
+      self.add_css(bytes, style.span);
+    } else {
+      self.errors.push(AmpErrorKind::ForbiddenStyle.error(
+        "Only <style amp-custom> in <head> is allowed in AMP",
+        style.span,
+      ));
+    }
+  }
+
+  fn attributes(&mut self, element: &Element) {
+    for attribute in &element.attributes {
+      let name = attribute.key.name();
+      let value = attribute.value.as_ref().map_or("", |value| value.value);
+
+      if name.len() > 2 && name.starts_with("on") {
+        self.errors.push(AmpErrorKind::ForbiddenAttribute.error(
+          format!("Event handler attributes like `{name}` are not allowed in AMP"),
+          attribute.span,
+        ));
+      } else if name == "style" {
+        if value.len() > MAX_STYLE_ATTRIBUTE_BYTES {
+          self.errors.push(AmpErrorKind::CssTooLarge.error(
+            format!(
+              "`style` attribute of {} bytes, over the limit of {MAX_STYLE_ATTRIBUTE_BYTES} bytes",
+              value.len()
+            ),
+            attribute.span,
+          ));
+        }
+        self.add_css(value.len(), attribute.span);
+      } else if matches!(name, "href" | "src" | "action" | "formaction")
+        && value
+          .trim_ascii_start()
+          .get(..11)
+          .is_some_and(|scheme| scheme.eq_ignore_ascii_case("javascript:"))
+      {
+        self.errors.push(
+          AmpErrorKind::ForbiddenAttribute
+            .error("`javascript:` URLs are not allowed in AMP", attribute.span),
+        );
+      }
+    }
+  }
+
+  const fn add_css(&mut self, bytes: usize, span: Span) {
+    self.css_bytes += bytes;
+    if self.css_bytes > MAX_CSS_BYTES && self.css_exceeded_at.is_none() {
+      self.css_exceeded_at = Some(span);
+    }
+  }
+
+  fn finish(mut self, program: &Program) -> Vec<OxcDiagnostic> {
+    if let Some(span) = self.css_exceeded_at {
+      self.errors.push(AmpErrorKind::CssTooLarge.error(
+        format!(
+          "CSS of {} bytes, over the limit of {MAX_CSS_BYTES} bytes",
+          self.css_bytes
+        ),
+        span,
+      ));
+    }
+
+    // missing parts are reported at the element which should hold them
+    let document = Span::new(0, program.last().map_or(0, |node| node.span().end));
+    let head = self.head.or(self.html).unwrap_or(document);
+    let missing = [
+      (self.has_doctype, "<!doctype html>", document),
+      (self.html.is_some(), "<html>", document),
+      (self.head.is_some(), "<head>", self.html.unwrap_or(document)),
+      (self.has_body, "<body>", self.html.unwrap_or(document)),
+      (
+        self.has_charset,
+        r#"<meta charset="utf-8"> as the first child of <head>"#,
+        head,
+      ),
+      (self.has_viewport, r#"<meta name="viewport">"#, head),
+      (
+        self.has_runtime,
+        r#"<script async src="https://cdn.ampproject.org/v0.js"> in <head>"#,
+        head,
+      ),
+      (self.has_boilerplate, "<style amp-boilerplate>", head),
+      (
+        self.has_noscript_boilerplate,
+        "<noscript><style amp-boilerplate>",
+        head,
+      ),
+    ];
+    for (found, part, span) in missing {
+      if !found {
+        self
+          .errors
+          .push(AmpErrorKind::MissingBoilerplate.error(format!("Missing {part}"), span));
+      }
+    }
+
+    self.errors
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use super::{MAX_CSS_BYTES, validate};
+
+  const BOILERPLATE: &str = r#"<!doctype html>
+<html ⚡>
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width">
+  <script async src="https://cdn.ampproject.org/v0.js"></script>
+  <script async custom-element="amp-carousel" src="https://cdn.ampproject.org/v0/amp-carousel-0.1.js"></script>
+  <style amp-boilerplate>body{visibility:hidden}</style>
+  <noscript><style amp-boilerplate>body{visibility:visible}</style></noscript>
+  <style amp-custom>CSS</style>
+  <script type="application/ld+json">{}</script>
+</head>
+<body>BODY</body>
+</html>"#;
+
+  fn errors(css: &str, body: &str) -> Vec<String> {
+    let source_text = BOILERPLATE.replace("CSS", css).replace("BODY", body);
+    let allocator = Allocator::default();
+    let program = Parser::html(&allocator, &source_text).parse().program;
+    validate(&program)
+      .iter()
+      .map(|error| format!("{}: {error}", error.code))
+      .collect()
+  }
+
+  #[test]
+  fn valid() {
+    let body = r#"<amp-img src="a.png" width="1" height="1"></amp-img><noscript><img src="a.png"></noscript><a href="/" on="tap:x" style="color: red">x</a>"#;
+    assert_eq!(errors("h1 { color: red }", body), Vec::<String>::new());
+  }
+
+  #[test]
+  fn forbidden() {
+    let body = r#"<img src="a.png"><object></object><button onclick="go()">Go</button><a href=" JavaScript:go()">Go</a><script>go()</script><script src="app.js"></script><style>p {}</style>"#;
+    assert_eq!(
+      errors("", body),
+      [
+        "amp(forbidden-tag): <img> is not allowed in AMP, use <amp-img>",
+        "amp(forbidden-tag): <object> is not allowed in AMP",
+        "amp(forbidden-attribute): Event handler attributes like `onclick` are not allowed in AMP",
+        "amp(forbidden-attribute): `javascript:` URLs are not allowed in AMP",
+        "amp(forbidden-script): Inline scripts are not allowed in AMP",
+        "amp(forbidden-script): Only the AMP runtime, AMP components and JSON scripts are allowed in AMP",
+        "amp(forbidden-style): Only <style amp-custom> in <head> is allowed in AMP",
+      ]
+    );
+  }
+
+  #[test]
+  fn css_size() {
+    let style = format!(r#"<p style="{}">"#, "a".repeat(1001));
+    assert_eq!(
+      errors(&"a".repeat(MAX_CSS_BYTES), &style),
+      [
+        "amp(css-too-large): `style` attribute of 1001 bytes, over the limit of 1000 bytes",
+        "amp(css-too-large): CSS of 76001 bytes, over the limit of 75000 bytes",
+      ]
+    );
+  }
+
+  #[test]
+  fn missing_boilerplate() {
+    let allocator = Allocator::default();
+    let program = Parser::html(&allocator, "<html><head><title>x</title></head></html>")
+      .parse()
+      .program;
+    let errors: Vec<_> = validate(&program).iter().map(ToString::to_string).collect();
+    assert_eq!(
+      errors,
+      [
+        "<html> must have the `amp` or `⚡` attribute",
+        "Missing <!doctype html>",
+        "Missing <body>",
+        r#"Missing <meta charset="utf-8"> as the first child of <head>"#,
+        r#"Missing <meta name="viewport">"#,
+        r#"Missing <script async src="https://cdn.ampproject.org/v0.js"> in <head>"#,
+        "Missing <style amp-boilerplate>",
+        "Missing <noscript><style amp-boilerplate>",
+      ]
+    );
+  }
+}
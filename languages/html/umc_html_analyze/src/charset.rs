@@ -0,0 +1,146 @@
+use umc_html_ast::{Element, Node, Program};
+use umc_html_parser::encoding::{Encoding, extract_charset};
+use umc_span::Span;
+
+/// How a [`CharsetDeclaration`] was written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharsetSource {
+  /// `<meta charset="utf-8">`
+  MetaCharset,
+  /// `<meta http-equiv="content-type" content="text/html; charset=utf-8">`
+  HttpEquiv,
+}
+
+/// A character encoding declared in the document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CharsetDeclaration<'a> {
+  /// The encoding label as written, e.g. `UTF-8`
+  pub label: &'a str,
+  /// How the encoding was declared
+  pub source: CharsetSource,
+  /// Span of the `<meta>` element
+  pub span: Span,
+}
+
+impl CharsetDeclaration<'_> {
+  /// Resolve the label to an encoding, `None` if the label is unknown.
+  pub fn encoding(&self) -> Option<&'static Encoding> {
+    Encoding::for_label(self.label.as_bytes())
+  }
+}
+
+/// Get every character encoding declaration of the document, in document order.
+///
+/// Unlike the byte stream [prescan](umc_html_parser::encoding::prescan), this looks at the
+/// whole tree, so it can also report declarations browsers would ignore, e.g. after the
+/// first 1024 bytes or in `<body>`.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::charset::{CharsetSource, charset_declarations};
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, r#"<head><meta charset="utf-8"></head>"#);
+/// let program = parser.parse().program;
+///
+/// let declarations = charset_declarations(&program);
+/// assert_eq!(declarations[0].label, "utf-8");
+/// assert_eq!(declarations[0].source, CharsetSource::MetaCharset);
+/// ```
+pub fn charset_declarations<'a>(program: &Program<'a>) -> Vec<CharsetDeclaration<'a>> {
+  let mut declarations = Vec::new();
+  collect(program, &mut declarations);
+  declarations
+}
+
+/// Get the first character encoding declaration of the document.
+pub fn declared_charset<'a>(program: &Program<'a>) -> Option<CharsetDeclaration<'a>> {
+  charset_declarations(program).into_iter().next()
+}
+
+fn collect<'a>(nodes: &[Node<'a>], declarations: &mut Vec<CharsetDeclaration<'a>>) {
+  for node in nodes {
+    if let Node::Element(element) = node {
+      if element.tag_name.eq_ignore_ascii_case("meta")
+        && let Some(declaration) = meta_declaration(element)
+      {
+        declarations.push(declaration);
+      }
+      collect(&element.children, declarations);
+    }
+  }
+}
+
+fn meta_declaration<'a>(element: &Element<'a>) -> Option<CharsetDeclaration<'a>> {
+  let value = |name: &str| {
+    element
+      .attributes
+      .iter()
+      .find(|a| a.key.value.eq_ignore_ascii_case(name))
+      .map(|a| a.value.as_ref().map_or("", |v| v.value))
+  };
+
+  if let Some(label) = value("charset") {
+    return Some(CharsetDeclaration {
+      label: label.trim_ascii(),
+      source: CharsetSource::MetaCharset,
+      span: element.span,
+    });
+  }
+
+  let is_content_type = value("http-equiv").is_some_and(|v| v.eq_ignore_ascii_case("content-type"));
+  if is_content_type && let Some(label) = value("content").and_then(extract_charset) {
+    return Some(CharsetDeclaration {
+      label,
+      source: CharsetSource::HttpEquiv,
+      span: element.span,
+    });
+  }
+
+  None
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::{CreateHtml, encoding::Encoding};
+  use umc_parser::Parser;
+  use umc_span::Span;
+
+  use super::{CharsetDeclaration, CharsetSource, charset_declarations};
+
+  #[test]
+  fn declarations() {
+    let allocator = Allocator::default();
+    let parser = Parser::html(
+      &allocator,
+      r#"<html><head><meta http-equiv="Content-Type" content="text/html; charset=Shift_JIS"><meta name="x" content="charset=a"></head><body><meta charset=" latin1 "></body></html>"#,
+    );
+    let program = parser.parse().program;
+    let declarations = charset_declarations(&program);
+
+    assert_eq!(
+      declarations,
+      [
+        CharsetDeclaration {
+          label: "Shift_JIS",
+          source: CharsetSource::HttpEquiv,
+          span: Span::new(12, 83),
+        },
+        CharsetDeclaration {
+          label: "latin1",
+          source: CharsetSource::MetaCharset,
+          span: Span::new(131, 156),
+        },
+      ]
+    );
+    assert_eq!(
+      declarations[1].encoding().map(Encoding::name),
+      Some("windows-1252")
+    );
+  }
+}
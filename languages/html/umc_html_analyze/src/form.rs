@@ -0,0 +1,379 @@
+//! Forms of a document, with their controls and labels.
+//!
+//! A control belongs to the form given by its `form` attribute, or else to its nearest
+//! `<form>` ancestor, like its [form owner](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#form-owner)
+//! in browsers. A label labels the element given by its `for` attribute, or else its first
+//! labelable descendant.
+
+use std::collections::HashMap;
+
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use umc_html_ast::{Element, Node, Program};
+use umc_span::Span;
+
+/// A `<form>` element.
+#[derive(Debug, Clone)]
+pub struct Form<'e, 'a> {
+  pub element: &'e Element<'a>,
+  pub id: Option<&'a str>,
+  pub name: Option<&'a str>,
+  /// Indexes of the controls owned by the form in [`FormModel::controls`]
+  pub controls: Vec<usize>,
+}
+
+/// A form control: a form-associated element like `<input>`, or a labelable one like
+/// `<meter>`.
+#[derive(Debug, Clone)]
+pub struct FormControl<'e, 'a> {
+  pub element: &'e Element<'a>,
+  pub id: Option<&'a str>,
+  pub name: Option<&'a str>,
+  /// Index of the form owning the control in [`FormModel::forms`]
+  pub form: Option<usize>,
+  /// Indexes of the labels of the control in [`FormModel::labels`]
+  pub labels: Vec<usize>,
+}
+
+/// A `<label>` element.
+#[derive(Debug, Clone)]
+pub struct Label<'e, 'a> {
+  pub element: &'e Element<'a>,
+  /// The value of the `for` attribute
+  pub for_id: Option<&'a str>,
+  /// Index of the labelled control in [`FormModel::controls`]
+  pub control: Option<usize>,
+}
+
+/// The forms, controls and labels of a document, in document order.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::form::FormModel;
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(
+///   &allocator,
+///   "<form id=login><label>User <input name=user></label></form><button form=login>Go</button>",
+/// );
+/// let program = parser.parse().program;
+///
+/// let model = FormModel::from_program(&program);
+/// assert_eq!(model.forms[0].controls, [0, 1]);
+/// assert_eq!(model.labels[0].control, Some(0));
+/// assert!(model.errors.is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct FormModel<'e, 'a> {
+  pub forms: Vec<Form<'e, 'a>>,
+  pub controls: Vec<FormControl<'e, 'a>>,
+  pub labels: Vec<Label<'e, 'a>>,
+  /// Duplicate ids, duplicate names in a form, and `form` or `for` attributes referencing
+  /// no suitable element
+  pub errors: Vec<OxcDiagnostic>,
+}
+
+/// An element with an id.
+#[derive(Clone, Copy)]
+enum Target {
+  Form(usize),
+  Control(usize),
+  Other,
+}
+
+#[derive(Default)]
+struct Builder<'e, 'a> {
+  model: FormModel<'e, 'a>,
+  /// id -> first element with this id, and the span of its value
+  ids: HashMap<&'a str, (Target, Span)>,
+  /// `form` attributes of the controls, resolved once every id is known
+  form_attributes: Vec<(usize, &'a str, Span)>,
+  /// Labels without `for` waiting for a labelable descendant
+  open_labels: Vec<usize>,
+}
+
+impl<'e, 'a> FormModel<'e, 'a> {
+  /// Analyze the forms of a document.
+  pub fn from_program(program: &'e Program<'a>) -> Self {
+    let mut builder = Builder::default();
+    builder.walk(program, None);
+    builder.finish()
+  }
+}
+
+impl<'e, 'a> Builder<'e, 'a> {
+  fn walk(&mut self, nodes: &'e [Node<'a>], ancestor: Option<usize>) {
+    for node in nodes {
+      match node {
+        Node::Element(element) => self.element(element, ancestor),
+        Node::Comment(comment) => {
+          if let Some(conditional) = &comment.conditional {
+            self.walk(&conditional.children, ancestor);
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  fn element(&mut self, element: &'e Element<'a>, ancestor: Option<usize>) {
+    let id = element.attribute("id").filter(|id| !id.is_empty());
+    let name = element.attribute("name");
+    let mut ancestor = ancestor;
+    let mut open_label = false;
+
+    let target = match element.name() {
+      "form" => {
+        self.model.forms.push(Form {
+          element,
+          id,
+          name,
+          controls: Vec::new(),
+        });
+        ancestor = Some(self.model.forms.len() - 1);
+        ancestor.map_or(Target::Other, Target::Form)
+      }
+      "label" => {
+        let for_id = element.attribute("for");
+        self.model.labels.push(Label {
+          element,
+          for_id,
+          control: None,
+        });
+        if for_id.is_none() {
+          self.open_labels.push(self.model.labels.len() - 1);
+          open_label = true;
+        }
+        Target::Other
+      }
+      tag_name if is_listed(tag_name) || is_labelable(element) => {
+        let index = self.model.controls.len();
+        let listed = is_listed(tag_name);
+        let form = match element
+          .attributes
+          .iter()
+          .find(|attribute| attribute.key.normalized.eq_ignore_ascii_case("form"))
+        {
+          Some(attribute) if listed => {
+            let value = attribute.value.as_ref();
+            self.form_attributes.push((
+              index,
+              value.map_or("", |value| value.value),
+              value.map_or(attribute.span, |value| value.span),
+            ));
+            None
+          }
+          _ if listed => ancestor,
+          _ => None,
+        };
+        self.model.controls.push(FormControl {
+          element,
+          id,
+          name,
+          form,
+          labels: Vec::new(),
+        });
+        if is_labelable(element) {
+          for &label in &self.open_labels {
+            self.model.labels[label].control.get_or_insert(index);
+          }
+        }
+        Target::Control(index)
+      }
+      _ => Target::Other,
+    };
+
+    if let Some(id) = id {
+      self.id(id, element, target);
+    }
+    self.walk(&element.children, ancestor);
+    if open_label {
+      self.open_labels.pop();
+    }
+  }
+
+  /// Record an id, reporting it if it is a duplicate.
+  fn id(&mut self, id: &'a str, element: &Element<'a>, target: Target) {
+    let span = element
+      .attributes
+      .iter()
+      .find(|attribute| attribute.key.normalized.eq_ignore_ascii_case("id"))
+      .and_then(|attribute| attribute.value.as_ref())
+      .map_or(element.span, |value| value.span);
+    if let Some((_, first)) = self.ids.get(id) {
+      self.model.errors.push(
+        OxcDiagnostic::error(format!("Duplicate id `{id}`")).with_labels([
+          LabeledSpan::new_primary_with_span(Some("duplicated here".to_string()), span),
+          LabeledSpan::new_with_span(Some("first used here".to_string()), *first),
+        ]),
+      );
+    } else {
+      self.ids.insert(id, (target, span));
+    }
+  }
+
+  /// Resolve the `form` and `for` attributes, and report duplicate names.
+  fn finish(mut self) -> FormModel<'e, 'a> {
+    for &(control, id, span) in &self.form_attributes {
+      match self.ids.get(id) {
+        Some((Target::Form(form), _)) => self.model.controls[control].form = Some(*form),
+        _ => self.model.errors.push(
+          OxcDiagnostic::error(format!("No form with id `{id}` for this control")).with_label(span),
+        ),
+      }
+    }
+
+    for label in &mut self.model.labels {
+      let Some(id) = label.for_id else {
+        continue;
+      };
+      match self.ids.get(id) {
+        Some((Target::Control(control), _))
+          if is_labelable(self.model.controls[*control].element) =>
+        {
+          label.control = Some(*control);
+        }
+        _ => self.model.errors.push(
+          OxcDiagnostic::error(format!(
+            "No labelable element with id `{id}` for this label"
+          ))
+          .with_label(label.element.span),
+        ),
+      }
+    }
+    for (index, label) in self.model.labels.iter().enumerate() {
+      if let Some(control) = label.control {
+        self.model.controls[control].labels.push(index);
+      }
+    }
+
+    for (index, control) in self.model.controls.iter().enumerate() {
+      if let Some(form) = control.form {
+        self.model.forms[form].controls.push(index);
+      }
+    }
+    self.duplicate_names();
+    self.model
+  }
+
+  /// Report controls of a form with the name of another, radio buttons and checkboxes aside
+  /// as they share a name on purpose.
+  fn duplicate_names(&mut self) {
+    for form in &self.model.forms {
+      let mut names: HashMap<&str, Span> = HashMap::new();
+      for &index in &form.controls {
+        let control = &self.model.controls[index];
+        let Some(name) = control.name.filter(|name| !name.is_empty()) else {
+          continue;
+        };
+        let grouped = control.element.attribute("type").is_some_and(|kind| {
+          kind.eq_ignore_ascii_case("radio") || kind.eq_ignore_ascii_case("checkbox")
+        });
+        if grouped {
+          continue;
+        }
+        let span = control.element.span;
+        if let Some(first) = names.get(name) {
+          self.model.errors.push(
+            OxcDiagnostic::error(format!("Duplicate name `{name}` in a form")).with_labels([
+              LabeledSpan::new_primary_with_span(Some("duplicated here".to_string()), span),
+              LabeledSpan::new_with_span(Some("first used here".to_string()), *first),
+            ]),
+          );
+        } else {
+          names.insert(name, span);
+        }
+      }
+    }
+  }
+}
+
+/// Whether an element is a [listed](https://html.spec.whatwg.org/multipage/forms.html#category-listed)
+/// form-associated element, which has a form owner.
+fn is_listed(tag_name: &str) -> bool {
+  matches!(
+    tag_name,
+    "button" | "fieldset" | "input" | "object" | "output" | "select" | "textarea"
+  )
+}
+
+/// Whether an element is [labelable](https://html.spec.whatwg.org/multipage/forms.html#category-label).
+fn is_labelable(element: &Element) -> bool {
+  match element.name() {
+    "button" | "meter" | "output" | "progress" | "select" | "textarea" => true,
+    "input" => !element
+      .attribute("type")
+      .is_some_and(|kind| kind.eq_ignore_ascii_case("hidden")),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use super::FormModel;
+
+  #[test]
+  fn forms() {
+    let allocator = Allocator::default();
+    let html = r"<form id=a name=first>
+        <label for=email>Email</label><input id=email name=email>
+        <label>Remember <input type=checkbox name=remember><input type=checkbox name=remember></label>
+        <input name=email form=b><input type=hidden name=token><meter id=m></meter>
+        <fieldset><select name=email></select></fieldset>
+      </form>
+      <form id=b><label for=m>Level</label><label for=token>Token</label></form>
+      <button form=missing>Go</button><input form=a name=outside><p id=email></p>";
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+    let model = FormModel::from_program(&program);
+
+    let names = |form: usize| -> Vec<_> {
+      model.forms[form]
+        .controls
+        .iter()
+        .map(|&index| {
+          let control = &model.controls[index];
+          control.name.unwrap_or_else(|| control.element.name())
+        })
+        .collect()
+    };
+    assert_eq!(
+      names(0),
+      [
+        "email", "remember", "remember", "token", "fieldset", "email", "outside"
+      ]
+    );
+    assert_eq!(names(1), ["email"]);
+
+    let labels: Vec<_> = model
+      .labels
+      .iter()
+      .map(|label| {
+        label
+          .control
+          .map(|index| model.controls[index].element.name())
+      })
+      .collect();
+    assert_eq!(labels, [Some("input"), Some("input"), Some("meter"), None]);
+    assert_eq!(model.controls[1].labels, [1]);
+    assert_eq!(model.controls[0].labels, [0]);
+
+    let errors: Vec<_> = model.errors.iter().map(ToString::to_string).collect();
+    assert_eq!(
+      errors,
+      [
+        "Duplicate id `email`",
+        "No form with id `missing` for this control",
+        "No labelable element with id `token` for this label",
+        "Duplicate name `email` in a form",
+      ]
+    );
+  }
+}
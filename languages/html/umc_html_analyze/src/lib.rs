@@ -0,0 +1,25 @@
+//! Analysis helpers for HTML ASTs.
+//!
+//! This crate collects analyses which work on an already parsed tree, such as the
+//! attribute microsyntaxes of the HTML standard:
+//!
+//! - [`srcset`]: image candidates of `srcset` attributes.
+//! - [`sizes`]: source sizes of `sizes` attributes.
+//! - [`charset`]: character encodings declared by `<meta>` elements.
+//! - [`form`]: forms with their controls and labels.
+//! - [`outline`]: headings, sections and elements with an `id`, as a tree of symbols.
+//! - [`table`]: the grid of tables, laid out like browsers do from tag soup.
+//! - [`amp`]: validation of AMP documents.
+//! - [`mathml`]: the MathML elements of a document and the validation of their children.
+//!
+//! Every parsed value keeps the spans of its parts in the source text, and malformed input
+//! is reported as [`OxcDiagnostic`](oxc_diagnostics::OxcDiagnostic)s.
+
+pub mod amp;
+pub mod charset;
+pub mod form;
+pub mod mathml;
+pub mod outline;
+pub mod sizes;
+pub mod srcset;
+pub mod table;
@@ -0,0 +1,426 @@
+//! Validation of [MathML](https://www.w3.org/TR/mathml-core/) in HTML documents.
+//!
+//! Like browsers, elements are in the MathML namespace from a `<math>` element down to the
+//! HTML integration points: the content of token elements like `<mi>`, except `<mglyph>` and
+//! `<malignmark>`, and of `<annotation-xml>` with an HTML encoding. `<svg>` starts SVG
+//! content. In the MathML namespace, unknown elements and the children MathML does not
+//! allow are reported, e.g. an `<mfrac>` without exactly a numerator and a denominator.
+//!
+//! Every diagnostic has the code of its [`MathMlErrorKind`], e.g. `mathml(wrong-arity)`.
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Element, Node, Program};
+use umc_span::Span;
+
+/// Scope of the error code of every MathML diagnostic.
+pub const ERROR_CODE_SCOPE: &str = "mathml";
+
+/// The MathML namespace, the only value allowed for the `xmlns` of `<math>`.
+pub const NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Elements of MathML Core, and of MathML 3 still supported by browsers.
+const ELEMENTS: &[&str] = &[
+  "annotation",
+  "annotation-xml",
+  "maction",
+  "malignmark",
+  "math",
+  "menclose",
+  "merror",
+  "mfenced",
+  "mfrac",
+  "mglyph",
+  "mi",
+  "mmultiscripts",
+  "mn",
+  "mo",
+  "mover",
+  "mpadded",
+  "mphantom",
+  "mprescripts",
+  "mroot",
+  "mrow",
+  "ms",
+  "mspace",
+  "msqrt",
+  "mstyle",
+  "msub",
+  "msubsup",
+  "msup",
+  "mtable",
+  "mtd",
+  "mtext",
+  "mtr",
+  "munder",
+  "munderover",
+  "none",
+  "semantics",
+];
+
+/// Token elements, whose content is text, and HTML for the elements other than
+/// [`TOKEN_CHILDREN`].
+const TOKEN_ELEMENTS: &[&str] = &["mi", "mn", "mo", "ms", "mtext"];
+
+/// The MathML elements allowed in token elements.
+const TOKEN_CHILDREN: &[&str] = &["mglyph", "malignmark"];
+
+/// Elements with a fixed number of children, like the numerator and denominator of
+/// `<mfrac>`, with the names of their children for messages.
+const ARITIES: &[(&str, &[&str])] = &[
+  ("mfrac", &["numerator", "denominator"]),
+  ("mroot", &["base", "index"]),
+  ("msub", &["base", "subscript"]),
+  ("msup", &["base", "superscript"]),
+  ("msubsup", &["base", "subscript", "superscript"]),
+  ("munder", &["base", "underscript"]),
+  ("mover", &["base", "overscript"]),
+  ("munderover", &["base", "underscript", "overscript"]),
+];
+
+/// Kind of a MathML validation error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MathMlErrorKind {
+  /// An element in the MathML namespace which is not a MathML element, e.g. `<mfoo>`
+  UnknownElement,
+  /// An element with a fixed number of children with another number, e.g. `<mfrac>` with a
+  /// single child
+  WrongArity,
+  /// A child MathML does not allow in its parent, e.g. an `<mtd>` outside of `<mtr>`
+  InvalidChild,
+  /// A `<math>` with an `xmlns` other than the MathML namespace
+  WrongNamespace,
+}
+
+impl MathMlErrorKind {
+  /// The code of the kind, e.g. `wrong-arity`.
+  pub const fn code(self) -> &'static str {
+    match self {
+      Self::UnknownElement => "unknown-element",
+      Self::WrongArity => "wrong-arity",
+      Self::InvalidChild => "invalid-child",
+      Self::WrongNamespace => "wrong-namespace",
+    }
+  }
+
+  fn error(self, message: impl Into<String>, span: Span) -> OxcDiagnostic {
+    let diagnostic = if self == Self::UnknownElement {
+      OxcDiagnostic::warn(message.into())
+    } else {
+      OxcDiagnostic::error(message.into())
+    };
+    diagnostic
+      .with_label(span)
+      .with_error_code(ERROR_CODE_SCOPE, self.code())
+  }
+}
+
+/// Whether a name is the one of a MathML element, e.g. `mfrac`.
+pub fn is_mathml_element(name: &str) -> bool {
+  ELEMENTS.contains(&name)
+}
+
+/// Get the elements in the MathML namespace, in document order.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::mathml::mathml_elements;
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<p><math><mi>x</mi><mtext><b>if</b></mtext></math></p>");
+/// let program = parser.parse().program;
+///
+/// let names: Vec<_> = mathml_elements(&program).iter().map(|element| element.name()).collect();
+/// assert_eq!(names, ["math", "mi", "mtext"]);
+/// ```
+pub fn mathml_elements<'b, 'a>(program: &'b Program<'a>) -> Vec<&'b Element<'a>> {
+  let mut elements = Vec::new();
+  walk(program, false, &mut |element| elements.push(element));
+  elements
+}
+
+/// Validate the MathML of a document, getting its errors in document order.
+///
+/// Unknown elements are warnings, since browsers render them like `<mrow>`.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::mathml::validate;
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<math><mfrac><mn>1</mn></mfrac></math>");
+/// let program = parser.parse().program;
+///
+/// let errors = validate(&program);
+/// assert_eq!(errors[0].to_string(), "<mfrac> must have 2 children, found 1");
+/// ```
+pub fn validate(program: &Program) -> Vec<OxcDiagnostic> {
+  let mut errors = Vec::new();
+  walk(program, false, &mut |element| check(element, &mut errors));
+  errors
+}
+
+/// Call `f` with the elements of `nodes` and their descendants in the MathML namespace.
+fn walk<'b, 'a>(nodes: &'b [Node<'a>], in_mathml: bool, f: &mut impl FnMut(&'b Element<'a>)) {
+  for node in nodes {
+    let Node::Element(element) = node else {
+      continue;
+    };
+    let name = element.name();
+    if !in_mathml && name != "math" {
+      walk(&element.children, false, f);
+      continue;
+    }
+    if in_mathml && name == "svg" {
+      continue;
+    }
+    f(element);
+    if TOKEN_ELEMENTS.contains(&name) {
+      // The content of token elements is HTML, except the token children
+      for child in &element.children {
+        if let Node::Element(child) = child {
+          if TOKEN_CHILDREN.contains(&child.name()) {
+            f(child);
+          } else {
+            walk(&child.children, false, f);
+          }
+        }
+      }
+    } else {
+      walk(&element.children, !is_html_annotation(element), f);
+    }
+  }
+}
+
+/// Whether an element is an `<annotation-xml>` whose content is HTML.
+fn is_html_annotation(element: &Element) -> bool {
+  element.name() == "annotation-xml"
+    && element.attribute("encoding").is_some_and(|encoding| {
+      encoding.eq_ignore_ascii_case("text/html")
+        || encoding.eq_ignore_ascii_case("application/xhtml+xml")
+    })
+}
+
+fn child_elements<'b, 'a>(element: &'b Element<'a>) -> impl Iterator<Item = &'b Element<'a>> {
+  element.children.iter().filter_map(|node| match node {
+    Node::Element(element) => Some(&**element),
+    _ => None,
+  })
+}
+
+fn check(element: &Element, errors: &mut Vec<OxcDiagnostic>) {
+  let name = element.name();
+  if !is_mathml_element(name) {
+    errors.push(
+      MathMlErrorKind::UnknownElement
+        .error(format!("<{name}> is not a MathML element"), element.span),
+    );
+    return;
+  }
+  let children: Vec<_> = child_elements(element).collect();
+
+  if let Some((_, roles)) = ARITIES.iter().find(|(parent, _)| *parent == name) {
+    if children.len() != roles.len() {
+      errors.push(
+        MathMlErrorKind::WrongArity
+          .error(
+            format!(
+              "<{name}> must have {} children, found {}",
+              roles.len(),
+              children.len()
+            ),
+            element.span,
+          )
+          .with_help(format!(
+            "Its children are the {}, wrap several elements in <mrow>",
+            roles.join(", ")
+          )),
+      );
+    }
+    return;
+  }
+
+  let invalid_child =
+    |child: &Element, message: String| MathMlErrorKind::InvalidChild.error(message, child.span);
+  match name {
+    "math" => {
+      if let Some(xmlns) = element.attribute("xmlns")
+        && xmlns != NAMESPACE
+      {
+        errors.push(MathMlErrorKind::WrongNamespace.error(
+          format!("The namespace of <math> must be `{NAMESPACE}`, found `{xmlns}`"),
+          element.span,
+        ));
+      }
+    }
+    "mmultiscripts" => check_multiscripts(element, &children, errors),
+    "mtable" => errors.extend(
+      children
+        .iter()
+        .filter(|child| child.name() != "mtr")
+        .map(|child| {
+          invalid_child(
+            child,
+            format!("<{}> is not allowed in <mtable>, only <mtr>", child.name()),
+          )
+        }),
+    ),
+    "mtr" => errors.extend(
+      children
+        .iter()
+        .filter(|child| child.name() != "mtd")
+        .map(|child| {
+          invalid_child(
+            child,
+            format!("<{}> is not allowed in <mtr>, only <mtd>", child.name()),
+          )
+        }),
+    ),
+    "semantics" => errors.extend(
+      children
+        .iter()
+        .skip(1)
+        .filter(|child| !matches!(child.name(), "annotation" | "annotation-xml"))
+        .map(|child| {
+          invalid_child(
+            child,
+            format!(
+              "<{}> is not allowed after the first child of <semantics>, only annotations",
+              child.name()
+            ),
+          )
+        }),
+    ),
+    _ => {}
+  }
+  for child in &children {
+    let parent = match child.name() {
+      "mtr" => "mtable",
+      "mtd" => "mtr",
+      "mprescripts" => "mmultiscripts",
+      "annotation" | "annotation-xml" => "semantics",
+      _ => continue,
+    };
+    if name != parent {
+      errors.push(invalid_child(
+        child,
+        format!("<{}> is only allowed in <{parent}>", child.name()),
+      ));
+    }
+  }
+}
+
+/// Check the children of `<mmultiscripts>`: a base, then pairs of subscripts and
+/// superscripts, then `<mprescripts>` and pairs of prescripts.
+fn check_multiscripts(element: &Element, children: &[&Element], errors: &mut Vec<OxcDiagnostic>) {
+  let mut prescripts = children
+    .iter()
+    .enumerate()
+    .filter(|(_, child)| child.name() == "mprescripts");
+  let split = prescripts.next().map(|(index, _)| index);
+  if let Some((_, extra)) = prescripts.next() {
+    errors.push(MathMlErrorKind::InvalidChild.error(
+      "<mmultiscripts> must have at most one <mprescripts>",
+      extra.span,
+    ));
+    return;
+  }
+  let (scripts, prescripts) = split.map_or((children, &[][..]), |index| {
+    (&children[..index], &children[index + 1..])
+  });
+  if scripts
+    .first()
+    .is_none_or(|base| base.name() == "mprescripts")
+    || scripts.len() % 2 == 0
+  {
+    errors.push(
+      MathMlErrorKind::WrongArity
+        .error(
+          "<mmultiscripts> must have a base followed by pairs of scripts",
+          element.span,
+        )
+        .with_help("Use <none/> for a missing script"),
+    );
+  } else if prescripts.len() % 2 != 0 {
+    errors.push(
+      MathMlErrorKind::WrongArity
+        .error(
+          "<mmultiscripts> must have pairs of prescripts after <mprescripts>",
+          element.span,
+        )
+        .with_help("Use <none/> for a missing script"),
+    );
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use super::validate;
+
+  fn errors(source_text: &str) -> Vec<String> {
+    let allocator = Allocator::default();
+    let program = Parser::html(&allocator, source_text).parse().program;
+    validate(&program)
+      .iter()
+      .map(|error| format!("{}: {error}", error.code))
+      .collect()
+  }
+
+  #[test]
+  fn valid() {
+    let math = r#"<math xmlns="http://www.w3.org/1998/Math/MathML" display="block">
+  <semantics>
+    <mrow>
+      <mfrac><mi>a</mi><mrow><mi>b</mi><mo>+</mo><mn>1</mn></mrow></mfrac>
+      <msubsup><mi>x</mi><mn>0</mn><mn>2</mn></msubsup>
+      <mmultiscripts><mi>F</mi><mi>a</mi><none/><mprescripts/><mi>b</mi><none/></mmultiscripts>
+      <mtable><mtr><mtd><mtext>if <b>x</b> <mfrac></mfrac></mtext></mtd></mtr></mtable>
+      <mi><mglyph src="a.png"/></mi>
+    </mrow>
+    <annotation-xml encoding="text/html"><mfrac></mfrac></annotation-xml>
+    <annotation encoding="application/x-tex">\frac{a}{b+1}</annotation>
+  </semantics>
+</math>
+<mfrac></mfrac>"#;
+    assert_eq!(errors(math), Vec::<String>::new());
+  }
+
+  #[test]
+  fn invalid() {
+    let math = r#"<math xmlns="http://www.w3.org/1999/xhtml">
+  <mfrac><mi>a</mi><mi>b</mi><mi>c</mi></mfrac>
+  <msqrt><mtd></mtd></msqrt>
+  <mtable><mtd></mtd></mtable>
+  <mmultiscripts><mi>F</mi><mi>a</mi></mmultiscripts>
+  <mmultiscripts><mi>F</mi><mprescripts/><mi>a</mi></mmultiscripts>
+  <semantics><mi>x</mi><mi>y</mi></semantics>
+  <mfoo></mfoo>
+  <svg><mfrac></mfrac></svg>
+</math>"#;
+    assert_eq!(
+      errors(math),
+      [
+        "mathml(wrong-namespace): The namespace of <math> must be `http://www.w3.org/1998/Math/MathML`, found `http://www.w3.org/1999/xhtml`",
+        "mathml(wrong-arity): <mfrac> must have 2 children, found 3",
+        "mathml(invalid-child): <mtd> is only allowed in <mtr>",
+        "mathml(invalid-child): <mtd> is not allowed in <mtable>, only <mtr>",
+        "mathml(invalid-child): <mtd> is only allowed in <mtr>",
+        "mathml(wrong-arity): <mmultiscripts> must have a base followed by pairs of scripts",
+        "mathml(wrong-arity): <mmultiscripts> must have pairs of prescripts after <mprescripts>",
+        "mathml(invalid-child): <mi> is not allowed after the first child of <semantics>, only annotations",
+        "mathml(unknown-element): <mfoo> is not a MathML element",
+      ]
+    );
+  }
+}
@@ -0,0 +1,259 @@
+use umc_html_ast::{Element, Node, Program};
+use umc_span::Span;
+
+/// Kind of an [`OutlineSymbol`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+  /// A heading, `<h1>` to `<h6>`, with its rank from 1 to 6
+  Heading(u8),
+  /// A sectioning element: `<article>`, `<aside>`, `<nav>` or `<section>`
+  Section,
+  /// Any other element with an `id`, which can be the target of a fragment
+  Id,
+}
+
+/// An entry of the outline of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineSymbol<'a> {
+  pub kind: SymbolKind,
+  /// The text of a heading, whitespace collapsed, or the tag name followed by `#` and the
+  /// id, e.g. `section#intro`
+  pub name: String,
+  /// The normalized tag name
+  pub tag_name: &'a str,
+  /// The value of the `id` attribute
+  pub id: Option<&'a str>,
+  /// Span of the element
+  pub span: Span,
+  /// Span of the name: the text of a heading, or the value of the `id`, else the element
+  pub name_span: Span,
+  /// Symbols nested in this one
+  pub children: Vec<Self>,
+}
+
+/// Get the outline of a document, for document symbols or a table of contents.
+///
+/// Headings, sectioning elements and elements with an `id` are symbols. A symbol contains
+/// the symbols of its descendants, and a heading the symbols following it, up to the next
+/// heading of the same or a higher rank, within the same sectioning element.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::outline::{SymbolKind, outline};
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<h1>Guide</h1><h2 id=install>Install</h2>");
+/// let program = parser.parse().program;
+///
+/// let symbols = outline(&program);
+/// assert_eq!(symbols[0].name, "Guide");
+/// assert_eq!(symbols[0].children[0].kind, SymbolKind::Heading(2));
+/// assert_eq!(symbols[0].children[0].id, Some("install"));
+/// ```
+pub fn outline<'a>(program: &Program<'a>) -> Vec<OutlineSymbol<'a>> {
+  let mut symbols = Vec::new();
+  collect(program, &mut symbols);
+  symbols
+}
+
+/// Get the headings of the outline of a document, nested by rank, for a table of contents.
+pub fn table_of_contents<'a>(program: &Program<'a>) -> Vec<OutlineSymbol<'a>> {
+  only_headings(outline(program))
+}
+
+fn only_headings(symbols: Vec<OutlineSymbol<'_>>) -> Vec<OutlineSymbol<'_>> {
+  let mut headings = Vec::new();
+  for mut symbol in symbols {
+    let children = only_headings(std::mem::take(&mut symbol.children));
+    if matches!(symbol.kind, SymbolKind::Heading(_)) {
+      symbol.children = children;
+      headings.push(symbol);
+    } else {
+      headings.extend(children);
+    }
+  }
+  headings
+}
+
+fn collect<'a>(nodes: &[Node<'a>], symbols: &mut Vec<OutlineSymbol<'a>>) {
+  for node in nodes {
+    match node {
+      Node::Element(element) => {
+        if let Some(mut symbol) = element_symbol(element) {
+          collect(&element.children, &mut symbol.children);
+          push(symbols, symbol);
+        } else {
+          collect(&element.children, symbols);
+        }
+      }
+      Node::Comment(comment) => {
+        if let Some(conditional) = &comment.conditional {
+          collect(&conditional.children, symbols);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Push a symbol under the last heading of a lower rank, if any.
+fn push<'a>(symbols: &mut Vec<OutlineSymbol<'a>>, symbol: OutlineSymbol<'a>) {
+  match symbols.last_mut() {
+    Some(last) if contains(last.kind, symbol.kind) => push(&mut last.children, symbol),
+    _ => symbols.push(symbol),
+  }
+}
+
+const fn contains(heading: SymbolKind, symbol: SymbolKind) -> bool {
+  match (heading, symbol) {
+    (SymbolKind::Heading(rank), SymbolKind::Heading(other)) => other > rank,
+    (SymbolKind::Heading(_), _) => true,
+    _ => false,
+  }
+}
+
+fn element_symbol<'a>(element: &Element<'a>) -> Option<OutlineSymbol<'a>> {
+  let tag_name = element.name();
+  let id = element
+    .attributes
+    .iter()
+    .find(|attribute| attribute.key.normalized.eq_ignore_ascii_case("id"))
+    .and_then(|attribute| attribute.value.as_ref())
+    .filter(|value| !value.value.is_empty());
+
+  let kind = match tag_name {
+    "h1" => SymbolKind::Heading(1),
+    "h2" => SymbolKind::Heading(2),
+    "h3" => SymbolKind::Heading(3),
+    "h4" => SymbolKind::Heading(4),
+    "h5" => SymbolKind::Heading(5),
+    "h6" => SymbolKind::Heading(6),
+    "article" | "aside" | "nav" | "section" => SymbolKind::Section,
+    _ if id.is_some() => SymbolKind::Id,
+    _ => return None,
+  };
+
+  let (name, name_span) = if let SymbolKind::Heading(_) = kind {
+    let mut text = String::new();
+    let mut span = None;
+    heading_text(&element.children, &mut text, &mut span);
+    (text, span.unwrap_or(element.span))
+  } else {
+    let name = id.map_or_else(
+      || tag_name.to_string(),
+      |id| format!("{tag_name}#{}", id.value),
+    );
+    (name, id.map_or(element.span, |id| id.span))
+  };
+
+  Some(OutlineSymbol {
+    kind,
+    name,
+    tag_name,
+    id: id.map(|id| id.value),
+    span: element.span,
+    name_span,
+    children: Vec::new(),
+  })
+}
+
+/// Append the text of the nodes to `text`, collapsing whitespace, and extend `span` over it.
+fn heading_text(nodes: &[Node<'_>], text: &mut String, span: &mut Option<Span>) {
+  for node in nodes {
+    match node {
+      Node::Text(node) => {
+        for word in node.value.split_ascii_whitespace() {
+          if !text.is_empty() {
+            text.push(' ');
+          }
+          text.push_str(word);
+        }
+        if !node.value.trim_ascii().is_empty() {
+          let start =
+            node.span.start + (node.value.len() - node.value.trim_ascii_start().len()) as u32;
+          let end = node.span.end - (node.value.len() - node.value.trim_ascii_end().len()) as u32;
+          *span = Some(span.map_or(Span::new(start, end), |span| Span::new(span.start, end)));
+        }
+      }
+      Node::Element(element) => heading_text(&element.children, text, span),
+      _ => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::fmt::Write;
+
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use super::{OutlineSymbol, outline, table_of_contents};
+
+  fn render(symbols: &[OutlineSymbol], depth: usize, out: &mut String) {
+    for symbol in symbols {
+      writeln!(
+        out,
+        "{}{:?} {:?}",
+        "  ".repeat(depth),
+        symbol.kind,
+        symbol.name
+      )
+      .unwrap();
+      render(&symbol.children, depth + 1, out);
+    }
+  }
+
+  #[test]
+  fn outline_symbols() {
+    let allocator = Allocator::default();
+    let html = "<header><H1>  My\n <em>site</em> </H1></header>\
+      <h2>A</h2><p id=a1>x</p><h3>A.1</h3><h2>B</h2>\
+      <section id=b><h3>B.1</h3><div id=''></div><h1>Inner</h1></section>\
+      <h4>Deep</h4><h2></h2>";
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+    let symbols = outline(&program);
+
+    let mut rendered = String::new();
+    render(&symbols, 0, &mut rendered);
+    assert_eq!(
+      rendered,
+      r#"Heading(1) "My site"
+  Heading(2) "A"
+    Id "p#a1"
+    Heading(3) "A.1"
+  Heading(2) "B"
+    Section "section#b"
+      Heading(3) "B.1"
+      Heading(1) "Inner"
+    Heading(4) "Deep"
+  Heading(2) ""
+"#
+    );
+
+    let slice = |span: umc_span::Span| &html[span.start as usize..span.end as usize];
+    assert_eq!(slice(symbols[0].name_span), "My\n <em>site");
+    assert_eq!(slice(symbols[0].children[1].children[0].name_span), "b");
+
+    let mut rendered = String::new();
+    render(&table_of_contents(&program), 0, &mut rendered);
+    assert_eq!(
+      rendered,
+      r#"Heading(1) "My site"
+  Heading(2) "A"
+    Heading(3) "A.1"
+  Heading(2) "B"
+    Heading(3) "B.1"
+    Heading(1) "Inner"
+    Heading(4) "Deep"
+  Heading(2) ""
+"#
+    );
+  }
+}
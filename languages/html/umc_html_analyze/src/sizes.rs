@@ -0,0 +1,222 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Attribute, AttributeValue};
+use umc_span::Span;
+
+/// CSS length units allowed in a source size.
+const LENGTH_UNITS: &[&str] = &[
+  "cap", "ch", "cm", "dvb", "dvh", "dvi", "dvmax", "dvmin", "dvw", "em", "ex", "ic", "in", "lh",
+  "lvb", "lvh", "lvi", "lvmax", "lvmin", "lvw", "mm", "pc", "pt", "px", "q", "rcap", "rch", "rem",
+  "rex", "ric", "rlh", "svb", "svh", "svi", "svmax", "svmin", "svw", "vb", "vh", "vi", "vmax",
+  "vmin", "vw",
+];
+
+/// CSS functions allowed as a source size.
+const SIZE_FUNCTIONS: &[&str] = &["calc", "clamp", "max", "min"];
+
+/// A single entry of a `sizes` attribute, e.g. `(max-width: 600px) 480px`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceSize<'a> {
+  /// Span of the whole entry, without surrounding whitespace
+  pub span: Span,
+  /// The media condition, e.g. `(max-width: 600px)`
+  pub media: Option<&'a str>,
+  /// The size, e.g. `480px`, `calc(100vw - 2em)` or `auto`
+  pub size: &'a str,
+  /// Span of the size
+  pub size_span: Span,
+}
+
+/// A parsed `sizes` attribute.
+///
+/// Follows the [source size list](https://html.spec.whatwg.org/multipage/images.html#sizes-attributes)
+/// syntax. Media conditions are kept as written, and sizes are only checked to be lengths.
+#[derive(Debug, Default)]
+pub struct Sizes<'a> {
+  /// Valid entries, in source order
+  pub sizes: Vec<SourceSize<'a>>,
+  /// Malformed entries
+  pub errors: Vec<OxcDiagnostic>,
+}
+
+impl<'a> Sizes<'a> {
+  /// Parse a `sizes` or `imagesizes` attribute, or `None` for any other attribute.
+  pub fn from_attribute(attribute: &Attribute<'a>) -> Option<Self> {
+    let name = attribute.key.value;
+    if !name.eq_ignore_ascii_case("sizes") && !name.eq_ignore_ascii_case("imagesizes") {
+      return None;
+    }
+    Some(
+      attribute
+        .value
+        .as_ref()
+        .map_or_else(Self::default, Self::parse),
+    )
+  }
+
+  /// Parse the value of a `sizes` attribute.
+  pub fn parse(value: &AttributeValue<'a>) -> Self {
+    Self::parse_str(value.value, value.value_span.start)
+  }
+
+  /// Parse `input`, which starts at offset `start` of the source text.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_analyze::sizes::Sizes;
+  ///
+  /// let sizes = Sizes::parse_str("(max-width: 600px) 100vw, 50vw", 0);
+  /// assert_eq!(sizes.sizes[0].media, Some("(max-width: 600px)"));
+  /// assert_eq!(sizes.sizes[1].size, "50vw");
+  /// assert!(sizes.errors.is_empty());
+  /// ```
+  #[expect(clippy::cast_possible_truncation)]
+  pub fn parse_str(input: &'a str, start: u32) -> Self {
+    let span = |from: usize, to: usize| Span::new(start + from as u32, start + to as u32);
+    let mut result = Self::default();
+
+    let entries = split_top_level(input);
+    let count = entries.len();
+    for (index, (entry_start, entry_end)) in entries.into_iter().enumerate() {
+      let entry = &input[entry_start..entry_end];
+      let trimmed_start = entry_start + (entry.len() - entry.trim_ascii_start().len());
+      let entry = entry.trim_ascii();
+      let trimmed_end = trimmed_start + entry.len();
+      let is_last = index + 1 == count;
+
+      if entry.is_empty() {
+        result.errors.push(
+          OxcDiagnostic::error("Empty entry in `sizes`").with_label(span(entry_start, entry_end)),
+        );
+        continue;
+      }
+
+      let size_offset = size_start(entry);
+      let size = &entry[size_offset..];
+      let media = entry[..size_offset].trim_ascii_end();
+      let size_span = span(trimmed_start + size_offset, trimmed_end);
+
+      if !is_valid_size(size) {
+        result.errors.push(
+          OxcDiagnostic::error(format!(
+            "Invalid source size `{size}`, expected a CSS length"
+          ))
+          .with_label(size_span),
+        );
+        continue;
+      }
+      if media.is_empty() && !is_last {
+        result.errors.push(
+          OxcDiagnostic::error("Only the last entry of `sizes` may omit the media condition")
+            .with_label(span(trimmed_start, trimmed_end)),
+        );
+      }
+
+      result.sizes.push(SourceSize {
+        span: span(trimmed_start, trimmed_end),
+        media: (!media.is_empty()).then_some(media),
+        size,
+        size_span,
+      });
+    }
+
+    result
+  }
+}
+
+/// Split at commas outside of parentheses, returning `(start, end)` offsets.
+fn split_top_level(input: &str) -> Vec<(usize, usize)> {
+  let mut entries = Vec::new();
+  let mut depth = 0usize;
+  let mut start = 0;
+
+  for (i, c) in input.bytes().enumerate() {
+    match c {
+      b'(' => depth += 1,
+      b')' => depth = depth.saturating_sub(1),
+      b',' if depth == 0 => {
+        entries.push((start, i));
+        start = i + 1;
+      }
+      _ => {}
+    }
+  }
+  entries.push((start, input.len()));
+  entries
+}
+
+/// Get the offset of the size in a trimmed entry: the last component value.
+fn size_start(entry: &str) -> usize {
+  if entry.ends_with(')') {
+    // a function like `calc(...)`, find the matching parenthesis
+    let mut depth = 0usize;
+    for (i, c) in entry.bytes().enumerate().rev() {
+      match c {
+        b')' => depth += 1,
+        b'(' => {
+          depth -= 1;
+          if depth == 0 {
+            let name_start = entry[..i]
+              .rfind(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+              .map_or(0, |p| p + 1);
+            return name_start;
+          }
+        }
+        _ => {}
+      }
+    }
+    return 0;
+  }
+  entry
+    .rfind(|c: char| c.is_ascii_whitespace())
+    .map_or(0, |p| p + 1)
+}
+
+fn is_valid_size(size: &str) -> bool {
+  if size.eq_ignore_ascii_case("auto") || size == "0" {
+    return true;
+  }
+
+  if let Some(open) = size.find('(') {
+    let name = &size[..open];
+    return SIZE_FUNCTIONS
+      .iter()
+      .any(|function| function.eq_ignore_ascii_case(name));
+  }
+
+  let number_end = size
+    .find(|c: char| !c.is_ascii_digit() && c != '.')
+    .unwrap_or(size.len());
+  let (number, unit) = size.split_at(number_end);
+  !number.is_empty()
+    && number.parse::<f64>().is_ok()
+    && LENGTH_UNITS
+      .iter()
+      .any(|length| length.eq_ignore_ascii_case(unit))
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use super::Sizes;
+
+  #[test]
+  fn sizes() {
+    let sizes = Sizes::parse_str(
+      "(max-width: 600px) calc(100vw - 2em), (orientation: portrait) 50%, 30vw, , 640px",
+      0,
+    );
+    let errors: Vec<_> = sizes
+      .errors
+      .iter()
+      .map(|error| {
+        let label = error.labels.as_ref().and_then(|labels| labels.first());
+        let range = label.map(|label| label.offset()..label.offset() + label.len());
+        format!("{} {range:?}", error.message)
+      })
+      .collect();
+
+    assert_snapshot!(format!("Sizes: {:#?}\nErrors: {errors:#?}", sizes.sizes));
+  }
+}
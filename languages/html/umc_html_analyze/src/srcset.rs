@@ -0,0 +1,420 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Attribute, AttributeValue};
+use umc_span::Span;
+
+/// Descriptor of an [`ImageCandidate`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Descriptor {
+  /// Width in CSS pixels, e.g. `640w`
+  Width(u32),
+  /// Pixel density, e.g. `2x`
+  Density(f64),
+}
+
+/// A single entry of a `srcset` attribute, e.g. `image.png 2x`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageCandidate<'a> {
+  /// Span of the whole candidate, without surrounding whitespace and commas
+  pub span: Span,
+  /// The URL, as written
+  pub url: &'a str,
+  /// Span of the URL
+  pub url_span: Span,
+  /// The width or density descriptor, `None` when omitted (which means `1x`)
+  pub descriptor: Option<Descriptor>,
+}
+
+impl ImageCandidate<'_> {
+  /// Get the pixel density of this candidate, if it is not width based.
+  pub const fn density(&self) -> Option<f64> {
+    match self.descriptor {
+      None => Some(1.0),
+      Some(Descriptor::Density(density)) => Some(density),
+      Some(Descriptor::Width(_)) => None,
+    }
+  }
+}
+
+/// A parsed `srcset` attribute.
+///
+/// Follows the [srcset parsing algorithm](https://html.spec.whatwg.org/multipage/images.html#parsing-a-srcset-attribute).
+/// Invalid candidates are dropped like browsers do, and reported in `errors`.
+#[derive(Debug, Default)]
+pub struct Srcset<'a> {
+  /// Valid image candidates, in source order
+  pub candidates: Vec<ImageCandidate<'a>>,
+  /// Malformed candidates and conformance errors
+  pub errors: Vec<OxcDiagnostic>,
+}
+
+impl<'a> Srcset<'a> {
+  /// Parse a `srcset` or `imagesrcset` attribute, or `None` for any other attribute.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use oxc_allocator::Allocator;
+  /// use umc_html_analyze::srcset::{Descriptor, Srcset};
+  /// use umc_html_ast::Node;
+  /// use umc_html_parser::CreateHtml;
+  /// use umc_parser::Parser;
+  ///
+  /// let allocator = Allocator::default();
+  /// let parser = Parser::html(&allocator, r#"<img srcset="a.png 1x, b.png 2x">"#);
+  /// let program = parser.parse().program;
+  /// let Node::Element(img) = &program[0] else { unreachable!() };
+  ///
+  /// let srcset = Srcset::from_attribute(&img.attributes[0]).unwrap();
+  /// assert_eq!(srcset.candidates[1].url, "b.png");
+  /// assert_eq!(srcset.candidates[1].descriptor, Some(Descriptor::Density(2.0)));
+  /// ```
+  pub fn from_attribute(attribute: &Attribute<'a>) -> Option<Self> {
+    let name = attribute.key.value;
+    if !name.eq_ignore_ascii_case("srcset") && !name.eq_ignore_ascii_case("imagesrcset") {
+      return None;
+    }
+    Some(
+      attribute
+        .value
+        .as_ref()
+        .map_or_else(Self::default, Self::parse),
+    )
+  }
+
+  /// Parse the value of a `srcset` attribute.
+  pub fn parse(value: &AttributeValue<'a>) -> Self {
+    Self::parse_str(value.value, value.value_span.start)
+  }
+
+  /// Parse `input`, which starts at offset `start` of the source text.
+  pub fn parse_str(input: &'a str, start: u32) -> Self {
+    let mut parser = SrcsetParser {
+      input,
+      start,
+      pos: 0,
+      srcset: Self::default(),
+    };
+    parser.parse();
+    parser.check_conformance();
+    parser.srcset
+  }
+}
+
+struct SrcsetParser<'a> {
+  input: &'a str,
+  start: u32,
+  pos: usize,
+  srcset: Srcset<'a>,
+}
+
+#[derive(Clone, Copy)]
+enum DescriptorState {
+  InDescriptor,
+  InParens,
+  AfterDescriptor,
+}
+
+impl SrcsetParser<'_> {
+  #[expect(clippy::cast_possible_truncation)]
+  const fn span(&self, start: usize, end: usize) -> Span {
+    Span::new(self.start + start as u32, self.start + end as u32)
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.input.as_bytes().get(self.pos).copied()
+  }
+
+  fn skip_while(&mut self, f: impl Fn(u8) -> bool) -> usize {
+    let start = self.pos;
+    while self.peek().is_some_and(&f) {
+      self.pos += 1;
+    }
+    self.pos - start
+  }
+
+  fn error(&mut self, message: impl Into<String>, span: Span) {
+    self
+      .srcset
+      .errors
+      .push(OxcDiagnostic::error(message.into()).with_label(span));
+  }
+
+  fn parse(&mut self) {
+    loop {
+      let separator_start = self.pos;
+      self.skip_while(|c| c.is_ascii_whitespace() || c == b',');
+      if self.input[separator_start..self.pos].contains(',') {
+        self.error(
+          "Unexpected comma in `srcset`",
+          self.span(separator_start, self.pos),
+        );
+      }
+
+      if self.pos >= self.input.len() {
+        return;
+      }
+
+      let url_start = self.pos;
+      self.skip_while(|c| !c.is_ascii_whitespace());
+      let mut url_end = self.pos;
+
+      let mut descriptors = Vec::new();
+      if self.input[url_start..url_end].ends_with(',') {
+        let trailing = self.input[url_start..url_end]
+          .bytes()
+          .rev()
+          .take_while(|c| *c == b',')
+          .count();
+        url_end -= trailing;
+        if trailing > 1 {
+          self.error(
+            "Several commas after an image candidate URL",
+            self.span(url_end, url_end + trailing),
+          );
+        }
+      } else {
+        descriptors = self.tokenize_descriptors();
+      }
+
+      let url_span = self.span(url_start, url_end);
+      let end = descriptors.last().map_or(url_end, |(_, end)| *end);
+      if let Ok(descriptor) = self.parse_descriptors(&descriptors, url_span) {
+        self.srcset.candidates.push(ImageCandidate {
+          span: self.span(url_start, end),
+          url: &self.input[url_start..url_end],
+          url_span,
+          descriptor,
+        });
+      }
+    }
+  }
+
+  /// Collect descriptor tokens as `(start, end)` offsets, consuming the separating comma.
+  fn tokenize_descriptors(&mut self) -> Vec<(usize, usize)> {
+    let mut descriptors = Vec::new();
+    let mut state = DescriptorState::InDescriptor;
+
+    self.skip_while(|c| c.is_ascii_whitespace());
+    let mut token_start = self.pos;
+
+    loop {
+      let c = self.peek();
+      match (state, c) {
+        (DescriptorState::InDescriptor, Some(c)) if c.is_ascii_whitespace() => {
+          if token_start < self.pos {
+            descriptors.push((token_start, self.pos));
+          }
+          state = DescriptorState::AfterDescriptor;
+        }
+        (DescriptorState::InDescriptor, Some(b',')) => {
+          if token_start < self.pos {
+            descriptors.push((token_start, self.pos));
+          }
+          self.pos += 1;
+          return descriptors;
+        }
+        (DescriptorState::InDescriptor, Some(b'(')) => state = DescriptorState::InParens,
+        (DescriptorState::InParens, Some(b')')) => state = DescriptorState::InDescriptor,
+        (DescriptorState::InDescriptor | DescriptorState::InParens, None) => {
+          if token_start < self.pos {
+            descriptors.push((token_start, self.pos));
+          }
+          return descriptors;
+        }
+        (DescriptorState::AfterDescriptor, Some(c)) if c.is_ascii_whitespace() => {}
+        (DescriptorState::AfterDescriptor, None) => return descriptors,
+        (DescriptorState::AfterDescriptor, Some(_)) => {
+          state = DescriptorState::InDescriptor;
+          token_start = self.pos;
+          // reconsume in the descriptor state
+          continue;
+        }
+        (DescriptorState::InDescriptor | DescriptorState::InParens, Some(_)) => {}
+      }
+      self.pos += 1;
+    }
+  }
+
+  /// Parse descriptor tokens. Returns `Err` if the candidate is invalid.
+  fn parse_descriptors(
+    &mut self,
+    descriptors: &[(usize, usize)],
+    url_span: Span,
+  ) -> Result<Option<Descriptor>, ()> {
+    let mut width = None;
+    let mut density = None;
+    let mut height = None;
+    let mut valid = true;
+
+    for &(start, end) in descriptors {
+      let token = &self.input[start..end];
+      let span = self.span(start, end);
+      let (number, unit) = token.split_at(token.len() - 1);
+
+      match unit {
+        "w" | "h" => {
+          let duplicate = if unit == "w" {
+            width.is_some() || density.is_some()
+          } else {
+            height.is_some() || density.is_some()
+          };
+          match parse_positive_integer(number) {
+            Some(value) if !duplicate => {
+              if unit == "w" {
+                width = Some(value);
+              } else {
+                height = Some(value);
+              }
+            }
+            Some(_) => {
+              self.error(format!("Conflicting descriptor `{token}`"), span);
+              valid = false;
+            }
+            None => {
+              self.error(
+                format!("Invalid `{unit}` descriptor `{token}`, expected a positive integer"),
+                span,
+              );
+              valid = false;
+            }
+          }
+        }
+        "x" => {
+          let duplicate = width.is_some() || density.is_some() || height.is_some();
+          match parse_float(number) {
+            Some(value) if value >= 0.0 && !duplicate => density = Some(value),
+            Some(value) if value >= 0.0 => {
+              self.error(format!("Conflicting descriptor `{token}`"), span);
+              valid = false;
+            }
+            _ => {
+              self.error(
+                format!(
+                  "Invalid `x` descriptor `{token}`, expected a non-negative floating-point number"
+                ),
+                span,
+              );
+              valid = false;
+            }
+          }
+        }
+        _ => {
+          self.error(format!("Unknown descriptor `{token}`"), span);
+          valid = false;
+        }
+      }
+    }
+
+    if valid && height.is_some() && width.is_none() {
+      self.error("An `h` descriptor requires a `w` descriptor", url_span);
+      valid = false;
+    }
+
+    if !valid {
+      return Err(());
+    }
+    Ok(
+      width
+        .map(Descriptor::Width)
+        .or_else(|| density.map(Descriptor::Density)),
+    )
+  }
+
+  fn check_conformance(&mut self) {
+    let candidates = &self.srcset.candidates;
+    let has_width = candidates
+      .iter()
+      .any(|c| matches!(c.descriptor, Some(Descriptor::Width(_))));
+
+    let mut errors = Vec::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+      let is_width = matches!(candidate.descriptor, Some(Descriptor::Width(_)));
+      if has_width && !is_width {
+        errors.push(
+          OxcDiagnostic::error(
+            "Width descriptors must be used on every image candidate, or on none",
+          )
+          .with_label(candidate.span),
+        );
+        continue;
+      }
+
+      let duplicate = candidates[..i].iter().any(|previous| {
+        previous.descriptor == candidate.descriptor
+          || (previous.density().is_some() && previous.density() == candidate.density())
+      });
+      if duplicate {
+        errors.push(
+          OxcDiagnostic::error("Several image candidates with the same descriptor")
+            .with_label(candidate.span),
+        );
+      }
+    }
+
+    self.srcset.errors.extend(errors);
+  }
+}
+
+/// Parse a [valid non-negative integer](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-non-negative-integer) greater than zero.
+fn parse_positive_integer(input: &str) -> Option<u32> {
+  if input.is_empty() || !input.bytes().all(|c| c.is_ascii_digit()) {
+    return None;
+  }
+  input.parse().ok().filter(|value| *value > 0)
+}
+
+/// Parse a [valid floating-point number](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#valid-floating-point-number).
+fn parse_float(input: &str) -> Option<f64> {
+  let digits = input.strip_prefix('-').unwrap_or(input);
+  let mantissa = digits.split(['e', 'E']).next().unwrap_or_default();
+
+  let valid = !mantissa.is_empty()
+    && !mantissa.ends_with('.')
+    && mantissa.bytes().any(|c| c.is_ascii_digit())
+    && digits
+      .bytes()
+      .all(|c| c.is_ascii_digit() || matches!(c, b'.' | b'e' | b'E' | b'+' | b'-'));
+  if !valid {
+    return None;
+  }
+  input.parse().ok()
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use super::Srcset;
+
+  fn parse(input: &str) -> String {
+    let srcset = Srcset::parse_str(input, 0);
+    let errors: Vec<_> = srcset
+      .errors
+      .iter()
+      .map(|error| {
+        let label = error.labels.as_ref().and_then(|labels| labels.first());
+        let range = label.map(|label| label.offset()..label.offset() + label.len());
+        format!("{} {range:?}", error.message)
+      })
+      .collect();
+
+    format!("Candidates: {:#?}\nErrors: {errors:#?}", srcset.candidates)
+  }
+
+  #[test]
+  fn densities() {
+    assert_snapshot!(parse(" a.png, b.png 1.5x,c.png 2x "));
+  }
+
+  #[test]
+  fn widths() {
+    assert_snapshot!(parse("data:image/png;base64,AAA= 320w, b.png 640w 480h"));
+  }
+
+  #[test]
+  fn malformed() {
+    assert_snapshot!(parse(
+      ",a.png 0w, b.png 2x 2x, c.png 1y, d.png,, e.png 1x, f.png 100w"
+    ));
+  }
+}
@@ -0,0 +1,570 @@
+//! Tables as browsers lay them out, following the
+//! [table model](https://html.spec.whatwg.org/multipage/tables.html#table-processing-model).
+//!
+//! The tree of a table written as tag soup is not the one browsers build: they imply the
+//! `<tbody>` of rows outside of row groups, close a cell at the next cell, and move the
+//! content found outside of cells before the table. [`TableModel`] applies these fixups to
+//! the tree as parsed, then lays the cells out on a grid, resolving their `colspan` and
+//! `rowspan`.
+
+use std::ops::Range;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Element, Node, Program};
+
+/// Kind of a [`RowGroup`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowGroupKind {
+  /// `<thead>`
+  Head,
+  /// `<tbody>`, or rows outside of any row group
+  Body,
+  /// `<tfoot>`, laid out after the other row groups wherever it is written
+  Foot,
+}
+
+/// A group of consecutive rows of a table.
+#[derive(Debug, Clone)]
+pub struct RowGroup<'e, 'a> {
+  pub kind: RowGroupKind,
+  /// The `<thead>`, `<tbody>` or `<tfoot>` element, `None` for an implied `<tbody>`
+  pub element: Option<&'e Element<'a>>,
+  /// Indexes of the rows of the group in [`TableModel::rows`]
+  pub rows: Range<usize>,
+}
+
+/// A row of a table.
+#[derive(Debug, Clone)]
+pub struct TableRow<'e, 'a> {
+  /// The `<tr>` element, `None` for a row implied by cells outside of any row, or by cells
+  /// spanning past the last row of their group
+  pub element: Option<&'e Element<'a>>,
+}
+
+/// A cell of a table, covering `width` columns and `height` rows from its top left slot.
+#[derive(Debug, Clone)]
+pub struct TableCell<'e, 'a> {
+  /// The `<td>` or `<th>` element
+  pub element: &'e Element<'a>,
+  /// Whether the cell is a header cell, `<th>`
+  pub header: bool,
+  /// Column of the top left slot
+  pub x: usize,
+  /// Row of the top left slot
+  pub y: usize,
+  pub width: usize,
+  pub height: usize,
+}
+
+/// The grid of a `<table>` element.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_analyze::table::tables;
+/// use umc_html_parser::CreateHtml;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<table><tr><td rowspan=2>a<td>b<tr><td>c</table>");
+/// let program = parser.parse().program;
+///
+/// let table = &tables(&program)[0];
+/// assert_eq!((table.width, table.height()), (2, 2));
+/// assert_eq!(table.cell_at(0, 1).map(|cell| cell.y), Some(0));
+/// assert_eq!(table.cell_at(1, 1).map(|cell| cell.y), Some(1));
+/// ```
+#[derive(Debug)]
+pub struct TableModel<'e, 'a> {
+  /// The `<table>` element
+  pub element: &'e Element<'a>,
+  /// The first `<caption>` element
+  pub caption: Option<&'e Element<'a>>,
+  /// Number of columns
+  pub width: usize,
+  /// Row groups, in layout order
+  pub row_groups: Vec<RowGroup<'e, 'a>>,
+  /// Rows, in layout order
+  pub rows: Vec<TableRow<'e, 'a>>,
+  /// Cells, in layout order
+  pub cells: Vec<TableCell<'e, 'a>>,
+  /// Text and elements outside of cells, which browsers move before the table
+  pub fostered: Vec<&'e Node<'a>>,
+  /// Overlapping cells and fostered content
+  pub errors: Vec<OxcDiagnostic>,
+  /// Index of the cell covering each slot, row by row
+  slots: Vec<Option<usize>>,
+}
+
+impl<'e, 'a> TableModel<'e, 'a> {
+  /// Lay out a `<table>` element, or `None` for any other element.
+  pub fn from_element(element: &'e Element<'a>) -> Option<Self> {
+    if !element.name().eq_ignore_ascii_case("table") {
+      return None;
+    }
+    let mut structure = Structure::default();
+    structure.walk(&element.children, false);
+    Some(structure.lay_out(element))
+  }
+
+  /// Number of rows.
+  pub const fn height(&self) -> usize {
+    self.rows.len()
+  }
+
+  /// Get the cell covering a slot, `None` for an empty slot or one out of the table.
+  pub fn cell_at(&self, x: usize, y: usize) -> Option<&TableCell<'e, 'a>> {
+    if x >= self.width {
+      return None;
+    }
+    let index = (*self.slots.get(y * self.width + x)?)?;
+    self.cells.get(index)
+  }
+}
+
+/// Lay out every table of a document, nested tables included, in document order.
+pub fn tables<'e, 'a>(program: &'e Program<'a>) -> Vec<TableModel<'e, 'a>> {
+  let mut tables = Vec::new();
+  collect(program, &mut tables);
+  tables
+}
+
+fn collect<'e, 'a>(nodes: &'e [Node<'a>], tables: &mut Vec<TableModel<'e, 'a>>) {
+  for node in nodes {
+    match node {
+      Node::Element(element) => {
+        tables.extend(TableModel::from_element(element));
+        collect(&element.children, tables);
+      }
+      Node::Comment(comment) => {
+        if let Some(conditional) = &comment.conditional {
+          collect(&conditional.children, tables);
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+struct GroupStructure<'e, 'a> {
+  kind: RowGroupKind,
+  element: Option<&'e Element<'a>>,
+  rows: Vec<RowStructure<'e, 'a>>,
+}
+
+struct RowStructure<'e, 'a> {
+  element: Option<&'e Element<'a>>,
+  cells: Vec<&'e Element<'a>>,
+}
+
+/// Row groups, rows and cells of a table, as browsers would build its tree.
+#[derive(Default)]
+struct Structure<'e, 'a> {
+  caption: Option<&'e Element<'a>>,
+  columns: usize,
+  groups: Vec<GroupStructure<'e, 'a>>,
+  fostered: Vec<&'e Node<'a>>,
+  /// Whether the last row group is open
+  in_group: bool,
+  /// Whether the last row of the last row group is open
+  in_row: bool,
+}
+
+impl<'e, 'a> Structure<'e, 'a> {
+  /// Walk the nodes of a table, `in_content` when they are in a cell, a caption, or a
+  /// fostered element.
+  ///
+  /// Browsers close a cell at the next cell or row, so cells and rows are looked for in the
+  /// content of cells too, like in `<td>a<td>b`, nested tables aside.
+  fn walk(&mut self, nodes: &'e [Node<'a>], in_content: bool) {
+    for node in nodes {
+      match node {
+        Node::Element(element) => self.element(node, element, in_content),
+        Node::Text(text) if !in_content && !text.value.trim_ascii().is_empty() => {
+          self.fostered.push(node);
+        }
+        Node::Comment(comment) => {
+          if let Some(conditional) = &comment.conditional {
+            self.walk(&conditional.children, in_content);
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  fn element(&mut self, node: &'e Node<'a>, element: &'e Element<'a>, in_content: bool) {
+    match element.name() {
+      "caption" => {
+        self.caption.get_or_insert(element);
+        self.close_group();
+        self.walk(&element.children, true);
+      }
+      "colgroup" => {
+        self.close_group();
+        let columns: usize = element
+          .children
+          .iter()
+          .filter_map(|node| match node {
+            Node::Element(col) if col.name() == "col" => Some(span(col, "span")),
+            _ => None,
+          })
+          .sum();
+        self.columns += if columns == 0 {
+          span(element, "span")
+        } else {
+          columns
+        };
+      }
+      "col" => {
+        self.close_group();
+        self.columns += span(element, "span");
+      }
+      "thead" | "tbody" | "tfoot" => {
+        let kind = match element.name() {
+          "thead" => RowGroupKind::Head,
+          "tfoot" => RowGroupKind::Foot,
+          _ => RowGroupKind::Body,
+        };
+        self.groups.push(GroupStructure {
+          kind,
+          element: Some(element),
+          rows: Vec::new(),
+        });
+        self.in_group = true;
+        self.in_row = false;
+        self.walk(&element.children, false);
+        self.close_group();
+      }
+      "tr" => {
+        self.row(Some(element));
+        self.walk(&element.children, false);
+        self.in_row = false;
+      }
+      "td" | "th" => {
+        if !self.in_row {
+          self.row(None);
+        }
+        if let Some(row) = self
+          .groups
+          .last_mut()
+          .and_then(|group| group.rows.last_mut())
+        {
+          row.cells.push(element);
+        }
+        self.walk(&element.children, true);
+      }
+      // a nested table has a model of its own
+      "table" if in_content => {}
+      _ if in_content => self.walk(&element.children, true),
+      "script" | "style" | "template" => {}
+      "input" if is_hidden_input(element) => {}
+      _ => {
+        self.fostered.push(node);
+        self.walk(&element.children, true);
+      }
+    }
+  }
+
+  const fn close_group(&mut self) {
+    self.in_group = false;
+    self.in_row = false;
+  }
+
+  /// Open a row, in an implied `<tbody>` if no row group is open.
+  fn row(&mut self, element: Option<&'e Element<'a>>) {
+    if !self.in_group {
+      self.groups.push(GroupStructure {
+        kind: RowGroupKind::Body,
+        element: None,
+        rows: Vec::new(),
+      });
+      self.in_group = true;
+    }
+    if let Some(group) = self.groups.last_mut() {
+      group.rows.push(RowStructure {
+        element,
+        cells: Vec::new(),
+      });
+    }
+    self.in_row = true;
+  }
+
+  /// Lay the cells out on the grid, with the `<tfoot>` groups last.
+  fn lay_out(self, element: &'e Element<'a>) -> TableModel<'e, 'a> {
+    let (mut groups, feet): (Vec<_>, Vec<_>) = self
+      .groups
+      .into_iter()
+      .partition(|group| group.kind != RowGroupKind::Foot);
+    groups.extend(feet);
+
+    let mut grid = Grid::default();
+    let mut row_groups = Vec::with_capacity(groups.len());
+    for group in groups {
+      let start = grid.rows.len();
+      grid.lay_out_group(group.rows);
+      row_groups.push(RowGroup {
+        kind: group.kind,
+        element: group.element,
+        rows: start..grid.rows.len(),
+      });
+    }
+
+    let width = grid.width.max(self.columns);
+    let mut slots = vec![None; width * grid.rows.len()];
+    for (index, cell) in grid.cells.iter().enumerate() {
+      for y in cell.y..cell.y + cell.height {
+        for x in cell.x..cell.x + cell.width {
+          slots[y * width + x].get_or_insert(index);
+        }
+      }
+    }
+
+    let mut errors = grid.errors;
+    errors.extend(self.fostered.iter().map(|node| {
+      OxcDiagnostic::error("Content of a table outside of its cells, moved before the table")
+        .with_label(node.span())
+    }));
+
+    TableModel {
+      element,
+      caption: self.caption,
+      width,
+      row_groups,
+      rows: grid.rows,
+      cells: grid.cells,
+      fostered: self.fostered,
+      errors,
+      slots,
+    }
+  }
+}
+
+#[derive(Default)]
+struct Grid<'e, 'a> {
+  width: usize,
+  rows: Vec<TableRow<'e, 'a>>,
+  cells: Vec<TableCell<'e, 'a>>,
+  /// Index of the cell covering each slot, row by row, as wide as needed
+  slots: Vec<Vec<Option<usize>>>,
+  errors: Vec<OxcDiagnostic>,
+}
+
+impl<'e, 'a> Grid<'e, 'a> {
+  fn lay_out_group(&mut self, rows: Vec<RowStructure<'e, 'a>>) {
+    // cells with `rowspan=0`, growing down to the end of the group
+    let mut growing = Vec::new();
+    for row in rows {
+      let y = self.rows.len();
+      self.rows.push(TableRow {
+        element: row.element,
+      });
+      self.grow(&growing, y);
+
+      let mut x = 0;
+      for element in row.cells {
+        while self.slot(x, y).is_some() {
+          x += 1;
+        }
+        let width = span(element, "colspan");
+        let rowspan = rowspan(element);
+        if rowspan == 0 {
+          growing.push(self.cells.len());
+        }
+        let cell = TableCell {
+          element,
+          header: element.name() == "th",
+          x,
+          y,
+          width,
+          height: rowspan.max(1),
+        };
+        self.place(cell);
+        x += width;
+      }
+    }
+
+    // rows spanned past the last row of the group
+    while self.rows.len() < self.slots.len() {
+      let y = self.rows.len();
+      self.rows.push(TableRow { element: None });
+      self.grow(&growing, y);
+    }
+  }
+
+  fn slot(&self, x: usize, y: usize) -> Option<usize> {
+    self.slots.get(y)?.get(x).copied().flatten()
+  }
+
+  fn fill(&mut self, x: Range<usize>, y: usize, index: usize) -> bool {
+    if self.slots.len() <= y {
+      self.slots.resize_with(y + 1, Vec::new);
+    }
+    let row = &mut self.slots[y];
+    if row.len() < x.end {
+      row.resize(x.end, None);
+    }
+    let mut overlaps = false;
+    for slot in &mut row[x] {
+      overlaps |= slot.is_some();
+      slot.get_or_insert(index);
+    }
+    overlaps
+  }
+
+  fn place(&mut self, cell: TableCell<'e, 'a>) {
+    let index = self.cells.len();
+    let mut overlaps = false;
+    for y in cell.y..cell.y + cell.height {
+      overlaps |= self.fill(cell.x..cell.x + cell.width, y, index);
+    }
+    if overlaps {
+      self.errors.push(
+        OxcDiagnostic::error("Table cell overlapping another cell").with_label(cell.element.span),
+      );
+    }
+    self.width = self.width.max(cell.x + cell.width);
+    self.cells.push(cell);
+  }
+
+  /// Grow the cells with `rowspan=0` into a new row of their group.
+  fn grow(&mut self, growing: &[usize], y: usize) {
+    for &index in growing {
+      let cell = &mut self.cells[index];
+      if cell.y + cell.height <= y {
+        cell.height = y - cell.y + 1;
+        let columns = cell.x..cell.x + cell.width;
+        self.fill(columns, y, index);
+      }
+    }
+  }
+}
+
+/// Parse a [non-negative integer](https://html.spec.whatwg.org/multipage/common-microsyntaxes.html#rules-for-parsing-non-negative-integers).
+fn non_negative_integer(value: &str) -> Option<usize> {
+  let value = value.trim_start_matches(|c: char| c.is_ascii_whitespace());
+  let value = value.strip_prefix('+').unwrap_or(value);
+  let digits = value.bytes().take_while(u8::is_ascii_digit).count();
+  if digits == 0 {
+    return None;
+  }
+  Some(value[..digits].parse().unwrap_or(usize::MAX))
+}
+
+/// The `span` of a column or the `colspan` of a cell, 1 to 1000.
+fn span(element: &Element, name: &str) -> usize {
+  element
+    .attribute(name)
+    .and_then(non_negative_integer)
+    .map_or(1, |span| span.clamp(1, 1000))
+}
+
+/// The `rowspan` of a cell, 0 to 65534, where 0 spans to the end of the row group.
+fn rowspan(element: &Element) -> usize {
+  element
+    .attribute("rowspan")
+    .and_then(non_negative_integer)
+    .map_or(1, |span| span.min(65534))
+}
+
+fn is_hidden_input(element: &Element) -> bool {
+  element
+    .attribute("type")
+    .is_some_and(|value| value.eq_ignore_ascii_case("hidden"))
+}
+
+#[cfg(test)]
+mod test {
+  use std::fmt::Write;
+
+  use oxc_allocator::Allocator;
+  use umc_html_ast::Node;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use super::{RowGroupKind, TableModel, tables};
+
+  /// Render the grid with the first text of each cell, `.` for an empty slot.
+  fn render(table: &TableModel) -> String {
+    let mut grid = String::new();
+    for y in 0..table.height() {
+      for x in 0..table.width {
+        let text = table
+          .cell_at(x, y)
+          .map_or(".", |cell| match cell.element.children.first() {
+            Some(Node::Text(text)) => text.value,
+            _ => "?",
+          });
+        write!(grid, "{text} ").unwrap();
+      }
+      grid.pop();
+      grid.push('\n');
+    }
+    grid
+  }
+
+  #[test]
+  fn tag_soup() {
+    let allocator = Allocator::default();
+    let html = "<table>a<tr><td rowspan=2>1<td colspan=+2x>2<tr><td>3<b>x</b></table>";
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+    let table = &tables(&program)[0];
+
+    assert_eq!(render(table), "1 2 2\n1 3 .\n");
+    assert_eq!(table.row_groups.len(), 1);
+    assert!(table.row_groups[0].element.is_none());
+    assert_eq!(table.fostered.len(), 1);
+    assert_eq!(table.errors.len(), 1);
+  }
+
+  #[test]
+  fn row_groups() {
+    let allocator = Allocator::default();
+    let html = "<table><caption>c</caption><col span=3><tfoot><tr><td>f</tfoot>\
+      <thead><tr><th>h</thead><tr><td rowspan=0>b<td>c</tr><tr><td>d</tr>\
+      <tbody><tr><td>e<td rowspan=3>g</tbody></table>";
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+    let table = &tables(&program)[0];
+
+    assert_eq!(
+      render(table),
+      "h . .\nb c .\nb d .\ne g .\n. g .\n. g .\nf . .\n"
+    );
+    let groups: Vec<_> = table
+      .row_groups
+      .iter()
+      .map(|group| (group.kind, group.rows.clone()))
+      .collect();
+    assert_eq!(
+      groups,
+      [
+        (RowGroupKind::Head, 0..1),
+        (RowGroupKind::Body, 1..3),
+        (RowGroupKind::Body, 3..6),
+        (RowGroupKind::Foot, 6..7),
+      ]
+    );
+    assert!(table.cells[0].header);
+    assert!(table.caption.is_some());
+    assert!(table.rows[4].element.is_none());
+    assert!(table.errors.is_empty());
+  }
+
+  #[test]
+  fn overlap_and_nested() {
+    let allocator = Allocator::default();
+    let html = "<table><tr><td>a<td rowspan=2>b<tr><td colspan=2>c<td>\
+      <table><tr><td>n</table></table>";
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+    let tables = tables(&program);
+
+    assert_eq!(tables.len(), 2);
+    assert_eq!(render(&tables[0]), "a b .\nc b ?\n");
+    assert_eq!(tables[0].errors.len(), 1);
+    assert_eq!(render(&tables[1]), "n\n");
+  }
+}
@@ -0,0 +1,54 @@
+//! [Custom elements](https://html.spec.whatwg.org/multipage/custom-elements.html), like
+//! `<my-widget>`, and built-in elements customized with `is`.
+
+use crate::Element;
+
+/// Names containing a hyphen which are not custom element names, from SVG and MathML.
+const RESERVED_NAMES: &[&str] = &[
+  "annotation-xml",
+  "color-profile",
+  "font-face",
+  "font-face-src",
+  "font-face-uri",
+  "font-face-format",
+  "font-face-name",
+  "missing-glyph",
+];
+
+/// Whether a name is a valid custom element name, e.g. `my-widget` but not `font-face`.
+///
+/// [Valid names](https://html.spec.whatwg.org/multipage/custom-elements.html#valid-custom-element-name)
+/// start with a lowercase ASCII letter and contain a hyphen, without uppercase ASCII
+/// letters, and are not reserved.
+pub fn is_custom_element_name(name: &str) -> bool {
+  name.starts_with(|c: char| c.is_ascii_lowercase())
+    && name.contains('-')
+    && name.chars().all(|c| {
+      matches!(c, 'a'..='z' | '0'..='9' | '-' | '.' | '_' | '\u{B7}') || u32::from(c) >= 0xC0
+    })
+    && !RESERVED_NAMES.contains(&name)
+}
+
+impl<'a> Element<'a> {
+  /// Whether the element is an autonomous custom element, e.g. `<my-widget>`, see
+  /// [`is_custom_element_name`].
+  ///
+  /// Its content model is up to its definition, unlike the one of an unknown element.
+  pub fn is_custom_element(&self) -> bool {
+    is_custom_element_name(self.name())
+  }
+
+  /// The custom element a built-in element is customized as, the value of its `is`
+  /// attribute, e.g. `fancy-button` for `<button is="fancy-button">`.
+  ///
+  /// `None` without `is` attribute, or if its value is not a custom element name.
+  pub fn is_value(&self) -> Option<&'a str> {
+    self
+      .attributes
+      .iter()
+      .find(|attribute| attribute.key.name() == "is")
+      .and_then(|attribute| attribute.value.as_ref())
+      .map(|value| value.value)
+      .filter(|value| is_custom_element_name(value))
+  }
+}
@@ -10,6 +10,25 @@
 //! - Collections use `oxc_allocator::Vec<'a, T>` for cache-friendly traversal
 //! - Memory is released in bulk when the allocator is dropped (no individual Drop calls)
 //!
+//! # Spans
+//!
+//! Every node implements [`GetSpan`](umc_span::GetSpan), and [`node_at_offset`] finds the
+//! deepest node at an offset of the source text, e.g. under the cursor of an editor.
+//! [`Node::source_text`] and [`Element::inner_source`] slice the source text of a node as
+//! written.
+//!
+//! # Owned AST
+//!
+//! [`ToOwnedTree::to_owned_tree`] copies a tree into the [`owned`] AST, which does not
+//! borrow the allocator, e.g. to return it from the function parsing it or to send it to
+//! another thread.
+//!
+//! # Serialization
+//!
+//! With the `serialize` feature, every node implements `serde::Serialize`. Nodes are tagged
+//! with a `type` field, fields are in camelCase, and scripts are serialized with their
+//! source text as `content`.
+//!
 //! # Example
 //!
 //! ```
@@ -26,15 +45,35 @@
 //!
 //! let element = Element {
 //!     span: Span::new(0, 20),
-//!     tag_name: "div",
+//!     tag_name: "DIV",
+//!     normalized_name: "div",
 //!     attributes: oxc_allocator::Vec::new_in(&allocator),
 //!     children: oxc_allocator::Vec::new_in(&allocator),
 //! };
+//! assert_eq!(element.raw_name(), "DIV");
+//! assert_eq!(element.name(), "div");
 //! ```
 
-use oxc_allocator::{Box, Vec};
+use std::{cell::OnceCell, fmt};
+
+use oxc_allocator::{Allocator, Box, Vec};
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use oxc_parser::{ParseOptions, Parser as JsParser};
+use oxc_span::SourceType;
 use umc_span::Span;
 
+mod custom_element;
+pub mod owned;
+mod retain;
+mod script;
+mod span;
+
+pub use custom_element::is_custom_element_name;
+pub use owned::ToOwnedTree;
+pub use retain::RetainNodes;
+pub use script::{ScriptInfo, ScriptKind};
+pub use span::{child_at_offset, node_at_offset};
+
 /// HTML AST node types.
 ///
 /// Represents the different kinds of nodes that can appear in an HTML document.
@@ -42,6 +81,7 @@ use umc_span::Span;
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory for this AST.
 #[derive(Debug)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize), serde(tag = "type"))]
 pub enum Node<'a> {
   /// HTML DOCTYPE declaration
   Doctype(Box<'a, Doctype<'a>>),
@@ -53,6 +93,14 @@ pub enum Node<'a> {
   Comment(Box<'a, Comment<'a>>),
   /// Script element with parsed JavaScript content
   Script(Box<'a, Script<'a>>),
+  /// Construct the parser could not understand, kept verbatim
+  RawUnknown(Box<'a, RawUnknown<'a>>),
+  /// Handlebars or Mustache tag, like `{{name}}` or a `{{#if}}` block
+  Mustache(Box<'a, Mustache<'a>>),
+  /// Tag of a foreign template language, like `{% if user %}`, kept verbatim
+  TemplateTag(Box<'a, TemplateTag<'a>>),
+  /// Processing instruction before the root element, like `<?xml version="1.0"?>`
+  Directive(Box<'a, Directive<'a>>),
 }
 
 /// An alias for a vector of HTML AST nodes.
@@ -67,12 +115,26 @@ pub type Program<'a> = Vec<'a, Node<'a>>;
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory.
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Doctype<'a> {
   /// Source location of this DOCTYPE declaration
   pub span: Span,
   /// Attributes of the DOCTYPE (rarely used in modern HTML5).
   /// Stored in arena-allocated vector for cache-friendly traversal.
   pub attributes: Vec<'a, Attribute<'a>>,
+  /// Name of the DOCTYPE, `html` in `<!DOCTYPE html>`
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub name: Option<&'a str>,
+  /// Public identifier of the DOCTYPE, without its quotes
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub public_id: Option<&'a str>,
+  /// System identifier of the DOCTYPE, without its quotes
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub system_id: Option<&'a str>,
 }
 
 /// HTML element node.
@@ -82,12 +144,20 @@ pub struct Doctype<'a> {
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory.
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Element<'a> {
   /// Source location of this element
   pub span: Span,
-  /// Tag name (e.g., "div", "span", "html").
+  /// Tag name (e.g., "div", "span", "html"), in the case written in the source.
   /// References the original source text (zero-copy).
   pub tag_name: &'a str,
+  /// Tag name normalized by the parser, lowercased for HTML.
+  /// Same as `tag_name` when the parser keeps the case, e.g. for XML dialects.
+  pub normalized_name: &'a str,
   /// Element attributes (e.g., class, id, href).
   /// Stored in arena-allocated vector for cache-friendly traversal.
   pub attributes: Vec<'a, Attribute<'a>>,
@@ -96,6 +166,30 @@ pub struct Element<'a> {
   pub children: Vec<'a, Node<'a>>,
 }
 
+impl<'a> Element<'a> {
+  /// The tag name as written in the source, e.g. `DIV` for `<DIV>`.
+  pub const fn raw_name(&self) -> &'a str {
+    self.tag_name
+  }
+
+  /// The normalized tag name, e.g. `div` for `<DIV>` in HTML.
+  pub const fn name(&self) -> &'a str {
+    self.normalized_name
+  }
+
+  /// Get the value of an attribute by its normalized name, `Some("")` for an attribute
+  /// without value.
+  ///
+  /// Attribute names are compared ignoring ASCII case.
+  pub fn attribute(&self, name: &str) -> Option<&'a str> {
+    self
+      .attributes
+      .iter()
+      .find(|attribute| attribute.key.name().eq_ignore_ascii_case(name))
+      .map(|attribute| attribute.value.as_ref().map_or("", |value| value.value))
+  }
+}
+
 /// Text content node.
 ///
 /// Represents plain text content within HTML elements.
@@ -103,6 +197,11 @@ pub struct Element<'a> {
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory.
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Text<'a> {
   /// Source location of this text node
   pub span: Span,
@@ -116,6 +215,11 @@ pub struct Text<'a> {
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory.
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Comment<'a> {
   /// Source location of this comment
   pub span: Span,
@@ -124,6 +228,34 @@ pub struct Comment<'a> {
   /// The comment text content (without the `<!--` and `-->` delimiters).
   /// References the original source text (zero-copy).
   pub value: &'a str,
+  /// The comment as written, with its delimiters, e.g. `<!-- a -->`.
+  /// References the original source text (zero-copy).
+  pub raw: &'a str,
+  /// The parsed condition and content of an IE conditional comment,
+  /// e.g. `<!--[if lt IE 9]><script src="html5shiv.js"></script><![endif]-->`.
+  /// Only set when the parser is asked to parse conditional comments.
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub conditional: Option<Box<'a, ConditionalComment<'a>>>,
+}
+
+/// IE conditional comment, see [`Comment::conditional`].
+///
+/// Browsers other than old Internet Explorer treat the whole construct as a comment, so its
+/// content is not part of the document tree.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ConditionalComment<'a> {
+  /// Source location of the content, between `]>` and `<![endif]`
+  pub span: Span,
+  /// The condition, e.g. `lt IE 9` for `<!--[if lt IE 9]>`.
+  /// References the original source text (zero-copy).
+  pub condition: &'a str,
+  /// The content, parsed as HTML.
+  pub children: Vec<'a, Node<'a>>,
 }
 
 /// Script element with parsed JavaScript content.
@@ -133,6 +265,11 @@ pub struct Comment<'a> {
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory.
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Script<'a> {
   /// Source location of this script element
   pub span: Span,
@@ -140,8 +277,326 @@ pub struct Script<'a> {
   pub tag_name: &'a str,
   /// Element attributes (e.g., type, src, defer)
   pub attributes: Vec<'a, Attribute<'a>>,
-  /// The parsed JavaScript program from oxc_parser
+  /// The kind of the script and how it is loaded, from its attributes
+  pub info: ScriptInfo<'a>,
+  /// The source type the program is parsed with: its goal, from
+  /// [`ScriptKind::source_type`], and whether it is TypeScript.
+  #[cfg_attr(feature = "serialize", serde(skip))]
+  pub source_type: SourceType,
+  /// The parsed JavaScript program from oxc_parser.
+  /// Serialized as its source text, under `content`.
+  ///
+  /// Scripts parsed lazily have an empty program with the source text of the script here,
+  /// use [`program`](Self::program) to get the program in either case.
+  #[cfg_attr(
+    feature = "serialize",
+    serde(rename = "content", serialize_with = "serialize_program")
+  )]
   pub program: oxc_ast::ast::Program<'a>,
+  /// The program parsed on first use, for scripts parsed lazily.
+  #[cfg_attr(feature = "serialize", serde(skip))]
+  pub lazy: Option<Box<'a, LazyProgram<'a>>>,
+  /// Whether the program is the content of the `src` of the script, loaded by the parser.
+  ///
+  /// Its spans are then relative to that content, not to the document.
+  pub synthetic: bool,
+  /// The imports and exports of a `<script type="module">`, `None` for other scripts and
+  /// for scripts parsed lazily.
+  pub module: Option<Box<'a, ModuleRecord<'a>>>,
+}
+
+impl<'a> Script<'a> {
+  /// The parsed JavaScript program, parsed now if the script is parsed lazily.
+  pub fn program(&self) -> &oxc_ast::ast::Program<'a> {
+    self.lazy.as_ref().map_or(&self.program, |lazy| lazy.get())
+  }
+}
+
+/// A JavaScript program parsed on first use, see [`Script::lazy`].
+///
+/// The program is allocated in the allocator of the document when it is parsed, so
+/// pipelines only looking at the markup never pay for parsing scripts.
+pub struct LazyProgram<'a> {
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  source_type: SourceType,
+  options: ParseOptions,
+  /// Offset of the source text in the document
+  offset: u32,
+  program: OnceCell<oxc_ast::ast::Program<'a>>,
+}
+
+impl<'a> LazyProgram<'a> {
+  /// Create a program to parse from `source_text` as `source_type`, found at `offset` in
+  /// the document.
+  pub const fn new(
+    allocator: &'a Allocator,
+    source_text: &'a str,
+    source_type: SourceType,
+    options: ParseOptions,
+    offset: u32,
+  ) -> Self {
+    Self {
+      allocator,
+      source_text,
+      source_type,
+      options,
+      offset,
+      program: OnceCell::new(),
+    }
+  }
+
+  /// Get the program, parsing it on the first call.
+  pub fn get(&self) -> &oxc_ast::ast::Program<'a> {
+    self.program.get_or_init(|| {
+      JsParser::new(self.allocator, self.source_text, self.source_type)
+        .with_options(self.options)
+        .parse()
+        .program
+    })
+  }
+
+  /// Whether the program was parsed already.
+  pub fn is_parsed(&self) -> bool {
+    self.program.get().is_some()
+  }
+
+  /// The source text of the program.
+  pub const fn source_text(&self) -> &'a str {
+    self.source_text
+  }
+
+  /// Get the errors of the program, with spans in the document.
+  ///
+  /// Diagnostics cannot be kept in the arena, so the script is parsed again in a temporary
+  /// allocator: this is meant for the few scripts still validated by a pipeline parsing
+  /// them lazily.
+  pub fn errors(&self) -> std::vec::Vec<OxcDiagnostic> {
+    let allocator = Allocator::default();
+    let ret = JsParser::new(&allocator, self.source_text, self.source_type)
+      .with_options(self.options)
+      .parse();
+    let offset = self.offset as usize;
+    ret
+      .errors
+      .into_iter()
+      .map(|mut error| {
+        if let Some(labels) = error.labels.take() {
+          let labels = labels
+            .into_iter()
+            .map(|label| {
+              let msg = label.label().map(ToString::to_string);
+              LabeledSpan::new_with_span(msg, (label.offset() + offset, label.len()))
+            })
+            .collect();
+          error.labels = Some(labels);
+        }
+        error
+      })
+      .collect()
+  }
+}
+
+impl fmt::Debug for LazyProgram<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("LazyProgram")
+      .field("source_text", &self.source_text)
+      .field("offset", &self.offset)
+      .field("program", &self.program.get())
+      .finish_non_exhaustive()
+  }
+}
+
+/// The static imports and exports of a module script, see [`Script::module`].
+///
+/// Spans are in the document, unless the script is [synthetic](Script::synthetic).
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ModuleRecord<'a> {
+  /// Modules imported, or re-exported with `export ... from`, in source order
+  pub imports: Vec<'a, ModuleImport<'a>>,
+  /// Names exported, in source order
+  pub exports: Vec<'a, ModuleExport<'a>>,
+}
+
+/// A module imported by a module script, e.g. `./app.js` in `import "./app.js"`.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ModuleImport<'a> {
+  /// Source location of the specifier, quotes included
+  pub span: Span,
+  /// The specifier as written
+  pub specifier: &'a str,
+  /// The specifier mapped by the `<script type="importmap">` of the document, if any maps it
+  pub resolved: Option<&'a str>,
+}
+
+/// A name exported by a module script, e.g. `a` in `export const a = 1`.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ModuleExport<'a> {
+  /// Source location of the exported name, or of the export for `export default`
+  pub span: Span,
+  /// The exported name, `default` for the default export
+  pub name: &'a str,
+}
+
+/// Construct the parser could not understand.
+///
+/// Only produced when the parser is configured to preserve unknown constructs,
+/// e.g. bogus comments like `<?php echo 1 ?>` or closing tags without a matching
+/// opening tag. The source text is kept verbatim, so printing the tree never loses it.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct RawUnknown<'a> {
+  /// Source location of this construct
+  pub span: Span,
+  /// The exact source text of this construct.
+  /// References the original source text (zero-copy).
+  pub raw: &'a str,
+}
+
+/// Handlebars or Mustache tag, e.g. `{{user.name}}`, `{{> header}}`, or a block like
+/// `{{#each items}}<li>{{this}}</li>{{/each}}` with its content as children.
+///
+/// Only produced when the parser is configured to parse Handlebars. Tags are recognized in
+/// the content of elements, not in their attributes.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Mustache<'a> {
+  /// Source location of this tag, up to the closing tag of a block
+  pub span: Span,
+  /// The kind of tag, from its sigil, e.g. a block for `{{#`
+  pub kind: MustacheKind,
+  /// The content of the tag after its sigil, trimmed, e.g. `each items` for
+  /// `{{#each items}}`. References the original source text (zero-copy).
+  pub expression: &'a str,
+  /// Content of a block, up to its `{{else}}` or closing tag.
+  /// Stored in arena-allocated vector for cache-friendly traversal.
+  pub children: Vec<'a, Node<'a>>,
+  /// Content of a block after its `{{else}}`, empty without `{{else}}`.
+  pub inverse: Vec<'a, Node<'a>>,
+}
+
+/// Kind of a [`Mustache`] tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub enum MustacheKind {
+  /// Escaped expression: `{{expr}}`
+  Expression,
+  /// Unescaped expression: `{{{expr}}}` or `{{& expr}}`
+  Unescaped,
+  /// Block: `{{#name}}...{{/name}}`
+  Block,
+  /// Inverted block, rendered when its value is falsy: `{{^name}}...{{/name}}`
+  InvertedBlock,
+  /// Partial: `{{> name}}`
+  Partial,
+  /// Comment: `{{! ... }}` or `{{!-- ... --}}`
+  Comment,
+  /// `{{else}}` outside of a block
+  Else,
+  /// Closing tag without a matching block: `{{/name}}`
+  CloseBlock,
+}
+
+impl<'a> Mustache<'a> {
+  /// The name of a block or partial, the first word of its expression, e.g. `each` for
+  /// `{{#each items}}`.
+  pub fn name(&self) -> &'a str {
+    self
+      .expression
+      .split_ascii_whitespace()
+      .next()
+      .unwrap_or_default()
+  }
+}
+
+/// Tag of a foreign template language passed through by the parser, like `{% if user %}`
+/// in Liquid, Jinja or Twig, or `<%= name %>` in ERB.
+///
+/// Only produced when the parser is configured with the delimiters of the language. The
+/// tag is not interpreted, so the elements between `{% if %}` and `{% endif %}` stay its
+/// siblings.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct TemplateTag<'a> {
+  /// Source location of this tag, delimiters included
+  pub span: Span,
+  /// The opening delimiter, e.g. `{%`
+  pub open: &'a str,
+  /// The closing delimiter, e.g. `%}`, empty if the tag is unterminated
+  pub close: &'a str,
+  /// The content between the delimiters, as written, e.g. ` if user ` for
+  /// `{% if user %}`. References the original source text (zero-copy).
+  pub value: &'a str,
+}
+
+/// Processing instruction in the prelude of a document, before its root element, like the
+/// XML declaration `<?xml version="1.0"?>` or `<?xml-stylesheet href="feed.xsl"?>`.
+///
+/// HTML has no processing instructions: later in the document, `<?...>` is a bogus
+/// [`Comment`], as in browsers.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Directive<'a> {
+  /// Source location of this directive, delimiters included
+  pub span: Span,
+  /// The target, e.g. `xml` for `<?xml version="1.0"?>`
+  pub name: &'a str,
+  /// The content after the target, without the closing `?>`, e.g. ` version="1.0"`.
+  /// References the original source text (zero-copy).
+  pub value: &'a str,
+  /// The directive as written, e.g. `<?xml version="1.0"?>`.
+  /// References the original source text (zero-copy).
+  pub raw: &'a str,
+}
+
+impl Directive<'_> {
+  /// Whether the directive is the XML declaration, `<?xml ...?>`.
+  pub const fn is_xml_declaration(&self) -> bool {
+    self.name.eq_ignore_ascii_case("xml")
+  }
 }
 
 /// HTML element attribute.
@@ -154,6 +609,11 @@ pub struct Script<'a> {
 ///
 /// The lifetime `'a` is tied to the allocator that owns the memory.
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct Attribute<'a> {
   /// Source location of this attribute
   pub span: Span,
@@ -162,17 +622,82 @@ pub struct Attribute<'a> {
   pub key: AttributeKey<'a>,
   /// Attribute value. References the original source text.
   pub value: Option<AttributeValue<'a>>,
+  /// The CSS declarations of a `style` attribute, only parsed when the parser is asked to.
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub style: Option<Vec<'a, StyleDeclaration<'a>>>,
+}
+
+/// CSS declaration of a `style` attribute, e.g. `color: red` in `style="color: red"`.
+///
+/// The lifetime `'a` is tied to the allocator that owns the memory.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct StyleDeclaration<'a> {
+  /// Source location of the declaration, from its property to its value or `!important`
+  pub span: Span,
+  /// Property name as written, e.g. `color`. References the original source text.
+  pub property: &'a str,
+  /// Source location of the property name, without the whitespace around it
+  pub property_span: Span,
+  /// Value as written, without `!important`, e.g. `red`. References the original source
+  /// text.
+  pub value: &'a str,
+  /// Source location of the value, without the whitespace around it and `!important`
+  pub value_span: Span,
+  /// Whether the declaration ends with `!important`
+  pub important: bool,
 }
 
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct AttributeKey<'a> {
   pub span: Span,
+  /// Attribute name in the case written in the source.
   pub value: &'a str,
+  /// Attribute name normalized by the parser, lowercased for HTML.
+  /// Same as `value` when the parser keeps the case, e.g. `viewBox` in SVG.
+  pub normalized: &'a str,
+}
+
+impl<'a> AttributeKey<'a> {
+  /// The attribute name as written in the source, e.g. `ID` for `<div ID=a>`.
+  pub const fn raw_name(&self) -> &'a str {
+    self.value
+  }
+
+  /// The normalized attribute name, e.g. `id` for `<div ID=a>` in HTML.
+  pub const fn name(&self) -> &'a str {
+    self.normalized
+  }
 }
 
 #[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
 pub struct AttributeValue<'a> {
+  /// Source location of the value, with its quotes
   pub span: Span,
+  /// Source location of `value`, inside the quotes
+  pub value_span: Span,
   pub value: &'a str,
   pub raw: &'a str,
 }
+
+#[cfg(feature = "serialize")]
+fn serialize_program<S: serde::Serializer>(
+  program: &oxc_ast::ast::Program<'_>,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(program.source_text)
+}
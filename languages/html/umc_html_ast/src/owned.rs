@@ -0,0 +1,598 @@
+//! An owned copy of the AST, without the lifetime of the allocator.
+//!
+//! The AST borrows its allocator and source text, so it cannot outlive them, be returned
+//! from the function owning them, or be sent to another thread. The owned AST copies the
+//! tree into `String`s and `Vec`s for these cases, see [`ToOwnedTree`].
+//!
+//! The programs of scripts are allocated with the tree and are not copied: an owned
+//! [`Script`] keeps their source text.
+
+use oxc_span::SourceType;
+use umc_span::Span;
+
+/// Owned [`Node`](crate::Node).
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize), serde(tag = "type"))]
+pub enum Node {
+  Doctype(Box<Doctype>),
+  Element(Box<Element>),
+  Text(Box<Text>),
+  Comment(Box<Comment>),
+  Script(Box<Script>),
+  RawUnknown(Box<RawUnknown>),
+  Mustache(Box<Mustache>),
+  TemplateTag(Box<TemplateTag>),
+  Directive(Box<Directive>),
+}
+
+/// Owned [`Program`](crate::Program).
+pub type Program = Vec<Node>;
+
+/// Owned [`Doctype`](crate::Doctype).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Doctype {
+  pub span: Span,
+  pub attributes: Vec<Attribute>,
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub name: Option<String>,
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub public_id: Option<String>,
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub system_id: Option<String>,
+}
+
+/// Owned [`Element`](crate::Element).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Element {
+  pub span: Span,
+  pub tag_name: String,
+  pub normalized_name: String,
+  pub attributes: Vec<Attribute>,
+  pub children: Vec<Node>,
+}
+
+impl Element {
+  /// The tag name as written in the source, e.g. `DIV` for `<DIV>`.
+  pub fn raw_name(&self) -> &str {
+    &self.tag_name
+  }
+
+  /// The normalized tag name, e.g. `div` for `<DIV>` in HTML.
+  pub fn name(&self) -> &str {
+    &self.normalized_name
+  }
+}
+
+/// Owned [`Text`](crate::Text).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Text {
+  pub span: Span,
+  pub value: String,
+}
+
+/// Owned [`Comment`](crate::Comment).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Comment {
+  pub span: Span,
+  pub bogus: bool,
+  pub value: String,
+  pub raw: String,
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub conditional: Option<Box<ConditionalComment>>,
+}
+
+/// Owned [`ConditionalComment`](crate::ConditionalComment).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ConditionalComment {
+  pub span: Span,
+  pub condition: String,
+  pub children: Vec<Node>,
+}
+
+/// Owned [`Script`](crate::Script).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Script {
+  pub span: Span,
+  pub tag_name: String,
+  pub attributes: Vec<Attribute>,
+  pub info: ScriptInfo,
+  #[cfg_attr(feature = "serialize", serde(skip))]
+  pub source_type: SourceType,
+  /// The source text of the program, parse it again to get its AST.
+  pub content: String,
+  pub synthetic: bool,
+  pub module: Option<Box<ModuleRecord>>,
+}
+
+/// Owned [`ScriptKind`](crate::ScriptKind).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub enum ScriptKind {
+  Classic,
+  Module,
+  ImportMap,
+  SpeculationRules,
+  DataBlock(String),
+}
+
+/// Owned [`ScriptInfo`](crate::ScriptInfo).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ScriptInfo {
+  pub kind: ScriptKind,
+  pub no_module: bool,
+  pub is_async: bool,
+  pub defer: bool,
+  pub external: bool,
+}
+
+/// Owned [`ModuleRecord`](crate::ModuleRecord).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ModuleRecord {
+  pub imports: Vec<ModuleImport>,
+  pub exports: Vec<ModuleExport>,
+}
+
+/// Owned [`ModuleImport`](crate::ModuleImport).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ModuleImport {
+  pub span: Span,
+  pub specifier: String,
+  pub resolved: Option<String>,
+}
+
+/// Owned [`ModuleExport`](crate::ModuleExport).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct ModuleExport {
+  pub span: Span,
+  pub name: String,
+}
+
+/// Owned [`RawUnknown`](crate::RawUnknown).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct RawUnknown {
+  pub span: Span,
+  pub raw: String,
+}
+
+/// Owned [`Mustache`](crate::Mustache).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Mustache {
+  pub span: Span,
+  pub kind: crate::MustacheKind,
+  pub expression: String,
+  pub children: Vec<Node>,
+  pub inverse: Vec<Node>,
+}
+
+/// Owned [`TemplateTag`](crate::TemplateTag).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct TemplateTag {
+  pub span: Span,
+  pub open: String,
+  pub close: String,
+  pub value: String,
+}
+
+/// Owned [`Directive`](crate::Directive).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Directive {
+  pub span: Span,
+  pub name: String,
+  pub value: String,
+  pub raw: String,
+}
+
+/// Owned [`Attribute`](crate::Attribute).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct Attribute {
+  pub span: Span,
+  pub key: AttributeKey,
+  pub value: Option<AttributeValue>,
+  #[cfg_attr(feature = "serialize", serde(skip_serializing_if = "Option::is_none"))]
+  pub style: Option<Vec<StyleDeclaration>>,
+}
+
+/// Owned [`StyleDeclaration`](crate::StyleDeclaration).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct StyleDeclaration {
+  pub span: Span,
+  pub property: String,
+  pub property_span: Span,
+  pub value: String,
+  pub value_span: Span,
+  pub important: bool,
+}
+
+/// Owned [`AttributeKey`](crate::AttributeKey).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct AttributeKey {
+  pub span: Span,
+  pub value: String,
+  pub normalized: String,
+}
+
+impl AttributeKey {
+  /// The attribute name as written in the source, e.g. `ID` for `<div ID=a>`.
+  pub fn raw_name(&self) -> &str {
+    &self.value
+  }
+
+  /// The normalized attribute name, e.g. `id` for `<div ID=a>` in HTML.
+  pub fn name(&self) -> &str {
+    &self.normalized
+  }
+}
+
+/// Owned [`AttributeValue`](crate::AttributeValue).
+#[derive(Debug, Clone)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub struct AttributeValue {
+  pub span: Span,
+  pub value_span: Span,
+  pub value: String,
+  pub raw: String,
+}
+
+/// Copy of a tree into the owned AST.
+///
+/// Implemented for [`Program`](crate::Program) and the children of nodes.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::{Allocator, Box, Vec};
+/// use umc_html_ast::{Node, Text, ToOwnedTree, owned};
+/// use umc_span::SPAN;
+///
+/// fn parse() -> owned::Program {
+///   let allocator = Allocator::default();
+///   let source_text = String::from("Hi");
+///   let text = Text { span: SPAN, value: &source_text };
+///   let program = Vec::from_iter_in([Node::Text(Box::new_in(text, &allocator))], &allocator);
+///   program.to_owned_tree()
+/// }
+///
+/// let program = std::thread::spawn(parse).join().unwrap();
+/// assert!(matches!(&program[0], owned::Node::Text(text) if text.value == "Hi"));
+/// ```
+pub trait ToOwnedTree {
+  fn to_owned_tree(&self) -> Program;
+}
+
+impl ToOwnedTree for [crate::Node<'_>] {
+  fn to_owned_tree(&self) -> Program {
+    nodes(self)
+  }
+}
+
+fn nodes(nodes: &[crate::Node]) -> Vec<Node> {
+  nodes.iter().map(Node::from).collect()
+}
+
+fn attributes(attributes: &[crate::Attribute]) -> Vec<Attribute> {
+  attributes.iter().map(Attribute::from).collect()
+}
+
+impl From<&crate::Node<'_>> for Node {
+  fn from(node: &crate::Node) -> Self {
+    match node {
+      crate::Node::Doctype(doctype) => Self::Doctype(Box::new(Doctype::from(&**doctype))),
+      crate::Node::Element(element) => Self::Element(Box::new(Element::from(&**element))),
+      crate::Node::Text(text) => Self::Text(Box::new(Text::from(&**text))),
+      crate::Node::Comment(comment) => Self::Comment(Box::new(Comment::from(&**comment))),
+      crate::Node::Script(script) => Self::Script(Box::new(Script::from(&**script))),
+      crate::Node::RawUnknown(raw) => Self::RawUnknown(Box::new(RawUnknown::from(&**raw))),
+      crate::Node::Mustache(mustache) => Self::Mustache(Box::new(Mustache::from(&**mustache))),
+      crate::Node::TemplateTag(tag) => Self::TemplateTag(Box::new(TemplateTag::from(&**tag))),
+      crate::Node::Directive(directive) => Self::Directive(Box::new(Directive::from(&**directive))),
+    }
+  }
+}
+
+impl From<&crate::Doctype<'_>> for Doctype {
+  fn from(doctype: &crate::Doctype) -> Self {
+    Self {
+      span: doctype.span,
+      attributes: attributes(&doctype.attributes),
+      name: doctype.name.map(str::to_string),
+      public_id: doctype.public_id.map(str::to_string),
+      system_id: doctype.system_id.map(str::to_string),
+    }
+  }
+}
+
+impl From<&crate::Element<'_>> for Element {
+  fn from(element: &crate::Element) -> Self {
+    Self {
+      span: element.span,
+      tag_name: element.tag_name.to_string(),
+      normalized_name: element.normalized_name.to_string(),
+      attributes: attributes(&element.attributes),
+      children: nodes(&element.children),
+    }
+  }
+}
+
+impl From<&crate::Text<'_>> for Text {
+  fn from(text: &crate::Text) -> Self {
+    Self {
+      span: text.span,
+      value: text.value.to_string(),
+    }
+  }
+}
+
+impl From<&crate::Comment<'_>> for Comment {
+  fn from(comment: &crate::Comment) -> Self {
+    Self {
+      span: comment.span,
+      bogus: comment.bogus,
+      value: comment.value.to_string(),
+      raw: comment.raw.to_string(),
+      conditional: comment
+        .conditional
+        .as_ref()
+        .map(|conditional| Box::new(ConditionalComment::from(&**conditional))),
+    }
+  }
+}
+
+impl From<&crate::ConditionalComment<'_>> for ConditionalComment {
+  fn from(conditional: &crate::ConditionalComment) -> Self {
+    Self {
+      span: conditional.span,
+      condition: conditional.condition.to_string(),
+      children: nodes(&conditional.children),
+    }
+  }
+}
+
+impl From<&crate::Script<'_>> for Script {
+  fn from(script: &crate::Script) -> Self {
+    Self {
+      span: script.span,
+      tag_name: script.tag_name.to_string(),
+      attributes: attributes(&script.attributes),
+      info: ScriptInfo::from(&script.info),
+      source_type: script.source_type,
+      // lazy scripts keep their source text in the empty program too
+      content: script.program.source_text.to_string(),
+      synthetic: script.synthetic,
+      module: script
+        .module
+        .as_ref()
+        .map(|module| Box::new(ModuleRecord::from(&**module))),
+    }
+  }
+}
+
+impl From<&crate::ScriptInfo<'_>> for ScriptInfo {
+  fn from(info: &crate::ScriptInfo) -> Self {
+    Self {
+      kind: match info.kind {
+        crate::ScriptKind::Classic => ScriptKind::Classic,
+        crate::ScriptKind::Module => ScriptKind::Module,
+        crate::ScriptKind::ImportMap => ScriptKind::ImportMap,
+        crate::ScriptKind::SpeculationRules => ScriptKind::SpeculationRules,
+        crate::ScriptKind::DataBlock(mime) => ScriptKind::DataBlock(mime.to_string()),
+      },
+      no_module: info.no_module,
+      is_async: info.is_async,
+      defer: info.defer,
+      external: info.external,
+    }
+  }
+}
+
+impl From<&crate::ModuleRecord<'_>> for ModuleRecord {
+  fn from(module: &crate::ModuleRecord) -> Self {
+    Self {
+      imports: module.imports.iter().map(ModuleImport::from).collect(),
+      exports: module.exports.iter().map(ModuleExport::from).collect(),
+    }
+  }
+}
+
+impl From<&crate::ModuleImport<'_>> for ModuleImport {
+  fn from(import: &crate::ModuleImport) -> Self {
+    Self {
+      span: import.span,
+      specifier: import.specifier.to_string(),
+      resolved: import.resolved.map(ToString::to_string),
+    }
+  }
+}
+
+impl From<&crate::ModuleExport<'_>> for ModuleExport {
+  fn from(export: &crate::ModuleExport) -> Self {
+    Self {
+      span: export.span,
+      name: export.name.to_string(),
+    }
+  }
+}
+
+impl From<&crate::RawUnknown<'_>> for RawUnknown {
+  fn from(raw: &crate::RawUnknown) -> Self {
+    Self {
+      span: raw.span,
+      raw: raw.raw.to_string(),
+    }
+  }
+}
+
+impl From<&crate::Mustache<'_>> for Mustache {
+  fn from(mustache: &crate::Mustache) -> Self {
+    Self {
+      span: mustache.span,
+      kind: mustache.kind,
+      expression: mustache.expression.to_string(),
+      children: nodes(&mustache.children),
+      inverse: nodes(&mustache.inverse),
+    }
+  }
+}
+
+impl From<&crate::TemplateTag<'_>> for TemplateTag {
+  fn from(tag: &crate::TemplateTag) -> Self {
+    Self {
+      span: tag.span,
+      open: tag.open.to_string(),
+      close: tag.close.to_string(),
+      value: tag.value.to_string(),
+    }
+  }
+}
+
+impl From<&crate::Directive<'_>> for Directive {
+  fn from(directive: &crate::Directive) -> Self {
+    Self {
+      span: directive.span,
+      name: directive.name.to_string(),
+      value: directive.value.to_string(),
+      raw: directive.raw.to_string(),
+    }
+  }
+}
+
+impl From<&crate::Attribute<'_>> for Attribute {
+  fn from(attribute: &crate::Attribute) -> Self {
+    Self {
+      span: attribute.span,
+      key: AttributeKey::from(&attribute.key),
+      value: attribute.value.as_ref().map(AttributeValue::from),
+      style: attribute
+        .style
+        .as_ref()
+        .map(|style| style.iter().map(StyleDeclaration::from).collect()),
+    }
+  }
+}
+
+impl From<&crate::StyleDeclaration<'_>> for StyleDeclaration {
+  fn from(declaration: &crate::StyleDeclaration) -> Self {
+    Self {
+      span: declaration.span,
+      property: declaration.property.to_string(),
+      property_span: declaration.property_span,
+      value: declaration.value.to_string(),
+      value_span: declaration.value_span,
+      important: declaration.important,
+    }
+  }
+}
+
+impl From<&crate::AttributeKey<'_>> for AttributeKey {
+  fn from(key: &crate::AttributeKey) -> Self {
+    Self {
+      span: key.span,
+      value: key.value.to_string(),
+      normalized: key.normalized.to_string(),
+    }
+  }
+}
+
+impl From<&crate::AttributeValue<'_>> for AttributeValue {
+  fn from(value: &crate::AttributeValue) -> Self {
+    Self {
+      span: value.span,
+      value_span: value.value_span,
+      value: value.value.to_string(),
+      raw: value.raw.to_string(),
+    }
+  }
+}
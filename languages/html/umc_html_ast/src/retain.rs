@@ -0,0 +1,88 @@
+//! Bulk removal of nodes, see [`RetainNodes`].
+
+use oxc_allocator::Vec;
+
+use crate::{Element, Node};
+
+/// Removal of the nodes of a tree failing a predicate, e.g. the comments of a document.
+///
+/// Implemented for [`Program`](crate::Program) and the children of nodes.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::{Allocator, Box, Vec};
+/// use umc_html_ast::{Comment, Element, Node, RetainNodes, Text};
+/// use umc_span::SPAN;
+///
+/// let allocator = Allocator::default();
+/// // <p>a<!---->b</p><!---->
+/// let text = |value| Node::Text(Box::new_in(Text { span: SPAN, value }, &allocator));
+/// let comment = || {
+///   let comment = Comment {
+///     span: SPAN,
+///     bogus: false,
+///     value: "",
+///     raw: "<!---->",
+///     conditional: None,
+///   };
+///   Node::Comment(Box::new_in(comment, &allocator))
+/// };
+/// let p = Element {
+///   span: SPAN,
+///   tag_name: "p",
+///   normalized_name: "p",
+///   attributes: Vec::new_in(&allocator),
+///   children: Vec::from_iter_in([text("a"), comment(), text("b")], &allocator),
+/// };
+/// let mut program =
+///   Vec::from_iter_in([Node::Element(Box::new_in(p, &allocator)), comment()], &allocator);
+///
+/// program.retain_recursive(|node| !matches!(node, Node::Comment(_)));
+///
+/// assert_eq!(program.len(), 1);
+/// let Node::Element(p) = &program[0] else { unreachable!() };
+/// assert_eq!(p.children.len(), 2);
+/// ```
+pub trait RetainNodes<'a> {
+  /// Remove the nodes failing `f`, with their descendants, and then the descendants failing
+  /// it in the nodes kept, looking into elements, conditional comments and Handlebars
+  /// blocks.
+  fn retain_recursive<F: FnMut(&Node<'a>) -> bool>(&mut self, f: F);
+}
+
+impl<'a> RetainNodes<'a> for Vec<'a, Node<'a>> {
+  fn retain_recursive<F: FnMut(&Node<'a>) -> bool>(&mut self, mut f: F) {
+    retain_in(self, &mut f);
+  }
+}
+
+fn retain_in<'a>(nodes: &mut Vec<'a, Node<'a>>, f: &mut impl FnMut(&Node<'a>) -> bool) {
+  nodes.retain_mut(|node| {
+    if !f(node) {
+      return false;
+    }
+    match node {
+      Node::Element(element) => retain_in(&mut element.children, f),
+      Node::Mustache(mustache) => {
+        retain_in(&mut mustache.children, f);
+        retain_in(&mut mustache.inverse, f);
+      }
+      Node::Comment(comment) => {
+        if let Some(conditional) = &mut comment.conditional {
+          retain_in(&mut conditional.children, f);
+        }
+      }
+      _ => {}
+    }
+    true
+  });
+}
+
+impl<'a> Element<'a> {
+  /// Remove the children failing `f`, without looking into the children kept, see
+  /// [`RetainNodes::retain_recursive`] to look into them.
+  pub fn retain_children<F: FnMut(&Node<'a>) -> bool>(&mut self, f: F) {
+    self.children.retain(f);
+  }
+}
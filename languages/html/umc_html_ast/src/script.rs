@@ -0,0 +1,157 @@
+//! What a `<script>` is, from its attributes, see [`ScriptInfo`].
+
+use oxc_span::SourceType;
+
+use crate::{Attribute, Element};
+
+/// MIME types of JavaScript, the
+/// [JavaScript MIME type essence matches](https://mimesniff.spec.whatwg.org/#javascript-mime-type).
+const JAVASCRIPT_MIME_TYPES: &[&str] = &[
+  "application/ecmascript",
+  "application/javascript",
+  "application/x-ecmascript",
+  "application/x-javascript",
+  "text/ecmascript",
+  "text/javascript",
+  "text/javascript1.0",
+  "text/javascript1.1",
+  "text/javascript1.2",
+  "text/javascript1.3",
+  "text/javascript1.4",
+  "text/javascript1.5",
+  "text/jscript",
+  "text/livescript",
+  "text/x-ecmascript",
+  "text/x-javascript",
+];
+
+/// The type of a script, from its `type` attribute, as browsers
+/// [prepare it](https://html.spec.whatwg.org/multipage/scripting.html#prepare-the-script-element).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+pub enum ScriptKind<'a> {
+  /// A classic script, without `type`, with an empty one or a JavaScript MIME type
+  Classic,
+  /// `type="module"`
+  Module,
+  /// `type="importmap"`
+  ImportMap,
+  /// `type="speculationrules"`
+  SpeculationRules,
+  /// Any other type, like `text/template` or `application/ld+json`, not run by browsers.
+  /// The type as written.
+  DataBlock(&'a str),
+}
+
+impl<'a> ScriptKind<'a> {
+  /// Get the kind of a script from the value of its `type` attribute, `None` without one.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_ast::ScriptKind;
+  ///
+  /// assert_eq!(ScriptKind::from_type(None), ScriptKind::Classic);
+  /// assert_eq!(ScriptKind::from_type(Some("text/JavaScript")), ScriptKind::Classic);
+  /// assert_eq!(ScriptKind::from_type(Some(" module ")), ScriptKind::Module);
+  /// assert_eq!(
+  ///   ScriptKind::from_type(Some("text/x-template")),
+  ///   ScriptKind::DataBlock("text/x-template")
+  /// );
+  /// ```
+  pub fn from_type(value: Option<&'a str>) -> Self {
+    let Some(value) = value else {
+      return Self::Classic;
+    };
+    // MIME types are matched exactly, the other types without surrounding whitespace
+    if value.is_empty()
+      || JAVASCRIPT_MIME_TYPES
+        .iter()
+        .any(|mime| value.eq_ignore_ascii_case(mime))
+    {
+      return Self::Classic;
+    }
+    let trimmed = value.trim_matches(|c: char| c.is_ascii_whitespace());
+    if trimmed.eq_ignore_ascii_case("module") {
+      Self::Module
+    } else if trimmed.eq_ignore_ascii_case("importmap") {
+      Self::ImportMap
+    } else if trimmed.eq_ignore_ascii_case("speculationrules") {
+      Self::SpeculationRules
+    } else {
+      Self::DataBlock(value)
+    }
+  }
+
+  /// Whether the script is JavaScript, a classic or module script.
+  pub const fn is_javascript(self) -> bool {
+    matches!(self, Self::Classic | Self::Module)
+  }
+
+  /// The source type to parse a script of this kind with, the module goal for modules and
+  /// the script goal for the others.
+  pub const fn source_type(self) -> SourceType {
+    match self {
+      Self::Module => SourceType::mjs(),
+      _ => SourceType::cjs(),
+    }
+  }
+}
+
+/// What a `<script>` is: its [kind](ScriptKind), and the attributes deciding how browsers
+/// load and run it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(rename_all = "camelCase")
+)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ScriptInfo<'a> {
+  pub kind: ScriptKind<'a>,
+  /// Whether the script has `nomodule`, so browsers supporting modules do not run it
+  pub no_module: bool,
+  /// Whether the script has `async`
+  pub is_async: bool,
+  /// Whether the script has `defer`
+  pub defer: bool,
+  /// Whether the script has `src`, so its content is loaded from there
+  pub external: bool,
+}
+
+impl<'a> ScriptInfo<'a> {
+  /// Get the information of a script from its attributes.
+  pub fn from_attributes(attributes: &[Attribute<'a>]) -> Self {
+    let has = |name: &str| {
+      attributes
+        .iter()
+        .any(|attribute| attribute.key.name().eq_ignore_ascii_case(name))
+    };
+    let type_value = attributes
+      .iter()
+      .find(|attribute| attribute.key.name().eq_ignore_ascii_case("type"))
+      .map(|attribute| attribute.value.as_ref().map_or("", |value| value.value));
+    Self {
+      kind: ScriptKind::from_type(type_value),
+      no_module: has("nomodule"),
+      is_async: has("async"),
+      defer: has("defer"),
+      external: has("src"),
+    }
+  }
+}
+
+impl<'a> Element<'a> {
+  /// The information of a `<script>` element, `None` for other elements. Scripts the parser
+  /// does not parse, like data blocks, are elements.
+  pub fn script_info(&self) -> Option<ScriptInfo<'a>> {
+    self
+      .name()
+      .eq_ignore_ascii_case("script")
+      .then(|| ScriptInfo::from_attributes(&self.attributes))
+  }
+}
@@ -0,0 +1,226 @@
+//! [`GetSpan`] for every node, lookups of nodes by offset, and the source text of nodes.
+
+use umc_span::{GetSpan, GetSpanMut, Span};
+
+use crate::{
+  Attribute, AttributeKey, AttributeValue, Comment, ConditionalComment, Directive, Doctype,
+  Element, ModuleExport, ModuleImport, Mustache, Node, RawUnknown, Script, StyleDeclaration,
+  TemplateTag, Text,
+};
+
+macro_rules! impl_get_span {
+  ($($ty:ident),* $(,)?) => {
+    $(
+      impl GetSpan for $ty<'_> {
+        #[inline]
+        fn span(&self) -> Span {
+          self.span
+        }
+      }
+
+      impl GetSpanMut for $ty<'_> {
+        #[inline]
+        fn span_mut(&mut self) -> &mut Span {
+          &mut self.span
+        }
+      }
+    )*
+  };
+}
+
+impl_get_span!(
+  Doctype,
+  Element,
+  Text,
+  Comment,
+  ConditionalComment,
+  Script,
+  ModuleImport,
+  ModuleExport,
+  RawUnknown,
+  Mustache,
+  TemplateTag,
+  Directive,
+  Attribute,
+  StyleDeclaration,
+  AttributeKey,
+  AttributeValue,
+);
+
+impl Node<'_> {
+  /// Source location of the node.
+  pub fn span(&self) -> Span {
+    match self {
+      Self::Doctype(node) => node.span,
+      Self::Element(node) => node.span,
+      Self::Text(node) => node.span,
+      Self::Comment(node) => node.span,
+      Self::Script(node) => node.span,
+      Self::RawUnknown(node) => node.span,
+      Self::Mustache(node) => node.span,
+      Self::TemplateTag(node) => node.span,
+      Self::Directive(node) => node.span,
+    }
+  }
+
+  /// The source text of the node as written, with its tags, e.g. `<p>Hi</p>`.
+  ///
+  /// `source_text` must be the source the node was parsed from.
+  pub fn source_text<'s>(&self, source_text: &'s str) -> &'s str {
+    self.span().source_text(source_text)
+  }
+
+  /// The child nodes of an element, of a conditional comment, or of a Handlebars block up
+  /// to its `{{else}}`.
+  pub fn children(&self) -> &[Self] {
+    match self {
+      Self::Element(element) => &element.children,
+      Self::Mustache(mustache) => &mustache.children,
+      Self::Comment(comment) => comment
+        .conditional
+        .as_ref()
+        .map_or(&[], |conditional| &conditional.children),
+      _ => &[],
+    }
+  }
+}
+
+impl Element<'_> {
+  /// The source text of the content of the element as written, between its opening and
+  /// closing tags, e.g. `Hi <b>there</b>` for `<p>Hi <b>there</b></p>`. Empty for void and
+  /// self-closed elements.
+  ///
+  /// The content of an element closed implicitly runs to the end of its last child, and the
+  /// content of an implied element, like `<body>`, starts with its first child.
+  ///
+  /// `source_text` must be the source the element was parsed from.
+  pub fn inner_source<'s>(&self, source_text: &'s str) -> &'s str {
+    let outer = self.span.source_text(source_text);
+    let start = self.opening_tag_end(outer);
+    let children_end = self
+      .children
+      .last()
+      .map_or(start, |child| (child.span().end - self.span.start) as usize);
+    let end = outer
+      .rfind("</")
+      .filter(|&end| end >= children_end && outer.ends_with('>'))
+      .filter(|&end| is_tag_named(&outer[end + 2..], self.tag_name))
+      .unwrap_or(outer.len());
+    &outer[start..end.max(start)]
+  }
+
+  /// The length of the opening tag at the start of `outer`, the source text of the element,
+  /// `0` for an implied element.
+  fn opening_tag_end(&self, outer: &str) -> usize {
+    if !outer
+      .strip_prefix('<')
+      .is_some_and(|rest| is_tag_named(rest, self.tag_name))
+    {
+      return 0;
+    }
+    // `>` cannot appear in an attribute other than in its quoted value
+    let after_attributes = self
+      .attributes
+      .last()
+      .map_or(1 + self.tag_name.len(), |attribute| {
+        (attribute.span.end - self.span.start) as usize
+      });
+    outer[after_attributes..]
+      .find('>')
+      .map_or(outer.len(), |index| after_attributes + index + 1)
+  }
+}
+
+/// Whether `text` starts with the tag name `name`, ignoring ASCII case, followed by the end
+/// of the name.
+fn is_tag_named(text: &str, name: &str) -> bool {
+  text
+    .get(..name.len())
+    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+    && text[name.len()..]
+      .chars()
+      .next()
+      .is_none_or(|c| c.is_ascii_whitespace() || c == '/' || c == '>')
+}
+
+impl GetSpan for Node<'_> {
+  #[inline]
+  fn span(&self) -> Span {
+    Node::span(self)
+  }
+}
+
+impl GetSpanMut for Node<'_> {
+  fn span_mut(&mut self) -> &mut Span {
+    match self {
+      Self::Doctype(node) => &mut node.span,
+      Self::Element(node) => &mut node.span,
+      Self::Text(node) => &mut node.span,
+      Self::Comment(node) => &mut node.span,
+      Self::Script(node) => &mut node.span,
+      Self::RawUnknown(node) => &mut node.span,
+      Self::Mustache(node) => &mut node.span,
+      Self::TemplateTag(node) => &mut node.span,
+      Self::Directive(node) => &mut node.span,
+    }
+  }
+}
+
+/// Get the index of the node covering `offset` among sibling nodes, by binary search.
+///
+/// A node covers the offsets from its start, included, to its end, excluded. Siblings are
+/// expected in source order, without overlapping spans, like the parser produces them.
+pub fn child_at_offset(nodes: &[Node], offset: u32) -> Option<usize> {
+  let index = nodes.partition_point(|node| node.span().end <= offset);
+  nodes
+    .get(index)
+    .is_some_and(|node| node.span().start <= offset)
+    .then_some(index)
+}
+
+/// Get the deepest node covering `offset`, looking into elements, conditional comments and
+/// Handlebars blocks.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::{Allocator, Box, Vec};
+/// use umc_html_ast::{Element, Node, Text, node_at_offset};
+/// use umc_span::Span;
+///
+/// let allocator = Allocator::default();
+/// // <p>Hi</p>
+/// let text = Text { span: Span::new(3, 5), value: "Hi" };
+/// let element = Element {
+///   span: Span::new(0, 9),
+///   tag_name: "p",
+///   normalized_name: "p",
+///   attributes: Vec::new_in(&allocator),
+///   children: Vec::from_iter_in([Node::Text(Box::new_in(text, &allocator))], &allocator),
+/// };
+/// let program = Vec::from_iter_in([Node::Element(Box::new_in(element, &allocator))], &allocator);
+///
+/// assert!(matches!(node_at_offset(&program, 4), Some(Node::Text(_))));
+/// assert!(matches!(node_at_offset(&program, 6), Some(Node::Element(_))));
+/// assert!(node_at_offset(&program, 9).is_none());
+/// ```
+pub fn node_at_offset<'n, 'a>(nodes: &'n [Node<'a>], offset: u32) -> Option<&'n Node<'a>> {
+  let mut node = &nodes[child_at_offset(nodes, offset)?];
+  loop {
+    let children = match node {
+      Node::Mustache(mustache)
+        if mustache
+          .inverse
+          .first()
+          .is_some_and(|first| first.span().start <= offset) =>
+      {
+        &mustache.inverse
+      }
+      _ => node.children(),
+    };
+    let Some(index) = child_at_offset(children, offset) else {
+      return Some(node);
+    };
+    node = &children[index];
+  }
+}
@@ -0,0 +1,290 @@
+//! Conformance harness for [html5lib-tests](https://github.com/html5lib/html5lib-tests).
+//!
+//! This developer-facing crate runs the tokenizer and tree construction fixtures of
+//! html5lib-tests against UMC, and compares the output with the expected one:
+//!
+//! - `tokenizer/*.test` fixtures are tokenized with the
+//!   [`HtmlStreamParser`](umc_html_parser::stream::HtmlStreamParser), and the events are
+//!   converted to the token arrays of the fixtures
+//! - `tree-construction/*.dat` fixtures are parsed with [`Parser::html`](umc_html_parser::CreateHtml::html),
+//!   and the AST is serialized to the `| <tag>` tree format of the fixtures
+//!
+//! Parse errors are not compared, since UMC reports its own diagnostics rather than the
+//! error codes of the spec.
+//!
+//! UMC does not implement every algorithm of the spec yet, so most fixtures are expected
+//! to fail. The failures known so far are listed in an [`Allowlist`], and a test only fails
+//! the run when it is not listed, or when it is listed but passes, so the list shrinks as
+//! the parser gets closer to the spec.
+//!
+//! # Running
+//!
+//! The fixtures are not vendored. Clone html5lib-tests, then run the ignored test of this
+//! crate with the path of the checkout:
+//!
+//! ```sh
+//! HTML5LIB_TESTS=../html5lib-tests cargo test -p umc_html_conformance -- --ignored
+//! ```
+//!
+//! Set `UPDATE_KNOWN_FAILURES=1` as well to rewrite `known_failures.txt` with the current
+//! failures instead of checking them.
+//!
+//! # Example
+//!
+//! ```
+//! use umc_html_conformance::{Allowlist, Outcome, run_tree_construction};
+//!
+//! let fixture = "#data\n<p>Hi\n#errors\n#document\n| <p>\n|   \"Hi\"\n";
+//! let results = run_tree_construction("example.dat", fixture, &Allowlist::default());
+//!
+//! assert_eq!(results[0].id, "example.dat#0");
+//! assert_eq!(results[0].outcome, Outcome::Pass);
+//! ```
+
+mod tokenizer;
+mod tree;
+
+use std::{
+  collections::BTreeSet,
+  fmt::{self, Display},
+  fs, io,
+  path::Path,
+};
+
+pub use tokenizer::run_tokenizer;
+pub use tree::{run_tree_construction, serialize_program};
+
+/// Identifiers of the tests known to fail.
+///
+/// Identifiers are `<fixture file name>#<index of the test in the file>`, e.g.
+/// `tests1.dat#12`.
+#[derive(Debug, Default, Clone)]
+pub struct Allowlist {
+  ids: BTreeSet<String>,
+}
+
+impl Allowlist {
+  /// Parse an allowlist with one test identifier per line.
+  ///
+  /// Blank lines and lines starting with `#` are ignored.
+  pub fn parse(text: &str) -> Self {
+    let ids = text
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(ToString::to_string)
+      .collect();
+    Self { ids }
+  }
+
+  /// Whether the test `id` is known to fail.
+  pub fn contains(&self, id: &str) -> bool {
+    self.ids.contains(id)
+  }
+
+  fn outcome(&self, id: &str, expected: String, actual: String) -> Outcome {
+    match (expected == actual, self.contains(id)) {
+      (true, false) => Outcome::Pass,
+      (true, true) => Outcome::UnexpectedPass,
+      (false, true) => Outcome::KnownFailure,
+      (false, false) => Outcome::Fail { expected, actual },
+    }
+  }
+}
+
+/// The outcome of a single test.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+  /// The output matches
+  Pass,
+  /// The output differs
+  Fail {
+    /// Expected output, in the format of the fixture
+    expected: String,
+    /// Output of UMC, in the same format
+    actual: String,
+  },
+  /// The output differs, and the test is in the allowlist
+  KnownFailure,
+  /// The output matches, but the test is in the allowlist
+  UnexpectedPass,
+  /// The test uses a feature the harness does not support, e.g. a tokenizer initial state
+  Skipped(&'static str),
+}
+
+/// The result of a single test.
+#[derive(Debug, Clone)]
+pub struct TestResult {
+  /// Identifier of the test, see [`Allowlist`]
+  pub id: String,
+  /// The input document
+  pub input: String,
+  /// The outcome
+  pub outcome: Outcome,
+}
+
+/// Counts of the outcomes of a run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+  pub passed: usize,
+  pub failed: usize,
+  pub known_failures: usize,
+  pub unexpected_passes: usize,
+  pub skipped: usize,
+}
+
+impl Summary {
+  /// Count the outcomes of `results`.
+  pub fn new(results: &[TestResult]) -> Self {
+    let mut summary = Self::default();
+    for result in results {
+      match result.outcome {
+        Outcome::Pass => summary.passed += 1,
+        Outcome::Fail { .. } => summary.failed += 1,
+        Outcome::KnownFailure => summary.known_failures += 1,
+        Outcome::UnexpectedPass => summary.unexpected_passes += 1,
+        Outcome::Skipped(_) => summary.skipped += 1,
+      }
+    }
+    summary
+  }
+
+  /// Whether the run has no new failure and no unexpected pass.
+  pub const fn is_success(&self) -> bool {
+    self.failed == 0 && self.unexpected_passes == 0
+  }
+}
+
+impl Display for Summary {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} passed, {} failed, {} known failures, {} unexpected passes, {} skipped",
+      self.passed, self.failed, self.known_failures, self.unexpected_passes, self.skipped
+    )
+  }
+}
+
+/// Run every fixture of an html5lib-tests checkout.
+///
+/// Reads `tokenizer/*.test` and `tree-construction/*.dat` under `root`, in file name order.
+///
+/// # Errors
+///
+/// Returns an error if a fixture cannot be read, or if a tokenizer fixture is not valid JSON.
+pub fn run_directory(root: &Path, allowlist: &Allowlist) -> io::Result<Vec<TestResult>> {
+  let mut results = Vec::new();
+
+  for path in fixtures(&root.join("tokenizer"), "test")? {
+    let name = file_name(&path);
+    let json = fs::read_to_string(&path)?;
+    let tests = run_tokenizer(&name, &json, allowlist)
+      .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, format!("{name}: {error}")))?;
+    results.extend(tests);
+  }
+
+  for path in fixtures(&root.join("tree-construction"), "dat")? {
+    let dat = fs::read_to_string(&path)?;
+    results.extend(run_tree_construction(&file_name(&path), &dat, allowlist));
+  }
+
+  Ok(results)
+}
+
+fn fixtures(directory: &Path, extension: &str) -> io::Result<Vec<std::path::PathBuf>> {
+  let mut paths = Vec::new();
+  for entry in fs::read_dir(directory)? {
+    let path = entry?.path();
+    if path.extension().is_some_and(|ext| ext == extension) {
+      paths.push(path);
+    }
+  }
+  paths.sort();
+  Ok(paths)
+}
+
+fn file_name(path: &Path) -> String {
+  path
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+  use std::{env, fmt::Write, path::Path};
+
+  use crate::{Allowlist, Outcome, Summary, run_directory};
+
+  const KNOWN_FAILURES: &str = include_str!("../known_failures.txt");
+
+  #[test]
+  fn allowlist() {
+    let allowlist = Allowlist::parse("# header\n\ntests1.dat#0\n  test1.test#3  \n");
+    assert!(allowlist.contains("tests1.dat#0"));
+    assert!(allowlist.contains("test1.test#3"));
+    assert!(!allowlist.contains("# header"));
+
+    let outcome = |id, expected: &str, actual: &str| {
+      allowlist.outcome(id, expected.to_string(), actual.to_string())
+    };
+    assert_eq!(outcome("tests1.dat#0", "a", "a"), Outcome::UnexpectedPass);
+    assert_eq!(outcome("tests1.dat#0", "a", "b"), Outcome::KnownFailure);
+    assert_eq!(outcome("tests1.dat#1", "a", "a"), Outcome::Pass);
+    assert!(matches!(
+      outcome("tests1.dat#1", "a", "b"),
+      Outcome::Fail { .. }
+    ));
+  }
+
+  #[test]
+  #[ignore = "needs a checkout of html5lib-tests in HTML5LIB_TESTS"]
+  fn html5lib_tests() {
+    let root = env::var("HTML5LIB_TESTS").expect("HTML5LIB_TESTS is not set");
+    let update = env::var_os("UPDATE_KNOWN_FAILURES").is_some();
+    let allowlist = if update {
+      Allowlist::default()
+    } else {
+      Allowlist::parse(KNOWN_FAILURES)
+    };
+
+    let results = run_directory(Path::new(&root), &allowlist).unwrap();
+    let summary = Summary::new(&results);
+    println!("{summary}");
+
+    if update {
+      let mut text = KNOWN_FAILURES
+        .lines()
+        .take_while(|line| line.starts_with('#'))
+        .fold(String::new(), |text, line| text + line + "\n");
+      for result in &results {
+        if matches!(result.outcome, Outcome::Fail { .. }) {
+          writeln!(text, "{}", result.id).unwrap();
+        }
+      }
+      let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("known_failures.txt");
+      std::fs::write(path, text).unwrap();
+      return;
+    }
+
+    let mut report = String::new();
+    for result in &results {
+      match &result.outcome {
+        Outcome::Fail { expected, actual } => writeln!(
+          report,
+          "{} failed\ninput:\n{}\nexpected:\n{expected}\nactual:\n{actual}\n",
+          result.id, result.input
+        )
+        .unwrap(),
+        Outcome::UnexpectedPass => writeln!(
+          report,
+          "{} passes, remove it from known_failures.txt",
+          result.id
+        )
+        .unwrap(),
+        _ => {}
+      }
+    }
+    assert!(summary.is_success(), "{report}{summary}");
+  }
+}
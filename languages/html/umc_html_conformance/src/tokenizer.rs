@@ -0,0 +1,250 @@
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use umc_html_parser::stream::{HtmlStreamParser, StreamEvent};
+
+use crate::{Allowlist, Outcome, TestResult};
+
+/// A tokenizer fixture file.
+#[derive(Deserialize)]
+struct Fixture {
+  #[serde(default)]
+  tests: Vec<Test>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Test {
+  input: String,
+  output: Vec<Value>,
+  #[serde(default)]
+  initial_states: Vec<String>,
+  #[serde(default)]
+  double_escaped: bool,
+}
+
+/// Run the tests of a tokenizer fixture file named `name`.
+///
+/// # Errors
+///
+/// Returns an error if `json` is not a valid fixture.
+pub fn run_tokenizer(
+  name: &str,
+  json: &str,
+  allowlist: &Allowlist,
+) -> serde_json::Result<Vec<TestResult>> {
+  let fixture: Fixture = serde_json::from_str(json)?;
+
+  Ok(
+    fixture
+      .tests
+      .into_iter()
+      .enumerate()
+      .map(|(index, test)| {
+        let id = format!("{name}#{index}");
+        let (input, outcome) = run_test(&id, test, allowlist);
+        TestResult { id, input, outcome }
+      })
+      .collect(),
+  )
+}
+
+fn run_test(id: &str, test: Test, allowlist: &Allowlist) -> (String, Outcome) {
+  if test
+    .initial_states
+    .iter()
+    .any(|state| state != "Data state")
+  {
+    return (test.input, Outcome::Skipped("initial state"));
+  }
+
+  let mut expected = test.output;
+  let input = if test.double_escaped {
+    let Some(input) = unescape(&test.input) else {
+      return (test.input, Outcome::Skipped("lone surrogate"));
+    };
+    if !expected.iter_mut().all(unescape_value) {
+      return (input, Outcome::Skipped("lone surrogate"));
+    }
+    input
+  } else {
+    test.input
+  };
+  // older fixtures list errors among the tokens
+  expected.retain(|token| token != "ParseError");
+
+  let expected = Value::Array(merge_characters(expected)).to_string();
+  let actual = Value::Array(tokenize(&input)).to_string();
+  let outcome = allowlist.outcome(id, expected, actual);
+  (input, outcome)
+}
+
+/// Tokenize `input` into the token arrays of the fixtures.
+fn tokenize(input: &str) -> Vec<Value> {
+  let mut parser = HtmlStreamParser::default();
+  parser.feed(input);
+  parser.end();
+
+  let mut tokens = Vec::new();
+  while let Some(event) = parser.next_event() {
+    tokens.push(match event {
+      StreamEvent::Doctype { attributes, .. } => {
+        let words: Vec<&str> = attributes
+          .iter()
+          .map(|attribute| attribute.key.value)
+          .collect();
+        let (name, public_id, system_id) = doctype_ids(&words);
+        json!(["DOCTYPE", name, public_id, system_id, true])
+      }
+      StreamEvent::StartTag {
+        name,
+        attributes,
+        self_closing,
+        ..
+      } => {
+        let mut map = Map::new();
+        for attribute in &attributes {
+          let value = attribute.value.as_ref().map_or("", |value| value.value);
+          map
+            .entry(attribute.key.value.to_ascii_lowercase())
+            .or_insert_with(|| value.into());
+        }
+        let mut token = vec![
+          json!("StartTag"),
+          json!(name.to_ascii_lowercase()),
+          map.into(),
+        ];
+        if self_closing {
+          token.push(true.into());
+        }
+        token.into()
+      }
+      StreamEvent::EndTag { name, .. } => json!(["EndTag", name.to_ascii_lowercase()]),
+      StreamEvent::Text { value, .. } => json!(["Character", value]),
+      StreamEvent::Comment { value, .. } => json!(["Comment", value]),
+    });
+  }
+
+  merge_characters(tokens)
+}
+
+/// Merge consecutive character tokens, which the fixtures always expect as one.
+fn merge_characters(tokens: Vec<Value>) -> Vec<Value> {
+  let mut merged: Vec<Value> = Vec::with_capacity(tokens.len());
+  for token in tokens {
+    if let (Some(text), Some(previous)) = (character(&token), merged.last_mut())
+      && let Some(previous_text) = character(previous)
+    {
+      *previous = json!(["Character", previous_text.to_string() + text]);
+      continue;
+    }
+    merged.push(token);
+  }
+  merged
+}
+
+fn character(token: &Value) -> Option<&str> {
+  match token.as_array()?.as_slice() {
+    [kind, text] if kind == "Character" => text.as_str(),
+    _ => None,
+  }
+}
+
+/// Split the words of a doctype into its name, public identifier and system identifier.
+fn doctype_ids(words: &[&str]) -> (Option<String>, Option<String>, Option<String>) {
+  let name = words
+    .iter()
+    .find(|word| !word.eq_ignore_ascii_case("doctype"))
+    .map(|word| word.to_ascii_lowercase());
+  let unquote = |word: &&str| word.trim_matches(|c| c == '"' || c == '\'').to_string();
+
+  let keyword = words
+    .iter()
+    .position(|word| word.eq_ignore_ascii_case("public") || word.eq_ignore_ascii_case("system"));
+  let Some(keyword) = keyword else {
+    return (name, None, None);
+  };
+  let mut ids = words[keyword + 1..].iter().map(unquote);
+
+  if words[keyword].eq_ignore_ascii_case("public") {
+    (name, ids.next(), ids.next())
+  } else {
+    (name, None, ids.next())
+  }
+}
+
+/// Decode the `\uXXXX` escapes of a `doubleEscaped` test.
+///
+/// Returns `None` for a lone surrogate, which a Rust string cannot hold.
+fn unescape(text: &str) -> Option<String> {
+  let mut units = Vec::with_capacity(text.len());
+  let mut rest = text;
+
+  while let Some(index) = rest.find("\\u") {
+    units.extend(rest[..index].encode_utf16());
+    let hex = rest.get(index + 2..index + 6)?;
+    units.push(u16::from_str_radix(hex, 16).ok()?);
+    rest = &rest[index + 6..];
+  }
+  units.extend(rest.encode_utf16());
+
+  String::from_utf16(&units).ok()
+}
+
+fn unescape_value(value: &mut Value) -> bool {
+  match value {
+    Value::String(text) => unescape(text).map(|unescaped| *text = unescaped).is_some(),
+    Value::Array(values) => values.iter_mut().all(unescape_value),
+    Value::Object(map) => {
+      let entries = std::mem::take(map);
+      for (key, mut value) in entries {
+        let Some(key) = unescape(&key) else {
+          return false;
+        };
+        if !unescape_value(&mut value) {
+          return false;
+        }
+        map.insert(key, value);
+      }
+      true
+    }
+    _ => true,
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use crate::{Allowlist, Outcome, tokenizer::run_tokenizer};
+
+  const FIXTURE: &str = r#"{"tests": [
+    {"description": "Start and end tags", "input": "<A HREF='/' Href=x>Hi</a>",
+     "output": [["StartTag", "a", {"href": "/"}], ["Character", "Hi"], ["EndTag", "a"]]},
+    {"description": "Self-closing and comment", "input": "<br/><!--c-->",
+     "output": [["StartTag", "br", {}, true], ["Comment", "c"]]},
+    {"description": "Doctype", "input": "<!DOCTYPE html PUBLIC \"-//W3C//DTD HTML 4.01//EN\">",
+     "output": [["DOCTYPE", "html", "-//W3C//DTD HTML 4.01//EN", null, true]]},
+    {"description": "Character reference", "input": "&amp;",
+     "output": [["Character", "&"]], "errors": [{"code": "x", "line": 1, "col": 1}]},
+    {"description": "Initial state", "input": "<b>", "initialStates": ["RCDATA state"],
+     "output": [["Character", "<b>"]]},
+    {"description": "Escaped", "input": "\\u00e9\\uD83D\\uDE00", "doubleEscaped": true,
+     "output": [["Character", "\\u00e9\\uD83D\\uDE00"]]}
+  ]}"#;
+
+  #[test]
+  fn tokenizer_fixture() {
+    let results = run_tokenizer("test1.test", FIXTURE, &Allowlist::default()).unwrap();
+    let snapshot = results
+      .iter()
+      .map(|result| match &result.outcome {
+        Outcome::Fail { expected, actual } => {
+          format!("{}: expected {expected}, actual {actual}", result.id)
+        }
+        outcome => format!("{}: {outcome:?}", result.id),
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+    assert_snapshot!(snapshot);
+  }
+}
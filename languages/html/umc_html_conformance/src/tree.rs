@@ -0,0 +1,268 @@
+use std::fmt::Write;
+
+use oxc_allocator::Allocator;
+use umc_html_ast::{Attribute, Node, Program};
+use umc_html_parser::CreateHtml;
+use umc_parser::Parser;
+
+use crate::{Allowlist, Outcome, TestResult};
+
+/// A test of a tree construction fixture.
+#[derive(Debug, Default)]
+struct Test {
+  data: String,
+  document: String,
+  fragment: bool,
+  script_on: bool,
+}
+
+/// Run the tests of a tree construction fixture file named `name`.
+pub fn run_tree_construction(name: &str, dat: &str, allowlist: &Allowlist) -> Vec<TestResult> {
+  parse_fixture(dat)
+    .into_iter()
+    .enumerate()
+    .map(|(index, test)| {
+      let id = format!("{name}#{index}");
+      let outcome = if test.fragment {
+        Outcome::Skipped("document fragment")
+      } else if test.script_on {
+        Outcome::Skipped("scripting enabled")
+      } else {
+        let allocator = Allocator::default();
+        let parser = Parser::html(&allocator, &test.data);
+        let program = parser.parse().program;
+        allowlist.outcome(&id, test.document, serialize_program(&program))
+      };
+      TestResult {
+        id,
+        input: test.data,
+        outcome,
+      }
+    })
+    .collect()
+}
+
+/// Split a `.dat` fixture into its tests.
+///
+/// Each test starts with a `#data` line, followed by sections like `#errors` and
+/// `#document`, each running until the next section.
+fn parse_fixture(dat: &str) -> Vec<Test> {
+  let mut tests = Vec::new();
+  let mut section = "";
+  let mut lines: Vec<&str> = Vec::new();
+
+  let finish_section = |tests: &mut Vec<Test>, section: &str, lines: &mut Vec<&str>| {
+    let Some(test) = tests.last_mut() else {
+      return;
+    };
+    match section {
+      "#data" => test.data = lines.join("\n"),
+      "#document" => {
+        // tests are separated by a blank line
+        if lines.last() == Some(&"") {
+          lines.pop();
+        }
+        test.document = lines.join("\n");
+      }
+      "#document-fragment" => test.fragment = true,
+      "#script-on" => test.script_on = true,
+      _ => {}
+    }
+    lines.clear();
+  };
+
+  for line in dat.lines() {
+    if line.starts_with('#') && !line.starts_with("#|") {
+      finish_section(&mut tests, section, &mut lines);
+      section = line.trim_end();
+      if section == "#data" {
+        tests.push(Test::default());
+      }
+    } else {
+      lines.push(line);
+    }
+  }
+  finish_section(&mut tests, section, &mut lines);
+
+  tests
+}
+
+/// Serialize a program to the tree format of html5lib-tests.
+///
+/// ```text
+/// | <!DOCTYPE html>
+/// | <p>
+/// |   class="a"
+/// |   "Hello"
+/// ```
+///
+/// Attributes are sorted by name, and names are lowercased like the tokenizer of the spec.
+/// Text is not decoded, as the AST keeps character references as written.
+pub fn serialize_program(program: &Program) -> String {
+  let mut output = String::new();
+  serialize_nodes(&mut output, program, 0);
+  output.truncate(output.trim_end_matches('\n').len());
+  output
+}
+
+fn serialize_nodes(output: &mut String, nodes: &[Node], depth: usize) {
+  for node in nodes {
+    let indent = "  ".repeat(depth);
+    match node {
+      Node::Doctype(doctype) => {
+        let words: Vec<&str> = doctype
+          .attributes
+          .iter()
+          .map(|attribute| attribute.key.value)
+          .collect();
+        let name = words
+          .iter()
+          .find(|word| !word.eq_ignore_ascii_case("doctype"))
+          .map(|word| word.to_ascii_lowercase())
+          .unwrap_or_default();
+        writeln!(output, "| {indent}<!DOCTYPE {name}>").unwrap();
+      }
+      Node::Element(element) => {
+        writeln!(
+          output,
+          "| {indent}<{}>",
+          element.tag_name.to_ascii_lowercase()
+        )
+        .unwrap();
+        serialize_attributes(output, &element.attributes, depth + 1);
+        serialize_nodes(output, &element.children, depth + 1);
+      }
+      Node::Script(script) => {
+        writeln!(
+          output,
+          "| {indent}<{}>",
+          script.tag_name.to_ascii_lowercase()
+        )
+        .unwrap();
+        serialize_attributes(output, &script.attributes, depth + 1);
+        if !script.program.source_text.is_empty() {
+          writeln!(output, "| {indent}  \"{}\"", script.program.source_text).unwrap();
+        }
+      }
+      Node::Text(text) => writeln!(output, "| {indent}\"{}\"", text.value).unwrap(),
+      Node::Comment(comment) => writeln!(output, "| {indent}<!-- {} -->", comment.value).unwrap(),
+      Node::RawUnknown(raw) => writeln!(output, "| {indent}\"{}\"", raw.raw).unwrap(),
+      // A bogus comment in the spec, its data is everything between `<` and `>`
+      Node::Directive(directive) => {
+        let data = &directive.raw[1..];
+        let data = data.strip_suffix('>').unwrap_or(data);
+        writeln!(output, "| {indent}<!-- {data} -->").unwrap();
+      }
+      // The spec has no Handlebars, a block is written as its tag followed by its content
+      Node::Mustache(mustache) => {
+        writeln!(output, "| {indent}\"{{{{{}}}}}\"", mustache.expression).unwrap();
+        serialize_nodes(output, &mustache.children, depth);
+        serialize_nodes(output, &mustache.inverse, depth);
+      }
+      Node::TemplateTag(tag) => {
+        writeln!(
+          output,
+          "| {indent}\"{}{}{}\"",
+          tag.open, tag.value, tag.close
+        )
+        .unwrap();
+      }
+    }
+  }
+}
+
+fn serialize_attributes(output: &mut String, attributes: &[Attribute], depth: usize) {
+  let mut attributes: Vec<(String, &str)> = attributes
+    .iter()
+    .map(|attribute| {
+      (
+        attribute.key.value.to_ascii_lowercase(),
+        attribute.value.as_ref().map_or("", |value| value.value),
+      )
+    })
+    .collect();
+  attributes.sort_by(|a, b| a.0.cmp(&b.0));
+  // the first of duplicated attributes wins
+  attributes.dedup_by(|b, a| a.0 == b.0);
+
+  let indent = "  ".repeat(depth);
+  for (name, value) in attributes {
+    writeln!(output, "| {indent}{name}=\"{value}\"").unwrap();
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use crate::{Allowlist, Outcome, tree::run_tree_construction};
+
+  const FIXTURE: &str = r#"#data
+<div id=b ID=c class=a>Hello<!--x--></div>
+#errors
+(1,5): expected-doctype-but-got-start-tag
+#document
+| <div>
+|   class="a"
+|   id="b"
+|   "Hello"
+|   <!-- x -->
+
+#data
+<p>One
+two
+#errors
+#document
+| <html>
+|   <head>
+|   <body>
+|     <p>
+|       "One
+two"
+
+#data
+<b>
+#errors
+#document-fragment
+td
+#document
+| <b>
+
+#data
+<!DOCTYPE html><script>a</script>
+#errors
+#script-on
+#document
+| <!DOCTYPE html>
+"#;
+
+  #[test]
+  fn tree_construction_fixture() {
+    let allowlist = Allowlist::parse("tests1.dat#1");
+    let results = run_tree_construction("tests1.dat", FIXTURE, &allowlist);
+    let snapshot = results
+      .iter()
+      .map(|result| match &result.outcome {
+        Outcome::Fail { expected, actual } => {
+          format!("{}: expected\n{expected}\nactual\n{actual}", result.id)
+        }
+        outcome => format!("{}: {outcome:?} {:?}", result.id, result.input),
+      })
+      .collect::<Vec<_>>()
+      .join("\n");
+    assert_snapshot!(snapshot);
+  }
+
+  #[test]
+  fn serialize() {
+    let results = run_tree_construction(
+      "example.dat",
+      "#data\n<!doctype HTML><ul><li>a<li>b</ul><script>let x</script>\n#errors\n#document\n",
+      &Allowlist::default(),
+    );
+    let Outcome::Fail { actual, .. } = &results[0].outcome else {
+      panic!("expected a failure");
+    };
+    assert_snapshot!(actual);
+  }
+}
@@ -0,0 +1,517 @@
+//! Tree diffing for HTML ASTs.
+//!
+//! This crate compares two [`Program`]s and produces an edit script describing how to
+//! turn the old tree into the new one. Every edit carries the location of the affected
+//! node in both trees, so it can drive visual-regression reports as well as
+//! morphdom-style patching of a live DOM.
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_html_diff::{EditKind, diff};
+//! use umc_html_parser::CreateHtml;
+//! use umc_parser::Parser;
+//!
+//! let allocator = Allocator::default();
+//! let old = Parser::html(&allocator, r#"<a href="/old">Link</a>"#);
+//! let new = Parser::html(&allocator, r#"<a href="/new">Link</a>"#);
+//! let (old, new) = (old.parse().program, new.parse().program);
+//!
+//! let edits = diff(&old, &new);
+//! assert_eq!(edits.len(), 1);
+//! assert!(matches!(edits[0].kind, EditKind::SetAttribute { name: "href", value: Some("/new") }));
+//! ```
+
+use umc_html_ast::{Attribute, Node, Program};
+use umc_span::Span;
+
+/// The kind of an [`Edit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind<'a> {
+  /// A node only present in the new tree
+  Insert,
+  /// A node only present in the old tree
+  Remove,
+  /// A node whose kind, tag name or content changed
+  Replace,
+  /// An attribute was added, or its value changed
+  SetAttribute {
+    /// Attribute name as written in the new tree
+    name: &'a str,
+    /// Attribute value in the new tree, without quotes
+    value: Option<&'a str>,
+  },
+  /// An attribute was removed
+  RemoveAttribute {
+    /// Attribute name as written in the old tree
+    name: &'a str,
+  },
+}
+
+/// A single operation of the edit script.
+///
+/// Paths are child indices starting from the program root, e.g. `[1, 0]` is the first child
+/// of the second root node. The content of a Handlebars block after its `{{else}}` is indexed
+/// after the content before it. For an [`Insert`](EditKind::Insert) the old path is the index the
+/// node would take among the old siblings, and for a [`Remove`](EditKind::Remove) the new path
+/// is the index the node would have taken among the new siblings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit<'a> {
+  /// The operation
+  pub kind: EditKind<'a>,
+  /// Location of the node in the old tree
+  pub old_path: Vec<usize>,
+  /// Location of the node in the new tree
+  pub new_path: Vec<usize>,
+  /// Span in the old source.
+  /// For an insertion, this is an empty span at the insertion point, or the parent span
+  /// if the parent had no children.
+  pub old_span: Span,
+  /// Span in the new source.
+  /// For a removal, this is an empty span at the removal point, or the parent span
+  /// if the parent has no children left.
+  pub new_span: Span,
+}
+
+/// Compare two programs and produce the edit script turning `old` into `new`.
+///
+/// Siblings are matched by kind, tag name and `id` attribute using a longest common
+/// subsequence, so moving a single element does not cause its siblings to be replaced.
+/// Adjacent removals and insertions at the same position are reported as replacements.
+pub fn diff<'a>(old: &'a Program<'_>, new: &'a Program<'_>) -> Vec<Edit<'a>> {
+  let mut differ = Differ {
+    edits: Vec::new(),
+    old_path: Vec::new(),
+    new_path: Vec::new(),
+  };
+  let root = Span::new(0, 0);
+  differ.diff_children(old, new, (root, root), (0, 0));
+  differ.edits
+}
+
+struct Differ<'a> {
+  edits: Vec<Edit<'a>>,
+  old_path: Vec<usize>,
+  new_path: Vec<usize>,
+}
+
+impl<'a> Differ<'a> {
+  fn push(&mut self, kind: EditKind<'a>, old_index: usize, new_index: usize, spans: (Span, Span)) {
+    let mut old_path = self.old_path.clone();
+    old_path.push(old_index);
+    let mut new_path = self.new_path.clone();
+    new_path.push(new_index);
+
+    self.edits.push(Edit {
+      kind,
+      old_path,
+      new_path,
+      old_span: spans.0,
+      new_span: spans.1,
+    });
+  }
+
+  /// Diff two sibling lists, the first ones at index `first` of their parents.
+  fn diff_children(
+    &mut self,
+    old: &'a [Node<'_>],
+    new: &'a [Node<'_>],
+    (old_parent, new_parent): (Span, Span),
+    (old_first, new_first): (usize, usize),
+  ) {
+    let pairs = lcs(old, new);
+
+    let (mut i, mut j) = (0, 0);
+    for (matched_i, matched_j) in pairs.into_iter().chain([(old.len(), new.len())]) {
+      // Unmatched runs before the next matched pair
+      let removed = i..matched_i;
+      let inserted = j..matched_j;
+
+      let replaced = removed.len().min(inserted.len());
+      for offset in 0..replaced {
+        let (oi, nj) = (i + offset, j + offset);
+        self.push(
+          EditKind::Replace,
+          old_first + oi,
+          new_first + nj,
+          (old[oi].span(), new[nj].span()),
+        );
+      }
+
+      let at = insertion_point(new, matched_j, new_parent);
+      for (oi, node) in old[..removed.end]
+        .iter()
+        .enumerate()
+        .skip(removed.start + replaced)
+      {
+        self.push(
+          EditKind::Remove,
+          old_first + oi,
+          new_first + matched_j,
+          (node.span(), at),
+        );
+      }
+
+      let at = insertion_point(old, matched_i, old_parent);
+      for (nj, node) in new[..inserted.end]
+        .iter()
+        .enumerate()
+        .skip(inserted.start + replaced)
+      {
+        self.push(
+          EditKind::Insert,
+          old_first + matched_i,
+          new_first + nj,
+          (at, node.span()),
+        );
+      }
+
+      if matched_i < old.len() {
+        self.diff_node(
+          &old[matched_i],
+          &new[matched_j],
+          old_first + matched_i,
+          new_first + matched_j,
+        );
+      }
+
+      i = matched_i + 1;
+      j = matched_j + 1;
+    }
+  }
+
+  fn diff_node(
+    &mut self,
+    old: &'a Node<'_>,
+    new: &'a Node<'_>,
+    old_index: usize,
+    new_index: usize,
+  ) {
+    let spans = (old.span(), new.span());
+
+    match (old, new) {
+      (Node::Element(old), Node::Element(new)) => {
+        self.diff_attributes(
+          &old.attributes,
+          &new.attributes,
+          old_index,
+          new_index,
+          spans,
+        );
+
+        self.old_path.push(old_index);
+        self.new_path.push(new_index);
+        self.diff_children(&old.children, &new.children, spans, (0, 0));
+        self.old_path.pop();
+        self.new_path.pop();
+      }
+      (Node::Mustache(old), Node::Mustache(new)) => {
+        self.old_path.push(old_index);
+        self.new_path.push(new_index);
+        self.diff_children(&old.children, &new.children, spans, (0, 0));
+        self.diff_children(
+          &old.inverse,
+          &new.inverse,
+          spans,
+          (old.children.len(), new.children.len()),
+        );
+        self.old_path.pop();
+        self.new_path.pop();
+      }
+      (Node::Script(old), Node::Script(new)) => {
+        self.diff_attributes(
+          &old.attributes,
+          &new.attributes,
+          old_index,
+          new_index,
+          spans,
+        );
+
+        if old.program.source_text != new.program.source_text {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      (Node::Text(old), Node::Text(new)) => {
+        if old.value != new.value {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      (Node::Comment(old), Node::Comment(new)) => {
+        if old.value != new.value || old.bogus != new.bogus {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      (Node::TemplateTag(old), Node::TemplateTag(new)) => {
+        if (old.open, old.value, old.close) != (new.open, new.value, new.close) {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      (Node::Directive(old), Node::Directive(new)) => {
+        if old.raw != new.raw {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      (Node::RawUnknown(old), Node::RawUnknown(new)) => {
+        if old.raw != new.raw {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      (Node::Doctype(old), Node::Doctype(new)) => {
+        let same = old.attributes.len() == new.attributes.len()
+          && old
+            .attributes
+            .iter()
+            .zip(new.attributes.iter())
+            .all(|(a, b)| a.key.value.eq_ignore_ascii_case(b.key.value));
+
+        if !same {
+          self.push(EditKind::Replace, old_index, new_index, spans);
+        }
+      }
+      // unreachable, `lcs` only matches nodes of the same kind
+      _ => self.push(EditKind::Replace, old_index, new_index, spans),
+    }
+  }
+
+  fn diff_attributes(
+    &mut self,
+    old: &'a [Attribute<'_>],
+    new: &'a [Attribute<'_>],
+    old_index: usize,
+    new_index: usize,
+    (old_element, new_element): (Span, Span),
+  ) {
+    for attribute in new {
+      let previous = old
+        .iter()
+        .find(|a| a.key.value.eq_ignore_ascii_case(attribute.key.value));
+      let value = attribute.value.as_ref().map(|v| v.value);
+
+      let changed = previous.is_none_or(|p| p.value.as_ref().map(|v| v.value) != value);
+      if changed {
+        self.push(
+          EditKind::SetAttribute {
+            name: attribute.key.value,
+            value,
+          },
+          old_index,
+          new_index,
+          (previous.map_or(old_element, |p| p.span), attribute.span),
+        );
+      }
+    }
+
+    for attribute in old {
+      if !new
+        .iter()
+        .any(|a| a.key.value.eq_ignore_ascii_case(attribute.key.value))
+      {
+        self.push(
+          EditKind::RemoveAttribute {
+            name: attribute.key.value,
+          },
+          old_index,
+          new_index,
+          (attribute.span, new_element),
+        );
+      }
+    }
+  }
+}
+
+/// Get the span where a node would be inserted at `index` among `siblings`.
+fn insertion_point(siblings: &[Node], index: usize, parent: Span) -> Span {
+  siblings.get(index).map_or_else(
+    || {
+      index
+        .checked_sub(1)
+        .and_then(|i| siblings.get(i))
+        .map_or(parent, |previous| Span::empty(previous.span().end))
+    },
+    |next| Span::empty(next.span().start),
+  )
+}
+
+/// Whether two nodes may be matched to each other and diffed in place.
+fn same_identity(old: &Node, new: &Node) -> bool {
+  match (old, new) {
+    (Node::Element(old), Node::Element(new)) => {
+      old.tag_name.eq_ignore_ascii_case(new.tag_name) && id(&old.attributes) == id(&new.attributes)
+    }
+    (Node::Script(old), Node::Script(new)) => id(&old.attributes) == id(&new.attributes),
+    (Node::Text(old), Node::Text(new)) => old.value == new.value,
+    (Node::Directive(old), Node::Directive(new)) => old.raw == new.raw,
+    (Node::TemplateTag(old), Node::TemplateTag(new)) => {
+      (old.open, old.value, old.close) == (new.open, new.value, new.close)
+    }
+    (Node::Mustache(old), Node::Mustache(new)) => {
+      old.kind == new.kind && old.expression == new.expression
+    }
+    (Node::Comment(_), Node::Comment(_))
+    | (Node::Doctype(_), Node::Doctype(_))
+    | (Node::RawUnknown(_), Node::RawUnknown(_)) => true,
+    _ => false,
+  }
+}
+
+fn id<'a>(attributes: &'a [Attribute<'_>]) -> Option<&'a str> {
+  attributes
+    .iter()
+    .find(|a| a.key.value.eq_ignore_ascii_case("id"))
+    .and_then(|a| a.value.as_ref())
+    .map(|v| v.value)
+}
+
+/// Longest common subsequence of two sibling lists, as pairs of matched indices.
+fn lcs(old: &[Node], new: &[Node]) -> Vec<(usize, usize)> {
+  let (n, m) = (old.len(), new.len());
+  // lengths[i][j] = LCS length of old[i..] and new[j..]
+  let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+  let at = |i: usize, j: usize| i * (m + 1) + j;
+
+  for i in (0..n).rev() {
+    for j in (0..m).rev() {
+      lengths[at(i, j)] = if same_identity(&old[i], &new[j]) {
+        lengths[at(i + 1, j + 1)] + 1
+      } else {
+        lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+      };
+    }
+  }
+
+  let mut pairs = Vec::new();
+  let (mut i, mut j) = (0, 0);
+  while i < n && j < m {
+    if same_identity(&old[i], &new[j]) {
+      pairs.push((i, j));
+      i += 1;
+      j += 1;
+    } else if lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+      i += 1;
+    } else {
+      j += 1;
+    }
+  }
+
+  pairs
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+  use oxc_allocator::Allocator;
+  use umc_html_parser::{
+    CreateHtml,
+    option::{HtmlParserOption, TemplateDelimiters},
+  };
+  use umc_parser::Parser;
+
+  use crate::{EditKind, diff};
+
+  fn test(old: &str, new: &str) -> String {
+    let allocator = Allocator::default();
+    let old_parser = Parser::html(&allocator, old);
+    let new_parser = Parser::html(&allocator, new);
+    let old = old_parser.parse().program;
+    let new = new_parser.parse().program;
+
+    format!("Edits: {:#?}", diff(&old, &new))
+  }
+
+  #[test]
+  fn identical() {
+    const HTML: &str = r#"<div class="a"><p>Hello</p><!-- c --></div>"#;
+
+    assert_eq!(test(HTML, HTML), "Edits: []");
+  }
+
+  #[test]
+  fn identical_directives() {
+    const HTML: &str = "<?xml version=\"1.0\"?>\n<?xml-stylesheet href=\"a.xsl\"?><feed></feed>";
+
+    assert_eq!(test(HTML, HTML), "Edits: []");
+  }
+
+  #[test]
+  fn identical_handlebars() {
+    const HTML: &str = "<ul>{{#each items}}<li>{{name}}</li>{{else}}<li>None</li>{{/each}}</ul>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder().handlebars(true).build();
+    let old = Parser::html(&allocator, HTML)
+      .with_options(options.clone())
+      .parse()
+      .program;
+    let new = Parser::html(&allocator, HTML)
+      .with_options(options.clone())
+      .parse()
+      .program;
+    assert!(diff(&old, &new).is_empty());
+
+    // the content after `{{else}}` follows the content before it
+    let changed = HTML.replace("None", "Empty");
+    let new = Parser::html(&allocator, &changed)
+      .with_options(options)
+      .parse()
+      .program;
+    let edits = diff(&old, &new);
+    assert_eq!(edits.len(), 1);
+    assert_eq!(edits[0].kind, EditKind::Replace);
+    assert_eq!(edits[0].new_path, [0, 0, 1, 0]);
+  }
+
+  #[test]
+  fn identical_template_tags() {
+    const HTML: &str = "{% if a %}<p>{{ name }}</p>{% endif %}";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .template_delimiters(vec![
+        TemplateDelimiters::STATEMENT,
+        TemplateDelimiters::EXPRESSION,
+      ])
+      .build();
+    let old = Parser::html(&allocator, HTML)
+      .with_options(options.clone())
+      .parse()
+      .program;
+    let new = Parser::html(&allocator, HTML)
+      .with_options(options)
+      .parse()
+      .program;
+    assert!(diff(&old, &new).is_empty());
+  }
+
+  #[test]
+  fn attributes() {
+    assert_snapshot!(test(
+      r#"<div class="a" hidden data-x="1"></div>"#,
+      r#"<div class="b" data-x="1" title></div>"#
+    ));
+  }
+
+  #[test]
+  fn insert_and_remove() {
+    assert_snapshot!(test(
+      r#"<ul><li id="a"></li><li id="b">b</li></ul>"#,
+      r#"<ul><li id="b">b</li><li id="c"></li></ul>"#
+    ));
+  }
+
+  #[test]
+  fn replace() {
+    assert_snapshot!(test(
+      "<div><p>a</p>text</div>",
+      "<div><span>a</span>other</div>"
+    ));
+  }
+
+  #[test]
+  fn keyed_move() {
+    assert_snapshot!(test(
+      r#"<div id="a"></div><div id="b"></div>"#,
+      r#"<div id="b"></div><div id="a"></div>"#
+    ));
+  }
+}
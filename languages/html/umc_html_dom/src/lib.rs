@@ -0,0 +1,216 @@
+//! DOM-like read API over the HTML AST.
+//!
+//! [`Document`] indexes a parsed [`Program`] once, linking every node to its parent and
+//! siblings, so the tree can be walked and queried with familiar methods instead of
+//! implementing the traversal traits:
+//!
+//! - [`Document::query_selector`], [`Document::query_selector_all`] and the same methods on
+//!   [`NodeRef`], with the selectors of `umc_html_selector`
+//! - [`Document::get_element_by_id`] and [`Document::get_elements_by_tag_name`]
+//! - [`NodeRef::parent`], [`NodeRef::children`], [`NodeRef::next_sibling`], ...
+//! - [`NodeRef::outer_html`], [`NodeRef::inner_html`] and [`NodeRef::text_content`]
+//!
+//! `<script>` nodes are elements like any other. As the AST keeps the source locations,
+//! `outer_html` and `inner_html` return the source text instead of serializing the nodes.
+//!
+//! ## Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_html_dom::Document;
+//! use umc_html_parser::CreateHtml;
+//! use umc_parser::Parser;
+//!
+//! let allocator = Allocator::default();
+//! let source = r#"<ul id="list"><li>One</li><li class="active">Two</li></ul>"#;
+//! let parser = Parser::html(&allocator, source);
+//! let program = parser.parse().program;
+//!
+//! let document = Document::new(&program, source);
+//! let active = document.query_selector("#list > .active").unwrap().unwrap();
+//! assert_eq!(active.text_content(), "Two");
+//! assert_eq!(active.previous_sibling().unwrap().outer_html(), "<li>One</li>");
+//! assert_eq!(active.parent(), document.get_element_by_id("list"));
+//! ```
+
+mod node;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Node, Program};
+use umc_html_selector::SelectorList;
+
+pub use crate::node::{Children, NodeRef};
+
+/// Identifier of a node in a [`Document`].
+///
+/// Identifiers follow document order: a node comes after its ancestors and before its
+/// following siblings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(u32);
+
+impl NodeId {
+  /// The index of the node in document order.
+  pub const fn index(self) -> usize {
+    self.0 as usize
+  }
+}
+
+/// An indexed document, see the [crate documentation](crate).
+pub struct Document<'d, 'a> {
+  source_text: &'a str,
+  nodes: Vec<NodeData<'d, 'a>>,
+  /// Top level nodes
+  first_child: Option<NodeId>,
+  last_child: Option<NodeId>,
+}
+
+struct NodeData<'d, 'a> {
+  node: &'d Node<'a>,
+  parent: Option<NodeId>,
+  previous_sibling: Option<NodeId>,
+  next_sibling: Option<NodeId>,
+  first_child: Option<NodeId>,
+  last_child: Option<NodeId>,
+  /// The id following the last descendant
+  subtree_end: NodeId,
+}
+
+impl<'d, 'a> Document<'d, 'a> {
+  /// Index `program`, parsed from `source_text`.
+  pub fn new(program: &'d Program<'a>, source_text: &'a str) -> Self {
+    let mut document = Self {
+      source_text,
+      nodes: Vec::new(),
+      first_child: None,
+      last_child: None,
+    };
+    let (first_child, last_child) = document.add_children(program, None);
+    document.first_child = first_child;
+    document.last_child = last_child;
+    document
+  }
+
+  /// The source text the document was parsed from.
+  pub const fn source_text(&self) -> &'a str {
+    self.source_text
+  }
+
+  /// Number of nodes in the document.
+  pub const fn len(&self) -> usize {
+    self.nodes.len()
+  }
+
+  /// Whether the document has no nodes.
+  pub const fn is_empty(&self) -> bool {
+    self.nodes.is_empty()
+  }
+
+  /// Get a node by id.
+  ///
+  /// # Panics
+  ///
+  /// Panics if the id belongs to another document.
+  pub fn get(&self, id: NodeId) -> NodeRef<'_, 'd, 'a> {
+    assert!(id.index() < self.nodes.len(), "{id:?} is out of range");
+    NodeRef::new(self, id)
+  }
+
+  /// Iterate the top level nodes.
+  pub const fn children(&self) -> Children<'_, 'd, 'a> {
+    Children::new(self, self.first_child, self.last_child)
+  }
+
+  /// Iterate every node in document order.
+  pub fn nodes(&self) -> impl DoubleEndedIterator<Item = NodeRef<'_, 'd, 'a>> {
+    (0..self.nodes.len()).map(|index| NodeRef::new(self, NodeId(index as u32)))
+  }
+
+  /// Iterate every element in document order.
+  pub fn elements(&self) -> impl Iterator<Item = NodeRef<'_, 'd, 'a>> {
+    self.nodes().filter(NodeRef::is_element)
+  }
+
+  /// The first element matching `selectors`, in document order.
+  pub fn query_selector(
+    &self,
+    selectors: &str,
+  ) -> Result<Option<NodeRef<'_, 'd, 'a>>, OxcDiagnostic> {
+    Ok(self.query_selector_all(selectors)?.into_iter().next())
+  }
+
+  /// Every element matching `selectors`, in document order.
+  pub fn query_selector_all(
+    &self,
+    selectors: &str,
+  ) -> Result<Vec<NodeRef<'_, 'd, 'a>>, OxcDiagnostic> {
+    let selectors = SelectorList::parse(selectors)?;
+    Ok(select(&selectors, self.elements()))
+  }
+
+  /// The first element whose `id` attribute is `id`.
+  pub fn get_element_by_id(&self, id: &str) -> Option<NodeRef<'_, 'd, 'a>> {
+    self
+      .elements()
+      .find(|element| element.attribute("id") == Some(id))
+  }
+
+  /// Every element with the tag name `tag_name`, compared ignoring ASCII case.
+  pub fn get_elements_by_tag_name(&self, tag_name: &str) -> Vec<NodeRef<'_, 'd, 'a>> {
+    self
+      .elements()
+      .filter(|element| {
+        element
+          .tag_name()
+          .is_some_and(|name| name.eq_ignore_ascii_case(tag_name))
+      })
+      .collect()
+  }
+
+  /// Add `children` and their descendants, returning the first and last child.
+  fn add_children(
+    &mut self,
+    children: &'d [Node<'a>],
+    parent: Option<NodeId>,
+  ) -> (Option<NodeId>, Option<NodeId>) {
+    let mut first_child = None;
+    let mut previous_sibling: Option<NodeId> = None;
+
+    for node in children {
+      let id = NodeId(self.nodes.len() as u32);
+      self.nodes.push(NodeData {
+        node,
+        parent,
+        previous_sibling,
+        next_sibling: None,
+        first_child: None,
+        last_child: None,
+        subtree_end: id,
+      });
+      if let Some(previous_sibling) = previous_sibling {
+        self.nodes[previous_sibling.index()].next_sibling = Some(id);
+      }
+      first_child.get_or_insert(id);
+      previous_sibling = Some(id);
+
+      if let Node::Element(element) = node {
+        let (first, last) = self.add_children(&element.children, Some(id));
+        let data = &mut self.nodes[id.index()];
+        data.first_child = first;
+        data.last_child = last;
+      }
+      self.nodes[id.index()].subtree_end = NodeId(self.nodes.len() as u32);
+    }
+
+    (first_child, previous_sibling)
+  }
+}
+
+/// Keep the elements of `candidates` matching `selectors`.
+fn select<'s, 'd, 'a>(
+  selectors: &SelectorList,
+  candidates: impl Iterator<Item = NodeRef<'s, 'd, 'a>>,
+) -> Vec<NodeRef<'s, 'd, 'a>> {
+  candidates
+    .filter(|element| selectors.matches(element))
+    .collect()
+}
@@ -0,0 +1,409 @@
+use std::fmt::{self, Debug};
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Attribute, Node};
+use umc_html_selector::{SelectorElement, SelectorList};
+use umc_span::Span;
+
+use crate::{Document, NodeId, select};
+
+/// A node of a [`Document`].
+///
+/// A cheap handle, which can be copied and compared.
+#[derive(Clone, Copy)]
+pub struct NodeRef<'s, 'd, 'a> {
+  document: &'s Document<'d, 'a>,
+  id: NodeId,
+}
+
+impl<'s, 'd, 'a> NodeRef<'s, 'd, 'a> {
+  pub(crate) const fn new(document: &'s Document<'d, 'a>, id: NodeId) -> Self {
+    Self { document, id }
+  }
+
+  /// The id of the node.
+  pub const fn id(self) -> NodeId {
+    self.id
+  }
+
+  /// The AST node.
+  pub fn node(self) -> &'d Node<'a> {
+    self.data().node
+  }
+
+  /// Whether the node is an element, including `<script>`.
+  pub fn is_element(&self) -> bool {
+    matches!(self.data().node, Node::Element(_) | Node::Script(_))
+  }
+
+  /// The tag name of an element, as written in the source.
+  pub fn tag_name(self) -> Option<&'a str> {
+    match self.node() {
+      Node::Element(element) => Some(element.tag_name),
+      Node::Script(script) => Some(script.tag_name),
+      _ => None,
+    }
+  }
+
+  /// The attributes of an element or a doctype, empty for other nodes.
+  pub fn attributes(self) -> &'d [Attribute<'a>] {
+    match self.node() {
+      Node::Element(element) => &element.attributes,
+      Node::Script(script) => &script.attributes,
+      Node::Doctype(doctype) => &doctype.attributes,
+      _ => &[],
+    }
+  }
+
+  /// Get the value of an attribute, `Some("")` for an attribute without value.
+  ///
+  /// Attribute names are compared ignoring ASCII case.
+  pub fn attribute(self, name: &str) -> Option<&'a str> {
+    self
+      .attributes()
+      .iter()
+      .find(|attribute| attribute.key.value.eq_ignore_ascii_case(name))
+      .map(|attribute| attribute.value.as_ref().map_or("", |value| value.value))
+  }
+
+  /// Whether the element has an attribute.
+  pub fn has_attribute(self, name: &str) -> bool {
+    self.attribute(name).is_some()
+  }
+
+  /// The parent element, `None` for top level nodes.
+  pub fn parent(self) -> Option<Self> {
+    self.data().parent.map(|id| self.at(id))
+  }
+
+  pub fn first_child(self) -> Option<Self> {
+    self.data().first_child.map(|id| self.at(id))
+  }
+
+  pub fn last_child(self) -> Option<Self> {
+    self.data().last_child.map(|id| self.at(id))
+  }
+
+  pub fn previous_sibling(self) -> Option<Self> {
+    self.data().previous_sibling.map(|id| self.at(id))
+  }
+
+  pub fn next_sibling(self) -> Option<Self> {
+    self.data().next_sibling.map(|id| self.at(id))
+  }
+
+  /// The closest previous sibling which is an element.
+  pub fn previous_element_sibling(self) -> Option<Self> {
+    std::iter::successors(self.previous_sibling(), |node| {
+      Self::previous_sibling(*node)
+    })
+    .find(Self::is_element)
+  }
+
+  /// The closest next sibling which is an element.
+  pub fn next_element_sibling(self) -> Option<Self> {
+    std::iter::successors(self.next_sibling(), |node| Self::next_sibling(*node))
+      .find(Self::is_element)
+  }
+
+  /// Iterate the child nodes.
+  pub fn children(self) -> Children<'s, 'd, 'a> {
+    let data = self.data();
+    Children::new(self.document, data.first_child, data.last_child)
+  }
+
+  /// Iterate the child elements.
+  pub fn element_children(self) -> impl Iterator<Item = Self> {
+    self.children().filter(Self::is_element)
+  }
+
+  /// Iterate the ancestors, starting with the parent.
+  pub fn ancestors(self) -> impl Iterator<Item = Self> {
+    std::iter::successors(self.parent(), |node| Self::parent(*node))
+  }
+
+  /// Iterate the descendants in document order, not including the node itself.
+  pub fn descendants(self) -> impl DoubleEndedIterator<Item = Self> {
+    let document = self.document;
+    (self.id.0 + 1..self.data().subtree_end.0).map(move |id| Self::new(document, NodeId(id)))
+  }
+
+  /// The first descendant element matching `selectors`, in document order.
+  pub fn query_selector(self, selectors: &str) -> Result<Option<Self>, OxcDiagnostic> {
+    Ok(self.query_selector_all(selectors)?.into_iter().next())
+  }
+
+  /// Every descendant element matching `selectors`, in document order.
+  ///
+  /// Like in the DOM, the whole selector is matched against the document, so ancestors of
+  /// this node can match its first compound selectors.
+  pub fn query_selector_all(self, selectors: &str) -> Result<Vec<Self>, OxcDiagnostic> {
+    let selectors = SelectorList::parse(selectors)?;
+    let elements = self.descendants().filter(Self::is_element);
+    Ok(select(&selectors, elements))
+  }
+
+  /// The source location of the node.
+  pub fn span(self) -> Span {
+    match self.node() {
+      Node::Doctype(doctype) => doctype.span,
+      Node::Element(element) => element.span,
+      Node::Text(text) => text.span,
+      Node::Comment(comment) => comment.span,
+      Node::Script(script) => script.span,
+      Node::RawUnknown(raw) => raw.span,
+      Node::Mustache(mustache) => mustache.span,
+      Node::TemplateTag(tag) => tag.span,
+      Node::Directive(directive) => directive.span,
+    }
+  }
+
+  /// The source text of the node, including its tags.
+  pub fn outer_html(self) -> &'a str {
+    self.node().source_text(self.document.source_text)
+  }
+
+  /// The source text of the content of an element, empty for other nodes.
+  pub fn inner_html(self) -> &'a str {
+    match self.node() {
+      Node::Element(element) => return element.inner_source(self.document.source_text),
+      Node::Script(script) => return script.program.source_text,
+      _ => {}
+    }
+    match (self.first_child(), self.last_child()) {
+      (Some(first), Some(last)) => {
+        Span::new(first.span().start, last.span().end).source_text(self.document.source_text)
+      }
+      _ => "",
+    }
+  }
+
+  /// The text of the node and its descendants, without comments.
+  ///
+  /// Character references are not decoded.
+  pub fn text_content(self) -> String {
+    let mut text = String::new();
+    for node in std::iter::once(self).chain(self.descendants()) {
+      match node.node() {
+        Node::Text(value) => text.push_str(value.value),
+        Node::Script(script) => text.push_str(script.program.source_text),
+        Node::Comment(comment) if node == self => text.push_str(comment.value),
+        _ => {}
+      }
+    }
+    text
+  }
+
+  fn data(&self) -> &'s crate::NodeData<'d, 'a> {
+    &self.document.nodes[self.id.index()]
+  }
+
+  const fn at(self, id: NodeId) -> Self {
+    Self::new(self.document, id)
+  }
+}
+
+impl PartialEq for NodeRef<'_, '_, '_> {
+  fn eq(&self, other: &Self) -> bool {
+    self.id == other.id && std::ptr::eq(self.document, other.document)
+  }
+}
+
+impl Eq for NodeRef<'_, '_, '_> {}
+
+impl Debug for NodeRef<'_, '_, '_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_struct("NodeRef")
+      .field("id", &self.id)
+      .field("node", self.node())
+      .finish()
+  }
+}
+
+impl SelectorElement for NodeRef<'_, '_, '_> {
+  fn tag_name(&self) -> &str {
+    Self::tag_name(*self).unwrap_or_default()
+  }
+
+  fn attribute(&self, name: &str) -> Option<&str> {
+    Self::attribute(*self, name)
+  }
+
+  fn parent(&self) -> Option<Self> {
+    Self::parent(*self)
+  }
+
+  fn previous_sibling(&self) -> Option<Self> {
+    self.previous_element_sibling()
+  }
+}
+
+/// Iterator of the children of a node, see [`NodeRef::children`].
+pub struct Children<'s, 'd, 'a> {
+  document: &'s Document<'d, 'a>,
+  front: Option<NodeId>,
+  back: Option<NodeId>,
+}
+
+impl<'s, 'd, 'a> Children<'s, 'd, 'a> {
+  pub(crate) const fn new(
+    document: &'s Document<'d, 'a>,
+    front: Option<NodeId>,
+    back: Option<NodeId>,
+  ) -> Self {
+    Self {
+      document,
+      front,
+      back,
+    }
+  }
+}
+
+impl<'s, 'd, 'a> Iterator for Children<'s, 'd, 'a> {
+  type Item = NodeRef<'s, 'd, 'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let id = self.front?;
+    if self.front == self.back {
+      self.front = None;
+      self.back = None;
+    } else {
+      self.front = self.document.nodes[id.index()].next_sibling;
+    }
+    Some(NodeRef::new(self.document, id))
+  }
+}
+
+impl DoubleEndedIterator for Children<'_, '_, '_> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let id = self.back?;
+    if self.front == self.back {
+      self.front = None;
+      self.back = None;
+    } else {
+      self.back = self.document.nodes[id.index()].previous_sibling;
+    }
+    Some(NodeRef::new(self.document, id))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use crate::{Document, NodeRef};
+
+  const HTML: &str = r#"<!DOCTYPE html>
+<main id="app">
+  <h1 class="title">Hello <b>DOM</b></h1>
+  <!-- list -->
+  <ul>
+    <li id="first">One</li>
+    <li class="item active">Two</li>
+    <li class="item">Three</li>
+  </ul>
+  <script>let a = 1;</script>
+</main>
+"#;
+
+  #[test]
+  fn navigation() {
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, HTML);
+    let program = parser.parse().program;
+    let document = Document::new(&program, HTML);
+
+    let main = document.get_element_by_id("app").unwrap();
+    assert_eq!(main.tag_name(), Some("main"));
+    assert_eq!(main.parent(), None);
+    assert_eq!(
+      main
+        .element_children()
+        .filter_map(NodeRef::tag_name)
+        .collect::<Vec<_>>(),
+      ["h1", "ul", "script"]
+    );
+    assert_eq!(
+      main
+        .children()
+        .rev()
+        .filter_map(NodeRef::tag_name)
+        .collect::<Vec<_>>(),
+      ["script", "ul", "h1"]
+    );
+
+    let first = document.get_element_by_id("first").unwrap();
+    let second = first.next_element_sibling().unwrap();
+    assert_eq!(second.attribute("CLASS"), Some("item active"));
+    assert_eq!(second.previous_element_sibling(), Some(first));
+    assert_eq!(first.ancestors().count(), 2);
+    assert_eq!(document.get_elements_by_tag_name("LI").len(), 3);
+
+    let h1 = document.get_elements_by_tag_name("h1")[0];
+    assert_eq!(
+      h1.outer_html(),
+      r#"<h1 class="title">Hello <b>DOM</b></h1>"#
+    );
+    assert_eq!(h1.inner_html(), "Hello <b>DOM</b>");
+    assert_eq!(h1.text_content(), "Hello DOM");
+    assert_eq!(h1.descendants().count(), 3);
+
+    let script = document.get_elements_by_tag_name("script")[0];
+    assert!(script.is_element());
+    assert_eq!(script.inner_html(), "let a = 1;");
+  }
+
+  #[test]
+  fn inner_html() {
+    for (html, inner) in [
+      ("<P class=\"a\">Hi <b>there</b></p >", "Hi <b>there</b>"),
+      ("<a title=\"<b>\"> x </A>", " x "),
+      ("<div><div>a</div>", "<div>a</div>"),
+      ("<ul><li>One<li>Two</ul>", "<li>One<li>Two"),
+      ("<p></p>", ""),
+      ("<br>", ""),
+      ("<div/>", ""),
+    ] {
+      let allocator = Allocator::default();
+      let parser = Parser::html(&allocator, html);
+      let program = parser.parse().program;
+      let document = Document::new(&program, html);
+      let element = document.children().next().unwrap();
+      assert_eq!(element.inner_html(), inner, "{html}");
+    }
+  }
+
+  #[test]
+  fn selectors() {
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, HTML);
+    let program = parser.parse().program;
+    let document = Document::new(&program, HTML);
+    let ids = |selectors: &str| {
+      document
+        .query_selector_all(selectors)
+        .unwrap()
+        .iter()
+        .map(|node| node.id().index())
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids("li"), ids("ul > *"));
+    assert_eq!(ids("#first ~ .item"), ids(".item"));
+    assert_eq!(ids("#first + li"), ids(".active"));
+    assert_eq!(ids("h1 + ul > li:not(.item)"), ids("#first"));
+    assert_eq!(ids("main script"), ids("script"));
+    assert!(document.query_selector("p").unwrap().is_none());
+    assert!(document.query_selector("li[").is_err());
+
+    let ul = document.query_selector("ul").unwrap().unwrap();
+    assert_eq!(ul.query_selector_all("main li").unwrap().len(), 3);
+    assert_eq!(ul.query_selector("ul").unwrap(), None);
+    assert_eq!(
+      ul.query_selector(".item").unwrap().unwrap().text_content(),
+      "Two"
+    );
+  }
+}
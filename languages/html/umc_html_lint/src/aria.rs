@@ -0,0 +1,490 @@
+//! A model of WAI-ARIA roles and attributes, used by the accessibility rules.
+//!
+//! Follows [WAI-ARIA 1.2](https://www.w3.org/TR/wai-aria-1.2/) and the implicit roles of
+//! [HTML-AAM](https://www.w3.org/TR/html-aam-1.0/).
+
+use umc_html_ast::Element;
+
+use crate::rules::attribute;
+
+/// Roles which may be used in a `role` attribute.
+const CONCRETE_ROLES: &[&str] = &[
+  "alert",
+  "alertdialog",
+  "application",
+  "article",
+  "banner",
+  "blockquote",
+  "button",
+  "caption",
+  "cell",
+  "checkbox",
+  "code",
+  "columnheader",
+  "combobox",
+  "comment",
+  "complementary",
+  "contentinfo",
+  "definition",
+  "deletion",
+  "dialog",
+  "directory",
+  "document",
+  "emphasis",
+  "feed",
+  "figure",
+  "form",
+  "generic",
+  "grid",
+  "gridcell",
+  "group",
+  "heading",
+  "img",
+  "insertion",
+  "link",
+  "list",
+  "listbox",
+  "listitem",
+  "log",
+  "main",
+  "mark",
+  "marquee",
+  "math",
+  "menu",
+  "menubar",
+  "menuitem",
+  "menuitemcheckbox",
+  "menuitemradio",
+  "meter",
+  "navigation",
+  "none",
+  "note",
+  "option",
+  "paragraph",
+  "presentation",
+  "progressbar",
+  "radio",
+  "radiogroup",
+  "region",
+  "row",
+  "rowgroup",
+  "rowheader",
+  "scrollbar",
+  "search",
+  "searchbox",
+  "separator",
+  "slider",
+  "spinbutton",
+  "status",
+  "strong",
+  "subscript",
+  "superscript",
+  "switch",
+  "tab",
+  "table",
+  "tablist",
+  "tabpanel",
+  "term",
+  "textbox",
+  "time",
+  "timer",
+  "toolbar",
+  "tooltip",
+  "tree",
+  "treegrid",
+  "treeitem",
+];
+
+/// Roles only used to build the ontology, which must not be used by authors.
+const ABSTRACT_ROLES: &[&str] = &[
+  "command",
+  "composite",
+  "input",
+  "landmark",
+  "range",
+  "roletype",
+  "section",
+  "sectionhead",
+  "select",
+  "structure",
+  "widget",
+  "window",
+];
+
+/// States and properties allowed on every element.
+const GLOBAL_ATTRIBUTES: &[&str] = &[
+  "aria-atomic",
+  "aria-braillelabel",
+  "aria-brailleroledescription",
+  "aria-busy",
+  "aria-controls",
+  "aria-current",
+  "aria-describedby",
+  "aria-description",
+  "aria-details",
+  "aria-disabled",
+  "aria-dropeffect",
+  "aria-errormessage",
+  "aria-flowto",
+  "aria-grabbed",
+  "aria-haspopup",
+  "aria-hidden",
+  "aria-invalid",
+  "aria-keyshortcuts",
+  "aria-label",
+  "aria-labelledby",
+  "aria-live",
+  "aria-owns",
+  "aria-relevant",
+  "aria-roledescription",
+];
+
+const RANGE_ROLES: &[&str] = &[
+  "meter",
+  "progressbar",
+  "scrollbar",
+  "separator",
+  "slider",
+  "spinbutton",
+];
+
+/// States and properties allowed on specific roles only, with the roles supporting them.
+const ROLE_ATTRIBUTES: &[(&str, &[&str])] = &[
+  (
+    "aria-activedescendant",
+    &[
+      "application",
+      "combobox",
+      "grid",
+      "group",
+      "listbox",
+      "menu",
+      "menubar",
+      "radiogroup",
+      "row",
+      "searchbox",
+      "spinbutton",
+      "tablist",
+      "textbox",
+      "toolbar",
+      "tree",
+      "treegrid",
+    ],
+  ),
+  ("aria-autocomplete", &["combobox", "searchbox", "textbox"]),
+  (
+    "aria-checked",
+    &[
+      "checkbox",
+      "menuitemcheckbox",
+      "menuitemradio",
+      "option",
+      "radio",
+      "switch",
+      "treeitem",
+    ],
+  ),
+  ("aria-colcount", &["grid", "table", "treegrid"]),
+  (
+    "aria-colindex",
+    &["cell", "columnheader", "gridcell", "row", "rowheader"],
+  ),
+  (
+    "aria-colspan",
+    &["cell", "columnheader", "gridcell", "rowheader"],
+  ),
+  (
+    "aria-expanded",
+    &[
+      "application",
+      "button",
+      "checkbox",
+      "columnheader",
+      "combobox",
+      "gridcell",
+      "link",
+      "listbox",
+      "menuitem",
+      "menuitemcheckbox",
+      "menuitemradio",
+      "row",
+      "rowheader",
+      "switch",
+      "tab",
+      "treeitem",
+    ],
+  ),
+  (
+    "aria-level",
+    &["comment", "heading", "listitem", "row", "treeitem"],
+  ),
+  ("aria-modal", &["alertdialog", "dialog"]),
+  ("aria-multiline", &["searchbox", "textbox"]),
+  (
+    "aria-multiselectable",
+    &["grid", "listbox", "tablist", "tree", "treegrid"],
+  ),
+  (
+    "aria-orientation",
+    &[
+      "listbox",
+      "menu",
+      "menubar",
+      "radiogroup",
+      "scrollbar",
+      "separator",
+      "slider",
+      "tablist",
+      "toolbar",
+      "tree",
+      "treegrid",
+    ],
+  ),
+  ("aria-placeholder", &["searchbox", "textbox"]),
+  (
+    "aria-posinset",
+    &[
+      "article",
+      "comment",
+      "listitem",
+      "menuitem",
+      "menuitemcheckbox",
+      "menuitemradio",
+      "option",
+      "radio",
+      "row",
+      "tab",
+      "treeitem",
+    ],
+  ),
+  ("aria-pressed", &["button"]),
+  (
+    "aria-readonly",
+    &[
+      "checkbox",
+      "columnheader",
+      "combobox",
+      "grid",
+      "gridcell",
+      "listbox",
+      "menuitemcheckbox",
+      "menuitemradio",
+      "radiogroup",
+      "rowheader",
+      "searchbox",
+      "slider",
+      "spinbutton",
+      "switch",
+      "textbox",
+      "treegrid",
+    ],
+  ),
+  (
+    "aria-required",
+    &[
+      "checkbox",
+      "columnheader",
+      "combobox",
+      "gridcell",
+      "listbox",
+      "radiogroup",
+      "rowheader",
+      "searchbox",
+      "spinbutton",
+      "switch",
+      "textbox",
+      "tree",
+      "treegrid",
+    ],
+  ),
+  ("aria-rowcount", &["grid", "table", "treegrid"]),
+  (
+    "aria-rowindex",
+    &["cell", "columnheader", "gridcell", "row", "rowheader"],
+  ),
+  (
+    "aria-rowspan",
+    &["cell", "columnheader", "gridcell", "rowheader"],
+  ),
+  (
+    "aria-selected",
+    &[
+      "columnheader",
+      "gridcell",
+      "option",
+      "row",
+      "rowheader",
+      "tab",
+      "treeitem",
+    ],
+  ),
+  (
+    "aria-setsize",
+    &[
+      "article",
+      "comment",
+      "listitem",
+      "menuitem",
+      "menuitemcheckbox",
+      "menuitemradio",
+      "option",
+      "radio",
+      "row",
+      "tab",
+      "treeitem",
+    ],
+  ),
+  ("aria-sort", &["columnheader", "rowheader"]),
+  ("aria-valuemax", RANGE_ROLES),
+  ("aria-valuemin", RANGE_ROLES),
+  ("aria-valuenow", RANGE_ROLES),
+  ("aria-valuetext", RANGE_ROLES),
+];
+
+/// Roles of interactive widgets, which need an accessible name.
+pub const WIDGET_ROLES: &[&str] = &[
+  "button",
+  "checkbox",
+  "combobox",
+  "link",
+  "listbox",
+  "menuitem",
+  "menuitemcheckbox",
+  "menuitemradio",
+  "option",
+  "radio",
+  "searchbox",
+  "slider",
+  "spinbutton",
+  "switch",
+  "tab",
+  "textbox",
+  "treeitem",
+];
+
+/// Whether `role` is a concrete role which may be used in a `role` attribute.
+pub fn is_concrete_role(role: &str) -> bool {
+  CONCRETE_ROLES.contains(&role)
+}
+
+/// Whether `role` is an abstract role.
+pub fn is_abstract_role(role: &str) -> bool {
+  ABSTRACT_ROLES.contains(&role)
+}
+
+/// Whether `name` is a state or property defined by WAI-ARIA, e.g. `aria-label`.
+pub fn is_aria_attribute(name: &str) -> bool {
+  GLOBAL_ATTRIBUTES.contains(&name) || ROLE_ATTRIBUTES.iter().any(|(n, _)| *n == name)
+}
+
+/// Whether the state or property `name` may be used on an element with `role`.
+///
+/// Unknown attributes are never supported.
+pub fn role_supports(role: &str, name: &str) -> bool {
+  GLOBAL_ATTRIBUTES.contains(&name)
+    || ROLE_ATTRIBUTES
+      .iter()
+      .find(|(n, _)| *n == name)
+      .is_some_and(|(_, roles)| roles.contains(&role))
+}
+
+/// Get the role of an element: the first concrete role of its `role` attribute,
+/// or its implicit role.
+pub fn role(element: &Element) -> Option<&'static str> {
+  if let Some(value) = attribute(element, "role").and_then(|a| a.value.as_ref()) {
+    let explicit = value.value.split_ascii_whitespace().find_map(|token| {
+      CONCRETE_ROLES
+        .iter()
+        .find(|role| role.eq_ignore_ascii_case(token))
+    });
+    if let Some(role) = explicit {
+      return Some(role);
+    }
+  }
+  implicit_role(element)
+}
+
+/// Get the implicit role of an element, if it has a well-defined one.
+///
+/// Elements whose role depends on their context (e.g. `<header>`), and elements mapped
+/// to `generic` are reported as `None`.
+pub fn implicit_role(element: &Element) -> Option<&'static str> {
+  let tag_name = element.tag_name.to_ascii_lowercase();
+  let has = |name: &str| attribute(element, name).is_some();
+
+  let role = match tag_name.as_str() {
+    "a" | "area" if has("href") => "link",
+    "article" => "article",
+    "aside" => "complementary",
+    "button" => "button",
+    "datalist" => "listbox",
+    "details" | "fieldset" => "group",
+    "dialog" => "dialog",
+    "form" => "form",
+    "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+    "hr" => "separator",
+    "img" => {
+      let alt = attribute(element, "alt").and_then(|a| a.value.as_ref());
+      if alt.is_some_and(|alt| alt.value.is_empty()) {
+        "presentation"
+      } else {
+        "img"
+      }
+    }
+    "input" => return input_role(element),
+    "li" => "listitem",
+    "main" => "main",
+    "math" => "math",
+    "menu" | "ol" | "ul" => "list",
+    "meter" => "meter",
+    "nav" => "navigation",
+    "option" => "option",
+    "output" => "status",
+    "progress" => "progressbar",
+    "search" => "search",
+    "select" => {
+      if has("multiple") || has("size") {
+        "listbox"
+      } else {
+        "combobox"
+      }
+    }
+    "table" => "table",
+    "tbody" | "tfoot" | "thead" => "rowgroup",
+    "td" => "cell",
+    "textarea" => "textbox",
+    "th" => "columnheader",
+    "tr" => "row",
+    _ => return None,
+  };
+  Some(role)
+}
+
+fn input_role(element: &Element) -> Option<&'static str> {
+  let input_type = attribute(element, "type")
+    .and_then(|a| a.value.as_ref())
+    .map_or_else(|| "text".to_string(), |v| v.value.to_ascii_lowercase());
+  let has_list = attribute(element, "list").is_some();
+
+  let role = match input_type.as_str() {
+    "button" | "image" | "reset" | "submit" => "button",
+    "checkbox" => "checkbox",
+    "radio" => "radio",
+    "range" => "slider",
+    "number" => "spinbutton",
+    "search" if !has_list => "searchbox",
+    "email" | "tel" | "text" | "url" if !has_list => "textbox",
+    "email" | "search" | "tel" | "text" | "url" => "combobox",
+    _ => return None,
+  };
+  Some(role)
+}
+
+/// Whether an element is [labelable](https://html.spec.whatwg.org/multipage/forms.html#category-label).
+pub fn is_labelable(tag_name: &str, input_type: Option<&str>) -> bool {
+  match tag_name.to_ascii_lowercase().as_str() {
+    "button" | "meter" | "output" | "progress" | "select" | "textarea" => true,
+    "input" => !input_type.is_some_and(|t| t.eq_ignore_ascii_case("hidden")),
+    _ => false,
+  }
+}
@@ -0,0 +1,346 @@
+//! Rule based linter for HTML.
+//!
+//! Rules implement the [`Rule`] trait and are called while the [`Linter`] traverses the tree
+//! with [`TraverseHtml`]. Each rule is known by a kebab-case name in a [`RuleRegistry`], which
+//! is also used to configure its severity. Diagnostics are plain [`OxcDiagnostic`]s, with
+//! the rule name as error code, e.g. `html(no-duplicate-id)`.
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_html_lint::{Linter, RuleSeverity};
+//! use umc_html_parser::CreateHtml;
+//! use umc_parser::Parser;
+//!
+//! let allocator = Allocator::default();
+//! let parser = Parser::html(&allocator, r#"<img src="a.png"><img src="b.png" alt="">"#);
+//! let program = parser.parse().program;
+//!
+//! let linter = Linter::default().with_severity("img-alt", RuleSeverity::Error);
+//! assert_eq!(linter.lint(&program).len(), 1);
+//! ```
+
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use umc_html_ast::{Element, Program, Text};
+use umc_html_traverse::{TraverseHtml, traverse_program};
+use umc_traverse::TraverseOperate;
+
+pub mod aria;
+pub mod rules;
+
+/// Scope of the error code of every lint diagnostic.
+pub const ERROR_CODE_SCOPE: &str = "html";
+
+/// Configured severity of a rule.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSeverity {
+  /// The rule does not run
+  Off,
+  /// Diagnostics are reported as warnings
+  #[default]
+  Warn,
+  /// Diagnostics are reported as errors
+  Error,
+}
+
+/// A lint rule.
+///
+/// A new instance is created for every linted program, so rules may keep state across
+/// calls, e.g. the ids seen so far. Every method has an empty default implementation.
+#[expect(unused_variables)]
+pub trait Rule {
+  /// Called when entering an element, before its children.
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {}
+  /// Called when leaving an element, after its children.
+  fn exit_element(&mut self, element: &Element, ctx: &mut LintContext) {}
+  /// Called for every text node.
+  fn text(&mut self, text: &Text, ctx: &mut LintContext) {}
+  /// Called once the whole program was traversed.
+  fn finish(&mut self, ctx: &mut LintContext) {}
+}
+
+/// Reports diagnostics on behalf of a rule.
+pub struct LintContext<'l> {
+  name: &'static str,
+  severity: Severity,
+  diagnostics: &'l mut Vec<OxcDiagnostic>,
+}
+
+impl LintContext<'_> {
+  /// Name of the rule currently running.
+  pub const fn rule_name(&self) -> &'static str {
+    self.name
+  }
+
+  /// Report a diagnostic. Its severity and error code are set from the rule configuration.
+  pub fn report(&mut self, diagnostic: OxcDiagnostic) {
+    self.diagnostics.push(
+      diagnostic
+        .with_severity(self.severity)
+        .with_error_code(ERROR_CODE_SCOPE, self.name),
+    );
+  }
+}
+
+/// A rule known to a [`RuleRegistry`].
+#[derive(Debug, Clone, Copy)]
+pub struct RuleEntry {
+  /// Unique kebab-case name, e.g. `img-alt`
+  pub name: &'static str,
+  /// Severity used when the rule is not configured
+  pub default_severity: RuleSeverity,
+  /// Create a fresh instance of the rule
+  pub create: fn() -> Box<dyn Rule>,
+}
+
+/// The set of rules a [`Linter`] can run.
+#[derive(Debug, Clone)]
+pub struct RuleRegistry {
+  rules: Vec<RuleEntry>,
+}
+
+impl Default for RuleRegistry {
+  /// A registry with every built-in rule.
+  fn default() -> Self {
+    let mut registry = Self::empty();
+    rules::register_builtin(&mut registry);
+    registry
+  }
+}
+
+impl RuleRegistry {
+  /// A registry without any rule.
+  pub const fn empty() -> Self {
+    Self { rules: Vec::new() }
+  }
+
+  /// Register a rule, replacing any rule with the same name.
+  pub fn register(
+    &mut self,
+    name: &'static str,
+    default_severity: RuleSeverity,
+    create: fn() -> Box<dyn Rule>,
+  ) {
+    let entry = RuleEntry {
+      name,
+      default_severity,
+      create,
+    };
+
+    if let Some(existing) = self.rules.iter_mut().find(|rule| rule.name == name) {
+      *existing = entry;
+    } else {
+      self.rules.push(entry);
+    }
+  }
+
+  /// Get a rule by name.
+  pub fn get(&self, name: &str) -> Option<&RuleEntry> {
+    self.rules.iter().find(|rule| rule.name == name)
+  }
+
+  /// Iterate over the registered rules in registration order.
+  pub fn iter(&self) -> impl Iterator<Item = &RuleEntry> {
+    self.rules.iter()
+  }
+}
+
+/// Runs the rules of a [`RuleRegistry`] with their configured severity.
+#[derive(Debug, Clone, Default)]
+pub struct Linter {
+  registry: RuleRegistry,
+  /// Configured severities by rule name, overriding the defaults
+  severities: Vec<(&'static str, RuleSeverity)>,
+}
+
+impl Linter {
+  /// Create a linter running the rules of `registry`.
+  pub const fn new(registry: RuleRegistry) -> Self {
+    Self {
+      registry,
+      severities: Vec::new(),
+    }
+  }
+
+  /// Configure the severity of a rule. Unknown rule names are ignored.
+  #[must_use]
+  pub fn with_severity(mut self, name: &str, severity: RuleSeverity) -> Self {
+    if let Some(rule) = self.registry.get(name) {
+      let name = rule.name;
+      self
+        .severities
+        .retain(|(configured, _)| *configured != name);
+      self.severities.push((name, severity));
+    }
+    self
+  }
+
+  /// Get the effective severity of a rule.
+  pub fn severity(&self, name: &str) -> RuleSeverity {
+    self
+      .severities
+      .iter()
+      .find(|(configured, _)| *configured == name)
+      .map(|(_, severity)| *severity)
+      .or_else(|| self.registry.get(name).map(|rule| rule.default_severity))
+      .unwrap_or(RuleSeverity::Off)
+  }
+
+  /// Get the registry of this linter.
+  pub const fn registry(&self) -> &RuleRegistry {
+    &self.registry
+  }
+
+  /// Lint a program, returning the diagnostics of every enabled rule in document order.
+  pub fn lint(&self, program: &Program) -> Vec<OxcDiagnostic> {
+    let rules = self
+      .registry
+      .iter()
+      .filter_map(|rule| {
+        let severity = match self.severity(rule.name) {
+          RuleSeverity::Off => return None,
+          RuleSeverity::Warn => Severity::Warning,
+          RuleSeverity::Error => Severity::Error,
+        };
+        Some(ActiveRule {
+          name: rule.name,
+          severity,
+          rule: (rule.create)(),
+        })
+      })
+      .collect();
+
+    let mut runner = LintRunner {
+      rules,
+      diagnostics: Vec::new(),
+    };
+    traverse_program(program, &mut runner);
+    runner.diagnostics
+  }
+}
+
+struct ActiveRule {
+  name: &'static str,
+  severity: Severity,
+  rule: Box<dyn Rule>,
+}
+
+struct LintRunner {
+  rules: Vec<ActiveRule>,
+  diagnostics: Vec<OxcDiagnostic>,
+}
+
+impl LintRunner {
+  fn each(&mut self, mut f: impl FnMut(&mut dyn Rule, &mut LintContext)) {
+    for active in &mut self.rules {
+      let mut ctx = LintContext {
+        name: active.name,
+        severity: active.severity,
+        diagnostics: &mut self.diagnostics,
+      };
+      f(active.rule.as_mut(), &mut ctx);
+    }
+  }
+}
+
+impl<'a> TraverseHtml<'a> for LintRunner {
+  fn enter_element(&mut self, element: &Element<'a>) -> TraverseOperate {
+    self.each(|rule, ctx| rule.enter_element(element, ctx));
+    TraverseOperate::Continue
+  }
+
+  fn exit_element(&mut self, element: &Element<'a>) {
+    self.each(|rule, ctx| rule.exit_element(element, ctx));
+  }
+
+  fn enter_text(&mut self, text: &Text<'a>) -> TraverseOperate {
+    self.each(|rule, ctx| rule.text(text, ctx));
+    TraverseOperate::Continue
+  }
+
+  fn exit_program(&mut self, _program: &Program<'a>) {
+    self.each(|rule, ctx| rule.finish(ctx));
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::fmt::Write;
+
+  use insta::assert_snapshot;
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use crate::{Linter, RuleSeverity};
+
+  pub fn lint(html: &str, linter: &Linter) -> String {
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+
+    let mut output = String::new();
+    for diagnostic in linter.lint(&program) {
+      let labels: Vec<_> = diagnostic
+        .labels
+        .iter()
+        .flatten()
+        .map(|label| label.offset()..label.offset() + label.len())
+        .collect();
+      writeln!(
+        output,
+        "{:?} {}: {} {labels:?}",
+        diagnostic.severity, diagnostic.code, diagnostic.message
+      )
+      .unwrap();
+    }
+    output
+  }
+
+  #[test]
+  fn builtin_rules() {
+    assert_snapshot!(lint(
+      r#"<h1 id="title">Title</h1><h3 id="title">Sub</h3><center><img src="a.png"></center><a href="">Empty</a>"#,
+      &Linter::default()
+    ));
+  }
+
+  #[test]
+  fn aria_rules() {
+    assert_snapshot!(lint(
+      r#"<div role="buton widget"></div><div aria-lable="x"></div><button aria-checked="true">Ok</button><div role="checkbox" aria-checked="false">Agree</div>"#,
+      &Linter::default()
+    ));
+  }
+
+  #[test]
+  fn accessible_names() {
+    assert_snapshot!(lint(
+      r#"<button><svg></svg></button><a href="/"><img src="logo.png" alt="Home"></a><label>Name <input></label><input id="email"><label for="email">Email</label><input id="q"><input type="submit"><select></select>"#,
+      &Linter::default()
+    ));
+  }
+
+  #[test]
+  fn label_for() {
+    assert_snapshot!(lint(
+      r#"<label for="missing">A</label><label for="box">B</label><div id="box"></div><label for="">C</label>"#,
+      &Linter::default()
+    ));
+  }
+
+  #[test]
+  fn configure_severity() {
+    let linter = Linter::default()
+      .with_severity("no-duplicate-id", RuleSeverity::Off)
+      .with_severity("img-alt", RuleSeverity::Error);
+
+    assert_eq!(linter.severity("no-duplicate-id"), RuleSeverity::Off);
+    assert_eq!(linter.severity("unknown-rule"), RuleSeverity::Off);
+    assert_snapshot!(lint(
+      r#"<p id="a"></p><p id="a"></p><img src="a.png">"#,
+      &linter
+    ));
+  }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashSet;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Element, Text};
+use umc_span::Span;
+
+use super::attribute;
+use crate::{LintContext, Rule, aria};
+
+/// Require interactive elements to have an accessible name.
+///
+/// A name can come from `aria-label`, `aria-labelledby` or `title`, from the text content
+/// (and `alt` of images) for buttons and links, or from a `<label>` for form controls.
+///
+/// ```html
+/// <button><svg></svg></button> <!-- error -->
+/// <input id="q">               <!-- error, unless a label has `for="q"` -->
+/// ```
+#[derive(Debug, Default)]
+pub struct AccessibleName {
+  /// Interactive elements currently open
+  stack: Vec<Frame>,
+  /// Depth of `<label>` elements
+  label_depth: usize,
+  /// `for` values of every label
+  labels: HashSet<String>,
+  /// Form controls only named if a label references their id
+  pending: Vec<(String, Span, String)>,
+}
+
+#[derive(Debug)]
+struct Frame {
+  span: Span,
+  tag_name: String,
+  named: bool,
+  name_from_content: bool,
+  id: Option<String>,
+}
+
+fn non_blank(element: &Element, name: &str) -> bool {
+  attribute(element, name)
+    .and_then(|a| a.value.as_ref())
+    .is_some_and(|v| !v.value.trim_ascii().is_empty())
+}
+
+impl AccessibleName {
+  fn mark_content(&mut self) {
+    for frame in &mut self.stack {
+      frame.named |= frame.name_from_content;
+    }
+  }
+}
+
+impl Rule for AccessibleName {
+  fn enter_element(&mut self, element: &Element, _ctx: &mut LintContext) {
+    let tag_name = element.tag_name.to_ascii_lowercase();
+
+    if tag_name == "label" {
+      self.label_depth += 1;
+      if let Some(target) = attribute(element, "for").and_then(|a| a.value.as_ref()) {
+        self.labels.insert(target.value.to_string());
+      }
+    }
+    if tag_name == "img" && non_blank(element, "alt") {
+      self.mark_content();
+    }
+
+    let Some(role) = aria::role(element) else {
+      return;
+    };
+    if !aria::WIDGET_ROLES.contains(&role) {
+      return;
+    }
+
+    let input_type = attribute(element, "type")
+      .and_then(|a| a.value.as_ref())
+      .map(|v| v.value.to_ascii_lowercase());
+    let is_form_control = matches!(tag_name.as_str(), "input" | "select" | "textarea");
+
+    let named = non_blank(element, "aria-label")
+      || non_blank(element, "aria-labelledby")
+      || non_blank(element, "title")
+      || (is_form_control && self.label_depth > 0)
+      || match (tag_name.as_str(), input_type.as_deref()) {
+        // default names like "Submit"
+        ("input", Some("submit" | "reset")) => true,
+        ("input", Some("button")) => non_blank(element, "value"),
+        ("input", Some("image")) => non_blank(element, "alt"),
+        _ => false,
+      };
+
+    self.stack.push(Frame {
+      span: element.span,
+      tag_name,
+      named,
+      name_from_content: !is_form_control,
+      id: attribute(element, "id")
+        .and_then(|a| a.value.as_ref())
+        .map(|v| v.value.to_string()),
+    });
+  }
+
+  fn exit_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    if element.tag_name.eq_ignore_ascii_case("label") {
+      self.label_depth = self.label_depth.saturating_sub(1);
+    }
+
+    if self
+      .stack
+      .last()
+      .is_none_or(|frame| frame.span != element.span)
+    {
+      return;
+    }
+    let Some(frame) = self.stack.pop() else {
+      return;
+    };
+
+    if !frame.named {
+      match frame.id {
+        Some(id) if !frame.name_from_content => {
+          self.pending.push((id, frame.span, frame.tag_name));
+        }
+        _ => report(ctx, frame.span, &frame.tag_name),
+      }
+    }
+  }
+
+  fn text(&mut self, text: &Text, _ctx: &mut LintContext) {
+    if !text.value.trim_ascii().is_empty() {
+      self.mark_content();
+    }
+  }
+
+  fn finish(&mut self, ctx: &mut LintContext) {
+    for (id, span, tag_name) in &self.pending {
+      if !self.labels.contains(id) {
+        report(ctx, *span, tag_name);
+      }
+    }
+  }
+}
+
+fn report(ctx: &mut LintContext, span: Span, tag_name: &str) {
+  ctx.report(
+    OxcDiagnostic::warn(format!("`<{tag_name}>` has no accessible name"))
+      .with_label(span)
+      .with_help("Add text content, a `<label>`, or an `aria-label` attribute"),
+  );
+}
@@ -0,0 +1,43 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+
+use crate::{LintContext, Rule, aria};
+
+/// Require `aria-*` attributes to exist, and to be supported by the role of the element.
+///
+/// The role is the explicit `role` of the element, or its implicit role. Elements without
+/// a well-defined role, like `<div>`, are only checked for unknown attributes.
+///
+/// ```html
+/// <div aria-lable="x"></div>           <!-- error, unknown attribute -->
+/// <button aria-checked="true"></button> <!-- error, not supported by `button` -->
+/// ```
+#[derive(Debug, Default)]
+pub struct AriaProps;
+
+impl Rule for AriaProps {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    let role = aria::role(element);
+
+    for attribute in &element.attributes {
+      let name = attribute.key.value.to_ascii_lowercase();
+      if !name.starts_with("aria-") {
+        continue;
+      }
+
+      if !aria::is_aria_attribute(&name) {
+        ctx.report(
+          OxcDiagnostic::warn(format!("`{name}` is not a valid ARIA attribute"))
+            .with_label(attribute.key.span),
+        );
+      } else if let Some(role) = role
+        && !aria::role_supports(role, &name)
+      {
+        ctx.report(
+          OxcDiagnostic::warn(format!("`{name}` is not supported by role `{role}`"))
+            .with_label(attribute.key.span),
+        );
+      }
+    }
+  }
+}
@@ -0,0 +1,42 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+
+use super::attribute;
+use crate::{LintContext, Rule, aria};
+
+/// Require `role` attributes to only contain concrete WAI-ARIA roles.
+///
+/// ```html
+/// <div role="buton"></div>  <!-- error, unknown role -->
+/// <div role="widget"></div> <!-- error, abstract role -->
+/// ```
+#[derive(Debug, Default)]
+pub struct AriaRole;
+
+impl Rule for AriaRole {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    let Some(role) = attribute(element, "role") else {
+      return;
+    };
+    let value = role.value.as_ref().map_or("", |v| v.value);
+
+    if value.trim_ascii().is_empty() {
+      ctx.report(OxcDiagnostic::warn("Empty `role` attribute").with_label(role.span));
+      return;
+    }
+
+    for token in value.split_ascii_whitespace() {
+      let token = token.to_ascii_lowercase();
+      if aria::is_concrete_role(&token) {
+        continue;
+      }
+
+      let diagnostic = if aria::is_abstract_role(&token) {
+        OxcDiagnostic::warn(format!("Abstract role `{token}` must not be used"))
+      } else {
+        OxcDiagnostic::warn(format!("`{token}` is not a valid ARIA role"))
+      };
+      ctx.report(diagnostic.with_label(role.span));
+    }
+  }
+}
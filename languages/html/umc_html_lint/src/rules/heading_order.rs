@@ -0,0 +1,42 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+
+use crate::{LintContext, Rule};
+
+/// Disallow skipping heading levels, e.g. an `<h3>` directly following an `<h1>`.
+///
+/// The first heading may be of any level.
+#[derive(Debug, Default)]
+pub struct HeadingOrder {
+  previous: Option<u8>,
+}
+
+/// Get the level of a heading element, e.g. 2 for `<h2>`.
+fn heading_level(tag_name: &str) -> Option<u8> {
+  match tag_name.as_bytes() {
+    [b'h' | b'H', level @ b'1'..=b'6'] => Some(level - b'0'),
+    _ => None,
+  }
+}
+
+impl Rule for HeadingOrder {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    let Some(level) = heading_level(element.tag_name) else {
+      return;
+    };
+
+    if let Some(previous) = self.previous
+      && level > previous + 1
+    {
+      ctx.report(
+        OxcDiagnostic::warn(format!(
+          "Heading level skipped from `<h{previous}>` to `<h{level}>`"
+        ))
+        .with_label(element.span)
+        .with_help(format!("Use `<h{}>` instead", previous + 1)),
+      );
+    }
+
+    self.previous = Some(level);
+  }
+}
@@ -0,0 +1,23 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+
+use super::attribute;
+use crate::{LintContext, Rule};
+
+/// Require an `alt` attribute on `<img>`.
+///
+/// An empty `alt=""` is allowed, it marks the image as decorative.
+#[derive(Debug, Default)]
+pub struct ImgAlt;
+
+impl Rule for ImgAlt {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    if element.tag_name.eq_ignore_ascii_case("img") && attribute(element, "alt").is_none() {
+      ctx.report(
+        OxcDiagnostic::warn("`<img>` is missing an `alt` attribute")
+          .with_label(element.span)
+          .with_help("Describe the image, or use `alt=\"\"` for decorative images"),
+      );
+    }
+  }
+}
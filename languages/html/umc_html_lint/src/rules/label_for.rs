@@ -0,0 +1,61 @@
+use std::collections::HashMap;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+use umc_span::Span;
+
+use super::attribute;
+use crate::{LintContext, Rule, aria};
+
+/// Require the `for` attribute of a `<label>` to reference a labelable element.
+///
+/// ```html
+/// <label for="name">Name</label>
+/// <div id="name"></div> <!-- error, not labelable -->
+/// ```
+#[derive(Debug, Default)]
+pub struct LabelFor {
+  /// id -> whether the element with this id is labelable
+  ids: HashMap<String, bool>,
+  /// `for` values with the span of their attribute
+  labels: Vec<(String, Span)>,
+}
+
+impl Rule for LabelFor {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    let value = |name| attribute(element, name).and_then(|a| a.value.as_ref());
+
+    if let Some(id) = value("id") {
+      let labelable = aria::is_labelable(element.tag_name, value("type").map(|v| v.value));
+      self.ids.entry(id.value.to_string()).or_insert(labelable);
+    }
+
+    if element.tag_name.eq_ignore_ascii_case("label")
+      && let Some(target) = attribute(element, "for")
+    {
+      let id = target.value.as_ref().map_or("", |v| v.value);
+      if id.is_empty() {
+        ctx.report(OxcDiagnostic::warn("Empty `for` attribute").with_label(target.span));
+      } else {
+        self.labels.push((id.to_string(), target.span));
+      }
+    }
+  }
+
+  fn finish(&mut self, ctx: &mut LintContext) {
+    for (id, span) in &self.labels {
+      match self.ids.get(id) {
+        None => ctx.report(
+          OxcDiagnostic::warn(format!("No element with id `{id}` for this label"))
+            .with_label(*span),
+        ),
+        Some(false) => ctx.report(
+          OxcDiagnostic::warn(format!("The element with id `{id}` cannot be labelled"))
+            .with_label(*span)
+            .with_help("Labels can reference `<input>`, `<select>`, `<textarea>`, `<button>`, `<meter>`, `<output>` and `<progress>`"),
+        ),
+        Some(true) => {}
+      }
+    }
+  }
+}
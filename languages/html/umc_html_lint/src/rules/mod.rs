@@ -0,0 +1,56 @@
+//! Built-in rules.
+
+mod accessible_name;
+mod aria_props;
+mod aria_role;
+mod heading_order;
+mod img_alt;
+mod label_for;
+mod no_deprecated_tags;
+mod no_duplicate_id;
+mod no_empty_href;
+
+pub use accessible_name::AccessibleName;
+pub use aria_props::AriaProps;
+pub use aria_role::AriaRole;
+pub use heading_order::HeadingOrder;
+pub use img_alt::ImgAlt;
+pub use label_for::LabelFor;
+pub use no_deprecated_tags::NoDeprecatedTags;
+pub use no_duplicate_id::NoDuplicateId;
+pub use no_empty_href::NoEmptyHref;
+use umc_html_ast::{Attribute, Element};
+
+use crate::{RuleRegistry, RuleSeverity};
+
+pub(crate) fn register_builtin(registry: &mut RuleRegistry) {
+  registry.register("no-duplicate-id", RuleSeverity::Error, || {
+    Box::new(NoDuplicateId::default())
+  });
+  registry.register("img-alt", RuleSeverity::Warn, || Box::new(ImgAlt));
+  registry.register("no-empty-href", RuleSeverity::Warn, || {
+    Box::new(NoEmptyHref)
+  });
+  registry.register("no-deprecated-tags", RuleSeverity::Warn, || {
+    Box::new(NoDeprecatedTags)
+  });
+  registry.register("heading-order", RuleSeverity::Warn, || {
+    Box::new(HeadingOrder::default())
+  });
+  registry.register("aria-role", RuleSeverity::Error, || Box::new(AriaRole));
+  registry.register("aria-props", RuleSeverity::Error, || Box::new(AriaProps));
+  registry.register("accessible-name", RuleSeverity::Warn, || {
+    Box::new(AccessibleName::default())
+  });
+  registry.register("label-for", RuleSeverity::Warn, || {
+    Box::new(LabelFor::default())
+  });
+}
+
+/// Find an attribute of an element by name, case-insensitively.
+pub fn attribute<'e, 'a>(element: &'e Element<'a>, name: &str) -> Option<&'e Attribute<'a>> {
+  element
+    .attributes
+    .iter()
+    .find(|a| a.key.value.eq_ignore_ascii_case(name))
+}
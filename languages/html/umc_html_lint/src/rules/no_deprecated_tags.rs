@@ -0,0 +1,51 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+
+use crate::{LintContext, Rule};
+
+/// Elements which are obsolete per the HTML standard.
+/// https://html.spec.whatwg.org/multipage/obsolete.html#non-conforming-features
+const DEPRECATED_TAGS: &[&str] = &[
+  "acronym",
+  "applet",
+  "basefont",
+  "bgsound",
+  "big",
+  "blink",
+  "center",
+  "dir",
+  "font",
+  "frame",
+  "frameset",
+  "isindex",
+  "keygen",
+  "listing",
+  "marquee",
+  "menuitem",
+  "nextid",
+  "nobr",
+  "noembed",
+  "noframes",
+  "plaintext",
+  "rb",
+  "rtc",
+  "spacer",
+  "strike",
+  "tt",
+  "xmp",
+];
+
+/// Disallow obsolete elements like `<center>` or `<font>`.
+#[derive(Debug, Default)]
+pub struct NoDeprecatedTags;
+
+impl Rule for NoDeprecatedTags {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    if let Some(tag) = DEPRECATED_TAGS
+      .iter()
+      .find(|tag| element.tag_name.eq_ignore_ascii_case(tag))
+    {
+      ctx.report(OxcDiagnostic::warn(format!("`<{tag}>` is deprecated")).with_label(element.span));
+    }
+  }
+}
@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use umc_html_ast::Element;
+use umc_span::Span;
+
+use super::attribute;
+use crate::{LintContext, Rule};
+
+/// Disallow several elements with the same `id`.
+///
+/// ```html
+/// <p id="intro"></p>
+/// <p id="intro"></p> <!-- error -->
+/// ```
+#[derive(Debug, Default)]
+pub struct NoDuplicateId {
+  /// id -> span of its first occurrence
+  seen: HashMap<String, Span>,
+}
+
+impl Rule for NoDuplicateId {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    let Some(value) = attribute(element, "id").and_then(|a| a.value.as_ref()) else {
+      return;
+    };
+
+    if let Some(first) = self.seen.get(value.value) {
+      ctx.report(
+        OxcDiagnostic::warn(format!("Duplicate id `{}`", value.value))
+          .with_labels([
+            LabeledSpan::new_primary_with_span(Some("duplicated here".to_string()), value.span),
+            LabeledSpan::new_with_span(Some("first used here".to_string()), *first),
+          ])
+          .with_help("Ids must be unique in a document"),
+      );
+    } else {
+      self.seen.insert(value.value.to_string(), value.span);
+    }
+  }
+}
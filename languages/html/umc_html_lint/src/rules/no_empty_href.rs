@@ -0,0 +1,26 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Element;
+
+use super::attribute;
+use crate::{LintContext, Rule};
+
+/// Disallow links with an empty or blank `href`, which point to the current page.
+#[derive(Debug, Default)]
+pub struct NoEmptyHref;
+
+impl Rule for NoEmptyHref {
+  fn enter_element(&mut self, element: &Element, ctx: &mut LintContext) {
+    if !element.tag_name.eq_ignore_ascii_case("a") {
+      return;
+    }
+
+    if let Some(href) = attribute(element, "href")
+      && href
+        .value
+        .as_ref()
+        .is_none_or(|value| value.value.trim_ascii().is_empty())
+    {
+      ctx.report(OxcDiagnostic::warn("Empty `href`").with_label(href.span));
+    }
+  }
+}
@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// A node of a serialized browser DOM.
+///
+/// The dump is a JSON tree where every node has a `type` field:
+///
+/// ```json
+/// { "type": "document", "children": [
+///   { "type": "doctype", "name": "html" },
+///   { "type": "element", "name": "html", "attributes": { "lang": "en" }, "children": [
+///     { "type": "text", "value": "Hello" },
+///     { "type": "comment", "value": " note " }
+///   ] }
+/// ] }
+/// ```
+///
+/// `attributes` and `children` may be omitted when empty. Element names are compared
+/// case-insensitively, since browsers report `tagName` in upper case for HTML elements.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DomNode {
+  /// The document root
+  Document {
+    /// Top level nodes
+    #[serde(default)]
+    children: Vec<Self>,
+  },
+  /// An element
+  Element {
+    /// Local name of the element
+    name: String,
+    /// Attributes by name
+    #[serde(default)]
+    attributes: BTreeMap<String, String>,
+    /// Child nodes
+    #[serde(default)]
+    children: Vec<Self>,
+  },
+  /// A text node
+  Text {
+    /// Text content
+    value: String,
+  },
+  /// A comment node
+  Comment {
+    /// Comment content, without delimiters
+    value: String,
+  },
+  /// The document type node
+  Doctype {
+    /// The doctype name, e.g. `html`
+    name: String,
+  },
+}
+
+impl DomNode {
+  /// Parse a JSON DOM dump.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_parity::DomNode;
+  ///
+  /// let dump = DomNode::from_json(r#"{ "type": "text", "value": "Hi" }"#).unwrap();
+  /// assert_eq!(dump, DomNode::Text { value: "Hi".to_string() });
+  /// ```
+  pub fn from_json(json: &str) -> serde_json::Result<Self> {
+    serde_json::from_str(json)
+  }
+
+  /// Get a short description of the node, used in paths and messages.
+  pub fn describe(&self) -> &str {
+    match self {
+      Self::Document { .. } => "#document",
+      Self::Element { name, .. } => name,
+      Self::Text { .. } => "#text",
+      Self::Comment { .. } => "#comment",
+      Self::Doctype { .. } => "#doctype",
+    }
+  }
+}
@@ -0,0 +1,512 @@
+//! Browser parity checks for the UMC HTML parser.
+//!
+//! This developer-facing crate compares the tree produced by the parser with a DOM
+//! serialized from a real browser, and reports every structural divergence with the
+//! span of the offending node. It is used to track parity while the spec algorithms
+//! (implied tags, adoption agency, ...) are implemented.
+//!
+//! # Dumping a DOM
+//!
+//! The expected format is documented on [`DomNode`]. A dump can be produced with
+//! puppeteer, or any other tool able to evaluate a script in the page:
+//!
+//! ```js
+//! const dump = (node) => {
+//!   switch (node.nodeType) {
+//!     case Node.DOCUMENT_NODE:
+//!       return { type: "document", children: [...node.childNodes].map(dump) };
+//!     case Node.ELEMENT_NODE:
+//!       return {
+//!         type: "element",
+//!         name: node.localName,
+//!         attributes: Object.fromEntries([...node.attributes].map((a) => [a.name, a.value])),
+//!         children: [...(node.content ?? node).childNodes].map(dump),
+//!       };
+//!     case Node.TEXT_NODE:
+//!       return { type: "text", value: node.data };
+//!     case Node.COMMENT_NODE:
+//!       return { type: "comment", value: node.data };
+//!     case Node.DOCUMENT_TYPE_NODE:
+//!       return { type: "doctype", name: node.name };
+//!   }
+//! };
+//! const json = await page.evaluate(() => JSON.stringify(dump(document)));
+//! ```
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_html_parity::{DomNode, ParityOptions, compare};
+//! use umc_html_parser::CreateHtml;
+//! use umc_parser::Parser;
+//!
+//! let allocator = Allocator::default();
+//! let parser = Parser::html(&allocator, "<p>Hello</p>");
+//! let program = parser.parse().program;
+//!
+//! let dump = DomNode::from_json(
+//!   r#"{ "type": "document", "children": [
+//!     { "type": "element", "name": "p", "children": [{ "type": "text", "value": "Hello" }] }
+//!   ] }"#,
+//! )
+//! .unwrap();
+//!
+//! assert!(compare(&program, &dump, &ParityOptions::default()).is_empty());
+//! ```
+
+use std::{
+  collections::BTreeMap,
+  fmt::{self, Display},
+};
+
+use umc_html_ast::{Attribute, Node, Program};
+use umc_span::Span;
+
+mod dump;
+
+pub use dump::DomNode;
+
+/// Options for [`compare`].
+#[derive(Debug, Default, Clone)]
+pub struct ParityOptions {
+  /// Ignore text nodes consisting only of whitespace on both sides.
+  ///
+  /// Browsers drop some inter-element whitespace depending on the insertion mode,
+  /// which is noise when the structure itself is under test.
+  pub ignore_whitespace_text: bool,
+}
+
+/// The kind of a [`Divergence`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DivergenceKind {
+  /// The browser has a node the parser did not produce
+  Missing {
+    /// Description of the browser node
+    expected: String,
+  },
+  /// The parser produced a node the browser does not have
+  Extra {
+    /// Description of the parsed node
+    found: String,
+  },
+  /// The nodes are of different types, e.g. an element against a text node
+  NodeType {
+    /// Description of the browser node
+    expected: String,
+    /// Description of the parsed node
+    found: String,
+  },
+  /// The element names differ
+  TagName {
+    /// Name of the browser element
+    expected: String,
+    /// Name of the parsed element
+    found: String,
+  },
+  /// An attribute is missing, extra, or has a different value
+  Attribute {
+    /// Attribute name
+    name: String,
+    /// Value in the browser, `None` if absent
+    expected: Option<String>,
+    /// Value in the parsed tree, `None` if absent
+    found: Option<String>,
+  },
+  /// Text or comment content differs
+  Content {
+    /// Content in the browser
+    expected: String,
+    /// Content in the parsed tree
+    found: String,
+  },
+}
+
+/// A difference between the parsed tree and the browser DOM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+  /// What differs
+  pub kind: DivergenceKind,
+  /// Location in the browser DOM, e.g. `html[1] > body[2] > #text[0]`.
+  /// Indices count all sibling nodes, including text and comments. The last segment of
+  /// an [`Extra`](DivergenceKind::Extra) node uses its index in the parsed tree instead.
+  pub path: String,
+  /// Span of the parsed node. For a missing node, the span of the parent,
+  /// or an empty span at the end of the document for a missing root node.
+  pub span: Span,
+}
+
+impl Display for Divergence {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}: ", self.path)?;
+    match &self.kind {
+      DivergenceKind::Missing { expected } => write!(f, "missing {expected}"),
+      DivergenceKind::Extra { found } => write!(f, "unexpected {found}"),
+      DivergenceKind::NodeType { expected, found }
+      | DivergenceKind::TagName { expected, found } => {
+        write!(f, "expected {expected}, found {found}")
+      }
+      DivergenceKind::Attribute {
+        name,
+        expected,
+        found,
+      } => write!(
+        f,
+        "attribute `{name}` expected {expected:?}, found {found:?}"
+      ),
+      DivergenceKind::Content { expected, found } => {
+        write!(f, "expected content {expected:?}, found {found:?}")
+      }
+    }
+  }
+}
+
+/// Compare a parsed program against a browser DOM dump.
+///
+/// `dump` is usually a [`DomNode::Document`]; any other node is treated as the single
+/// expected root node. Nodes are aligned by position, and the children of two nodes
+/// are only compared when the nodes themselves match in type and name.
+pub fn compare(program: &Program, dump: &DomNode, options: &ParityOptions) -> Vec<Divergence> {
+  let mut comparer = Comparer {
+    options,
+    path: Vec::new(),
+    divergences: Vec::new(),
+  };
+
+  let expected = match dump {
+    DomNode::Document { children } => children.as_slice(),
+    other => std::slice::from_ref(other),
+  };
+  let end = program.last().map_or(0, |node| node.span().end);
+  comparer.compare_children(program, expected, Span::empty(end));
+
+  comparer.divergences
+}
+
+struct Comparer<'o> {
+  options: &'o ParityOptions,
+  path: Vec<String>,
+  divergences: Vec<Divergence>,
+}
+
+impl Comparer<'_> {
+  fn report(&mut self, kind: DivergenceKind, span: Span) {
+    self.divergences.push(Divergence {
+      kind,
+      path: self.path.join(" > "),
+      span,
+    });
+  }
+
+  fn compare_children(&mut self, found: &[Node], expected: &[DomNode], parent: Span) {
+    let found: Vec<(usize, &Node)> = found
+      .iter()
+      .enumerate()
+      .filter(|(_, node)| !(self.options.ignore_whitespace_text && is_whitespace_node(node)))
+      .collect();
+    let expected: Vec<(usize, &DomNode)> = expected
+      .iter()
+      .enumerate()
+      .filter(|(_, node)| !(self.options.ignore_whitespace_text && is_whitespace_dom(node)))
+      .collect();
+
+    for i in 0..found.len().max(expected.len()) {
+      match (found.get(i), expected.get(i)) {
+        (Some(&(_, node)), Some(&(index, dom))) => {
+          self.path.push(format!("{}[{index}]", dom.describe()));
+          self.compare_node(node, dom);
+          self.path.pop();
+        }
+        (Some(&(index, node)), None) => {
+          self.path.push(format!("{}[{index}]", describe(node)));
+          self.report(
+            DivergenceKind::Extra {
+              found: describe(node).to_string(),
+            },
+            node.span(),
+          );
+          self.path.pop();
+        }
+        (None, Some(&(index, dom))) => {
+          self.path.push(format!("{}[{index}]", dom.describe()));
+          self.report(
+            DivergenceKind::Missing {
+              expected: dom.describe().to_string(),
+            },
+            parent,
+          );
+          self.path.pop();
+        }
+        (None, None) => unreachable!(),
+      }
+    }
+  }
+
+  #[allow(clippy::too_many_lines)]
+  fn compare_node(&mut self, node: &Node, dom: &DomNode) {
+    let span = node.span();
+
+    match (node, dom) {
+      (
+        Node::Element(element),
+        DomNode::Element {
+          name,
+          attributes,
+          children,
+        },
+      ) => {
+        if !element.tag_name.eq_ignore_ascii_case(name) {
+          self.report(
+            DivergenceKind::TagName {
+              expected: name.clone(),
+              found: element.tag_name.to_string(),
+            },
+            span,
+          );
+          return;
+        }
+
+        self.compare_attributes(&element.attributes, attributes, span);
+        self.compare_children(&element.children, children, span);
+      }
+      (
+        Node::Script(script),
+        DomNode::Element {
+          name,
+          attributes,
+          children,
+        },
+      ) if name.eq_ignore_ascii_case("script") => {
+        self.compare_attributes(&script.attributes, attributes, span);
+
+        let expected: String = children
+          .iter()
+          .filter_map(|child| match child {
+            DomNode::Text { value } => Some(value.as_str()),
+            _ => None,
+          })
+          .collect();
+        if expected != script.program.source_text {
+          self.report(
+            DivergenceKind::Content {
+              expected,
+              found: script.program.source_text.to_string(),
+            },
+            span,
+          );
+        }
+      }
+      (Node::Text(text), DomNode::Text { value }) => {
+        if text.value != value {
+          self.report(
+            DivergenceKind::Content {
+              expected: value.clone(),
+              found: text.value.to_string(),
+            },
+            span,
+          );
+        }
+      }
+      (Node::Comment(comment), DomNode::Comment { value }) => {
+        if comment.value != value {
+          self.report(
+            DivergenceKind::Content {
+              expected: value.clone(),
+              found: comment.value.to_string(),
+            },
+            span,
+          );
+        }
+      }
+      // browsers parse a directive as a bogus comment, of the data between `<` and `>`
+      (Node::Directive(directive), DomNode::Comment { value }) => {
+        let data = &directive.raw[1..];
+        let data = data.strip_suffix('>').unwrap_or(data);
+        if data != value {
+          self.report(
+            DivergenceKind::Content {
+              expected: value.clone(),
+              found: data.to_string(),
+            },
+            span,
+          );
+        }
+      }
+      (Node::Doctype(doctype), DomNode::Doctype { name }) => {
+        let found = doctype.attributes.first().map_or("", |a| a.key.value);
+        if !found.eq_ignore_ascii_case(name) {
+          self.report(
+            DivergenceKind::Content {
+              expected: name.clone(),
+              found: found.to_string(),
+            },
+            span,
+          );
+        }
+      }
+      _ => self.report(
+        DivergenceKind::NodeType {
+          expected: dom.describe().to_string(),
+          found: describe(node).to_string(),
+        },
+        span,
+      ),
+    }
+  }
+
+  fn compare_attributes(
+    &mut self,
+    found: &[Attribute],
+    expected: &BTreeMap<String, String>,
+    element: Span,
+  ) {
+    for (name, value) in expected {
+      let attribute = found
+        .iter()
+        .find(|a| a.key.value.eq_ignore_ascii_case(name));
+
+      let found_value = attribute.map(|a| a.value.as_ref().map_or("", |v| v.value));
+      if found_value != Some(value.as_str()) {
+        let span = attribute.map_or(element, |a| a.span);
+        self.report(
+          DivergenceKind::Attribute {
+            name: name.clone(),
+            expected: Some(value.clone()),
+            found: found_value.map(ToString::to_string),
+          },
+          span,
+        );
+      }
+    }
+
+    for attribute in found {
+      if !expected
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case(attribute.key.value))
+      {
+        self.report(
+          DivergenceKind::Attribute {
+            name: attribute.key.value.to_string(),
+            expected: None,
+            found: Some(attribute.value.as_ref().map_or("", |v| v.value).to_string()),
+          },
+          attribute.span,
+        );
+      }
+    }
+  }
+}
+
+fn describe<'a>(node: &Node<'a>) -> &'a str {
+  match node {
+    Node::Doctype(_) => "#doctype",
+    Node::Element(element) => element.tag_name,
+    Node::Text(_) => "#text",
+    Node::Comment(_) | Node::Directive(_) => "#comment",
+    Node::Script(script) => script.tag_name,
+    Node::RawUnknown(_) => "#unknown",
+    Node::Mustache(_) => "#mustache",
+    Node::TemplateTag(_) => "#template",
+  }
+}
+
+fn is_whitespace_node(node: &Node) -> bool {
+  matches!(node, Node::Text(text) if text.value.trim_ascii().is_empty())
+}
+
+fn is_whitespace_dom(node: &DomNode) -> bool {
+  matches!(node, DomNode::Text { value } if value.trim_ascii().is_empty())
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+  use oxc_allocator::Allocator;
+  use umc_html_parser::{CreateHtml, option::HtmlParserOption};
+  use umc_parser::Parser;
+
+  use crate::{DomNode, ParityOptions, compare};
+
+  fn test(html: &str, dump: &str, options: &ParityOptions) -> String {
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, html);
+    let program = parser.parse().program;
+    let dump = DomNode::from_json(dump).unwrap();
+
+    compare(&program, &dump, options)
+      .iter()
+      .map(|d| format!("{d} @ {}..{}", d.span.start, d.span.end))
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  #[test]
+  fn matching_tree() {
+    const HTML: &str = "<!DOCTYPE html>\n<DIV class=\"a\">Hi<!--c--></DIV>";
+    const DUMP: &str = r#"{ "type": "document", "children": [
+      { "type": "doctype", "name": "html" },
+      { "type": "text", "value": "\n" },
+      { "type": "element", "name": "div", "attributes": { "class": "a" }, "children": [
+        { "type": "text", "value": "Hi" },
+        { "type": "comment", "value": "c" }
+      ] }
+    ] }"#;
+
+    assert_eq!(test(HTML, DUMP, &ParityOptions::default()), "");
+  }
+
+  #[test]
+  fn implied_and_misnested() {
+    const HTML: &str = "<b>bold<i>both</b>italic</i>";
+    const DUMP: &str = r#"{ "type": "document", "children": [
+      { "type": "element", "name": "html", "children": [
+        { "type": "element", "name": "head" },
+        { "type": "element", "name": "body", "children": [
+          { "type": "element", "name": "b", "children": [
+            { "type": "text", "value": "bold" },
+            { "type": "element", "name": "i", "children": [{ "type": "text", "value": "both" }] }
+          ] },
+          { "type": "element", "name": "i", "children": [{ "type": "text", "value": "italic" }] }
+        ] }
+      ] }
+    ] }"#;
+
+    assert_snapshot!(test(HTML, DUMP, &ParityOptions::default()));
+
+    // the spec algorithms of the parser build the tree of the browser
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .implied_tags(true)
+      .adoption_agency(true)
+      .build();
+    let program = Parser::html(&allocator, HTML)
+      .with_options(options)
+      .parse()
+      .program;
+    let dump = DomNode::from_json(DUMP).unwrap();
+    assert!(compare(&program, &dump, &ParityOptions::default()).is_empty());
+  }
+
+  #[test]
+  fn attributes_and_content() {
+    const HTML: &str = "<p id=\"x\" hidden>\n  text\n</p>";
+    const DUMP: &str = r#"{ "type": "element", "name": "p", "attributes": { "id": "y", "title": "" }, "children": [
+      { "type": "text", "value": "\n  other\n" }
+    ] }"#;
+
+    assert_snapshot!(test(HTML, DUMP, &ParityOptions::default()));
+  }
+
+  #[test]
+  fn ignore_whitespace_text() {
+    const HTML: &str = "<ul>\n  <li>a</li>\n</ul>";
+    const DUMP: &str = r#"{ "type": "element", "name": "ul", "children": [
+      { "type": "element", "name": "li", "children": [{ "type": "text", "value": "a" }] }
+    ] }"#;
+
+    let options = ParityOptions {
+      ignore_whitespace_text: true,
+    };
+    assert_eq!(test(HTML, DUMP, &options), "");
+  }
+}
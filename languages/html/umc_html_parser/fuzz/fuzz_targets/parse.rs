@@ -0,0 +1,27 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use oxc_allocator::Allocator;
+use umc_html_parser::{CreateHtml, encoding::decode_document, option::HtmlParserOption};
+use umc_parser::Parser;
+
+fuzz_target!(|data: &[u8]| {
+  let allocator = Allocator::default();
+  let (source_text, _) = decode_document(&allocator, data, None);
+
+  // keep adversarial input from spending the time of the fuzzer in deep trees
+  let options = HtmlParserOption {
+    max_depth: Some(256),
+    max_attributes: Some(256),
+    max_tokens: Some(64 * 1024),
+    ..HtmlParserOption::default()
+  };
+  let parser = Parser::html(&allocator, source_text).with_options(options);
+  let result = parser.parse();
+
+  for error in &result.errors {
+    for label in error.labels.iter().flatten() {
+      assert!(label.offset() + label.len() <= source_text.len());
+    }
+  }
+});
@@ -0,0 +1,37 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use umc_html_parser::stream::{HtmlStreamParser, StreamEvent};
+
+fuzz_target!(|data: &[u8]| {
+  // the first byte picks the chunk size, to exercise tokens split across chunks
+  let Some((&chunk_size, data)) = data.split_first() else {
+    return;
+  };
+  let chunk_size = usize::from(chunk_size).max(1);
+
+  let mut parser = HtmlStreamParser::default();
+  let mut end = 0;
+  let mut check = |parser: &mut HtmlStreamParser| {
+    while let Some(event) = parser.next_event() {
+      let span = match event {
+        StreamEvent::Doctype { span, .. }
+        | StreamEvent::StartTag { span, .. }
+        | StreamEvent::EndTag { span, .. }
+        | StreamEvent::Text { span, .. }
+        | StreamEvent::Comment { span, .. } => span,
+      };
+      // events are emitted in document order
+      assert!(span.start >= end);
+      end = span.end;
+    }
+  };
+
+  for chunk in data.chunks(chunk_size) {
+    parser.feed_bytes(chunk);
+    check(&mut parser);
+  }
+  parser.end();
+  check(&mut parser);
+  assert!(parser.is_finished());
+});
@@ -0,0 +1,41 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use oxc_allocator::Allocator;
+use umc_html_parser::{
+  CreateHtml,
+  option::{HtmlParserOption, TemplateDelimiters},
+};
+use umc_parser::Parser;
+
+/// Syntax next to characters of every UTF-8 length, so multi-byte characters end up in
+/// names, values, comments and template tags.
+const PIECES: &[&str] = &[
+  "<", ">", "</", "<!--", "-->", "<!", "<?", "=", "\"", "'", "/", " ", "a", "é", "€", "😀",
+  "{{", "}}", "«", "»", "<script>", "</script>", "<style>", "style=", "<![CDATA[", "]]>",
+];
+
+fuzz_target!(|data: &[u8]| {
+  let source_text: String = data
+    .iter()
+    .map(|&byte| PIECES[usize::from(byte) % PIECES.len()])
+    .collect();
+
+  let allocator = Allocator::default();
+  let options = HtmlParserOption::builder()
+    .max_depth(Some(256))
+    .handlebars(true)
+    .template_delimiters(vec![TemplateDelimiters::new("«", "»")])
+    .parse_style_attributes(true)
+    .build();
+  let parser = Parser::html(&allocator, &source_text).with_options(options);
+  // the parser asserts that tokens are on character boundaries in debug builds
+  let result = parser.parse();
+
+  for error in &result.errors {
+    for label in error.labels.iter().flatten() {
+      assert!(source_text.is_char_boundary(label.offset()));
+      assert!(source_text.is_char_boundary(label.offset() + label.len()));
+    }
+  }
+});
@@ -0,0 +1,91 @@
+//! Hard limits on the work done by the parser.
+//!
+//! Unlike the recovering limits of [`HtmlParserOption`](crate::option::HtmlParserOption),
+//! like `max_depth`, an exceeded [`ParseBudget`] aborts the parse. The AST built so far is
+//! returned, with a [`BudgetExceeded`] diagnostic among its errors.
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_span::Span;
+
+use crate::error::HtmlParseErrorKind;
+
+/// Limits aborting the parse when exceeded, all `None` (unlimited) by default.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::{
+///   CreateHtml,
+///   budget::{BudgetExceeded, ParseBudget},
+///   option::HtmlParserOption,
+/// };
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let options = HtmlParserOption {
+///   budget: ParseBudget {
+///     max_nodes: Some(2),
+///     ..ParseBudget::default()
+///   },
+///   ..HtmlParserOption::default()
+/// };
+/// let parser = Parser::html(&allocator, "<p>a</p><p>b</p>").with_options(options);
+/// let result = parser.parse();
+///
+/// assert_eq!(result.program.len(), 1);
+/// let exceeded = result.errors.iter().find_map(BudgetExceeded::from_diagnostic);
+/// assert_eq!(exceeded, Some(BudgetExceeded::Nodes));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Deserialize),
+  serde(default, rename_all = "camelCase", deny_unknown_fields)
+)]
+pub struct ParseBudget {
+  /// Maximum length of the parsed source, in bytes.
+  ///
+  /// Tokens ending after it are not read.
+  pub max_bytes: Option<usize>,
+  /// Maximum number of nodes in the AST.
+  pub max_nodes: Option<usize>,
+  /// Maximum nesting depth of elements.
+  pub max_depth: Option<usize>,
+  /// Maximum number of errors, the parse aborts at the next one.
+  pub max_errors: Option<usize>,
+}
+
+/// The limit of a [`ParseBudget`] which was exceeded.
+///
+/// The diagnostic has the [`HtmlParseErrorKind::BudgetExceeded`] kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BudgetExceeded {
+  Bytes,
+  Nodes,
+  Depth,
+  Errors,
+}
+
+impl BudgetExceeded {
+  /// Get the exceeded limit from a diagnostic, `None` for other diagnostics.
+  pub fn from_diagnostic(diagnostic: &OxcDiagnostic) -> Option<Self> {
+    match HtmlParseErrorKind::from_diagnostic(diagnostic)? {
+      HtmlParseErrorKind::BudgetExceeded(limit) => Some(limit),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn diagnostic(self, max: usize, offset: u32) -> OxcDiagnostic {
+    let message = match self {
+      Self::Bytes => format!("Parse budget exceeded: the document is longer than {max} bytes"),
+      Self::Nodes => format!("Parse budget exceeded: more than {max} nodes"),
+      Self::Depth => format!("Parse budget exceeded: elements nested deeper than {max}"),
+      Self::Errors => format!("Parse budget exceeded: more than {max} errors"),
+    };
+    HtmlParseErrorKind::BudgetExceeded(self)
+      .error(message)
+      .with_label(Span::empty(offset))
+      .with_help("Parsing was aborted here, the rest of the document is ignored")
+  }
+}
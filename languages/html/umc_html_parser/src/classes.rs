@@ -0,0 +1,216 @@
+//! Extraction of the classes of a document from its tokens, without building an AST.
+//!
+//! Utility-CSS tools like Tailwind scan many files for class names, and only need the
+//! classes with their location. [`extract_classes`] reads them from the tokens of the
+//! lexer, so it does not pay for the allocation of a tree, nor for parsing scripts.
+//!
+//! Template dialects bind classes in other attributes, like `class:active={on}` in Svelte.
+//! A [`ClassExtractor`] takes a [hook](ClassExtractor::with_bindings) telling where an
+//! attribute holds classes, e.g. [`class_directive`].
+
+use umc_span::Span;
+
+use crate::{
+  lexer::{HtmlLexer, HtmlLexerOption, kind::HtmlKind},
+  option::TagSet,
+};
+
+/// Where an attribute holds classes, given its name, see [`ClassExtractor::with_bindings`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassSource {
+  /// No class in the attribute
+  None,
+  /// The value is a whitespace-separated list of classes, like in `class="a b"`
+  Value,
+  /// The name is a class after a prefix of this length, like `active` in `class:active`
+  Name(usize),
+}
+
+/// The default hook: classes are in the value of `class` attributes.
+pub const fn class_attribute(name: &str) -> ClassSource {
+  if name.eq_ignore_ascii_case("class") {
+    ClassSource::Value
+  } else {
+    ClassSource::None
+  }
+}
+
+/// Classes of `class` attributes, and of `class:` directives like `class:active={on}` in
+/// Svelte and Astro.
+pub fn class_directive(name: &str) -> ClassSource {
+  match name.get(..6) {
+    Some(prefix) if prefix.eq_ignore_ascii_case("class:") && name.len() > 6 => ClassSource::Name(6),
+    _ => class_attribute(name),
+  }
+}
+
+/// Get the classes of a document in source order, with their span.
+///
+/// ## Example
+///
+/// ```
+/// use umc_html_parser::classes::extract_classes;
+///
+/// let classes: Vec<_> = extract_classes(r#"<p class="mt-2  text-sm">Hi</p>"#)
+///   .map(|(_, class)| class)
+///   .collect();
+/// assert_eq!(classes, ["mt-2", "text-sm"]);
+/// ```
+pub fn extract_classes(source_text: &str) -> ClassExtractor<'_> {
+  ClassExtractor::new(source_text)
+}
+
+/// Iterator over the classes of a document, see [`extract_classes`].
+pub struct ClassExtractor<'a> {
+  source_text: &'a str,
+  lexer: HtmlLexer<'a>,
+  bindings: fn(&str) -> ClassSource,
+  /// Whether the tokens are inside a tag, where attributes can be classes
+  in_tag: bool,
+  /// Where the classes of the last attribute name are, until its value
+  pending: ClassSource,
+  /// Whether the next attribute token is a value
+  after_eq: bool,
+  /// Classes of a value left to read, and their offset
+  value: Option<(&'a str, u32)>,
+}
+
+impl<'a> ClassExtractor<'a> {
+  /// Create an iterator over the classes of `class` attributes of a document.
+  pub fn new(source_text: &'a str) -> Self {
+    let lexer = HtmlLexer::new(
+      source_text,
+      HtmlLexerOption {
+        raw_text_tags: TagSet::Html5RawText,
+        spec_self_closing: false,
+        cdata_sections: false,
+        mustache: false,
+        template_delimiters: Vec::new(),
+      },
+    );
+    Self {
+      source_text,
+      lexer,
+      bindings: class_attribute,
+      in_tag: false,
+      pending: ClassSource::None,
+      after_eq: false,
+      value: None,
+    }
+  }
+
+  /// Find classes in other attributes, like the `class:` directives of template dialects
+  /// with [`class_directive`].
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_parser::classes::{ClassExtractor, class_directive};
+  ///
+  /// let classes: Vec<_> = ClassExtractor::new("<p class=a class:b={on}>")
+  ///   .with_bindings(class_directive)
+  ///   .map(|(_, class)| class)
+  ///   .collect();
+  /// assert_eq!(classes, ["a", "b"]);
+  /// ```
+  #[must_use]
+  pub fn with_bindings(mut self, bindings: fn(&str) -> ClassSource) -> Self {
+    self.bindings = bindings;
+    self
+  }
+
+  /// Take the next class of the value being read.
+  fn next_in_value(&mut self) -> Option<(Span, &'a str)> {
+    let (value, offset) = self.value.take()?;
+    let start = value.len()
+      - value
+        .trim_start_matches(|c: char| c.is_ascii_whitespace())
+        .len();
+    let rest = &value[start..];
+    let len = rest
+      .find(|c: char| c.is_ascii_whitespace())
+      .unwrap_or(rest.len());
+    if len == 0 {
+      return None;
+    }
+    let class_start = offset + start as u32;
+    self.value = Some((&rest[len..], class_start + len as u32));
+    Some((
+      Span::new(class_start, class_start + len as u32),
+      &rest[..len],
+    ))
+  }
+}
+
+impl<'a> Iterator for ClassExtractor<'a> {
+  type Item = (Span, &'a str);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      if let Some(class) = self.next_in_value() {
+        return Some(class);
+      }
+
+      let token = self.lexer.next_token()?;
+      match token.kind {
+        HtmlKind::TagStart => self.in_tag = true,
+        HtmlKind::TagEnd | HtmlKind::SelfCloseTagEnd | HtmlKind::Doctype => {
+          self.in_tag = false;
+          self.after_eq = false;
+        }
+        HtmlKind::Eq if self.in_tag => self.after_eq = true,
+        HtmlKind::Attribute if self.in_tag && self.after_eq => {
+          self.after_eq = false;
+          if self.pending == ClassSource::Value {
+            let raw = &self.source_text[token.start as usize..token.end as usize];
+            let quoted = raw.len() >= 2
+              && (raw.starts_with('"') && raw.ends_with('"')
+                || raw.starts_with('\'') && raw.ends_with('\''));
+            self.value = Some(if quoted {
+              (&raw[1..raw.len() - 1], token.start + 1)
+            } else {
+              (raw, token.start)
+            });
+          }
+          self.pending = ClassSource::None;
+        }
+        HtmlKind::Attribute if self.in_tag => {
+          let name = &self.source_text[token.start as usize..token.end as usize];
+          self.pending = (self.bindings)(name);
+          if let ClassSource::Name(prefix) = self.pending
+            && prefix < name.len()
+          {
+            let start = token.start + prefix as u32;
+            return Some((Span::new(start, token.end), &name[prefix..]));
+          }
+        }
+        HtmlKind::Eof => return None,
+        _ => {}
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::classes::{ClassExtractor, class_directive, extract_classes};
+
+  #[test]
+  fn classes() {
+    let html = "<!DOCTYPE class><div id=class class=' a\tb ' CLASS=c><p class>x</p>\
+      <script>let a = '<p class=no>'</script><i class=d/>class=e";
+    let classes: Vec<_> = extract_classes(html)
+      .map(|(span, class)| {
+        assert_eq!(&html[span.start as usize..span.end as usize], class);
+        class
+      })
+      .collect();
+    assert_eq!(classes, ["a", "b", "c", "d/"]);
+
+    let classes: Vec<_> = ClassExtractor::new("<p class:on={a} class:='b' class=\"c\">")
+      .with_bindings(class_directive)
+      .map(|(_, class)| class)
+      .collect();
+    assert_eq!(classes, ["on", "c"]);
+  }
+}
@@ -0,0 +1,216 @@
+//! Reading [`HtmlParserOption`] from a configuration file, with the `serialize` feature.
+//!
+//! Options are read from a map with camelCase keys, all optional, e.g. in JSON:
+//!
+//! ```json
+//! {
+//!   "parseScript": true,
+//!   "embeddedLanguageTags": ["script", "style"],
+//!   "voidTags": ["br", "img", "x-icon"],
+//!   "preformattedTags": ["pre", "textarea"],
+//!   "preserveUnknown": false,
+//!   "maxDepth": 512,
+//!   "maxAttributes": 256,
+//!   "maxTokens": 1000000,
+//!   "budget": { "maxBytes": 10000000, "maxNodes": 100000, "maxDepth": 1024, "maxErrors": 100 },
+//!   "errors": { "implicitly-closed-element": "warn", "orphan-end-tag": "off" },
+//!   "strict": false,
+//!   "whitespace": "preserve",
+//!   "preserveComments": true,
+//!   "parseConditionalComments": false,
+//!   "normalizeCase": true,
+//!   "caseSensitive": false,
+//!   "cdataSections": false,
+//!   "typescriptScripts": false,
+//!   "parseStyleAttributes": false,
+//!   "htmlType": "auto",
+//!   "handlebars": false,
+//!   "templateDelimiters": [["{%", "%}"], ["{{", "}}"]],
+//!   "adoptionAgency": false,
+//!   "impliedTags": false,
+//!   "recoveryStrategy": "autoClose",
+//!   "selfClosing": "treatSelfClosingAsClosed"
+//! }
+//! ```
+//!
+//! The tags and case keys set the [dialect spec](crate::dialect::DialectSpec), from the one
+//! of HTML. Unknown keys are rejected, to catch typos.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, de::Error};
+
+use crate::{
+  budget::ParseBudget,
+  error::ErrorLevels,
+  option::{
+    HtmlParserOption, HtmlType, RecoveryStrategy, SelfClosing, TagSet, TemplateDelimiters,
+    Whitespace,
+  },
+};
+
+/// The options as written in a configuration file.
+#[derive(Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
+struct Config {
+  parse_script: Option<bool>,
+  embedded_language_tags: Option<Vec<String>>,
+  void_tags: Option<Vec<String>>,
+  preformatted_tags: Option<Vec<String>>,
+  preserve_unknown: Option<bool>,
+  max_depth: Option<usize>,
+  max_attributes: Option<usize>,
+  max_tokens: Option<usize>,
+  budget: Option<ParseBudget>,
+  errors: Option<BTreeMap<String, String>>,
+  strict: Option<bool>,
+  whitespace: Option<Whitespace>,
+  preserve_comments: Option<bool>,
+  parse_conditional_comments: Option<bool>,
+  normalize_case: Option<bool>,
+  case_sensitive: Option<bool>,
+  cdata_sections: Option<bool>,
+  typescript_scripts: Option<bool>,
+  parse_style_attributes: Option<bool>,
+  html_type: Option<HtmlType>,
+  handlebars: Option<bool>,
+  template_delimiters: Option<Vec<(String, String)>>,
+  adoption_agency: Option<bool>,
+  implied_tags: Option<bool>,
+  recovery_strategy: Option<RecoveryStrategy>,
+  self_closing: Option<SelfClosing>,
+}
+
+impl<'de> Deserialize<'de> for HtmlParserOption {
+  /// Read options from a configuration file, see [the format](self).
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let config = Config::deserialize(deserializer)?;
+    let mut options = Self::default();
+
+    if config.parse_script == Some(false) {
+      options.parse_script = None;
+    }
+    let spec = &mut options.dialect_spec;
+    if let Some(tags) = config.embedded_language_tags {
+      spec.raw_text_tags = TagSet::from_names(tags);
+    }
+    if let Some(tags) = config.void_tags {
+      spec.void_tags = TagSet::from_names(tags);
+    }
+    if let Some(tags) = config.preformatted_tags {
+      spec.preformatted_tags = TagSet::from_names(tags);
+    }
+    spec.normalize_case = config.normalize_case.unwrap_or(spec.normalize_case);
+    spec.case_sensitive = config.case_sensitive.unwrap_or(spec.case_sensitive);
+    spec.cdata_sections = config.cdata_sections.unwrap_or(spec.cdata_sections);
+    if let Some(errors) = config.errors {
+      options.errors = ErrorLevels::from_codes(
+        errors
+          .iter()
+          .map(|(code, level)| (code.as_str(), level.as_str())),
+      )
+      .map_err(|error| D::Error::custom(&error.message))?;
+    }
+    options.preserve_unknown = config.preserve_unknown.unwrap_or(options.preserve_unknown);
+    options.max_depth = config.max_depth.or(options.max_depth);
+    options.max_attributes = config.max_attributes.or(options.max_attributes);
+    options.max_tokens = config.max_tokens.or(options.max_tokens);
+    options.budget = config.budget.unwrap_or(options.budget);
+    options.strict = config.strict.unwrap_or(options.strict);
+    options.whitespace = config.whitespace.unwrap_or(options.whitespace);
+    options.preserve_comments = config
+      .preserve_comments
+      .unwrap_or(options.preserve_comments);
+    options.parse_conditional_comments = config
+      .parse_conditional_comments
+      .unwrap_or(options.parse_conditional_comments);
+    options.typescript_scripts = config
+      .typescript_scripts
+      .unwrap_or(options.typescript_scripts);
+    options.parse_style_attributes = config
+      .parse_style_attributes
+      .unwrap_or(options.parse_style_attributes);
+    options.html_type = config.html_type.unwrap_or(options.html_type);
+    options.handlebars = config.handlebars.unwrap_or(options.handlebars);
+    options.adoption_agency = config.adoption_agency.unwrap_or(options.adoption_agency);
+    options.implied_tags = config.implied_tags.unwrap_or(options.implied_tags);
+    options.recovery_strategy = config
+      .recovery_strategy
+      .unwrap_or(options.recovery_strategy);
+    options.self_closing = config.self_closing.unwrap_or(options.self_closing);
+    if let Some(delimiters) = config.template_delimiters {
+      options.template_delimiters = delimiters
+        .into_iter()
+        .map(|(open, close)| TemplateDelimiters {
+          open: open.into(),
+          close: close.into(),
+          balanced: false,
+        })
+        .collect();
+    }
+
+    Ok(options)
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    error::{ErrorLevel, HtmlParseErrorKind},
+    option::{
+      HtmlParserOption, HtmlType, RecoveryStrategy, SelfClosing, TemplateDelimiters, Whitespace,
+    },
+  };
+
+  #[test]
+  fn deserialize() {
+    let options: HtmlParserOption = serde_json::from_str(
+      r#"{
+        "parseScript": false,
+        "voidTags": ["x-icon"],
+        "caseSensitive": true,
+        "maxDepth": 8,
+        "typescriptScripts": true,
+        "budget": { "maxNodes": 100 },
+        "errors": { "orphan-end-tag": "warn" },
+        "whitespace": "trim",
+        "htmlType": "xhtml",
+        "templateDelimiters": [["<%", "%>"]],
+        "recoveryStrategy": "bubbleToSection",
+        "selfClosing": "spec"
+      }"#,
+    )
+    .unwrap();
+
+    assert!(options.parse_script.is_none());
+    let spec = &options.dialect_spec;
+    assert!(spec.void_tags.contains("X-ICON"));
+    assert!(!spec.void_tags.contains("br"));
+    assert!(spec.raw_text_tags.contains("script"));
+    assert!(spec.preformatted_tags.contains("pre"));
+    assert!(spec.case_sensitive && spec.normalize_case);
+    assert_eq!(options.max_depth, Some(8));
+    assert!(options.typescript_scripts);
+    assert_eq!(options.budget.max_nodes, Some(100));
+    assert_eq!(
+      options.errors.get(HtmlParseErrorKind::OrphanEndTag),
+      ErrorLevel::Warn
+    );
+    assert_eq!(options.whitespace, Whitespace::Trim);
+    assert_eq!(options.html_type, HtmlType::Xhtml);
+    assert!(options.preserve_comments);
+    assert_eq!(options.template_delimiters, [TemplateDelimiters::ERB]);
+    assert_eq!(options.recovery_strategy, RecoveryStrategy::BubbleToSection);
+    assert_eq!(options.self_closing, SelfClosing::Spec);
+
+    let error = |json| {
+      serde_json::from_str::<HtmlParserOption>(json)
+        .unwrap_err()
+        .to_string()
+    };
+    assert!(error(r#"{ "parse_script": false }"#).starts_with("unknown field `parse_script`"));
+    assert!(error(r#"{ "errors": { "x": "warn" } }"#).starts_with("Unknown parse error code `x`"));
+    assert!(error(r#"{ "budget": { "maxBites": 1 } }"#).starts_with("unknown field `maxBites`"));
+  }
+}
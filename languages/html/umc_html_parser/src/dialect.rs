@@ -0,0 +1,299 @@
+//! The dialect of a document, declared by its DOCTYPE, see [`Dialect`].
+//!
+//! With [`HtmlType::Auto`](crate::option::HtmlType::Auto), the parser switches its rules
+//! to the dialect of the first DOCTYPE of the document.
+//!
+//! The rules of a markup language, like its void tags, are declared by a [`DialectSpec`],
+//! to parse dialects like XHTML or XML formats without forking the parser.
+
+use umc_html_ast::{Doctype, Node, Program};
+use umc_parser::ParseResult;
+
+use crate::option::{HTML5_PREFORMATTED_TAGS, TagSet};
+
+/// The rules of a markup dialect, consumed by the lexer and the parser, see
+/// [`dialect_spec`](crate::option::HtmlParserOption::dialect_spec).
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_ast::Node;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_parser::dialect::DialectSpec;
+/// use umc_html_parser::option::{HtmlParserOption, TagSet};
+/// use umc_parser::Parser;
+///
+/// // a feed format, whose `<link>` has content and whose `<summary>` is raw text
+/// let spec = DialectSpec {
+///   raw_text_tags: TagSet::Custom(&["summary"]),
+///   ..DialectSpec::XML
+/// };
+/// let options = HtmlParserOption::builder().dialect_spec(spec).build();
+///
+/// let allocator = Allocator::default();
+/// let source_text = "<Entry><link>https://a.com</link><summary><b>Hi</b></summary></Entry>";
+/// let result = Parser::html(&allocator, source_text).with_options(options).parse();
+///
+/// assert!(result.errors.is_empty());
+/// let Node::Element(entry) = &result.program[0] else { unreachable!() };
+/// assert_eq!(entry.name(), "Entry");
+/// let Node::Element(link) = &entry.children[0] else { unreachable!() };
+/// assert_eq!(link.children.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DialectSpec {
+  /// Void tags, which have no content nor closing tag, the void elements of HTML by default.
+  pub void_tags: TagSet,
+  /// Tags whose content is raw text, like an embedded language, lexed up to their closing
+  /// tag, `<script>` and `<style>` by default.
+  pub raw_text_tags: TagSet,
+  /// Tags whose whitespace is significant, kept whatever the
+  /// [`whitespace`](crate::option::HtmlParserOption::whitespace) option, `<pre>` and
+  /// `<textarea>` by default.
+  pub preformatted_tags: TagSet,
+  /// Lowercase the [normalized names](umc_html_ast::Element::name) of elements and
+  /// attributes, as HTML names are case-insensitive.
+  ///
+  /// The names as written stay available as [`raw_name`](umc_html_ast::Element::raw_name).
+  /// The [streaming parser](crate::stream::HtmlStreamParser) always keeps the case.
+  pub normalize_case: bool,
+  /// Whether names are case-sensitive, like in XML: closing tags only match opening tags
+  /// with the same case, and names are never lowercased.
+  pub case_sensitive: bool,
+  /// Lex `<![CDATA[...]]>` sections whole, up to their `]]>`, like in XML, instead of as
+  /// bogus comments ending at the first `>`. Sections are
+  /// [bogus comments](umc_html_ast::Comment::bogus) either way, with the value
+  /// `[CDATA[...]]`.
+  pub cdata_sections: bool,
+}
+
+impl DialectSpec {
+  /// HTML.
+  pub const HTML5: Self = Self {
+    void_tags: TagSet::Html5Void,
+    raw_text_tags: TagSet::Html5RawText,
+    preformatted_tags: TagSet::Custom(HTML5_PREFORMATTED_TAGS),
+    normalize_case: true,
+    case_sensitive: false,
+    cdata_sections: false,
+  };
+
+  /// XHTML, HTML with case-sensitive names and CDATA sections.
+  pub const XHTML: Self = Self {
+    normalize_case: false,
+    case_sensitive: true,
+    cdata_sections: true,
+    ..Self::HTML5
+  };
+
+  /// A generic XML format, with case-sensitive names and CDATA sections, and without void,
+  /// raw text nor preformatted tags.
+  pub const XML: Self = Self {
+    void_tags: TagSet::Custom(&[]),
+    raw_text_tags: TagSet::Custom(&[]),
+    preformatted_tags: TagSet::Custom(&[]),
+    normalize_case: false,
+    case_sensitive: true,
+    cdata_sections: true,
+  };
+}
+
+impl Default for DialectSpec {
+  fn default() -> Self {
+    Self::HTML5
+  }
+}
+
+/// The dialect of an HTML document.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_parser::dialect::{Dialect, DetectDialect};
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let result = Parser::html(&allocator, "<!DOCTYPE html><p>Hello</p>").parse();
+/// assert_eq!(result.dialect(), Dialect::Html5);
+///
+/// let result = Parser::html(&allocator, "<p>Hello</p>").parse();
+/// assert_eq!(result.dialect(), Dialect::Quirks);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+  /// HTML, declared by `<!DOCTYPE html>` or another standards mode DOCTYPE
+  #[default]
+  Html5,
+  /// XHTML, declared by the DOCTYPE of XHTML 1.0 or 1.1. Names are case-sensitive.
+  Xhtml,
+  /// HTML in [quirks mode](https://quirks.spec.whatwg.org/), for documents without
+  /// DOCTYPE or with a legacy one, parsed as HTML.
+  Quirks,
+}
+
+/// Prefixes of the public identifiers putting a document in quirks mode, lowercase.
+/// https://html.spec.whatwg.org/multipage/parsing.html#the-initial-insertion-mode
+const QUIRKS_PUBLIC_PREFIXES: &[&str] = &[
+  "+//silmaril//dtd html pro v0r11 19970101//",
+  "-//as//dtd html 3.0 aswedit + extensions//",
+  "-//advasoft ltd//dtd html 3.0 aswedit + extensions//",
+  "-//ietf//dtd html 2.0 level 1//",
+  "-//ietf//dtd html 2.0 level 2//",
+  "-//ietf//dtd html 2.0 strict level 1//",
+  "-//ietf//dtd html 2.0 strict level 2//",
+  "-//ietf//dtd html 2.0 strict//",
+  "-//ietf//dtd html 2.0//",
+  "-//ietf//dtd html 2.1e//",
+  "-//ietf//dtd html 3.0//",
+  "-//ietf//dtd html 3.2 final//",
+  "-//ietf//dtd html 3.2//",
+  "-//ietf//dtd html 3//",
+  "-//ietf//dtd html level 0//",
+  "-//ietf//dtd html level 1//",
+  "-//ietf//dtd html level 2//",
+  "-//ietf//dtd html level 3//",
+  "-//ietf//dtd html strict level 0//",
+  "-//ietf//dtd html strict level 1//",
+  "-//ietf//dtd html strict level 2//",
+  "-//ietf//dtd html strict level 3//",
+  "-//ietf//dtd html strict//",
+  "-//ietf//dtd html//",
+  "-//metrius//dtd metrius presentational//",
+  "-//microsoft//dtd internet explorer 2.0 html strict//",
+  "-//microsoft//dtd internet explorer 2.0 html//",
+  "-//microsoft//dtd internet explorer 2.0 tables//",
+  "-//microsoft//dtd internet explorer 3.0 html strict//",
+  "-//microsoft//dtd internet explorer 3.0 html//",
+  "-//microsoft//dtd internet explorer 3.0 tables//",
+  "-//netscape comm. corp.//dtd html//",
+  "-//netscape comm. corp.//dtd strict html//",
+  "-//o'reilly and associates//dtd html 2.0//",
+  "-//o'reilly and associates//dtd html extended 1.0//",
+  "-//o'reilly and associates//dtd html extended relaxed 1.0//",
+  "-//sq//dtd html 2.0 hotmetal + extensions//",
+  "-//softquad software//dtd hotmetal pro 6.0::19990601::extensions to html 4.0//",
+  "-//softquad//dtd hotmetal pro 4.0::19971010::extensions to html 4.0//",
+  "-//spyglass//dtd html 2.0 extended//",
+  "-//sun microsystems corp.//dtd hotjava html//",
+  "-//sun microsystems corp.//dtd hotjava strict html//",
+  "-//w3c//dtd html 3 1995-03-24//",
+  "-//w3c//dtd html 3.2 draft//",
+  "-//w3c//dtd html 3.2 final//",
+  "-//w3c//dtd html 3.2//",
+  "-//w3c//dtd html 3.2s draft//",
+  "-//w3c//dtd html 4.0 frameset//",
+  "-//w3c//dtd html 4.0 transitional//",
+  "-//w3c//dtd html experimental 19960712//",
+  "-//w3c//dtd html experimental 970421//",
+  "-//w3c//dtd w3 html//",
+  "-//w3o//dtd w3 html 3.0//",
+  "-//webtechs//dtd mozilla html 2.0//",
+  "-//webtechs//dtd mozilla html//",
+];
+
+/// Public identifiers putting a document in quirks mode, lowercase.
+const QUIRKS_PUBLIC_IDS: &[&str] = &[
+  "-//w3o//dtd w3 html strict 3.0//en//",
+  "-/w3c/dtd html 4.0 transitional/en",
+  "html",
+];
+
+/// Prefixes of the public identifiers putting a document in quirks mode when it has no
+/// system identifier, lowercase.
+const QUIRKS_PUBLIC_PREFIXES_WITHOUT_SYSTEM: &[&str] = &[
+  "-//w3c//dtd html 4.01 frameset//",
+  "-//w3c//dtd html 4.01 transitional//",
+];
+
+const QUIRKS_SYSTEM_ID: &str = "http://www.ibm.com/data/dtd/v11/ibmxhtml1-transitional.dtd";
+
+const XHTML_PUBLIC_PREFIX: &str = "-//w3c//dtd xhtml ";
+
+impl Dialect {
+  /// Get the dialect declared by a DOCTYPE, [`Quirks`](Self::Quirks) without DOCTYPE.
+  pub fn from_doctype(doctype: Option<&Doctype>) -> Self {
+    let Some(doctype) = doctype else {
+      return Self::Quirks;
+    };
+    let mut words = doctype
+      .attributes
+      .iter()
+      .map(|attribute| attribute.key.value);
+    if !words
+      .next()
+      .is_some_and(|name| name.eq_ignore_ascii_case("html"))
+    {
+      return Self::Quirks;
+    }
+    let (public_id, system_id) = match words.next() {
+      Some(keyword) if keyword.eq_ignore_ascii_case("public") => {
+        let Some(public_id) = words.next().and_then(unquote) else {
+          return Self::Quirks;
+        };
+        (Some(public_id), words.next().and_then(unquote))
+      }
+      Some(keyword) if keyword.eq_ignore_ascii_case("system") => {
+        let Some(system_id) = words.next().and_then(unquote) else {
+          return Self::Quirks;
+        };
+        (None, Some(system_id))
+      }
+      Some(_) => return Self::Quirks,
+      None => (None, None),
+    };
+
+    if system_id.is_some_and(|id| id.eq_ignore_ascii_case(QUIRKS_SYSTEM_ID)) {
+      return Self::Quirks;
+    }
+    let Some(public_id) = public_id.map(str::to_ascii_lowercase) else {
+      return Self::Html5;
+    };
+    let quirks = QUIRKS_PUBLIC_IDS.contains(&public_id.as_str())
+      || QUIRKS_PUBLIC_PREFIXES
+        .iter()
+        .any(|prefix| public_id.starts_with(prefix))
+      || system_id.is_none()
+        && QUIRKS_PUBLIC_PREFIXES_WITHOUT_SYSTEM
+          .iter()
+          .any(|prefix| public_id.starts_with(prefix));
+    if quirks {
+      Self::Quirks
+    } else if public_id.starts_with(XHTML_PUBLIC_PREFIX) {
+      Self::Xhtml
+    } else {
+      Self::Html5
+    }
+  }
+
+  /// Get the dialect declared by the first DOCTYPE of a program.
+  pub fn detect(program: &[Node]) -> Self {
+    let doctype = program.iter().find_map(|node| match node {
+      Node::Doctype(doctype) => Some(&**doctype),
+      _ => None,
+    });
+    Self::from_doctype(doctype)
+  }
+}
+
+/// Get the content of a quoted DOCTYPE identifier, `None` if it is not quoted.
+fn unquote(word: &str) -> Option<&str> {
+  let quote = word.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+  let content = &word[1..];
+  Some(content.strip_suffix(quote).unwrap_or(content))
+}
+
+/// Get the dialect declared by a parsed document.
+pub trait DetectDialect {
+  /// The dialect declared by the first DOCTYPE of the document, the one
+  /// [`HtmlType::Auto`](crate::option::HtmlType::Auto) parses it with.
+  fn dialect(&self) -> Dialect;
+}
+
+impl DetectDialect for ParseResult<Program<'_>> {
+  fn dialect(&self) -> Dialect {
+    Dialect::detect(&self.program)
+  }
+}
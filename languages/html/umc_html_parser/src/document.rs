@@ -0,0 +1,106 @@
+//! A parse result owning its allocator and source text, see [`ParsedDocument`].
+
+use std::ptr;
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Program;
+use umc_parser::{Parser, metrics::ParseMetrics};
+
+use crate::{CreateHtml, dialect::Dialect, option::HtmlParserOption};
+
+/// A parsed HTML document, owning the allocator of its AST and its source text.
+///
+/// A [`ParseResult`](umc_parser::ParseResult) borrows its allocator and source text, so it
+/// cannot be returned from the function owning them or kept in a cache. A document owns
+/// them instead, and lends the program through [`program`](Self::program).
+///
+/// ## Example
+///
+/// ```
+/// use std::collections::HashMap;
+///
+/// use umc_html_ast::Node;
+/// use umc_html_parser::document::ParsedDocument;
+///
+/// let mut cache = HashMap::new();
+/// for path in ["a.html", "b.html"] {
+///   let source_text = format!("<title>{path}</title>");
+///   cache.insert(path, ParsedDocument::parse(source_text));
+/// }
+///
+/// let document = &cache["b.html"];
+/// assert!(document.errors().is_empty());
+/// assert!(matches!(&document.program()[0], Node::Element(title) if title.name() == "title"));
+/// ```
+pub struct ParsedDocument {
+  // The fields borrow from the ones declared after them, and are dropped first.
+  program: Program<'static>,
+  errors: Vec<OxcDiagnostic>,
+  metrics: Option<ParseMetrics>,
+  source_text: Box<str>,
+  _allocator: Box<Allocator>,
+}
+
+impl ParsedDocument {
+  /// Parse a document with the default options.
+  pub fn parse(source_text: impl Into<String>) -> Self {
+    Self::parse_with_options(source_text, HtmlParserOption::default())
+  }
+
+  /// Parse a document.
+  #[allow(unsafe_code)]
+  pub fn parse_with_options(source_text: impl Into<String>, options: HtmlParserOption) -> Self {
+    let allocator = Box::new(Allocator::default());
+    let source_text: Box<str> = source_text.into().into_boxed_str();
+    // SAFETY: the allocator and the source text are boxed, so they don't move with the
+    // document, and they outlive the AST, see the field order.
+    let (allocator_ref, source_ref) = unsafe {
+      (
+        &*ptr::from_ref::<Allocator>(&*allocator),
+        &*ptr::from_ref::<str>(&*source_text),
+      )
+    };
+
+    let result = Parser::html(allocator_ref, source_ref)
+      .with_options(options)
+      .parse();
+
+    Self {
+      program: result.program,
+      errors: result.errors,
+      metrics: result.metrics,
+      source_text,
+      _allocator: allocator,
+    }
+  }
+
+  /// The parsed program, borrowed from the document.
+  #[allow(unsafe_code)]
+  pub const fn program(&self) -> &Program<'_> {
+    // SAFETY: the nodes live as long as the document. The program is not covariant, since
+    // lazy scripts parse into a cell, so its lifetime is shortened with a cast. The cell is
+    // private, and only ever holds a program allocated in the allocator of the document.
+    unsafe { &*ptr::from_ref(&self.program).cast::<Program<'_>>() }
+  }
+
+  /// The source text the document was parsed from, which its spans are offsets into.
+  pub fn source_text(&self) -> &str {
+    &self.source_text
+  }
+
+  /// The errors and warnings reported while parsing the document.
+  pub fn errors(&self) -> &[OxcDiagnostic] {
+    &self.errors
+  }
+
+  /// The dialect declared by the DOCTYPE of the document, see [`Dialect::detect`].
+  pub fn dialect(&self) -> Dialect {
+    Dialect::detect(self.program())
+  }
+
+  /// Cost of the parse, `None` unless the options asked to collect it
+  pub const fn metrics(&self) -> Option<&ParseMetrics> {
+    self.metrics.as_ref()
+  }
+}
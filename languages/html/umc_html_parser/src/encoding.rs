@@ -0,0 +1,434 @@
+//! Character encoding detection and decoding.
+//!
+//! The parser works on `&str`, so documents in other encodings have to be decoded first.
+//! This module implements the [prescan](https://html.spec.whatwg.org/multipage/parsing.html#prescan-a-byte-stream-to-determine-its-encoding)
+//! of the HTML standard, which finds a `<meta charset>` or `<meta http-equiv="content-type">`
+//! declaration in the first 1024 bytes, and decodes input into the arena with [`encoding_rs`].
+
+use oxc_allocator::Allocator;
+
+pub use encoding_rs::Encoding;
+use encoding_rs::{UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252, X_USER_DEFINED};
+
+/// Number of bytes examined by [`prescan`].
+pub const PRESCAN_LENGTH: usize = 1024;
+
+/// Find the encoding declared by a `<meta>` element in the first 1024 bytes of `bytes`.
+///
+/// ## Example
+///
+/// ```
+/// use umc_html_parser::encoding::prescan;
+///
+/// let encoding = prescan(br#"<!doctype html><meta charset="Shift_JIS">"#);
+/// assert_eq!(encoding.map(|e| e.name()), Some("Shift_JIS"));
+/// ```
+pub fn prescan(bytes: &[u8]) -> Option<&'static Encoding> {
+  let input = &bytes[..bytes.len().min(PRESCAN_LENGTH)];
+  let mut prescan = Prescan { input, pos: 0 };
+  prescan.run().ok().flatten()
+}
+
+/// Decode `bytes` with `encoding` into the arena, replacing malformed sequences with U+FFFD.
+///
+/// A byte order mark is not handled, use [`Encoding::for_bom`] to detect it beforehand.
+/// UTF-8 input which is valid is returned as is, without copying.
+pub fn decode<'a>(
+  allocator: &'a Allocator,
+  bytes: &'a [u8],
+  encoding: &'static Encoding,
+) -> &'a str {
+  match encoding.decode_without_bom_handling(bytes).0 {
+    std::borrow::Cow::Borrowed(text) => text,
+    std::borrow::Cow::Owned(text) => allocator.alloc_str(&text),
+  }
+}
+
+/// Decode `bytes` with the encoding declared in the document, or `fallback` if there is none.
+///
+/// Returns the decoded text and the encoding used, so the caller can report it.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::encoding::{Encoding, decode_declared};
+///
+/// let allocator = Allocator::default();
+/// let bytes = b"<meta charset=windows-1252><p>caf\xE9</p>";
+/// let utf8 = Encoding::for_label(b"utf-8").unwrap();
+///
+/// let (text, encoding) = decode_declared(&allocator, bytes, utf8);
+/// assert_eq!(text, "<meta charset=windows-1252><p>caf\u{e9}</p>");
+/// assert_eq!(encoding.name(), "windows-1252");
+/// ```
+pub fn decode_declared<'a>(
+  allocator: &'a Allocator,
+  bytes: &'a [u8],
+  fallback: &'static Encoding,
+) -> (&'a str, &'static Encoding) {
+  let encoding = prescan(bytes).unwrap_or(fallback);
+  (decode(allocator, bytes, encoding), encoding)
+}
+
+/// Determine the encoding of a document, following the
+/// [encoding sniffing algorithm](https://html.spec.whatwg.org/multipage/parsing.html#encoding-sniffing-algorithm).
+///
+/// In order: a byte order mark, the `hint` (e.g. the charset of an HTTP `Content-Type`),
+/// the [`prescan`], UTF-8 if the input is valid UTF-8, and finally windows-1252.
+/// Returns the encoding and the length of the byte order mark, if any.
+///
+/// ## Example
+///
+/// ```
+/// use umc_html_parser::encoding::sniff;
+///
+/// let (encoding, bom_length) = sniff(b"\xEF\xBB\xBF<p>", None);
+/// assert_eq!((encoding.name(), bom_length), ("UTF-8", 3));
+///
+/// let (encoding, _) = sniff(b"<p>caf\xE9</p>", None);
+/// assert_eq!(encoding.name(), "windows-1252");
+/// ```
+pub fn sniff(bytes: &[u8], hint: Option<&'static Encoding>) -> (&'static Encoding, usize) {
+  if let Some((encoding, bom_length)) = Encoding::for_bom(bytes) {
+    return (encoding, bom_length);
+  }
+
+  let encoding = hint.or_else(|| prescan(bytes)).unwrap_or_else(|| {
+    if str::from_utf8(bytes).is_ok() {
+      UTF_8
+    } else {
+      WINDOWS_1252
+    }
+  });
+  (encoding, 0)
+}
+
+/// Decode a document of unknown encoding into the arena.
+///
+/// The encoding is determined by [`sniff`], and a byte order mark is stripped.
+/// Returns the decoded text and the encoding used.
+pub fn decode_document<'a>(
+  allocator: &'a Allocator,
+  bytes: &'a [u8],
+  hint: Option<&'static Encoding>,
+) -> (&'a str, &'static Encoding) {
+  let (encoding, bom_length) = sniff(bytes, hint);
+  (decode(allocator, &bytes[bom_length..], encoding), encoding)
+}
+
+/// Extract the encoding label from the `content` of a `<meta http-equiv="content-type">`,
+/// e.g. `utf-8` from `text/html; charset=utf-8`.
+///
+/// Follows the [extraction algorithm](https://html.spec.whatwg.org/multipage/urls-and-fetching.html#algorithm-for-extracting-a-character-encoding-from-a-meta-element).
+///
+/// ## Example
+///
+/// ```
+/// use umc_html_parser::encoding::extract_charset;
+///
+/// assert_eq!(extract_charset("text/html; Charset = \"utf-8\""), Some("utf-8"));
+/// assert_eq!(extract_charset("text/html"), None);
+/// ```
+pub fn extract_charset(content: &str) -> Option<&str> {
+  // the result is delimited by ASCII bytes, so it is always valid UTF-8
+  extract_charset_bytes(content.as_bytes()).and_then(|label| std::str::from_utf8(label).ok())
+}
+
+fn extract_charset_bytes(content: &[u8]) -> Option<&[u8]> {
+  let mut pos = 0;
+
+  loop {
+    let found = content[pos..]
+      .windows(7)
+      .position(|window| window.eq_ignore_ascii_case(b"charset"))?;
+    pos += found + 7;
+
+    while content.get(pos).is_some_and(|c| is_whitespace(*c)) {
+      pos += 1;
+    }
+    if content.get(pos) != Some(&b'=') {
+      continue;
+    }
+    pos += 1;
+    while content.get(pos).is_some_and(|c| is_whitespace(*c)) {
+      pos += 1;
+    }
+
+    return match content.get(pos) {
+      Some(quote @ (b'"' | b'\'')) => {
+        let rest = &content[pos + 1..];
+        rest.iter().position(|c| c == quote).map(|end| &rest[..end])
+      }
+      Some(_) => {
+        let rest = &content[pos..];
+        let end = rest
+          .iter()
+          .position(|c| *c == b';' || is_whitespace(*c))
+          .unwrap_or(rest.len());
+        Some(&rest[..end])
+      }
+      None => None,
+    };
+  }
+}
+
+/// Get the encoding for a label found by the prescan.
+fn prescan_encoding(label: &[u8]) -> Option<&'static Encoding> {
+  let encoding = Encoding::for_label(label)?;
+  Some(if encoding == UTF_16BE || encoding == UTF_16LE {
+    UTF_8
+  } else if encoding == X_USER_DEFINED {
+    WINDOWS_1252
+  } else {
+    encoding
+  })
+}
+
+const fn is_whitespace(c: u8) -> bool {
+  matches!(c, b'\t' | b'\n' | b'\x0C' | b'\r' | b' ')
+}
+
+/// The end of the input was reached, which aborts the prescan.
+struct Eof;
+
+/// Lowercased name and value of an attribute found by the prescan.
+type SniffedAttribute = (Vec<u8>, Vec<u8>);
+
+struct Prescan<'b> {
+  input: &'b [u8],
+  pos: usize,
+}
+
+impl Prescan<'_> {
+  fn peek(&self) -> Result<u8, Eof> {
+    self.input.get(self.pos).copied().ok_or(Eof)
+  }
+
+  fn starts_with_ignore_case(&self, prefix: &[u8]) -> bool {
+    self
+      .input
+      .get(self.pos..self.pos + prefix.len())
+      .is_some_and(|bytes| bytes.eq_ignore_ascii_case(prefix))
+  }
+
+  /// Advance to the next `byte`.
+  fn advance_to(&mut self, byte: u8) -> Result<(), Eof> {
+    let found = self.input[self.pos..]
+      .iter()
+      .position(|c| *c == byte)
+      .ok_or(Eof)?;
+    self.pos += found;
+    Ok(())
+  }
+
+  fn run(&mut self) -> Result<Option<&'static Encoding>, Eof> {
+    while self.pos < self.input.len() {
+      if self.starts_with_ignore_case(b"<!--") {
+        let found = self.input[self.pos + 2..]
+          .windows(3)
+          .position(|window| window == b"-->")
+          .ok_or(Eof)?;
+        self.pos += 2 + found + 2;
+      } else if self.starts_with_ignore_case(b"<meta")
+        && self
+          .input
+          .get(self.pos + 5)
+          .is_some_and(|c| is_whitespace(*c) || *c == b'/')
+      {
+        self.pos += 5;
+        if let Some(encoding) = self.meta()? {
+          return Ok(Some(encoding));
+        }
+      } else if self.input[self.pos] == b'<'
+        && (self
+          .input
+          .get(self.pos + 1)
+          .is_some_and(u8::is_ascii_alphabetic)
+          || (self.input.get(self.pos + 1) == Some(&b'/')
+            && self
+              .input
+              .get(self.pos + 2)
+              .is_some_and(u8::is_ascii_alphabetic)))
+      {
+        while !is_whitespace(self.peek()?) && self.peek()? != b'>' {
+          self.pos += 1;
+        }
+        while self.attribute()?.is_some() {}
+      } else if self.starts_with_ignore_case(b"<!")
+        || self.starts_with_ignore_case(b"</")
+        || self.starts_with_ignore_case(b"<?")
+      {
+        self.advance_to(b'>')?;
+      }
+
+      self.pos += 1;
+    }
+
+    Ok(None)
+  }
+
+  /// Process the attributes of a `<meta>` element.
+  fn meta(&mut self) -> Result<Option<&'static Encoding>, Eof> {
+    let mut seen: Vec<Vec<u8>> = Vec::new();
+    let mut got_pragma = false;
+    let mut need_pragma = None;
+    let mut charset = None;
+
+    while let Some((name, value)) = self.attribute()? {
+      if seen.contains(&name) {
+        continue;
+      }
+
+      match name.as_slice() {
+        b"http-equiv" if value == b"content-type" => got_pragma = true,
+        b"content" if charset.is_none() => {
+          if let Some(label) = extract_charset_bytes(&value) {
+            charset = prescan_encoding(label);
+            need_pragma = Some(true);
+          }
+        }
+        b"charset" => {
+          charset = prescan_encoding(&value);
+          need_pragma = Some(false);
+        }
+        _ => {}
+      }
+      seen.push(name);
+    }
+
+    Ok(match need_pragma {
+      Some(true) if !got_pragma => None,
+      Some(_) => charset,
+      None => None,
+    })
+  }
+
+  /// [Get an attribute](https://html.spec.whatwg.org/multipage/parsing.html#concept-get-attributes-when-sniffing),
+  /// returning its lowercased name and value.
+  fn attribute(&mut self) -> Result<Option<SniffedAttribute>, Eof> {
+    while is_whitespace(self.peek()?) || self.peek()? == b'/' {
+      self.pos += 1;
+    }
+    if self.peek()? == b'>' {
+      return Ok(None);
+    }
+
+    let mut name = Vec::new();
+    let mut value = Vec::new();
+
+    // attribute name
+    loop {
+      let c = self.peek()?;
+      match c {
+        b'=' if !name.is_empty() => {
+          self.pos += 1;
+          break;
+        }
+        c if is_whitespace(c) => {
+          while is_whitespace(self.peek()?) {
+            self.pos += 1;
+          }
+          if self.peek()? != b'=' {
+            return Ok(Some((name, value)));
+          }
+          self.pos += 1;
+          break;
+        }
+        b'/' | b'>' => return Ok(Some((name, value))),
+        c => name.push(c.to_ascii_lowercase()),
+      }
+      self.pos += 1;
+    }
+
+    // attribute value
+    while is_whitespace(self.peek()?) {
+      self.pos += 1;
+    }
+    match self.peek()? {
+      quote @ (b'"' | b'\'') => {
+        self.pos += 1;
+        loop {
+          let c = self.peek()?;
+          self.pos += 1;
+          if c == quote {
+            return Ok(Some((name, value)));
+          }
+          value.push(c.to_ascii_lowercase());
+        }
+      }
+      b'>' => return Ok(Some((name, value))),
+      _ => {}
+    }
+    loop {
+      let c = self.peek()?;
+      if is_whitespace(c) || c == b'>' {
+        return Ok(Some((name, value)));
+      }
+      value.push(c.to_ascii_lowercase());
+      self.pos += 1;
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::{Encoding, prescan, sniff};
+
+  fn name(bytes: &[u8]) -> Option<&'static str> {
+    prescan(bytes).map(Encoding::name)
+  }
+
+  #[test]
+  fn meta_charset() {
+    assert_eq!(name(b"<meta charset=utf-8>"), Some("UTF-8"));
+    assert_eq!(name(b"<META CHARSET='ISO-8859-1'>"), Some("windows-1252"));
+    assert_eq!(name(b"<meta charset=utf-16le>"), Some("UTF-8"));
+    assert_eq!(name(b"<meta charset=unknown>"), None);
+  }
+
+  #[test]
+  fn http_equiv() {
+    assert_eq!(
+      name(br#"<meta http-equiv="Content-Type" content="text/html; charset=euc-jp">"#),
+      Some("EUC-JP")
+    );
+    assert_eq!(
+      name(br#"<meta content="text/html; charset=euc-jp" http-equiv=content-type>"#),
+      Some("EUC-JP")
+    );
+    // `content` without the pragma is ignored
+    assert_eq!(name(br#"<meta content="text/html; charset=euc-jp">"#), None);
+  }
+
+  #[test]
+  fn skipped_constructs() {
+    assert_eq!(
+      name(b"<!-- <meta charset=gbk> --><meta charset=big5>"),
+      Some("Big5")
+    );
+    assert_eq!(
+      name(br#"<div title="<meta charset=gbk>"><meta charset=big5>"#),
+      Some("Big5")
+    );
+    assert_eq!(name(b"<!-- <meta charset=gbk>"), None);
+  }
+
+  #[test]
+  fn sniff_order() {
+    let koi8 = Encoding::for_label(b"koi8-r");
+    let name = |bytes: &[u8], hint| sniff(bytes, hint).0.name();
+
+    assert_eq!(name(b"\xFF\xFE<\0p\0>\0", koi8), "UTF-16LE");
+    assert_eq!(name(b"<meta charset=gbk>", koi8), "KOI8-R");
+    assert_eq!(name(b"<meta charset=gbk>", None), "GBK");
+    assert_eq!(name("<p>caf\u{e9}</p>".as_bytes(), None), "UTF-8");
+  }
+
+  #[test]
+  fn limit() {
+    let mut bytes = vec![b' '; 1024];
+    bytes.extend_from_slice(b"<meta charset=gbk>");
+    assert_eq!(name(&bytes), None);
+  }
+}
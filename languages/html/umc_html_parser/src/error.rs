@@ -0,0 +1,319 @@
+//! Kinds of parse errors.
+//!
+//! Every diagnostic reported by the parser carries an error code made of
+//! [`ERROR_CODE_SCOPE`] and the code of its [`HtmlParseErrorKind`], e.g.
+//! `html-parser(unclosed-element)`, so errors can be filtered without matching their
+//! message. Syntax errors of scripts keep the code given by `oxc_parser`, if any.
+
+use std::{borrow::Cow, collections::HashMap, str::FromStr};
+
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+
+use crate::budget::BudgetExceeded;
+
+/// Scope of the error code of every parse error.
+pub const ERROR_CODE_SCOPE: &str = "html-parser";
+
+/// The kind of a parse error.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::{CreateHtml, error::HtmlParseErrorKind};
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<div></span>");
+/// let kinds: Vec<_> = parser
+///   .parse()
+///   .errors
+///   .iter()
+///   .filter_map(HtmlParseErrorKind::from_diagnostic)
+///   .collect();
+///
+/// assert_eq!(
+///   kinds,
+///   [HtmlParseErrorKind::OrphanEndTag, HtmlParseErrorKind::UnclosedElement]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HtmlParseErrorKind {
+  /// An element still open at the end of the document, `<div>`
+  UnclosedElement,
+  /// An element closed by the closing tag of an ancestor, `<div><p></div>`
+  ImplicitlyClosedElement,
+  /// A closing tag without matching opening tag, `</span>`
+  OrphanEndTag,
+  /// A closing tag of a formatting element with other elements still open in it,
+  /// `<b><i></b>`, with [`adoption_agency`](crate::option::HtmlParserOption::adoption_agency)
+  MisnestedFormattingElement,
+  /// A closing tag of a void element, `<meta></meta>`. The closing tag is dropped like an
+  /// [orphan](Self::OrphanEndTag) one.
+  VoidElementEndTag,
+  /// Content written in a void element, `Text` in `<meta>Text</meta>`, reported with its
+  /// [closing tag](Self::VoidElementEndTag). The content is parsed after the void element,
+  /// in its parent, as browsers do.
+  VoidElementChildren,
+  /// A comment or doctype not terminated before the end of the document, `<!-- a`
+  UnterminatedComment,
+  /// An empty comment closed right after its `<!--`, `<!-->` or `<!--->`
+  AbruptClosingOfEmptyComment,
+  /// A comment closed by `--!>` rather than `-->`, `<!-- a --!>`
+  IncorrectlyClosedComment,
+  /// A quoted attribute value not terminated before the end of the document, `<a href="/`
+  UnterminatedAttributeValue,
+  /// The content of `<script>` or `<style>` without closing tag
+  UnterminatedRawText,
+  /// `=` without a value, `<div class=>`
+  MissingAttributeValue,
+  /// `"`, `'`, `<`, `=` or `` ` `` in an unquoted attribute value, `<a title=a'b>`
+  UnexpectedCharacterInUnquotedValue,
+  /// A `/>` ignored on an element which is not void, `<div/>`, with
+  /// [`SelfClosing::Spec`](crate::option::SelfClosing::Spec). A warning by default.
+  TrailingSolidus,
+  /// Attributes in a closing tag, `</div class="a">`, which are ignored
+  EndTagWithAttributes,
+  /// A doctype after the content of the document, `<p>Text</p><!DOCTYPE html>`. Only
+  /// comments, [directives](umc_html_ast::Directive) and whitespace go before it.
+  ContentBeforeDoctype,
+  /// An `<html>` or `<body>` opening tag in the body, `<p>a<body class="b">`, with
+  /// [`implied_tags`](crate::option::HtmlParserOption::implied_tags). The tag is dropped,
+  /// and the attributes the open element is missing are added to it.
+  NestedDocumentElement,
+  /// A Handlebars tag not terminated before the end of the document, `{{name`
+  UnterminatedMustache,
+  /// A Handlebars block still open at the end of its parent, `{{#if a}}`
+  UnclosedBlock,
+  /// A Handlebars closing tag without matching block, `{{/if}}`
+  OrphanBlockEnd,
+  /// A template tag not terminated before the end of the document, `{% if user`
+  UnterminatedTemplateTag,
+  /// A syntax error in a `<script>`, without a code of its own
+  ScriptSyntax,
+  /// The [`max_attributes`](crate::option::HtmlParserOption::max_attributes) limit was reached
+  AttributeLimit,
+  /// The [`max_depth`](crate::option::HtmlParserOption::max_depth) limit was reached
+  DepthLimit,
+  /// The [`max_tokens`](crate::option::HtmlParserOption::max_tokens) limit was reached
+  TokenLimit,
+  /// The [parse budget](crate::budget::ParseBudget) was exceeded
+  BudgetExceeded(BudgetExceeded),
+}
+
+impl HtmlParseErrorKind {
+  /// Every kind of parse error.
+  pub const ALL: [Self; 29] = [
+    Self::UnclosedElement,
+    Self::ImplicitlyClosedElement,
+    Self::OrphanEndTag,
+    Self::MisnestedFormattingElement,
+    Self::VoidElementEndTag,
+    Self::VoidElementChildren,
+    Self::UnterminatedComment,
+    Self::AbruptClosingOfEmptyComment,
+    Self::IncorrectlyClosedComment,
+    Self::UnterminatedAttributeValue,
+    Self::UnterminatedRawText,
+    Self::MissingAttributeValue,
+    Self::UnexpectedCharacterInUnquotedValue,
+    Self::TrailingSolidus,
+    Self::EndTagWithAttributes,
+    Self::ContentBeforeDoctype,
+    Self::NestedDocumentElement,
+    Self::UnterminatedMustache,
+    Self::UnclosedBlock,
+    Self::OrphanBlockEnd,
+    Self::UnterminatedTemplateTag,
+    Self::ScriptSyntax,
+    Self::AttributeLimit,
+    Self::DepthLimit,
+    Self::TokenLimit,
+    Self::BudgetExceeded(BudgetExceeded::Bytes),
+    Self::BudgetExceeded(BudgetExceeded::Nodes),
+    Self::BudgetExceeded(BudgetExceeded::Depth),
+    Self::BudgetExceeded(BudgetExceeded::Errors),
+  ];
+
+  /// The kebab-case error code number, e.g. `unclosed-element`.
+  pub const fn code(self) -> &'static str {
+    match self {
+      Self::UnclosedElement => "unclosed-element",
+      Self::ImplicitlyClosedElement => "implicitly-closed-element",
+      Self::OrphanEndTag => "orphan-end-tag",
+      Self::MisnestedFormattingElement => "misnested-formatting-element",
+      Self::VoidElementEndTag => "void-element-end-tag",
+      Self::VoidElementChildren => "void-element-children",
+      Self::UnterminatedComment => "unterminated-comment",
+      Self::AbruptClosingOfEmptyComment => "abrupt-closing-of-empty-comment",
+      Self::IncorrectlyClosedComment => "incorrectly-closed-comment",
+      Self::UnterminatedAttributeValue => "unterminated-attribute-value",
+      Self::UnterminatedRawText => "unterminated-raw-text",
+      Self::MissingAttributeValue => "missing-attribute-value",
+      Self::UnexpectedCharacterInUnquotedValue => "unexpected-character-in-unquoted-value",
+      Self::TrailingSolidus => "trailing-solidus",
+      Self::EndTagWithAttributes => "end-tag-with-attributes",
+      Self::ContentBeforeDoctype => "content-before-doctype",
+      Self::NestedDocumentElement => "nested-document-element",
+      Self::UnterminatedMustache => "unterminated-mustache",
+      Self::UnclosedBlock => "unclosed-block",
+      Self::OrphanBlockEnd => "orphan-block-end",
+      Self::UnterminatedTemplateTag => "unterminated-template-tag",
+      Self::ScriptSyntax => "script-syntax",
+      Self::AttributeLimit => "attribute-limit",
+      Self::DepthLimit => "depth-limit",
+      Self::TokenLimit => "token-limit",
+      Self::BudgetExceeded(BudgetExceeded::Bytes) => "budget-exceeded-bytes",
+      Self::BudgetExceeded(BudgetExceeded::Nodes) => "budget-exceeded-nodes",
+      Self::BudgetExceeded(BudgetExceeded::Depth) => "budget-exceeded-depth",
+      Self::BudgetExceeded(BudgetExceeded::Errors) => "budget-exceeded-errors",
+    }
+  }
+
+  /// The level of the kind when it is not configured, a warning for
+  /// [`TrailingSolidus`](Self::TrailingSolidus) and an error for the others.
+  pub const fn default_level(self) -> ErrorLevel {
+    match self {
+      Self::TrailingSolidus => ErrorLevel::Warn,
+      _ => ErrorLevel::Error,
+    }
+  }
+
+  /// Get the kind from its error code number.
+  pub fn from_code(code: &str) -> Option<Self> {
+    Self::ALL.into_iter().find(|kind| kind.code() == code)
+  }
+
+  /// Get the kind of a diagnostic reported by the parser, `None` for other diagnostics.
+  pub fn from_diagnostic(diagnostic: &OxcDiagnostic) -> Option<Self> {
+    if diagnostic.code.scope.as_deref() != Some(ERROR_CODE_SCOPE) {
+      return None;
+    }
+    Self::from_code(diagnostic.code.number.as_deref()?)
+  }
+
+  /// Create an error of this kind.
+  pub(crate) fn error(self, message: impl Into<Cow<'static, str>>) -> OxcDiagnostic {
+    OxcDiagnostic::error(message).with_error_code(ERROR_CODE_SCOPE, self.code())
+  }
+}
+
+/// Configured severity of a kind of parse error.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorLevel {
+  /// The error is not reported
+  Off,
+  /// The error is reported as a warning
+  Warn,
+  /// The error is reported as an error
+  #[default]
+  Error,
+}
+
+impl FromStr for ErrorLevel {
+  type Err = OxcDiagnostic;
+
+  /// Parse `off`, `warn` or `error`.
+  fn from_str(level: &str) -> Result<Self, Self::Err> {
+    match level {
+      "off" => Ok(Self::Off),
+      "warn" => Ok(Self::Warn),
+      "error" => Ok(Self::Error),
+      _ => Err(OxcDiagnostic::error(format!(
+        "Unknown error level `{level}`, expected `off`, `warn` or `error`"
+      ))),
+    }
+  }
+}
+
+/// The severity of each kind of parse error, see
+/// [`HtmlParserOption::errors`](crate::option::HtmlParserOption::errors).
+///
+/// Kinds which are not configured are reported at their
+/// [default level](HtmlParseErrorKind::default_level). An exceeded
+/// [budget](crate::budget::ParseBudget) is always reported as an error, since it aborts the
+/// parse.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use oxc_diagnostics::Severity;
+/// use umc_html_parser::{CreateHtml, error::ErrorLevels, option::HtmlParserOption};
+/// use umc_parser::Parser;
+///
+/// let errors = ErrorLevels::from_codes([
+///   ("implicitly-closed-element", "warn"),
+///   ("orphan-end-tag", "off"),
+/// ])
+/// .unwrap();
+/// let options = HtmlParserOption {
+///   errors,
+///   ..HtmlParserOption::default()
+/// };
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<ul><li>One</ul></p>").with_options(options);
+/// let result = parser.parse();
+///
+/// assert_eq!(result.errors.len(), 1);
+/// assert_eq!(result.errors[0].severity, Severity::Warning);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ErrorLevels {
+  levels: HashMap<HtmlParseErrorKind, ErrorLevel>,
+}
+
+impl ErrorLevels {
+  /// Configure the level of a kind of error.
+  #[must_use]
+  pub fn with(mut self, kind: HtmlParseErrorKind, level: ErrorLevel) -> Self {
+    self.set(kind, level);
+    self
+  }
+
+  /// Configure the level of a kind of error.
+  pub fn set(&mut self, kind: HtmlParseErrorKind, level: ErrorLevel) {
+    self.levels.insert(kind, level);
+  }
+
+  /// Get the level of a kind of error.
+  pub fn get(&self, kind: HtmlParseErrorKind) -> ErrorLevel {
+    self
+      .levels
+      .get(&kind)
+      .copied()
+      .unwrap_or_else(|| kind.default_level())
+  }
+
+  /// Configure levels from pairs of error code and level, like
+  /// `{ "implicitly-closed-element": "warn" }` in a configuration file.
+  ///
+  /// # Errors
+  ///
+  /// Returns an error for an unknown code or level.
+  pub fn from_codes<'s>(
+    config: impl IntoIterator<Item = (&'s str, &'s str)>,
+  ) -> Result<Self, OxcDiagnostic> {
+    let mut levels = Self::default();
+    for (code, level) in config {
+      let kind = HtmlParseErrorKind::from_code(code)
+        .ok_or_else(|| OxcDiagnostic::error(format!("Unknown parse error code `{code}`")))?;
+      levels.set(kind, level.parse()?);
+    }
+    Ok(levels)
+  }
+
+  /// Apply the configured level to an error, `None` if it is turned off.
+  pub(crate) fn apply(&self, error: OxcDiagnostic) -> Option<OxcDiagnostic> {
+    let level = match HtmlParseErrorKind::from_diagnostic(&error) {
+      Some(HtmlParseErrorKind::BudgetExceeded(_)) | None => ErrorLevel::Error,
+      Some(kind) => self.get(kind),
+    };
+    match level {
+      ErrorLevel::Off => None,
+      ErrorLevel::Warn => Some(error.with_severity(Severity::Warning)),
+      ErrorLevel::Error => Some(error),
+    }
+  }
+}
@@ -0,0 +1,219 @@
+//! Automatic fixes of parse errors.
+//!
+//! Some parse errors have an obvious fix, like closing an unclosed element or removing a
+//! closing tag without opening tag. [`Fix::from_diagnostic`] gets it from the kind and the
+//! labels of the diagnostic, and [`apply_fixes`] applies the fixes of a list of diagnostics
+//! to the source text, for a `--fix` option of a linter.
+//!
+//! Fixing may uncover other errors, e.g. the element of an unterminated attribute value is
+//! only closed once the value is, so tools may parse and fix again until no fix is left.
+
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use umc_span::Span;
+
+use crate::error::HtmlParseErrorKind;
+
+/// A replacement of a span of the source text.
+///
+/// An empty span is an insertion, an empty replacement a deletion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+  pub span: Span,
+  pub replacement: String,
+}
+
+impl Fix {
+  /// Get the fix of a parse error, `None` if it has none or is not a parse error.
+  ///
+  /// Fixable errors are:
+  ///
+  /// - [`UnclosedElement`](HtmlParseErrorKind::UnclosedElement), the closing tag is added at the
+  ///   end of the document
+  /// - [`ImplicitlyClosedElement`](HtmlParseErrorKind::ImplicitlyClosedElement), the closing tag
+  ///   is added before the closing tag of its ancestor
+  /// - [`OrphanEndTag`](HtmlParseErrorKind::OrphanEndTag) and
+  ///   [`VoidElementEndTag`](HtmlParseErrorKind::VoidElementEndTag), the closing tag is removed
+  /// - [`UnterminatedAttributeValue`](HtmlParseErrorKind::UnterminatedAttributeValue), the quote
+  ///   and the tag are closed
+  /// - [`MissingAttributeValue`](HtmlParseErrorKind::MissingAttributeValue), an empty value is
+  ///   added
+  /// - [`UnexpectedCharacterInUnquotedValue`](HtmlParseErrorKind::UnexpectedCharacterInUnquotedValue),
+  ///   the value is quoted
+  /// - [`AbruptClosingOfEmptyComment`](HtmlParseErrorKind::AbruptClosingOfEmptyComment) and
+  ///   [`IncorrectlyClosedComment`](HtmlParseErrorKind::IncorrectlyClosedComment), the comment
+  ///   is closed by `-->`
+  /// - [`EndTagWithAttributes`](HtmlParseErrorKind::EndTagWithAttributes), the attributes are
+  ///   removed
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use oxc_allocator::Allocator;
+  /// use umc_html_parser::{CreateHtml, fix::Fix};
+  /// use umc_parser::Parser;
+  /// use umc_span::Span;
+  ///
+  /// let allocator = Allocator::default();
+  /// let source_text = "<div>Hello";
+  /// let parser = Parser::html(&allocator, source_text);
+  /// let result = parser.parse();
+  ///
+  /// let fix = Fix::from_diagnostic(source_text, &result.errors[0]).unwrap();
+  /// assert_eq!(fix.span, Span::empty(10));
+  /// assert_eq!(fix.replacement, "</div>");
+  /// ```
+  pub fn from_diagnostic(source_text: &str, diagnostic: &OxcDiagnostic) -> Option<Self> {
+    let kind = HtmlParseErrorKind::from_diagnostic(diagnostic)?;
+    let labels: Vec<Span> = diagnostic.labels.iter().flatten().map(label_span).collect();
+
+    let (span, replacement) = match (kind, labels.as_slice()) {
+      // the opening tag, then where its closing tag is expected
+      (
+        HtmlParseErrorKind::UnclosedElement | HtmlParseErrorKind::ImplicitlyClosedElement,
+        [opening_tag, expected, ..],
+      ) => {
+        let name = tag_name(opening_tag.source_text(source_text))?;
+        (Span::empty(expected.start), format!("</{name}>"))
+      }
+      (
+        HtmlParseErrorKind::OrphanEndTag | HtmlParseErrorKind::VoidElementEndTag,
+        [closing_tag, ..],
+      ) => (*closing_tag, String::new()),
+      // the end of the document, then the opening quote, the tag is closed as well
+      (HtmlParseErrorKind::UnterminatedAttributeValue, [end, quote, ..]) => {
+        (*end, format!("{}>", quote.source_text(source_text)))
+      }
+      // the `=`
+      (HtmlParseErrorKind::MissingAttributeValue, [equal, ..]) => {
+        (Span::empty(equal.end), "\"\"".to_string())
+      }
+      // the character, then the value
+      (HtmlParseErrorKind::UnexpectedCharacterInUnquotedValue, [_, value, ..]) => {
+        (*value, quote(value.source_text(source_text)))
+      }
+      // the closing delimiter
+      (
+        HtmlParseErrorKind::AbruptClosingOfEmptyComment
+        | HtmlParseErrorKind::IncorrectlyClosedComment,
+        [close, ..],
+      ) => (*close, "-->".to_string()),
+      // the attributes, in source order
+      (HtmlParseErrorKind::EndTagWithAttributes, [first, rest @ ..]) => (
+        Span::new(first.start, rest.last().unwrap_or(first).end),
+        String::new(),
+      ),
+      _ => return None,
+    };
+
+    Some(Self { span, replacement })
+  }
+}
+
+/// Apply the fixes of parse errors to the source text they were reported for.
+///
+/// Fixes are applied in source order, and a fix overlapping an earlier one is skipped.
+/// Insertions at the same offset keep the order of their diagnostics.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::{CreateHtml, fix::apply_fixes};
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let source_text = "<ul><li>One</span></ul><p>Two";
+/// let parser = Parser::html(&allocator, source_text);
+/// let result = parser.parse();
+///
+/// let fixed = apply_fixes(source_text, &result.errors);
+/// assert_eq!(fixed, "<ul><li>One</li></ul><p>Two</p>");
+/// ```
+pub fn apply_fixes(source_text: &str, diagnostics: &[OxcDiagnostic]) -> String {
+  let mut fixes: Vec<Fix> = diagnostics
+    .iter()
+    .filter_map(|diagnostic| Fix::from_diagnostic(source_text, diagnostic))
+    .collect();
+  fixes.sort_by_key(|fix| fix.span.start);
+
+  let mut output = String::with_capacity(source_text.len());
+  let mut last_end = 0;
+  for fix in fixes {
+    if fix.span.start < last_end {
+      continue;
+    }
+    output.push_str(&source_text[last_end as usize..fix.span.start as usize]);
+    output.push_str(&fix.replacement);
+    last_end = fix.span.end;
+  }
+  output.push_str(&source_text[last_end as usize..]);
+
+  output
+}
+
+const fn label_span(label: &LabeledSpan) -> Span {
+  Span::new(label.offset() as u32, (label.offset() + label.len()) as u32)
+}
+
+/// Quote an attribute value, with single quotes if it has double quotes only, and `&quot;`
+/// for the double quotes of a value with both.
+fn quote(value: &str) -> String {
+  if value.contains('"') && !value.contains('\'') {
+    format!("'{value}'")
+  } else {
+    format!("\"{}\"", value.replace('"', "&quot;"))
+  }
+}
+
+/// The name of an opening tag, `div` for `<div class="a">`, `None` if it is not terminated.
+fn tag_name(opening_tag: &str) -> Option<&str> {
+  let name = opening_tag.strip_prefix('<')?.strip_suffix('>')?;
+  let end = name
+    .find(|c: char| c.is_ascii_whitespace() || c == '/' || c == '>')
+    .unwrap_or(name.len());
+  (end > 0).then(|| &name[..end])
+}
+
+#[cfg(test)]
+mod test {
+  use std::borrow::Cow;
+
+  use oxc_allocator::Allocator;
+  use umc_parser::{Parser, ParserImpl};
+
+  use crate::{Html, fix::apply_fixes, option::HtmlParserOption, parse::HtmlParserImpl};
+
+  fn fix(source_text: &str) -> String {
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+    apply_fixes(source_text, &result.errors)
+  }
+
+  #[test]
+  fn fixes() {
+    assert_eq!(fix("<div><p>Text</div>"), "<div><p>Text</p></div>");
+    assert_eq!(fix("<section><div>"), "<section><div></div></section>");
+    assert_eq!(fix("<p>a</p></span>"), "<p>a</p>");
+    assert_eq!(fix("<img alt=>"), "<img alt=\"\">");
+    assert_eq!(fix("<a title='x"), "<a title='x'>");
+    assert_eq!(fix("<!-- a"), "<!-- a");
+    assert_eq!(fix("<!-->a<!--->"), "<!---->a<!---->");
+    assert_eq!(fix("<!-- a --!>"), "<!-- a -->");
+    assert_eq!(fix("<img alt=a\"b>"), "<img alt='a\"b'>");
+    assert_eq!(fix("<p>a</p class=\"b\" c>"), "<p>a</p >");
+    assert_eq!(
+      fix("<img alt=a'\"b src=/?a=b>"),
+      "<img alt=\"a'&quot;b\" src=\"/?a=b\">"
+    );
+
+    // fixing again once the tag is closed
+    let allocator = Allocator::default();
+    let fixed = fix("<a title='x");
+    let parser = Parser::<Html>::new(&allocator, &fixed);
+    assert_eq!(
+      apply_fixes(&fixed, &parser.parse().errors),
+      "<a title='x'></a>"
+    );
+  }
+}
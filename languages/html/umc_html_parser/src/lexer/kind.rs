@@ -24,6 +24,16 @@ pub enum HtmlKind {
   SelfCloseTagEnd,
   /// DOCTYPE declaration: `<!DOCTYPE`
   Doctype,
+  /// Name of a DOCTYPE: `html`
+  DoctypeName,
+  /// `PUBLIC` or `SYSTEM` keyword of a DOCTYPE
+  DoctypeKeyword,
+  /// Public identifier of a DOCTYPE, with its quotes: `"-//W3C//DTD HTML 4.01//EN"`
+  PublicId,
+  /// System identifier of a DOCTYPE, with its quotes: `"about:legacy-compat"`
+  SystemId,
+  /// Content of a DOCTYPE which is none of its parts, up to its `>`
+  BogusDoctype,
 
   // Identifier
   /// HTML element name (e.g., div, span, html)
@@ -36,6 +46,10 @@ pub enum HtmlKind {
   TextContent,
   /// HTML comment: `<!-- ... -->`
   Comment,
+  /// Handlebars or Mustache tag: `{{ ... }}`
+  Mustache,
+  /// Tag of a foreign template language, e.g. `{% ... %}`
+  TemplateTag,
 
   // Misc
   /// Equals sign in attributes: `=`
@@ -63,11 +77,18 @@ impl HtmlKind {
 
       TextContent => "text",
       Comment => "<!-- comment -->",
+      Mustache => "{{ mustache }}",
+      TemplateTag => "template tag",
 
       Eq => "=",
       Whitespace => "Whitespace",
 
       Doctype => "<!DOCTYPE",
+      DoctypeName => "doctype-name",
+      DoctypeKeyword => "PUBLIC or SYSTEM",
+      PublicId => "public-id",
+      SystemId => "system-id",
+      BogusDoctype => "bogus-doctype",
     }
   }
 }
@@ -1,10 +1,21 @@
-use memchr::{memchr, memchr_iter, memmem::find};
-use oxc_diagnostics::OxcDiagnostic;
+use memchr::{memchr, memchr_iter, memchr2, memmem::find};
 use std::iter::from_fn;
 use umc_parser::token::Token;
 use umc_span::Span;
 
-use crate::lexer::{HtmlLexer, kind::HtmlKind, state::LexerStateKind};
+use crate::{
+  error::HtmlParseErrorKind,
+  lexer::{
+    HtmlLexer,
+    kind::HtmlKind,
+    scan::{
+      CommentClose, ScriptEscape, char_len, doctype_word_len, find_comment_end, find_raw_text_end,
+      find_template_end, is_char_boundary, name_len, unquoted_value_len, whitespace_len,
+    },
+    state::{DoctypePart, LexerStateKind},
+  },
+  option::TemplateDelimiters,
+};
 
 impl HtmlLexer<'_> {
   pub fn tokens(&mut self) -> impl Iterator<Item = Token<HtmlKind>> {
@@ -12,11 +23,65 @@ impl HtmlLexer<'_> {
   }
 
   /// Get the next token, and move the pointer
-  fn next_token(&mut self) -> Option<Token<HtmlKind>> {
+  pub(crate) fn next_token(&mut self) -> Option<Token<HtmlKind>> {
+    self.lookahead.pop_front().or_else(|| self.lex_token())
+  }
+
+  /// Get the token `n` tokens after the next one without reading it, `peek_nth(0)` being
+  /// the next token. The tokens before it are lexed once, and kept until they are read.
+  pub(crate) fn peek_nth(&mut self, n: usize) -> Option<&Token<HtmlKind>> {
+    while self.lookahead.len() <= n {
+      let token = self.lex_token()?;
+      self.lookahead.push_back(token);
+    }
+    self.lookahead.get(n)
+  }
+
+  /// Lex a token after the ones lexed ahead.
+  fn lex_token(&mut self) -> Option<Token<HtmlKind>> {
+    #[cfg(feature = "tracing")]
+    let state = self.state.kind;
+
+    let token = self.lex();
+
+    // tokens end at ASCII syntax characters, so slicing the source text with their offsets
+    // never splits a character
+    debug_assert!(
+      token.as_ref().is_none_or(|token| {
+        is_char_boundary(self.source.source_text, token.start as usize)
+          && is_char_boundary(self.source.source_text, token.end as usize)
+      }),
+      "{token:?} splits a character"
+    );
+
+    #[cfg(feature = "tracing")]
+    if let Some(token) = &token {
+      tracing::trace!(kind = ?token.kind, start = token.start, end = token.end, "token");
+      if state != self.state.kind {
+        tracing::trace!(from = ?state, to = ?self.state.kind, "lexer state");
+      }
+    }
+
+    token
+  }
+
+  fn lex(&mut self) -> Option<Token<HtmlKind>> {
     // the file end, but still calling this function
     if self.is_eof() {
       return match self.state.kind {
         LexerStateKind::Finished => None,
+        LexerStateKind::Doctype => {
+          self.errors.push(
+            HtmlParseErrorKind::UnterminatedComment
+              .error(format!(
+                "Expected {}, but found {}",
+                HtmlKind::TagEnd,
+                HtmlKind::Eof
+              ))
+              .with_label(Span::empty(self.source.pointer)),
+          );
+          Some(self.finish())
+        }
         _ => Some(self.finish()),
       };
     }
@@ -27,6 +92,7 @@ impl HtmlLexer<'_> {
       LexerStateKind::EmbeddedContent => Some(self.handle_embedded_content()),
       LexerStateKind::AfterTagName => Some(self.handle_after_tag_name()),
       LexerStateKind::InTag => Some(self.handle_in_tag()),
+      LexerStateKind::Doctype => Some(self.handle_doctype()),
       LexerStateKind::Finished => None,
     }
   }
@@ -52,9 +118,18 @@ impl HtmlLexer<'_> {
 impl HtmlLexer<'_> {
   fn handle_content(&mut self) -> Token<HtmlKind> {
     let start = self.source.pointer;
+    if self.template_at(self.source.rest()).is_some() {
+      return self.handle_template_tag(start);
+    }
+    if self.option.mustache && self.source.rest().starts_with(b"{{") {
+      return self.handle_mustache(start);
+    }
 
-    self.source.advance(1);
-    match self.source.get(start).unwrap() {
+    // safe unwrap, won't direct to this branch if pointer == file.len()
+    let current = self.source.get(start).unwrap();
+    // over a whole character, to keep the pointer on a character boundary
+    self.source.advance(char_len(current));
+    match current {
       b'<' => {
         match self.source.get(start + 1) {
           Some(item) if item.is_ascii_alphabetic() => {
@@ -64,8 +139,7 @@ impl HtmlLexer<'_> {
               end: self.source.pointer,
             };
 
-            self.state.kind = LexerStateKind::InTag; // update state
-            self.state.allow_to_set_tag_name();
+            self.state.start_tag(true);
             result
           }
 
@@ -78,14 +152,16 @@ impl HtmlLexer<'_> {
               end: self.source.pointer,
             };
 
-            self.state.kind = LexerStateKind::InTag; // update state
+            self.state.start_tag(false);
             result
           }
 
           // for ! character, as comment or doctype
           Some(b'!') => {
             const DOCTYPE: &[u8] = b"doctype";
-            const COMMENT_START: &[u8] = b"!--";
+            // the `!` is already consumed
+            const COMMENT_START: &[u8] = b"--";
+            const CDATA_START: &[u8] = b"[CDATA[";
 
             self.source.advance(1);
             if self.source.starts_with_lowercase(DOCTYPE) {
@@ -96,13 +172,15 @@ impl HtmlLexer<'_> {
                 end: self.source.pointer,
               };
 
-              self.state.kind = LexerStateKind::AfterTagName;
+              self.state.kind = LexerStateKind::Doctype;
+              self.state.doctype = DoctypePart::Name;
               result
             } else if self.source.starts_with_lowercase(COMMENT_START) {
-              // for <!--> situation, don't go ahead
-              let comment_end = find(self.source.rest(), b"-->");
-
-              if let Some(end) = comment_end.map(|i| i as u32) {
+              self.source.advance(COMMENT_START.len() as u32);
+              self.comment(start)
+            } else if self.option.cdata_sections && self.source.rest().starts_with(CDATA_START) {
+              // the section ends at `]]>`, not at the first `>` of its content
+              if let Some(end) = find(self.source.rest(), b"]]>").map(|i| i as u32) {
                 self.source.advance(end + 3);
                 Token::<HtmlKind> {
                   kind: HtmlKind::Comment,
@@ -113,21 +191,13 @@ impl HtmlLexer<'_> {
                 self.tailless_comment(start)
               }
             } else {
-              let comment_end = memchr(b'>', self.source.rest());
-
-              if let Some(end) = comment_end.map(|i| i as u32) {
-                self.source.advance(end + 1);
-                Token::<HtmlKind> {
-                  kind: HtmlKind::Comment,
-                  start,
-                  end: self.source.pointer,
-                }
-              } else {
-                self.tailless_comment(start)
-              }
+              self.bogus_comment(start)
             }
           }
 
+          // for ? character, as bogus comment, e.g. <?xml version="1.0"?>
+          Some(b'?') => self.bogus_comment(start),
+
           Some(_) | None => self.handle_content_text(start),
         }
       }
@@ -141,13 +211,25 @@ impl HtmlLexer<'_> {
 
     while let Some(i) = iter.next().map(|i| i as u32) {
       if let Some(next) = self.source.get(self.source.pointer + i + 1)
-        && (next.is_ascii_alphabetic() || next == b'/' || next == b'!')
+        && (next.is_ascii_alphabetic() || matches!(next, b'/' | b'!' | b'?'))
       {
         index = self.source.pointer + i;
         break;
       }
     }
 
+    if self.option.mustache {
+      let text = &self.source.rest()[..(index - self.source.pointer) as usize];
+      if let Some(i) = find(text, b"{{") {
+        index = self.source.pointer + i as u32;
+      }
+    }
+
+    let text = &self.source.rest()[..(index - self.source.pointer) as usize];
+    if let Some(i) = self.find_template(text) {
+      index = self.source.pointer + i as u32;
+    }
+
     self.source.to(index);
 
     Token::<HtmlKind> {
@@ -157,18 +239,105 @@ impl HtmlLexer<'_> {
     }
   }
 
+  /// Lex a Handlebars tag, ending at the `}}` matching its opening, e.g. `}}}` for `{{{`
+  fn handle_mustache(&mut self, start: u32) -> Token<HtmlKind> {
+    let rest = self.source.rest();
+    let (open, close): (usize, &[u8]) = if rest.starts_with(b"{{!--") {
+      (5, b"--}}")
+    } else if rest.starts_with(b"{{{") {
+      (3, b"}}}")
+    } else {
+      (2, b"}}")
+    };
+
+    if let Some(end) = find(&rest[open..], close) {
+      self.source.advance((open + end + close.len()) as u32);
+    } else {
+      self.source.to(self.source.source_text.len() as u32);
+      self.errors.push(
+        HtmlParseErrorKind::UnterminatedMustache
+          .error(format!(
+            "Expected {}, but found {}",
+            String::from_utf8_lossy(close),
+            HtmlKind::Eof
+          ))
+          .with_labels([
+            Span::empty(self.source.pointer).primary(),
+            Span::new(start, start + open as u32).label("tag starts here"),
+          ]),
+      );
+    }
+
+    Token::<HtmlKind> {
+      kind: HtmlKind::Mustache,
+      start,
+      end: self.source.pointer,
+    }
+  }
+
+  /// Lex a comment after its `<!--`, reporting the closing delimiters browsers accept with
+  /// a parse error
+  fn comment(&mut self, start: u32) -> Token<HtmlKind> {
+    let Some((end, len, close)) = find_comment_end(self.source.rest()) else {
+      return self.tailless_comment(start);
+    };
+    let close_start = self.source.pointer + end as u32;
+    self.source.advance((end + len) as u32);
+    let close_span = Span::new(close_start, self.source.pointer);
+
+    match close {
+      CommentClose::Normal => {}
+      CommentClose::Abrupt => self.errors.push(
+        HtmlParseErrorKind::AbruptClosingOfEmptyComment
+          .error("Abrupt closing of empty comment")
+          .with_label(close_span.primary_label("closes the comment"))
+          .with_help("Close the comment with `-->`"),
+      ),
+      CommentClose::Bang => self.errors.push(
+        HtmlParseErrorKind::IncorrectlyClosedComment
+          .error("Incorrectly closed comment")
+          .with_label(close_span.primary_label("closes the comment"))
+          .with_help("Close the comment with `-->`"),
+      ),
+    }
+
+    Token::<HtmlKind> {
+      kind: HtmlKind::Comment,
+      start,
+      end: self.source.pointer,
+    }
+  }
+
+  /// Lex a bogus comment, which ends at the first `>`
+  /// https://html.spec.whatwg.org/multipage/parsing.html#bogus-comment-state
+  fn bogus_comment(&mut self, start: u32) -> Token<HtmlKind> {
+    let comment_end = memchr(b'>', self.source.rest());
+
+    if let Some(end) = comment_end.map(|i| i as u32) {
+      self.source.advance(end + 1);
+      Token::<HtmlKind> {
+        kind: HtmlKind::Comment,
+        start,
+        end: self.source.pointer,
+      }
+    } else {
+      self.tailless_comment(start)
+    }
+  }
+
   fn tailless_comment(&mut self, start: u32) -> Token<HtmlKind> {
     // eof without finishing doctype or comment
     self.source.to(self.source.source_text.len() as u32);
 
     // throw an error
     self.errors.push(
-      OxcDiagnostic::error(format!(
-        "Expected {}, but found {}",
-        HtmlKind::TagEnd,
-        HtmlKind::Eof
-      ))
-      .with_label(Span::new(self.source.pointer, self.source.pointer)),
+      HtmlParseErrorKind::UnterminatedComment
+        .error(format!(
+          "Expected {}, but found {}",
+          HtmlKind::TagEnd,
+          HtmlKind::Eof
+        ))
+        .with_label(Span::new(self.source.pointer, self.source.pointer)),
     );
 
     // return as comment
@@ -183,23 +352,29 @@ impl HtmlLexer<'_> {
 // handler for HtmlLexerState::EmbeddedContent
 impl HtmlLexer<'_> {
   fn handle_embedded_content(&mut self) -> Token<HtmlKind> {
-    let closing_tag_string = format!("</{}", self.state.take_tag_name().unwrap());
-    let closing_tag = closing_tag_string.as_bytes(); // safe unwrap because only script/style can enter this state
+    // safe unwrap, only the opening tag of a raw text element enters this state
+    let closing_tag_string = format!("</{}", self.state.take_embedded().unwrap());
+    let closing_tag = closing_tag_string.as_bytes();
 
     let start = self.source.pointer;
     let mut end = self.source.source_text.len() as u32;
 
-    if let Some(tag_end) = find(self.source.rest(), closing_tag).map(|e| e as u32) {
+    let rest = self.source.rest();
+    let mut escape = ScriptEscape::default();
+    if let Some(tag_end) =
+      find_raw_text_end(rest, closing_tag, rest.len(), &mut escape).map(|e| e as u32)
+    {
       end = start + tag_end;
       self.state.kind = LexerStateKind::Content; // update state
     } else {
       self.errors.push(
-        OxcDiagnostic::error(format!(
-          "Expected {}, but found {}",
-          str::from_utf8(closing_tag).unwrap(),
-          HtmlKind::Eof
-        ))
-        .with_label(Span::new(end, end)),
+        HtmlParseErrorKind::UnterminatedRawText
+          .error(format!(
+            "Expected {}, but found {}",
+            str::from_utf8(closing_tag).unwrap(),
+            HtmlKind::Eof
+          ))
+          .with_label(Span::new(end, end)),
       );
     }
 
@@ -219,15 +394,19 @@ impl HtmlLexer<'_> {
     let start = self.source.pointer;
 
     // safe unwarp, won't direct to this branch if pointer == file.len()
-    match self.source.get(start).unwrap() {
+    let current = self.source.get(start).unwrap();
+    let expect_value = self.state.expect_value && !current.is_ascii_whitespace();
+    if expect_value {
+      self.state.expect_value = false;
+    } else if self.template_at(self.source.rest()).is_some() {
+      return self.handle_template_tag(start);
+    }
+
+    match current {
       w if w.is_ascii_whitespace() => {
         self.source.advance(1);
-        let mut i = 0;
-        while i < self.source.rest().len() && self.source.rest()[i].is_ascii_whitespace() {
-          i += 1;
-        }
-
-        self.source.advance(i as u32);
+        let length = whitespace_len(self.source.rest());
+        self.source.advance(length as u32);
 
         Token::<HtmlKind> {
           kind: HtmlKind::Whitespace,
@@ -238,6 +417,7 @@ impl HtmlLexer<'_> {
 
       b'=' => {
         self.source.advance(1);
+        self.state.expect_value = true;
 
         Token::<HtmlKind> {
           kind: HtmlKind::Eq,
@@ -249,13 +429,11 @@ impl HtmlLexer<'_> {
       b'>' => {
         self.source.advance(1);
 
-        if let Some(tag_name) = self.state.get_tag_name()
-          && (self.option.is_embedded_language_tag)(tag_name)
-        {
-          self.state.kind = LexerStateKind::EmbeddedContent;
-        } else {
-          self.state.kind = LexerStateKind::Content;
-        }
+        let embedded = self
+          .state
+          .tag_name()
+          .is_some_and(|tag_name| self.option.raw_text_tags.contains(tag_name));
+        self.state.end_tag(embedded);
 
         Token::<HtmlKind> {
           kind: HtmlKind::TagEnd,
@@ -264,20 +442,35 @@ impl HtmlLexer<'_> {
         }
       }
 
+      b'/' if expect_value => self.handle_unquoted_value(start),
+
       b'/' => {
         if let Some(next) = self.source.get(self.source.pointer + 1)
           && next == b'>'
         {
           self.source.advance(2);
-          self.state.take_tag_name(); // clear tag name
-          self.state.kind = LexerStateKind::Content; // update lexer state
+          // a self-closing tag has no content, even a raw text one like `<script />`, unless
+          // the slash is ignored as browsers do
+          let embedded = self.option.spec_self_closing
+            && self
+              .state
+              .tag_name()
+              .is_some_and(|tag_name| self.option.raw_text_tags.contains(tag_name));
+          self.state.end_tag(embedded);
           Token::<HtmlKind> {
             kind: HtmlKind::SelfCloseTagEnd,
             start,
             end: self.source.pointer,
           }
         } else {
-          self.handle_tag(start, HtmlKind::Attribute)
+          // a solidus not followed by `>` is ignored, like whitespace
+          // https://html.spec.whatwg.org/multipage/parsing.html#parse-error-unexpected-solidus-in-tag
+          self.source.advance(1);
+          Token::<HtmlKind> {
+            kind: HtmlKind::Whitespace,
+            start,
+            end: self.source.pointer,
+          }
         }
       }
 
@@ -294,24 +487,63 @@ impl HtmlLexer<'_> {
       }
 
       // for attribute without `"`
+      _ if expect_value => self.handle_unquoted_value(start),
       _ => self.handle_tag(start, HtmlKind::Attribute),
     }
   }
 
+  /// Lex an unquoted attribute value, which may contain `/` and `=`
+  /// https://html.spec.whatwg.org/multipage/parsing.html#attribute-value-(unquoted)-state
+  fn handle_unquoted_value(&mut self, start: u32) -> Token<HtmlKind> {
+    let length = self.len_with_templates(self.source.rest(), unquoted_value_len);
+    let value = &self.source.rest()[..length];
+    // characters of template tags are theirs
+    if self.find_template(value).is_none()
+      && let Some(index) = value
+        .iter()
+        .position(|byte| matches!(byte, b'"' | b'\'' | b'<' | b'=' | b'`'))
+    {
+      let at = start + index as u32;
+      self.errors.push(
+        HtmlParseErrorKind::UnexpectedCharacterInUnquotedValue
+          .error(format!(
+            "Unexpected character `{}` in unquoted attribute value",
+            char::from(value[index])
+          ))
+          .with_labels([
+            Span::sized(at, 1).primary(),
+            Span::sized(start, length as u32).label("value starts here"),
+          ])
+          .with_help("Quote the attribute value"),
+      );
+    }
+    self.source.advance(length as u32);
+
+    Token::<HtmlKind> {
+      kind: HtmlKind::Attribute,
+      start,
+      end: self.source.pointer,
+    }
+  }
+
   fn handle_quote_attribute(&mut self, start: u32, quote: u8) -> Token<HtmlKind> {
     // since html don't support \ escape, we don't need to manage its state
-    let end = if let Some(index) = memchr(quote, self.source.rest()) {
+    let end = if let Some(index) = self.find_quote(quote) {
       self.source.pointer + index as u32 + 1
     } else {
       // throw an error, expect quote, but found eof
       let end = self.source.source_text.len() as u32;
       self.errors.push(
-        OxcDiagnostic::error(format!(
-          "Expected {}, but found {}",
-          char::from(quote),
-          HtmlKind::Eof
-        ))
-        .with_label(Span::new(end, end)),
+        HtmlParseErrorKind::UnterminatedAttributeValue
+          .error(format!(
+            "Expected {}, but found {}",
+            char::from(quote),
+            HtmlKind::Eof
+          ))
+          .with_labels([
+            Span::empty(end).primary(),
+            Span::new(start, start + 1).label("quoted value starts here"),
+          ]),
       );
 
       end
@@ -327,12 +559,91 @@ impl HtmlLexer<'_> {
   }
 }
 
+// handler for HtmlLexerState::Doctype
+impl HtmlLexer<'_> {
+  /// Lex the parts of a DOCTYPE, each in turn, up to its `>`
+  /// https://html.spec.whatwg.org/multipage/parsing.html#doctype-state
+  fn handle_doctype(&mut self) -> Token<HtmlKind> {
+    const PUBLIC: &[u8] = b"public";
+    const SYSTEM: &[u8] = b"system";
+
+    let start = self.source.pointer;
+    // safe unwrap, won't direct to this branch if pointer == file.len()
+    let current = self.source.get(start).unwrap();
+    let part = self.state.doctype;
+
+    let (kind, next) = match current {
+      w if w.is_ascii_whitespace() => {
+        self.source.advance(1);
+        let length = whitespace_len(self.source.rest());
+        self.source.advance(length as u32);
+        (HtmlKind::Whitespace, part)
+      }
+
+      b'>' => {
+        self.source.advance(1);
+        self.state.kind = LexerStateKind::Content;
+        (HtmlKind::TagEnd, DoctypePart::Name)
+      }
+
+      _ if part == DoctypePart::Name => {
+        let length = doctype_word_len(self.source.rest());
+        self.source.advance(length as u32);
+        (HtmlKind::DoctypeName, DoctypePart::Keyword)
+      }
+
+      // the keyword may be followed by its identifier without whitespace
+      _ if part == DoctypePart::Keyword && self.source.starts_with_lowercase(PUBLIC) => {
+        self.source.advance(PUBLIC.len() as u32);
+        (HtmlKind::DoctypeKeyword, DoctypePart::PublicId)
+      }
+      _ if part == DoctypePart::Keyword && self.source.starts_with_lowercase(SYSTEM) => {
+        self.source.advance(SYSTEM.len() as u32);
+        (HtmlKind::DoctypeKeyword, DoctypePart::SystemId)
+      }
+
+      b'"' | b'\'' if part == DoctypePart::PublicId => {
+        self.doctype_id(current);
+        (HtmlKind::PublicId, DoctypePart::SystemId)
+      }
+      b'"' | b'\'' if part == DoctypePart::SystemId => {
+        self.doctype_id(current);
+        (HtmlKind::SystemId, DoctypePart::Bogus)
+      }
+
+      _ => {
+        let rest = self.source.rest();
+        let length = memchr(b'>', rest).unwrap_or(rest.len());
+        self.source.advance(length as u32);
+        (HtmlKind::BogusDoctype, DoctypePart::Bogus)
+      }
+    };
+    self.state.doctype = next;
+
+    Token::<HtmlKind> {
+      kind,
+      start,
+      end: self.source.pointer,
+    }
+  }
+
+  /// Move over a quoted identifier of a DOCTYPE, which a `>` ends before its closing quote
+  fn doctype_id(&mut self, quote: u8) {
+    let rest = &self.source.rest()[1..];
+    let length = match memchr2(quote, b'>', rest) {
+      Some(index) if rest[index] == quote => index + 1,
+      Some(index) => index,
+      None => rest.len(),
+    };
+    self.source.advance(1 + length as u32);
+  }
+}
+
 // handler for HtmlLexerState::InTag
 impl HtmlLexer<'_> {
   fn handle_in_tag(&mut self) -> Token<HtmlKind> {
     // call the handle_tag
     let result = self.handle_tag(self.source.pointer, HtmlKind::ElementName);
-    self.state.kind = LexerStateKind::AfterTagName; // update state
     self.state.set_tag_name(
       str::from_utf8(&self.source.source_text[result.start as usize..result.end as usize]).unwrap(),
     );
@@ -343,20 +654,100 @@ impl HtmlLexer<'_> {
 // some universal functions
 impl HtmlLexer<'_> {
   fn handle_tag(&mut self, start: u32, kind: HtmlKind) -> Token<HtmlKind> {
-    let mut i = 0;
-    while i < self.source.rest().len()
-      && !{
-        let item = self.source.rest()[i];
-        item.is_ascii_whitespace() || item == b'>' || item == b'=' || item == b'/'
-      }
-    {
-      i += 1;
+    let length = self.len_with_templates(self.source.rest(), name_len);
+    self.source.advance(length as u32);
+
+    Token::<HtmlKind> {
+      kind,
+      start,
+      end: self.source.pointer,
     }
+  }
+}
 
-    self.source.advance(i as u32);
+// template tags, see HtmlLexerOption::template_delimiters
+impl HtmlLexer<'_> {
+  /// Get the delimiters of the template tag starting `rest`, if any
+  fn template_at(&self, rest: &[u8]) -> Option<&TemplateDelimiters> {
+    self.option.template_delimiters.iter().find(|delimiters| {
+      !delimiters.open.is_empty() && rest.starts_with(delimiters.open.as_bytes())
+    })
+  }
+
+  /// Get the offset of the first template tag in `text`
+  fn find_template(&self, text: &[u8]) -> Option<usize> {
+    self
+      .option
+      .template_delimiters
+      .iter()
+      .filter(|delimiters| !delimiters.open.is_empty())
+      .filter_map(|delimiters| find(text, delimiters.open.as_bytes()))
+      .min()
+  }
+
+  /// Get the length of the template tag starting `rest`, to the end of `rest` if it is
+  /// unterminated
+  fn template_len(&self, rest: &[u8]) -> Option<usize> {
+    let delimiters = self.template_at(rest)?;
+    let open = delimiters.open.len();
+    Some(
+      find_template_end(delimiters, &rest[open..])
+        .map_or(rest.len(), |end| open + end + delimiters.close.len()),
+    )
+  }
+
+  /// Get the length of the name or value starting `rest` measured by `len`, with the
+  /// template tags it contains, e.g. `data-{{ key }}`
+  fn len_with_templates(&self, rest: &[u8], len: fn(&[u8]) -> usize) -> usize {
+    let mut length = 0;
+    loop {
+      let end = length + len(&rest[length..]);
+      let Some(offset) = self.find_template(&rest[length..end]) else {
+        return end;
+      };
+      length += offset;
+      length += self.template_len(&rest[length..]).unwrap();
+    }
+  }
+
+  /// Get the offset of the first `quote` outside of template tags
+  fn find_quote(&self, quote: u8) -> Option<usize> {
+    let rest = self.source.rest();
+    let mut offset = 0;
+    loop {
+      let index = offset + memchr(quote, &rest[offset..])?;
+      let Some(tag) = self.find_template(&rest[offset..index]) else {
+        return Some(index);
+      };
+      offset += tag;
+      offset += self.template_len(&rest[offset..]).unwrap();
+    }
+  }
+
+  /// Lex a template tag, ending at its first closing delimiter
+  fn handle_template_tag(&mut self, start: u32) -> Token<HtmlKind> {
+    let rest = self.source.rest();
+    let delimiters = self.template_at(rest).unwrap();
+    let open = delimiters.open.len();
+
+    if let Some(end) = find_template_end(delimiters, &rest[open..]) {
+      let length = open + end + delimiters.close.len();
+      self.source.advance(length as u32);
+    } else {
+      let message = format!("Expected {}, but found {}", delimiters.close, HtmlKind::Eof);
+      self.source.to(self.source.source_text.len() as u32);
+      self.errors.push(
+        HtmlParseErrorKind::UnterminatedTemplateTag
+          .error(message)
+          .with_labels([
+            Span::empty(self.source.pointer).primary(),
+            Span::new(start, start + open as u32).label("tag starts here"),
+          ]),
+      );
+    }
 
     Token::<HtmlKind> {
-      kind,
+      kind: HtmlKind::TemplateTag,
       start,
       end: self.source.pointer,
     }
@@ -1,47 +1,78 @@
-use crate::lexer::state::{LexerState, LexerStateKind};
+use crate::{
+  lexer::state::{LexerState, LexerStateKind},
+  option::{TagSet, TemplateDelimiters},
+};
 use oxc_diagnostics::OxcDiagnostic;
-use umc_parser::source::Source;
+use std::collections::VecDeque;
+use umc_parser::{source::Source, token::Token};
 
 pub mod kind;
 mod lexe;
+pub mod scan;
 mod state;
 
-pub struct HtmlLexerOption<'a> {
-  pub is_embedded_language_tag: &'a dyn Fn(&str) -> bool,
+pub struct HtmlLexerOption {
+  /// Tags whose content is lexed as raw text, see
+  /// [`DialectSpec::raw_text_tags`](crate::dialect::DialectSpec::raw_text_tags)
+  pub raw_text_tags: TagSet,
+  /// Ignore the `/>` of raw text tags, like `<script/>`, lexing their content as raw text
+  /// as browsers do, see [`SelfClosing::Spec`](crate::option::SelfClosing::Spec)
+  pub spec_self_closing: bool,
+  /// Lex `<![CDATA[...]]>` sections whole, see
+  /// [`DialectSpec::cdata_sections`](crate::dialect::DialectSpec::cdata_sections)
+  pub cdata_sections: bool,
+  /// Lex Handlebars tags in content as [`Mustache`](kind::HtmlKind::Mustache) tokens
+  pub mustache: bool,
+  /// Lex the tags of a foreign template language as [`TemplateTag`](kind::HtmlKind::TemplateTag)
+  /// tokens in content and between attributes, and skip them in names and values
+  pub template_delimiters: Vec<TemplateDelimiters>,
 }
 
 pub struct HtmlLexer<'a> {
   source: Source<'a>,
   state: LexerState<'a>,
-  option: HtmlLexerOption<'a>,
+  option: HtmlLexerOption,
+  /// Tokens lexed ahead by [`peek_nth`](Self::peek_nth), in order
+  lookahead: VecDeque<Token<kind::HtmlKind>>,
   pub errors: Vec<OxcDiagnostic>,
 }
 
 impl<'a> HtmlLexer<'a> {
-  pub const fn new(source_text: &'a str, option: HtmlLexerOption<'a>) -> Self {
+  pub const fn new(source_text: &'a str, option: HtmlLexerOption) -> Self {
     HtmlLexer {
       source: Source::new(source_text),
       state: LexerState::new(LexerStateKind::Content),
       option,
+      lookahead: VecDeque::new(),
       errors: Vec::new(),
     }
   }
+
+  /// Start lexing at `start` rather than at the beginning of the source text.
+  pub const fn starting_at(mut self, start: u32) -> Self {
+    self.source.pointer = start;
+    self
+  }
 }
 
 #[cfg(test)]
 mod test {
-  use crate::lexer::{HtmlLexer, HtmlLexerOption, kind::HtmlKind};
+  use crate::{
+    lexer::{HtmlLexer, HtmlLexerOption, kind::HtmlKind},
+    option::TagSet,
+  };
   use insta::assert_snapshot;
   use umc_parser::token::Token;
 
   fn test(source_text: &str) -> String {
-    let func =
-      |tag_name: &str| matches!(tag_name.to_ascii_lowercase().as_str(), "script" | "style");
-
     let mut lexer = HtmlLexer::new(
       source_text,
       HtmlLexerOption {
-        is_embedded_language_tag: &func,
+        raw_text_tags: TagSet::Html5RawText,
+        spec_self_closing: false,
+        cdata_sections: false,
+        mustache: false,
+        template_delimiters: Vec::new(),
       },
     );
 
@@ -95,6 +126,27 @@ mod test {
     assert_snapshot!(test(HTML_STRING));
   }
 
+  #[test]
+  fn self_close_style_tag() {
+    const HTML_STRING: &str = "<style/><p>a</p><script/></script><b>b</b>";
+
+    assert_snapshot!(test(HTML_STRING));
+  }
+
+  #[test]
+  fn solidus_in_tag() {
+    const HTML_STRING: &str = r"<a/b c=/d/><br/";
+
+    assert_snapshot!(test(HTML_STRING));
+  }
+
+  #[test]
+  fn mixed_case_closing_tag() {
+    const HTML_STRING: &str = "<SCRIPT>a</scripts></Script >b<style>c</STYLE/>";
+
+    assert_snapshot!(test(HTML_STRING));
+  }
+
   // errors
   #[test]
   fn no_complete_doctype() {
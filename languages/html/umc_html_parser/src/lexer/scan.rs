@@ -0,0 +1,433 @@
+//! Scanning of the runs inside a tag, of the end of raw text, of the end of balanced
+//! template tags, and of the delimiters of comments.
+//!
+//! Names and whitespace runs are short, so classifying each byte with a single lookup in a
+//! 256-entry table beats both chained comparisons and the setup of a SIMD search, which
+//! `memchr` is kept for in the long runs of text and raw text.
+
+use memchr::{memchr, memchr_iter, memchr2, memmem::find};
+
+use crate::option::TemplateDelimiters;
+
+/// `\t`, `\n`, `\x0C`, `\r` and ` `, like [`u8::is_ascii_whitespace`]
+const WHITESPACE: u8 = 1;
+/// Ends a tag name or an attribute name
+const NAME_END: u8 = 1 << 1;
+/// Ends an unquoted attribute value
+const UNQUOTED_VALUE_END: u8 = 1 << 2;
+
+static CLASSES: [u8; 256] = {
+  let mut classes = [0; 256];
+  let mut byte = 0;
+  while byte < 256 {
+    if (byte as u8).is_ascii_whitespace() {
+      classes[byte] = WHITESPACE | NAME_END | UNQUOTED_VALUE_END;
+    }
+    byte += 1;
+  }
+  classes[b'>' as usize] = NAME_END | UNQUOTED_VALUE_END;
+  classes[b'=' as usize] = NAME_END;
+  classes[b'/' as usize] = NAME_END;
+  classes
+};
+
+/// Length of the run of bytes at the start of `bytes` without any of the `class`.
+#[inline]
+fn run_without(bytes: &[u8], class: u8) -> usize {
+  bytes
+    .iter()
+    .position(|&byte| CLASSES[byte as usize] & class != 0)
+    .unwrap_or(bytes.len())
+}
+
+/// Length of the whitespace at the start of `bytes`.
+#[inline]
+pub fn whitespace_len(bytes: &[u8]) -> usize {
+  bytes
+    .iter()
+    .position(|&byte| CLASSES[byte as usize] & WHITESPACE == 0)
+    .unwrap_or(bytes.len())
+}
+
+/// Length of the tag or attribute name at the start of `bytes`, up to whitespace, `>`,
+/// `=` or `/`.
+#[inline]
+pub fn name_len(bytes: &[u8]) -> usize {
+  run_without(bytes, NAME_END)
+}
+
+/// Length of the unquoted attribute value at the start of `bytes`, up to whitespace or `>`.
+#[inline]
+pub fn unquoted_value_len(bytes: &[u8]) -> usize {
+  run_without(bytes, UNQUOTED_VALUE_END)
+}
+
+/// Length of the name of a DOCTYPE, up to whitespace or `>`
+/// https://html.spec.whatwg.org/multipage/parsing.html#doctype-name-state
+#[inline]
+pub fn doctype_word_len(bytes: &[u8]) -> usize {
+  bytes
+    .iter()
+    .position(|&byte| byte.is_ascii_whitespace() || byte == b'>')
+    .unwrap_or(bytes.len())
+}
+
+/// Whether `index` is on a character boundary of the UTF-8 `bytes`, like
+/// [`str::is_char_boundary`]. Every syntax character of HTML is ASCII, so scanning up to
+/// one never splits a character.
+#[inline]
+pub const fn is_char_boundary(bytes: &[u8], index: usize) -> bool {
+  if index >= bytes.len() {
+    return index == bytes.len();
+  }
+  // not a continuation byte, `0b10xx_xxxx`
+  bytes[index] & 0xc0 != 0x80
+}
+
+/// Length of the UTF-8 character starting with `byte`, `1` for a continuation byte.
+#[inline]
+pub const fn char_len(byte: u8) -> u32 {
+  match byte {
+    0xC0..=0xDF => 2,
+    0xE0..=0xEF => 3,
+    0xF0..=0xFF => 4,
+    _ => 1,
+  }
+}
+
+/// How a comment is closed, see [`find_comment_end`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentClose {
+  /// `-->`
+  Normal,
+  /// `>` or `->` right after `<!--`, closing an empty comment
+  Abrupt,
+  /// `--!>`
+  Bang,
+}
+
+/// Find the closing delimiter of a comment in `text`, which follows its `<!--`: its offset,
+/// its length, and how it closes the comment.
+/// https://html.spec.whatwg.org/multipage/parsing.html#comment-start-state
+pub fn find_comment_end(text: &[u8]) -> Option<(usize, usize, CommentClose)> {
+  if text.starts_with(b">") {
+    return Some((0, 1, CommentClose::Abrupt));
+  }
+  if text.starts_with(b"->") {
+    return Some((0, 2, CommentClose::Abrupt));
+  }
+  memchr_iter(b'>', text).find_map(|index| {
+    let before = &text[..index];
+    if before.ends_with(b"--") {
+      Some((index - 2, 3, CommentClose::Normal))
+    } else if before.ends_with(b"--!") {
+      Some((index - 3, 4, CommentClose::Bang))
+    } else {
+      None
+    }
+  })
+}
+
+/// Get the value of a [`Comment`](super::kind::HtmlKind::Comment) token, without its
+/// delimiters, and whether it is a bogus comment.
+///
+/// Comments end at the first `-->` or `--!>`, or right after `<!--` for the abrupt `<!-->`
+/// and `<!--->`, whose value is empty, see [`find_comment_end`]. An unterminated comment
+/// has no closing delimiter.
+/// https://html.spec.whatwg.org/multipage/parsing.html#parse-error-abrupt-closing-of-empty-comment
+pub fn comment_value(text: &str) -> (&str, bool) {
+  if let Some(rest) = text.strip_prefix("<!--") {
+    if matches!(rest, ">" | "->") {
+      return ("", false);
+    }
+    let value = rest
+      .strip_suffix("--!>")
+      .or_else(|| rest.strip_suffix("-->"))
+      .unwrap_or(rest);
+    (value, false)
+  } else if let Some(rest) = text.strip_prefix("<!") {
+    // bogus comment, or CDATA section: `<! ... >`
+    (rest.strip_suffix('>').unwrap_or(rest), true)
+  } else if let Some(rest) = text.strip_prefix('<') {
+    // bogus comment: `<? ... >`, the `?` belongs to the comment data
+    (rest.strip_suffix('>').unwrap_or(rest), true)
+  } else {
+    (text, false)
+  }
+}
+
+/// Escape state of script data, where `</script>` does not always end the script.
+/// https://html.spec.whatwg.org/multipage/parsing.html#script-data-escaped-state
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptEscape {
+  #[default]
+  Unescaped,
+  /// After `<!--`, until `-->`
+  Escaped,
+  /// After `<script>` in an escaped section, until `</script>` or `-->`
+  DoubleEscaped,
+}
+
+/// Find the appropriate end tag of raw text: `closing_tag`, like `</script`, ignoring case,
+/// followed by whitespace, `/` or `>`. Only candidates starting before `until` are looked
+/// at, so text after it can be used as lookahead.
+/// https://html.spec.whatwg.org/multipage/parsing.html#appropriate-end-tag-token
+///
+/// Script data follows the escape states, from `escape` and updated up to `until`: the end
+/// tag ends a script in an escaped section like `<!-- </script>`, but not in a double
+/// escaped one like `<!-- <script></script>`. A `"</script>"` string in JavaScript ends
+/// the script, like in browsers.
+pub fn find_raw_text_end(
+  text: &[u8],
+  closing_tag: &[u8],
+  until: usize,
+  escape: &mut ScriptEscape,
+) -> Option<usize> {
+  let script = closing_tag.eq_ignore_ascii_case(b"</script");
+  let mut from = 0;
+
+  while from < until {
+    let index = if script {
+      memchr2(b'<', b'-', &text[from..until])
+    } else {
+      memchr(b'<', &text[from..until])
+    };
+    let i = from + index?;
+    let rest = &text[i..];
+    from = i + 1;
+
+    match escape {
+      ScriptEscape::Unescaped | ScriptEscape::Escaped if starts_with_tag(rest, closing_tag) => {
+        return Some(i);
+      }
+      ScriptEscape::Unescaped if script && rest.starts_with(b"<!--") => {
+        *escape = ScriptEscape::Escaped;
+        // the dashes may be those of `-->` too, like in `<!-->`
+        from = i + 2;
+      }
+      ScriptEscape::Escaped if starts_with_tag(rest, b"<script") => {
+        *escape = ScriptEscape::DoubleEscaped;
+        from = i + 7;
+      }
+      ScriptEscape::DoubleEscaped if starts_with_tag(rest, closing_tag) => {
+        *escape = ScriptEscape::Escaped;
+        from = i + closing_tag.len();
+      }
+      ScriptEscape::Escaped | ScriptEscape::DoubleEscaped if rest.starts_with(b"-->") => {
+        *escape = ScriptEscape::Unescaped;
+        from = i + 3;
+      }
+      _ => {}
+    }
+  }
+
+  None
+}
+
+/// Find the closing delimiter of a template tag in `content`, the text after its opening
+/// one.
+pub fn find_template_end(delimiters: &TemplateDelimiters, content: &[u8]) -> Option<usize> {
+  let close = delimiters.close.as_bytes();
+  if delimiters.balanced {
+    find_balanced_end(content, delimiters.open.as_bytes(), close)
+  } else {
+    find(content, close)
+  }
+}
+
+/// Find the `close` ending the content at the start of `text`, after the `open`s and
+/// `close`s nested in it, skipping the strings, template literals and comments of
+/// JavaScript, like the `{...}` expressions of JSX.
+///
+/// Quotes in the text of elements nested in an expression are read as strings, so they
+/// must be balanced, e.g. `{ok && <p>It&apos;s fine</p>}`.
+pub fn find_balanced_end(text: &[u8], open: &[u8], close: &[u8]) -> Option<usize> {
+  let mut depth = 0;
+  let mut i = 0;
+  while i < text.len() {
+    let rest = &text[i..];
+    match rest[0] {
+      quote @ (b'"' | b'\'' | b'`') => i += 1 + string_len(&rest[1..], quote),
+      b'/' if rest.starts_with(b"//") => i += memchr(b'\n', rest).unwrap_or(rest.len()),
+      b'/' if rest.starts_with(b"/*") => i += find(rest, b"*/").map_or(rest.len(), |end| end + 2),
+      _ if rest.starts_with(close) => {
+        if depth == 0 {
+          return Some(i);
+        }
+        depth -= 1;
+        i += close.len();
+      }
+      _ if rest.starts_with(open) => {
+        depth += 1;
+        i += open.len();
+      }
+      _ => i += 1,
+    }
+  }
+  None
+}
+
+/// Length of a JavaScript string up to and including its closing `quote`, after its
+/// opening one, to the end of `text` if it is unterminated.
+fn string_len(text: &[u8], quote: u8) -> usize {
+  let mut i = 0;
+  while let Some(index) = memchr2(quote, b'\\', &text[i..]) {
+    i += index;
+    if text[i] == quote {
+      return i + 1;
+    }
+    // skip the escaped character
+    i += 2;
+    if i >= text.len() {
+      break;
+    }
+  }
+  text.len()
+}
+
+/// Whether `text` starts with `tag`, ignoring case, followed by whitespace, `/` or `>`.
+fn starts_with_tag(text: &[u8], tag: &[u8]) -> bool {
+  text
+    .get(..tag.len())
+    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(tag))
+    && text
+      .get(tag.len())
+      .is_some_and(|&c| c == b'/' || c == b'>' || c.is_ascii_whitespace())
+}
+
+#[cfg(test)]
+mod test {
+  use crate::lexer::scan::{
+    CommentClose, ScriptEscape, char_len, comment_value, find_balanced_end, find_comment_end,
+    find_raw_text_end, is_char_boundary, name_len, unquoted_value_len, whitespace_len,
+  };
+
+  #[test]
+  fn classes() {
+    for byte in 0..=u8::MAX {
+      let bytes = [byte];
+      let whitespace = byte.is_ascii_whitespace();
+      assert_eq!(whitespace_len(&bytes), usize::from(whitespace));
+      assert_eq!(
+        name_len(&bytes),
+        usize::from(!whitespace && !matches!(byte, b'>' | b'=' | b'/'))
+      );
+      assert_eq!(
+        unquoted_value_len(&bytes),
+        usize::from(!whitespace && byte != b'>')
+      );
+    }
+
+    assert_eq!(whitespace_len(b" \t\r\n\x0Cx"), 5);
+    assert_eq!(name_len("data-é=1".as_bytes()), 7);
+    assert_eq!(unquoted_value_len(b"/a=b/ c"), 5);
+    assert_eq!(name_len(b"abc"), 3);
+  }
+
+  #[test]
+  fn comment_ends() {
+    assert_eq!(
+      find_comment_end(b">a-->"),
+      Some((0, 1, CommentClose::Abrupt))
+    );
+    assert_eq!(
+      find_comment_end(b"->a-->"),
+      Some((0, 2, CommentClose::Abrupt))
+    );
+    assert_eq!(find_comment_end(b"-->"), Some((0, 3, CommentClose::Normal)));
+    assert_eq!(
+      find_comment_end(b" a > b -> c -->"),
+      Some((12, 3, CommentClose::Normal))
+    );
+    assert_eq!(
+      find_comment_end(b" a --!> -->"),
+      Some((3, 4, CommentClose::Bang))
+    );
+    assert_eq!(find_comment_end(b" a -!>"), None);
+  }
+
+  #[test]
+  fn comment_values() {
+    for (text, value) in [
+      ("<!-- a -->", " a "),
+      ("<!-- a --!>", " a "),
+      ("<!---->", ""),
+      ("<!-- a --->", " a -"),
+      ("<!-->", ""),
+      ("<!--->", ""),
+      ("<!-- a", " a"),
+      ("<!--", ""),
+    ] {
+      assert_eq!(comment_value(text), (value, false), "{text}");
+    }
+    assert_eq!(comment_value("<! a >"), (" a ", true));
+    assert_eq!(comment_value("<?xml?>"), ("?xml?", true));
+    assert_eq!(comment_value("<![CDATA[a]]>"), ("[CDATA[a]]", true));
+  }
+
+  #[test]
+  fn char_boundaries() {
+    let text = "aé€😀<";
+    for index in 0..=text.len() + 1 {
+      assert_eq!(
+        is_char_boundary(text.as_bytes(), index),
+        text.is_char_boundary(index)
+      );
+    }
+    for (_, c) in text.char_indices() {
+      let mut bytes = [0; 4];
+      let encoded = c.encode_utf8(&mut bytes);
+      assert_eq!(char_len(encoded.as_bytes()[0]) as usize, encoded.len());
+    }
+  }
+
+  #[test]
+  fn end_tag() {
+    let find = |text: &str, closing_tag: &[u8]| {
+      let mut escape = ScriptEscape::default();
+      find_raw_text_end(text.as_bytes(), closing_tag, text.len(), &mut escape)
+    };
+
+    assert_eq!(find("a</scripts></SCRIPT>", b"</script"), Some(11));
+    assert_eq!(find("a</Script\t>", b"</script"), Some(1));
+    assert_eq!(find("a</script", b"</script"), None);
+    assert_eq!(find("<!-- </style>", b"</style"), Some(5));
+
+    // escaped, double escaped, and back
+    assert_eq!(find("<!-- </script>", b"</script"), Some(5));
+    assert_eq!(
+      find("<!-- <script></script> --></script>", b"</script"),
+      Some(26)
+    );
+    assert_eq!(find("<!--<script>--></script>", b"</script"), Some(15));
+    assert_eq!(find("<!--> <script></script>", b"</script"), Some(14));
+    assert_eq!(find("x = '<!-- <script>'; </script>", b"</script"), None);
+    assert_eq!(find("x = '</script>'", b"</script"), Some(5));
+  }
+
+  #[test]
+  fn resumable() {
+    let text = b"<!-- <script> </script> --> </script>";
+    let mut escape = ScriptEscape::default();
+    assert_eq!(find_raw_text_end(text, b"</script", 10, &mut escape), None);
+    assert_eq!(escape, ScriptEscape::DoubleEscaped);
+    let rest = &text[10..];
+    assert_eq!(
+      find_raw_text_end(rest, b"</script", rest.len(), &mut escape),
+      Some(18)
+    );
+  }
+
+  #[test]
+  fn balanced_end() {
+    let find = |text: &str| find_balanced_end(text.as_bytes(), b"{", b"}");
+
+    assert_eq!(find("a}"), Some(1));
+    assert_eq!(find("{ a: { b } }}"), Some(12));
+    assert_eq!(find(r#"'}' + "\"}" + `}`}"#), Some(17));
+    assert_eq!(find("a /* } */ // }\n}"), Some(15));
+    assert_eq!(find("items.map((i) => <li>{i}</li>)}"), Some(30));
+    assert_eq!(find("{a}"), None);
+    assert_eq!(find("'}"), None);
+  }
+}
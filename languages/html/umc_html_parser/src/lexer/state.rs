@@ -1,3 +1,4 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub(super) enum LexerStateKind {
   /// In the element content
@@ -12,44 +13,94 @@ pub(super) enum LexerStateKind {
   /// After tag name but before the tag end
   /// e.g. <a|>foo</a> or <a href|="https://example.com">foo</a>
   AfterTagName,
+  /// After `<!DOCTYPE` but before its end, see [`DoctypePart`]
+  /// e.g. <!DOCTYPE| html>
+  Doctype,
   /// Finished lexing
   Finished,
 }
 
+/// The next part of a DOCTYPE, in the order they are written
+/// https://html.spec.whatwg.org/multipage/parsing.html#doctype-state
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(super) enum DoctypePart {
+  /// `html`
+  #[default]
+  Name,
+  /// `PUBLIC` or `SYSTEM`
+  Keyword,
+  /// The quoted identifier after `PUBLIC`
+  PublicId,
+  /// The quoted identifier after `SYSTEM`, or after the public one
+  SystemId,
+  /// Anything else, up to the `>`
+  Bogus,
+}
+
 pub(super) struct LexerState<'a> {
   pub kind: LexerStateKind,
+  /// Whether the tag being lexed is an opening tag, until its name is lexed
+  opening: bool,
+  /// The name of the opening tag being lexed, `None` in closing tags
   tag_name: Option<&'a str>,
-  allow_to_set_tag_name: bool,
+  /// The name of the element the embedded content is in, e.g. `script`
+  embedded: Option<&'a str>,
+  /// After `=` in a tag, so the next attribute token is a value
+  pub expect_value: bool,
+  /// In a DOCTYPE, the part the next word is
+  pub doctype: DoctypePart,
 }
 
 impl LexerState<'_> {
   pub const fn new(kind: LexerStateKind) -> Self {
     LexerState {
       kind,
+      opening: false,
       tag_name: None,
-      allow_to_set_tag_name: false,
+      embedded: None,
+      expect_value: false,
+      doctype: DoctypePart::Name,
     }
   }
 }
 
 impl<'a> LexerState<'a> {
-  pub const fn allow_to_set_tag_name(&mut self) {
-    self.allow_to_set_tag_name = true;
+  /// Start a tag after its `<` or `</`
+  pub const fn start_tag(&mut self, opening: bool) {
+    self.kind = LexerStateKind::InTag;
+    self.opening = opening;
+    self.tag_name = None;
   }
 
+  /// Keep the name of the tag, if it is an opening tag
   pub const fn set_tag_name(&mut self, tag_name: &'a str) {
-    if self.allow_to_set_tag_name {
+    self.kind = LexerStateKind::AfterTagName;
+    if self.opening {
       self.tag_name = Some(tag_name);
     }
-
-    self.allow_to_set_tag_name = false;
+    self.opening = false;
   }
 
-  pub const fn get_tag_name(&self) -> Option<&str> {
+  /// The name of the opening tag being lexed
+  pub const fn tag_name(&self) -> Option<&'a str> {
     self.tag_name
   }
 
-  pub const fn take_tag_name(&mut self) -> Option<&str> {
-    self.tag_name.take()
+  /// End the tag at its `>` or `/>`, its content being embedded when `embedded` is true.
+  /// Only an opening tag which is not self-closing has embedded content.
+  pub const fn end_tag(&mut self, embedded: bool) {
+    let tag_name = self.tag_name.take();
+    self.opening = false;
+    if embedded && tag_name.is_some() {
+      self.kind = LexerStateKind::EmbeddedContent;
+      self.embedded = tag_name;
+    } else {
+      self.kind = LexerStateKind::Content;
+    }
+  }
+
+  /// Take the name of the element the embedded content is in
+  pub const fn take_embedded(&mut self) -> Option<&'a str> {
+    self.embedded.take()
   }
 }
@@ -21,10 +21,25 @@ use oxc_parser::ParseOptions;
 use umc_html_ast::Program;
 use umc_parser::{LanguageParser, Parser};
 
-use crate::{option::HtmlParserOption, parse::HtmlParserImpl};
+use crate::{encoding::Encoding, option::HtmlParserOption, parse::HtmlParserImpl};
 
+pub mod budget;
+pub mod classes;
+#[cfg(feature = "serialize")]
+mod config;
+pub mod dialect;
+pub mod document;
+pub mod encoding;
+pub mod error;
+pub mod fix;
 mod lexer;
+mod module;
+mod parallel;
 mod parse;
+pub mod stream;
+pub mod style;
+pub mod tokenizer;
+mod trace;
 
 /// HTML language parser marker type.
 ///
@@ -33,6 +48,10 @@ mod parse;
 pub struct Html;
 
 impl LanguageParser for Html {
+  const NAME: &'static str = "html";
+  const EXTENSIONS: &'static [&'static str] = &["html", "htm"];
+  const MIME_TYPES: &'static [&'static str] = &["text/html"];
+
   /// The parsed result is an arena-allocated vector of AST nodes.
   /// Uses `oxc_allocator::Vec` for cache-friendly traversal and bulk deallocation.
   type Result<'a> = Program<'a>;
@@ -61,6 +80,34 @@ pub trait CreateHtml<'a> {
   /// - `allocator`: Memory arena for allocating AST nodes
   /// - `source_text`: HTML source code to parse
   fn html(allocator: &'a Allocator, source_text: &'a str) -> Self;
+
+  /// Create a parser for an HTML document of unknown encoding.
+  ///
+  /// The encoding is detected with [`sniff`](encoding::sniff), and the document is
+  /// decoded into the allocator before parsing. Spans refer to the decoded text,
+  /// available as [`Parser::source_text`].
+  ///
+  /// # Parameters
+  /// - `allocator`: Memory arena for allocating AST nodes and the decoded text
+  /// - `bytes`: HTML document to parse
+  /// - `encoding_hint`: Encoding reported by the transport layer, e.g. an HTTP `Content-Type`
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// use oxc_allocator::Allocator;
+  /// use umc_html_parser::CreateHtml;
+  /// use umc_parser::Parser;
+  ///
+  /// let allocator = Allocator::default();
+  /// let parser = Parser::html_bytes(&allocator, b"<meta charset=latin1><p>caf\xE9</p>", None);
+  /// assert_eq!(parser.source_text, "<meta charset=latin1><p>caf\u{e9}</p>");
+  /// ```
+  fn html_bytes(
+    allocator: &'a Allocator,
+    bytes: &'a [u8],
+    encoding_hint: Option<&'static Encoding>,
+  ) -> Self;
 }
 
 impl<'a> CreateHtml<'a> for Parser<'a, Html> {
@@ -68,6 +115,16 @@ impl<'a> CreateHtml<'a> for Parser<'a, Html> {
   fn html(allocator: &'a Allocator, source_text: &'a str) -> Self {
     Parser::<Html>::new(allocator, source_text)
   }
+
+  /// Create a parser for Html parsing from bytes of unknown encoding
+  fn html_bytes(
+    allocator: &'a Allocator,
+    bytes: &'a [u8],
+    encoding_hint: Option<&'static Encoding>,
+  ) -> Self {
+    let (source_text, _) = encoding::decode_document(allocator, bytes, encoding_hint);
+    Parser::<Html>::new(allocator, source_text)
+  }
 }
 
 /// HTML parser configuration options.
@@ -75,64 +132,723 @@ impl<'a> CreateHtml<'a> for Parser<'a, Html> {
 /// This module contains the [`HtmlParserOption`] struct for configuring
 /// how the HTML parser handles embedded languages and special content.
 pub mod option {
+  use std::{borrow::Cow, fmt, sync::Arc};
+
   use super::ParseOptions;
+  use crate::{
+    budget::ParseBudget,
+    dialect::{Dialect, DialectSpec},
+    error::ErrorLevels,
+  };
 
   /// HTML parser configuration options.
   ///
   /// Configures how the HTML parser handles embedded languages like JavaScript and CSS.
+  ///
+  /// Options are `Send + Sync`, so a single value can be shared by parses running on many
+  /// threads, or cloned for each of them.
+  #[derive(Debug, Clone)]
+  #[allow(clippy::struct_excessive_bools)]
   pub struct HtmlParserOption {
     /// The oxc_parser options for parsing content inside <script> tags.
     /// If get None, the content in <script> tag will be regarded as [Text](umc_html_ast::Text)
     pub parse_script: Option<ParseOptions>,
-    /// A function that returns true if the given tag name is an embedded language tag (e.g., "script", "style")
-    ///
-    /// # Examples
-    /// ```ignore
-    /// let option = HtmlParserOption {
-    ///   is_embedded_language_tag: Box::new(|tag_name: &str| matches!(tag_name, "script" | "style")),
-    ///   // some other options
-    /// }
+    /// The rules of the markup dialect, like its void and raw text tags, see
+    /// [`DialectSpec`]. HTML by default.
+    pub dialect_spec: DialectSpec,
+    /// Keep constructs the parser cannot understand as [RawUnknown](umc_html_ast::RawUnknown) nodes
+    /// with their exact source text, instead of coercing them into comments or dropping them.
+    ///
+    /// This covers bogus comments (`<! ... >`, `<? ... >`) and closing tags without a matching
+    /// opening tag. Parse errors are reported either way.
+    pub preserve_unknown: bool,
+    /// Maximum nesting depth of elements, `None` for no limit.
+    ///
+    /// Deeper elements are kept without children, their content becoming their siblings.
+    /// An error is reported the first time the limit is reached.
+    pub max_depth: Option<usize>,
+    /// Maximum number of attributes of an element, `None` for no limit.
+    ///
+    /// Further attributes are dropped with an error.
+    pub max_attributes: Option<usize>,
+    /// Maximum number of tokens of the document, `None` for no limit.
+    ///
+    /// Parsing stops with an error at the limit, as if the document ended there.
+    pub max_tokens: Option<usize>,
+    /// Limits aborting the parse when exceeded, see [`ParseBudget`].
+    pub budget: ParseBudget,
+    /// The severity of each kind of parse error, e.g. to report implicitly closed elements
+    /// as warnings, or not at all, when parsing tag soup.
+    pub errors: ErrorLevels,
+    /// Abort at the first error, instead of recovering from it.
+    ///
+    /// Only the first error in source order is reported, after the warnings before it. Pair
+    /// it with [`ParseResult::into_result`](umc_parser::ParseResult::into_result) to get
+    /// either a program parsed without error, or the error. Errors turned into warnings by
+    /// [`errors`](Self::errors) do not abort.
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub strict: bool,
+    /// How text nodes made of whitespace are handled, see [`Whitespace`].
+    pub whitespace: Whitespace,
+    /// Keep comments in the AST, `false` to drop them while parsing.
+    ///
+    /// Bogus comments kept as [RawUnknown](umc_html_ast::RawUnknown) nodes with
+    /// [`preserve_unknown`](Self::preserve_unknown) are kept either way.
+    pub preserve_comments: bool,
+    /// Parse the content of IE conditional comments like `<!--[if IE]> ... <![endif]-->`
+    /// into [`Comment::conditional`](umc_html_ast::Comment::conditional).
+    pub parse_conditional_comments: bool,
+    /// Measure the cost of the parse into
+    /// [`ParseResult::metrics`](umc_parser::ParseResult::metrics), e.g. to monitor parsing
+    /// in production.
+    ///
+    /// Off by default, as the clock is read around each embedded script.
+    pub collect_metrics: bool,
+    /// Parse the scripts of the document in parallel once the HTML is parsed, instead of
+    /// each one as it is found.
+    ///
+    /// Each thread parses into an arena of its own, and the programs are then copied into
+    /// the allocator of the document, so it only pays off for pages with several large
+    /// scripts. The errors of the scripts are reported after the errors of the HTML, and
+    /// do not count towards [`ParseBudget::max_errors`].
+    pub parallel_scripts: bool,
+    /// Parse scripts on first use, with [`Script::program`](umc_html_ast::Script::program),
+    /// instead of while parsing the document, for pipelines only looking at the markup.
+    ///
+    /// The errors of a script are then not reported with the errors of the document, but
+    /// by [`LazyProgram::errors`](umc_html_ast::LazyProgram::errors). Takes precedence over
+    /// [`parallel_scripts`](Self::parallel_scripts).
+    pub lazy_scripts: bool,
+    /// Load the content of external scripts, given the value of their `src`, e.g. by
+    /// reading a file or fetching a URL, to parse them like inline scripts.
+    ///
+    /// The program of a loaded script is [synthetic](umc_html_ast::Script::synthetic): its
+    /// spans are relative to the loaded content, and its errors are reported at the `src`.
+    /// Scripts the resolver returns `None` for are kept as elements, like without resolver.
+    pub resolve_script: Option<ScriptResolver>,
+    /// Parse `<script lang="ts">` and `<script type="text/typescript">` as TypeScript,
+    /// instead of as JavaScript and as a data block element like browsers.
+    ///
+    /// Scripts are parsed with the goal of their kind either way, see
+    /// [`Script::source_type`](umc_html_ast::Script::source_type).
+    pub typescript_scripts: bool,
+    /// Parse the value of `style` attributes into CSS declarations, in
+    /// [`Attribute::style`](umc_html_ast::Attribute::style), see [`style`](crate::style).
+    pub parse_style_attributes: bool,
+    /// The dialect the document is parsed as, see [`HtmlType`].
+    pub html_type: HtmlType,
+    /// Parse Handlebars and Mustache tags in the content of elements, like `{{name}}` or
+    /// `{{#each items}}...{{/each}}`, into [`Mustache`](umc_html_ast::Mustache) nodes, the
+    /// content of blocks as their children.
+    ///
+    /// Blocks are nested within the elements they are in: a block left open at the end of
+    /// its parent element is closed there, with an error.
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub handlebars: bool,
+    /// The delimiters of the tags of a foreign template language to pass through, like
+    /// `{% if user %}` in Liquid, Jinja or Twig, see [`TemplateDelimiters`].
+    ///
+    /// Template tags are kept whole wherever they are: in content as
+    /// [`TemplateTag`](umc_html_ast::TemplateTag) nodes, between attributes as attributes
+    /// without value, and inside attribute names and values. Their content is not read as
+    /// HTML, so a quote or a `>` in a tag does not end the attribute or the tag it is in.
+    /// They take precedence over [`handlebars`](Self::handlebars) tags.
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub template_delimiters: Vec<TemplateDelimiters>,
+    /// Build the tree of misnested formatting elements like browsers do, with the
+    /// [adoption agency algorithm](https://html.spec.whatwg.org/multipage/parsing.html#adoption-agency-algorithm),
+    /// e.g. `<b>1<i>2</b>3</i>` as `<b>1<i>2</i></b><i>3</i>`.
+    ///
+    /// A closing tag of a formatting element like `<b>` or `<a>` then closes it with the
+    /// elements still open in it, which are reopened after it: the formatting elements
+    /// before the next text or element, and the others right away, with a copy of the
+    /// formatting element around their content. The formatting elements closed by the
+    /// closing tag of an ancestor, as in `<p><b>1</p>2`, are reopened as well. Reopened
+    /// elements have the attributes of the original, and a zero-width opening tag where
+    /// they are reopened. An opening `<a>` or `<nobr>` tag closes the one still open the
+    /// same way, as in `<a href=x>1<a href=y>2</a>`.
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub adoption_agency: bool,
+    /// Add the `<html>`, `<head>` and `<body>` elements browsers add when they are missing,
+    /// following the [insertion modes](https://html.spec.whatwg.org/multipage/parsing.html#the-insertion-mode)
+    /// of the specification, e.g. `<title>A</title><p>B` as
+    /// `<html><head><title>A</title></head><body><p>B</p></body></html>`.
+    ///
+    /// Implied elements have a zero-width span where they are opened when empty, like the
+    /// `<head>` of `<p>A`, and no errors are reported for their missing tags.
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub implied_tags: bool,
+    /// The shape of the elements still open at the end of the document, see
+    /// [`RecoveryStrategy`]. They are reported as unclosed whatever the strategy.
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub recovery_strategy: RecoveryStrategy,
+    /// What a `/>` ending an opening tag does, like in `<div/>`, see [`SelfClosing`].
+    ///
+    /// The [streaming parser](crate::stream::HtmlStreamParser) ignores this option.
+    pub self_closing: SelfClosing,
+  }
+
+  /// The opening and closing delimiters of the tags of a template language, see
+  /// [`template_delimiters`](HtmlParserOption::template_delimiters).
+  ///
+  /// A tag ends at the first closing delimiter after its opening one, or at the one
+  /// matching it for [`balanced`](Self::balanced) delimiters. Tags are matched with the
+  /// first delimiters of the list whose opening one is found.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use oxc_allocator::Allocator;
+  /// use umc_html_ast::Node;
+  /// use umc_html_parser::CreateHtml;
+  /// use umc_html_parser::option::{HtmlParserOption, TemplateDelimiters};
+  /// use umc_parser::Parser;
+  ///
+  /// let allocator = Allocator::default();
+  /// let options = HtmlParserOption::builder()
+  ///   .template_delimiters(vec![TemplateDelimiters::STATEMENT, TemplateDelimiters::EXPRESSION])
+  ///   .build();
+  /// let source_text = r#"{% for link in links %}<a title="{{ link.title | default: "none" }}"></a>{% endfor %}"#;
+  /// let result = Parser::html(&allocator, source_text).with_options(options).parse();
+  ///
+  /// assert!(result.errors.is_empty());
+  /// let Node::Element(a) = &result.program[1] else { unreachable!() };
+  /// assert_eq!(a.attributes[0].value.as_ref().unwrap().value, r#"{{ link.title | default: "none" }}"#);
+  /// ```
+  #[derive(Debug, Clone, PartialEq, Eq)]
+  pub struct TemplateDelimiters {
+    /// The opening delimiter, e.g. `{%`. Delimiters with an empty one are ignored.
+    pub open: Cow<'static, str>,
+    /// The closing delimiter, e.g. `%}`
+    pub close: Cow<'static, str>,
+    /// Whether tags nest, like the `{...}` expressions of JSX, whose closing delimiter is
+    /// the one matching the opening one, outside of JavaScript strings and comments
+    pub balanced: bool,
+  }
+
+  impl TemplateDelimiters {
+    /// Statement tags of Liquid, Jinja and Twig: `{% ... %}`
+    pub const STATEMENT: Self = Self::new("{%", "%}");
+    /// Expression tags of Liquid, Jinja, Twig and Mustache: `{{ ... }}`
+    pub const EXPRESSION: Self = Self::new("{{", "}}");
+    /// Comment tags of Jinja and Twig: `{# ... #}`
+    pub const COMMENT: Self = Self::new("{#", "#}");
+    /// Tags of ERB and EJS, like `<%= ... %>` or `<% ... %>`
+    pub const ERB: Self = Self::new("<%", "%>");
+    /// Expressions of JSX and Astro, like `{items.map((item) => <li>{item}</li>)}`
+    pub const JSX: Self = Self::new("{", "}").balanced();
+
+    /// Create delimiters from their opening and closing ones.
+    pub const fn new(open: &'static str, close: &'static str) -> Self {
+      Self {
+        open: Cow::Borrowed(open),
+        close: Cow::Borrowed(close),
+        balanced: false,
+      }
+    }
+
+    /// Make the tags nest, see [`balanced`](Self::balanced).
+    #[must_use]
+    pub const fn balanced(mut self) -> Self {
+      self.balanced = true;
+      self
+    }
+  }
+
+  /// Loads the content of external scripts, see
+  /// [`resolve_script`](HtmlParserOption::resolve_script).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_parser::option::ScriptResolver;
+  ///
+  /// let resolver = ScriptResolver::new(|src| std::fs::read_to_string(src).ok().map(Into::into));
+  /// assert!(resolver.resolve("does/not/exist.js").is_none());
+  /// ```
+  #[derive(Clone)]
+  pub struct ScriptResolver(Arc<ResolveScript>);
+
+  type ResolveScript = dyn Fn(&str) -> Option<Cow<'static, str>> + Send + Sync;
+
+  impl ScriptResolver {
+    /// Create a resolver from a function of the `src` of a script.
+    pub fn new(
+      resolve: impl Fn(&str) -> Option<Cow<'static, str>> + Send + Sync + 'static,
+    ) -> Self {
+      Self(Arc::new(resolve))
+    }
+
+    /// Get the content of the script at `src`, `None` if it cannot be loaded.
+    pub fn resolve(&self, src: &str) -> Option<Cow<'static, str>> {
+      (self.0)(src)
+    }
+  }
+
+  impl fmt::Debug for ScriptResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      f.write_str("ScriptResolver(..)")
+    }
+  }
+
+  /// How the parser handles whitespace in text nodes.
+  ///
+  /// Whitespace is significant inside `<pre>` and `<textarea>`, whose text is always
+  /// preserved.
+  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+  #[cfg_attr(
+    feature = "serialize",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+  )]
+  pub enum Whitespace {
+    /// Keep text nodes as written
+    #[default]
+    Preserve,
+    /// Trim leading and trailing whitespace of text nodes, and drop the ones left empty
+    Trim,
+    /// Drop text nodes made of whitespace only, e.g. the indentation between elements
+    Skip,
+  }
+
+  /// The dialect the parser reads a document as.
+  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+  #[cfg_attr(
+    feature = "serialize",
+    derive(serde::Deserialize),
+    serde(rename_all = "lowercase")
+  )]
+  pub enum HtmlType {
+    /// HTML, whatever its DOCTYPE
+    #[default]
+    Html,
+    /// XHTML, whose element and attribute names are case-sensitive, even with
+    /// [`normalize_case`](DialectSpec::normalize_case)
+    Xhtml,
+    /// The [dialect](crate::dialect::Dialect) declared by the first DOCTYPE of the
+    /// document, HTML until then. Documents without DOCTYPE are parsed as HTML, in quirks
+    /// mode.
+    Auto,
+  }
+
+  /// What the parser does with the elements still open at the end of the document, e.g. of a
+  /// truncated document like `<section><h2>Title</h2><p>Some te`.
+  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+  #[cfg_attr(
+    feature = "serialize",
+    derive(serde::Deserialize),
+    serde(rename_all = "camelCase")
+  )]
+  pub enum RecoveryStrategy {
+    /// Close them where their content ends, as browsers do
+    #[default]
+    AutoClose,
+    /// Drop the innermost one with its content, the part of the document being written when
+    /// it was cut, and close the others. `<html>`, `<head>` and `<body>`, whose closing tags
+    /// are optional, are always closed.
+    DiscardIncomplete,
+    /// Move the content of the ones in the innermost open sectioning element (`<article>`,
+    /// `<aside>`, `<nav>`, `<section>` or `<body>`) up into it, and close the others. With
+    /// no sectioning element open, they are closed.
+    BubbleToSection,
+  }
+
+  /// What a `/>` ending an opening tag does, like in `<div/>`.
+  #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+  #[cfg_attr(
+    feature = "serialize",
+    derive(serde::Deserialize),
+    serde(rename_all = "camelCase")
+  )]
+  pub enum SelfClosing {
+    /// Close the element, like in XML or JSX
+    #[default]
+    TreatSelfClosingAsClosed,
+    /// Ignore the slash on the elements of HTML which are not void, as browsers do, with a
+    /// [`TrailingSolidus`](crate::error::HtmlParseErrorKind::TrailingSolidus) warning.
+    /// The slash still closes the elements of SVG and MathML, like `<path/>`, outside of their
+    /// HTML integration points like `<foreignObject>`. The content of raw text elements like
+    /// `<script/>` is read up to their closing tag.
+    Spec,
+  }
+
+  impl HtmlType {
+    /// Get the dialect to parse with, `None` if it is read from the DOCTYPE.
+    pub(crate) const fn dialect(self) -> Option<Dialect> {
+      match self {
+        Self::Html => Some(Dialect::Html5),
+        Self::Xhtml => Some(Dialect::Xhtml),
+        Self::Auto => None,
+      }
+    }
+  }
+
+  impl HtmlParserOption {
+    /// Create options from the defaults with chained setters.
+    ///
+    /// ## Example
+    ///
     /// ```
-    pub is_embedded_language_tag: Box<dyn Fn(&str) -> bool>,
-    /// A function that returns true if the given tag name is a void tag (e.g., "br", "hr", "img")
-    ///
-    /// # Examples
-    /// ```ignore
-    /// let option = HtmlParserOption {
-    ///   is_void_tag: Box::new(|tag_name: &str| matches!(tag_name, "br" | "hr" | "img")),
-    ///   // some other options
-    /// }
+    /// use umc_html_parser::option::{HtmlParserOption, TagSet, Whitespace};
+    ///
+    /// let options = HtmlParserOption::builder()
+    ///   .void_tags(TagSet::Custom(&["br", "x-icon"]))
+    ///   .whitespace(Whitespace::Skip)
+    ///   .strict(true)
+    ///   .build();
+    ///
+    /// assert!(options.strict);
+    /// assert!(options.dialect_spec.void_tags.contains("x-icon"));
     /// ```
-    pub is_void_tag: Box<dyn Fn(&str) -> bool>,
+    pub fn builder() -> HtmlParserOptionBuilder {
+      HtmlParserOptionBuilder {
+        options: Self::default(),
+      }
+    }
+  }
+
+  /// Builder of [`HtmlParserOption`], see [`HtmlParserOption::builder`].
+  #[derive(Debug, Clone)]
+  pub struct HtmlParserOptionBuilder {
+    options: HtmlParserOption,
+  }
+
+  impl HtmlParserOptionBuilder {
+    /// Set [`parse_script`](HtmlParserOption::parse_script).
+    #[must_use]
+    pub const fn parse_script(mut self, parse_script: Option<ParseOptions>) -> Self {
+      self.options.parse_script = parse_script;
+      self
+    }
+
+    /// Set [`dialect_spec`](HtmlParserOption::dialect_spec).
+    #[must_use]
+    pub fn dialect_spec(mut self, dialect_spec: DialectSpec) -> Self {
+      self.options.dialect_spec = dialect_spec;
+      self
+    }
+
+    /// Set the [`raw_text_tags`](DialectSpec::raw_text_tags) of the dialect.
+    #[must_use]
+    pub fn embedded_language_tags(mut self, embedded_language_tags: TagSet) -> Self {
+      self.options.dialect_spec.raw_text_tags = embedded_language_tags;
+      self
+    }
+
+    /// Set the [`void_tags`](DialectSpec::void_tags) of the dialect.
+    #[must_use]
+    pub fn void_tags(mut self, void_tags: TagSet) -> Self {
+      self.options.dialect_spec.void_tags = void_tags;
+      self
+    }
+
+    /// Set [`preserve_unknown`](HtmlParserOption::preserve_unknown).
+    #[must_use]
+    pub const fn preserve_unknown(mut self, preserve_unknown: bool) -> Self {
+      self.options.preserve_unknown = preserve_unknown;
+      self
+    }
+
+    /// Set [`max_depth`](HtmlParserOption::max_depth).
+    #[must_use]
+    pub const fn max_depth(mut self, max_depth: Option<usize>) -> Self {
+      self.options.max_depth = max_depth;
+      self
+    }
+
+    /// Set [`max_attributes`](HtmlParserOption::max_attributes).
+    #[must_use]
+    pub const fn max_attributes(mut self, max_attributes: Option<usize>) -> Self {
+      self.options.max_attributes = max_attributes;
+      self
+    }
+
+    /// Set [`max_tokens`](HtmlParserOption::max_tokens).
+    #[must_use]
+    pub const fn max_tokens(mut self, max_tokens: Option<usize>) -> Self {
+      self.options.max_tokens = max_tokens;
+      self
+    }
+
+    /// Set [`budget`](HtmlParserOption::budget).
+    #[must_use]
+    pub const fn budget(mut self, budget: ParseBudget) -> Self {
+      self.options.budget = budget;
+      self
+    }
+
+    /// Set [`errors`](HtmlParserOption::errors).
+    #[must_use]
+    pub fn errors(mut self, errors: ErrorLevels) -> Self {
+      self.options.errors = errors;
+      self
+    }
+
+    /// Set [`strict`](HtmlParserOption::strict).
+    #[must_use]
+    pub const fn strict(mut self, strict: bool) -> Self {
+      self.options.strict = strict;
+      self
+    }
+
+    /// Set [`whitespace`](HtmlParserOption::whitespace).
+    #[must_use]
+    pub const fn whitespace(mut self, whitespace: Whitespace) -> Self {
+      self.options.whitespace = whitespace;
+      self
+    }
+
+    /// Set [`preserve_comments`](HtmlParserOption::preserve_comments).
+    #[must_use]
+    pub const fn preserve_comments(mut self, preserve_comments: bool) -> Self {
+      self.options.preserve_comments = preserve_comments;
+      self
+    }
+
+    /// Set [`parse_conditional_comments`](HtmlParserOption::parse_conditional_comments).
+    #[must_use]
+    pub const fn parse_conditional_comments(mut self, parse_conditional_comments: bool) -> Self {
+      self.options.parse_conditional_comments = parse_conditional_comments;
+      self
+    }
+
+    /// Set the [`normalize_case`](DialectSpec::normalize_case) of the dialect.
+    #[must_use]
+    pub const fn normalize_case(mut self, normalize_case: bool) -> Self {
+      self.options.dialect_spec.normalize_case = normalize_case;
+      self
+    }
+
+    /// Set [`collect_metrics`](HtmlParserOption::collect_metrics).
+    #[must_use]
+    pub const fn collect_metrics(mut self, collect_metrics: bool) -> Self {
+      self.options.collect_metrics = collect_metrics;
+      self
+    }
+
+    /// Set [`parallel_scripts`](HtmlParserOption::parallel_scripts).
+    #[must_use]
+    pub const fn parallel_scripts(mut self, parallel_scripts: bool) -> Self {
+      self.options.parallel_scripts = parallel_scripts;
+      self
+    }
+
+    /// Set [`lazy_scripts`](HtmlParserOption::lazy_scripts).
+    #[must_use]
+    pub const fn lazy_scripts(mut self, lazy_scripts: bool) -> Self {
+      self.options.lazy_scripts = lazy_scripts;
+      self
+    }
+
+    /// Set [`resolve_script`](HtmlParserOption::resolve_script).
+    #[must_use]
+    pub fn resolve_script(mut self, resolve_script: Option<ScriptResolver>) -> Self {
+      self.options.resolve_script = resolve_script;
+      self
+    }
+
+    /// Set [`typescript_scripts`](HtmlParserOption::typescript_scripts).
+    #[must_use]
+    pub const fn typescript_scripts(mut self, typescript_scripts: bool) -> Self {
+      self.options.typescript_scripts = typescript_scripts;
+      self
+    }
+
+    /// Set [`parse_style_attributes`](HtmlParserOption::parse_style_attributes).
+    #[must_use]
+    pub const fn parse_style_attributes(mut self, parse_style_attributes: bool) -> Self {
+      self.options.parse_style_attributes = parse_style_attributes;
+      self
+    }
+
+    /// Set [`html_type`](HtmlParserOption::html_type).
+    #[must_use]
+    pub const fn html_type(mut self, html_type: HtmlType) -> Self {
+      self.options.html_type = html_type;
+      self
+    }
+
+    /// Set [`handlebars`](HtmlParserOption::handlebars).
+    #[must_use]
+    pub const fn handlebars(mut self, handlebars: bool) -> Self {
+      self.options.handlebars = handlebars;
+      self
+    }
+
+    /// Set [`template_delimiters`](HtmlParserOption::template_delimiters).
+    #[must_use]
+    pub fn template_delimiters(mut self, template_delimiters: Vec<TemplateDelimiters>) -> Self {
+      self.options.template_delimiters = template_delimiters;
+      self
+    }
+
+    /// Set [`adoption_agency`](HtmlParserOption::adoption_agency).
+    #[must_use]
+    pub const fn adoption_agency(mut self, adoption_agency: bool) -> Self {
+      self.options.adoption_agency = adoption_agency;
+      self
+    }
+
+    /// Set [`implied_tags`](HtmlParserOption::implied_tags).
+    #[must_use]
+    pub const fn implied_tags(mut self, implied_tags: bool) -> Self {
+      self.options.implied_tags = implied_tags;
+      self
+    }
+
+    /// Set [`recovery_strategy`](HtmlParserOption::recovery_strategy).
+    #[must_use]
+    pub const fn recovery_strategy(mut self, recovery_strategy: RecoveryStrategy) -> Self {
+      self.options.recovery_strategy = recovery_strategy;
+      self
+    }
+
+    /// Set [`self_closing`](HtmlParserOption::self_closing).
+    #[must_use]
+    pub const fn self_closing(mut self, self_closing: SelfClosing) -> Self {
+      self.options.self_closing = self_closing;
+      self
+    }
+
+    /// Get the options.
+    pub fn build(self) -> HtmlParserOption {
+      self.options
+    }
+  }
+
+  /// A set of tag names, like the void tags of HTML.
+  ///
+  /// Tag names are compared ASCII case-insensitively, without allocating, except by
+  /// [`Callback`](Self::Callback) and [`Closure`](Self::Closure) which get the name as written.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_parser::option::TagSet;
+  ///
+  /// assert!(TagSet::Html5Void.contains("BR"));
+  ///
+  /// let tags = TagSet::Custom(&["x-icon", "x-spacer"]);
+  /// assert!(tags.contains("X-Icon"));
+  /// assert!(!tags.contains("br"));
+  ///
+  /// let tags = TagSet::Callback(|name| name.starts_with("x-"));
+  /// assert!(tags.contains("x-icon"));
+  /// ```
+  #[derive(Clone)]
+  pub enum TagSet {
+    /// The void elements of HTML, like `br` and `img`, see [`HTML5_VOID_TAGS`]
+    Html5Void,
+    /// The raw text elements of HTML, `script` and `style`, see [`HTML5_RAW_TEXT_TAGS`]
+    Html5RawText,
+    /// The given tag names
+    Custom(&'static [&'static str]),
+    /// Tag names for which the function returns true
+    Callback(fn(&str) -> bool),
+    /// Tag names for which the closure returns true, e.g. for a set only known at runtime
+    Closure(Arc<dyn Fn(&str) -> bool + Send + Sync>),
+  }
+
+  /// The void elements of HTML.
+  pub const HTML5_VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "keygen", "link", "meta", "param",
+    "source", "track", "wbr",
+  ];
+
+  /// The raw text elements of HTML.
+  pub const HTML5_RAW_TEXT_TAGS: &[&str] = &["script", "style"];
+
+  /// The elements of HTML whose whitespace is significant.
+  pub const HTML5_PREFORMATTED_TAGS: &[&str] = &["pre", "textarea"];
+
+  impl TagSet {
+    /// A set of tag names only known at runtime, e.g. read from a configuration file.
+    pub fn from_names(names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+      let names: Vec<String> = names.into_iter().map(Into::into).collect();
+      Self::Closure(Arc::new(move |tag_name: &str| {
+        names.iter().any(|name| name.eq_ignore_ascii_case(tag_name))
+      }))
+    }
+
+    /// The set with more tag names, e.g. to register custom elements as void or raw text
+    /// elements next to the ones of HTML.
+    ///
+    /// ## Example
+    ///
+    /// ```
+    /// use umc_html_parser::option::TagSet;
+    ///
+    /// let tags = TagSet::Html5Void.with_names(["x-icon"]);
+    /// assert!(tags.contains("br"));
+    /// assert!(tags.contains("x-icon"));
+    /// ```
+    #[must_use]
+    pub fn with_names(self, names: impl IntoIterator<Item = impl Into<String>>) -> Self {
+      let names: Vec<String> = names.into_iter().map(Into::into).collect();
+      Self::Closure(Arc::new(move |tag_name: &str| {
+        self.contains(tag_name) || names.iter().any(|name| name.eq_ignore_ascii_case(tag_name))
+      }))
+    }
+
+    /// Whether the set contains a tag name.
+    pub fn contains(&self, tag_name: &str) -> bool {
+      let contains = |tags: &[&str]| tags.iter().any(|tag| tag.eq_ignore_ascii_case(tag_name));
+      match self {
+        Self::Html5Void => contains(HTML5_VOID_TAGS),
+        Self::Html5RawText => contains(HTML5_RAW_TEXT_TAGS),
+        Self::Custom(tags) => contains(tags),
+        Self::Callback(callback) => callback(tag_name),
+        Self::Closure(closure) => closure(tag_name),
+      }
+    }
+  }
+
+  impl fmt::Debug for TagSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+      match self {
+        Self::Html5Void => f.write_str("Html5Void"),
+        Self::Html5RawText => f.write_str("Html5RawText"),
+        Self::Custom(tags) => f.debug_tuple("Custom").field(tags).finish(),
+        Self::Callback(_) => f.write_str("Callback(..)"),
+        Self::Closure(_) => f.write_str("Closure(..)"),
+      }
+    }
   }
 
   impl Default for HtmlParserOption {
     fn default() -> Self {
       Self {
         parse_script: Some(ParseOptions::default()),
-        is_embedded_language_tag: Box::new(|tag_name: &str| {
-          matches!(tag_name.to_ascii_lowercase().as_str(), "script" | "style")
-        }),
-        is_void_tag: Box::new(|tag_name: &str| {
-          matches!(
-            tag_name.to_ascii_lowercase().as_str(),
-            "area"
-              | "base"
-              | "br"
-              | "col"
-              | "embed"
-              | "hr"
-              | "img"
-              | "input"
-              | "keygen"
-              | "link"
-              | "meta"
-              | "param"
-              | "source"
-              | "track"
-              | "wbr"
-          )
-        }),
+        dialect_spec: DialectSpec::HTML5,
+        preserve_unknown: false,
+        max_depth: None,
+        max_attributes: None,
+        max_tokens: None,
+        budget: ParseBudget::default(),
+        errors: ErrorLevels::default(),
+        strict: false,
+        whitespace: Whitespace::Preserve,
+        preserve_comments: true,
+        parse_conditional_comments: false,
+        collect_metrics: false,
+        parallel_scripts: false,
+        lazy_scripts: false,
+        resolve_script: None,
+        typescript_scripts: false,
+        parse_style_attributes: false,
+        html_type: HtmlType::Html,
+        handlebars: false,
+        template_delimiters: Vec::new(),
+        adoption_agency: false,
+        implied_tags: false,
+        recovery_strategy: RecoveryStrategy::AutoClose,
+        self_closing: SelfClosing::TreatSelfClosingAsClosed,
       }
     }
   }
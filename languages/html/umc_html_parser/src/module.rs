@@ -0,0 +1,186 @@
+//! Imports and exports of module scripts, see [`Script::module`](umc_html_ast::Script::module).
+//!
+//! Only the static imports and exports, at the top level of the module, are recorded:
+//! dynamic `import()` calls are left to JavaScript tools.
+//!
+//! Specifiers are resolved by the `"imports"` of the `<script type="importmap">` before
+//! them in the document, matching a specifier exactly or by its longest prefix ending with
+//! `/`. Scopes and the resolution of URLs against the base URL of the document are left to
+//! bundlers.
+
+use oxc_allocator::{Allocator, Box, Vec as ArenaVec};
+use oxc_ast::ast::{
+  Declaration, Expression, ModuleExportName, ObjectPropertyKind, Program as JsProgram, Statement,
+};
+use oxc_parser::Parser as JsParser;
+use oxc_span::{GetSpan, SourceType};
+use umc_html_ast::{ModuleExport, ModuleImport, ModuleRecord, Node, ScriptKind};
+use umc_span::Span;
+
+/// Record the imports and exports of a module, whose source starts at `offset` in the
+/// document.
+pub fn module_record<'a>(
+  allocator: &'a Allocator,
+  program: &JsProgram<'a>,
+  offset: u32,
+) -> Box<'a, ModuleRecord<'a>> {
+  let span = |span: oxc_span::Span| Span::new(span.start + offset, span.end + offset);
+  let mut imports = ArenaVec::new_in(allocator);
+  let mut exports = ArenaVec::new_in(allocator);
+  let mut import = |source: &oxc_ast::ast::StringLiteral<'a>| {
+    imports.push(ModuleImport {
+      span: span(source.span),
+      specifier: source.value.as_str(),
+      resolved: None,
+    });
+  };
+  let mut export = |span: Span, name: &'a str| exports.push(ModuleExport { span, name });
+  let export_name = |name: &ModuleExportName<'a>| (span(name.span()), name.name().as_str());
+
+  for statement in &program.body {
+    match statement {
+      Statement::ImportDeclaration(declaration) => import(&declaration.source),
+      Statement::ExportAllDeclaration(declaration) => {
+        import(&declaration.source);
+        if let Some(name) = &declaration.exported {
+          let (span, name) = export_name(name);
+          export(span, name);
+        }
+      }
+      Statement::ExportNamedDeclaration(declaration) => {
+        if let Some(source) = &declaration.source {
+          import(source);
+        }
+        for specifier in &declaration.specifiers {
+          let (span, name) = export_name(&specifier.exported);
+          export(span, name);
+        }
+        match &declaration.declaration {
+          Some(Declaration::VariableDeclaration(variables)) => {
+            for declarator in &variables.declarations {
+              for id in declarator.id.get_binding_identifiers() {
+                export(span(id.span), id.name.as_str());
+              }
+            }
+          }
+          Some(declaration) => {
+            if let Some(id) = declaration.id() {
+              export(span(id.span), id.name.as_str());
+            }
+          }
+          None => {}
+        }
+      }
+      Statement::ExportDefaultDeclaration(declaration) => {
+        export(span(declaration.span), "default");
+      }
+      _ => {}
+    }
+  }
+
+  Box::new_in(ModuleRecord { imports, exports }, allocator)
+}
+
+/// Resolve the imports of the module scripts with the import maps before them.
+pub fn resolve_imports<'a>(allocator: &'a Allocator, nodes: &mut [Node<'a>]) {
+  let mut import_map = Vec::new();
+  resolve_in(allocator, nodes, &mut import_map);
+}
+
+fn resolve_in<'a>(
+  allocator: &'a Allocator,
+  nodes: &mut [Node<'a>],
+  import_map: &mut Vec<(&'a str, &'a str)>,
+) {
+  for node in nodes {
+    match node {
+      Node::Element(element) => {
+        if element
+          .script_info()
+          .is_some_and(|info| info.kind == ScriptKind::ImportMap)
+        {
+          let json: String = element
+            .children
+            .iter()
+            .filter_map(|node| match node {
+              Node::Text(text) => Some(text.value),
+              _ => None,
+            })
+            .collect();
+          read_import_map(allocator, &json, import_map);
+        } else {
+          resolve_in(allocator, &mut element.children, import_map);
+        }
+      }
+      Node::Comment(comment) => {
+        if let Some(conditional) = &mut comment.conditional {
+          resolve_in(allocator, &mut conditional.children, import_map);
+        }
+      }
+      Node::Script(script) => {
+        if let Some(module) = &mut script.module {
+          for import in &mut module.imports {
+            import.resolved = resolve(allocator, import_map, import.specifier);
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+}
+
+/// Add the `"imports"` of an import map to `import_map`, ignoring an invalid import map.
+fn read_import_map<'a>(
+  allocator: &'a Allocator,
+  json: &str,
+  import_map: &mut Vec<(&'a str, &'a str)>,
+) {
+  // JSON is a JavaScript expression, parse it with a temporary allocator
+  let temporary = Allocator::default();
+  let Ok(Expression::ObjectExpression(map)) =
+    JsParser::new(&temporary, json, SourceType::default()).parse_expression()
+  else {
+    return;
+  };
+  for property in &map.properties {
+    let ObjectPropertyKind::ObjectProperty(property) = property else {
+      continue;
+    };
+    if property.key.static_name().as_deref() != Some("imports") {
+      continue;
+    }
+    let Expression::ObjectExpression(imports) = &property.value else {
+      continue;
+    };
+    for import in &imports.properties {
+      if let ObjectPropertyKind::ObjectProperty(import) = import
+        && let Some(specifier) = import.key.static_name()
+        && let Expression::StringLiteral(address) = &import.value
+      {
+        // earlier mappings win, like in browsers
+        if import_map.iter().all(|(key, _)| *key != specifier) {
+          import_map.push((
+            allocator.alloc_str(&specifier),
+            allocator.alloc_str(&address.value),
+          ));
+        }
+      }
+    }
+  }
+}
+
+/// Map a specifier exactly, or by the longest prefix ending with `/`.
+fn resolve<'a>(
+  allocator: &'a Allocator,
+  import_map: &[(&'a str, &'a str)],
+  specifier: &str,
+) -> Option<&'a str> {
+  if let Some((_, address)) = import_map.iter().find(|(key, _)| *key == specifier) {
+    return Some(address);
+  }
+  let (key, address) = import_map
+    .iter()
+    .filter(|(key, _)| key.ends_with('/') && specifier.starts_with(key))
+    .max_by_key(|(key, _)| key.len())?;
+  Some(allocator.alloc_str(&format!("{address}{}", &specifier[key.len()..])))
+}
@@ -0,0 +1,93 @@
+//! Parsing of the scripts of a document in parallel, see
+//! [`parallel_scripts`](crate::option::HtmlParserOption::parallel_scripts).
+//!
+//! An [`Allocator`] cannot be shared between threads, so each thread parses its share of the
+//! scripts into an arena of its own. The programs are then copied into the arena of the
+//! document, and the arenas of the threads dropped.
+
+use std::{num::NonZeroUsize, panic, thread};
+
+use oxc_allocator::{Allocator, CloneIn};
+use oxc_ast::ast::Program;
+use oxc_diagnostics::OxcDiagnostic;
+use oxc_parser::{ParseOptions, Parser as JsParser};
+use oxc_span::SourceType;
+
+/// A script whose parse is deferred to the end of the document.
+pub struct PendingScript<'a> {
+  /// Start of the script element, identifying its node
+  pub start: u32,
+  /// The JavaScript source
+  pub content: &'a str,
+  /// The source type to parse it with
+  pub source_type: SourceType,
+  /// Offset of the content in the document, for the spans of the errors
+  pub offset: u32,
+}
+
+/// A parsed script, with the errors of the parse.
+pub struct ParsedScript<'a> {
+  pub program: Program<'a>,
+  pub errors: Vec<OxcDiagnostic>,
+}
+
+// SAFETY: the program only references the arena of the thread which parsed it, exclusively
+// borrowed by that thread, and the scripts. Once the thread is joined, the arena is only
+// used by the thread receiving the program.
+#[allow(unsafe_code, clippy::non_send_fields_in_send_ty)]
+unsafe impl Send for ParsedScript<'_> {}
+
+/// Parse scripts on scoped threads, into `allocator`, in order.
+pub fn parse_scripts<'a>(
+  allocator: &'a Allocator,
+  scripts: &[PendingScript<'a>],
+  options: ParseOptions,
+) -> Vec<ParsedScript<'a>> {
+  let threads = thread::available_parallelism()
+    .map_or(1, NonZeroUsize::get)
+    .min(scripts.len())
+    .max(1);
+  let chunk_size = scripts.len().div_ceil(threads).max(1);
+  let mut arenas: Vec<Allocator> = (0..threads).map(|_| Allocator::default()).collect();
+
+  let parsed: Vec<ParsedScript> = thread::scope(|scope| {
+    let handles: Vec<_> = arenas
+      .iter_mut()
+      .zip(scripts.chunks(chunk_size))
+      .map(|(arena, scripts)| {
+        scope.spawn(move || {
+          let arena = &*arena;
+          scripts
+            .iter()
+            .map(|script| {
+              let ret = JsParser::new(arena, script.content, script.source_type)
+                .with_options(options)
+                .parse();
+              ParsedScript {
+                program: ret.program,
+                errors: ret.errors,
+              }
+            })
+            .collect::<Vec<_>>()
+        })
+      })
+      .collect();
+
+    handles
+      .into_iter()
+      .flat_map(|handle| {
+        handle
+          .join()
+          .unwrap_or_else(|payload| panic::resume_unwind(payload))
+      })
+      .collect()
+  });
+
+  parsed
+    .into_iter()
+    .map(|script| ParsedScript {
+      program: script.program.clone_in(allocator),
+      errors: script.errors,
+    })
+    .collect()
+}
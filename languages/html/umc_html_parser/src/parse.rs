@@ -1,19 +1,45 @@
-use std::iter::Peekable;
+use std::{
+  borrow::Cow,
+  collections::{BTreeMap, HashMap, VecDeque},
+  iter::Peekable,
+  time::{Duration, Instant},
+};
 
-use oxc_allocator::{Allocator, Box, Vec as ArenaVec};
-use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use oxc_allocator::{Allocator, Box, Dummy, Vec as ArenaVec};
+use oxc_ast::ast::Program as JsProgram;
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic, Severity};
 use oxc_parser::Parser as JsParser;
 use oxc_span::SourceType;
 use umc_html_ast::{
-  Attribute, AttributeKey, AttributeValue, Comment, Doctype, Element, Node, Program, Script, Text,
+  Attribute, AttributeKey, AttributeValue, Comment, ConditionalComment, Directive, Doctype,
+  Element, LazyProgram, ModuleRecord, Mustache, MustacheKind, Node, Program, RawUnknown, Script,
+  ScriptInfo, ScriptKind, TemplateTag, Text,
 };
-use umc_parser::{LanguageParser, ParseResult, ParserImpl, token::Token};
+use umc_parser::{LanguageParser, ParseResult, ParserImpl, metrics::ParseMetrics, token::Token};
 use umc_span::Span;
 
+mod adoption;
+mod implied;
+mod recovery;
+mod void;
+
+use implied::InsertionMode;
+
 use crate::{
   Html,
-  lexer::{HtmlLexer, HtmlLexerOption, kind::HtmlKind},
-  option::HtmlParserOption,
+  budget::BudgetExceeded,
+  dialect::Dialect,
+  error::{ERROR_CODE_SCOPE, HtmlParseErrorKind},
+  lexer::{
+    HtmlLexer, HtmlLexerOption,
+    kind::HtmlKind,
+    scan::{comment_value, find_template_end},
+  },
+  module,
+  option::{HtmlParserOption, SelfClosing, Whitespace},
+  parallel::{self, PendingScript},
+  style,
+  trace::trace,
 };
 
 /// HTML parser implementation.
@@ -29,45 +55,120 @@ pub struct HtmlParserImpl<'a> {
   /// providing O(1) allocation and bulk deallocation.
   allocator: &'a Allocator,
   source_text: &'a str,
-  options: &'a HtmlParserOption,
+  options: Cow<'a, HtmlParserOption>,
   errors: Vec<OxcDiagnostic>,
+  /// Whether the [`max_depth`](HtmlParserOption::max_depth) error was reported
+  depth_exceeded: bool,
+  /// Number of nodes created, for the [budget](crate::budget::ParseBudget)
+  node_count: usize,
+  /// Whether the budget was exceeded, and parsing must stop
+  aborted: bool,
+  /// Number of tokens read, for the [metrics](HtmlParserOption::collect_metrics)
+  token_count: usize,
+  /// Time spent parsing scripts, for the [metrics](HtmlParserOption::collect_metrics)
+  embedded_duration: Duration,
+  /// Scripts to parse at the end, with [`parallel_scripts`](HtmlParserOption::parallel_scripts)
+  pending_scripts: Vec<PendingScript<'a>>,
+  /// Whether a module script has imports to resolve at the end
+  has_imports: bool,
+  /// The dialect of the document, see [`html_type`](HtmlParserOption::html_type), `None`
+  /// until the first DOCTYPE with [`HtmlType::Auto`](crate::option::HtmlType::Auto)
+  dialect: Option<Dialect>,
+  /// Where the document starts in the source text, see [`starting_at`](Self::starting_at)
+  start: u32,
+  /// Formatting elements closed before their closing tag, to reopen before the next text
+  /// or element, with [`adoption_agency`](HtmlParserOption::adoption_agency)
+  pending_formatting: Vec<ElementBuilder<'a>>,
+  /// Where the parser is in the structure of the document, `None` unless
+  /// [`implied_tags`](HtmlParserOption::implied_tags) is set
+  insertion_mode: Option<InsertionMode>,
 }
 
 impl<'a> ParserImpl<'a, Html> for HtmlParserImpl<'a> {
   fn new(
     allocator: &'a Allocator,
     source_text: &'a str,
-    options: &'a <Html as LanguageParser>::Option,
+    options: Cow<'a, <Html as LanguageParser>::Option>,
   ) -> Self {
     HtmlParserImpl {
+      dialect: options.html_type.dialect(),
+      insertion_mode: options.implied_tags.then_some(InsertionMode::BeforeHtml),
       allocator,
       source_text,
       options,
       errors: Vec::new(),
+      depth_exceeded: false,
+      node_count: 0,
+      aborted: false,
+      token_count: 0,
+      embedded_duration: Duration::ZERO,
+      pending_scripts: Vec::new(),
+      has_imports: false,
+      start: 0,
+      pending_formatting: Vec::new(),
     }
   }
 
   fn parse(mut self) -> ParseResult<Program<'a>> {
-    let mut lexer = HtmlLexer::new(
-      self.source_text,
-      HtmlLexerOption {
-        is_embedded_language_tag: &self.options.is_embedded_language_tag,
-      },
-    );
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!("parse_html", bytes = self.source_text.len()).entered();
 
-    // Transfer lexer errors
-    self.errors.append(&mut lexer.errors);
+    let started = self
+      .options
+      .collect_metrics
+      .then(|| (Instant::now(), self.allocator.used_bytes()));
 
-    let iter = lexer.tokens().peekable();
+    let mut nodes = self.parse_from(self.start);
+    if self.options.handlebars {
+      self.nest_blocks(&mut nodes, &[]);
+    }
+    if !self.pending_scripts.is_empty() {
+      self.parse_pending_scripts(&mut nodes);
+    }
+    if self.has_imports {
+      module::resolve_imports(self.allocator, &mut nodes);
+    }
 
-    // Parse tokens into AST
-    let nodes = self.parse_tokens(iter);
+    let metrics = started.map(|(start, used_bytes)| {
+      let mut node_counts = BTreeMap::new();
+      count_nodes(&nodes, &mut node_counts);
+      ParseMetrics {
+        bytes: self.source_text.len(),
+        duration: start.elapsed(),
+        tokens: self.token_count,
+        nodes: node_counts,
+        arena_bytes: self.allocator.used_bytes().saturating_sub(used_bytes),
+        embedded_duration: self.embedded_duration,
+      }
+    });
+
+    if self.options.strict {
+      // errors are reported once known, keep the first error in source order
+      let offset = |error: &OxcDiagnostic| {
+        error
+          .labels
+          .iter()
+          .flatten()
+          .map(LabeledSpan::offset)
+          .min()
+          .unwrap_or_default()
+      };
+      self.errors.sort_by_key(offset);
+      if let Some(first) = self
+        .errors
+        .iter()
+        .position(|error| error.severity == Severity::Error)
+      {
+        self.errors.truncate(first + 1);
+      }
+    }
 
     let Self { errors, .. } = self;
 
     ParseResult {
       program: nodes,
       errors,
+      metrics,
     }
   }
 }
@@ -79,46 +180,260 @@ struct ElementBuilder<'a> {
   attributes: ArenaVec<'a, Attribute<'a>>,
   children: ArenaVec<'a, Node<'a>>,
   start: u32,
+  /// End of the opening tag
+  tag_end: u32,
+  /// Whether the element is implied, see [`implied_tags`](HtmlParserOption::implied_tags)
+  implied: bool,
+}
+
+/// Whether a name is in a list of names, compared case-insensitively.
+fn is_in(names: &[&str], name: &str) -> bool {
+  names.iter().any(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// Tokens read one at a time, with one token of lookahead.
+pub trait TokenCursor: Iterator<Item = Token<HtmlKind>> {
+  /// Get the next token without reading it.
+  fn peek(&mut self) -> Option<&Token<HtmlKind>>;
+}
+
+impl<I: Iterator<Item = Token<HtmlKind>>> TokenCursor for Peekable<I> {
+  fn peek(&mut self) -> Option<&Token<HtmlKind>> {
+    Self::peek(self)
+  }
+}
+
+/// The tokens of the lexer, lexed as the parser reads them.
+///
+/// Stops at the token limit and the byte budget, as if the document ended there.
+struct TokenStream<'a> {
+  lexer: HtmlLexer<'a>,
+  /// Once the end is reached, reading again gives `None` too
+  peeked: Option<Token<HtmlKind>>,
+  /// Number of tokens read, the end of file aside
+  count: usize,
+  max_tokens: usize,
+  max_bytes: usize,
+  /// Where the token limit was exceeded
+  tokens_exceeded_at: Option<u32>,
+  /// Where the byte budget was exceeded
+  bytes_exceeded_at: Option<u32>,
+}
+
+impl TokenStream<'_> {
+  fn read(&mut self) -> Option<Token<HtmlKind>> {
+    if self.tokens_exceeded_at.is_some() || self.bytes_exceeded_at.is_some() {
+      return None;
+    }
+    let token = self.lexer.next_token()?;
+    if token.end as usize > self.max_bytes {
+      self.bytes_exceeded_at = Some(token.start);
+      return None;
+    }
+    if token.kind != HtmlKind::Eof {
+      self.count += 1;
+    }
+    if self.count > self.max_tokens {
+      self.tokens_exceeded_at = Some(token.start);
+      return None;
+    }
+    Some(token)
+  }
+}
+
+impl Iterator for TokenStream<'_> {
+  type Item = Token<HtmlKind>;
+
+  fn next(&mut self) -> Option<Token<HtmlKind>> {
+    self.peeked.take().or_else(|| self.read())
+  }
+}
+
+impl TokenCursor for TokenStream<'_> {
+  fn peek(&mut self) -> Option<&Token<HtmlKind>> {
+    if self.peeked.is_none() {
+      self.peeked = self.read();
+    }
+    self.peeked.as_ref()
+  }
 }
 
 impl<'a> HtmlParserImpl<'a> {
-  fn parse_tokens(
-    &mut self,
-    mut iter: Peekable<impl Iterator<Item = Token<HtmlKind>>>,
-  ) -> Program<'a> {
+  /// Parse the source text from `start` rather than from its beginning, e.g. after the
+  /// frontmatter of a component. Spans stay offsets in the whole source text.
+  #[must_use]
+  pub const fn starting_at(mut self, start: u32) -> Self {
+    self.start = start;
+    self
+  }
+
+  /// Parse the source text from `start` to its end.
+  fn parse_from(&mut self, start: u32) -> Program<'a> {
+    let lexer = HtmlLexer::new(
+      self.source_text,
+      HtmlLexerOption {
+        raw_text_tags: self.options.dialect_spec.raw_text_tags.clone(),
+        spec_self_closing: self.options.self_closing == SelfClosing::Spec,
+        cdata_sections: self.options.dialect_spec.cdata_sections,
+        mustache: self.options.handlebars,
+        template_delimiters: self.options.template_delimiters.clone(),
+      },
+    )
+    .starting_at(start);
+    let mut tokens = TokenStream {
+      lexer,
+      peeked: None,
+      count: 0,
+      max_tokens: self.options.max_tokens.unwrap_or(usize::MAX),
+      max_bytes: self.options.budget.max_bytes.unwrap_or(usize::MAX),
+      tokens_exceeded_at: None,
+      bytes_exceeded_at: None,
+    };
+
+    let nodes = self.parse_tokens(&mut tokens);
+    self.token_count += tokens.count;
+
+    if let Some(start) = tokens.tokens_exceeded_at {
+      self.report(
+        HtmlParseErrorKind::TokenLimit
+          .error(format!("Token limit of {} exceeded", tokens.max_tokens))
+          .with_label(Span::empty(start))
+          .with_help("The rest of the document is ignored"),
+      );
+    }
+
+    nodes
+  }
+
+  /// Report the errors of the lexer, as they are found.
+  fn report_lexer_errors(&mut self, tokens: &mut TokenStream) {
+    for error in tokens.lexer.errors.drain(..) {
+      self.report(error);
+    }
+  }
+
+  /// Parse a comment token into a comment, or a raw unknown node for a bogus comment,
+  /// `None` if the options drop it.
+  fn parse_comment_node(&mut self, token: &Token<HtmlKind>) -> Option<Node<'a>> {
+    let (_, bogus) = comment_value(self.get_token_text(token));
+    if bogus && self.options.preserve_unknown {
+      Some(Node::RawUnknown(Box::new_in(
+        self.parse_raw_unknown(token),
+        self.allocator,
+      )))
+    } else if self.options.preserve_comments {
+      Some(Node::Comment(Box::new_in(
+        self.parse_comment(token),
+        self.allocator,
+      )))
+    } else {
+      None
+    }
+  }
+
+  #[allow(clippy::too_many_lines)]
+  fn parse_tokens(&mut self, iter: &mut TokenStream<'a>) -> Program<'a> {
     // Create arena-allocated vector for root nodes
     // Uses bump allocation: O(1) push operations, cache-friendly traversal
     let mut nodes: ArenaVec<'a, Node<'a>> = ArenaVec::new_in(self.allocator);
     let mut element_stack: Vec<ElementBuilder<'a>> = Vec::new();
+    self.pending_formatting.clear();
 
     while let Some(token) = iter.next() {
+      self.report_lexer_errors(iter);
+      if let Some(max_errors) = self.options.budget.max_errors
+        && self.errors.len() > max_errors
+      {
+        self.exceed_budget(BudgetExceeded::Errors, max_errors, token.start);
+      }
+      if matches!(
+        token.kind,
+        HtmlKind::Doctype
+          | HtmlKind::TagStart
+          | HtmlKind::TextContent
+          | HtmlKind::Comment
+          | HtmlKind::Mustache
+          | HtmlKind::TemplateTag
+      ) {
+        self.charge_node(token.start);
+      }
+      if self.aborted {
+        break;
+      }
+
       match token.kind {
         HtmlKind::Eof => break,
 
         HtmlKind::Doctype => {
-          let doctype = self.parse_doctype(&token, &mut iter);
+          let doctype = self.parse_doctype(&token, iter);
+          self.check_prelude(&doctype, &nodes, &element_stack);
+          if self.dialect.is_none() {
+            let dialect = Dialect::from_doctype(Some(&doctype));
+            trace!(dialect = ?dialect, "dialect detected");
+            self.dialect = Some(dialect);
+          }
           let doctype = Box::new_in(doctype, self.allocator);
           Self::push_node(&mut nodes, &mut element_stack, Node::Doctype(doctype));
         }
 
         HtmlKind::TagStart => {
-          self.parse_opening_tag(&token, &mut iter, &mut nodes, &mut element_stack);
+          let name = iter
+            .peek()
+            .filter(|name| name.kind == HtmlKind::ElementName);
+          let tag_name = name.map(|name| self.get_token_text(name));
+          let name_end = name.map_or(token.end, |name| name.end);
+          self.imply_tags(
+            Some(tag_name.unwrap_or_default()),
+            token.start,
+            &mut nodes,
+            &mut element_stack,
+          );
+          if self.options.adoption_agency {
+            self.adopt_opening(
+              tag_name.unwrap_or_default(),
+              Span::new(token.start, name_end),
+              &mut nodes,
+              &mut element_stack,
+            );
+            self.reopen_formatting(tag_name, token.start, &mut element_stack);
+          }
+          self.parse_opening_tag(&token, iter, &mut nodes, &mut element_stack);
         }
 
         HtmlKind::CloseTagStart => {
-          self.parse_closing_tag(&token, &mut iter, &mut nodes, &mut element_stack);
+          self.parse_closing_tag(&token, iter, &mut nodes, &mut element_stack);
+          self.close_head(&element_stack);
+          self.close_body(&element_stack);
         }
 
         HtmlKind::TextContent => {
           let text = self.parse_text(&token);
-          let text = Box::new_in(text, self.allocator);
-          Self::push_node(&mut nodes, &mut element_stack, Node::Text(text));
+          if let Some(text) = self.handle_whitespace(text, &element_stack) {
+            if !text.value.trim_ascii().is_empty() {
+              self.imply_tags(None, text.span.start, &mut nodes, &mut element_stack);
+            }
+            if self.options.adoption_agency {
+              self.reopen_formatting(None, text.span.start, &mut element_stack);
+            }
+            let text = Box::new_in(text, self.allocator);
+            Self::push_node(&mut nodes, &mut element_stack, Node::Text(text));
+          }
         }
 
         HtmlKind::Comment => {
-          let comment = self.parse_comment(&token);
-          let comment = Box::new_in(comment, self.allocator);
-          Self::push_node(&mut nodes, &mut element_stack, Node::Comment(comment));
+          if self.get_token_text(&token).starts_with("<?") && in_prelude(&nodes, &element_stack) {
+            let directive = Box::new_in(self.parse_directive(&token), self.allocator);
+            nodes.push(Node::Directive(directive));
+          } else if let Some(node) = self.parse_comment_node(&token) {
+            Self::push_node(&mut nodes, &mut element_stack, node);
+          }
+        }
+
+        HtmlKind::Mustache | HtmlKind::TemplateTag => {
+          // template tags render content, they go in `<body>` like text
+          self.imply_tags(None, token.start, &mut nodes, &mut element_stack);
+          let node = self.parse_template_node(&token);
+          Self::push_node(&mut nodes, &mut element_stack, node);
         }
 
         // Other token kinds are handled by the specific parsing functions above
@@ -128,21 +443,52 @@ impl<'a> HtmlParserImpl<'a> {
       }
     }
 
+    self.report_lexer_errors(iter);
+    if let Some(offset) = iter.bytes_exceeded_at
+      && let Some(max_bytes) = self.options.budget.max_bytes
+    {
+      self.exceed_budget(BudgetExceeded::Bytes, max_bytes, offset);
+    }
+
     // Close any unclosed elements
+    let recovery_depth = self.recovery_depth(&element_stack);
     while let Some(builder) = element_stack.pop() {
       let end = builder
         .children
         .last()
         .map_or(builder.start, |n| Self::node_end(n));
 
-      self.errors.push(
-        OxcDiagnostic::error(format!("Unclosed element: <{}>", builder.tag_name))
-          .with_label(Span::new(builder.start, end)),
+      trace!(
+        tag = builder.tag_name,
+        "close element at the end of the document"
       );
 
+      // elements are expected to be unclosed after an abort, and implied ones have no tags
+      if !self.aborted && !builder.implied {
+        let tag_name = builder.tag_name;
+        let eof = Span::empty(self.source_text.len() as u32);
+        self.report(
+          HtmlParseErrorKind::UnclosedElement
+            .error(format!("Unclosed element: <{tag_name}>"))
+            .with_labels([
+              Span::new(builder.start, builder.tag_end).primary_label("opened here"),
+              eof.label(format!(
+                "expected </{tag_name}> before the end of the document"
+              )),
+            ])
+            .with_help(format!("Add a closing tag </{tag_name}>")),
+        );
+      }
+
+      if recovery_depth.is_some_and(|depth| element_stack.len() >= depth) {
+        self.recover(builder, &mut nodes, &mut element_stack);
+        continue;
+      }
+
       let element = Element {
         span: Span::new(builder.start, end),
         tag_name: builder.tag_name,
+        normalized_name: self.normalize_name(builder.tag_name),
         attributes: builder.attributes,
         children: builder.children,
       };
@@ -154,18 +500,19 @@ impl<'a> HtmlParserImpl<'a> {
     nodes
   }
 
-  /// Parse DOCTYPE declaration with its attributes.
+  /// Parse DOCTYPE declaration with its parts, each also kept as a valueless attribute.
   fn parse_doctype(
     &self,
     doctype_token: &Token<HtmlKind>,
-    iter: &mut Peekable<impl Iterator<Item = Token<HtmlKind>>>,
+    iter: &mut impl TokenCursor,
   ) -> Doctype<'a> {
     let start = doctype_token.start;
     let mut end = doctype_token.end;
     // Create arena-allocated vector for DOCTYPE attributes
     let mut attributes: ArenaVec<'a, Attribute<'a>> = ArenaVec::new_in(self.allocator);
+    let (mut name, mut public_id, mut system_id) = (None, None, None);
 
-    // Parse DOCTYPE attributes until TagEnd
+    // Parse DOCTYPE parts until TagEnd
     while let Some(token) = iter.peek() {
       match token.kind {
         HtmlKind::TagEnd => {
@@ -173,19 +520,30 @@ impl<'a> HtmlParserImpl<'a> {
           iter.next();
           break;
         }
-        HtmlKind::Attribute => {
-          let attr_token = iter.next().unwrap();
-          let attr_text = self.get_token_text(&attr_token);
-          // Eq is not expected, all attributes are without value
+        HtmlKind::DoctypeName
+        | HtmlKind::DoctypeKeyword
+        | HtmlKind::PublicId
+        | HtmlKind::SystemId
+        | HtmlKind::BogusDoctype => {
+          let part_token = iter.next().unwrap();
+          let text = self.get_token_text(&part_token);
+          match part_token.kind {
+            HtmlKind::DoctypeName => name = Some(text),
+            HtmlKind::PublicId => public_id = Some(doctype_id(text)),
+            HtmlKind::SystemId => system_id = Some(doctype_id(text)),
+            _ => {}
+          }
           attributes.push(Attribute {
             key: AttributeKey {
-              span: attr_token.span(),
-              value: attr_text,
+              span: part_token.span(),
+              value: text,
+              normalized: text,
             },
             value: None,
-            span: attr_token.span(),
+            span: part_token.span(),
+            style: None,
           });
-          end = attr_token.end;
+          end = part_token.end;
         }
         HtmlKind::Eof => break,
         _ => {
@@ -197,6 +555,9 @@ impl<'a> HtmlParserImpl<'a> {
     Doctype {
       span: Span::new(start, end),
       attributes,
+      name,
+      public_id,
+      system_id,
     }
   }
 
@@ -205,7 +566,7 @@ impl<'a> HtmlParserImpl<'a> {
   fn parse_opening_tag(
     &mut self,
     tag_start_token: &Token<HtmlKind>,
-    iter: &mut Peekable<impl Iterator<Item = Token<HtmlKind>>>,
+    iter: &mut impl TokenCursor,
     nodes: &mut ArenaVec<'a, Node<'a>>,
     element_stack: &mut Vec<ElementBuilder<'a>>,
   ) {
@@ -213,7 +574,6 @@ impl<'a> HtmlParserImpl<'a> {
     let mut tag_name: &'a str = "";
     // Create arena-allocated vector for element attributes
     let mut attributes: ArenaVec<'a, Attribute<'a>> = ArenaVec::new_in(self.allocator);
-    let mut is_self_closing = false;
 
     // Parse element name
     if let Some(token) = iter.peek()
@@ -225,100 +585,101 @@ impl<'a> HtmlParserImpl<'a> {
     }
 
     // Parse attributes until TagEnd or SelfCloseTagEnd
-    let mut current_attr_key: Option<AttributeKey<'a>> = None;
-
-    while let Some(token) = iter.peek() {
-      match token.kind {
-        HtmlKind::TagEnd => {
-          iter.next();
-          break;
-        }
-        HtmlKind::SelfCloseTagEnd => {
-          is_self_closing = true;
-          iter.next();
-          break;
-        }
-        HtmlKind::Attribute => {
-          let attr_token = iter.next().unwrap();
-          let attr_text = self.get_token_text(&attr_token);
-
-          // If we have a pending attribute key without value, stop storing it because a new attribute is coming
-          if let Some(key) = current_attr_key.take() {
-            let span = key.span;
-            attributes.push(Attribute {
-              span,
-              key,
-              value: None,
-            });
+    let max_attributes = self.options.max_attributes.unwrap_or(usize::MAX);
+    let mut dropped: Option<Span> = None;
+    let mut attribute_errors = Vec::new();
+    let close_token = parse_attributes(
+      self.source_text,
+      iter,
+      &mut attribute_errors,
+      |mut attribute| {
+        if attributes.len() < max_attributes {
+          attribute.key.normalized = self.normalize_name(attribute.key.value);
+          if self.options.parse_style_attributes
+            && attribute.key.normalized.eq_ignore_ascii_case("style")
+            && let Some(value) = &attribute.value
+          {
+            attribute.style = Some(style::parse_declarations(
+              self.allocator,
+              value.value,
+              value.value_span.start,
+            ));
           }
-
-          current_attr_key = Some(AttributeKey {
-            span: attr_token.span(),
-            value: attr_text,
-          });
+          attributes.push(attribute);
+        } else {
+          dropped.get_or_insert(attribute.span);
         }
-        HtmlKind::Eq => {
-          let eq_token = iter.next().unwrap();
+      },
+    );
 
-          // skip possible whitespace
-          if let Some(token) = iter.peek()
-            && token.kind == HtmlKind::Whitespace
-          {
-            iter.next();
-          }
+    for error in attribute_errors {
+      self.report(error);
+    }
 
-          // Next token should be attribute value
-          if let Some(value_token) = iter.peek()
-            && value_token.kind == HtmlKind::Attribute
-          {
-            let value_token = iter.next().unwrap();
-            if let Some(key) = current_attr_key.take() {
-              let value = self.unquote_attribute(&value_token);
-              let span = Span::new(key.span.start, value.span.end);
-              attributes.push(Attribute {
-                span,
-                key,
-                value: Some(value),
-              });
-            }
-          } else {
-            // Handle missing value after =
-            self.errors.push(
-              OxcDiagnostic::error("Expected attribute value after '='")
-                .with_label(Span::new(eq_token.start, eq_token.end)),
-            );
-          }
-        }
-        HtmlKind::Eof => break,
-        _ => {
-          iter.next();
-        }
-      }
+    // an opening tag without `>` ends with the document
+    let tag_end = close_token
+      .as_ref()
+      .map_or(self.source_text.len() as u32, |token| token.end);
+
+    if let Some(span) = dropped {
+      self.report(
+        HtmlParseErrorKind::AttributeLimit
+          .error(format!(
+            "Attribute limit of {max_attributes} exceeded: <{tag_name}>"
+          ))
+          .with_label(span)
+          .with_help("Further attributes are dropped"),
+      );
     }
 
-    // Add any remaining attribute without value
-    if let Some(key) = current_attr_key.take() {
-      let span = key.span;
-      attributes.push(Attribute {
-        span,
-        key,
-        value: None,
-      });
+    if self.merge_document_element(
+      tag_name,
+      &mut attributes,
+      Span::new(start, tag_end),
+      element_stack,
+    ) {
+      return;
+    }
+
+    let too_deep = self
+      .options
+      .max_depth
+      .is_some_and(|max_depth| element_stack.len() >= max_depth);
+    if too_deep && !self.depth_exceeded {
+      self.depth_exceeded = true;
+      self.report(
+        HtmlParseErrorKind::DepthLimit
+          .error(format!(
+            "Nesting depth limit of {} exceeded: <{tag_name}>",
+            element_stack.len()
+          ))
+          .with_label(Span::new(start, tag_end))
+          .with_help("Deeper elements are parsed without children"),
+      );
     }
 
-    // Check for void elements (self-closing by nature)
-    if is_self_closing || (self.options.is_void_tag)(tag_name) {
-      // Self-closing elements don't go on the stack
-      let end = iter
-        .peek()
-        .map_or(self.source_text.len() as u32, |t| t.start);
+    let is_void = self.options.dialect_spec.void_tags.contains(tag_name);
+    let is_self_closing = close_token.is_some_and(|token| token.kind == HtmlKind::SelfCloseTagEnd)
+      && (is_void || self.closes_self(tag_name, tag_end, element_stack));
+
+    // Check for void elements (self-closing by nature), and elements over the depth limit
+    if is_self_closing || too_deep || is_void {
+      trace!(
+        tag = tag_name,
+        self_closing = is_self_closing,
+        too_deep,
+        "element without children"
+      );
+
+      // Self-closing elements don't go on the stack, and end at their `>` or `/>`
 
       // Create arena-allocated empty vector for children
       let children: ArenaVec<'a, Node<'a>> = ArenaVec::new_in(self.allocator);
 
       let element = Element {
-        span: Span::new(start, end),
+        span: Span::new(start, tag_end),
         tag_name,
+        normalized_name: self.normalize_name(tag_name),
         attributes,
         children,
       };
@@ -326,15 +687,26 @@ impl<'a> HtmlParserImpl<'a> {
       // Push to parent or root
       self.create_and_push_element(element, nodes, element_stack);
     } else {
+      if let Some(max_depth) = self.options.budget.max_depth
+        && element_stack.len() >= max_depth
+      {
+        self.exceed_budget(BudgetExceeded::Depth, max_depth, start);
+        return;
+      }
+
       // Create arena-allocated vector for children
       let children: ArenaVec<'a, Node<'a>> = ArenaVec::new_in(self.allocator);
 
+      trace!(tag = tag_name, depth = element_stack.len(), "open element");
+
       // Push to element stack for later matching with closing tag
       element_stack.push(ElementBuilder {
         tag_name,
         attributes,
         children,
         start,
+        tag_end,
+        implied: false,
       });
     }
   }
@@ -344,7 +716,7 @@ impl<'a> HtmlParserImpl<'a> {
   fn parse_closing_tag(
     &mut self,
     close_tag_token: &Token<HtmlKind>,
-    iter: &mut Peekable<impl Iterator<Item = Token<HtmlKind>>>,
+    iter: &mut impl TokenCursor,
     nodes: &mut ArenaVec<'a, Node<'a>>,
     element_stack: &mut Vec<ElementBuilder<'a>>,
   ) {
@@ -359,34 +731,64 @@ impl<'a> HtmlParserImpl<'a> {
       close_tag_token.end
     };
 
-    // Skip until TagEnd
-    while let Some(token) = iter.peek() {
-      match token.kind {
-        HtmlKind::TagEnd => {
-          end = token.end;
-          iter.next();
-          break;
-        }
-        HtmlKind::Eof => break,
-        _ => {
-          iter.next();
-        }
-      }
+    // Skip until TagEnd, the attributes of a closing tag are ignored
+    let mut attributes = Vec::new();
+    let mut attribute_errors = Vec::new();
+    let close_token = parse_attributes(self.source_text, iter, &mut attribute_errors, |a| {
+      attributes.push(a.span);
+    });
+    if let Some(token) = close_token {
+      end = token.end;
+    }
+    for error in attribute_errors {
+      self.report(error);
+    }
+    if let (Some(first), Some(last)) = (attributes.first(), attributes.last()) {
+      let raw = &self.source_text[first.start as usize..last.end as usize];
+      trace!(tag = tag_name, "ignore attributes in closing tag");
+      self.report(
+        HtmlParseErrorKind::EndTagWithAttributes
+          .error(format!("Attributes in closing tag </{tag_name}>: {raw}"))
+          .with_labels(attributes.iter().enumerate().map(|(i, span)| {
+            if i == 0 {
+              span.primary_label("ignored")
+            } else {
+              span.label("ignored")
+            }
+          }))
+          .with_help("Remove the attributes, closing tags have none"),
+      );
+    }
+
+    let close_tag_span = Span::new(close_tag_token.start, end);
+
+    if self.options.adoption_agency && self.adopt(tag_name, close_tag_span, nodes, element_stack) {
+      return;
     }
 
     // Find matching opening tag in stack
     let mut found_index = None;
     for (i, builder) in element_stack.iter().enumerate().rev() {
-      if builder.tag_name.eq_ignore_ascii_case(tag_name) {
+      if self.names_match(builder.tag_name, tag_name) {
         found_index = Some(i);
         break;
       }
     }
 
     if let Some(index) = found_index {
+      trace!(
+        tag = tag_name,
+        implicitly_closed = element_stack.len() - index - 1,
+        "close element"
+      );
+
       // Close all elements from top of stack down to the matching one
+      let pending = self.pending_formatting.len();
       while element_stack.len() > index {
         let builder = element_stack.pop().unwrap();
+        if self.options.adoption_agency {
+          self.close_formatting(&builder, element_stack.len() > index, pending);
+        }
         let elem_end = if element_stack.len() == index {
           end
         } else {
@@ -398,61 +800,43 @@ impl<'a> HtmlParserImpl<'a> {
 
         let span = Span::new(builder.start, elem_end);
 
-        // Check if this is a script element that should be parsed
-        let is_script = builder.tag_name.eq_ignore_ascii_case("script");
-        let mut should_parse = is_script && self.options.parse_script.is_some();
-
-        if should_parse {
-          for attr in &builder.attributes {
-            let key = attr.key.value;
-            if key.eq_ignore_ascii_case("src") {
-              should_parse = false;
-              break;
-            }
-            #[allow(clippy::collapsible_if)]
-            if key.eq_ignore_ascii_case("type") {
-              if let Some(val) = &attr.value {
-                let v = val.value.to_ascii_lowercase();
-                match v.as_str() {
-                  ""
-                  | "text/javascript"
-                  | "application/javascript"
-                  | "module"
-                  | "text/ecmascript"
-                  | "application/ecmascript" => {}
-                  _ => {
-                    should_parse = false;
-                    break;
-                  }
-                }
-              }
-            }
+        // Check if this is a script element that should be parsed, a JavaScript one
+        let mut script = (self.options.parse_script.is_some()
+          && builder.tag_name.eq_ignore_ascii_case("script"))
+        .then(|| ScriptInfo::from_attributes(&builder.attributes))
+        .and_then(|info| Some((info, self.script_source_type(info, &builder.attributes)?)));
+
+        // external scripts are only parsed once loaded
+        let mut resolved = None;
+        if script.is_some_and(|(info, _)| info.external) {
+          resolved = self.resolve_script(&builder.attributes);
+          if resolved.is_none() {
+            script = None;
           }
         }
 
-        if element_stack.len() > index {
+        if element_stack.len() > index && !builder.implied {
           // This is an implicitly closed element
-          self.errors.push(
-            OxcDiagnostic::error(format!("Implicitly closed element: <{}>", builder.tag_name))
-              .with_label(span),
+          let opened = builder.tag_name;
+          self.report(
+            HtmlParseErrorKind::ImplicitlyClosedElement
+              .error(format!("Implicitly closed element: <{opened}>"))
+              .with_labels([
+                Span::new(builder.start, builder.tag_end).primary_label("opened here"),
+                close_tag_span.label(format!("expected </{opened}> before this </{tag_name}>")),
+              ]),
           );
         }
 
-        if should_parse {
+        if let Some(script) = script {
           // Create a Script node with parsed JavaScript
-          self.create_and_push_script(
-            span,
-            builder.tag_name,
-            builder.attributes,
-            &builder.children,
-            nodes,
-            element_stack,
-          );
+          self.create_and_push_script(span, builder, script, resolved, nodes, element_stack);
         } else {
           // Create a regular Element node
           let element = Element {
             span,
             tag_name: builder.tag_name,
+            normalized_name: self.normalize_name(builder.tag_name),
             attributes: builder.attributes,
             children: builder.children,
           };
@@ -462,11 +846,40 @@ impl<'a> HtmlParserImpl<'a> {
         }
       }
     } else {
-      // No matching opening tag - this is an orphan closing tag
-      self.errors.push(
-        OxcDiagnostic::error(format!("Unexpected closing tag: </{tag_name}>"))
-          .with_label(Span::new(close_tag_token.start, end)),
-      );
+      let span = close_tag_span;
+      if self.options.dialect_spec.void_tags.contains(tag_name) {
+        self.report_void_end_tag(tag_name, span, nodes, element_stack);
+      } else {
+        // No matching opening tag - this is an orphan closing tag
+        trace!(tag = tag_name, "orphan closing tag");
+        let mut error = HtmlParseErrorKind::OrphanEndTag
+          .error(format!("Unexpected closing tag: </{tag_name}>"))
+          .with_label(span.primary_label("no matching opening tag"));
+        if let Some(open) = element_stack.last() {
+          error = error
+            .and_label(
+              Span::new(open.start, open.tag_end)
+                .label(format!("<{}> is still open", open.tag_name)),
+            )
+            .with_help(format!(
+              "Expected </{}>, or remove this closing tag",
+              open.tag_name
+            ));
+        }
+        self.report(error);
+      }
+
+      if self.options.preserve_unknown && self.charge_node(span.start) {
+        let raw = RawUnknown {
+          span,
+          raw: &self.source_text[span.start as usize..span.end as usize],
+        };
+        Self::push_node(
+          nodes,
+          element_stack,
+          Node::RawUnknown(Box::new_in(raw, self.allocator)),
+        );
+      }
     }
   }
 
@@ -478,124 +891,763 @@ impl<'a> HtmlParserImpl<'a> {
     }
   }
 
-  /// Parse comment.
-  fn parse_comment(&self, token: &Token<HtmlKind>) -> Comment<'a> {
-    let text = self.get_token_text(token);
+  /// Normalize the name of an element or attribute, see
+  /// [`normalize_case`](crate::dialect::DialectSpec::normalize_case).
+  fn normalize_name(&self, name: &'a str) -> &'a str {
+    if self.options.dialect_spec.normalize_case
+      && !self.case_sensitive()
+      && name.bytes().any(|byte| byte.is_ascii_uppercase())
+    {
+      self.allocator.alloc_str(&name.to_ascii_lowercase())
+    } else {
+      name
+    }
+  }
 
-    // Determine if it's a regular comment or bogus
-    let (value, bogus) = if text.starts_with("<!--") {
-      // Regular comment: <!-- ... -->
-      let content = text
-        .strip_prefix("<!--")
-        .and_then(|s| s.strip_suffix("-->"))
-        .unwrap_or_else(|| text.strip_prefix("<!--").unwrap());
-      (content, false)
-    } else if text.starts_with("<!") {
-      // Bogus comment: <! ... >
-      let content = text
-        .strip_prefix("<!")
-        .and_then(|s| s.strip_suffix(">"))
-        .unwrap_or_else(|| text.strip_prefix("<!").unwrap());
-      (content, true)
+  /// Whether names are case-sensitive, in XHTML or by the
+  /// [`DialectSpec`](crate::dialect::DialectSpec).
+  fn case_sensitive(&self) -> bool {
+    self.options.dialect_spec.case_sensitive || self.dialect == Some(Dialect::Xhtml)
+  }
+
+  /// Whether a closing tag name matches an opening one, case-sensitively in XHTML.
+  fn names_match(&self, opening: &str, closing: &str) -> bool {
+    if self.case_sensitive() {
+      opening == closing
     } else {
-      (text, false)
-    };
+      opening.eq_ignore_ascii_case(closing)
+    }
+  }
 
-    Comment {
-      span: token.span(),
-      bogus,
-      value,
+  /// Apply the [`Whitespace`] option to a text node, `None` if it is dropped.
+  fn handle_whitespace(
+    &self,
+    text: Text<'a>,
+    element_stack: &[ElementBuilder<'a>],
+  ) -> Option<Text<'a>> {
+    let whitespace = self.options.whitespace;
+    if whitespace == Whitespace::Preserve
+      || element_stack.iter().any(|builder| {
+        self
+          .options
+          .dialect_spec
+          .preformatted_tags
+          .contains(builder.tag_name)
+      })
+    {
+      return Some(text);
     }
+
+    let is_whitespace = |c: char| c.is_ascii_whitespace();
+    let trimmed = text.value.trim_matches(is_whitespace);
+    if trimmed.is_empty() {
+      return None;
+    }
+    if whitespace == Whitespace::Skip {
+      return Some(text);
+    }
+
+    let start = text.span.start
+      + (text.value.len() - text.value.trim_start_matches(is_whitespace).len()) as u32;
+    Some(Text {
+      span: Span::new(start, start + trimmed.len() as u32),
+      value: trimmed,
+    })
   }
-}
 
-// Some common function and utils
-impl<'a> HtmlParserImpl<'a> {
-  /// Push a node to the appropriate location (parent element or root).
-  fn push_node(
-    nodes: &mut ArenaVec<'a, Node<'a>>,
-    element_stack: &mut [ElementBuilder<'a>],
-    node: Node<'a>,
-  ) {
-    if let Some(parent) = element_stack.last_mut() {
-      parent.children.push(node);
-    } else {
-      nodes.push(node);
+  /// Parse a processing instruction of the prelude, `<?target value?>`.
+  fn parse_directive(&self, token: &Token<HtmlKind>) -> Directive<'a> {
+    let raw = self.get_token_text(token);
+    let content = &raw[2..];
+    let content = content.strip_suffix('>').unwrap_or(content);
+    let content = content.strip_suffix('?').unwrap_or(content);
+    let name_end = content
+      .find(|c: char| c.is_ascii_whitespace())
+      .unwrap_or(content.len());
+    Directive {
+      span: token.span(),
+      name: &content[..name_end],
+      value: &content[name_end..],
+      raw,
     }
   }
 
-  /// Get the text content of a token.
-  fn get_token_text(&self, token: &Token<HtmlKind>) -> &'a str {
-    // SAFETY: The source_text has lifetime 'a, and we return a slice of it.
-    // This slice is valid as long as the allocator and source text are alive.
-    &self.source_text[token.start as usize..token.end as usize]
+  /// Report a doctype after the content of the document, see
+  /// [`ContentBeforeDoctype`](HtmlParseErrorKind::ContentBeforeDoctype).
+  fn check_prelude(
+    &mut self,
+    doctype: &Doctype<'a>,
+    nodes: &[Node<'a>],
+    element_stack: &[ElementBuilder<'a>],
+  ) {
+    let content = nodes
+      .iter()
+      .find(|node| !is_prelude(node))
+      .map(|node| Span::new(Self::node_start(node), Self::node_end(node)))
+      .or_else(|| {
+        element_stack
+          .first()
+          .map(|builder| Span::new(builder.start, builder.tag_end))
+      });
+    let Some(content) = content else {
+      return;
+    };
+    trace!(start = content.start, "content before doctype");
+    self.report(
+      HtmlParseErrorKind::ContentBeforeDoctype
+        .error("Content before the DOCTYPE")
+        .with_labels([
+          content.primary_label("content before the DOCTYPE"),
+          doctype.span.label("DOCTYPE"),
+        ])
+        .with_help("Move the DOCTYPE to the start of the document"),
+    );
   }
 
-  /// Remove quotes from attribute value.
-  fn unquote_attribute(&self, value: &Token<HtmlKind>) -> AttributeValue<'a> {
-    let span = value.span();
-    let value = self.get_token_text(value);
+  /// Parse a construct kept verbatim.
+  fn parse_raw_unknown(&self, token: &Token<HtmlKind>) -> RawUnknown<'a> {
+    RawUnknown {
+      span: token.span(),
+      raw: self.get_token_text(token),
+    }
+  }
 
-    if (value.starts_with('"') && value.ends_with('"'))
-      || (value.starts_with('\'') && value.ends_with('\''))
-    {
-      AttributeValue {
-        value: &value[1..value.len() - 1],
-        raw: value,
-        span,
-      }
+  /// Parse a Handlebars or template tag token into its node.
+  fn parse_template_node(&self, token: &Token<HtmlKind>) -> Node<'a> {
+    if token.kind == HtmlKind::Mustache {
+      Node::Mustache(Box::new_in(self.parse_mustache(token), self.allocator))
     } else {
-      AttributeValue {
-        value,
-        raw: value,
-        span,
-      }
+      Node::TemplateTag(Box::new_in(self.parse_template_tag(token), self.allocator))
     }
   }
 
-  /// Get the end position of a node.
-  fn node_end(node: &Node) -> u32 {
-    match node {
-      Node::Doctype(d) => d.span.end,
-      Node::Element(e) => e.span.end,
-      Node::Text(t) => t.span.end,
-      Node::Comment(c) => c.span.end,
-      Node::Script(s) => s.span.end,
+  /// Parse a template tag, split by the delimiters the lexer matched it with.
+  fn parse_template_tag(&self, token: &Token<HtmlKind>) -> TemplateTag<'a> {
+    let text = self.get_token_text(token);
+    let delimiters = self
+      .options
+      .template_delimiters
+      .iter()
+      .find(|delimiters| !delimiters.open.is_empty() && text.starts_with(&*delimiters.open))
+      .unwrap();
+    let (open, rest) = text.split_at(delimiters.open.len());
+    // an unterminated tag runs to the end of the document, without closing delimiter
+    let (value, close) =
+      find_template_end(delimiters, rest.as_bytes()).map_or((rest, ""), |end| rest.split_at(end));
+    TemplateTag {
+      span: token.span(),
+      open,
+      close,
+      value,
     }
   }
 
-  fn create_and_push_element(
-    &self,
-    element: Element<'a>,
-    nodes: &mut ArenaVec<'a, Node<'a>>,
-    element_stack: &mut [ElementBuilder<'a>],
-  ) {
-    let element = Box::new_in(element, self.allocator);
+  /// Parse comment.
+  fn parse_comment(&mut self, token: &Token<HtmlKind>) -> Comment<'a> {
+    let text = self.get_token_text(token);
 
-    if let Some(parent) = element_stack.last_mut() {
-      parent.children.push(Node::Element(element));
+    let (value, bogus) = comment_value(text);
+
+    let conditional = if self.options.parse_conditional_comments && !bogus {
+      // the value follows `<!--`
+      self
+        .parse_conditional_comment(token.start + 4, value)
+        .map(|conditional| Box::new_in(conditional, self.allocator))
     } else {
-      nodes.push(Node::Element(element));
+      None
+    };
+
+    Comment {
+      span: token.span(),
+      bogus,
+      value,
+      raw: text,
+      conditional,
+    }
+  }
+
+  /// Parse a comment value like `[if IE]><p>IE</p><![endif]`, starting at `start`.
+  fn parse_conditional_comment(
+    &mut self,
+    start: u32,
+    value: &'a str,
+  ) -> Option<ConditionalComment<'a>> {
+    let prefix = value.get(..4)?;
+    let suffix = value.get(value.len().checked_sub(9)?..)?;
+    if !prefix.eq_ignore_ascii_case("[if ") || !suffix.eq_ignore_ascii_case("<![endif]") {
+      return None;
+    }
+    let condition_end = value.find("]>")?;
+    let content_start = condition_end + 2;
+    let content_end = value.len() - 9;
+    if content_start > content_end {
+      return None;
+    }
+
+    // parse the content alone, ending where it ends, with the spans of the document
+    let mut parser = Self {
+      source_text: &self.source_text[..start as usize + content_end],
+      options: std::mem::take(&mut self.options),
+      errors: Vec::new(),
+      pending_scripts: Vec::new(),
+      pending_formatting: Vec::new(),
+      // the content of a conditional comment is a fragment
+      insertion_mode: None,
+      ..*self
+    };
+    let children = parser.parse_from(start + content_start as u32);
+    self.options = parser.options;
+    self.node_count = parser.node_count;
+    self.depth_exceeded = parser.depth_exceeded;
+    self.aborted = parser.aborted;
+    self.token_count = parser.token_count;
+    self.embedded_duration = parser.embedded_duration;
+    self.errors.extend(parser.errors);
+    self.pending_scripts.extend(parser.pending_scripts);
+    self.has_imports |= parser.has_imports;
+
+    Some(ConditionalComment {
+      span: Span::new(start + content_start as u32, start + content_end as u32),
+      condition: value[4..condition_end].trim(),
+      children,
+    })
+  }
+}
+
+/// A Handlebars block whose closing tag is not reached yet.
+struct OpenBlock<'a> {
+  mustache: Box<'a, Mustache<'a>>,
+  /// The nodes before the block, in its parent
+  outer: ArenaVec<'a, Node<'a>>,
+  /// Whether its `{{else}}` was reached, the nodes read being its inverse
+  in_inverse: bool,
+}
+
+// Handlebars
+impl<'a> HtmlParserImpl<'a> {
+  /// Parse a Handlebars tag, blocks without their content, see [`nest_blocks`](Self::nest_blocks).
+  fn parse_mustache(&self, token: &Token<HtmlKind>) -> Mustache<'a> {
+    let (kind, expression) = mustache_parts(self.get_token_text(token));
+    Mustache {
+      span: token.span(),
+      kind,
+      expression: expression.trim(),
+      children: ArenaVec::new_in(self.allocator),
+      inverse: ArenaVec::new_in(self.allocator),
+    }
+  }
+
+  /// Move the content of Handlebars blocks into them, in `nodes` and their descendants.
+  ///
+  /// Blocks are matched among siblings: a block still open at the end of its parent is
+  /// closed there, and one closed by the closing tag of an enclosing block is closed too.
+  /// The closing tag of a block of `enclosing`, the names of the blocks open around the
+  /// parent, closes the parent instead, like `</ul>` closes `<li>`: it is returned with the
+  /// nodes after it, to be nested in the list of the block.
+  fn nest_blocks(
+    &mut self,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    enclosing: &[&'a str],
+  ) -> VecDeque<Node<'a>> {
+    let mut open: Vec<OpenBlock<'a>> = Vec::new();
+    let mut current = ArenaVec::new_in(self.allocator);
+    let mut queue: VecDeque<Node<'a>> = std::mem::replace(nodes, ArenaVec::new_in(self.allocator))
+      .into_iter()
+      .collect();
+    while let Some(node) = queue.pop_front() {
+      let mustache = match node {
+        Node::Element(mut element) => {
+          let mut names = enclosing.to_vec();
+          names.extend(open.iter().map(|block| block.mustache.name()));
+          let rest = self.nest_blocks(&mut element.children, &names);
+          // the closing tag of a block opened before the element closes it, like `</ul>`
+          // closes `<li>`, and the nodes after it follow the element
+          if let Some(close) = rest.front() {
+            element.span.end = element
+              .children
+              .last()
+              .map_or_else(|| Self::node_start(close), Self::node_end);
+          }
+          current.push(Node::Element(element));
+          for node in rest.into_iter().rev() {
+            queue.push_front(node);
+          }
+          continue;
+        }
+        Node::Comment(mut comment) => {
+          if let Some(conditional) = &mut comment.conditional {
+            self.nest_blocks(&mut conditional.children, &[]);
+          }
+          current.push(Node::Comment(comment));
+          continue;
+        }
+        Node::Mustache(mustache) => mustache,
+        node => {
+          current.push(node);
+          continue;
+        }
+      };
+      match mustache.kind {
+        MustacheKind::Block | MustacheKind::InvertedBlock => open.push(OpenBlock {
+          mustache,
+          outer: std::mem::replace(&mut current, ArenaVec::new_in(self.allocator)),
+          in_inverse: false,
+        }),
+        MustacheKind::Else if open.last().is_some_and(|block| !block.in_inverse) => {
+          let block = open.last_mut().unwrap();
+          block.mustache.children =
+            std::mem::replace(&mut current, ArenaVec::new_in(self.allocator));
+          block.in_inverse = true;
+          // a chained `{{else if ...}}` starts the inverse
+          if !mustache.expression.is_empty() {
+            current.push(Node::Mustache(mustache));
+          }
+        }
+        MustacheKind::CloseBlock => {
+          let name = mustache.name();
+          if let Some(index) = open.iter().rposition(|block| block.mustache.name() == name) {
+            while open.len() > index {
+              let block = open.pop().unwrap();
+              let end = if open.len() == index {
+                mustache.span.end
+              } else {
+                self.report_unclosed_block(&block.mustache, mustache.span);
+                current
+                  .last()
+                  .map_or(block.mustache.span.end, Self::node_end)
+              };
+              current = Self::close_block(block, current, end);
+            }
+          } else if enclosing.contains(&name) {
+            // closes a block opened before the parent element, in an ancestor
+            while let Some(block) = open.pop() {
+              self.report_unclosed_block(&block.mustache, mustache.span);
+              let end = current
+                .last()
+                .map_or(block.mustache.span.end, Self::node_end);
+              current = Self::close_block(block, current, end);
+            }
+            queue.push_front(Node::Mustache(mustache));
+            *nodes = current;
+            return queue;
+          } else {
+            self.report(
+              HtmlParseErrorKind::OrphanBlockEnd
+                .error(format!("Unexpected closing tag: {{{{/{name}}}}}"))
+                .with_label(mustache.span.primary_label("no matching block")),
+            );
+            current.push(Node::Mustache(mustache));
+          }
+        }
+        _ => current.push(Node::Mustache(mustache)),
+      }
+    }
+
+    while let Some(block) = open.pop() {
+      let end = current
+        .last()
+        .map_or(block.mustache.span.end, Self::node_end);
+      self.report_unclosed_block(&block.mustache, Span::empty(end));
+      current = Self::close_block(block, current, end);
+    }
+    *nodes = current;
+    queue
+  }
+
+  /// Give a block its content, ending at `end`, and push it to the nodes before it.
+  fn close_block(
+    block: OpenBlock<'a>,
+    content: ArenaVec<'a, Node<'a>>,
+    end: u32,
+  ) -> ArenaVec<'a, Node<'a>> {
+    let OpenBlock {
+      mut mustache,
+      mut outer,
+      in_inverse,
+    } = block;
+    if in_inverse {
+      mustache.inverse = content;
+    } else {
+      mustache.children = content;
+    }
+    mustache.span.end = end;
+    outer.push(Node::Mustache(mustache));
+    outer
+  }
+
+  /// Report a block not closed before `closed_at`.
+  fn report_unclosed_block(&mut self, block: &Mustache<'a>, closed_at: Span) {
+    let name = block.name();
+    self.report(
+      HtmlParseErrorKind::UnclosedBlock
+        .error(format!("Unclosed block: {{{{#{name}}}}}"))
+        .with_labels([
+          block.span.primary_label("opened here"),
+          closed_at.label(format!("expected {{{{/{name}}}}} before this")),
+        ])
+        .with_help(format!("Add a closing tag {{{{/{name}}}}}")),
+    );
+  }
+}
+
+/// Get the kind of a Handlebars tag, and its content after the sigil.
+fn mustache_parts(text: &str) -> (MustacheKind, &str) {
+  // unterminated tags end at the end of the document
+  fn strip<'t>(text: &'t str, open: &str, close: &str) -> &'t str {
+    let inner = &text[open.len()..];
+    inner.strip_suffix(close).unwrap_or(inner)
+  }
+
+  if text.starts_with("{{!--") {
+    return (MustacheKind::Comment, strip(text, "{{!--", "--}}"));
+  }
+  if text.starts_with("{{{") {
+    return (MustacheKind::Unescaped, strip(text, "{{{", "}}}"));
+  }
+  // `~` strips the whitespace around the tag when rendering
+  let inner = strip(text, "{{", "}}");
+  let inner = inner.strip_prefix('~').unwrap_or(inner);
+  let inner = inner.strip_suffix('~').unwrap_or(inner);
+
+  let kind = match inner.as_bytes().first() {
+    Some(b'#') => MustacheKind::Block,
+    Some(b'^') => MustacheKind::InvertedBlock,
+    Some(b'/') => MustacheKind::CloseBlock,
+    Some(b'>') => MustacheKind::Partial,
+    Some(b'!') => MustacheKind::Comment,
+    Some(b'&') => MustacheKind::Unescaped,
+    _ => {
+      let trimmed = inner.trim_start();
+      return match trimmed.strip_prefix("else") {
+        Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => {
+          (MustacheKind::Else, rest)
+        }
+        _ => (MustacheKind::Expression, inner),
+      };
+    }
+  };
+  (kind, &inner[1..])
+}
+
+// Some common function and utils
+impl<'a> HtmlParserImpl<'a> {
+  /// Report an error, with the severity configured in [`HtmlParserOption::errors`].
+  fn report(&mut self, error: OxcDiagnostic) {
+    if let Some(error) = self.options.errors.apply(error) {
+      if self.options.strict && error.severity == Severity::Error {
+        self.aborted = true;
+      }
+      self.errors.push(error);
+    }
+  }
+
+  /// Count a new node against the budget, returning whether it may be created.
+  fn charge_node(&mut self, offset: u32) -> bool {
+    self.node_count += 1;
+    if let Some(max_nodes) = self.options.budget.max_nodes
+      && self.node_count > max_nodes
+    {
+      self.exceed_budget(BudgetExceeded::Nodes, max_nodes, offset);
+    }
+    !self.aborted
+  }
+
+  /// Report an exceeded budget, and stop parsing.
+  fn exceed_budget(&mut self, exceeded: BudgetExceeded, max: usize, offset: u32) {
+    if !self.aborted {
+      trace!(?exceeded, max, offset, "abort the parse");
+      self.aborted = true;
+      self.errors.push(exceeded.diagnostic(max, offset));
+    }
+  }
+
+  /// Push a node to the appropriate location (parent element or root).
+  fn push_node(
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut [ElementBuilder<'a>],
+    node: Node<'a>,
+  ) {
+    if let Some(parent) = element_stack.last_mut() {
+      parent.children.push(node);
+    } else {
+      nodes.push(node);
+    }
+  }
+
+  /// Get the text content of a token.
+  fn get_token_text(&self, token: &Token<HtmlKind>) -> &'a str {
+    // SAFETY: The source_text has lifetime 'a, and we return a slice of it.
+    // This slice is valid as long as the allocator and source text are alive.
+    debug_assert!(
+      self.source_text.is_char_boundary(token.start as usize)
+        && self.source_text.is_char_boundary(token.end as usize),
+      "{token:?} splits a character"
+    );
+    &self.source_text[token.start as usize..token.end as usize]
+  }
+
+  /// Get the start position of a node.
+  fn node_start(node: &Node) -> u32 {
+    match node {
+      Node::Doctype(d) => d.span.start,
+      Node::Element(e) => e.span.start,
+      Node::Text(t) => t.span.start,
+      Node::Comment(c) => c.span.start,
+      Node::Script(s) => s.span.start,
+      Node::RawUnknown(r) => r.span.start,
+      Node::Mustache(m) => m.span.start,
+      Node::TemplateTag(t) => t.span.start,
+      Node::Directive(d) => d.span.start,
+    }
+  }
+
+  /// Get the end position of a node.
+  fn node_end(node: &Node) -> u32 {
+    match node {
+      Node::Doctype(d) => d.span.end,
+      Node::Element(e) => e.span.end,
+      Node::Text(t) => t.span.end,
+      Node::Comment(c) => c.span.end,
+      Node::Script(s) => s.span.end,
+      Node::RawUnknown(r) => r.span.end,
+      Node::Mustache(m) => m.span.end,
+      Node::TemplateTag(t) => t.span.end,
+      Node::Directive(d) => d.span.end,
+    }
+  }
+
+  /// Close an element, at `end` or after its last child.
+  fn close_builder(
+    &self,
+    builder: ElementBuilder<'a>,
+    end: Option<u32>,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut [ElementBuilder<'a>],
+  ) {
+    let end = end.unwrap_or_else(|| {
+      builder
+        .children
+        .last()
+        .map_or(builder.tag_end, |n| Self::node_end(n))
+    });
+    let element = Element {
+      span: Span::new(builder.start, end),
+      tag_name: builder.tag_name,
+      normalized_name: self.normalize_name(builder.tag_name),
+      attributes: builder.attributes,
+      children: builder.children,
+    };
+    self.create_and_push_element(element, nodes, element_stack);
+  }
+
+  fn create_and_push_element(
+    &self,
+    element: Element<'a>,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut [ElementBuilder<'a>],
+  ) {
+    let element = Box::new_in(element, self.allocator);
+
+    if let Some(parent) = element_stack.last_mut() {
+      parent.children.push(Node::Element(element));
+    } else {
+      nodes.push(Node::Element(element));
     }
   }
 
   /// Create a Script node with parsed JavaScript content.
   ///
-  /// Extracts the text content from children (if any), parses it with oxc_parser,
-  /// and creates a Script node containing the parsed JavaScript AST.
+  /// Extracts the text content from children (if any), or takes the `resolved` content of
+  /// an external script, parses it with oxc_parser, and creates a Script node containing
+  /// the parsed JavaScript AST.
   fn create_and_push_script(
     &mut self,
     span: Span,
-    tag_name: &'a str,
-    attributes: ArenaVec<'a, Attribute<'a>>,
-    children: &ArenaVec<'a, Node<'a>>,
+    builder: ElementBuilder<'a>,
+    (info, source_type): (ScriptInfo<'a>, SourceType),
+    resolved: Option<(&'a str, Span)>,
     nodes: &mut ArenaVec<'a, Node<'a>>,
     element_stack: &mut [ElementBuilder<'a>],
   ) {
+    let ElementBuilder {
+      tag_name,
+      attributes,
+      children,
+      ..
+    } = builder;
+    if let Some((content, src)) = resolved {
+      let program = self.parse_resolved_script(content, src, source_type);
+      let module = self.module_record(info.kind, &program, 0);
+      let script = Script {
+        span,
+        tag_name,
+        attributes,
+        info,
+        source_type,
+        program,
+        lazy: None,
+        synthetic: true,
+        module,
+      };
+      Self::push_node(
+        nodes,
+        element_stack,
+        Node::Script(Box::new_in(script, self.allocator)),
+      );
+      return;
+    }
+
+    let (script_content, start_offset) = self.script_content(&children, span.start);
+
+    let parse_options = *self.options.parse_script.as_ref().unwrap();
+    let mut lazy = None;
+    let program = if self.options.lazy_scripts {
+      lazy = Some(Box::new_in(
+        LazyProgram::new(
+          self.allocator,
+          script_content,
+          source_type,
+          parse_options,
+          start_offset,
+        ),
+        self.allocator,
+      ));
+      let mut program = JsProgram::dummy(self.allocator);
+      program.source_text = script_content;
+      program
+    } else if self.options.parallel_scripts {
+      trace!(bytes = script_content.len(), "defer script");
+      self.pending_scripts.push(PendingScript {
+        start: span.start,
+        content: script_content,
+        source_type,
+        offset: start_offset,
+      });
+      JsProgram::dummy(self.allocator)
+    } else {
+      trace!(bytes = script_content.len(), "parse script");
+      let started = self.options.collect_metrics.then(Instant::now);
+      let ret = JsParser::new(self.allocator, script_content, source_type)
+        .with_options(parse_options)
+        .parse();
+      if let Some(started) = started {
+        self.embedded_duration += started.elapsed();
+      }
+      self.report_script_errors(ret.errors, start_offset);
+      ret.program
+    };
+    let module = if lazy.is_some() || self.options.parallel_scripts {
+      None
+    } else {
+      self.module_record(info.kind, &program, start_offset)
+    };
+
+    let script = Script {
+      span,
+      tag_name,
+      attributes,
+      info,
+      source_type,
+      program,
+      lazy,
+      synthetic: false,
+      module,
+    };
+
+    let script = Box::new_in(script, self.allocator);
+
+    if let Some(parent) = element_stack.last_mut() {
+      parent.children.push(Node::Script(script));
+    } else {
+      nodes.push(Node::Script(script));
+    }
+  }
+
+  /// The source type of a script, `None` for the scripts which are not parsed: the ones
+  /// which are neither JavaScript nor, with
+  /// [`typescript_scripts`](HtmlParserOption::typescript_scripts), TypeScript.
+  fn script_source_type(
+    &self,
+    info: ScriptInfo<'a>,
+    attributes: &[Attribute<'a>],
+  ) -> Option<SourceType> {
+    let typescript = self.options.typescript_scripts
+      && match info.kind {
+        ScriptKind::Classic | ScriptKind::Module => attributes.iter().any(|attribute| {
+          attribute.key.name().eq_ignore_ascii_case("lang")
+            && attribute
+              .value
+              .as_ref()
+              .is_some_and(|value| value.value.trim().eq_ignore_ascii_case("ts"))
+        }),
+        ScriptKind::DataBlock(mime) => mime.trim().eq_ignore_ascii_case("text/typescript"),
+        ScriptKind::ImportMap | ScriptKind::SpeculationRules => false,
+      };
+    let source_type = info.kind.source_type();
+    if typescript {
+      Some(source_type.with_typescript(true))
+    } else {
+      info.kind.is_javascript().then_some(source_type)
+    }
+  }
+
+  /// Get the content of an external script with
+  /// [`resolve_script`](HtmlParserOption::resolve_script), and the span of its `src`.
+  fn resolve_script(&self, attributes: &[Attribute<'a>]) -> Option<(&'a str, Span)> {
+    let resolver = self.options.resolve_script.as_ref()?;
+    let src = attributes
+      .iter()
+      .find(|attribute| attribute.key.normalized.eq_ignore_ascii_case("src"))?
+      .value
+      .as_ref()?;
+    let content = resolver.resolve(src.value)?;
+    Some((self.allocator.alloc_str(&content), src.span))
+  }
+
+  /// Parse the content of an external script, reporting its errors at its `src`.
+  fn parse_resolved_script(
+    &mut self,
+    content: &'a str,
+    src: Span,
+    source_type: SourceType,
+  ) -> JsProgram<'a> {
+    trace!(bytes = content.len(), "parse external script");
+    let parse_options = *self.options.parse_script.as_ref().unwrap();
+    let started = self.options.collect_metrics.then(Instant::now);
+    let ret = JsParser::new(self.allocator, content, source_type)
+      .with_options(parse_options)
+      .parse();
+    if let Some(started) = started {
+      self.embedded_duration += started.elapsed();
+    }
+
+    for mut error in ret.errors {
+      // the spans are in the external script, point at where it is loaded instead
+      let label = error
+        .labels
+        .take()
+        .into_iter()
+        .flatten()
+        .find_map(|label| label.label().map(ToString::to_string));
+      error.labels =
+        Some(vec![src.label(
+          label.unwrap_or_else(|| "in the script loaded here".to_string()),
+        )]);
+      if !error.code.is_some() {
+        error = error.with_error_code(ERROR_CODE_SCOPE, HtmlParseErrorKind::ScriptSyntax.code());
+      }
+      self.report(error);
+    }
+
+    ret.program
+  }
+
+  /// Get the content of a script from its children, and its offset in the document.
+  fn script_content(&self, children: &[Node<'a>], start: u32) -> (&'a str, u32) {
     // Extract script content from children
     // If there is a single text node, use it directly (zero-copy)
     // Otherwise, concatenate text nodes and allocate in arena
-    let script_content: &str = if children.len() == 1 {
+    let script_content: &'a str = if children.len() == 1 {
       if let Some(Node::Text(text)) = children.first() {
         text.value
       } else {
@@ -616,187 +1668,2322 @@ impl<'a> HtmlParserImpl<'a> {
       self.allocator.alloc_str(&content)
     };
 
-    // Parse the JavaScript content
-    let source_type = SourceType::default();
-    let parse_options = *self.options.parse_script.as_ref().unwrap();
+    // Error spans are relative to the script, and moved to the HTML source
+    let start_offset = children
+      .iter()
+      .find_map(|node| {
+        if let Node::Text(text) = node {
+          Some(text.span.start)
+        } else {
+          None
+        }
+      })
+      .unwrap_or(start);
+
+    (script_content, start_offset)
+  }
+
+  /// Record the imports and exports of a module script starting at `offset`.
+  fn module_record(
+    &mut self,
+    kind: ScriptKind<'a>,
+    program: &JsProgram<'a>,
+    offset: u32,
+  ) -> Option<Box<'a, ModuleRecord<'a>>> {
+    if kind != ScriptKind::Module {
+      return None;
+    }
+    let record = module::module_record(self.allocator, program, offset);
+    self.has_imports |= !record.imports.is_empty();
+    Some(record)
+  }
+
+  /// Report the errors of a script starting at `start_offset` in the document.
+  fn report_script_errors(&mut self, errors: Vec<OxcDiagnostic>, start_offset: u32) {
+    for mut error in errors {
+      if let Some(labels) = error.labels.take() {
+        let new_labels = labels
+          .into_iter()
+          .map(|label| {
+            let offset = label.offset() + start_offset as usize;
+            let len = label.len();
+            let msg = label.label().map(ToString::to_string);
+            LabeledSpan::new_with_span(msg, (offset, len))
+          })
+          .collect();
+        error.labels = Some(new_labels);
+      }
+      if !error.code.is_some() {
+        error = error.with_error_code(ERROR_CODE_SCOPE, HtmlParseErrorKind::ScriptSyntax.code());
+      }
+      self.report(error);
+    }
+  }
+
+  /// Parse the scripts deferred by [`parallel_scripts`](HtmlParserOption::parallel_scripts),
+  /// and put their programs in their nodes.
+  fn parse_pending_scripts(&mut self, nodes: &mut [Node<'a>]) {
+    let scripts = std::mem::take(&mut self.pending_scripts);
+    let parse_options = *self.options.parse_script.as_ref().unwrap();
+    let started = self.options.collect_metrics.then(Instant::now);
+    let parsed = parallel::parse_scripts(self.allocator, &scripts, parse_options);
+    if let Some(started) = started {
+      self.embedded_duration += started.elapsed();
+    }
+
+    let mut programs = HashMap::with_capacity(scripts.len());
+    for (script, parsed) in scripts.iter().zip(parsed) {
+      self.report_script_errors(parsed.errors, script.offset);
+      programs.insert(script.start, (parsed.program, script.offset));
+    }
+    self.set_script_programs(nodes, &mut programs);
+  }
+
+  /// Put the programs of scripts, by start of their element, in their nodes, with the
+  /// offset of their source.
+  fn set_script_programs(
+    &mut self,
+    nodes: &mut [Node<'a>],
+    programs: &mut HashMap<u32, (JsProgram<'a>, u32)>,
+  ) {
+    for node in nodes {
+      match node {
+        Node::Script(script) => {
+          if let Some((program, offset)) = programs.remove(&script.span.start) {
+            script.module = self.module_record(script.info.kind, &program, offset);
+            script.program = program;
+          }
+        }
+        Node::Element(element) => self.set_script_programs(&mut element.children, programs),
+        Node::Comment(comment) => {
+          if let Some(conditional) = &mut comment.conditional {
+            self.set_script_programs(&mut conditional.children, programs);
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+/// Parse the attributes of a tag, up to and including its `>` or `/>`.
+///
+/// Returns the `>` or `/>` token, `None` if the tag is not closed.
+pub fn parse_attributes<'a>(
+  source_text: &'a str,
+  iter: &mut impl TokenCursor,
+  errors: &mut Vec<OxcDiagnostic>,
+  mut push: impl FnMut(Attribute<'a>),
+) -> Option<Token<HtmlKind>> {
+  let mut tag_end = None;
+  let mut current_attr_key: Option<AttributeKey<'a>> = None;
+
+  while let Some(token) = iter.peek() {
+    match token.kind {
+      HtmlKind::TagEnd | HtmlKind::SelfCloseTagEnd => {
+        tag_end = iter.next();
+        break;
+      }
+      // a template tag between attributes is kept as an attribute without value
+      HtmlKind::Attribute | HtmlKind::TemplateTag => {
+        let attr_token = iter.next().unwrap();
+        let attr_text = &source_text[attr_token.start as usize..attr_token.end as usize];
+
+        // If we have a pending attribute key without value, stop storing it because a new attribute is coming
+        if let Some(key) = current_attr_key.take() {
+          let span = key.span;
+          push(Attribute {
+            span,
+            key,
+            value: None,
+            style: None,
+          });
+        }
+
+        current_attr_key = Some(AttributeKey {
+          span: attr_token.span(),
+          value: attr_text,
+          normalized: attr_text,
+        });
+      }
+      HtmlKind::Eq => {
+        let eq_token = iter.next().unwrap();
+
+        // skip the whitespace before the value, however many tokens it is lexed in
+        while iter
+          .peek()
+          .is_some_and(|token| token.kind == HtmlKind::Whitespace)
+        {
+          iter.next();
+        }
+
+        // Next token should be attribute value
+        if let Some(value_token) = iter.peek()
+          && value_token.kind == HtmlKind::Attribute
+        {
+          let value_token = iter.next().unwrap();
+          if let Some(key) = current_attr_key.take() {
+            let value = unquote_attribute(source_text, &value_token);
+            let span = Span::new(key.span.start, value.span.end);
+            push(Attribute {
+              span,
+              key,
+              value: Some(value),
+              style: None,
+            });
+          }
+        } else {
+          // Handle missing value after =
+          errors.push(
+            HtmlParseErrorKind::MissingAttributeValue
+              .error("Expected attribute value after '='")
+              .with_label(Span::new(eq_token.start, eq_token.end)),
+          );
+        }
+      }
+      HtmlKind::Eof => break,
+      _ => {
+        iter.next();
+      }
+    }
+  }
+
+  // Add any remaining attribute without value
+  if let Some(key) = current_attr_key.take() {
+    let span = key.span;
+    push(Attribute {
+      span,
+      key,
+      value: None,
+      style: None,
+    });
+  }
+
+  tag_end
+}
+
+/// Remove quotes from attribute value.
+pub fn unquote_attribute<'a>(source_text: &'a str, token: &Token<HtmlKind>) -> AttributeValue<'a> {
+  let span = token.span();
+  let value = &source_text[token.start as usize..token.end as usize];
+
+  // a lone quote is both the opening and the closing one
+  if value.len() >= 2
+    && ((value.starts_with('"') && value.ends_with('"'))
+      || (value.starts_with('\'') && value.ends_with('\'')))
+  {
+    AttributeValue {
+      value: &value[1..value.len() - 1],
+      raw: value,
+      span,
+      value_span: Span::new(span.start + 1, span.end - 1),
+    }
+  } else {
+    AttributeValue {
+      value,
+      raw: value,
+      span,
+      value_span: span,
+    }
+  }
+}
+
+/// Get a quoted identifier of a DOCTYPE without its quotes, the closing one being missing
+/// when a `>` ends the identifier.
+fn doctype_id(text: &str) -> &str {
+  let content = &text[1..];
+  content.strip_suffix(&text[..1]).unwrap_or(content)
+}
+
+/// Whether a root node can go before the doctype and the root element: a comment, a
+/// directive, a doctype, a template tag or whitespace.
+fn is_prelude(node: &Node) -> bool {
+  match node {
+    Node::Comment(_)
+    | Node::Directive(_)
+    | Node::Doctype(_)
+    | Node::RawUnknown(_)
+    | Node::TemplateTag(_) => true,
+    Node::Text(text) => text.value.trim_ascii().is_empty(),
+    Node::Element(_) | Node::Script(_) | Node::Mustache(_) => false,
+  }
+}
+
+/// Whether the parser is in the prelude of the document, before its root element and any
+/// content.
+fn in_prelude(nodes: &[Node], element_stack: &[ElementBuilder]) -> bool {
+  element_stack.is_empty() && nodes.iter().all(is_prelude)
+}
+
+/// Count the nodes of a tree by kind, for the [metrics](HtmlParserOption::collect_metrics).
+fn count_nodes(nodes: &[Node], counts: &mut BTreeMap<&'static str, usize>) {
+  for node in nodes {
+    let kind = match node {
+      Node::Doctype(_) => "doctype",
+      Node::Element(element) => {
+        count_nodes(&element.children, counts);
+        "element"
+      }
+      Node::Text(_) => "text",
+      Node::Comment(comment) => {
+        if let Some(conditional) = &comment.conditional {
+          count_nodes(&conditional.children, counts);
+        }
+        "comment"
+      }
+      Node::Script(_) => "script",
+      Node::RawUnknown(_) => "raw_unknown",
+      Node::Mustache(mustache) => {
+        count_nodes(&mustache.children, counts);
+        count_nodes(&mustache.inverse, counts);
+        "mustache"
+      }
+      Node::TemplateTag(_) => "template_tag",
+      Node::Directive(_) => "directive",
+    };
+    *counts.entry(kind).or_default() += 1;
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::option::ScriptResolver;
+  use insta::assert_snapshot;
+
+  fn parse(source_text: &str) -> String {
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let parser = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options));
+    let result = parser.parse();
+
+    format!("Nodes: {:#?}\nErrors: {:#?}", result.program, result.errors)
+  }
+
+  #[test]
+  fn basic_html() {
+    const HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8">
+  <title>Document</title>
+</head>
+<body>
+  <p>Hello World</p>
+</body>
+</html>"#;
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn nested_elements() {
+    const HTML: &str = r"<div>
+  <p>Paragraph 1</p>
+  <p>Paragraph 2</p>
+</div>";
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn self_closing_elements() {
+    const HTML: &str = r#"<div>
+  <br>
+  <img src="test.jpg" alt="Test">
+  <input type="text" />
+</div>"#;
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn comments() {
+    const HTML: &str = r"<!-- This is a comment -->
+<div>Content</div>
+<!-- Another comment -->
+<! This is a bogus comment >
+<!Bogus Comment Too>
+";
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn attribute_value_spans() {
+    const HTML: &str = r#"<a b="c" d='' e=f>"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let Node::Element(element) = &result.program[0] else {
+      unreachable!()
+    };
+    let spans: Vec<_> = element
+      .attributes
+      .iter()
+      .map(|attribute| {
+        let value = attribute.value.as_ref().unwrap();
+        assert_eq!(value.value_span.source_text(HTML), value.value);
+        (value.span.source_text(HTML), value.value_span)
+      })
+      .collect();
+    assert_eq!(
+      spans,
+      [
+        (r#""c""#, Span::new(6, 7)),
+        ("''", Span::empty(12)),
+        ("f", Span::new(16, 17)),
+      ]
+    );
+  }
+
+  #[test]
+  fn childless_element_spans() {
+    const HTML: &str = "<img src=\"a.png\">\n<br/>text<div/> <!-- a --><input";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder().preserve_comments(false).build();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let elements: Vec<_> = result
+      .program
+      .iter()
+      .filter_map(|node| match node {
+        Node::Element(element) => Some(element.span.source_text(HTML)),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(
+      elements,
+      ["<img src=\"a.png\">", "<br/>", "<div/>", "<input"]
+    );
+  }
+
+  #[test]
+  fn doctype_identifiers() {
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let doctype = |source_text| {
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let Some(Node::Doctype(doctype)) = result.program.into_iter().next() else {
+        unreachable!()
+      };
+      let words: Vec<_> = doctype.attributes.iter().map(|a| a.key.value).collect();
+      (doctype.name, doctype.public_id, doctype.system_id, words)
+    };
+
+    assert_eq!(
+      doctype(
+        r#"<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 4.01//EN" 'http://www.w3.org/TR/html4/strict.dtd'>"#
+      ),
+      (
+        Some("HTML"),
+        Some("-//W3C//DTD HTML 4.01//EN"),
+        Some("http://www.w3.org/TR/html4/strict.dtd"),
+        vec![
+          "HTML",
+          "PUBLIC",
+          r#""-//W3C//DTD HTML 4.01//EN""#,
+          "'http://www.w3.org/TR/html4/strict.dtd'"
+        ]
+      )
+    );
+    assert_eq!(
+      doctype(r#"<!doctype html system"about:legacy-compat">"#),
+      (
+        Some("html"),
+        None,
+        Some("about:legacy-compat"),
+        vec!["html", "system", r#""about:legacy-compat""#]
+      )
+    );
+    // a `>` ends the identifier, and what does not fit the DOCTYPE is bogus
+    assert_eq!(
+      doctype(r#"<!DOCTYPE html PUBLIC "a>"#),
+      (
+        Some("html"),
+        Some("a"),
+        None,
+        vec!["html", "PUBLIC", r#""a"#]
+      )
+    );
+    assert_eq!(
+      doctype("<!DOCTYPE html foo bar>"),
+      (Some("html"), None, None, vec!["html", "foo bar"])
+    );
+  }
+
+  #[test]
+  fn abrupt_comments() {
+    const HTML: &str = "<!-->a<!--->b<!---->c<!-- d --->e<!-- f --!>";
+
+    let allocator = Allocator::default();
+    let result =
+      HtmlParserImpl::new(&allocator, HTML, Cow::Owned(HtmlParserOption::default())).parse();
+    let comments: Vec<_> = result
+      .program
+      .iter()
+      .filter_map(|node| match node {
+        Node::Comment(comment) => Some((comment.raw, comment.value)),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(
+      comments,
+      [
+        ("<!-->", ""),
+        ("<!--->", ""),
+        ("<!---->", ""),
+        ("<!-- d --->", " d -"),
+        ("<!-- f --!>", " f "),
+      ]
+    );
+    let errors: Vec<_> = result
+      .errors
+      .iter()
+      .map(|error| {
+        (
+          HtmlParseErrorKind::from_diagnostic(error),
+          error.labels.clone(),
+        )
+      })
+      .map(|(kind, labels)| (kind.unwrap(), labels.unwrap()[0].offset()))
+      .collect();
+    assert_eq!(
+      errors,
+      [
+        (HtmlParseErrorKind::AbruptClosingOfEmptyComment, 4),
+        (HtmlParseErrorKind::AbruptClosingOfEmptyComment, 10),
+        (HtmlParseErrorKind::IncorrectlyClosedComment, 40),
+      ]
+    );
+  }
+
+  #[test]
+  fn attribute_with_whitespaces() {
+    const HTML: &str = r#"<div class = "test" a= "b">Content</div>"#;
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn attributes_across_whitespace() {
+    const HTML: &str = "<div class =   \"x\"\n  id\n=\n\ty title\r\n=\r\n  'z' / hidden\n>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let Node::Element(element) = &result.program[0] else {
+      unreachable!()
+    };
+    let attributes: Vec<_> = element
+      .attributes
+      .iter()
+      .map(|attribute| {
+        (
+          attribute.key.name(),
+          attribute.value.as_ref().map(|value| value.value),
+        )
+      })
+      .collect();
+    assert_eq!(
+      attributes,
+      [
+        ("class", Some("x")),
+        ("id", Some("y")),
+        ("title", Some("z")),
+        ("hidden", None),
+      ]
+    );
+
+    // the lexer lexes a whitespace run in one token, other token sources may not
+    let source_text = "title= \t\"x\">";
+    let mut tokens = [
+      (HtmlKind::Attribute, 0, 5),
+      (HtmlKind::Eq, 5, 6),
+      (HtmlKind::Whitespace, 6, 7),
+      (HtmlKind::Whitespace, 7, 8),
+      (HtmlKind::Attribute, 8, 11),
+      (HtmlKind::TagEnd, 11, 12),
+    ]
+    .into_iter()
+    .map(|(kind, start, end)| Token { kind, start, end })
+    .peekable();
+    let mut attributes = Vec::new();
+    parse_attributes(source_text, &mut tokens, &mut Vec::new(), |attribute| {
+      attributes.push((
+        attribute.key.value,
+        attribute.value.map(|value| value.value),
+      ));
+    });
+    assert_eq!(attributes, [("title", Some("x"))]);
+  }
+
+  #[test]
+  fn end_tag_attributes() {
+    const HTML: &str = r#"<div><p>a</p class="b" c></div/><i>d</i>"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let mut output = String::new();
+    tree(&result.program, &mut output);
+    assert_eq!(output, "div(p(a))i(d)");
+
+    assert_eq!(result.errors.len(), 1);
+    let error = &result.errors[0];
+    assert_eq!(
+      HtmlParseErrorKind::from_diagnostic(error),
+      Some(HtmlParseErrorKind::EndTagWithAttributes)
+    );
+    assert_eq!(
+      error.message,
+      r#"Attributes in closing tag </p>: class="b" c"#
+    );
+    assert_eq!(
+      error.labels,
+      Some(vec![
+        Span::new(13, 22).primary_label("ignored"),
+        Span::new(23, 24).label("ignored"),
+      ])
+    );
+  }
+
+  #[test]
+  fn directives() {
+    const HTML: &str = "<?xml version=\"1.0\"?>\n<?xml-stylesheet href=\"a.xsl\"?>\n<!DOCTYPE html><p><?php echo 1 ?></p>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty());
+
+    let directives: Vec<_> = result
+      .program
+      .iter()
+      .filter_map(|node| match node {
+        Node::Directive(directive) => Some((directive.name, directive.value, directive.span)),
+        _ => None,
+      })
+      .collect();
+    assert_eq!(
+      directives,
+      [
+        ("xml", r#" version="1.0""#, Span::new(0, 21)),
+        ("xml-stylesheet", r#" href="a.xsl""#, Span::new(22, 53)),
+      ]
+    );
+    let Some(Node::Directive(declaration)) = result.program.first() else {
+      unreachable!()
+    };
+    assert!(declaration.is_xml_declaration());
+
+    // after the prelude, `<?...>` is a bogus comment
+    let Some(Node::Element(p)) = result.program.last() else {
+      unreachable!()
+    };
+    assert!(matches!(&p.children[0], Node::Comment(comment) if comment.bogus));
+  }
+
+  #[test]
+  fn content_before_doctype() {
+    for (html, content) in [
+      ("Text<!DOCTYPE html>", Span::new(0, 4)),
+      ("<!-- a --><p>a</p><!DOCTYPE html>", Span::new(10, 18)),
+      ("<html><!DOCTYPE html>", Span::new(0, 6)),
+    ] {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::default();
+      let result = HtmlParserImpl::new(&allocator, html, Cow::Borrowed(&options)).parse();
+      let errors: Vec<_> = result
+        .errors
+        .iter()
+        .filter(|error| {
+          HtmlParseErrorKind::from_diagnostic(error)
+            == Some(HtmlParseErrorKind::ContentBeforeDoctype)
+        })
+        .collect();
+      assert_eq!(errors.len(), 1, "{html}");
+      let doctype = Span::new(html.len() as u32 - 15, html.len() as u32);
+      assert_eq!(
+        errors[0].labels,
+        Some(vec![
+          content.primary_label("content before the DOCTYPE"),
+          doctype.label("DOCTYPE"),
+        ]),
+        "{html}"
+      );
+    }
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(
+      &allocator,
+      "<!-- a -->\n<!DOCTYPE html>",
+      Cow::Borrowed(&options),
+    )
+    .parse();
+    assert!(result.errors.is_empty());
+  }
+
+  #[test]
+  fn multiple_no_value_attributes() {
+    const HTML: &str = r"<input checked disabled readonly>";
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn void_elements() {
+    const HTML: &str = r#"<div>
+  <br>
+  <img src="test.jpg" alt="Test">
+  <input type="text">
+  <meta>Text</meta> <!-- Should Error -->
+</div>"#;
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn self_close_tags() {
+    const HTML: &str = r"<div/><p />";
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn no_closing_tag() {
+    const HTML: &str = r"<div>
+  <p>Unclosed paragraph
+</div>";
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn orphan_closing_tag() {
+    const HTML: &str = r"<div>Content</div>
+</span>
+<p>More content</p>";
+
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn incomplete_attribute() {
+    const HTML: &str = r"<div class=></div>";
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn processing_instruction() {
+    const HTML: &str = r#"<?xml version="1.0"?><p>Text</p>"#;
+    assert_snapshot!(parse(HTML));
+  }
+
+  #[test]
+  fn preserve_unknown() {
+    const HTML: &str = r"<div><?php echo 1 ?><! bogus ></span>Text</div>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      preserve_unknown: true,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+
+    assert_snapshot!(format!(
+      "Nodes: {:#?}\nErrors: {:#?}",
+      result.program, result.errors
+    ));
+  }
+
+  #[test]
+  fn limits() {
+    const HTML: &str =
+      r#"<div><p><span a b c>Deep</span></p></div><br x="1" y="2" z="3"><i>Rest</i>"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      max_depth: Some(2),
+      max_attributes: Some(1),
+      max_tokens: Some(43),
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+
+    assert_snapshot!(format!(
+      "Nodes: {:#?}\nErrors: {:#?}",
+      result.program, result.errors
+    ));
+  }
+
+  #[test]
+  fn whitespace() {
+    use umc_html_ast::Node;
+
+    use crate::option::Whitespace;
+
+    const HTML: &str = "<ul>\n  <li> One </li>\n</ul>\n<pre>\n  <b> x </b>\n</pre>";
+
+    // the source of each text, checking its span
+    fn texts(nodes: &[Node], output: &mut Vec<(u32, &'static str)>) {
+      for node in nodes {
+        match node {
+          Node::Text(text) => {
+            let source = &HTML[text.span.start as usize..text.span.end as usize];
+            assert_eq!(source, text.value);
+            output.push((text.span.start, source));
+          }
+          Node::Element(element) => texts(&element.children, output),
+          _ => {}
+        }
+      }
+    }
+
+    let parse = |whitespace| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption {
+        whitespace,
+        ..HtmlParserOption::default()
+      };
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      let mut output = Vec::new();
+      texts(&result.program, &mut output);
+      output
+    };
+
+    assert_eq!(
+      parse(Whitespace::Preserve),
+      [
+        (4, "\n  "),
+        (11, " One "),
+        (21, "\n"),
+        (27, "\n"),
+        (33, "\n  "),
+        (39, " x "),
+        (46, "\n"),
+      ]
+    );
+    assert_eq!(
+      parse(Whitespace::Skip),
+      [(11, " One "), (33, "\n  "), (39, " x "), (46, "\n")]
+    );
+    assert_eq!(
+      parse(Whitespace::Trim),
+      [(12, "One"), (33, "\n  "), (39, " x "), (46, "\n")]
+    );
+  }
+
+  #[test]
+  fn conditional_comments() {
+    const HTML: &str = "<!--[if lt IE 9]><script src=\"html5shiv.js\"></script><p>Old<![endif]--><!--[if]><![endif]--><!-- x -->";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      parse_conditional_comments: true,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert_snapshot!(format!(
+      "Nodes: {:#?}\nErrors: {:#?}",
+      result.program, result.errors
+    ));
+
+    let options = HtmlParserOption {
+      preserve_comments: false,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(
+      &allocator,
+      "<p><!-- a -->b</p><!-- c -->",
+      Cow::Borrowed(&options),
+    )
+    .parse();
+    assert_eq!(result.program.len(), 1);
+    let Node::Element(p) = &result.program[0] else {
+      unreachable!()
+    };
+    assert!(matches!(&p.children[..], [Node::Text(_)]));
+  }
+
+  #[test]
+  fn normalize_case() {
+    const HTML: &str = "<DIV Class=a><svg viewBox='0 0 1 1'></svg></div>";
+
+    let names = |normalize_case| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder()
+        .normalize_case(normalize_case)
+        .build();
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      assert!(result.errors.is_empty());
+      let Node::Element(div) = &result.program[0] else {
+        unreachable!()
+      };
+      let Node::Element(svg) = &div.children[0] else {
+        unreachable!()
+      };
+      assert_eq!(div.raw_name(), "DIV");
+      assert_eq!(div.attributes[0].key.raw_name(), "Class");
+      assert_eq!(svg.attributes[0].key.raw_name(), "viewBox");
+      format!(
+        "{} {} {}",
+        div.name(),
+        div.attributes[0].key.name(),
+        svg.attributes[0].key.name()
+      )
+    };
+
+    assert_eq!(names(true), "div class viewbox");
+    assert_eq!(names(false), "DIV Class viewBox");
+  }
+
+  #[test]
+  fn custom_elements() {
+    use crate::option::TagSet;
+
+    const HTML: &str = r#"<my-icon><my-code><b></my-code><button is="fancy-button" is-x></button><font-face></font-face>"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .void_tags(TagSet::Html5Void.with_names(["my-icon"]))
+      .embedded_language_tags(TagSet::Html5RawText.with_names(["my-code"]))
+      .build();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty());
+
+    let elements: Vec<_> = result
+      .program
+      .iter()
+      .map(|node| {
+        let Node::Element(element) = node else {
+          unreachable!()
+        };
+        (
+          element.name(),
+          element.is_custom_element(),
+          element.is_value(),
+          element.children.len(),
+        )
+      })
+      .collect();
+    assert_eq!(
+      elements,
+      [
+        ("my-icon", true, None, 0),
+        ("my-code", true, None, 1),
+        ("button", false, Some("fancy-button"), 0),
+        ("font-face", false, None, 0),
+      ]
+    );
+  }
+
+  // the tree in the notation of html5lib tests, checking the spans
+  fn tree(nodes: &[Node], output: &mut String) {
+    for node in nodes {
+      match node {
+        Node::Element(element) => {
+          let span = element.span;
+          assert!(span.start <= span.end);
+          output.push_str(element.name());
+          output.push('(');
+          tree(&element.children, output);
+          output.push(')');
+        }
+        Node::Text(text) => output.push_str(text.value),
+        Node::Mustache(mustache) => {
+          output.push_str("{{");
+          output.push_str(mustache.expression);
+          output.push_str("}}");
+          if matches!(
+            mustache.kind,
+            MustacheKind::Block | MustacheKind::InvertedBlock
+          ) {
+            output.push('(');
+            tree(&mustache.children, output);
+            output.push(')');
+          }
+        }
+        _ => {}
+      }
+    }
+  }
+
+  #[test]
+  fn adoption_agency() {
+    use crate::error::HtmlParseErrorKind;
+
+    let parse = |source_text: &str| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder().adoption_agency(true).build();
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let mut output = String::new();
+      tree(&result.program, &mut output);
+      let kinds: Vec<_> = result
+        .errors
+        .iter()
+        .map(|error| HtmlParseErrorKind::from_diagnostic(error).unwrap())
+        .collect();
+      (output, kinds)
+    };
+
+    let misnested = vec![HtmlParseErrorKind::MisnestedFormattingElement];
+    assert_eq!(
+      parse("<b>bold<i>both</b>italic</i>"),
+      ("b(boldi(both))i(italic)".to_string(), misnested.clone())
+    );
+    assert_eq!(
+      parse("<b>1<p>2</b>3</p>"),
+      ("b(1)p(b(2)3)".to_string(), misnested.clone())
+    );
+    assert_eq!(
+      parse("<a>1<b>2<div>3</a>4</div></b>"),
+      ("a(1b(2))b(div(a(3)4))".to_string(), misnested.clone())
+    );
+    // an opening `<a>` or `<nobr>` closes the one still open
+    assert_eq!(
+      parse("<a href=x>1<a href=y>2</a>"),
+      ("a(1)a(2)".to_string(), misnested.clone())
+    );
+    assert_eq!(
+      parse("<nobr>1<b>2<nobr>3</nobr></b>"),
+      ("nobr(1b(2))b(nobr(3))".to_string(), misnested.clone())
+    );
+    assert_eq!(
+      parse("<a>1<div>2<a>3</a></div>"),
+      ("a(1)div(a(2)a(3))".to_string(), misnested)
+    );
+    assert_eq!(
+      parse("<p><a>1</p><a>2</a>"),
+      (
+        "p(a(1))a(2)".to_string(),
+        vec![
+          HtmlParseErrorKind::ImplicitlyClosedElement,
+          HtmlParseErrorKind::MisnestedFormattingElement
+        ]
+      )
+    );
+    assert_eq!(
+      parse("<p><b>1</p>2<em>3</b>"),
+      (
+        "p(b(1))b(2em(3))".to_string(),
+        vec![
+          HtmlParseErrorKind::ImplicitlyClosedElement,
+          HtmlParseErrorKind::MisnestedFormattingElement
+        ]
+      )
+    );
+    assert_eq!(
+      parse("<b>1<td>2</b>3</td></b>"),
+      (
+        "b(1td(23))".to_string(),
+        vec![HtmlParseErrorKind::MisnestedFormattingElement]
+      )
+    );
+
+    // at most three identical formatting elements are reopened
+    assert_eq!(
+      parse("<p><b class=x><b class=x><b class=x><b class=x><b>1</p>2").0,
+      "p(b(b(b(b(b(1))))))b(b(b(b(2))))"
+    );
+    let (output, kinds) = parse(&format!(
+      "<p>{}{}",
+      "<b>".repeat(2000),
+      "</p><p>x".repeat(2000)
+    ));
+    assert!(output.len() < 40_000, "{}", output.len());
+    assert!(kinds.len() < 20_000, "{}", kinds.len());
+
+    // the default keeps the tree of the closing tags
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result =
+      HtmlParserImpl::new(&allocator, "<b>1<i>2</b>3</i>", Cow::Borrowed(&options)).parse();
+    let mut output = String::new();
+    tree(&result.program, &mut output);
+    assert_eq!(output, "b(1i(2))3");
+  }
+
+  #[test]
+  fn implied_tags() {
+    let parse = |source_text: &str| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder()
+        .implied_tags(true)
+        .adoption_agency(true)
+        .build();
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let mut output = String::new();
+      tree(&result.program, &mut output);
+      output
+    };
+
+    assert_eq!(
+      parse("<title>A</title>\n<p>B"),
+      "html(head(title(A)\n)body(p(B)))"
+    );
+    assert_eq!(
+      parse("<!DOCTYPE html>\n<html><head></head>\n<body>A</body></html>"),
+      "\nhtml(head()\nbody(A))"
+    );
+    assert_eq!(
+      parse("<html>\n<link rel=icon><b>A</b></html>"),
+      "html(\nhead(link())body(b(A)))"
+    );
+    assert_eq!(
+      parse("<b>bold<i>both</b>italic</i>"),
+      "html(head()body(b(boldi(both))i(italic)))"
+    );
+    // content after `</body>` and `</html>` goes in the body
+    assert_eq!(parse("<head></head><body></body>x"), "html(head()body(x))");
+    assert_eq!(
+      parse("<html><body>a</body></html>\n<p>b</p>"),
+      "html(head()body(a\np(b)))"
+    );
+
+    // implied elements have zero-width tags, and no errors for their missing tags
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder().implied_tags(true).build();
+    let result = HtmlParserImpl::new(&allocator, "<p>A</p>", Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty());
+    let reopened =
+      HtmlParserImpl::new(&allocator, "<body></body></html>x", Cow::Borrowed(&options)).parse();
+    assert!(reopened.errors.is_empty());
+    let Node::Element(html) = &result.program[0] else {
+      unreachable!()
+    };
+    let spans: Vec<_> = html
+      .children
+      .iter()
+      .map(|node| {
+        let Node::Element(element) = node else {
+          unreachable!()
+        };
+        (element.name(), element.span)
+      })
+      .collect();
+    assert_eq!(html.span, Span::new(0, 8));
+    assert_eq!(spans, [("head", Span::empty(0)), ("body", Span::new(0, 8))]);
+  }
+
+  #[test]
+  fn utf8_boundaries() {
+    use crate::option::TemplateDelimiters;
+
+    // syntax next to characters of every UTF-8 length
+    const PIECES: &[&str] = &[
+      "<", ">", "</", "<!--", "-->", "<!", "<?", "=", "\"", "'", "/", " ", "a", "é", "€", "😀",
+      "{{", "}}", "«", "»", "<script>", "style=",
+    ];
+
+    fn check(source_text: &str, nodes: &[Node]) {
+      let on_boundaries = |span: Span| {
+        assert!(
+          source_text.is_char_boundary(span.start as usize)
+            && source_text.is_char_boundary(span.end as usize),
+          "{span:?} splits a character of {source_text:?}"
+        );
+      };
+      for node in nodes {
+        match node {
+          Node::Element(element) => {
+            on_boundaries(element.span);
+            for attribute in &element.attributes {
+              on_boundaries(attribute.span);
+              if let Some(value) = &attribute.value {
+                on_boundaries(value.span);
+              }
+            }
+            check(source_text, &element.children);
+          }
+          Node::Text(text) => on_boundaries(text.span),
+          Node::Comment(comment) => on_boundaries(comment.span),
+          Node::RawUnknown(raw) => on_boundaries(raw.span),
+          Node::Mustache(mustache) => on_boundaries(mustache.span),
+          Node::TemplateTag(tag) => on_boundaries(tag.span),
+          _ => {}
+        }
+      }
+    }
+
+    let options = [
+      HtmlParserOption::default(),
+      HtmlParserOption::builder()
+        .handlebars(true)
+        .template_delimiters(vec![TemplateDelimiters::new("«", "»")])
+        .parse_style_attributes(true)
+        .whitespace(Whitespace::Trim)
+        .preserve_unknown(true)
+        .adoption_agency(true)
+        .implied_tags(true)
+        .build(),
+    ];
+    let mut source_text = String::new();
+    for a in PIECES {
+      for b in PIECES {
+        for c in PIECES {
+          source_text.clear();
+          source_text.extend([*a, *b, *c]);
+          for options in &options {
+            let allocator = Allocator::default();
+            let result =
+              HtmlParserImpl::new(&allocator, &source_text, Cow::Borrowed(options)).parse();
+            check(&source_text, &result.program);
+            for label in result
+              .errors
+              .iter()
+              .flat_map(|error| error.labels.iter().flatten())
+            {
+              assert!(source_text.is_char_boundary(label.offset()));
+              assert!(source_text.is_char_boundary(label.offset() + label.len()));
+            }
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn recovery_strategy() {
+    use crate::option::RecoveryStrategy;
+
+    const HTML: &str = "<body><section><h2>Title</h2><div><p>Some te";
+
+    let parse = |recovery_strategy| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder()
+        .recovery_strategy(recovery_strategy)
+        .build();
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      assert_eq!(result.errors.len(), 4);
+      let mut output = String::new();
+      tree(&result.program, &mut output);
+      output
+    };
+
+    assert_eq!(
+      parse(RecoveryStrategy::AutoClose),
+      "body(section(h2(Title)div(p(Some te))))"
+    );
+    assert_eq!(
+      parse(RecoveryStrategy::DiscardIncomplete),
+      "body(section(h2(Title)div()))"
+    );
+    assert_eq!(
+      parse(RecoveryStrategy::BubbleToSection),
+      "body(section(h2(Title)Some te))"
+    );
+  }
+
+  #[test]
+  fn self_closing() {
+    use oxc_diagnostics::Severity;
+
+    const HTML: &str = "<div/><br/><svg><path/></svg>text";
+
+    let parse = |self_closing| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder()
+        .self_closing(self_closing)
+        .build();
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      let errors: Vec<_> = result
+        .errors
+        .iter()
+        .map(|error| {
+          (
+            HtmlParseErrorKind::from_diagnostic(error),
+            error.severity,
+            error.labels.clone(),
+          )
+        })
+        .collect();
+      let mut output = String::new();
+      tree(&result.program, &mut output);
+      (output, errors)
+    };
+
+    assert_eq!(
+      parse(SelfClosing::TreatSelfClosingAsClosed),
+      ("div()br()svg(path())text".to_string(), vec![])
+    );
+    let (output, errors) = parse(SelfClosing::Spec);
+    assert_eq!(output, "div(br()svg(path())text)");
+    assert_eq!(
+      errors[0],
+      (
+        Some(HtmlParseErrorKind::TrailingSolidus),
+        Severity::Warning,
+        Some(vec![Span::new(4, 6).primary_label("ignored")]),
+      )
+    );
+    assert_eq!(errors[1].0, Some(HtmlParseErrorKind::UnclosedElement));
+  }
+
+  #[test]
+  fn self_closing_raw_text() {
+    const HTML: &str = r#"<script/>if (a<b) { c = "</p>" }</script><i>z</i>"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .self_closing(SelfClosing::Spec)
+      .build();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let mut output = String::new();
+    tree(&result.program, &mut output);
+    assert_eq!(output, "i(z)");
+    let Node::Script(script) = &result.program[0] else {
+      unreachable!()
+    };
+    assert_eq!(script.program.source_text, r#"if (a<b) { c = "</p>" }"#);
+    let kinds: Vec<_> = result
+      .errors
+      .iter()
+      .filter_map(HtmlParseErrorKind::from_diagnostic)
+      .collect();
+    assert_eq!(kinds, [HtmlParseErrorKind::TrailingSolidus]);
+  }
+
+  #[test]
+  fn self_closing_integration_points() {
+    const HTML: &str =
+      "<svg><foreignObject><div/>x</foreignObject><g/></svg><math><mi><p/>y</mi></math>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .self_closing(SelfClosing::Spec)
+      .build();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let mut output = String::new();
+    tree(&result.program, &mut output);
+    assert_eq!(output, "svg(foreignobject(div(x))g())math(mi(p(y)))");
+    let trailing = result
+      .errors
+      .iter()
+      .filter(|error| {
+        HtmlParseErrorKind::from_diagnostic(error) == Some(HtmlParseErrorKind::TrailingSolidus)
+      })
+      .count();
+    assert_eq!(trailing, 2);
+  }
+
+  #[test]
+  fn handlebars_across_elements() {
+    let parse = |source_text: &str| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder().handlebars(true).build();
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let mut output = String::new();
+      tree(&result.program, &mut output);
+      let kinds: Vec<_> = result
+        .errors
+        .iter()
+        .filter_map(HtmlParseErrorKind::from_diagnostic)
+        .collect();
+      (output, kinds)
+    };
+
+    assert_eq!(
+      parse("<ul>{{#each xs}}<li>{{this}}{{/each}}</ul>"),
+      (
+        "ul({{each xs}}(li({{this}})))".to_string(),
+        vec![HtmlParseErrorKind::ImplicitlyClosedElement]
+      )
+    );
+    assert_eq!(
+      parse("<ul>{{#each xs}}<li>a{{#if b}}c{{/each}}d</ul>"),
+      (
+        "ul({{each xs}}(li(a{{if b}}(c)))d)".to_string(),
+        vec![
+          HtmlParseErrorKind::ImplicitlyClosedElement,
+          HtmlParseErrorKind::UnclosedBlock
+        ]
+      )
+    );
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder().handlebars(true).build();
+    let result = HtmlParserImpl::new(
+      &allocator,
+      "<ul>{{#each xs}}<li>a{{/each}}</ul>",
+      Cow::Borrowed(&options),
+    )
+    .parse();
+    let Node::Element(ul) = &result.program[0] else {
+      unreachable!()
+    };
+    let Node::Mustache(each) = &ul.children[0] else {
+      unreachable!()
+    };
+    assert_eq!(each.span, Span::new(4, 30));
+    assert_eq!(each.children[0].span(), Span::new(16, 21));
+  }
+
+  #[test]
+  fn handlebars_implied_tags() {
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .handlebars(true)
+      .implied_tags(true)
+      .build();
+    let result = HtmlParserImpl::new(
+      &allocator,
+      "{{#if a}}<p>x</p>{{/if}}",
+      Cow::Borrowed(&options),
+    )
+    .parse();
+    let mut output = String::new();
+    tree(&result.program, &mut output);
+    assert_eq!(output, "html(head()body({{if a}}(p(x))))");
+    assert!(result.errors.is_empty());
+  }
+
+  #[test]
+  fn nested_document_elements() {
+    use crate::error::HtmlParseErrorKind;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder().implied_tags(true).build();
+    for (source_text, expected, attribute) in [
+      (
+        "x<body class=a>y",
+        "html(head()body(xy))",
+        ("body", "class", "a"),
+      ),
+      (
+        "<p>x<html lang=en>y</p>",
+        "html(head()body(p(xy)))",
+        ("html", "lang", "en"),
+      ),
+      (
+        "<html lang=fr><body>x</body></html><html lang=en class=a>",
+        "html(head()body(x))",
+        ("html", "class", "a"),
+      ),
+    ] {
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let mut output = String::new();
+      tree(&result.program, &mut output);
+      assert_eq!(output, expected);
+      let kinds: Vec<_> = result
+        .errors
+        .iter()
+        .map(|error| HtmlParseErrorKind::from_diagnostic(error).unwrap())
+        .collect();
+      assert_eq!(kinds, [HtmlParseErrorKind::NestedDocumentElement]);
+
+      let Node::Element(html) = &result.program[0] else {
+        unreachable!()
+      };
+      let element = if attribute.0 == "html" {
+        html
+      } else {
+        html
+          .children
+          .iter()
+          .find_map(|node| match node {
+            Node::Element(body) if body.name() == "body" => Some(body),
+            _ => None,
+          })
+          .unwrap()
+      };
+      assert_eq!(element.attribute(attribute.1), Some(attribute.2));
+    }
+  }
+
+  #[test]
+  fn handlebars() {
+    use umc_html_ast::MustacheKind;
+
+    const HTML: &str = "<ul>{{#each items}}<li>{{{name}}}</li>{{else}}{{> empty}}{{/each}}</ul>{{!-- {{x}} --}}{{^if a}}b{{/if}}";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder().handlebars(true).build();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty());
+
+    let Node::Element(ul) = &result.program[0] else {
+      unreachable!()
+    };
+    let Node::Mustache(each) = &ul.children[0] else {
+      unreachable!()
+    };
+    assert_eq!(
+      (each.kind, each.expression, each.name()),
+      (MustacheKind::Block, "each items", "each")
+    );
+    assert_eq!(each.span, Span::new(4, HTML.find("</ul>").unwrap() as u32));
+    let Node::Element(li) = &each.children[0] else {
+      unreachable!()
+    };
+    assert!(
+      matches!(&li.children[0], Node::Mustache(name) if name.kind == MustacheKind::Unescaped)
+    );
+    assert!(
+      matches!(&each.inverse[..], [Node::Mustache(partial)] if partial.kind == MustacheKind::Partial && partial.expression == "empty")
+    );
+
+    let kinds: Vec<_> = result.program[1..]
+      .iter()
+      .map(|node| match node {
+        Node::Mustache(mustache) => (mustache.kind, mustache.children.len()),
+        _ => unreachable!(),
+      })
+      .collect();
+    assert_eq!(
+      kinds,
+      [(MustacheKind::Comment, 0), (MustacheKind::InvertedBlock, 1)]
+    );
+
+    // Without the option, tags are text
+    let result = HtmlParserImpl::new(
+      &allocator,
+      "{{#if a}}b{{/if}}",
+      Cow::Owned(HtmlParserOption::default()),
+    )
+    .parse();
+    assert!(matches!(&result.program[..], [Node::Text(_)]));
+
+    for (source_text, code) in [
+      ("{{#if a}}b", HtmlParseErrorKind::UnclosedBlock),
+      ("<p>{{#if a}}b</p>", HtmlParseErrorKind::UnclosedBlock),
+      ("b{{/if}}", HtmlParseErrorKind::OrphanBlockEnd),
+      ("{{name", HtmlParseErrorKind::UnterminatedMustache),
+    ] {
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let codes: Vec<_> = result
+        .errors
+        .iter()
+        .filter_map(HtmlParseErrorKind::from_diagnostic)
+        .collect();
+      assert_eq!(codes, [code], "{source_text}");
+    }
+  }
+
+  #[test]
+  fn template_delimiters() {
+    use crate::option::TemplateDelimiters;
+
+    const HTML: &str = r#"{% if user %}<a {% if x > 1 %}hidden {% endif %} title="{{ "a" }}" href={{ url }} data-{{ key }}=1><%= name %></a>{% endif %}"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .template_delimiters(vec![
+        TemplateDelimiters::STATEMENT,
+        TemplateDelimiters::EXPRESSION,
+        TemplateDelimiters::ERB,
+      ])
+      .build();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty());
+
+    let [
+      Node::TemplateTag(open),
+      Node::Element(a),
+      Node::TemplateTag(close),
+    ] = &result.program[..]
+    else {
+      unreachable!()
+    };
+    assert_eq!(
+      (open.open, open.value, open.close),
+      ("{%", " if user ", "%}")
+    );
+    assert_eq!(close.value, " endif ");
+
+    let attributes: Vec<_> = a
+      .attributes
+      .iter()
+      .map(|attribute| {
+        (
+          attribute.key.value,
+          attribute.value.as_ref().map(|v| v.value),
+        )
+      })
+      .collect();
+    assert_eq!(
+      attributes,
+      [
+        ("{% if x > 1 %}", None),
+        ("hidden", None),
+        ("{% endif %}", None),
+        ("title", Some(r#"{{ "a" }}"#)),
+        ("href", Some("{{ url }}")),
+        ("data-{{ key }}", Some("1")),
+      ]
+    );
+    assert!(
+      matches!(&a.children[..], [Node::TemplateTag(erb)] if erb.open == "<%" && erb.value == "= name ")
+    );
+
+    let result = HtmlParserImpl::new(&allocator, "<p>{% if", Cow::Borrowed(&options)).parse();
+    let codes: Vec<_> = result
+      .errors
+      .iter()
+      .filter_map(HtmlParseErrorKind::from_diagnostic)
+      .collect();
+    assert_eq!(
+      codes,
+      [
+        HtmlParseErrorKind::UnterminatedTemplateTag,
+        HtmlParseErrorKind::UnclosedElement
+      ]
+    );
+    let Node::Element(p) = &result.program[0] else {
+      unreachable!()
+    };
+    assert!(
+      matches!(&p.children[..], [Node::TemplateTag(tag)] if tag.value == " if" && tag.close.is_empty())
+    );
+  }
+
+  #[test]
+  fn html_type() {
+    use crate::{
+      dialect::{DetectDialect, Dialect},
+      option::HtmlType,
+    };
 
-    let ret = JsParser::new(self.allocator, script_content, source_type)
-      .with_options(parse_options)
-      .parse();
+    const XHTML: &str = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd">"#;
+    const BODY: &str = "<Foo></FOO>";
 
-    // Store JavaScript parsing errors in the main parser errors
-    // Adjust error spans to be relative to the HTML source
-    let start_offset = children
-      .iter()
-      .find_map(|node| {
-        if let Node::Text(text) = node {
-          Some(text.span.start)
-        } else {
-          None
-        }
+    let parse = |html_type, source_text: &str| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption {
+        html_type,
+        ..HtmlParserOption::default()
+      };
+      let result = HtmlParserImpl::new(&allocator, source_text, Cow::Borrowed(&options)).parse();
+      let Some(Node::Element(element)) = result.program.last() else {
+        unreachable!()
+      };
+      // XHTML does not close <Foo> at </FOO>, an orphan
+      (
+        result.dialect(),
+        element.name().to_string(),
+        result.errors.len(),
+      )
+    };
+
+    let xhtml = format!("{XHTML}{BODY}");
+    assert_eq!(
+      parse(HtmlType::Auto, &xhtml),
+      (Dialect::Xhtml, "Foo".to_string(), 2)
+    );
+    assert_eq!(
+      parse(HtmlType::Html, &xhtml),
+      (Dialect::Xhtml, "foo".to_string(), 0)
+    );
+    assert_eq!(
+      parse(HtmlType::Xhtml, BODY),
+      (Dialect::Quirks, "Foo".to_string(), 2)
+    );
+    assert_eq!(
+      parse(HtmlType::Auto, &format!("<!doctype html>{BODY}")),
+      (Dialect::Html5, "foo".to_string(), 0)
+    );
+    assert_eq!(
+      parse(HtmlType::Auto, BODY),
+      (Dialect::Quirks, "foo".to_string(), 0)
+    );
+  }
+
+  #[test]
+  fn dialect_spec() {
+    use crate::{
+      dialect::DialectSpec,
+      option::{TagSet, Whitespace},
+    };
+
+    const XML: &str = "<Item><br><code> a </code> <b> </b><![CDATA[<p>]]></item>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::builder()
+      .dialect_spec(DialectSpec {
+        preformatted_tags: TagSet::Custom(&["code"]),
+        ..DialectSpec::XML
       })
-      .unwrap_or(span.start);
+      .whitespace(Whitespace::Skip)
+      .build();
+    let result = HtmlParserImpl::new(&allocator, XML, Cow::Borrowed(&options)).parse();
+    // <br> is not void and </item> does not close <Item>
+    assert_eq!(result.errors.len(), 3);
+    let Node::Element(item) = &result.program[0] else {
+      unreachable!()
+    };
+    assert_eq!(item.name(), "Item");
+    let Node::Element(br) = &item.children[0] else {
+      unreachable!()
+    };
+    let [Node::Element(code), Node::Element(b), Node::Comment(cdata)] = &br.children[..] else {
+      unreachable!()
+    };
+    assert!(matches!(&code.children[..], [Node::Text(text)] if text.value == " a "));
+    assert!(b.children.is_empty());
+    assert_eq!(cdata.value, "[CDATA[<p>]]");
+  }
 
-    for mut error in ret.errors {
-      if let Some(labels) = error.labels.take() {
-        let new_labels = labels
-          .into_iter()
-          .map(|label| {
-            let offset = label.offset() + start_offset as usize;
-            let len = label.len();
-            let msg = label.label().map(ToString::to_string);
-            LabeledSpan::new_with_span(msg, (offset, len))
-          })
-          .collect();
-        error.labels = Some(new_labels);
+  #[test]
+  fn shared_options() {
+    use std::sync::Arc;
+
+    use crate::option::TagSet;
+
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<HtmlParserOption>();
+
+    let options = HtmlParserOption::builder()
+      .void_tags(TagSet::Closure(Arc::new(|name: &str| name == "x-icon")))
+      .build();
+    let parse = |html: &'static str| {
+      let options = &options;
+      move || {
+        let allocator = Allocator::default();
+        let result = HtmlParserImpl::new(&allocator, html, Cow::Borrowed(options)).parse();
+        result.program.len()
       }
-      self.errors.push(error);
+    };
+    let lengths = std::thread::scope(|scope| {
+      let first = scope.spawn(parse("<x-icon><p>a</p>"));
+      let second = scope.spawn(parse("<x-icon>b"));
+      [first.join().unwrap(), second.join().unwrap()]
+    });
+    assert_eq!(lengths, [2, 2]);
+
+    let cloned = options.clone();
+    assert!(cloned.dialect_spec.void_tags.contains("x-icon"));
+    assert!(options.dialect_spec.void_tags.contains("x-icon"));
+    assert!(format!("{cloned:?}").contains("void_tags: Closure(..)"));
+  }
+
+  #[test]
+  fn reusable_allocator() {
+    use umc_parser::allocator::ReusableAllocator;
+
+    let mut allocator = ReusableAllocator::with_capacity(1024);
+    let options = HtmlParserOption::default();
+    let mut lengths = Vec::new();
+    for html in [
+      "<p>a</p>",
+      "<ul><li>a<li>b</ul>",
+      "<p>a</p><p>b</p><p>c</p>",
+    ] {
+      let result = allocator.parse::<Html>(html, &options);
+      lengths.push(result.program.len());
     }
+    assert_eq!(lengths, [1, 1, 3]);
+    assert!(allocator.capacity() >= 1024);
 
-    let script = Script {
-      span,
-      tag_name,
-      attributes,
-      program: ret.program,
+    let reset = allocator.reset();
+    assert_eq!(reset.used_bytes(), 0);
+  }
+
+  #[test]
+  fn dyn_parser() {
+    use umc_parser::dynamic::{DynLanguageParser, DynParser};
+
+    let parsers: Vec<std::boxed::Box<dyn DynLanguageParser>> = vec![
+      std::boxed::Box::new(DynParser::<Html>::default()),
+      std::boxed::Box::new(DynParser::<Html>::new(
+        HtmlParserOption::builder().preserve_comments(false).build(),
+      )),
+    ];
+
+    let allocator = Allocator::default();
+    let lengths: Vec<_> = parsers
+      .iter()
+      .map(|parser| {
+        let result = parser.parse(&allocator, "<p>a</p><!-- b -->");
+        assert!(result.program.is::<Html>());
+        result.program.downcast_ref::<Html>().unwrap().len()
+      })
+      .collect();
+    assert_eq!(lengths, [2, 1]);
+    assert_eq!(parsers[0].mime_types(), ["text/html"]);
+  }
+
+  #[test]
+  fn registry() {
+    use umc_parser::registry::LanguageRegistry;
+
+    use crate::option::TagSet;
+
+    let registry = LanguageRegistry::new().with::<Html>().with_options::<Html>(
+      HtmlParserOption::builder()
+        .void_tags(TagSet::Custom(&["x-icon"]))
+        .build(),
+    );
+    assert!(registry.for_extension("md").is_none());
+    assert!(registry.for_name("html").is_some());
+    assert!(registry.for_mime_type("Text/HTML; charset=utf-8").is_some());
+    let language = registry.for_path("pages/Index.HTM".as_ref()).unwrap();
+    assert_eq!(registry.languages().count(), 2);
+    assert_eq!(language.extensions(), ["html", "htm"]);
+
+    let allocator = Allocator::default();
+    let result = language.parse(&allocator, "<x-icon><p>a</p>");
+    assert_eq!(result.program.language_name(), "html");
+    let program = result.program.downcast_ref::<Html>().unwrap();
+    // the options registered last are used
+    assert_eq!(program.len(), 2);
+    assert_eq!(
+      result
+        .program
+        .downcast::<Html>()
+        .ok()
+        .map(|program| program.len()),
+      Some(2)
+    );
+  }
+
+  #[test]
+  fn budget() {
+    use crate::budget::{BudgetExceeded, ParseBudget};
+
+    const HTML: &str = "<div><p>One</p><p>Two <b>bold</b></p></i></span></div>";
+
+    let exceeded = |budget: ParseBudget| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption {
+        budget,
+        ..HtmlParserOption::default()
+      };
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      let error = result.errors.last().unwrap();
+      format!(
+        "{:?} at {:?}, {} errors, {} top level nodes",
+        BudgetExceeded::from_diagnostic(error),
+        error.labels.as_ref().unwrap()[0].offset(),
+        result.errors.len(),
+        result.program.len(),
+      )
     };
 
-    let script = Box::new_in(script, self.allocator);
+    assert_snapshot!(
+      [
+        exceeded(ParseBudget {
+          max_bytes: Some(20),
+          ..ParseBudget::default()
+        }),
+        exceeded(ParseBudget {
+          max_nodes: Some(4),
+          ..ParseBudget::default()
+        }),
+        exceeded(ParseBudget {
+          max_depth: Some(2),
+          ..ParseBudget::default()
+        }),
+        exceeded(ParseBudget {
+          max_errors: Some(1),
+          ..ParseBudget::default()
+        }),
+        exceeded(ParseBudget::default()),
+      ]
+      .join("\n")
+    );
+  }
 
-    if let Some(parent) = element_stack.last_mut() {
-      parent.children.push(Node::Script(script));
-    } else {
-      nodes.push(Node::Script(script));
+  #[test]
+  fn error_kinds() {
+    use crate::error::HtmlParseErrorKind;
+
+    const HTML: &str = r#"<div><p></div></span><script>let a =;</script><a title="x"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let kinds: Vec<_> = result
+      .errors
+      .iter()
+      .map(|error| HtmlParseErrorKind::from_diagnostic(error).unwrap())
+      .collect();
+
+    assert_eq!(
+      kinds,
+      [
+        HtmlParseErrorKind::ImplicitlyClosedElement,
+        HtmlParseErrorKind::OrphanEndTag,
+        HtmlParseErrorKind::ScriptSyntax,
+        // errors of the lexer are reported as the tokens are read
+        HtmlParseErrorKind::UnterminatedAttributeValue,
+        HtmlParseErrorKind::UnclosedElement,
+      ]
+    );
+    for kind in HtmlParseErrorKind::ALL {
+      assert_eq!(HtmlParseErrorKind::from_code(kind.code()), Some(kind));
     }
   }
-}
 
-#[cfg(test)]
-mod test {
-  use super::*;
-  use insta::assert_snapshot;
+  #[test]
+  fn related_spans() {
+    const HTML: &str = "<section><div></section></span>";
 
-  fn parse(source_text: &str) -> String {
     let allocator = Allocator::default();
     let options = HtmlParserOption::default();
-    let parser = HtmlParserImpl::new(&allocator, source_text, &options);
-    let result = parser.parse();
-
-    format!("Nodes: {:#?}\nErrors: {:#?}", result.program, result.errors)
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let labels: Vec<Vec<_>> = result
+      .errors
+      .iter()
+      .map(|error| {
+        error
+          .labels
+          .iter()
+          .flatten()
+          .map(|label| {
+            let start = label.offset();
+            let text = &HTML[start..start + label.len()];
+            (text, label.label().unwrap_or_default().to_string())
+          })
+          .collect()
+      })
+      .collect();
+
+    assert_eq!(
+      labels,
+      [
+        vec![
+          ("<div>", "opened here".to_string()),
+          (
+            "</section>",
+            "expected </div> before this </section>".to_string()
+          ),
+        ],
+        vec![("</span>", "no matching opening tag".to_string())],
+      ]
+    );
   }
 
   #[test]
-  fn basic_html() {
-    const HTML: &str = r#"<!DOCTYPE html>
-<html lang="en">
-<head>
-  <meta charset="UTF-8">
-  <title>Document</title>
-</head>
-<body>
-  <p>Hello World</p>
-</body>
-</html>"#;
+  fn strict() {
+    use crate::error::{ErrorLevel, ErrorLevels, HtmlParseErrorKind};
 
-    assert_snapshot!(parse(HTML));
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      strict: true,
+      ..HtmlParserOption::default()
+    };
+    let parse = |html| HtmlParserImpl::new(&allocator, html, Cow::Borrowed(&options)).parse();
+
+    let errors = parse("<div><p></div></span><a title=\"x")
+      .into_result()
+      .unwrap_err();
+    let kinds: Vec<_> = errors
+      .iter()
+      .filter_map(HtmlParseErrorKind::from_diagnostic)
+      .collect();
+    assert_eq!(kinds, [HtmlParseErrorKind::ImplicitlyClosedElement]);
+
+    // errors of the lexer are known after parsing, but still come after in source order
+    let errors = parse("</span><!-- a").into_result().unwrap_err();
+    let kinds: Vec<_> = errors
+      .iter()
+      .filter_map(HtmlParseErrorKind::from_diagnostic)
+      .collect();
+    assert_eq!(kinds, [HtmlParseErrorKind::OrphanEndTag]);
+    let errors = parse("<!-- a").into_result().unwrap_err();
+    assert_eq!(errors.len(), 1);
+
+    assert_eq!(parse("<div><p></p></div>").into_result().unwrap().len(), 1);
+
+    let options = HtmlParserOption {
+      strict: true,
+      errors: ErrorLevels::default().with(
+        HtmlParseErrorKind::ImplicitlyClosedElement,
+        ErrorLevel::Warn,
+      ),
+      ..HtmlParserOption::default()
+    };
+    let result =
+      HtmlParserImpl::new(&allocator, "<div><p></div></span>", Cow::Borrowed(&options)).parse();
+    assert_eq!(result.errors.len(), 2);
+    assert!(result.into_result().is_err());
   }
 
   #[test]
-  fn nested_elements() {
-    const HTML: &str = r"<div>
-  <p>Paragraph 1</p>
-  <p>Paragraph 2</p>
-</div>";
+  fn error_levels() {
+    use oxc_diagnostics::Severity;
+
+    use crate::error::{ErrorLevel, ErrorLevels, HtmlParseErrorKind};
+
+    const HTML: &str = "<ul><li>One</ul></span><div>";
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      errors: ErrorLevels::default()
+        .with(
+          HtmlParseErrorKind::ImplicitlyClosedElement,
+          ErrorLevel::Warn,
+        )
+        .with(HtmlParseErrorKind::OrphanEndTag, ErrorLevel::Off),
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let errors: Vec<_> = result
+      .errors
+      .iter()
+      .map(|error| {
+        (
+          HtmlParseErrorKind::from_diagnostic(error).unwrap(),
+          error.severity,
+        )
+      })
+      .collect();
+
+    assert_eq!(
+      errors,
+      [
+        (
+          HtmlParseErrorKind::ImplicitlyClosedElement,
+          Severity::Warning
+        ),
+        (HtmlParseErrorKind::UnclosedElement, Severity::Error),
+      ]
+    );
+    assert!(ErrorLevels::from_codes([("orphan-end-tag", "loud")]).is_err());
+    assert!(ErrorLevels::from_codes([("unknown", "off")]).is_err());
+  }
 
+  #[test]
+  fn script_parsing() {
+    const HTML: &str = r"<script>
+      const a = 1;
+      function b() { return a + 2; }
+    </script>";
     assert_snapshot!(parse(HTML));
   }
 
   #[test]
-  fn self_closing_elements() {
-    const HTML: &str = r#"<div>
-  <br>
-  <img src="test.jpg" alt="Test">
-  <input type="text" />
-</div>"#;
+  fn mixed_case_closing_tags() {
+    const HTML: &str = "<Script>let a = '</scripts>';</SCRIPT\n><p>b</p><style>c</Style>";
 
-    assert_snapshot!(parse(HTML));
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.program.len(), 3);
+    let Node::Script(script) = &result.program[0] else {
+      panic!("expected a script");
+    };
+    assert_eq!(script.program.source_text, "let a = '</scripts>';");
   }
 
   #[test]
-  fn comments() {
-    const HTML: &str = r"<!-- This is a comment -->
-<div>Content</div>
-<!-- Another comment -->
-<! This is a bogus comment >
-<!Bogus Comment Too>
-";
+  fn script_escapes() {
+    const HTML: &str = "<script><!-- document.write('<script></script>') --></script><p>a</p>";
 
-    assert_snapshot!(parse(HTML));
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      parse_script: None,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert_eq!(result.program.len(), 2);
+    let Node::Element(script) = &result.program[0] else {
+      panic!("expected an element");
+    };
+    let Node::Text(text) = &script.children[0] else {
+      panic!("expected a text");
+    };
+    assert_eq!(text.value, "<!-- document.write('<script></script>') -->");
   }
 
   #[test]
-  fn attribute_with_whitespaces() {
-    const HTML: &str = r#"<div class = "test" a= "b">Content</div>"#;
+  fn metrics() {
+    const HTML: &str =
+      "<!DOCTYPE html><div><!--[if IE]><p>IE</p><![endif]--><script>let a = 1;</script></div>";
 
-    assert_snapshot!(parse(HTML));
+    let allocator = Allocator::default();
+    let options = HtmlParserOption::default();
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.metrics.is_none());
+
+    let options = HtmlParserOption {
+      parse_conditional_comments: true,
+      collect_metrics: true,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let metrics = result.metrics.unwrap();
+    assert_eq!(metrics.bytes, HTML.len());
+    assert_eq!(metrics.tokens, 25);
+    assert_eq!(
+      metrics.nodes.into_iter().collect::<Vec<_>>(),
+      [
+        ("comment", 1),
+        ("doctype", 1),
+        ("element", 2),
+        ("script", 1),
+        ("text", 1)
+      ]
+    );
+    assert!(metrics.arena_bytes > 0);
+    assert!(metrics.embedded_duration <= metrics.duration);
   }
 
   #[test]
-  fn multiple_no_value_attributes() {
-    const HTML: &str = r"<input checked disabled readonly>";
+  fn lazy_scripts() {
+    const HTML: &str = "<p>a</p><script>let a = 1;</script><script>a =;</script>";
 
-    assert_snapshot!(parse(HTML));
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      lazy_scripts: true,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    assert!(result.errors.is_empty());
+    let Node::Script(script) = &result.program[1] else {
+      panic!("expected a script");
+    };
+    let lazy = script.lazy.as_ref().unwrap();
+    assert!(!lazy.is_parsed());
+    assert_eq!(script.program.source_text, "let a = 1;");
+    assert!(script.program.body.is_empty());
+
+    assert_eq!(script.program().body.len(), 1);
+    assert!(lazy.is_parsed());
+    assert!(lazy.errors().is_empty());
+
+    let Node::Script(script) = &result.program[2] else {
+      panic!("expected a script");
+    };
+    let errors = script.lazy.as_ref().unwrap().errors();
+    let labels = errors[0].labels.as_ref().unwrap();
+    assert_eq!(labels[0].offset(), HTML.rfind(';').unwrap());
   }
 
   #[test]
-  fn void_elements() {
-    const HTML: &str = r#"<div>
-  <br>
-  <img src="test.jpg" alt="Test">
-  <input type="text">
-  <meta>Text</meta> <!-- Should Error -->
-</div>"#;
+  fn resolve_script() {
+    const HTML: &str =
+      r#"<script src="a.js"></script><script src="bad.js"></script><script src="b.js">b</script>"#;
 
-    assert_snapshot!(parse(HTML));
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      resolve_script: Some(ScriptResolver::new(|src| match src {
+        "a.js" => Some("let a = 1;".into()),
+        "bad.js" => Some(String::from("a =;").into()),
+        _ => None,
+      })),
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let scripts: Vec<_> = result
+      .program
+      .iter()
+      .filter_map(|node| {
+        let Node::Script(script) = node else {
+          return None;
+        };
+        Some((script.synthetic, script.program.source_text))
+      })
+      .collect();
+    assert_eq!(scripts, [(true, "let a = 1;"), (true, "a =;")]);
+    assert!(matches!(result.program[2], Node::Element(_)));
+
+    assert_eq!(result.errors.len(), 1);
+    let labels = result.errors[0].labels.as_ref().unwrap();
+    assert_eq!(labels[0].offset(), HTML.find("\"bad.js\"").unwrap());
   }
 
   #[test]
-  fn self_close_tags() {
-    const HTML: &str = r"<div/><p />";
+  fn script_kinds() {
+    use umc_html_ast::{ScriptInfo, ScriptKind};
 
-    assert_snapshot!(parse(HTML));
+    const HTML: &str = r#"<script>a</script><script type="text/x-javascript" nomodule>b</script><script type=" Module " async defer>c</script><script type="importmap">{}</script><script type="speculationrules">{}</script><script type="text/x-template"><p></script><script type>d</script>"#;
+
+    let allocator = Allocator::default();
+    let result =
+      HtmlParserImpl::new(&allocator, HTML, Cow::Owned(HtmlParserOption::default())).parse();
+    assert!(result.errors.is_empty());
+    let infos: Vec<_> = result
+      .program
+      .iter()
+      .map(|node| match node {
+        Node::Script(script) => (true, script.info),
+        Node::Element(element) => (false, element.script_info().unwrap()),
+        _ => unreachable!(),
+      })
+      .collect();
+    let info = |kind, no_module, is_async, defer| ScriptInfo {
+      kind,
+      no_module,
+      is_async,
+      defer,
+      external: false,
+    };
+    assert_eq!(
+      infos,
+      [
+        (true, info(ScriptKind::Classic, false, false, false)),
+        (true, info(ScriptKind::Classic, true, false, false)),
+        (true, info(ScriptKind::Module, false, true, true)),
+        (false, info(ScriptKind::ImportMap, false, false, false)),
+        (
+          false,
+          info(ScriptKind::SpeculationRules, false, false, false)
+        ),
+        (
+          false,
+          info(
+            ScriptKind::DataBlock("text/x-template"),
+            false,
+            false,
+            false
+          )
+        ),
+        (true, info(ScriptKind::Classic, false, false, false)),
+      ]
+    );
   }
 
   #[test]
-  fn no_closing_tag() {
-    const HTML: &str = r"<div>
-  <p>Unclosed paragraph
-</div>";
+  fn script_source_types() {
+    use oxc_span::SourceType;
+
+    const HTML: &str = r#"<script>with (a) {}</script><script type="module">import "a";</script><script lang="ts">let a: number;</script><script type="text/typescript">let b: string;</script>"#;
+
+    let parse = |typescript_scripts| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption::builder()
+        .typescript_scripts(typescript_scripts)
+        .build();
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      // the type annotation in JavaScript
+      assert_eq!(result.errors.len(), usize::from(!typescript_scripts));
+      result
+        .program
+        .iter()
+        .map(|node| match node {
+          Node::Script(script) => Some(script.source_type),
+          _ => None,
+        })
+        .collect::<Vec<_>>()
+    };
 
-    assert_snapshot!(parse(HTML));
+    assert_eq!(
+      parse(false),
+      [
+        Some(SourceType::cjs()),
+        Some(SourceType::mjs()),
+        Some(SourceType::cjs()),
+        None
+      ]
+    );
+    assert_eq!(
+      parse(true),
+      [
+        Some(SourceType::cjs()),
+        Some(SourceType::mjs()),
+        Some(SourceType::cjs().with_typescript(true)),
+        Some(SourceType::cjs().with_typescript(true)),
+      ]
+    );
   }
 
   #[test]
-  fn orphan_closing_tag() {
-    const HTML: &str = r"<div>Content</div>
-</span>
-<p>More content</p>";
+  fn module_scripts() {
+    const HTML: &str = r#"<script type="importmap">
+{ "imports": { "vue": "/vendor/vue.js", "lib/": "/vendor/lib/" } }
+</script>
+<script type="module">
+import { ref } from "vue";
+import "lib/a.js";
+export * as b from "./b.js";
+export const { c, d } = {};
+export function e() {}
+export default 1;
+</script>
+<script>import "vue";</script>"#;
+
+    for parallel_scripts in [false, true] {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption {
+        parallel_scripts,
+        ..HtmlParserOption::default()
+      };
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      let scripts: Vec<_> = result
+        .program
+        .iter()
+        .filter_map(|node| match node {
+          Node::Script(script) => Some(script),
+          _ => None,
+        })
+        .collect();
+      assert_eq!(scripts.len(), 2);
+      assert!(scripts[1].module.is_none());
 
-    assert_snapshot!(parse(HTML));
+      let module = scripts[0].module.as_ref().unwrap();
+      let imports: Vec<_> = module
+        .imports
+        .iter()
+        .map(|import| (import.specifier, import.resolved))
+        .collect();
+      assert_eq!(
+        imports,
+        [
+          ("vue", Some("/vendor/vue.js")),
+          ("lib/a.js", Some("/vendor/lib/a.js")),
+          ("./b.js", None)
+        ]
+      );
+      let span = module.imports[0].span;
+      assert_eq!(&HTML[span.start as usize..span.end as usize], "\"vue\"");
+
+      let exports: Vec<_> = module.exports.iter().map(|export| export.name).collect();
+      assert_eq!(exports, ["b", "c", "d", "e", "default"]);
+      let span = module.exports[3].span;
+      assert_eq!(&HTML[span.start as usize..span.end as usize], "e");
+    }
   }
 
   #[test]
-  fn incomplete_attribute() {
-    const HTML: &str = r"<div class=></div>";
-    assert_snapshot!(parse(HTML));
+  fn style_attributes() {
+    const HTML: &str =
+      r#"<p style="color:red;width:100% !important" data-style="a:b"></p><i style=top:0>"#;
+
+    let allocator = Allocator::default();
+    let options = HtmlParserOption {
+      parse_style_attributes: true,
+      ..HtmlParserOption::default()
+    };
+    let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+    let Node::Element(p) = &result.program[0] else {
+      panic!("expected an element");
+    };
+    assert!(p.attributes[1].style.is_none());
+    let style = p.attributes[0].style.as_ref().unwrap();
+    assert_eq!(style.len(), 2);
+    assert!(style[1].important);
+    let span = style[1].value_span;
+    assert_eq!(&HTML[span.start as usize..span.end as usize], "100%");
+
+    let Node::Element(i) = &result.program[1] else {
+      panic!("expected an element");
+    };
+    let span = i.attributes[0].style.as_ref().unwrap()[0].span;
+    assert_eq!(&HTML[span.start as usize..span.end as usize], "top:0");
   }
 
   #[test]
-  fn script_parsing() {
-    const HTML: &str = r"<script>
-      const a = 1;
-      function b() { return a + 2; }
-    </script>";
-    assert_snapshot!(parse(HTML));
+  fn parallel_scripts() {
+    const HTML: &str = "<script>let a = 1;</script><div><script>function f() { return a }</script>\
+      <!--[if IE]><script>const b;</script><![endif]--></div><script>a =;</script>";
+
+    let debug = |parallel_scripts| {
+      let allocator = Allocator::default();
+      let options = HtmlParserOption {
+        parse_conditional_comments: true,
+        parallel_scripts,
+        ..HtmlParserOption::default()
+      };
+      let result = HtmlParserImpl::new(&allocator, HTML, Cow::Borrowed(&options)).parse();
+      assert_eq!(result.errors.len(), 2);
+      format!("{:#?}\n{:#?}", result.program, result.errors)
+    };
+    assert_eq!(debug(true), debug(false));
   }
 
   #[test]
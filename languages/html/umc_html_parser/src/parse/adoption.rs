@@ -0,0 +1,534 @@
+//! The [adoption agency algorithm](https://html.spec.whatwg.org/multipage/parsing.html#adoption-agency-algorithm)
+//! for misnested formatting elements, see
+//! [`adoption_agency`](crate::option::HtmlParserOption::adoption_agency).
+//!
+//! The list of active formatting elements of the specification is kept as the formatting
+//! elements on the element stack, and the pending ones: closed before their closing tag,
+//! and reopened before the next text or element.
+
+use oxc_allocator::Vec as ArenaVec;
+use umc_html_ast::{Attribute, AttributeKey, AttributeValue, Node, StyleDeclaration};
+use umc_span::Span;
+
+use crate::{error::HtmlParseErrorKind, trace::trace};
+
+use super::{ElementBuilder, HtmlParserImpl, is_in};
+
+/// Elements whose misnested closing tags are handled by the algorithm.
+const FORMATTING: &[&str] = &[
+  "a", "b", "big", "code", "em", "font", "i", "nobr", "s", "small", "strike", "strong", "tt", "u",
+];
+
+/// Formatting elements an opening tag of closes the one still open first, as in
+/// `<a href=x>1<a href=y>2</a>`.
+const NOT_NESTED: &[&str] = &["a", "nobr"];
+
+/// Elements of the special category, which the algorithm moves out of formatting elements
+/// instead of closing them.
+const SPECIAL: &[&str] = &[
+  "address",
+  "applet",
+  "area",
+  "article",
+  "aside",
+  "base",
+  "basefont",
+  "bgsound",
+  "blockquote",
+  "body",
+  "br",
+  "button",
+  "caption",
+  "center",
+  "col",
+  "colgroup",
+  "dd",
+  "details",
+  "dir",
+  "div",
+  "dl",
+  "dt",
+  "embed",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "frame",
+  "frameset",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "head",
+  "header",
+  "hgroup",
+  "hr",
+  "html",
+  "iframe",
+  "img",
+  "input",
+  "keygen",
+  "li",
+  "link",
+  "listing",
+  "main",
+  "marquee",
+  "menu",
+  "meta",
+  "nav",
+  "noembed",
+  "noframes",
+  "noscript",
+  "object",
+  "ol",
+  "p",
+  "param",
+  "plaintext",
+  "pre",
+  "script",
+  "search",
+  "section",
+  "select",
+  "source",
+  "style",
+  "summary",
+  "table",
+  "tbody",
+  "td",
+  "template",
+  "textarea",
+  "tfoot",
+  "th",
+  "thead",
+  "title",
+  "tr",
+  "track",
+  "ul",
+  "wbr",
+  "xmp",
+];
+
+/// Elements formatting elements opened outside of are not reopened in, the markers of the
+/// list of active formatting elements.
+const MARKERS: &[&str] = &[
+  "applet", "caption", "marquee", "object", "td", "th", "template",
+];
+
+/// Elements a closing tag of a formatting element opened outside of is ignored in.
+const SCOPE: &[&str] = &[
+  "applet", "caption", "html", "marquee", "object", "table", "td", "th", "template",
+];
+
+/// Opening tags pending formatting elements are not reopened before, as block and head
+/// elements are.
+const NOT_REOPENING: &[&str] = &[
+  "address",
+  "article",
+  "aside",
+  "base",
+  "basefont",
+  "bgsound",
+  "blockquote",
+  "body",
+  "caption",
+  "center",
+  "col",
+  "colgroup",
+  "dd",
+  "details",
+  "dialog",
+  "dir",
+  "div",
+  "dl",
+  "dt",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "frameset",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "head",
+  "header",
+  "hgroup",
+  "hr",
+  "html",
+  "iframe",
+  "li",
+  "link",
+  "listing",
+  "main",
+  "menu",
+  "meta",
+  "nav",
+  "noembed",
+  "noframes",
+  "ol",
+  "p",
+  "param",
+  "plaintext",
+  "pre",
+  "script",
+  "search",
+  "section",
+  "source",
+  "style",
+  "summary",
+  "table",
+  "tbody",
+  "td",
+  "template",
+  "textarea",
+  "tfoot",
+  "th",
+  "thead",
+  "title",
+  "tr",
+  "track",
+  "ul",
+];
+
+/// Formatting elements further from the furthest block are closed without being reopened.
+const MAX_REOPENED: usize = 3;
+
+/// Rounds of the outer loop of the algorithm.
+const MAX_ROUNDS: usize = 8;
+
+/// Formatting elements with the same name and attributes reopened at most, the last ones,
+/// as the "Noah's Ark" clause of the specification keeps.
+const MAX_IDENTICAL: usize = 3;
+
+impl<'a> HtmlParserImpl<'a> {
+  /// Handle the closing tag of a formatting element with elements still open in it,
+  /// returning whether it was handled. Other closing tags are handled by
+  /// [`parse_closing_tag`](Self::parse_closing_tag).
+  #[allow(clippy::too_many_lines)]
+  pub(super) fn adopt(
+    &mut self,
+    tag_name: &str,
+    close_tag_span: Span,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut Vec<ElementBuilder<'a>>,
+  ) -> bool {
+    if !is_in(FORMATTING, tag_name) {
+      return false;
+    }
+    let Some(index) = element_stack
+      .iter()
+      .rposition(|builder| self.names_match(builder.tag_name, tag_name))
+    else {
+      // already closed, e.g. `</b>` in `<p><b></p></b>`
+      let pending = self
+        .pending_formatting
+        .iter()
+        .rposition(|builder| self.names_match(builder.tag_name, tag_name));
+      if let Some(pending) = pending {
+        self.pending_formatting.remove(pending);
+      }
+      return pending.is_some();
+    };
+    if index + 1 == element_stack.len() {
+      return false;
+    }
+
+    if let Some(boundary) = element_stack[index + 1..]
+      .iter()
+      .rfind(|builder| is_in(SCOPE, builder.tag_name))
+    {
+      trace!(tag = tag_name, "ignore closing tag out of scope");
+      let error = HtmlParseErrorKind::MisnestedFormattingElement
+        .error(format!("Misnested closing tag: </{tag_name}>"))
+        .with_labels([
+          close_tag_span.primary_label("ignored"),
+          Span::new(boundary.start, boundary.tag_end)
+            .label(format!("<{}> is still open", boundary.tag_name)),
+        ])
+        .with_help(format!(
+          "Close <{}> before </{tag_name}>",
+          boundary.tag_name
+        ));
+      self.report(error);
+      return true;
+    }
+
+    let current = element_stack.last().unwrap();
+    let error = HtmlParseErrorKind::MisnestedFormattingElement
+      .error(format!("Misnested closing tag: </{tag_name}>"))
+      .with_labels([
+        close_tag_span.primary_label(format!("closes <{tag_name}> and the elements in it")),
+        Span::new(current.start, current.tag_end)
+          .label(format!("<{}> is still open", current.tag_name)),
+      ])
+      .with_help(format!("Close <{}> before </{tag_name}>", current.tag_name));
+    self.report(error);
+
+    self.run_adoption(index, Some(close_tag_span.end), nodes, element_stack);
+    true
+  }
+
+  /// Handle an opening `<a>` or `<nobr>` tag, spanning `tag_span` up to its name, while the
+  /// same formatting element is still open, closing it as its closing tag would.
+  pub(super) fn adopt_opening(
+    &mut self,
+    tag_name: &str,
+    tag_span: Span,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut Vec<ElementBuilder<'a>>,
+  ) {
+    if !is_in(NOT_NESTED, tag_name) {
+      return;
+    }
+    let marker = element_stack
+      .iter()
+      .rposition(|builder| is_in(MARKERS, builder.tag_name));
+    let index = element_stack[marker.map_or(0, |marker| marker + 1)..]
+      .iter()
+      .rposition(|builder| self.names_match(builder.tag_name, tag_name))
+      .map(|index| index + marker.map_or(0, |marker| marker + 1));
+    let open = if let Some(index) = index {
+      if element_stack[index + 1..]
+        .iter()
+        .any(|builder| is_in(SCOPE, builder.tag_name))
+      {
+        return;
+      }
+      Span::new(element_stack[index].start, element_stack[index].tag_end)
+    } else {
+      let marker = marker.map_or(0, |marker| element_stack[marker].start);
+      let Some(pending) = self.pending_formatting.iter().rposition(|builder| {
+        builder.start >= marker && self.names_match(builder.tag_name, tag_name)
+      }) else {
+        return;
+      };
+      // closed before its closing tag, e.g. the first `<a>` in `<p><a></p><a>`
+      let builder = self.pending_formatting.remove(pending);
+      Span::new(builder.start, builder.tag_end)
+    };
+
+    let error = HtmlParseErrorKind::MisnestedFormattingElement
+      .error(format!("Nested formatting element: <{tag_name}>"))
+      .with_labels([
+        tag_span.primary_label(format!("closes the open <{tag_name}>")),
+        open.label(format!("<{tag_name}> is still open")),
+      ])
+      .with_help(format!("Close <{tag_name}> before opening another one"));
+    self.report(error);
+
+    let Some(index) = index else {
+      return;
+    };
+    if index + 1 == element_stack.len() {
+      let builder = element_stack.pop().unwrap();
+      self.close_builder(builder, None, nodes, element_stack);
+    } else {
+      self.run_adoption(index, None, nodes, element_stack);
+    }
+  }
+
+  /// Close the formatting element at `index` of the stack, at `end` or after its content,
+  /// moving the special elements opened in it out of it.
+  fn run_adoption(
+    &mut self,
+    mut index: usize,
+    end: Option<u32>,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut Vec<ElementBuilder<'a>>,
+  ) {
+    for _ in 0..MAX_ROUNDS {
+      let Some(furthest) = element_stack[index + 1..]
+        .iter()
+        .position(|builder| is_in(SPECIAL, builder.tag_name))
+      else {
+        trace!(
+          tag = element_stack[index].tag_name,
+          "close formatting element"
+        );
+        // the formatting elements in it are reopened after it
+        let pending = self.pending_formatting.len();
+        while element_stack.len() > index + 1 {
+          let builder = element_stack.pop().unwrap();
+          if is_in(FORMATTING, builder.tag_name) {
+            let copy = self.copy_builder(&builder);
+            self.pending_formatting.insert(pending, copy);
+          }
+          self.close_builder(builder, None, nodes, element_stack);
+        }
+        let builder = element_stack.pop().unwrap();
+        self.close_builder(builder, end, nodes, element_stack);
+        return;
+      };
+      let furthest = index + 1 + furthest;
+
+      trace!(
+        tag = element_stack[index].tag_name,
+        block = element_stack[furthest].tag_name,
+        "adopt furthest block"
+      );
+      let above = element_stack.split_off(furthest + 1);
+      let mut block = element_stack.pop().unwrap();
+
+      // the formatting elements the furthest block is in are reopened around it
+      let first = element_stack
+        .len()
+        .saturating_sub(MAX_REOPENED)
+        .max(index + 1);
+      let mut reopened = Vec::new();
+      for builder in &element_stack[first..] {
+        if is_in(FORMATTING, builder.tag_name) {
+          let mut copy = self.copy_builder(builder);
+          copy.start = block.start;
+          copy.tag_end = block.start;
+          reopened.push(copy);
+        }
+      }
+
+      // and the formatting element around its content
+      let mut copy = self.copy_builder(&element_stack[index]);
+      copy.start = block.tag_end;
+      copy.tag_end = block.tag_end;
+      copy.children = std::mem::replace(&mut block.children, ArenaVec::new_in(self.allocator));
+
+      while element_stack.len() > index {
+        let builder = element_stack.pop().unwrap();
+        self.close_builder(builder, None, nodes, element_stack);
+      }
+
+      for _ in 0..=reopened.len() {
+        self.charge_node(block.start);
+      }
+      element_stack.extend(reopened);
+      element_stack.push(block);
+      index = element_stack.len();
+      element_stack.push(copy);
+      element_stack.extend(above);
+    }
+  }
+
+  /// Reopen the pending formatting elements before a text, or an opening tag with
+  /// `tag_name`, at `offset`.
+  pub(super) fn reopen_formatting(
+    &mut self,
+    tag_name: Option<&str>,
+    offset: u32,
+    element_stack: &mut Vec<ElementBuilder<'a>>,
+  ) {
+    if self.pending_formatting.is_empty()
+      || tag_name.is_some_and(|tag_name| is_in(NOT_REOPENING, tag_name))
+    {
+      return;
+    }
+    let marker = element_stack
+      .iter()
+      .rfind(|builder| is_in(MARKERS, builder.tag_name))
+      .map_or(0, |builder| builder.start);
+    let pending = std::mem::take(&mut self.pending_formatting);
+    let mut reopened: Vec<&ElementBuilder<'a>> = Vec::new();
+    let mut dropped = vec![false; pending.len()];
+    for (index, builder) in pending.iter().enumerate().rev() {
+      if builder.start < marker {
+        continue;
+      }
+      let identical = reopened
+        .iter()
+        .filter(|other| self.same_formatting(builder, other))
+        .count();
+      if identical < MAX_IDENTICAL {
+        reopened.push(builder);
+      } else {
+        dropped[index] = true;
+      }
+    }
+    for (mut builder, dropped) in pending.into_iter().zip(dropped) {
+      if dropped {
+        trace!(tag = builder.tag_name, "drop identical formatting element");
+        continue;
+      }
+      if builder.start < marker {
+        self.pending_formatting.push(builder);
+        continue;
+      }
+      trace!(tag = builder.tag_name, "reopen formatting element");
+      self.charge_node(offset);
+      builder.start = offset;
+      builder.tag_end = offset;
+      element_stack.push(builder);
+    }
+  }
+
+  /// Keep track of an element closed by a closing tag: the formatting elements closed
+  /// before their own closing tag are reopened later, at the position `pending` of the
+  /// list, and the ones opened in a closed marker are not.
+  pub(super) fn close_formatting(
+    &mut self,
+    builder: &ElementBuilder<'a>,
+    implicit: bool,
+    pending: usize,
+  ) {
+    if is_in(MARKERS, builder.tag_name) {
+      self
+        .pending_formatting
+        .retain(|pending| pending.start < builder.start);
+    } else if implicit && is_in(FORMATTING, builder.tag_name) {
+      let copy = self.copy_builder(builder);
+      let pending = pending.min(self.pending_formatting.len());
+      self.pending_formatting.insert(pending, copy);
+    }
+  }
+
+  /// Whether two formatting elements have the same name and attributes.
+  fn same_formatting(&self, a: &ElementBuilder<'a>, b: &ElementBuilder<'a>) -> bool {
+    self.names_match(a.tag_name, b.tag_name)
+      && a.attributes.len() == b.attributes.len()
+      && a.attributes.iter().all(|attribute| {
+        b.attributes.iter().any(|other| {
+          attribute.key.normalized == other.key.normalized
+            && attribute.value.as_ref().map(|value| value.value)
+              == other.value.as_ref().map(|value| value.value)
+        })
+      })
+  }
+
+  /// Copy an element without its children, to reopen it.
+  fn copy_builder(&self, builder: &ElementBuilder<'a>) -> ElementBuilder<'a> {
+    let mut attributes = ArenaVec::with_capacity_in(builder.attributes.len(), self.allocator);
+    attributes.extend(builder.attributes.iter().map(|attribute| Attribute {
+      span: attribute.span,
+      key: AttributeKey {
+        span: attribute.key.span,
+        value: attribute.key.value,
+        normalized: attribute.key.normalized,
+      },
+      value: attribute.value.as_ref().map(|value| AttributeValue {
+        span: value.span,
+        value_span: value.value_span,
+        value: value.value,
+        raw: value.raw,
+      }),
+      style: attribute.style.as_ref().map(|style| {
+        let mut copy = ArenaVec::with_capacity_in(style.len(), self.allocator);
+        copy.extend(
+          style
+            .iter()
+            .map(|declaration| StyleDeclaration { ..*declaration }),
+        );
+        copy
+      }),
+    }));
+    ElementBuilder {
+      tag_name: builder.tag_name,
+      attributes,
+      children: ArenaVec::new_in(self.allocator),
+      start: builder.start,
+      tag_end: builder.tag_end,
+      implied: false,
+    }
+  }
+}
@@ -0,0 +1,226 @@
+//! The `<html>`, `<head>` and `<body>` elements browsers add when they are missing, see
+//! [`implied_tags`](crate::option::HtmlParserOption::implied_tags).
+
+use oxc_allocator::Vec as ArenaVec;
+use umc_html_ast::{Attribute, Node};
+use umc_span::Span;
+
+use crate::{error::HtmlParseErrorKind, trace::trace};
+
+use super::{ElementBuilder, HtmlParserImpl};
+
+/// Elements which go in `<head>`.
+const HEAD_ELEMENTS: &[&str] = &[
+  "base", "basefont", "bgsound", "link", "meta", "noframes", "noscript", "script", "style",
+  "template", "title",
+];
+
+/// Where the parser is in the structure of the document, the insertion modes of the
+/// [specification](https://html.spec.whatwg.org/multipage/parsing.html#the-insertion-mode)
+/// deciding which elements are implied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum InsertionMode {
+  BeforeHtml,
+  BeforeHead,
+  InHead,
+  AfterHead,
+  InBody,
+  AfterBody,
+}
+
+impl<'a> HtmlParserImpl<'a> {
+  /// Open the elements implied before a text, or an opening tag with `tag_name`, at
+  /// `offset`.
+  pub(super) fn imply_tags(
+    &mut self,
+    tag_name: Option<&str>,
+    offset: u32,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut Vec<ElementBuilder<'a>>,
+  ) {
+    let is = |name: &str| tag_name.is_some_and(|tag_name| tag_name.eq_ignore_ascii_case(name));
+    let is_head_element = HEAD_ELEMENTS.iter().any(|name| is(name));
+    while let Some(mode) = self.insertion_mode {
+      // the next mode, and whether the tag opens the element of this mode itself
+      let (next, opened) = match mode {
+        InsertionMode::BeforeHtml if is("html") => (InsertionMode::BeforeHead, true),
+        InsertionMode::BeforeHtml => {
+          self.imply_element("html", offset, element_stack);
+          (InsertionMode::BeforeHead, false)
+        }
+        // an `<html>` tag after the first one is merged into it
+        InsertionMode::BeforeHead
+        | InsertionMode::InHead
+        | InsertionMode::AfterHead
+        | InsertionMode::InBody
+          if is("html") =>
+        {
+          return;
+        }
+        InsertionMode::BeforeHead if is("head") => (InsertionMode::InHead, true),
+        InsertionMode::BeforeHead => {
+          self.imply_element("head", offset, element_stack);
+          (InsertionMode::InHead, false)
+        }
+        InsertionMode::InHead | InsertionMode::AfterHead if is_head_element => return,
+        InsertionMode::InHead => {
+          // the head is left open when an element in it is, like `<noscript>`
+          if !element_stack
+            .last()
+            .is_some_and(|builder| builder.tag_name.eq_ignore_ascii_case("head"))
+          {
+            return;
+          }
+          let head = element_stack.pop().unwrap();
+          trace!(implied = head.implied, "close head");
+          self.close_builder(head, None, nodes, element_stack);
+          (InsertionMode::AfterHead, false)
+        }
+        InsertionMode::AfterHead if is("body") || is("frameset") => (InsertionMode::InBody, true),
+        InsertionMode::AfterHead => {
+          self.imply_element("body", offset, element_stack);
+          (InsertionMode::InBody, true)
+        }
+        InsertionMode::InBody => return,
+        // content after `</body>` or `</html>` goes in the body
+        InsertionMode::AfterBody => {
+          Self::reopen_body(nodes, element_stack);
+          (InsertionMode::InBody, true)
+        }
+      };
+      self.insertion_mode = Some(next);
+      if opened {
+        return;
+      }
+    }
+  }
+
+  /// Handle an `<html>` opening tag after the first one, or a `<body>` opening tag in the
+  /// body, spanning `span`: the tag is dropped, and the `attributes` the open element is
+  /// missing are added to it. Returns whether the tag was dropped.
+  pub(super) fn merge_document_element(
+    &mut self,
+    tag_name: &str,
+    attributes: &mut ArenaVec<'a, Attribute<'a>>,
+    span: Span,
+    element_stack: &mut [ElementBuilder<'a>],
+  ) -> bool {
+    let index = match self.insertion_mode {
+      Some(mode) if mode != InsertionMode::BeforeHtml && tag_name.eq_ignore_ascii_case("html") => 0,
+      Some(InsertionMode::InBody) if tag_name.eq_ignore_ascii_case("body") => 1,
+      _ => return false,
+    };
+    let Some(open) = element_stack
+      .get_mut(index)
+      .filter(|builder| builder.tag_name.eq_ignore_ascii_case(tag_name))
+    else {
+      return false;
+    };
+    trace!(tag = tag_name, "merge document element");
+    let error = HtmlParseErrorKind::NestedDocumentElement
+      .error(format!("Unexpected <{tag_name}> in the body"))
+      .with_labels([
+        span.primary_label("dropped"),
+        Span::new(open.start, open.tag_end).label(format!("<{}> is already open", open.tag_name)),
+      ])
+      .with_help(format!("Move the attributes to the first <{tag_name}>"));
+    for attribute in attributes.drain(..) {
+      if !open
+        .attributes
+        .iter()
+        .any(|other| other.key.normalized == attribute.key.normalized)
+      {
+        open.attributes.push(attribute);
+      }
+    }
+    self.report(error);
+    true
+  }
+
+  /// Leave `<head>` once it is closed by `</head>`.
+  pub(super) fn close_head(&mut self, element_stack: &[ElementBuilder<'a>]) {
+    if self.insertion_mode == Some(InsertionMode::InHead)
+      && !element_stack
+        .iter()
+        .any(|builder| builder.tag_name.eq_ignore_ascii_case("head"))
+    {
+      self.insertion_mode = Some(InsertionMode::AfterHead);
+    }
+  }
+
+  /// Leave `<body>` once it is closed by `</body>` or `</html>`.
+  pub(super) fn close_body(&mut self, element_stack: &[ElementBuilder<'a>]) {
+    if self.insertion_mode == Some(InsertionMode::InBody)
+      && !element_stack
+        .iter()
+        .any(|builder| builder.tag_name.eq_ignore_ascii_case("body"))
+    {
+      self.insertion_mode = Some(InsertionMode::AfterBody);
+    }
+  }
+
+  /// Open `<html>` and `<body>` again, with the nodes after them, for content after their
+  /// closing tags.
+  fn reopen_body(nodes: &mut ArenaVec<'a, Node<'a>>, element_stack: &mut Vec<ElementBuilder<'a>>) {
+    if element_stack.is_empty()
+      && let Some(html) = Self::reopen("html", nodes)
+    {
+      trace!("reopen html");
+      element_stack.push(html);
+    }
+    if let [html] = element_stack.as_mut_slice()
+      && html.tag_name.eq_ignore_ascii_case("html")
+      && let Some(body) = Self::reopen("body", &mut html.children)
+    {
+      trace!("reopen body");
+      element_stack.push(body);
+    }
+  }
+
+  /// Take the last element named `tag_name` out of `nodes` to open it again, with the nodes
+  /// after it as its last children.
+  fn reopen(tag_name: &str, nodes: &mut ArenaVec<'a, Node<'a>>) -> Option<ElementBuilder<'a>> {
+    let index = nodes.iter().rposition(
+      |node| matches!(node, Node::Element(element) if element.name().eq_ignore_ascii_case(tag_name)),
+    )?;
+    let after: Vec<_> = nodes.drain(index + 1..).collect();
+    let Some(Node::Element(element)) = nodes.pop() else {
+      unreachable!()
+    };
+    let element = element.unbox();
+    let tag_end = element
+      .children
+      .first()
+      .map_or(element.span.end, |child| child.span().start);
+    let mut children = element.children;
+    children.extend(after);
+    // closed by its closing tag already, so not reported as unclosed
+    Some(ElementBuilder {
+      tag_name: element.tag_name,
+      attributes: element.attributes,
+      children,
+      start: element.span.start,
+      tag_end,
+      implied: true,
+    })
+  }
+
+  /// Open an implied element, with a zero-width opening tag at `offset`.
+  fn imply_element(
+    &mut self,
+    tag_name: &'static str,
+    offset: u32,
+    element_stack: &mut Vec<ElementBuilder<'a>>,
+  ) {
+    trace!(tag = tag_name, offset, "imply element");
+    self.charge_node(offset);
+    element_stack.push(ElementBuilder {
+      tag_name,
+      attributes: ArenaVec::new_in(self.allocator),
+      children: ArenaVec::new_in(self.allocator),
+      start: offset,
+      tag_end: offset,
+      implied: true,
+    });
+  }
+}
@@ -0,0 +1,47 @@
+//! The elements still open at the end of the document, see
+//! [`RecoveryStrategy`].
+
+use oxc_allocator::Vec as ArenaVec;
+use umc_html_ast::Node;
+
+use crate::option::RecoveryStrategy;
+
+use super::{ElementBuilder, HtmlParserImpl, is_in};
+
+/// Elements whose closing tags are optional around the whole document.
+const DOCUMENT: &[&str] = &["html", "head", "body"];
+
+/// Elements the content of unclosed elements bubbles to.
+const SECTIONING: &[&str] = &["article", "aside", "body", "nav", "section"];
+
+impl<'a> HtmlParserImpl<'a> {
+  /// Get the depth from which the elements still open at the end of the document are not
+  /// closed, but dropped or unwrapped by [`recover`](Self::recover).
+  pub(super) fn recovery_depth(&self, element_stack: &[ElementBuilder<'a>]) -> Option<usize> {
+    match self.options.recovery_strategy {
+      RecoveryStrategy::AutoClose => None,
+      RecoveryStrategy::DiscardIncomplete => element_stack
+        .iter()
+        .rposition(|builder| !is_in(DOCUMENT, builder.tag_name)),
+      RecoveryStrategy::BubbleToSection => element_stack
+        .iter()
+        .rposition(|builder| is_in(SECTIONING, builder.tag_name))
+        .map(|section| section + 1),
+    }
+  }
+
+  /// Drop an element still open at the end of the document, moving its content to its
+  /// parent with [`BubbleToSection`](RecoveryStrategy::BubbleToSection).
+  pub(super) fn recover(
+    &self,
+    builder: ElementBuilder<'a>,
+    nodes: &mut ArenaVec<'a, Node<'a>>,
+    element_stack: &mut [ElementBuilder<'a>],
+  ) {
+    if self.options.recovery_strategy == RecoveryStrategy::BubbleToSection {
+      for child in builder.children {
+        Self::push_node(nodes, element_stack, child);
+      }
+    }
+  }
+}
@@ -0,0 +1,131 @@
+//! Closing tags of void elements, like `</meta>` in `<meta>Text</meta>`, and the `/>` of
+//! elements which are not void, like `<div/>`.
+//!
+//! Void elements have no content, so the content written before their closing tag stays
+//! after them, in their parent, as browsers parse it. The closing tag is dropped, or kept
+//! as a [`RawUnknown`](umc_html_ast::RawUnknown) node with
+//! [`preserve_unknown`](crate::option::HtmlParserOption::preserve_unknown), like other
+//! closing tags without opening tag.
+
+use oxc_allocator::Vec as ArenaVec;
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::Node;
+use umc_span::Span;
+
+use crate::{error::HtmlParseErrorKind, option::SelfClosing, trace::trace};
+
+use super::{ElementBuilder, HtmlParserImpl, is_in};
+
+/// Elements of foreign content, in which `/>` closes elements.
+const FOREIGN: &[&str] = &["math", "svg"];
+
+/// Elements of foreign content whose content is HTML again, the
+/// [HTML and MathML text integration points](https://html.spec.whatwg.org/multipage/parsing.html#html-integration-point).
+const INTEGRATION_POINTS: &[&str] = &[
+  "foreignObject",
+  "desc",
+  "title",
+  "annotation-xml",
+  "mi",
+  "mo",
+  "mn",
+  "ms",
+  "mtext",
+];
+
+impl<'a> HtmlParserImpl<'a> {
+  /// Report the closing tag of a void element, and the content written in it.
+  pub(super) fn report_void_end_tag(
+    &mut self,
+    tag_name: &str,
+    close_tag_span: Span,
+    nodes: &ArenaVec<'a, Node<'a>>,
+    element_stack: &[ElementBuilder<'a>],
+  ) {
+    trace!(tag = tag_name, "closing tag of a void element");
+    let siblings = element_stack
+      .last()
+      .map_or(nodes.as_slice(), |parent| parent.children.as_slice());
+    // the void element this closing tag was written for, the last one before it
+    let opened = siblings
+      .iter()
+      .enumerate()
+      .rev()
+      .find_map(|(index, node)| match node {
+        Node::Element(element) if self.names_match(element.tag_name, tag_name) => {
+          Some((index, element.span))
+        }
+        _ => None,
+      });
+
+    let mut error = HtmlParseErrorKind::VoidElementEndTag
+      .error(format!(
+        "Void element cannot have an end tag: </{tag_name}>"
+      ))
+      .with_label(close_tag_span.primary_label("void elements have no closing tag"))
+      .with_help("Remove this closing tag");
+    if let Some((opened, opening_tag)) = opened {
+      error = error.and_label(opening_tag.label(format!("<{tag_name}> is complete here")));
+
+      let content = &siblings[opened + 1..];
+      if content
+        .iter()
+        .any(|node| !matches!(node, Node::Text(text) if text.value.trim().is_empty()))
+      {
+        let span = Span::new(
+          Self::node_start(&content[0]),
+          Self::node_end(&content[content.len() - 1]),
+        );
+        self.report(Self::void_children_error(tag_name, opening_tag, span));
+      }
+    }
+    self.report(error);
+  }
+
+  fn void_children_error(tag_name: &str, opening_tag: Span, content: Span) -> OxcDiagnostic {
+    HtmlParseErrorKind::VoidElementChildren
+      .error(format!("Void element cannot have children: <{tag_name}>"))
+      .with_labels([
+        content.primary_label(format!("not in <{tag_name}>")),
+        opening_tag.label(format!("<{tag_name}> is a void element")),
+      ])
+      .with_help(format!(
+        "Move the content out of <{tag_name}>, it is parsed after it"
+      ))
+  }
+
+  /// Whether the `/>` ending at `end` closes an element which is not void, per the
+  /// [`self_closing`](crate::option::HtmlParserOption::self_closing) option.
+  pub(super) fn closes_self(
+    &mut self,
+    tag_name: &str,
+    end: u32,
+    element_stack: &[ElementBuilder<'a>],
+  ) -> bool {
+    if self.options.self_closing == SelfClosing::TreatSelfClosingAsClosed {
+      return true;
+    }
+    // the lexer reads the content of a raw text element after its `/>`, even in foreign
+    // content
+    let raw_text = self.options.dialect_spec.raw_text_tags.contains(tag_name);
+    // the nearest of the foreign elements and integration points decides what the tag is in
+    let in_foreign = element_stack
+      .iter()
+      .rfind(|builder| {
+        is_in(FOREIGN, builder.tag_name) || is_in(INTEGRATION_POINTS, builder.tag_name)
+      })
+      .is_some_and(|builder| is_in(FOREIGN, builder.tag_name));
+    if !raw_text && (is_in(FOREIGN, tag_name) || in_foreign) {
+      return true;
+    }
+    trace!(tag = tag_name, "ignore trailing solidus");
+    let error = HtmlParseErrorKind::TrailingSolidus
+      .error(format!(
+        "Self-closing tag on an element which is not void: <{tag_name}/>"
+      ))
+      .with_label(Span::new(end - 2, end).primary_label("ignored"))
+      .with_help(format!("Close the element with </{tag_name}>"));
+    self.report(error);
+    false
+  }
+}
@@ -0,0 +1,709 @@
+//! Streaming HTML parsing.
+//!
+//! [`HtmlStreamParser`] accepts the document in chunks as they arrive, e.g. from an HTTP
+//! body, and emits [`StreamEvent`]s as soon as each construct is complete. Only the
+//! unfinished tail of the input is buffered, so tokens split across chunk boundaries are
+//! handled without keeping the whole document in memory.
+//!
+//! Events are produced by the same lexer as [`Parser::html`](crate::CreateHtml::html), and
+//! spans are offsets into the whole document, as if it had been parsed at once.
+
+use std::io::{self, ErrorKind, Read};
+
+use encoding_rs::{CoderResult, Decoder, UTF_8};
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use umc_html_ast::{Attribute, AttributeKey};
+use umc_parser::{MAX_SOURCE_LEN, source_too_long, token::Token};
+use umc_span::Span;
+
+use crate::{
+  encoding::Encoding,
+  error::HtmlParseErrorKind,
+  lexer::{
+    HtmlLexer, HtmlLexerOption,
+    kind::HtmlKind,
+    scan::{ScriptEscape, comment_value, find_raw_text_end},
+  },
+  option::{HtmlParserOption, SelfClosing},
+  parse::parse_attributes,
+};
+
+/// Size of the chunks read by [`HtmlStreamParser::read_from`].
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+/// A construct emitted by [`HtmlStreamParser`].
+///
+/// Events are not nested: void and unclosed elements have no [`EndTag`](Self::EndTag),
+/// and closing tags are reported even when they don't match any start tag.
+#[derive(Debug)]
+#[cfg_attr(
+  feature = "serialize",
+  derive(serde::Serialize),
+  serde(tag = "type", rename_all_fields = "camelCase")
+)]
+pub enum StreamEvent<'s> {
+  /// `<!DOCTYPE html>`
+  Doctype {
+    span: Span,
+    raw: &'s str,
+    attributes: Vec<Attribute<'s>>,
+  },
+  /// `<a href="/">` or `<br />`
+  StartTag {
+    span: Span,
+    raw: &'s str,
+    name: &'s str,
+    attributes: Vec<Attribute<'s>>,
+    self_closing: bool,
+    /// Whether this is a void element like `<br>`, which never has an end tag
+    void: bool,
+  },
+  /// `</a>`
+  EndTag {
+    span: Span,
+    raw: &'s str,
+    name: &'s str,
+  },
+  /// Text content, including the content of `<script>` and `<style>`.
+  ///
+  /// Text is emitted as it arrives, so a run of text may be split into several
+  /// consecutive events.
+  Text { span: Span, value: &'s str },
+  /// `<!-- comment -->`, or a bogus comment like `<? ... >`
+  Comment {
+    span: Span,
+    raw: &'s str,
+    value: &'s str,
+    bogus: bool,
+  },
+}
+
+/// An incremental HTML parser, fed with chunks of the document.
+///
+/// Feed input with [`feed`](Self::feed) or [`feed_bytes`](Self::feed_bytes), mark the end
+/// with [`end`](Self::end), and pull events with [`next_event`](Self::next_event) in between.
+/// Async sources work the same way: feed each chunk when it arrives.
+///
+/// ## Example
+///
+/// ```
+/// use umc_html_parser::stream::{HtmlStreamParser, StreamEvent};
+///
+/// let mut parser = HtmlStreamParser::default();
+/// let mut names = Vec::new();
+///
+/// for chunk in ["<p cla", "ss=a>Hel", "lo</", "p>"] {
+///   parser.feed(chunk);
+///   while let Some(event) = parser.next_event() {
+///     if let StreamEvent::StartTag { name, .. } | StreamEvent::EndTag { name, .. } = event {
+///       names.push(name.to_string());
+///     }
+///   }
+/// }
+/// parser.end();
+/// while parser.next_event().is_some() {}
+///
+/// assert_eq!(names, ["p", "p"]);
+/// assert!(parser.is_finished());
+/// ```
+pub struct HtmlStreamParser {
+  options: HtmlParserOption,
+  /// Input which has not been emitted yet, preceded by `consumed` emitted bytes
+  buffer: String,
+  consumed: usize,
+  /// Offset of `buffer` in the document
+  offset: u32,
+  /// Length of the document parsed, the input after it is dropped, [`MAX_SOURCE_LEN`]
+  /// but in tests
+  max_len: usize,
+  /// Offset the input was cut at, once it is over `max_len`, and the bytes dropped after it
+  truncated: Option<(usize, usize)>,
+  /// The embedded language tag we are in, e.g. `script`
+  embedded_tag: Option<String>,
+  /// Escape state of the script data emitted so far
+  script_escape: ScriptEscape,
+  decoder: Decoder,
+  ended: bool,
+  /// Errors found so far, with spans relative to the whole document
+  pub errors: Vec<OxcDiagnostic>,
+}
+
+impl Default for HtmlStreamParser {
+  fn default() -> Self {
+    Self::new(HtmlParserOption::default())
+  }
+}
+
+impl HtmlStreamParser {
+  /// Create a streaming parser.
+  ///
+  /// Only the tag related options are used, as no AST is built.
+  pub fn new(options: HtmlParserOption) -> Self {
+    Self {
+      options,
+      buffer: String::new(),
+      consumed: 0,
+      offset: 0,
+      max_len: MAX_SOURCE_LEN,
+      truncated: None,
+      embedded_tag: None,
+      script_escape: ScriptEscape::Unescaped,
+      decoder: UTF_8.new_decoder(),
+      ended: false,
+      errors: Vec::new(),
+    }
+  }
+
+  /// Decode the input of [`feed_bytes`](Self::feed_bytes) with `encoding` instead of UTF-8.
+  ///
+  /// A byte order mark at the start of the input still takes precedence.
+  #[must_use]
+  pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+    self.decoder = encoding.new_decoder();
+    self
+  }
+
+  /// Append a chunk of text to the input.
+  ///
+  /// Input over [`MAX_SOURCE_LEN`] bytes is dropped, with an error at the end.
+  pub fn feed(&mut self, chunk: &str) {
+    self.compact();
+    self.buffer.push_str(chunk);
+    self.limit();
+  }
+
+  /// Append a chunk of bytes to the input.
+  ///
+  /// Characters split across chunks are decoded once they are complete.
+  pub fn feed_bytes(&mut self, chunk: &[u8]) {
+    self.compact();
+    self.decode(chunk, false);
+    self.limit();
+  }
+
+  /// Mark the end of the input.
+  ///
+  /// Constructs which are still unfinished are emitted as they are, like when parsing
+  /// the whole document at once.
+  pub fn end(&mut self) {
+    self.decode(&[], true);
+    self.limit();
+    self.ended = true;
+    if let Some((end, dropped)) = self.truncated {
+      self.errors.push(source_too_long(
+        end.saturating_add(dropped),
+        self.max_len,
+        end,
+      ));
+    }
+  }
+
+  /// Whether the input has ended and every event has been emitted.
+  pub const fn is_finished(&self) -> bool {
+    self.ended && self.consumed == self.buffer.len()
+  }
+
+  /// Read the whole `reader`, calling `handler` for each event.
+  ///
+  /// The input is decoded like [`feed_bytes`](Self::feed_bytes).
+  pub fn read_from(
+    &mut self,
+    mut reader: impl Read,
+    mut handler: impl FnMut(StreamEvent<'_>),
+  ) -> io::Result<()> {
+    let mut chunk = vec![0; READ_CHUNK_SIZE];
+
+    while !self.ended {
+      match reader.read(&mut chunk) {
+        Ok(0) => self.end(),
+        Ok(read) => self.feed_bytes(&chunk[..read]),
+        Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+        Err(error) => return Err(error),
+      }
+
+      while let Some(event) = self.next_event() {
+        handler(event);
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Get the next complete event.
+  ///
+  /// Returns `None` when more input is needed, or when the parser [is finished](Self::is_finished).
+  #[allow(clippy::too_many_lines)]
+  pub fn next_event(&mut self) -> Option<StreamEvent<'_>> {
+    let Self {
+      options,
+      buffer,
+      consumed,
+      offset,
+      embedded_tag,
+      script_escape,
+      ended,
+      errors,
+      ..
+    } = self;
+    let ended = *ended;
+    let rest = &buffer[*consumed..];
+    let base = advance(*offset, *consumed);
+
+    if rest.is_empty() {
+      return None;
+    }
+
+    // the content of an embedded language tag, until its closing tag
+    if let Some(closing_tag) = embedded_tag.as_ref().map(|tag| format!("</{tag}")) {
+      // keep what could be the start of the closing tag, and the byte after its name
+      let mut until = rest.len();
+      if !ended {
+        until = until.saturating_sub(closing_tag.len());
+        while !rest.is_char_boundary(until) {
+          until -= 1;
+        }
+      }
+
+      let end = if let Some(index) = find_raw_text_end(
+        rest.as_bytes(),
+        closing_tag.as_bytes(),
+        until,
+        script_escape,
+      ) {
+        *embedded_tag = None;
+        *script_escape = ScriptEscape::Unescaped;
+        index
+      } else if ended {
+        *embedded_tag = None;
+        *script_escape = ScriptEscape::Unescaped;
+        errors.extend(
+          options.errors.apply(
+            HtmlParseErrorKind::UnterminatedRawText
+              .error(format!(
+                "Expected {closing_tag}, but found {}",
+                HtmlKind::Eof
+              ))
+              .with_label(Span::empty(base + rest.len() as u32)),
+          ),
+        );
+        rest.len()
+      } else if until == 0 {
+        return None;
+      } else {
+        until
+      };
+
+      if end > 0 {
+        *consumed += end;
+        return Some(StreamEvent::Text {
+          span: Span::sized(base, end as u32),
+          value: &rest[..end],
+        });
+      }
+    }
+
+    let mut lexer = HtmlLexer::new(
+      rest,
+      HtmlLexerOption {
+        raw_text_tags: options.dialect_spec.raw_text_tags.clone(),
+        spec_self_closing: options.self_closing == SelfClosing::Spec,
+        cdata_sections: options.dialect_spec.cdata_sections,
+        mustache: false,
+        template_delimiters: Vec::new(),
+      },
+    );
+    let mut tokens = Vec::new();
+    for token in lexer.tokens() {
+      let kind = token.kind;
+      tokens.push(token);
+      if matches!(
+        kind,
+        HtmlKind::TextContent | HtmlKind::Comment | HtmlKind::TagEnd | HtmlKind::SelfCloseTagEnd
+      ) {
+        break;
+      }
+    }
+
+    let first = tokens.first()?;
+    let last = tokens.last()?;
+    let text = |token: &Token<HtmlKind>| &rest[token.start as usize..token.end as usize];
+
+    let (end, event) = match first.kind {
+      HtmlKind::TextContent => {
+        let mut end = first.end as usize;
+        if end == rest.len() && !ended {
+          // text may continue in the next chunk, and a trailing `<` may start a tag
+          end -= usize::from(rest.ends_with('<'));
+          if end == 0 {
+            return None;
+          }
+        }
+        let event = StreamEvent::Text {
+          span: Span::sized(base, end as u32),
+          value: &rest[..end],
+        };
+        (end, event)
+      }
+
+      HtmlKind::Comment => {
+        // an unfinished comment may end in the next chunk
+        if !ended
+          && lexer.errors.iter().any(|error| {
+            HtmlParseErrorKind::from_diagnostic(error)
+              == Some(HtmlParseErrorKind::UnterminatedComment)
+          })
+        {
+          return None;
+        }
+        let raw = text(first);
+        let (value, bogus) = comment_value(raw);
+        let event = StreamEvent::Comment {
+          span: first.span().move_right(base),
+          raw,
+          value,
+          bogus,
+        };
+        (first.end as usize, event)
+      }
+
+      HtmlKind::Doctype | HtmlKind::TagStart | HtmlKind::CloseTagStart => {
+        let is_complete = matches!(last.kind, HtmlKind::TagEnd | HtmlKind::SelfCloseTagEnd);
+        if !is_complete && !ended {
+          return None;
+        }
+
+        let end = last.end;
+        let span = Span::new(first.start, end).move_right(base);
+        let raw = &rest[first.start as usize..end as usize];
+        let name = tokens
+          .get(1)
+          .filter(|token| token.kind == HtmlKind::ElementName)
+          .map_or("", text);
+
+        let event = match first.kind {
+          HtmlKind::Doctype => StreamEvent::Doctype {
+            span,
+            raw,
+            attributes: tokens
+              .iter()
+              .filter(|token| {
+                matches!(
+                  token.kind,
+                  HtmlKind::DoctypeName
+                    | HtmlKind::DoctypeKeyword
+                    | HtmlKind::PublicId
+                    | HtmlKind::SystemId
+                    | HtmlKind::BogusDoctype
+                )
+              })
+              .map(|token| Attribute {
+                span: token.span().move_right(base),
+                key: AttributeKey {
+                  span: token.span().move_right(base),
+                  value: text(token),
+                  normalized: text(token),
+                },
+                value: None,
+                style: None,
+              })
+              .collect(),
+          },
+          HtmlKind::TagStart => {
+            let mut attributes = Vec::new();
+            let mut attribute_errors = Vec::new();
+            let mut iter = tokens.iter().skip(2).map(clone_token).peekable();
+            let self_closing = parse_attributes(rest, &mut iter, &mut attribute_errors, |a| {
+              attributes.push(a);
+            })
+            .is_some_and(|token| token.kind == HtmlKind::SelfCloseTagEnd);
+
+            for attribute in &mut attributes {
+              shift_attribute(attribute, base);
+            }
+            errors.extend(
+              attribute_errors
+                .into_iter()
+                .filter_map(|e| options.errors.apply(shift_diagnostic(e, base))),
+            );
+
+            if (!self_closing || options.self_closing == SelfClosing::Spec)
+              && options.dialect_spec.raw_text_tags.contains(name)
+            {
+              *embedded_tag = Some(name.to_string());
+            }
+
+            StreamEvent::StartTag {
+              span,
+              raw,
+              name,
+              attributes,
+              self_closing,
+              void: options.dialect_spec.void_tags.contains(name),
+            }
+          }
+          _ => StreamEvent::EndTag { span, raw, name },
+        };
+        (end as usize, event)
+      }
+
+      _ => return None,
+    };
+
+    errors.extend(
+      lexer
+        .errors
+        .into_iter()
+        .filter_map(|e| options.errors.apply(shift_diagnostic(e, base))),
+    );
+    *consumed += end;
+    Some(event)
+  }
+
+  /// Drop the emitted part of the buffer.
+  fn compact(&mut self) {
+    self.buffer.drain(..self.consumed);
+    self.offset = advance(self.offset, self.consumed);
+    self.consumed = 0;
+  }
+
+  /// Drop the input over `max_len` bytes, rounded down to a character, so offsets fit in
+  /// a `u32`.
+  fn limit(&mut self) {
+    let (end, dropped) = self.truncated.unwrap_or((self.max_len, 0));
+    let Some(mut keep) = end.checked_sub(self.offset as usize) else {
+      return;
+    };
+    if self.buffer.len() <= keep {
+      return;
+    }
+    while !self.buffer.is_char_boundary(keep) {
+      keep -= 1;
+    }
+    let dropped = dropped.saturating_add(self.buffer.len() - keep);
+    self.truncated = Some((self.offset as usize + keep, dropped));
+    self.buffer.truncate(keep);
+  }
+
+  fn decode(&mut self, mut bytes: &[u8], last: bool) {
+    loop {
+      if let Some(length) = self.decoder.max_utf8_buffer_length(bytes.len()) {
+        self.buffer.reserve(length);
+      }
+      let (result, read, _) = self.decoder.decode_to_string(bytes, &mut self.buffer, last);
+      bytes = &bytes[read..];
+      if result == CoderResult::InputEmpty {
+        break;
+      }
+    }
+  }
+}
+
+/// The document offset `bytes` after `offset`, which fits in a `u32` since the input is
+/// at most [`MAX_SOURCE_LEN`] bytes.
+fn advance(offset: u32, bytes: usize) -> u32 {
+  u32::try_from(bytes).map_or(u32::MAX, |bytes| offset.saturating_add(bytes))
+}
+
+const fn clone_token(token: &Token<HtmlKind>) -> Token<HtmlKind> {
+  Token {
+    kind: token.kind,
+    start: token.start,
+    end: token.end,
+  }
+}
+
+const fn shift_attribute(attribute: &mut Attribute, offset: u32) {
+  attribute.span = attribute.span.move_right(offset);
+  attribute.key.span = attribute.key.span.move_right(offset);
+  if let Some(value) = &mut attribute.value {
+    value.span = value.span.move_right(offset);
+    value.value_span = value.value_span.move_right(offset);
+  }
+}
+
+fn shift_diagnostic(mut error: OxcDiagnostic, offset: u32) -> OxcDiagnostic {
+  if let Some(labels) = &mut error.labels {
+    for label in labels {
+      let span = (label.offset() + offset as usize, label.len());
+      let text = label.label().map(ToString::to_string);
+      *label = if label.primary() {
+        LabeledSpan::new_primary_with_span(text, span)
+      } else {
+        LabeledSpan::new_with_span(text, span)
+      };
+    }
+  }
+  error
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+  use umc_html_ast::Attribute;
+  use umc_span::Span;
+
+  use super::{HtmlStreamParser, StreamEvent};
+
+  const HTML: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="UTF-8">
+  <title>Stream</title>
+  <script>if (a </b) { console.log("</scrip", "</scripts>", "<!-- <script></script> -->") }</Script >
+</head>
+<body>
+  <!-- comment --><? bogus ?>
+  <p class='a' hidden data-x=1>caf&eacute; café<br/></p>
+</body>
+</html>
+<style>p { color: red }"#;
+
+  fn describe(event: &StreamEvent) -> String {
+    let attributes = |attributes: &[Attribute]| {
+      let mut result = String::new();
+      for attribute in attributes {
+        result.push(' ');
+        result.push_str(attribute.key.value);
+        if let Some(value) = &attribute.value {
+          result.push('=');
+          result.push_str(value.raw);
+        }
+      }
+      result
+    };
+
+    let (span, description) = match event {
+      StreamEvent::Doctype {
+        span,
+        attributes: a,
+        ..
+      } => (span, format!("<!DOCTYPE{}>", attributes(a))),
+      StreamEvent::StartTag {
+        span,
+        name,
+        attributes: a,
+        self_closing,
+        ..
+      } => {
+        let slash = if *self_closing { "/" } else { "" };
+        (span, format!("<{name}{}{slash}>", attributes(a)))
+      }
+      StreamEvent::EndTag { span, name, .. } => (span, format!("</{name}>")),
+      StreamEvent::Text { span, value } => (span, format!("{value:?}")),
+      StreamEvent::Comment {
+        span, value, bogus, ..
+      } => (span, format!("comment {value:?} bogus={bogus}")),
+    };
+    format!("{}..{} {description}", span.start, span.end)
+  }
+
+  /// Parse in chunks of `size` bytes, merging consecutive text events.
+  fn events(size: usize) -> (Vec<String>, Vec<String>) {
+    let mut parser = HtmlStreamParser::default();
+    let mut events = Vec::new();
+    let mut text: Option<(Span, String)> = None;
+
+    let mut handle = |event: StreamEvent<'_>, events: &mut Vec<String>| {
+      if let StreamEvent::Text { span, value } = event {
+        let (start, mut merged) = text.take().unwrap_or((span, String::new()));
+        merged.push_str(value);
+        text = Some((Span::new(start.start, span.end), merged));
+        return;
+      }
+      if let Some((span, value)) = text.take() {
+        events.push(describe(&StreamEvent::Text {
+          span,
+          value: &value,
+        }));
+      }
+      events.push(describe(&event));
+    };
+
+    for chunk in HTML.as_bytes().chunks(size) {
+      parser.feed_bytes(chunk);
+      while let Some(event) = parser.next_event() {
+        handle(event, &mut events);
+      }
+    }
+    parser.end();
+    while let Some(event) = parser.next_event() {
+      handle(event, &mut events);
+    }
+    if let Some((span, value)) = text.take() {
+      events.push(describe(&StreamEvent::Text {
+        span,
+        value: &value,
+      }));
+    }
+
+    assert!(parser.is_finished());
+    let errors = parser.errors.iter().map(ToString::to_string).collect();
+    (events, errors)
+  }
+
+  #[test]
+  fn stream_events() {
+    let (events, errors) = events(HTML.len());
+    assert_snapshot!(format!("Events: {events:#?}\nErrors: {errors:#?}"));
+  }
+
+  #[test]
+  fn chunk_boundaries() {
+    let expected = events(HTML.len());
+    for size in 1..HTML.len() {
+      assert_eq!(events(size), expected, "chunk size {size}");
+    }
+  }
+
+  #[test]
+  fn unfinished_input() {
+    let mut parser = HtmlStreamParser::default();
+    parser.feed("<p title=\"a");
+    assert!(parser.next_event().is_none());
+
+    parser.end();
+    assert!(matches!(
+      parser.next_event(),
+      Some(StreamEvent::StartTag { name: "p", .. })
+    ));
+    assert!(parser.is_finished());
+    assert_eq!(parser.errors.len(), 1);
+  }
+
+  #[test]
+  fn source_too_long() {
+    let mut parser = HtmlStreamParser {
+      max_len: 12,
+      ..HtmlStreamParser::default()
+    };
+    for chunk in ["<p>a</p>", "<i>bé</i>", "x"] {
+      parser.feed(chunk);
+    }
+    parser.end();
+    let mut texts = Vec::new();
+    while let Some(event) = parser.next_event() {
+      if let StreamEvent::Text { value, .. } = event {
+        texts.push(value.to_string());
+      }
+    }
+
+    assert_eq!(texts, ["a", "b"]);
+    assert!(parser.is_finished());
+    let error = parser.errors.last().unwrap();
+    assert_eq!(
+      error.message,
+      "Source text too long: 19 bytes, over the limit of 12 bytes"
+    );
+    assert_eq!(error.labels.as_ref().unwrap()[0].offset(), 12);
+  }
+
+  #[test]
+  fn read_from() {
+    let mut parser = HtmlStreamParser::default();
+    let mut count = 0;
+    parser.read_from(HTML.as_bytes(), |_| count += 1).unwrap();
+
+    assert!(parser.is_finished());
+    assert!(count > 0);
+  }
+}
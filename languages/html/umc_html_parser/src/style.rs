@@ -0,0 +1,167 @@
+//! Declarations of `style` attributes, see
+//! [`parse_style_attributes`](crate::option::HtmlParserOption::parse_style_attributes).
+//!
+//! A `style` attribute holds the content of a CSS declaration block, like
+//! `color: red; width: 100%`. Declarations are split at the `;` outside of strings,
+//! parentheses and comments, and at their first `:`. Like in CSS, declarations without a
+//! property or a `:` are dropped. Values are kept as written, so escapes and comments in
+//! them are not processed.
+
+use oxc_allocator::{Allocator, Vec as ArenaVec};
+use umc_html_ast::StyleDeclaration;
+use umc_span::Span;
+
+/// Parse the declarations of a `style` attribute value, found at `offset` in the document.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::style::parse_declarations;
+///
+/// let allocator = Allocator::default();
+/// let declarations = parse_declarations(&allocator, "color:red; width: 100% !important", 0);
+///
+/// assert_eq!(declarations[0].property, "color");
+/// assert_eq!(declarations[1].value, "100%");
+/// assert!(declarations[1].important);
+/// ```
+pub fn parse_declarations<'a>(
+  allocator: &'a Allocator,
+  text: &'a str,
+  offset: u32,
+) -> ArenaVec<'a, StyleDeclaration<'a>> {
+  let mut declarations = ArenaVec::new_in(allocator);
+  let mut start = 0;
+  for end in split_points(text) {
+    if let Some(declaration) = parse_declaration(text, start, end, offset) {
+      declarations.push(declaration);
+    }
+    start = end + 1;
+  }
+  declarations
+}
+
+/// Offsets of the `;` ending declarations, and the end of the text.
+fn split_points(text: &str) -> impl Iterator<Item = usize> + '_ {
+  let bytes = text.as_bytes();
+  let mut index = 0;
+  let mut depth = 0usize;
+  let mut quote = None;
+  let mut done = false;
+
+  std::iter::from_fn(move || {
+    while index < bytes.len() {
+      let byte = bytes[index];
+      index += 1;
+      match quote {
+        Some(q) if byte == q => quote = None,
+        // an escape in a string, e.g. `"\""`
+        Some(_) if byte == b'\\' => index += 1,
+        Some(_) => {}
+        None => match byte {
+          b'"' | b'\'' => quote = Some(byte),
+          b'\\' => index += 1,
+          b'(' | b'[' | b'{' => depth += 1,
+          b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+          b'/' if bytes.get(index) == Some(&b'*') => {
+            index = text[index + 1..]
+              .find("*/")
+              .map_or(bytes.len(), |end| index + 1 + end + 2);
+          }
+          b';' if depth == 0 => return Some(index - 1),
+          _ => {}
+        },
+      }
+    }
+    if done {
+      return None;
+    }
+    done = true;
+    Some(bytes.len())
+  })
+}
+
+/// Parse the declaration between `start` and `end`, `None` for an empty or invalid one.
+fn parse_declaration(
+  text: &str,
+  start: usize,
+  end: usize,
+  offset: u32,
+) -> Option<StyleDeclaration<'_>> {
+  let span = |start: usize, end: usize| Span::new(offset + start as u32, offset + end as u32);
+  let declaration = &text[start..end];
+  let colon = declaration.find(':')?;
+
+  let property = declaration[..colon].trim();
+  if property.is_empty() || property.contains(char::is_whitespace) {
+    return None;
+  }
+  let property_start = start + declaration.find(property)?;
+
+  let value_start = start + colon + 1;
+  let mut value = text[value_start..end].trim_end();
+  let mut important = false;
+  if let Some(bang) = value.rfind('!')
+    && value[bang + 1..]
+      .trim_start()
+      .eq_ignore_ascii_case("important")
+  {
+    important = true;
+    value = value[..bang].trim_end();
+  }
+  let leading = value.len() - value.trim_start().len();
+  let value_start = value_start + leading;
+  let value = &value[leading..];
+
+  let declaration_start = property_start;
+  let declaration_end = start + declaration.trim_end().len();
+
+  Some(StyleDeclaration {
+    span: span(declaration_start, declaration_end),
+    property,
+    property_span: span(property_start, property_start + property.len()),
+    value,
+    value_span: span(value_start, value_start + value.len()),
+    important,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+
+  use crate::style::parse_declarations;
+
+  #[test]
+  fn declarations() {
+    let allocator = Allocator::default();
+    let text = " color : red ; ; background: url(\"a;b.png\") /* ; */ ;width:1px!IMPORTANT;x; :y";
+    let declarations = parse_declarations(&allocator, text, 10);
+
+    let parsed: Vec<_> = declarations
+      .iter()
+      .map(|declaration| {
+        (
+          declaration.property,
+          declaration.value,
+          declaration.important,
+        )
+      })
+      .collect();
+    assert_eq!(
+      parsed,
+      [
+        ("color", "red", false),
+        ("background", "url(\"a;b.png\") /* ; */", false),
+        ("width", "1px", true),
+      ]
+    );
+
+    let slice = |span: umc_span::Span| &text[span.start as usize - 10..span.end as usize - 10];
+    assert_eq!(slice(declarations[0].span), "color : red");
+    assert_eq!(slice(declarations[0].property_span), "color");
+    assert_eq!(slice(declarations[0].value_span), "red");
+    assert_eq!(slice(declarations[2].span), "width:1px!IMPORTANT");
+  }
+}
@@ -0,0 +1,344 @@
+//! The tokenizer of the parser, for tools which need tokens rather than an AST, like
+//! syntax highlighters.
+//!
+//! [`tokenize`] splits a document into [`Token`]s of an [`HtmlKind`]. Tokens cover the
+//! whole source text without gap nor overlap, and the last one is an
+//! [`Eof`](HtmlKind::Eof) token, after which the iterator ends.
+//!
+//! # States
+//!
+//! The kind of a token depends on where it is:
+//!
+//! - Between tags, text is a single [`TextContent`](HtmlKind::TextContent) token, up to the next
+//!   `<` starting a tag, a closing tag, a comment or a doctype. A `<` starting nothing is text.
+//! - A tag starts with [`TagStart`](HtmlKind::TagStart) (`<`) or
+//!   [`CloseTagStart`](HtmlKind::CloseTagStart) (`</`), followed by its
+//!   [`ElementName`](HtmlKind::ElementName). Attribute names and values are both
+//!   [`Attribute`](HtmlKind::Attribute) tokens, values keeping their quotes, separated by
+//!   [`Eq`](HtmlKind::Eq) and [`Whitespace`](HtmlKind::Whitespace). The tag ends with
+//!   [`TagEnd`](HtmlKind::TagEnd) (`>`) or [`SelfCloseTagEnd`](HtmlKind::SelfCloseTagEnd) (`/>`).
+//! - A [`Doctype`](HtmlKind::Doctype) token covers `<!DOCTYPE`, followed by its
+//!   [`DoctypeName`](HtmlKind::DoctypeName), its [`DoctypeKeyword`](HtmlKind::DoctypeKeyword)
+//!   and its quoted [`PublicId`](HtmlKind::PublicId) and [`SystemId`](HtmlKind::SystemId),
+//!   separated by [`Whitespace`](HtmlKind::Whitespace), up to its [`TagEnd`](HtmlKind::TagEnd).
+//!   Anything else is a [`BogusDoctype`](HtmlKind::BogusDoctype) token.
+//! - A [`Comment`](HtmlKind::Comment) token covers the whole comment with its delimiters,
+//!   including bogus comments like `<?xml ... ?>`.
+//! - The content of an embedded language tag, `<script>` and `<style>` by default, is a
+//!   single [`TextContent`](HtmlKind::TextContent) token up to its closing tag.
+//!
+//! # Trivia
+//!
+//! [`Tokenizer::with_trivia`] attaches trivia to the next significant token rather than
+//! yielding them, like the lossless syntax trees of rust-analyzer. Trivia are
+//! [`Whitespace`](HtmlKind::Whitespace), [`Comment`](HtmlKind::Comment) and whitespace-only
+//! [`TextContent`](HtmlKind::TextContent) tokens, and trivia at the end of the document are
+//! attached to the [`Eof`](HtmlKind::Eof) token, so the source text is still covered.
+//!
+//! [`HtmlKind`] is `#[non_exhaustive]`: new kinds may be added, but the existing ones keep
+//! their meaning.
+
+use oxc_diagnostics::OxcDiagnostic;
+pub use umc_parser::token::Token;
+use umc_span::Span;
+
+pub use crate::lexer::kind::HtmlKind;
+use crate::{
+  lexer::{HtmlLexer, HtmlLexerOption},
+  option::{HtmlParserOption, SelfClosing},
+};
+
+/// Iterator over the tokens of a document, see [`tokenize`].
+pub struct Tokenizer<'a> {
+  source_text: &'a str,
+  lexer: HtmlLexer<'a>,
+  options: &'a HtmlParserOption,
+}
+
+/// Split a document into tokens.
+///
+/// Only the [`raw_text_tags`](crate::dialect::DialectSpec::raw_text_tags) and
+/// [`cdata_sections`](crate::dialect::DialectSpec::cdata_sections) of the dialect and
+/// [`errors`](HtmlParserOption::errors) of the options are used.
+///
+/// ## Example
+///
+/// ```
+/// use umc_html_parser::{
+///   option::HtmlParserOption,
+///   tokenizer::{HtmlKind, tokenize},
+/// };
+///
+/// let options = HtmlParserOption::default();
+/// let kinds: Vec<HtmlKind> = tokenize("<p class=a>Hi</p>", &options)
+///   .map(|token| token.kind)
+///   .collect();
+///
+/// assert_eq!(
+///   kinds,
+///   [
+///     HtmlKind::TagStart,
+///     HtmlKind::ElementName,
+///     HtmlKind::Whitespace,
+///     HtmlKind::Attribute,
+///     HtmlKind::Eq,
+///     HtmlKind::Attribute,
+///     HtmlKind::TagEnd,
+///     HtmlKind::TextContent,
+///     HtmlKind::CloseTagStart,
+///     HtmlKind::ElementName,
+///     HtmlKind::TagEnd,
+///     HtmlKind::Eof,
+///   ]
+/// );
+/// ```
+pub fn tokenize<'a>(source_text: &'a str, options: &'a HtmlParserOption) -> Tokenizer<'a> {
+  let lexer = HtmlLexer::new(
+    source_text,
+    HtmlLexerOption {
+      raw_text_tags: options.dialect_spec.raw_text_tags.clone(),
+      spec_self_closing: options.self_closing == SelfClosing::Spec,
+      cdata_sections: options.dialect_spec.cdata_sections,
+      mustache: options.handlebars,
+      template_delimiters: options.template_delimiters.clone(),
+    },
+  );
+  Tokenizer {
+    source_text,
+    lexer,
+    options,
+  }
+}
+
+impl<'a> Tokenizer<'a> {
+  /// Take the errors found so far, like an unterminated comment.
+  pub fn take_errors(&mut self) -> Vec<OxcDiagnostic> {
+    std::mem::take(&mut self.lexer.errors)
+      .into_iter()
+      .filter_map(|error| self.options.errors.apply(error))
+      .collect()
+  }
+
+  /// Get the next token without reading it.
+  pub fn peek(&mut self) -> Option<&Token<HtmlKind>> {
+    self.lexer.peek_nth(0)
+  }
+
+  /// Get the token `n` tokens after the next one without reading it, `peek_nth(0)` being
+  /// the next token, e.g. to tell apart the syntax of a template dialect. The tokens
+  /// before it are lexed once, and kept until they are read.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_parser::{
+  ///   option::HtmlParserOption,
+  ///   tokenizer::{HtmlKind, tokenize},
+  /// };
+  ///
+  /// let options = HtmlParserOption::default();
+  /// let mut tokenizer = tokenize("</a>", &options);
+  ///
+  /// assert_eq!(tokenizer.peek_kind(1), Some(HtmlKind::ElementName));
+  /// assert_eq!(tokenizer.peek_nth(3).map(|token| token.kind), Some(HtmlKind::Eof));
+  /// assert_eq!(tokenizer.peek_nth(4), None);
+  /// assert_eq!(tokenizer.next().unwrap().kind, HtmlKind::CloseTagStart);
+  /// ```
+  pub fn peek_nth(&mut self, n: usize) -> Option<&Token<HtmlKind>> {
+    self.lexer.peek_nth(n)
+  }
+
+  /// Get the kind of the token `n` tokens after the next one, see [`peek_nth`](Self::peek_nth).
+  pub fn peek_kind(&mut self, n: usize) -> Option<HtmlKind> {
+    self.peek_nth(n).map(|token| token.kind)
+  }
+
+  /// Attach trivia to the next significant token, see [the module documentation](self#trivia).
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_parser::{
+  ///   option::HtmlParserOption,
+  ///   tokenizer::{HtmlKind, tokenize},
+  /// };
+  ///
+  /// let options = HtmlParserOption::default();
+  /// let tokens: Vec<_> = tokenize("<a\n  href=x>\n</a>", &options).with_trivia().collect();
+  ///
+  /// // the line break and indentation before `href`
+  /// assert_eq!(tokens[2].token.kind, HtmlKind::Attribute);
+  /// assert_eq!(tokens[2].leading[0].kind, HtmlKind::Whitespace);
+  /// assert_eq!(tokens[2].full_span().start, 2);
+  /// ```
+  pub const fn with_trivia(self) -> WithTrivia<'a> {
+    WithTrivia { tokenizer: self }
+  }
+
+  /// Whether a token is trivia, see [the module documentation](self#trivia).
+  pub fn is_trivia(&self, token: &Token<HtmlKind>) -> bool {
+    match token.kind {
+      HtmlKind::Whitespace | HtmlKind::Comment => true,
+      HtmlKind::TextContent => token
+        .span()
+        .source_text(self.source_text)
+        .bytes()
+        .all(|byte| byte.is_ascii_whitespace()),
+      _ => false,
+    }
+  }
+}
+
+impl Iterator for Tokenizer<'_> {
+  type Item = Token<HtmlKind>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    self.lexer.next_token()
+  }
+}
+
+/// A significant token with the trivia before it, see [`Tokenizer::with_trivia`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct TriviaToken {
+  /// Trivia between the previous significant token and this one, in source order
+  pub leading: Vec<Token<HtmlKind>>,
+  /// The significant token
+  pub token: Token<HtmlKind>,
+}
+
+impl TriviaToken {
+  /// The span of the token, including its leading trivia.
+  pub fn full_span(&self) -> Span {
+    let start = self.leading.first().unwrap_or(&self.token).start;
+    Span::new(start, self.token.end)
+  }
+}
+
+/// Iterator over the significant tokens of a document with their trivia, see
+/// [`Tokenizer::with_trivia`].
+pub struct WithTrivia<'a> {
+  tokenizer: Tokenizer<'a>,
+}
+
+impl WithTrivia<'_> {
+  /// Take the errors found so far, like an unterminated comment.
+  pub fn take_errors(&mut self) -> Vec<OxcDiagnostic> {
+    self.tokenizer.take_errors()
+  }
+}
+
+impl Iterator for WithTrivia<'_> {
+  type Item = TriviaToken;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let mut leading = Vec::new();
+    // the last token is `Eof`, which is not trivia, so no trivia is dropped
+    loop {
+      let token = self.tokenizer.next()?;
+      if self.tokenizer.is_trivia(&token) {
+        leading.push(token);
+      } else {
+        return Some(TriviaToken { leading, token });
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{
+    error::HtmlParseErrorKind,
+    option::HtmlParserOption,
+    tokenizer::{HtmlKind, tokenize},
+  };
+
+  #[test]
+  fn tokens_cover_source() {
+    const HTML: &str = "<!DOCTYPE html><style>p > a {}</style><!-- c --><a href='/' >x</a> < 1";
+
+    let options = HtmlParserOption::default();
+    let mut tokenizer = tokenize(HTML, &options);
+    let tokens: Vec<_> = tokenizer.by_ref().collect();
+
+    let mut end = 0;
+    for token in &tokens {
+      assert_eq!(token.start, end);
+      end = token.end;
+    }
+    assert_eq!(end as usize, HTML.len());
+    assert_eq!(tokens.last().unwrap().kind, HtmlKind::Eof);
+    assert!(tokenizer.take_errors().is_empty());
+
+    let mut tokenizer = tokenize("<!-- a", &options);
+    assert_eq!(tokenizer.by_ref().count(), 2);
+    let errors = tokenizer.take_errors();
+    assert_eq!(
+      HtmlParseErrorKind::from_diagnostic(&errors[0]),
+      Some(HtmlParseErrorKind::UnterminatedComment)
+    );
+  }
+
+  #[test]
+  fn lookahead() {
+    const HTML: &str = "<p>a<!-- b --></p>";
+
+    let options = HtmlParserOption::default();
+    let expected: Vec<_> = tokenize(HTML, &options).collect();
+
+    // peeking at any distance reads the same tokens
+    let mut tokenizer = tokenize(HTML, &options);
+    let mut tokens = Vec::new();
+    for n in 0..expected.len() {
+      assert_eq!(tokenizer.peek_nth(n), expected.get(n + tokens.len()));
+      tokens.extend(tokenizer.next());
+      assert_eq!(tokenizer.peek(), expected.get(tokens.len()));
+    }
+    assert_eq!(tokens, expected);
+    assert_eq!(tokenizer.peek_kind(0), None);
+    assert!(tokenizer.take_errors().is_empty());
+  }
+
+  #[test]
+  fn trivia() {
+    const HTML: &str = "<ul>\n  <!-- items -->\n  <li class = a>One</li>\n</ul>\n";
+
+    let options = HtmlParserOption::default();
+    let tokens: Vec<_> = tokenize(HTML, &options).with_trivia().collect();
+
+    let source: String = tokens
+      .iter()
+      .map(|token| token.full_span().source_text(HTML))
+      .collect();
+    assert_eq!(source, HTML);
+
+    let significant: Vec<_> = tokens
+      .iter()
+      .map(|token| {
+        let trivia: Vec<_> = token.leading.iter().map(|trivia| trivia.kind).collect();
+        format!("{trivia:?} {:?}", token.token.span().source_text(HTML))
+      })
+      .collect();
+    assert_eq!(
+      significant,
+      [
+        r#"[] "<""#,
+        r#"[] "ul""#,
+        r#"[] ">""#,
+        r#"[TextContent, Comment, TextContent] "<""#,
+        r#"[] "li""#,
+        r#"[Whitespace] "class""#,
+        r#"[Whitespace] "=""#,
+        r#"[Whitespace] "a""#,
+        r#"[] ">""#,
+        r#"[] "One""#,
+        r#"[] "</""#,
+        r#"[] "li""#,
+        r#"[] ">""#,
+        r#"[TextContent] "</""#,
+        r#"[] "ul""#,
+        r#"[] ">""#,
+        r#"[TextContent] """#,
+      ]
+    );
+  }
+}
@@ -0,0 +1,16 @@
+//! Instrumentation with `tracing`, behind the `tracing` feature.
+//!
+//! The parse is a `debug` span, and the decisions of the tree construction, like an element
+//! implicitly closed or an orphan closing tag ignored, are `trace` events. The lexer traces
+//! each token and state transition under the `umc_html_parser::lexer::lexe` target.
+//! Without the feature, nothing is compiled in.
+
+/// Emit a `trace` event, compiled out without the `tracing` feature.
+macro_rules! trace {
+  ($($arg:tt)*) => {
+    #[cfg(feature = "tracing")]
+    tracing::trace!($($arg)*);
+  };
+}
+
+pub(crate) use trace;
@@ -0,0 +1,268 @@
+use umc_html_selector::SelectorElement;
+
+/// How inserted content is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentType {
+  /// Written as is
+  Html,
+  /// Escaped, so it is shown as text
+  Text,
+}
+
+/// An attribute of an [`Element`].
+#[derive(Debug)]
+pub struct ElementAttribute {
+  pub name: String,
+  /// The value as written in the source, without quotes, `None` for `<input disabled>`
+  pub value: Option<String>,
+  /// The source text of the attribute, `None` once it is changed
+  pub raw: Option<String>,
+}
+
+/// An element matched by a handler of [`HtmlRewriter`](crate::HtmlRewriter).
+///
+/// Attribute values are the text written in the source, without quotes. Character
+/// references are not decoded, like in the AST.
+#[derive(Debug)]
+pub struct Element {
+  pub(crate) tag_name: String,
+  pub(crate) attributes: Vec<ElementAttribute>,
+  /// Whether the element has an end tag and can have content
+  pub(crate) has_content: bool,
+  /// Whether the start tag ends with `/>`
+  pub(crate) self_closing: bool,
+  /// Whether the attributes were changed, so the start tag must be serialized again
+  pub(crate) modified: bool,
+  pub(crate) before: String,
+  pub(crate) after: String,
+  pub(crate) prepend: String,
+  pub(crate) append: String,
+  pub(crate) inner_content: Option<String>,
+  pub(crate) removal: Removal,
+}
+
+/// How an element is removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Removal {
+  /// The element is kept
+  None,
+  /// The element and its content are removed
+  Element,
+  /// Only the start and end tags are removed
+  Tags,
+}
+
+impl Element {
+  pub(crate) const fn new(
+    tag_name: String,
+    attributes: Vec<ElementAttribute>,
+    has_content: bool,
+    self_closing: bool,
+  ) -> Self {
+    Self {
+      tag_name,
+      attributes,
+      has_content,
+      self_closing,
+      modified: false,
+      before: String::new(),
+      after: String::new(),
+      prepend: String::new(),
+      append: String::new(),
+      inner_content: None,
+      removal: Removal::None,
+    }
+  }
+
+  /// The tag name, as written in the source.
+  pub fn tag_name(&self) -> &str {
+    &self.tag_name
+  }
+
+  /// Whether the element can have content, i.e. it is neither void nor self-closing.
+  pub const fn can_have_content(&self) -> bool {
+    self.has_content
+  }
+
+  /// Get the value of an attribute, `Some("")` for an attribute without value.
+  ///
+  /// Attribute names are compared ignoring ASCII case.
+  pub fn attribute(&self, name: &str) -> Option<&str> {
+    self
+      .find(name)
+      .map(|attribute| attribute.value.as_deref().unwrap_or(""))
+  }
+
+  /// Whether the element has an attribute.
+  pub fn has_attribute(&self, name: &str) -> bool {
+    self.find(name).is_some()
+  }
+
+  /// Iterate the attributes as `(name, value)` pairs, in source order.
+  pub fn attributes(&self) -> impl Iterator<Item = (&str, &str)> {
+    self.attributes.iter().map(|attribute| {
+      (
+        attribute.name.as_str(),
+        attribute.value.as_deref().unwrap_or(""),
+      )
+    })
+  }
+
+  /// Set the value of an attribute, adding it if it doesn't exist.
+  ///
+  /// The value is escaped when written.
+  pub fn set_attribute(&mut self, name: &str, value: &str) {
+    self.modified = true;
+    if let Some(attribute) = self
+      .attributes
+      .iter_mut()
+      .find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+    {
+      attribute.value = Some(value.to_string());
+      attribute.raw = None;
+    } else {
+      self.attributes.push(ElementAttribute {
+        name: name.to_string(),
+        value: Some(value.to_string()),
+        raw: None,
+      });
+    }
+  }
+
+  /// Remove an attribute, if it exists.
+  pub fn remove_attribute(&mut self, name: &str) {
+    let length = self.attributes.len();
+    self
+      .attributes
+      .retain(|attribute| !attribute.name.eq_ignore_ascii_case(name));
+    self.modified |= self.attributes.len() != length;
+  }
+
+  /// Insert content before the start tag.
+  pub fn before(&mut self, content: &str, content_type: ContentType) {
+    push_content(&mut self.before, content, content_type);
+  }
+
+  /// Insert content after the end tag.
+  pub fn after(&mut self, content: &str, content_type: ContentType) {
+    push_content(&mut self.after, content, content_type);
+  }
+
+  /// Insert content right after the start tag. Ignored if the element can't have content.
+  pub fn prepend(&mut self, content: &str, content_type: ContentType) {
+    push_content(&mut self.prepend, content, content_type);
+  }
+
+  /// Insert content right before the end tag. Ignored if the element can't have content.
+  pub fn append(&mut self, content: &str, content_type: ContentType) {
+    push_content(&mut self.append, content, content_type);
+  }
+
+  /// Replace the content of the element. Ignored if the element can't have content.
+  pub fn set_inner_content(&mut self, content: &str, content_type: ContentType) {
+    let mut inner_content = String::new();
+    push_content(&mut inner_content, content, content_type);
+    self.inner_content = Some(inner_content);
+  }
+
+  /// Replace the element and its content.
+  pub fn replace(&mut self, content: &str, content_type: ContentType) {
+    self.before(content, content_type);
+    self.remove();
+  }
+
+  /// Remove the element and its content.
+  pub const fn remove(&mut self) {
+    self.removal = Removal::Element;
+  }
+
+  /// Remove the start and end tags of the element, keeping its content.
+  pub fn remove_and_keep_content(&mut self) {
+    if self.removal == Removal::None {
+      self.removal = Removal::Tags;
+    }
+  }
+
+  /// Whether the element is removed.
+  pub fn is_removed(&self) -> bool {
+    self.removal != Removal::None
+  }
+
+  /// Whether the content of the element is dropped from the output.
+  pub(crate) const fn drops_content(&self) -> bool {
+    self.has_content && (matches!(self.removal, Removal::Element) || self.inner_content.is_some())
+  }
+
+  /// Write the start tag, after the attributes were changed.
+  pub(crate) fn write_start_tag(&self, output: &mut String) {
+    output.push('<');
+    output.push_str(&self.tag_name);
+    for attribute in &self.attributes {
+      output.push(' ');
+      if let Some(raw) = &attribute.raw {
+        output.push_str(raw);
+        continue;
+      }
+      output.push_str(&attribute.name);
+      if let Some(value) = &attribute.value {
+        output.push_str("=\"");
+        escape(output, value, true);
+        output.push('"');
+      }
+    }
+    if self.self_closing {
+      output.push_str(" /");
+    }
+    output.push('>');
+  }
+
+  fn find(&self, name: &str) -> Option<&ElementAttribute> {
+    self
+      .attributes
+      .iter()
+      .find(|attribute| attribute.name.eq_ignore_ascii_case(name))
+  }
+}
+
+/// An element of the open element stack, used to match selectors.
+pub struct StackElement<'s> {
+  pub stack: &'s [Element],
+  pub index: usize,
+}
+
+impl SelectorElement for StackElement<'_> {
+  fn tag_name(&self) -> &str {
+    self.stack[self.index].tag_name()
+  }
+
+  fn attribute(&self, name: &str) -> Option<&str> {
+    self.stack[self.index].attribute(name)
+  }
+
+  fn parent(&self) -> Option<Self> {
+    self.index.checked_sub(1).map(|index| Self {
+      stack: self.stack,
+      index,
+    })
+  }
+}
+
+fn push_content(output: &mut String, content: &str, content_type: ContentType) {
+  match content_type {
+    ContentType::Html => output.push_str(content),
+    ContentType::Text => escape(output, content, false),
+  }
+}
+
+/// Escape text, or an attribute value when `attribute` is true.
+fn escape(output: &mut String, text: &str, attribute: bool) {
+  for c in text.chars() {
+    match c {
+      '&' => output.push_str("&amp;"),
+      '"' if attribute => output.push_str("&quot;"),
+      '<' if !attribute => output.push_str("&lt;"),
+      '>' if !attribute => output.push_str("&gt;"),
+      c => output.push(c),
+    }
+  }
+}
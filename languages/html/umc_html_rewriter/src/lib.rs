@@ -0,0 +1,362 @@
+//! Streaming HTML rewriter.
+//!
+//! [`HtmlRewriter`] runs handlers on the elements matching CSS selectors while the document
+//! streams through it, and writes the rewritten output as soon as each part is processed.
+//! It is built on [`HtmlStreamParser`], so only the unfinished tail of the input is buffered.
+//!
+//! ## Example
+//!
+//! ```
+//! use umc_html_rewriter::{ContentType, HtmlRewriter};
+//!
+//! let mut output = String::new();
+//! let mut rewriter = HtmlRewriter::new(|chunk: &str| output.push_str(chunk));
+//!
+//! rewriter
+//!   .on("a[href]", |element| element.set_attribute("rel", "nofollow"))
+//!   .unwrap();
+//! rewriter
+//!   .on("#ad", |element| element.replace("<!-- ad -->", ContentType::Html))
+//!   .unwrap();
+//!
+//! for chunk in ["<p><a hr", "ef=/home>Home</a><div id=ad>Buy <b>now</b></div></p>"] {
+//!   rewriter.write(chunk.as_bytes());
+//! }
+//! let errors = rewriter.end();
+//!
+//! assert!(errors.is_empty());
+//! assert_eq!(output, r#"<p><a href=/home rel="nofollow">Home</a><!-- ad --></p>"#);
+//! ```
+
+mod element;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_parser::{
+  encoding::Encoding,
+  option::HtmlParserOption,
+  stream::{HtmlStreamParser, StreamEvent},
+};
+use umc_html_selector::SelectorList;
+
+pub use crate::element::{ContentType, Element};
+use crate::element::{ElementAttribute, Removal, StackElement};
+
+type ElementHandler<'h> = Box<dyn FnMut(&mut Element) + 'h>;
+
+/// Rewrites HTML as it streams through, see the [crate documentation](crate).
+///
+/// Elements are nested as written: end tags the HTML standard would imply, like the one
+/// of `<li>` before another `<li>`, are not inferred.
+pub struct HtmlRewriter<'h> {
+  parser: HtmlStreamParser,
+  state: RewriterState<'h>,
+}
+
+struct RewriterState<'h> {
+  handlers: Vec<(SelectorList, ElementHandler<'h>)>,
+  output: Box<dyn FnMut(&str) + 'h>,
+  /// Open elements, the innermost last
+  stack: Vec<Element>,
+  /// Number of open elements whose content is dropped
+  dropping: usize,
+}
+
+impl<'h> HtmlRewriter<'h> {
+  /// Create a rewriter writing its output to `output`.
+  pub fn new(output: impl FnMut(&str) + 'h) -> Self {
+    Self {
+      parser: HtmlStreamParser::default(),
+      state: RewriterState {
+        handlers: Vec::new(),
+        output: Box::new(output),
+        stack: Vec::new(),
+        dropping: 0,
+      },
+    }
+  }
+
+  /// Use parser options, e.g. a custom set of void tags.
+  #[must_use]
+  pub fn with_options(mut self, options: HtmlParserOption) -> Self {
+    self.parser = HtmlStreamParser::new(options);
+    self
+  }
+
+  /// Decode the input with `encoding` instead of UTF-8. The output is always UTF-8.
+  #[must_use]
+  pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+    self.parser = self.parser.with_encoding(encoding);
+    self
+  }
+
+  /// Call `handler` for every element matching `selector`.
+  ///
+  /// Handlers run in registration order, when the start tag of the element is read. As
+  /// later siblings aren't known yet, selectors with sibling combinators are rejected.
+  /// Handlers are not called for elements inside removed content.
+  pub fn on(
+    &mut self,
+    selector: &str,
+    handler: impl FnMut(&mut Element) + 'h,
+  ) -> Result<&mut Self, OxcDiagnostic> {
+    let selectors = SelectorList::parse(selector)?;
+    if selectors.uses_siblings() {
+      return Err(OxcDiagnostic::error(format!(
+        "Sibling combinators are not supported by the streaming rewriter: `{selector}`"
+      )));
+    }
+    self.state.handlers.push((selectors, Box::new(handler)));
+    Ok(self)
+  }
+
+  /// Write a chunk of the input.
+  pub fn write(&mut self, chunk: &[u8]) {
+    self.parser.feed_bytes(chunk);
+    self.process();
+  }
+
+  /// Mark the end of the input, and write the rest of the output.
+  ///
+  /// Returns the errors found while parsing.
+  pub fn end(mut self) -> Vec<OxcDiagnostic> {
+    self.parser.end();
+    self.process();
+
+    // close the elements left open, without end tags
+    while let Some(element) = self.state.stack.pop() {
+      self.state.close(&element, None);
+    }
+
+    self.parser.errors
+  }
+
+  fn process(&mut self) {
+    let Self { parser, state } = self;
+    while let Some(event) = parser.next_event() {
+      state.handle(event);
+    }
+  }
+}
+
+impl RewriterState<'_> {
+  fn handle(&mut self, event: StreamEvent) {
+    match event {
+      StreamEvent::StartTag {
+        raw,
+        name,
+        attributes,
+        self_closing,
+        void,
+        ..
+      } => {
+        let attributes = attributes
+          .iter()
+          .map(|attribute| ElementAttribute {
+            name: attribute.key.value.to_string(),
+            value: attribute
+              .value
+              .as_ref()
+              .map(|value| value.value.to_string()),
+            raw: Some(attribute.value.as_ref().map_or_else(
+              || attribute.key.value.to_string(),
+              |value| format!("{}={}", attribute.key.value, value.raw),
+            )),
+          })
+          .collect();
+        let has_content = !self_closing && !void;
+        let element = Element::new(name.to_string(), attributes, has_content, self_closing);
+        self.start_tag(element, raw);
+      }
+      StreamEvent::EndTag { raw, name, .. } => self.end_tag(raw, name),
+      StreamEvent::Text { value, .. } => self.write(value),
+      StreamEvent::Comment { raw, .. } | StreamEvent::Doctype { raw, .. } => self.write(raw),
+    }
+  }
+
+  fn start_tag(&mut self, element: Element, raw: &str) {
+    self.stack.push(element);
+
+    if self.dropping == 0 {
+      let index = self.stack.len() - 1;
+      let matched: Vec<usize> = self
+        .handlers
+        .iter()
+        .enumerate()
+        .filter(|(_, (selectors, _))| {
+          selectors.matches(&StackElement {
+            stack: &self.stack,
+            index,
+          })
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+      let element = self.stack.last_mut().unwrap();
+      for index in matched {
+        (self.handlers[index].1)(element);
+      }
+    }
+
+    let element = self.stack.pop().unwrap();
+    self.write(&element.before);
+    if element.removal == Removal::None {
+      if element.modified {
+        let mut start_tag = String::new();
+        element.write_start_tag(&mut start_tag);
+        self.write(&start_tag);
+      } else {
+        self.write(raw);
+      }
+    }
+
+    if !element.has_content {
+      self.write(&element.after);
+      return;
+    }
+
+    if element.removal != Removal::Element {
+      self.write(&element.prepend);
+      if let Some(inner_content) = &element.inner_content {
+        self.write(inner_content);
+      }
+    }
+    if element.drops_content() {
+      self.dropping += 1;
+    }
+    self.stack.push(element);
+  }
+
+  fn end_tag(&mut self, raw: &str, name: &str) {
+    let Some(index) = self
+      .stack
+      .iter()
+      .rposition(|element| element.tag_name.eq_ignore_ascii_case(name))
+    else {
+      // a closing tag without start tag is kept as is
+      self.write(raw);
+      return;
+    };
+
+    // close the elements implicitly closed by this tag
+    while self.stack.len() > index + 1 {
+      let element = self.stack.pop().unwrap();
+      self.close(&element, None);
+    }
+    let element = self.stack.pop().unwrap();
+    self.close(&element, Some(raw));
+  }
+
+  /// Write the end of an element, with its end tag if it has one.
+  fn close(&mut self, element: &Element, end_tag: Option<&str>) {
+    if element.drops_content() {
+      self.dropping -= 1;
+    }
+    if element.removal != Removal::Element {
+      self.write(&element.append);
+      if element.removal == Removal::None
+        && let Some(end_tag) = end_tag
+      {
+        self.write(end_tag);
+      }
+    }
+    self.write(&element.after);
+  }
+
+  fn write(&mut self, text: &str) {
+    if self.dropping == 0 && !text.is_empty() {
+      (self.output)(text);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use crate::{ContentType, Element, HtmlRewriter};
+
+  const HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+  <title>Rewrite</title>
+  <script src="/tracker.js"></script>
+  <script>let a = "<div class=ad>";</script>
+</head>
+<body>
+  <nav><a href="/">Home</a> <a href="http://example.com" class=external>Out</a></nav>
+  <div class="ad">Buy <b>now</b></div>
+  <ul><li>One<li>Two</ul>
+  <img src=a.png><br/>
+  <section><span>unwrap me</span></section>
+  <p id=unclosed>Bye
+</body>
+</html>"#;
+
+  fn rewrite(size: usize) -> String {
+    let mut output = String::new();
+    let mut links = 0;
+    let mut rewriter = HtmlRewriter::new(|chunk: &str| output.push_str(chunk));
+
+    rewriter
+      .on("nav > a[href^='http:']", |element| {
+        element.set_attribute("rel", "nofollow \"external\"");
+        element.remove_attribute("CLASS");
+        element.after(" ↗", ContentType::Text);
+      })
+      .unwrap()
+      .on("a", |_| links += 1)
+      .unwrap()
+      .on("script[src], .ad", Element::remove)
+      .unwrap()
+      .on("title", |element| {
+        element.set_inner_content("<Rewritten>", ContentType::Text);
+      })
+      .unwrap()
+      .on("li", |element| {
+        element.prepend("- ", ContentType::Text);
+        element.append(";", ContentType::Text);
+      })
+      .unwrap()
+      .on("img", |element| {
+        element.set_attribute("loading", "lazy");
+        element.prepend("ignored", ContentType::Text);
+      })
+      .unwrap()
+      .on("section span", Element::remove_and_keep_content)
+      .unwrap()
+      .on("#unclosed", |element| {
+        element.before("<hr>", ContentType::Html);
+        element.append("!", ContentType::Text);
+      })
+      .unwrap();
+
+    for chunk in HTML.as_bytes().chunks(size) {
+      rewriter.write(chunk);
+    }
+    assert!(rewriter.end().is_empty());
+    assert_eq!(links, 2);
+
+    output
+  }
+
+  #[test]
+  fn rewrite_document() {
+    assert_snapshot!(rewrite(HTML.len()));
+  }
+
+  #[test]
+  fn chunk_boundaries() {
+    let expected = rewrite(HTML.len());
+    for size in 1..HTML.len() {
+      assert_eq!(rewrite(size), expected, "chunk size {size}");
+    }
+  }
+
+  #[test]
+  fn sibling_selectors() {
+    let mut rewriter = HtmlRewriter::new(|_: &str| {});
+    assert!(rewriter.on("h1 + p", |_| {}).is_err());
+    assert!(rewriter.on("p:not(h1 ~ p)", |_| {}).is_err());
+    assert!(rewriter.on("a[", |_| {}).is_err());
+  }
+}
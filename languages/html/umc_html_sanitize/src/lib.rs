@@ -0,0 +1,303 @@
+//! Allowlist based sanitizing for HTML ASTs.
+//!
+//! This crate removes everything from a parsed [`Program`] which is not explicitly allowed
+//! by a [`SanitizeOptions`]: unknown elements, `<script>`, event handler and style attributes,
+//! and URLs with a scheme like `javascript:`. The transform works on the AST in place, and
+//! every node left in the tree keeps the span it had in the source.
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_html_ast::Node;
+//! use umc_html_parser::CreateHtml;
+//! use umc_html_sanitize::{SanitizeOptions, sanitize};
+//! use umc_parser::Parser;
+//!
+//! const HTML: &str = r#"<a href="javascript:alert(1)" onclick="steal()">Link</a><script>steal()</script>"#;
+//!
+//! let allocator = Allocator::default();
+//! let parser = Parser::html(&allocator, HTML);
+//! let mut program = parser.parse().program;
+//!
+//! let changes = sanitize(&allocator, HTML, &mut program, &SanitizeOptions::default());
+//! assert_eq!(changes.len(), 3);
+//!
+//! let Node::Element(link) = &program[0] else { unreachable!() };
+//! assert!(link.attributes.is_empty());
+//! assert_eq!(program.len(), 1);
+//! ```
+
+use std::mem;
+
+use oxc_allocator::{Allocator, Box, Vec};
+use umc_html_ast::{Attribute, Node, Program, Text};
+use umc_span::Span;
+
+mod options;
+
+pub use options::{DisallowedTag, SanitizeOptions};
+
+/// What the sanitizer did to a construct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+  /// A node was removed with its content
+  RemoveNode,
+  /// The tags of an element were removed, its children were kept
+  StripTags,
+  /// The tags of an element, or an unknown construct, were turned into escaped text
+  Escape,
+  /// An attribute was removed
+  RemoveAttribute,
+}
+
+/// A single modification made by [`sanitize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Change {
+  /// What was done
+  pub kind: ChangeKind,
+  /// Span of the affected construct in the source
+  pub span: Span,
+}
+
+/// Sanitize `program` in place according to `options`.
+///
+/// `source_text` must be the source `program` was parsed from; it is used to escape
+/// disallowed tags verbatim. Escaped text is allocated in `allocator`.
+/// Returns every change made, in document order.
+pub fn sanitize<'a>(
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  program: &mut Program<'a>,
+  options: &SanitizeOptions,
+) -> std::vec::Vec<Change> {
+  let mut sanitizer = Sanitizer {
+    allocator,
+    source_text,
+    options,
+    changes: std::vec::Vec::new(),
+  };
+
+  let nodes = mem::replace(program, Vec::new_in(allocator));
+  *program = sanitizer.sanitize_nodes(nodes);
+
+  sanitizer.changes
+}
+
+struct Sanitizer<'a, 'o> {
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  options: &'o SanitizeOptions,
+  changes: std::vec::Vec<Change>,
+}
+
+impl<'a> Sanitizer<'a, '_> {
+  fn change(&mut self, kind: ChangeKind, span: Span) {
+    self.changes.push(Change { kind, span });
+  }
+
+  fn sanitize_nodes(&mut self, nodes: Vec<'a, Node<'a>>) -> Vec<'a, Node<'a>> {
+    let mut result = Vec::with_capacity_in(nodes.len(), self.allocator);
+    for node in nodes {
+      self.sanitize_node(node, &mut result);
+    }
+    result
+  }
+
+  fn sanitize_node(&mut self, node: Node<'a>, result: &mut Vec<'a, Node<'a>>) {
+    match node {
+      Node::Doctype(_) | Node::Text(_) | Node::TemplateTag(_) => result.push(node),
+      Node::Comment(comment) => {
+        if self.options.allow_comments {
+          result.push(Node::Comment(comment));
+        } else {
+          self.change(ChangeKind::RemoveNode, comment.span);
+        }
+      }
+      // A comment to a browser
+      Node::Directive(directive) => {
+        if self.options.allow_comments {
+          result.push(Node::Directive(directive));
+        } else {
+          self.change(ChangeKind::RemoveNode, directive.span);
+        }
+      }
+      Node::RawUnknown(raw_unknown) => match self.options.disallowed_tag {
+        DisallowedTag::Strip => self.change(ChangeKind::RemoveNode, raw_unknown.span),
+        DisallowedTag::Escape => {
+          self.change(ChangeKind::Escape, raw_unknown.span);
+          result.push(self.escaped_text(raw_unknown.span));
+        }
+      },
+      // Handlebars tags are text to a browser, only the content of blocks is sanitized
+      Node::Mustache(mut mustache) => {
+        let children = mem::replace(&mut mustache.children, Vec::new_in(self.allocator));
+        let inverse = mem::replace(&mut mustache.inverse, Vec::new_in(self.allocator));
+        mustache.children = self.sanitize_nodes(children);
+        mustache.inverse = self.sanitize_nodes(inverse);
+        result.push(Node::Mustache(mustache));
+      }
+      Node::Script(mut script) => {
+        if self.options.allowed_tags.contains("script") {
+          self.sanitize_attributes("script", &mut script.attributes);
+          result.push(Node::Script(script));
+        } else {
+          // The content of a script is never meaningful as text
+          self.change(ChangeKind::RemoveNode, script.span);
+        }
+      }
+      Node::Element(mut element) => {
+        let tag_name = element.tag_name.to_ascii_lowercase();
+        let children = mem::replace(&mut element.children, Vec::new_in(self.allocator));
+
+        if self.options.allowed_tags.contains(&tag_name) {
+          self.sanitize_attributes(&tag_name, &mut element.attributes);
+          element.children = self.sanitize_nodes(children);
+          result.push(Node::Element(element));
+          return;
+        }
+
+        if self.options.clean_content_tags.contains(&tag_name) {
+          self.change(ChangeKind::RemoveNode, element.span);
+          return;
+        }
+
+        // The start tag ends where the first child starts, and the end tag (if any)
+        // starts where the last child ends
+        let start_tag_end = children
+          .first()
+          .map_or(element.span.end, |c| c.span().start);
+        let end_tag_start = children.last().map_or(element.span.end, |c| c.span().end);
+
+        match self.options.disallowed_tag {
+          DisallowedTag::Strip => {
+            self.change(ChangeKind::StripTags, element.span);
+            for child in children {
+              self.sanitize_node(child, result);
+            }
+          }
+          DisallowedTag::Escape => {
+            self.change(ChangeKind::Escape, element.span);
+            result.push(self.escaped_text(Span::new(element.span.start, start_tag_end)));
+            for child in children {
+              self.sanitize_node(child, result);
+            }
+            if end_tag_start < element.span.end {
+              result.push(self.escaped_text(Span::new(end_tag_start, element.span.end)));
+            }
+          }
+        }
+      }
+    }
+  }
+
+  fn sanitize_attributes(&mut self, tag_name: &str, attributes: &mut Vec<'a, Attribute<'a>>) {
+    let options = self.options;
+    let changes = &mut self.changes;
+
+    attributes.retain(|attribute| {
+      let name = attribute.key.value.to_ascii_lowercase();
+      let allowed = options.is_attribute_allowed(tag_name, &name)
+        && (!options.url_attributes.contains(&name)
+          || attribute
+            .value
+            .as_ref()
+            .is_none_or(|value| options.is_url_allowed(value.value)));
+
+      if !allowed {
+        changes.push(Change {
+          kind: ChangeKind::RemoveAttribute,
+          span: attribute.span,
+        });
+      }
+      allowed
+    });
+  }
+
+  /// Create a text node displaying the source of `span` literally.
+  fn escaped_text(&self, span: Span) -> Node<'a> {
+    let raw = &self.source_text[span.start as usize..span.end as usize];
+
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+      match c {
+        '&' => escaped.push_str("&amp;"),
+        '<' => escaped.push_str("&lt;"),
+        '>' => escaped.push_str("&gt;"),
+        '"' => escaped.push_str("&quot;"),
+        _ => escaped.push(c),
+      }
+    }
+
+    let text = Text {
+      span,
+      value: self.allocator.alloc_str(&escaped),
+    };
+    Node::Text(Box::new_in(text, self.allocator))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+  use oxc_allocator::Allocator;
+  use umc_html_parser::{CreateHtml, option::HtmlParserOption};
+  use umc_parser::Parser;
+
+  use crate::{DisallowedTag, SanitizeOptions, sanitize};
+
+  fn test(html: &str, options: &SanitizeOptions) -> String {
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, html).with_options(HtmlParserOption {
+      preserve_unknown: true,
+      ..HtmlParserOption::default()
+    });
+    let mut program = parser.parse().program;
+    let changes = sanitize(&allocator, html, &mut program, options);
+
+    format!("Nodes: {program:#?}\nChanges: {changes:#?}")
+  }
+
+  #[test]
+  fn strip() {
+    assert_snapshot!(test(
+      r#"<div onclick="x()" class="a"><font color="red">Hi <b>there</b></font><style>p {}</style><!-- c --></div>"#,
+      &SanitizeOptions::default()
+    ));
+  }
+
+  #[test]
+  fn escape() {
+    assert_snapshot!(test(
+      r#"<p><blink title="a&b">Hi</blink><?php x ?><script>x()</script></p>"#,
+      &SanitizeOptions {
+        disallowed_tag: DisallowedTag::Escape,
+        ..SanitizeOptions::default()
+      }
+    ));
+  }
+
+  #[test]
+  fn urls() {
+    assert_snapshot!(test(
+      r#"<a href="java&#x09;script:x()">a</a><a href="JavaScript:x()">b</a><a href="/relative?a:b">c</a><img src="https://example.com/a.png" alt>"#,
+      &SanitizeOptions::default()
+    ));
+  }
+
+  #[test]
+  fn is_url_allowed() {
+    let options = SanitizeOptions::default();
+
+    assert!(options.is_url_allowed("#top"));
+    assert!(options.is_url_allowed("mailto:a@example.com"));
+    assert!(options.is_url_allowed("./a:b"));
+    assert!(!options.is_url_allowed("java\nscript:x()"));
+    assert!(!options.is_url_allowed("data:text/html,x"));
+    assert!(!options.is_url_allowed("&#106;avascript:x()"));
+    assert!(!options.is_url_allowed("javascript&colon;alert(1)"));
+    assert!(!options.is_url_allowed("javascript&#58;alert(1)"));
+    assert!(!options.is_url_allowed("javascript&#x3A;alert(1)"));
+    assert!(options.is_url_allowed("/search?a=1&b=2"));
+  }
+}
@@ -0,0 +1,171 @@
+use std::collections::{HashMap, HashSet};
+
+/// What to do with an element whose tag is not allowed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DisallowedTag {
+  /// Remove the tags but keep the (sanitized) children
+  #[default]
+  Strip,
+  /// Keep the tags as escaped text, so they are displayed instead of interpreted
+  Escape,
+}
+
+/// Allowlists used by [`sanitize`](crate::sanitize).
+///
+/// Tag and attribute names are matched case-insensitively, and must be given in lower case.
+/// Everything not listed here is removed, including `<script>`, event handlers such as
+/// `onclick` and `style` attributes.
+#[derive(Debug, Clone)]
+pub struct SanitizeOptions {
+  /// Elements which are kept
+  pub allowed_tags: HashSet<String>,
+  /// Elements which are removed together with their content, even with [`DisallowedTag::Strip`].
+  ///
+  /// Their content is not meant to be displayed as text, e.g. `<style>`.
+  /// `<script>` is always removed with its content unless it is allowed.
+  pub clean_content_tags: HashSet<String>,
+  /// Attributes which are kept on every allowed element
+  pub allowed_attributes: HashSet<String>,
+  /// Attributes which are kept on specific allowed elements, by tag name
+  pub allowed_tag_attributes: HashMap<String, HashSet<String>>,
+  /// Attributes holding an URL, whose scheme is checked against [`allowed_url_schemes`](Self::allowed_url_schemes)
+  pub url_attributes: HashSet<String>,
+  /// URL schemes which are kept, e.g. `https`. Relative URLs are always kept.
+  pub allowed_url_schemes: HashSet<String>,
+  /// Keep comments
+  pub allow_comments: bool,
+  /// What to do with elements which are not allowed
+  pub disallowed_tag: DisallowedTag,
+}
+
+fn set(items: &[&str]) -> HashSet<String> {
+  items.iter().map(ToString::to_string).collect()
+}
+
+impl Default for SanitizeOptions {
+  /// Options allowing common formatting elements, links and images.
+  fn default() -> Self {
+    Self {
+      allowed_tags: set(&[
+        "a",
+        "abbr",
+        "b",
+        "blockquote",
+        "br",
+        "caption",
+        "code",
+        "dd",
+        "del",
+        "div",
+        "dl",
+        "dt",
+        "em",
+        "h1",
+        "h2",
+        "h3",
+        "h4",
+        "h5",
+        "h6",
+        "hr",
+        "i",
+        "img",
+        "ins",
+        "kbd",
+        "li",
+        "ol",
+        "p",
+        "pre",
+        "q",
+        "s",
+        "small",
+        "span",
+        "strong",
+        "sub",
+        "sup",
+        "table",
+        "tbody",
+        "td",
+        "tfoot",
+        "th",
+        "thead",
+        "tr",
+        "u",
+        "ul",
+      ]),
+      clean_content_tags: set(&["iframe", "noscript", "object", "embed", "style", "template"]),
+      allowed_attributes: set(&["lang", "title"]),
+      allowed_tag_attributes: HashMap::from([
+        ("a".to_string(), set(&["href", "hreflang"])),
+        ("blockquote".to_string(), set(&["cite"])),
+        ("img".to_string(), set(&["alt", "height", "src", "width"])),
+        ("ol".to_string(), set(&["start"])),
+        ("q".to_string(), set(&["cite"])),
+        ("td".to_string(), set(&["colspan", "rowspan"])),
+        ("th".to_string(), set(&["colspan", "rowspan"])),
+      ]),
+      url_attributes: set(&[
+        "action",
+        "background",
+        "cite",
+        "formaction",
+        "href",
+        "longdesc",
+        "poster",
+        "src",
+        "xlink:href",
+      ]),
+      allowed_url_schemes: set(&["http", "https", "mailto"]),
+      allow_comments: false,
+      disallowed_tag: DisallowedTag::Strip,
+    }
+  }
+}
+
+impl SanitizeOptions {
+  /// Whether `attribute` is allowed on `tag`. Both must be in lower case.
+  pub fn is_attribute_allowed(&self, tag: &str, attribute: &str) -> bool {
+    self.allowed_attributes.contains(attribute)
+      || self
+        .allowed_tag_attributes
+        .get(tag)
+        .is_some_and(|attributes| attributes.contains(attribute))
+  }
+
+  /// Whether `url` is relative or uses an allowed scheme.
+  ///
+  /// Whitespace and control characters are ignored like browsers do, so `java\tscript:` is
+  /// still detected. A URL with a character reference before its first `/`, `?` or `#` is
+  /// never allowed, since attribute values are not decoded, so `javascript&colon;` is not
+  /// taken for a relative URL.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_sanitize::SanitizeOptions;
+  ///
+  /// let options = SanitizeOptions::default();
+  /// assert!(options.is_url_allowed("/about"));
+  /// assert!(options.is_url_allowed("HTTPS://example.com"));
+  /// assert!(!options.is_url_allowed(" javascript:alert(1)"));
+  /// ```
+  pub fn is_url_allowed(&self, url: &str) -> bool {
+    let url: String = url
+      .chars()
+      .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+      .collect();
+
+    let prefix = &url[..url.find(['/', '?', '#']).unwrap_or(url.len())];
+    if prefix.contains('&') {
+      return false;
+    }
+    // `a/b:c`, `?x:y` and `#x:y` are relative
+    let Some(colon) = prefix.find(':') else {
+      return true;
+    };
+    let scheme = &prefix[..colon];
+
+    self
+      .allowed_url_schemes
+      .contains(&scheme.to_ascii_lowercase())
+  }
+}
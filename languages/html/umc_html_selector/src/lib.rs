@@ -0,0 +1,46 @@
+//! CSS selectors for HTML.
+//!
+//! Supports the selectors commonly used to find elements in a document:
+//!
+//! - type (`a`), universal (`*`), id (`#main`) and class (`.item`) selectors
+//! - attribute selectors with every operator (`[href]`, `[rel~=nofollow]`, `[lang|=en]`,
+//!   `[href^=https]`, `[src$=".png"]`, `[title*=x]`), and the `i` flag
+//! - the `:not()` pseudo-class
+//! - descendant (` `), child (`>`), next-sibling (`+`) and subsequent-sibling (`~`) combinators
+//!
+//! Selectors are matched against any tree implementing [`SelectorElement`].
+//!
+//! ## Example
+//!
+//! ```
+//! use umc_html_selector::{SelectorElement, SelectorList};
+//!
+//! struct Link;
+//!
+//! impl SelectorElement for Link {
+//!   fn tag_name(&self) -> &str {
+//!     "a"
+//!   }
+//!
+//!   fn attribute(&self, name: &str) -> Option<&str> {
+//!     (name == "href").then_some("https://example.com")
+//!   }
+//!
+//!   fn parent(&self) -> Option<Self> {
+//!     None
+//!   }
+//! }
+//!
+//! let selectors = SelectorList::parse(r#"a[href^="https:"]"#).unwrap();
+//! assert!(selectors.matches(&Link));
+//! ```
+
+mod matching;
+mod parse;
+mod selector;
+
+pub use matching::SelectorElement;
+pub use selector::{
+  AttributeOperator, AttributeSelector, Combinator, ComplexSelector, CompoundSelector,
+  SelectorList, SimpleSelector,
+};
@@ -0,0 +1,214 @@
+use crate::selector::{
+  AttributeOperator, AttributeSelector, Combinator, ComplexSelector, CompoundSelector,
+  SelectorList, SimpleSelector,
+};
+
+/// An element selectors can be matched against.
+///
+/// Implementations are usually cheap handles, like a reference or an index into a tree.
+pub trait SelectorElement: Sized {
+  /// The tag name of the element, compared ignoring ASCII case.
+  fn tag_name(&self) -> &str;
+
+  /// Get the value of an attribute, `Some("")` for an attribute without value.
+  ///
+  /// `name` is in lowercase, and attribute names should be compared ignoring ASCII case.
+  fn attribute(&self, name: &str) -> Option<&str>;
+
+  /// The parent element.
+  fn parent(&self) -> Option<Self>;
+
+  /// The closest previous sibling which is an element.
+  ///
+  /// Trees which don't know the siblings of an element may keep the default, so sibling
+  /// combinators never match.
+  fn previous_sibling(&self) -> Option<Self> {
+    None
+  }
+}
+
+impl SelectorList {
+  /// Whether `element` matches any of the selectors.
+  pub fn matches<E: SelectorElement>(&self, element: &E) -> bool {
+    self
+      .selectors
+      .iter()
+      .any(|selector| selector.matches(element))
+  }
+}
+
+impl ComplexSelector {
+  /// Whether `element` matches the selector.
+  pub fn matches<E: SelectorElement>(&self, element: &E) -> bool {
+    let Some((_, last)) = self.rest.last() else {
+      return self.first.matches(element);
+    };
+    last.matches(element) && self.matches_from(self.rest.len() - 1, element)
+  }
+
+  /// Match the part before `rest[index]`, given `element` matches `rest[index]`.
+  fn matches_from<E: SelectorElement>(&self, index: usize, element: &E) -> bool {
+    let (combinator, _) = &self.rest[index];
+    let compound = if index == 0 {
+      &self.first
+    } else {
+      &self.rest[index - 1].1
+    };
+    let matches_rest = |candidate: &E| {
+      compound.matches(candidate) && (index == 0 || self.matches_from(index - 1, candidate))
+    };
+
+    match combinator {
+      Combinator::Child => element.parent().is_some_and(|parent| matches_rest(&parent)),
+      Combinator::NextSibling => element
+        .previous_sibling()
+        .is_some_and(|sibling| matches_rest(&sibling)),
+      Combinator::Descendant => {
+        let mut current = element.parent();
+        while let Some(ancestor) = current {
+          if matches_rest(&ancestor) {
+            return true;
+          }
+          current = ancestor.parent();
+        }
+        false
+      }
+      Combinator::SubsequentSibling => {
+        let mut current = element.previous_sibling();
+        while let Some(sibling) = current {
+          if matches_rest(&sibling) {
+            return true;
+          }
+          current = sibling.previous_sibling();
+        }
+        false
+      }
+    }
+  }
+}
+
+impl CompoundSelector {
+  /// Whether `element` matches every simple selector.
+  pub fn matches<E: SelectorElement>(&self, element: &E) -> bool {
+    self.selectors.iter().all(|selector| match selector {
+      SimpleSelector::Universal => true,
+      SimpleSelector::Type(name) => element.tag_name().eq_ignore_ascii_case(name),
+      SimpleSelector::Id(id) => element.attribute("id") == Some(id.as_str()),
+      SimpleSelector::Class(class) => element
+        .attribute("class")
+        .is_some_and(|value| value.split_ascii_whitespace().any(|c| c == class)),
+      SimpleSelector::Attribute(attribute) => attribute.matches(element),
+      SimpleSelector::Not(list) => !list.matches(element),
+    })
+  }
+}
+
+impl AttributeSelector {
+  fn matches<E: SelectorElement>(&self, element: &E) -> bool {
+    let Some(actual) = element.attribute(&self.name) else {
+      return false;
+    };
+    let Some((operator, expected)) = &self.operator else {
+      return true;
+    };
+
+    let (actual, expected) = if self.case_insensitive {
+      (actual.to_ascii_lowercase(), expected.to_ascii_lowercase())
+    } else {
+      (actual.to_string(), expected.clone())
+    };
+
+    match operator {
+      AttributeOperator::Equals => actual == expected,
+      AttributeOperator::Includes => actual.split_ascii_whitespace().any(|w| w == expected),
+      AttributeOperator::DashMatch => {
+        actual == expected
+          || actual
+            .strip_prefix(&expected)
+            .is_some_and(|rest| rest.starts_with('-'))
+      }
+      // an empty value never matches these operators
+      AttributeOperator::Prefix => !expected.is_empty() && actual.starts_with(&expected),
+      AttributeOperator::Suffix => !expected.is_empty() && actual.ends_with(&expected),
+      AttributeOperator::Substring => !expected.is_empty() && actual.contains(&expected),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use crate::{SelectorElement, SelectorList};
+
+  /// `(tag name, attributes, parent)`, siblings share a parent.
+  type TreeNode = (
+    &'static str,
+    Vec<(&'static str, &'static str)>,
+    Option<usize>,
+  );
+
+  struct Tree(Vec<TreeNode>);
+
+  #[derive(Clone, Copy)]
+  struct Node<'t>(&'t Tree, usize);
+
+  impl SelectorElement for Node<'_> {
+    fn tag_name(&self) -> &str {
+      self.0.0[self.1].0
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+      let attributes = &self.0.0[self.1].1;
+      attributes.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+
+    fn parent(&self) -> Option<Self> {
+      self.0.0[self.1].2.map(|parent| Node(self.0, parent))
+    }
+
+    fn previous_sibling(&self) -> Option<Self> {
+      let parent = self.0.0[self.1].2;
+      (0..self.1)
+        .rev()
+        .find(|&i| self.0.0[i].2 == parent)
+        .map(|i| Node(self.0, i))
+    }
+  }
+
+  #[test]
+  fn matching() {
+    // <nav class="top main"><h1 id="title" lang="en-US">
+    //   <a href="https://a.com/x.png"></a><span></span><a data-x="Value"></a>
+    // </h1></nav>
+    let tree = Tree(vec![
+      ("nav", vec![("class", "top main")], None),
+      ("H1", vec![("id", "title"), ("lang", "en-US")], Some(0)),
+      ("a", vec![("href", "https://a.com/x.png")], Some(1)),
+      ("span", vec![], Some(1)),
+      ("a", vec![("data-x", "Value")], Some(1)),
+    ]);
+    let matched = |selector: &str| {
+      let selectors = SelectorList::parse(selector).unwrap();
+      (0..tree.0.len())
+        .filter(|&i| selectors.matches(&Node(&tree, i)))
+        .collect::<Vec<_>>()
+    };
+
+    assert_eq!(matched("*"), [0, 1, 2, 3, 4]);
+    assert_eq!(matched("h1"), [1]);
+    assert_eq!(matched("#title, .main"), [0, 1]);
+    assert_eq!(matched(".top.main > #title"), [1]);
+    assert_eq!(matched("nav a"), [2, 4]);
+    assert_eq!(matched("nav > a"), Vec::<usize>::new());
+    assert_eq!(matched("a + span"), [3]);
+    assert_eq!(matched("a ~ a"), [4]);
+    assert_eq!(matched("a[href]"), [2]);
+    assert_eq!(matched("[href^='https:'][href$='.png']"), [2]);
+    assert_eq!(matched("[href*=a\\.com]"), [2]);
+    assert_eq!(matched("[lang|=en]"), [1]);
+    assert_eq!(matched("[class~=main]"), [0]);
+    assert_eq!(matched("[data-x=value]"), Vec::<usize>::new());
+    assert_eq!(matched("[data-x=value i]"), [4]);
+    assert_eq!(matched("a:not([href])"), [4]);
+    assert_eq!(matched("nav :not(a, h1)"), [3]);
+  }
+}
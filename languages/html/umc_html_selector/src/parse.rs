@@ -0,0 +1,388 @@
+use std::str::FromStr;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_span::Span;
+
+use crate::selector::{
+  AttributeOperator, AttributeSelector, Combinator, ComplexSelector, CompoundSelector,
+  SelectorList, SimpleSelector,
+};
+
+impl SelectorList {
+  /// Parse a selector list, e.g. `ul > li.active, a[href]`.
+  ///
+  /// The span of the error is relative to `input`.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_html_selector::SelectorList;
+  ///
+  /// let selectors = SelectorList::parse("NAV > a[href^='https:'], .item").unwrap();
+  /// assert_eq!(selectors.selectors.len(), 2);
+  /// assert_eq!(selectors.to_string(), r#"nav > a[href^="https:"], .item"#);
+  ///
+  /// assert!(SelectorList::parse("a >").is_err());
+  /// ```
+  pub fn parse(input: &str) -> Result<Self, OxcDiagnostic> {
+    let mut parser = SelectorParser { input, position: 0 };
+    parser.skip_whitespace();
+    let list = parser.parse_list()?;
+
+    if let Some(c) = parser.peek() {
+      return Err(parser.unexpected(c));
+    }
+    Ok(list)
+  }
+}
+
+impl FromStr for SelectorList {
+  type Err = OxcDiagnostic;
+
+  fn from_str(input: &str) -> Result<Self, Self::Err> {
+    Self::parse(input)
+  }
+}
+
+struct SelectorParser<'a> {
+  input: &'a str,
+  position: usize,
+}
+
+impl SelectorParser<'_> {
+  fn parse_list(&mut self) -> Result<SelectorList, OxcDiagnostic> {
+    let mut selectors = vec![self.parse_complex()?];
+    while self.eat(',') {
+      self.skip_whitespace();
+      selectors.push(self.parse_complex()?);
+    }
+    Ok(SelectorList { selectors })
+  }
+
+  fn parse_complex(&mut self) -> Result<ComplexSelector, OxcDiagnostic> {
+    let first = self.parse_compound()?;
+    let mut rest = Vec::new();
+
+    loop {
+      let had_whitespace = self.skip_whitespace();
+      let combinator = match self.peek() {
+        Some('>') => Combinator::Child,
+        Some('+') => Combinator::NextSibling,
+        Some('~') => Combinator::SubsequentSibling,
+        Some(',' | ')') | None => break,
+        Some(_) if had_whitespace => {
+          rest.push((Combinator::Descendant, self.parse_compound()?));
+          continue;
+        }
+        Some(c) => return Err(self.unexpected(c)),
+      };
+      self.position += 1;
+      self.skip_whitespace();
+      rest.push((combinator, self.parse_compound()?));
+    }
+
+    Ok(ComplexSelector { first, rest })
+  }
+
+  fn parse_compound(&mut self) -> Result<CompoundSelector, OxcDiagnostic> {
+    let mut selectors = Vec::new();
+
+    if self.eat('*') {
+      selectors.push(SimpleSelector::Universal);
+    } else if self.at_ident_start() {
+      selectors.push(SimpleSelector::Type(
+        self.parse_ident()?.to_ascii_lowercase(),
+      ));
+    }
+
+    loop {
+      let selector = match self.peek() {
+        Some('#') => {
+          self.position += 1;
+          SimpleSelector::Id(self.parse_ident()?)
+        }
+        Some('.') => {
+          self.position += 1;
+          SimpleSelector::Class(self.parse_ident()?)
+        }
+        Some('[') => {
+          self.position += 1;
+          SimpleSelector::Attribute(self.parse_attribute()?)
+        }
+        Some(':') => self.parse_pseudo_class()?,
+        _ => break,
+      };
+      selectors.push(selector);
+    }
+
+    if selectors.is_empty() {
+      return Err(self.unexpected_here());
+    }
+    Ok(CompoundSelector { selectors })
+  }
+
+  fn parse_attribute(&mut self) -> Result<AttributeSelector, OxcDiagnostic> {
+    self.skip_whitespace();
+    let name = self.parse_ident()?.to_ascii_lowercase();
+    self.skip_whitespace();
+
+    let operator = match self.peek() {
+      Some(']') => {
+        self.position += 1;
+        return Ok(AttributeSelector {
+          name,
+          operator: None,
+          case_insensitive: false,
+        });
+      }
+      Some('=') => AttributeOperator::Equals,
+      Some('~') => AttributeOperator::Includes,
+      Some('|') => AttributeOperator::DashMatch,
+      Some('^') => AttributeOperator::Prefix,
+      Some('$') => AttributeOperator::Suffix,
+      Some('*') => AttributeOperator::Substring,
+      Some(c) => return Err(self.unexpected(c)),
+      None => return Err(self.unexpected_end()),
+    };
+    self.position += 1;
+    if operator != AttributeOperator::Equals {
+      self.expect('=')?;
+    }
+    self.skip_whitespace();
+
+    let value = match self.peek() {
+      Some(quote @ ('"' | '\'')) => {
+        self.position += 1;
+        self.parse_string(quote)?
+      }
+      _ => self.parse_ident()?,
+    };
+    self.skip_whitespace();
+
+    let case_insensitive = match self.peek() {
+      Some('i' | 'I') => true,
+      Some('s' | 'S') => false,
+      _ => {
+        self.expect(']')?;
+        return Ok(AttributeSelector {
+          name,
+          operator: Some((operator, value)),
+          case_insensitive: false,
+        });
+      }
+    };
+    self.position += 1;
+    self.skip_whitespace();
+    self.expect(']')?;
+
+    Ok(AttributeSelector {
+      name,
+      operator: Some((operator, value)),
+      case_insensitive,
+    })
+  }
+
+  fn parse_pseudo_class(&mut self) -> Result<SimpleSelector, OxcDiagnostic> {
+    let start = self.position;
+    self.position += 1;
+    let name = self.parse_ident()?;
+
+    if !name.eq_ignore_ascii_case("not") {
+      return Err(
+        OxcDiagnostic::error(format!("Unsupported pseudo-class `:{name}`"))
+          .with_label(Span::new(start as u32, self.position as u32)),
+      );
+    }
+
+    self.expect('(')?;
+    self.skip_whitespace();
+    let list = self.parse_list()?;
+    self.expect(')')?;
+    Ok(SimpleSelector::Not(list))
+  }
+
+  fn parse_ident(&mut self) -> Result<String, OxcDiagnostic> {
+    if !self.at_ident_start() {
+      return Err(self.unexpected_here());
+    }
+
+    let mut ident = String::new();
+    while let Some(c) = self.peek() {
+      if c == '\\' {
+        self.position += 1;
+        ident.push(self.parse_escape());
+      } else if is_ident_char(c) {
+        self.position += c.len_utf8();
+        ident.push(c);
+      } else {
+        break;
+      }
+    }
+    Ok(ident)
+  }
+
+  /// Parse a quoted string, after the opening quote.
+  fn parse_string(&mut self, quote: char) -> Result<String, OxcDiagnostic> {
+    let mut value = String::new();
+    loop {
+      match self.peek() {
+        Some(c) if c == quote => {
+          self.position += 1;
+          return Ok(value);
+        }
+        Some('\\') => {
+          self.position += 1;
+          value.push(self.parse_escape());
+        }
+        Some(c) => {
+          self.position += c.len_utf8();
+          value.push(c);
+        }
+        None => return Err(self.unexpected_end()),
+      }
+    }
+  }
+
+  /// Parse an escape, after the backslash.
+  /// https://www.w3.org/TR/css-syntax-3/#consume-escaped-code-point
+  fn parse_escape(&mut self) -> char {
+    let rest = &self.input[self.position..];
+    let hex_length = rest
+      .bytes()
+      .take(6)
+      .take_while(u8::is_ascii_hexdigit)
+      .count();
+
+    if hex_length > 0 {
+      let code = u32::from_str_radix(&rest[..hex_length], 16).unwrap_or(0);
+      self.position += hex_length;
+      // a single whitespace after a hex escape belongs to the escape
+      if self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+        self.position += 1;
+      }
+      return match char::from_u32(code) {
+        Some(c) if code != 0 => c,
+        _ => char::REPLACEMENT_CHARACTER,
+      };
+    }
+
+    match self.peek() {
+      Some(c) => {
+        self.position += c.len_utf8();
+        c
+      }
+      None => char::REPLACEMENT_CHARACTER,
+    }
+  }
+
+  fn at_ident_start(&self) -> bool {
+    let mut chars = self.input[self.position..].chars();
+    match chars.next() {
+      Some('-') => chars
+        .next()
+        .is_some_and(|c| c == '-' || c == '\\' || is_ident_start(c)),
+      Some(c) => c == '\\' || is_ident_start(c),
+      None => false,
+    }
+  }
+
+  fn peek(&self) -> Option<char> {
+    self.input[self.position..].chars().next()
+  }
+
+  fn eat(&mut self, expected: char) -> bool {
+    let matched = self.peek() == Some(expected);
+    if matched {
+      self.position += expected.len_utf8();
+    }
+    matched
+  }
+
+  fn expect(&mut self, expected: char) -> Result<(), OxcDiagnostic> {
+    if self.eat(expected) {
+      return Ok(());
+    }
+    let found = self
+      .peek()
+      .map_or_else(|| "end of input".to_string(), |c| format!("`{c}`"));
+    Err(
+      OxcDiagnostic::error(format!("Expected `{expected}`, but found {found}"))
+        .with_label(self.current_span()),
+    )
+  }
+
+  /// Skip whitespace, returning whether there was any.
+  fn skip_whitespace(&mut self) -> bool {
+    let start = self.position;
+    while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+      self.position += 1;
+    }
+    self.position > start
+  }
+
+  fn current_span(&self) -> Span {
+    let length = self.peek().map_or(0, char::len_utf8);
+    Span::sized(self.position as u32, length as u32)
+  }
+
+  fn unexpected(&self, c: char) -> OxcDiagnostic {
+    OxcDiagnostic::error(format!("Unexpected `{c}` in selector")).with_label(self.current_span())
+  }
+
+  fn unexpected_here(&self) -> OxcDiagnostic {
+    self
+      .peek()
+      .map_or_else(|| self.unexpected_end(), |c| self.unexpected(c))
+  }
+
+  fn unexpected_end(&self) -> OxcDiagnostic {
+    OxcDiagnostic::error("Unexpected end of selector").with_label(self.current_span())
+  }
+}
+
+const fn is_ident_start(c: char) -> bool {
+  c.is_ascii_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+const fn is_ident_char(c: char) -> bool {
+  is_ident_start(c) || c.is_ascii_digit() || c == '-'
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use crate::SelectorList;
+
+  #[test]
+  fn parse() {
+    let selectors = SelectorList::parse(
+      "ul>li.a.b  ,\n*[ lang |= \"en\" i ]:not(#x, .y z) ~ p + \\31 0 , [data-\\\"x*='a b']",
+    )
+    .unwrap();
+
+    let selectors: Vec<_> = selectors
+      .selectors
+      .iter()
+      .map(ToString::to_string)
+      .collect();
+    assert_snapshot!(selectors.join("\n"));
+  }
+
+  #[test]
+  fn errors() {
+    let errors: Vec<_> = [
+      "", "a,", "a >", "a..b", "[href", "[href=]", "[href='x", "a:hover", "a:not(b", "a)",
+    ]
+    .into_iter()
+    .map(|selector| {
+      let error = SelectorList::parse(selector).unwrap_err();
+      let label = error.labels.as_ref().and_then(|labels| labels.first());
+      let range = label.map(|label| label.offset()..label.offset() + label.len());
+      format!("{selector:?}: {} {range:?}", error.message)
+    })
+    .collect();
+
+    assert_snapshot!(errors.join("\n"));
+  }
+}
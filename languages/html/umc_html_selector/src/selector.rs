@@ -0,0 +1,205 @@
+use std::fmt::{self, Display};
+
+/// A comma separated list of selectors, e.g. `h1, h2 > a`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelectorList {
+  pub selectors: Vec<ComplexSelector>,
+}
+
+/// Compound selectors joined by combinators, e.g. `nav > a.active`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComplexSelector {
+  /// The first compound selector
+  pub first: CompoundSelector,
+  /// Following compound selectors, with the combinator before each of them
+  pub rest: Vec<(Combinator, CompoundSelector)>,
+}
+
+/// Simple selectors which all apply to the same element, e.g. `a.active[href]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompoundSelector {
+  pub selectors: Vec<SimpleSelector>,
+}
+
+/// A single condition on an element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimpleSelector {
+  /// `*`
+  Universal,
+  /// `div`, stored in lowercase
+  Type(String),
+  /// `#main`
+  Id(String),
+  /// `.item`
+  Class(String),
+  /// `[href]`, `[lang|=en]`
+  Attribute(AttributeSelector),
+  /// `:not(a, .item)`
+  Not(SelectorList),
+}
+
+/// An attribute selector, e.g. `[type="text" i]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributeSelector {
+  /// Attribute name, stored in lowercase
+  pub name: String,
+  /// The operator and the value to compare with, `None` for `[name]`
+  pub operator: Option<(AttributeOperator, String)>,
+  /// Whether the value is compared ignoring ASCII case, the `i` flag
+  pub case_insensitive: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeOperator {
+  /// `=`
+  Equals,
+  /// `~=`, one of the whitespace separated words
+  Includes,
+  /// `|=`, the value or the value followed by `-`
+  DashMatch,
+  /// `^=`
+  Prefix,
+  /// `$=`
+  Suffix,
+  /// `*=`
+  Substring,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combinator {
+  /// `a b`
+  Descendant,
+  /// `a > b`
+  Child,
+  /// `a + b`
+  NextSibling,
+  /// `a ~ b`
+  SubsequentSibling,
+}
+
+impl SelectorList {
+  /// Whether any selector depends on the siblings of an element.
+  ///
+  /// Useful for consumers which only know the ancestors of an element, like streaming
+  /// rewriters.
+  pub fn uses_siblings(&self) -> bool {
+    self.selectors.iter().any(ComplexSelector::uses_siblings)
+  }
+}
+
+impl ComplexSelector {
+  /// Whether the selector depends on the siblings of an element.
+  pub fn uses_siblings(&self) -> bool {
+    self.rest.iter().any(|(combinator, _)| {
+      matches!(
+        combinator,
+        Combinator::NextSibling | Combinator::SubsequentSibling
+      )
+    }) || self.compounds().any(CompoundSelector::uses_siblings)
+  }
+
+  /// Iterate the compound selectors, in source order.
+  pub fn compounds(&self) -> impl Iterator<Item = &CompoundSelector> {
+    std::iter::once(&self.first).chain(self.rest.iter().map(|(_, compound)| compound))
+  }
+}
+
+impl CompoundSelector {
+  fn uses_siblings(&self) -> bool {
+    self.selectors.iter().any(|selector| match selector {
+      SimpleSelector::Not(list) => list.uses_siblings(),
+      _ => false,
+    })
+  }
+}
+
+impl Display for SelectorList {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for (index, selector) in self.selectors.iter().enumerate() {
+      if index > 0 {
+        f.write_str(", ")?;
+      }
+      selector.fmt(f)?;
+    }
+    Ok(())
+  }
+}
+
+impl Display for ComplexSelector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    self.first.fmt(f)?;
+    for (combinator, compound) in &self.rest {
+      match combinator {
+        Combinator::Descendant => f.write_str(" ")?,
+        Combinator::Child => f.write_str(" > ")?,
+        Combinator::NextSibling => f.write_str(" + ")?,
+        Combinator::SubsequentSibling => f.write_str(" ~ ")?,
+      }
+      compound.fmt(f)?;
+    }
+    Ok(())
+  }
+}
+
+impl Display for CompoundSelector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    for selector in &self.selectors {
+      match selector {
+        SimpleSelector::Universal => f.write_str("*")?,
+        SimpleSelector::Type(name) => write_ident(f, name)?,
+        SimpleSelector::Id(id) => {
+          f.write_str("#")?;
+          write_ident(f, id)?;
+        }
+        SimpleSelector::Class(class) => {
+          f.write_str(".")?;
+          write_ident(f, class)?;
+        }
+        SimpleSelector::Attribute(attribute) => attribute.fmt(f)?,
+        SimpleSelector::Not(list) => write!(f, ":not({list})")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+impl Display for AttributeSelector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.write_str("[")?;
+    write_ident(f, &self.name)?;
+    if let Some((operator, value)) = &self.operator {
+      let operator = match operator {
+        AttributeOperator::Equals => "=",
+        AttributeOperator::Includes => "~=",
+        AttributeOperator::DashMatch => "|=",
+        AttributeOperator::Prefix => "^=",
+        AttributeOperator::Suffix => "$=",
+        AttributeOperator::Substring => "*=",
+      };
+      write!(f, "{operator}{value:?}")?;
+      if self.case_insensitive {
+        f.write_str(" i")?;
+      }
+    }
+    f.write_str("]")
+  }
+}
+
+/// Write an identifier, escaping characters which can't appear in it as written.
+fn write_ident(f: &mut fmt::Formatter<'_>, ident: &str) -> fmt::Result {
+  for (index, c) in ident.chars().enumerate() {
+    let is_valid = c.is_ascii_alphabetic()
+      || c == '_'
+      || !c.is_ascii()
+      || (index > 0 && (c.is_ascii_digit() || c == '-'))
+      || (index == 0 && c == '-' && ident.len() > 1);
+    if is_valid {
+      write!(f, "{c}")?;
+    } else if c.is_ascii_digit() {
+      write!(f, "\\{:x} ", u32::from(c))?;
+    } else {
+      write!(f, "\\{c}")?;
+    }
+  }
+  Ok(())
+}
@@ -0,0 +1,339 @@
+use std::borrow::Cow;
+
+use umc_html_parser::{
+  encoding::Encoding,
+  option::HtmlParserOption,
+  stream::{HtmlStreamParser, StreamEvent},
+};
+
+use crate::{Attribute, NodeOrText, TreeSink};
+
+/// Parse a whole document into `sink`.
+pub fn parse_document<S: TreeSink>(sink: S, html: &str) -> S::Output {
+  let mut builder = TreeBuilder::new(sink);
+  builder.feed(html);
+  builder.finish()
+}
+
+/// Builds a [`TreeSink`] from a document fed in chunks.
+///
+/// ## Example
+///
+/// ```
+/// # use std::{borrow::Cow, cell::Cell};
+/// # use umc_html_sink::{Attribute, NodeOrText, TreeSink};
+/// # #[derive(Default)]
+/// # struct TextLength(Cell<usize>);
+/// # impl TreeSink for TextLength {
+/// #   type Handle = ();
+/// #   type Output = usize;
+/// #   fn finish(self) -> usize { self.0.get() }
+/// #   fn parse_error(&self, _: Cow<'static, str>) {}
+/// #   fn get_document(&self) {}
+/// #   fn create_element(&self, _: &str, _: Vec<Attribute<'_>>) {}
+/// #   fn create_comment(&self, _: &str) {}
+/// #   fn append(&self, _: &(), child: NodeOrText<'_, ()>) {
+/// #     if let NodeOrText::AppendText(text) = child {
+/// #       self.0.set(self.0.get() + text.len());
+/// #     }
+/// #   }
+/// #   fn append_doctype_to_document(&self, _: &str, _: &str, _: &str) {}
+/// # }
+/// use umc_html_sink::TreeBuilder;
+///
+/// let mut builder = TreeBuilder::new(TextLength::default());
+/// for chunk in ["<p>Hel", "lo <b>wor", "ld</b></p>"] {
+///   builder.feed(chunk);
+/// }
+/// assert_eq!(builder.finish(), "Hello world".len());
+/// ```
+pub struct TreeBuilder<S: TreeSink> {
+  sink: S,
+  parser: HtmlStreamParser,
+  /// Open elements with their lowercase tag names, the innermost last
+  open: Vec<(String, S::Handle)>,
+  /// Number of parser errors already reported to the sink
+  reported_errors: usize,
+}
+
+impl<S: TreeSink> TreeBuilder<S> {
+  /// Create a builder appending to the document of `sink`.
+  pub fn new(sink: S) -> Self {
+    Self {
+      sink,
+      parser: HtmlStreamParser::default(),
+      open: Vec::new(),
+      reported_errors: 0,
+    }
+  }
+
+  /// Use parser options, e.g. a custom set of void tags.
+  #[must_use]
+  pub fn with_options(mut self, options: HtmlParserOption) -> Self {
+    self.parser = HtmlStreamParser::new(options);
+    self
+  }
+
+  /// Decode the input of [`feed_bytes`](Self::feed_bytes) with `encoding` instead of UTF-8.
+  #[must_use]
+  pub fn with_encoding(mut self, encoding: &'static Encoding) -> Self {
+    self.parser = self.parser.with_encoding(encoding);
+    self
+  }
+
+  /// The sink being built.
+  pub const fn sink(&self) -> &S {
+    &self.sink
+  }
+
+  /// Feed a chunk of text.
+  pub fn feed(&mut self, chunk: &str) {
+    self.parser.feed(chunk);
+    self.process();
+  }
+
+  /// Feed a chunk of bytes.
+  pub fn feed_bytes(&mut self, chunk: &[u8]) {
+    self.parser.feed_bytes(chunk);
+    self.process();
+  }
+
+  /// Mark the end of the input, and return the output of the sink.
+  ///
+  /// Elements left open are closed silently, as the AST parser does.
+  pub fn finish(mut self) -> S::Output {
+    self.parser.end();
+    self.process();
+    self.sink.finish()
+  }
+
+  fn process(&mut self) {
+    while let Some(event) = self.parser.next_event() {
+      let parent = self
+        .open
+        .last()
+        .map_or_else(|| self.sink.get_document(), |(_, handle)| handle.clone());
+
+      match event {
+        StreamEvent::Doctype { attributes, .. } => {
+          let words: Vec<&str> = attributes
+            .iter()
+            .map(|attribute| attribute.key.value)
+            .collect();
+          let (name, public_id, system_id) = doctype_ids(&words);
+          self
+            .sink
+            .append_doctype_to_document(&name, public_id, system_id);
+        }
+        StreamEvent::StartTag {
+          name,
+          attributes,
+          self_closing,
+          void,
+          ..
+        } => {
+          let name = name.to_ascii_lowercase();
+          let attributes = attributes
+            .iter()
+            .map(|attribute| Attribute {
+              name: lowercase(attribute.key.value),
+              value: attribute.value.as_ref().map_or("", |value| value.value),
+            })
+            .collect();
+          let element = self.sink.create_element(&name, attributes);
+          self
+            .sink
+            .append(&parent, NodeOrText::AppendNode(element.clone()));
+          if !self_closing && !void {
+            self.open.push((name, element));
+          }
+        }
+        StreamEvent::EndTag { name, .. } => {
+          if let Some(index) = self
+            .open
+            .iter()
+            .rposition(|(open, _)| open.eq_ignore_ascii_case(name))
+          {
+            self.open.truncate(index);
+          } else {
+            self.sink.parse_error(Cow::Owned(format!(
+              "Unexpected closing tag `{name}` without matching start tag"
+            )));
+          }
+        }
+        StreamEvent::Text { value, .. } => {
+          self.sink.append(&parent, NodeOrText::AppendText(value));
+        }
+        StreamEvent::Comment { value, .. } => {
+          let comment = self.sink.create_comment(value);
+          self.sink.append(&parent, NodeOrText::AppendNode(comment));
+        }
+      }
+    }
+
+    for error in &self.parser.errors[self.reported_errors..] {
+      self.sink.parse_error(Cow::Owned(error.message.to_string()));
+    }
+    self.reported_errors = self.parser.errors.len();
+  }
+}
+
+fn lowercase(name: &str) -> Cow<'_, str> {
+  if name.bytes().any(|byte| byte.is_ascii_uppercase()) {
+    Cow::Owned(name.to_ascii_lowercase())
+  } else {
+    Cow::Borrowed(name)
+  }
+}
+
+/// Split the words of a doctype, like `html PUBLIC "-//W3C//DTD HTML 4.01//EN" "..."`, into
+/// its name, public identifier and system identifier.
+fn doctype_ids<'a>(words: &[&'a str]) -> (String, &'a str, &'a str) {
+  let name = words
+    .iter()
+    .find(|word| !word.eq_ignore_ascii_case("doctype"))
+    .map(|word| word.to_ascii_lowercase())
+    .unwrap_or_default();
+  let unquote = |word: &&'a str| word.trim_matches(|c| c == '"' || c == '\'');
+
+  let keyword = words
+    .iter()
+    .position(|word| word.eq_ignore_ascii_case("public") || word.eq_ignore_ascii_case("system"));
+  let Some(keyword) = keyword else {
+    return (name, "", "");
+  };
+  let mut ids = words[keyword + 1..].iter().map(unquote);
+
+  if words[keyword].eq_ignore_ascii_case("public") {
+    let public_id = ids.next().unwrap_or("");
+    (name, public_id, ids.next().unwrap_or(""))
+  } else {
+    (name, "", ids.next().unwrap_or(""))
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::{borrow::Cow, cell::RefCell, fmt::Write};
+
+  use insta::assert_snapshot;
+
+  use crate::{Attribute, NodeOrText, TreeBuilder, TreeSink, parse_document};
+
+  /// A DOM stored as a list of nodes, each with the indexes of its children.
+  #[derive(Default)]
+  struct TestDom {
+    nodes: RefCell<Vec<(String, Vec<usize>)>>,
+    errors: RefCell<Vec<String>>,
+  }
+
+  impl TestDom {
+    fn push(&self, node: String) -> usize {
+      let mut nodes = self.nodes.borrow_mut();
+      nodes.push((node, Vec::new()));
+      nodes.len() - 1
+    }
+
+    fn print(&self, output: &mut String, node: usize, depth: usize) {
+      let nodes = self.nodes.borrow();
+      writeln!(output, "{}{}", "  ".repeat(depth), nodes[node].0).unwrap();
+      for &child in &nodes[node].1 {
+        self.print(output, child, depth + 1);
+      }
+    }
+  }
+
+  impl TreeSink for TestDom {
+    type Handle = usize;
+    type Output = String;
+
+    fn finish(self) -> String {
+      let mut output = String::new();
+      self.print(&mut output, 0, 0);
+      for error in self.errors.borrow().iter() {
+        writeln!(output, "error: {error}").unwrap();
+      }
+      output
+    }
+
+    fn parse_error(&self, message: Cow<'static, str>) {
+      self.errors.borrow_mut().push(message.into_owned());
+    }
+
+    fn get_document(&self) -> usize {
+      if self.nodes.borrow().is_empty() {
+        self.push("#document".to_string());
+      }
+      0
+    }
+
+    fn create_element(&self, name: &str, attributes: Vec<Attribute<'_>>) -> usize {
+      let mut node = format!("<{name}");
+      for attribute in attributes {
+        write!(node, " {}={:?}", attribute.name, attribute.value).unwrap();
+      }
+      node.push('>');
+      self.push(node)
+    }
+
+    fn create_comment(&self, text: &str) -> usize {
+      self.push(format!("<!-- {text} -->"))
+    }
+
+    fn append(&self, parent: &usize, child: NodeOrText<'_, usize>) {
+      let child = match child {
+        NodeOrText::AppendNode(child) => child,
+        NodeOrText::AppendText(text) => {
+          let mut nodes = self.nodes.borrow_mut();
+          // merge with the text right before
+          if let Some(&last) = nodes[*parent].1.last()
+            && nodes[last].0.starts_with('"')
+          {
+            let value = &mut nodes[last].0;
+            value.pop();
+            value.push_str(&format!("{text:?}")[1..]);
+            return;
+          }
+          drop(nodes);
+          self.push(format!("{text:?}"))
+        }
+      };
+      self.nodes.borrow_mut()[*parent].1.push(child);
+    }
+
+    fn append_doctype_to_document(&self, name: &str, public_id: &str, system_id: &str) {
+      let doctype = self.push(format!("<!DOCTYPE {name} {public_id:?} {system_id:?}>"));
+      let document = self.get_document();
+      self.nodes.borrow_mut()[document].1.push(doctype);
+    }
+  }
+
+  const HTML: &str = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD HTML 4.01//EN" "http://www.w3.org/TR/html4/strict.dtd">
+<HTML Lang=en>
+<body>
+  <!-- nav -->
+  <P CLASS="intro" hidden>Hello <b>world</b><br>!</p>
+  <svg><path d="M0 0"/></svg>
+  <script>if (a < b) {}</script>
+  </div>
+  <p>unclosed
+</body>
+</html>"#;
+
+  #[test]
+  fn build_document() {
+    assert_snapshot!(parse_document(TestDom::default(), HTML));
+  }
+
+  #[test]
+  fn chunk_boundaries() {
+    let expected = parse_document(TestDom::default(), HTML);
+    for size in 1..HTML.len() {
+      let mut builder = TreeBuilder::new(TestDom::default());
+      for chunk in HTML.as_bytes().chunks(size) {
+        builder.feed_bytes(chunk);
+      }
+      assert_eq!(builder.finish(), expected, "chunk size {size}");
+    }
+  }
+}
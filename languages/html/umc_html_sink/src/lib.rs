@@ -0,0 +1,110 @@
+//! Build foreign DOM trees with the UMC lexer.
+//!
+//! [`TreeSink`] mirrors the tree building interface of html5ever, so DOMs already written
+//! for it, like the ones of `scraper` or kuchikiki, can be driven by [`TreeBuilder`] with a
+//! thin forwarding implementation instead of rewriting their tree types.
+//!
+//! The builder consumes the events of
+//! [`HtmlStreamParser`](umc_html_parser::stream::HtmlStreamParser), so documents can be
+//! fed in chunks. Like the rest of UMC, it nests elements as written: end tags the HTML
+//! standard would imply are not inferred, and `<html>`, `<head>` and `<body>` are not
+//! inserted when missing. Character references are not decoded.
+//!
+//! ## Example
+//!
+//! ```
+//! use std::{borrow::Cow, cell::RefCell};
+//!
+//! use umc_html_sink::{Attribute, NodeOrText, TreeSink, parse_document};
+//!
+//! /// Collects the tag names of the elements, in document order.
+//! #[derive(Default)]
+//! struct TagNames(RefCell<Vec<String>>);
+//!
+//! impl TreeSink for TagNames {
+//!   type Handle = ();
+//!   type Output = Vec<String>;
+//!
+//!   fn finish(self) -> Self::Output {
+//!     self.0.into_inner()
+//!   }
+//!
+//!   fn parse_error(&self, _message: Cow<'static, str>) {}
+//!
+//!   fn get_document(&self) -> Self::Handle {}
+//!
+//!   fn create_element(&self, name: &str, _attributes: Vec<Attribute<'_>>) -> Self::Handle {
+//!     self.0.borrow_mut().push(name.to_string());
+//!   }
+//!
+//!   fn create_comment(&self, _text: &str) -> Self::Handle {}
+//!
+//!   fn append(&self, _parent: &Self::Handle, _child: NodeOrText<'_, Self::Handle>) {}
+//!
+//!   fn append_doctype_to_document(&self, _name: &str, _public_id: &str, _system_id: &str) {}
+//! }
+//!
+//! let names = parse_document(TagNames::default(), "<UL><li>One</li><li>Two</li></UL>");
+//! assert_eq!(names, ["ul", "li", "li"]);
+//! ```
+
+mod builder;
+
+use std::borrow::Cow;
+
+pub use builder::{TreeBuilder, parse_document};
+
+/// A tree which can be built from parse events.
+///
+/// The methods follow `html5ever::tree_builder::TreeSink`, taking `&self` so handles can
+/// point into the tree while it is built. Only the methods needed without the insertion
+/// mode rules of the HTML standard are required.
+pub trait TreeSink {
+  /// A reference to a node of the tree.
+  type Handle: Clone;
+
+  /// The result of parsing, returned by [`TreeBuilder::finish`].
+  type Output;
+
+  /// Consume the sink once the document is built.
+  fn finish(self) -> Self::Output;
+
+  /// Report a parse error.
+  fn parse_error(&self, message: Cow<'static, str>);
+
+  /// The document node, the parent of top level nodes.
+  fn get_document(&self) -> Self::Handle;
+
+  /// Create an element, which is appended to its parent afterwards.
+  ///
+  /// `name` and the attribute names are in ASCII lowercase.
+  fn create_element(&self, name: &str, attributes: Vec<Attribute<'_>>) -> Self::Handle;
+
+  /// Create a comment, which is appended to its parent afterwards.
+  fn create_comment(&self, text: &str) -> Self::Handle;
+
+  /// Append a node or text as the last child of `parent`.
+  ///
+  /// Text of the same run may be appended in several pieces, which should be merged with
+  /// a text node right before it.
+  fn append(&self, parent: &Self::Handle, child: NodeOrText<'_, Self::Handle>);
+
+  /// Append a `<!DOCTYPE>` to the document.
+  fn append_doctype_to_document(&self, name: &str, public_id: &str, system_id: &str);
+}
+
+/// A child appended by [`TreeSink::append`].
+#[derive(Debug)]
+pub enum NodeOrText<'s, Handle> {
+  AppendNode(Handle),
+  AppendText(&'s str),
+}
+
+/// An attribute of an element created by [`TreeSink::create_element`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute<'s> {
+  /// The name, in ASCII lowercase
+  pub name: Cow<'s, str>,
+  /// The value without quotes, empty for an attribute without value
+  pub value: &'s str,
+}
@@ -0,0 +1,198 @@
+//! Visiting elements by tag name.
+//!
+//! Visitors that only look at a few tags register handlers on an [`ElementDispatch`]
+//! instead of comparing the name of every element. Known tag names are interned as a
+//! [`Tag`] with a single `match`, and handlers are looked up by its index, so elements
+//! without handlers cost no call.
+
+use std::collections::HashMap;
+
+use umc_html_ast::Element;
+use umc_traverse::TraverseOperate;
+
+use crate::TraverseHtml;
+
+macro_rules! tags {
+  ($($tag:ident => $name:literal),* $(,)?) => {
+    /// An [HTML element](https://html.spec.whatwg.org/multipage/indices.html#elements-3),
+    /// its tag name interned.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Tag {
+      $($tag),*
+    }
+
+    impl Tag {
+      /// Every tag, in the order of their names.
+      pub const ALL: &[Self] = &[$(Self::$tag),*];
+
+      /// Get the tag of a lowercase tag name, like [`Element::name`].
+      pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+          $($name => Some(Self::$tag),)*
+          _ => None,
+        }
+      }
+
+      pub const fn name(self) -> &'static str {
+        match self {
+          $(Self::$tag => $name),*
+        }
+      }
+    }
+  };
+}
+
+tags! {
+  A => "a", Abbr => "abbr", Address => "address", Area => "area", Article => "article",
+  Aside => "aside", Audio => "audio", B => "b", Base => "base", Bdi => "bdi", Bdo => "bdo",
+  Blockquote => "blockquote", Body => "body", Br => "br", Button => "button",
+  Canvas => "canvas", Caption => "caption", Cite => "cite", Code => "code", Col => "col",
+  Colgroup => "colgroup", Data => "data", Datalist => "datalist", Dd => "dd", Del => "del",
+  Details => "details", Dfn => "dfn", Dialog => "dialog", Div => "div", Dl => "dl",
+  Dt => "dt", Em => "em", Embed => "embed", Fieldset => "fieldset",
+  Figcaption => "figcaption", Figure => "figure", Footer => "footer", Form => "form",
+  H1 => "h1", H2 => "h2", H3 => "h3", H4 => "h4", H5 => "h5", H6 => "h6", Head => "head",
+  Header => "header", Hgroup => "hgroup", Hr => "hr", Html => "html", I => "i",
+  Iframe => "iframe", Img => "img", Input => "input", Ins => "ins", Kbd => "kbd",
+  Label => "label", Legend => "legend", Li => "li", Link => "link", Main => "main",
+  Map => "map", Mark => "mark", Math => "math", Menu => "menu", Meta => "meta",
+  Meter => "meter", Nav => "nav", Noscript => "noscript", Object => "object", Ol => "ol",
+  Optgroup => "optgroup", Option => "option", Output => "output", P => "p",
+  Picture => "picture", Pre => "pre", Progress => "progress", Q => "q", Rp => "rp",
+  Rt => "rt", Ruby => "ruby", S => "s", Samp => "samp", Script => "script",
+  Search => "search", Section => "section", Select => "select", Slot => "slot",
+  Small => "small", Source => "source", Span => "span", Strong => "strong", Style => "style",
+  Sub => "sub", Summary => "summary", Sup => "sup", Svg => "svg", Table => "table",
+  Tbody => "tbody", Td => "td", Template => "template", Textarea => "textarea",
+  Tfoot => "tfoot", Th => "th", Thead => "thead", Time => "time", Title => "title",
+  Tr => "tr", Track => "track", U => "u", Ul => "ul", Var => "var", Video => "video",
+  Wbr => "wbr",
+}
+
+/// Handlers by tag name: by [`Tag`] for known tags, by name for the others, like custom
+/// elements.
+struct Handlers<H> {
+  tags: Vec<Vec<H>>,
+  others: HashMap<String, Vec<H>>,
+}
+
+impl<H> Default for Handlers<H> {
+  fn default() -> Self {
+    Self {
+      tags: Vec::new(),
+      others: HashMap::new(),
+    }
+  }
+}
+
+impl<H> Handlers<H> {
+  fn push(&mut self, name: &str, handler: H) {
+    if let Some(tag) = Tag::from_name(name) {
+      let index = tag as usize;
+      if self.tags.len() <= index {
+        self.tags.resize_with(index + 1, Vec::new);
+      }
+      self.tags[index].push(handler);
+    } else {
+      self
+        .others
+        .entry(name.to_string())
+        .or_default()
+        .push(handler);
+    }
+  }
+
+  fn get_mut(&mut self, name: &str) -> &mut [H] {
+    let handlers = match Tag::from_name(name) {
+      Some(tag) => self.tags.get_mut(tag as usize),
+      None if self.others.is_empty() => None,
+      None => self.others.get_mut(name),
+    };
+    handlers.map_or(&mut [], Vec::as_mut_slice)
+  }
+}
+
+type EnterHandler<'h, 'a> = Box<dyn FnMut(&Element<'a>) -> TraverseOperate + 'h>;
+type ExitHandler<'h, 'a> = Box<dyn FnMut(&Element<'a>) + 'h>;
+
+/// A walk calling handlers registered by tag name.
+///
+/// Tag names are matched against [`Element::name`], which is lowercase for HTML. Scripts
+/// are not elements of the tree, see [`TraverseHtml::enter_script`].
+///
+/// ## Example
+///
+/// ```
+/// use std::cell::RefCell;
+///
+/// use oxc_allocator::Allocator;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::dispatch::ElementDispatch;
+/// use umc_html_traverse::traverse_program;
+/// use umc_parser::Parser;
+/// use umc_traverse::TraverseOperate;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, r#"<a href="/">Home</a><img src="a.png"><my-icon></my-icon>"#);
+/// let program = parser.parse().program;
+///
+/// let names = RefCell::new(Vec::new());
+/// let mut dispatch = ElementDispatch::new()
+///   .enter_element_named("a", |_| {
+///     names.borrow_mut().push("a");
+///     TraverseOperate::Continue
+///   })
+///   .exit_element_named("img", |_| names.borrow_mut().push("img"))
+///   .exit_element_named("my-icon", |_| names.borrow_mut().push("my-icon"));
+/// traverse_program(&program, &mut dispatch);
+///
+/// assert_eq!(*names.borrow(), ["a", "img", "my-icon"]);
+/// ```
+#[derive(Default)]
+pub struct ElementDispatch<'h, 'a> {
+  enter: Handlers<EnterHandler<'h, 'a>>,
+  exit: Handlers<ExitHandler<'h, 'a>>,
+}
+
+impl<'h, 'a> ElementDispatch<'h, 'a> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Call `handler` when entering the elements named `name`, skipping their children and
+  /// exit handlers if it returns [`TraverseOperate::Skip`].
+  #[must_use]
+  pub fn enter_element_named(
+    mut self,
+    name: &str,
+    handler: impl FnMut(&Element<'a>) -> TraverseOperate + 'h,
+  ) -> Self {
+    self.enter.push(name, Box::new(handler));
+    self
+  }
+
+  /// Call `handler` when exiting the elements named `name`.
+  #[must_use]
+  pub fn exit_element_named(mut self, name: &str, handler: impl FnMut(&Element<'a>) + 'h) -> Self {
+    self.exit.push(name, Box::new(handler));
+    self
+  }
+}
+
+impl<'a> TraverseHtml<'a> for ElementDispatch<'_, 'a> {
+  fn enter_element(&mut self, element: &Element<'a>) -> TraverseOperate {
+    let mut operate = TraverseOperate::Continue;
+    for handler in self.enter.get_mut(element.name()) {
+      if handler(element) == TraverseOperate::Skip {
+        operate = TraverseOperate::Skip;
+      }
+    }
+    operate
+  }
+
+  fn exit_element(&mut self, element: &Element<'a>) {
+    for handler in self.exit.get_mut(element.name()) {
+      handler(element);
+    }
+  }
+}
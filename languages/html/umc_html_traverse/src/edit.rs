@@ -0,0 +1,260 @@
+//! Structural edits of a tree: removing, replacing and inserting nodes.
+//!
+//! A walk borrows the children of the nodes it visits, so it cannot remove a node or insert
+//! siblings. Edits are instead queued with the path of their node, the indexes of the node
+//! and its ancestors among their siblings, and applied once the walk is over.
+
+use std::collections::BTreeMap;
+
+use oxc_allocator::Vec as ArenaVec;
+use umc_html_ast::{Node, Program};
+use umc_traverse::TraverseOperate;
+
+/// Edits of the nodes of a tree, applied with [`apply`](Self::apply).
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::{Allocator, Box};
+/// use umc_html_ast::{Node, Text};
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::edit::EditQueue;
+/// use umc_parser::Parser;
+/// use umc_span::SPAN;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<i></i><b></b><u></u>");
+/// let mut program = parser.parse().program;
+/// let text = |value| Node::Text(Box::new_in(Text { span: SPAN, value }, &allocator));
+///
+/// let mut edits = EditQueue::new();
+/// edits.insert_after(&[0], text("1"));
+/// edits.insert_before(&[1], text("2"));
+/// edits.insert_before(&[1], text("3"));
+/// edits.replace(&[1], text("4"));
+/// edits.remove(&[2]);
+/// edits.apply(&mut program);
+///
+/// let values: Vec<_> = program[1..]
+///   .iter()
+///   .map(|node| match node {
+///     Node::Text(text) => text.value,
+///     _ => unreachable!(),
+///   })
+///   .collect();
+/// assert_eq!(values, ["1", "2", "3", "4"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct EditQueue<'a> {
+  edits: BTreeMap<Vec<usize>, NodeEdits<'a>>,
+}
+
+#[derive(Debug, Default)]
+struct NodeEdits<'a> {
+  before: Vec<Node<'a>>,
+  action: Action<'a>,
+  after: Vec<Node<'a>>,
+}
+
+#[derive(Debug, Default)]
+enum Action<'a> {
+  #[default]
+  Keep,
+  Remove,
+  Replace(Node<'a>),
+}
+
+impl<'a> EditQueue<'a> {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.edits.is_empty()
+  }
+
+  fn at(&mut self, path: &[usize]) -> &mut NodeEdits<'a> {
+    self.edits.entry(path.to_vec()).or_default()
+  }
+
+  /// Remove the node at `path`, and whatever was queued for its descendants.
+  pub fn remove(&mut self, path: &[usize]) {
+    self.at(path).action = Action::Remove;
+  }
+
+  /// Replace the node at `path`, a later replacement or removal winning.
+  pub fn replace(&mut self, path: &[usize], node: Node<'a>) {
+    self.at(path).action = Action::Replace(node);
+  }
+
+  /// Insert a sibling before the node at `path`, after the siblings inserted before.
+  pub fn insert_before(&mut self, path: &[usize], node: Node<'a>) {
+    self.at(path).before.push(node);
+  }
+
+  /// Insert a sibling after the node at `path`, after the siblings inserted before.
+  pub fn insert_after(&mut self, path: &[usize], node: Node<'a>) {
+    self.at(path).after.push(node);
+  }
+
+  /// Apply the edits to the tree their paths were taken in, ignoring paths not found in it.
+  pub fn apply(self, program: &mut Program<'a>) {
+    // from the last path, so that editing a node does not move the nodes left to edit
+    for (path, edits) in self.edits.into_iter().rev() {
+      let Some((&index, parents)) = path.split_last() else {
+        continue;
+      };
+      let Some(siblings) = children_mut(program, parents) else {
+        continue;
+      };
+      if index >= siblings.len() {
+        continue;
+      }
+      let NodeEdits {
+        before,
+        action,
+        after,
+      } = edits;
+      let node = siblings.remove(index);
+      let node = match action {
+        Action::Keep => Some(node),
+        Action::Remove => None,
+        Action::Replace(replacement) => Some(replacement),
+      };
+      let nodes = before.into_iter().chain(node).chain(after);
+      siblings.splice(index..index, nodes);
+    }
+  }
+}
+
+/// Get the children of the node at `path`, the program itself for an empty path.
+fn children_mut<'p, 'a>(
+  program: &'p mut Program<'a>,
+  path: &[usize],
+) -> Option<&'p mut ArenaVec<'a, Node<'a>>> {
+  let mut children = program;
+  for &index in path {
+    children = match children.get_mut(index)? {
+      Node::Element(element) => &mut element.children,
+      Node::Comment(comment) => &mut comment.conditional.as_mut()?.children,
+      Node::Mustache(mustache) => &mut mustache.children,
+      _ => return None,
+    };
+  }
+  Some(children)
+}
+
+/// The position of the node being visited by a [`TransformHtml`], queuing its edits.
+#[derive(Debug, Default)]
+pub struct TransformContext<'a> {
+  path: Vec<usize>,
+  edits: EditQueue<'a>,
+}
+
+impl<'a> TransformContext<'a> {
+  /// The path of the node: its index among its siblings, and the ones of its ancestors.
+  pub fn path(&self) -> &[usize] {
+    &self.path
+  }
+
+  /// Remove the node once the walk is over.
+  pub fn remove(&mut self) {
+    self.edits.remove(&self.path);
+  }
+
+  /// Replace the node once the walk is over.
+  pub fn replace(&mut self, node: Node<'a>) {
+    self.edits.replace(&self.path, node);
+  }
+
+  /// Insert a sibling before the node once the walk is over.
+  pub fn insert_before(&mut self, node: Node<'a>) {
+    self.edits.insert_before(&self.path, node);
+  }
+
+  /// Insert a sibling after the node once the walk is over.
+  pub fn insert_after(&mut self, node: Node<'a>) {
+    self.edits.insert_after(&self.path, node);
+  }
+}
+
+/// A walk over the nodes of a tree, which can edit its structure through the
+/// [`TransformContext`].
+///
+/// Nodes can be mutated in place as with [`TraverseHtmlMut`](crate::TraverseHtmlMut), while
+/// removals, replacements and insertions are applied after the walk, so the walk still
+/// visits the nodes a removed node had.
+#[expect(unused_variables)]
+pub trait TransformHtml<'a> {
+  fn enter_node(&mut self, node: &mut Node<'a>, ctx: &mut TransformContext<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn exit_node(&mut self, node: &mut Node<'a>, ctx: &mut TransformContext<'a>) {}
+}
+
+/// Walk the nodes of a tree, then apply the structural edits queued by `transform`.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_ast::Node;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::edit::{TransformContext, TransformHtml, transform_program};
+/// use umc_parser::Parser;
+/// use umc_traverse::TraverseOperate;
+///
+/// struct StripComments;
+///
+/// impl<'a> TransformHtml<'a> for StripComments {
+///   fn enter_node(&mut self, node: &mut Node<'a>, ctx: &mut TransformContext<'a>) -> TraverseOperate {
+///     if let Node::Comment(comment) = node
+///       && comment.conditional.is_none()
+///     {
+///       ctx.remove();
+///     }
+///     TraverseOperate::Continue
+///   }
+/// }
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<p>a<!-- b -->c</p><!-- d -->");
+/// let mut program = parser.parse().program;
+/// transform_program(&mut program, &mut StripComments);
+///
+/// assert_eq!(program.len(), 1);
+/// let Node::Element(p) = &program[0] else { unreachable!() };
+/// assert_eq!(p.children.len(), 2);
+/// ```
+pub fn transform_program<'a>(program: &mut Program<'a>, transform: &mut impl TransformHtml<'a>) {
+  let mut ctx = TransformContext::default();
+  transform_nodes(program, transform, &mut ctx);
+  ctx.edits.apply(program);
+}
+
+fn transform_nodes<'a>(
+  nodes: &mut [Node<'a>],
+  transform: &mut impl TransformHtml<'a>,
+  ctx: &mut TransformContext<'a>,
+) {
+  for (index, node) in nodes.iter_mut().enumerate() {
+    ctx.path.push(index);
+    if transform.enter_node(node, ctx) != TraverseOperate::Skip {
+      match node {
+        Node::Element(element) => transform_nodes(&mut element.children, transform, ctx),
+        Node::Comment(comment) => {
+          if let Some(conditional) = &mut comment.conditional {
+            transform_nodes(&mut conditional.children, transform, ctx);
+          }
+        }
+        Node::Mustache(mustache) => {
+          transform_nodes(&mut mustache.children, transform, ctx);
+          transform_nodes(&mut mustache.inverse, transform, ctx);
+        }
+        _ => {}
+      }
+      transform.exit_node(node, ctx);
+    }
+    ctx.path.pop();
+  }
+}
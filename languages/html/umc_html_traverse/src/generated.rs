@@ -0,0 +1,595 @@
+// Generated by `just codegen` from the definitions of `umc_html_ast`, do not edit.
+
+use umc_html_ast::{
+  Attribute, AttributeKey, AttributeValue, Comment, ConditionalComment, Directive, Doctype,
+  Element, ModuleExport, ModuleImport, ModuleRecord, Mustache, Node, Program, RawUnknown, Script,
+  StyleDeclaration, TemplateTag, Text,
+};
+use umc_traverse::TraverseOperate;
+
+#[expect(unused_variables)]
+pub trait TraverseHtml<'a> {
+  fn enter_program(&mut self, program: &Program<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_node(&mut self, node: &Node<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_doctype(&mut self, doctype: &Doctype<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_element(&mut self, element: &Element<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_text(&mut self, text: &Text<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_comment(&mut self, comment: &Comment<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_conditional_comment(
+    &mut self,
+    conditional_comment: &ConditionalComment<'a>,
+  ) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_script(&mut self, script: &Script<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_module_record(&mut self, module_record: &ModuleRecord<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_module_import(&mut self, module_import: &ModuleImport<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_module_export(&mut self, module_export: &ModuleExport<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_raw_unknown(&mut self, raw_unknown: &RawUnknown<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_mustache(&mut self, mustache: &Mustache<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_template_tag(&mut self, template_tag: &TemplateTag<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_directive(&mut self, directive: &Directive<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_attribute(&mut self, attribute: &Attribute<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_style_declaration(
+    &mut self,
+    style_declaration: &StyleDeclaration<'a>,
+  ) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_attribute_key(&mut self, attribute_key: &AttributeKey<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_attribute_value(&mut self, attribute_value: &AttributeValue<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn exit_program(&mut self, program: &Program<'a>) {}
+  fn exit_node(&mut self, node: &Node<'a>) {}
+  fn exit_doctype(&mut self, doctype: &Doctype<'a>) {}
+  fn exit_element(&mut self, element: &Element<'a>) {}
+  fn exit_text(&mut self, text: &Text<'a>) {}
+  fn exit_comment(&mut self, comment: &Comment<'a>) {}
+  fn exit_conditional_comment(&mut self, conditional_comment: &ConditionalComment<'a>) {}
+  fn exit_script(&mut self, script: &Script<'a>) {}
+  fn exit_module_record(&mut self, module_record: &ModuleRecord<'a>) {}
+  fn exit_module_import(&mut self, module_import: &ModuleImport<'a>) {}
+  fn exit_module_export(&mut self, module_export: &ModuleExport<'a>) {}
+  fn exit_raw_unknown(&mut self, raw_unknown: &RawUnknown<'a>) {}
+  fn exit_mustache(&mut self, mustache: &Mustache<'a>) {}
+  fn exit_template_tag(&mut self, template_tag: &TemplateTag<'a>) {}
+  fn exit_directive(&mut self, directive: &Directive<'a>) {}
+  fn exit_attribute(&mut self, attribute: &Attribute<'a>) {}
+  fn exit_style_declaration(&mut self, style_declaration: &StyleDeclaration<'a>) {}
+  fn exit_attribute_key(&mut self, attribute_key: &AttributeKey<'a>) {}
+  fn exit_attribute_value(&mut self, attribute_value: &AttributeValue<'a>) {}
+}
+
+pub fn traverse_program<'a>(program: &Program<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_program(program) != TraverseOperate::Skip {
+    for node in program {
+      traverse_node(node, traverse);
+    }
+    traverse.exit_program(program);
+  }
+}
+
+pub fn traverse_node<'a>(node: &Node<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_node(node) != TraverseOperate::Skip {
+    match node {
+      Node::Doctype(doctype) => traverse_doctype(doctype, traverse),
+      Node::Element(element) => traverse_element(element, traverse),
+      Node::Text(text) => traverse_text(text, traverse),
+      Node::Comment(comment) => traverse_comment(comment, traverse),
+      Node::Script(script) => traverse_script(script, traverse),
+      Node::RawUnknown(raw_unknown) => traverse_raw_unknown(raw_unknown, traverse),
+      Node::Mustache(mustache) => traverse_mustache(mustache, traverse),
+      Node::TemplateTag(template_tag) => traverse_template_tag(template_tag, traverse),
+      Node::Directive(directive) => traverse_directive(directive, traverse),
+    }
+    traverse.exit_node(node);
+  }
+}
+
+pub fn traverse_doctype<'a>(doctype: &Doctype<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_doctype(doctype) != TraverseOperate::Skip {
+    for attribute in &doctype.attributes {
+      traverse_attribute(attribute, traverse);
+    }
+    traverse.exit_doctype(doctype);
+  }
+}
+
+pub fn traverse_element<'a>(element: &Element<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_element(element) != TraverseOperate::Skip {
+    for attribute in &element.attributes {
+      traverse_attribute(attribute, traverse);
+    }
+    for node in &element.children {
+      traverse_node(node, traverse);
+    }
+    traverse.exit_element(element);
+  }
+}
+
+pub fn traverse_text<'a>(text: &Text<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_text(text) != TraverseOperate::Skip {
+    traverse.exit_text(text);
+  }
+}
+
+pub fn traverse_comment<'a>(comment: &Comment<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_comment(comment) != TraverseOperate::Skip {
+    if let Some(conditional) = &comment.conditional {
+      traverse_conditional_comment(conditional, traverse);
+    }
+    traverse.exit_comment(comment);
+  }
+}
+
+pub fn traverse_conditional_comment<'a>(
+  conditional_comment: &ConditionalComment<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_conditional_comment(conditional_comment) != TraverseOperate::Skip {
+    for node in &conditional_comment.children {
+      traverse_node(node, traverse);
+    }
+    traverse.exit_conditional_comment(conditional_comment);
+  }
+}
+
+pub fn traverse_script<'a>(script: &Script<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_script(script) != TraverseOperate::Skip {
+    for attribute in &script.attributes {
+      traverse_attribute(attribute, traverse);
+    }
+    if let Some(module) = &script.module {
+      traverse_module_record(module, traverse);
+    }
+    traverse.exit_script(script);
+  }
+}
+
+pub fn traverse_module_record<'a>(
+  module_record: &ModuleRecord<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_module_record(module_record) != TraverseOperate::Skip {
+    for module_import in &module_record.imports {
+      traverse_module_import(module_import, traverse);
+    }
+    for module_export in &module_record.exports {
+      traverse_module_export(module_export, traverse);
+    }
+    traverse.exit_module_record(module_record);
+  }
+}
+
+pub fn traverse_module_import<'a>(
+  module_import: &ModuleImport<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_module_import(module_import) != TraverseOperate::Skip {
+    traverse.exit_module_import(module_import);
+  }
+}
+
+pub fn traverse_module_export<'a>(
+  module_export: &ModuleExport<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_module_export(module_export) != TraverseOperate::Skip {
+    traverse.exit_module_export(module_export);
+  }
+}
+
+pub fn traverse_raw_unknown<'a>(
+  raw_unknown: &RawUnknown<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_raw_unknown(raw_unknown) != TraverseOperate::Skip {
+    traverse.exit_raw_unknown(raw_unknown);
+  }
+}
+
+pub fn traverse_mustache<'a>(mustache: &Mustache<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_mustache(mustache) != TraverseOperate::Skip {
+    for node in &mustache.children {
+      traverse_node(node, traverse);
+    }
+    for node in &mustache.inverse {
+      traverse_node(node, traverse);
+    }
+    traverse.exit_mustache(mustache);
+  }
+}
+
+pub fn traverse_template_tag<'a>(
+  template_tag: &TemplateTag<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_template_tag(template_tag) != TraverseOperate::Skip {
+    traverse.exit_template_tag(template_tag);
+  }
+}
+
+pub fn traverse_directive<'a>(directive: &Directive<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_directive(directive) != TraverseOperate::Skip {
+    traverse.exit_directive(directive);
+  }
+}
+
+pub fn traverse_attribute<'a>(attribute: &Attribute<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  if traverse.enter_attribute(attribute) != TraverseOperate::Skip {
+    traverse_attribute_key(&attribute.key, traverse);
+    if let Some(value) = &attribute.value {
+      traverse_attribute_value(value, traverse);
+    }
+    if let Some(style) = &attribute.style {
+      for style_declaration in style {
+        traverse_style_declaration(style_declaration, traverse);
+      }
+    }
+    traverse.exit_attribute(attribute);
+  }
+}
+
+pub fn traverse_style_declaration<'a>(
+  style_declaration: &StyleDeclaration<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_style_declaration(style_declaration) != TraverseOperate::Skip {
+    traverse.exit_style_declaration(style_declaration);
+  }
+}
+
+pub fn traverse_attribute_key<'a>(
+  attribute_key: &AttributeKey<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_attribute_key(attribute_key) != TraverseOperate::Skip {
+    traverse.exit_attribute_key(attribute_key);
+  }
+}
+
+pub fn traverse_attribute_value<'a>(
+  attribute_value: &AttributeValue<'a>,
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  if traverse.enter_attribute_value(attribute_value) != TraverseOperate::Skip {
+    traverse.exit_attribute_value(attribute_value);
+  }
+}
+
+#[expect(unused_variables)]
+pub trait TraverseHtmlMut<'a> {
+  fn enter_program(&mut self, program: &mut Program<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_node(&mut self, node: &mut Node<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_doctype(&mut self, doctype: &mut Doctype<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_text(&mut self, text: &mut Text<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_comment(&mut self, comment: &mut Comment<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_conditional_comment(
+    &mut self,
+    conditional_comment: &mut ConditionalComment<'a>,
+  ) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_script(&mut self, script: &mut Script<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_module_record(&mut self, module_record: &mut ModuleRecord<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_module_import(&mut self, module_import: &mut ModuleImport<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_module_export(&mut self, module_export: &mut ModuleExport<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_raw_unknown(&mut self, raw_unknown: &mut RawUnknown<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_mustache(&mut self, mustache: &mut Mustache<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_template_tag(&mut self, template_tag: &mut TemplateTag<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_directive(&mut self, directive: &mut Directive<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_attribute(&mut self, attribute: &mut Attribute<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_style_declaration(
+    &mut self,
+    style_declaration: &mut StyleDeclaration<'a>,
+  ) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_attribute_key(&mut self, attribute_key: &mut AttributeKey<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn enter_attribute_value(&mut self, attribute_value: &mut AttributeValue<'a>) -> TraverseOperate {
+    TraverseOperate::Continue
+  }
+  fn exit_program(&mut self, program: &mut Program<'a>) {}
+  fn exit_node(&mut self, node: &mut Node<'a>) {}
+  fn exit_doctype(&mut self, doctype: &mut Doctype<'a>) {}
+  fn exit_element(&mut self, element: &mut Element<'a>) {}
+  fn exit_text(&mut self, text: &mut Text<'a>) {}
+  fn exit_comment(&mut self, comment: &mut Comment<'a>) {}
+  fn exit_conditional_comment(&mut self, conditional_comment: &mut ConditionalComment<'a>) {}
+  fn exit_script(&mut self, script: &mut Script<'a>) {}
+  fn exit_module_record(&mut self, module_record: &mut ModuleRecord<'a>) {}
+  fn exit_module_import(&mut self, module_import: &mut ModuleImport<'a>) {}
+  fn exit_module_export(&mut self, module_export: &mut ModuleExport<'a>) {}
+  fn exit_raw_unknown(&mut self, raw_unknown: &mut RawUnknown<'a>) {}
+  fn exit_mustache(&mut self, mustache: &mut Mustache<'a>) {}
+  fn exit_template_tag(&mut self, template_tag: &mut TemplateTag<'a>) {}
+  fn exit_directive(&mut self, directive: &mut Directive<'a>) {}
+  fn exit_attribute(&mut self, attribute: &mut Attribute<'a>) {}
+  fn exit_style_declaration(&mut self, style_declaration: &mut StyleDeclaration<'a>) {}
+  fn exit_attribute_key(&mut self, attribute_key: &mut AttributeKey<'a>) {}
+  fn exit_attribute_value(&mut self, attribute_value: &mut AttributeValue<'a>) {}
+}
+
+pub fn traverse_program_mut<'a>(
+  program: &mut Program<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_program(program) != TraverseOperate::Skip {
+    for node in &mut *program {
+      traverse_node_mut(&mut *node, traverse);
+    }
+    traverse.exit_program(program);
+  }
+}
+
+pub fn traverse_node_mut<'a>(node: &mut Node<'a>, traverse: &mut impl TraverseHtmlMut<'a>) {
+  if traverse.enter_node(node) != TraverseOperate::Skip {
+    match node {
+      Node::Doctype(doctype) => traverse_doctype_mut(doctype, traverse),
+      Node::Element(element) => traverse_element_mut(element, traverse),
+      Node::Text(text) => traverse_text_mut(text, traverse),
+      Node::Comment(comment) => traverse_comment_mut(comment, traverse),
+      Node::Script(script) => traverse_script_mut(script, traverse),
+      Node::RawUnknown(raw_unknown) => traverse_raw_unknown_mut(raw_unknown, traverse),
+      Node::Mustache(mustache) => traverse_mustache_mut(mustache, traverse),
+      Node::TemplateTag(template_tag) => traverse_template_tag_mut(template_tag, traverse),
+      Node::Directive(directive) => traverse_directive_mut(directive, traverse),
+    }
+    traverse.exit_node(node);
+  }
+}
+
+pub fn traverse_doctype_mut<'a>(
+  doctype: &mut Doctype<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_doctype(doctype) != TraverseOperate::Skip {
+    for attribute in &mut doctype.attributes {
+      traverse_attribute_mut(&mut *attribute, traverse);
+    }
+    traverse.exit_doctype(doctype);
+  }
+}
+
+pub fn traverse_element_mut<'a>(
+  element: &mut Element<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_element(element) != TraverseOperate::Skip {
+    for attribute in &mut element.attributes {
+      traverse_attribute_mut(&mut *attribute, traverse);
+    }
+    for node in &mut element.children {
+      traverse_node_mut(&mut *node, traverse);
+    }
+    traverse.exit_element(element);
+  }
+}
+
+pub fn traverse_text_mut<'a>(text: &mut Text<'a>, traverse: &mut impl TraverseHtmlMut<'a>) {
+  if traverse.enter_text(text) != TraverseOperate::Skip {
+    traverse.exit_text(text);
+  }
+}
+
+pub fn traverse_comment_mut<'a>(
+  comment: &mut Comment<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_comment(comment) != TraverseOperate::Skip {
+    if let Some(conditional) = &mut comment.conditional {
+      traverse_conditional_comment_mut(&mut *conditional, traverse);
+    }
+    traverse.exit_comment(comment);
+  }
+}
+
+pub fn traverse_conditional_comment_mut<'a>(
+  conditional_comment: &mut ConditionalComment<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_conditional_comment(conditional_comment) != TraverseOperate::Skip {
+    for node in &mut conditional_comment.children {
+      traverse_node_mut(&mut *node, traverse);
+    }
+    traverse.exit_conditional_comment(conditional_comment);
+  }
+}
+
+pub fn traverse_script_mut<'a>(script: &mut Script<'a>, traverse: &mut impl TraverseHtmlMut<'a>) {
+  if traverse.enter_script(script) != TraverseOperate::Skip {
+    for attribute in &mut script.attributes {
+      traverse_attribute_mut(&mut *attribute, traverse);
+    }
+    if let Some(module) = &mut script.module {
+      traverse_module_record_mut(&mut *module, traverse);
+    }
+    traverse.exit_script(script);
+  }
+}
+
+pub fn traverse_module_record_mut<'a>(
+  module_record: &mut ModuleRecord<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_module_record(module_record) != TraverseOperate::Skip {
+    for module_import in &mut module_record.imports {
+      traverse_module_import_mut(&mut *module_import, traverse);
+    }
+    for module_export in &mut module_record.exports {
+      traverse_module_export_mut(&mut *module_export, traverse);
+    }
+    traverse.exit_module_record(module_record);
+  }
+}
+
+pub fn traverse_module_import_mut<'a>(
+  module_import: &mut ModuleImport<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_module_import(module_import) != TraverseOperate::Skip {
+    traverse.exit_module_import(module_import);
+  }
+}
+
+pub fn traverse_module_export_mut<'a>(
+  module_export: &mut ModuleExport<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_module_export(module_export) != TraverseOperate::Skip {
+    traverse.exit_module_export(module_export);
+  }
+}
+
+pub fn traverse_raw_unknown_mut<'a>(
+  raw_unknown: &mut RawUnknown<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_raw_unknown(raw_unknown) != TraverseOperate::Skip {
+    traverse.exit_raw_unknown(raw_unknown);
+  }
+}
+
+pub fn traverse_mustache_mut<'a>(
+  mustache: &mut Mustache<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_mustache(mustache) != TraverseOperate::Skip {
+    for node in &mut mustache.children {
+      traverse_node_mut(&mut *node, traverse);
+    }
+    for node in &mut mustache.inverse {
+      traverse_node_mut(&mut *node, traverse);
+    }
+    traverse.exit_mustache(mustache);
+  }
+}
+
+pub fn traverse_template_tag_mut<'a>(
+  template_tag: &mut TemplateTag<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_template_tag(template_tag) != TraverseOperate::Skip {
+    traverse.exit_template_tag(template_tag);
+  }
+}
+
+pub fn traverse_directive_mut<'a>(
+  directive: &mut Directive<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_directive(directive) != TraverseOperate::Skip {
+    traverse.exit_directive(directive);
+  }
+}
+
+pub fn traverse_attribute_mut<'a>(
+  attribute: &mut Attribute<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_attribute(attribute) != TraverseOperate::Skip {
+    traverse_attribute_key_mut(&mut attribute.key, traverse);
+    if let Some(value) = &mut attribute.value {
+      traverse_attribute_value_mut(&mut *value, traverse);
+    }
+    if let Some(style) = &mut attribute.style {
+      for style_declaration in &mut *style {
+        traverse_style_declaration_mut(&mut *style_declaration, traverse);
+      }
+    }
+    traverse.exit_attribute(attribute);
+  }
+}
+
+pub fn traverse_style_declaration_mut<'a>(
+  style_declaration: &mut StyleDeclaration<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_style_declaration(style_declaration) != TraverseOperate::Skip {
+    traverse.exit_style_declaration(style_declaration);
+  }
+}
+
+pub fn traverse_attribute_key_mut<'a>(
+  attribute_key: &mut AttributeKey<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_attribute_key(attribute_key) != TraverseOperate::Skip {
+    traverse.exit_attribute_key(attribute_key);
+  }
+}
+
+pub fn traverse_attribute_value_mut<'a>(
+  attribute_value: &mut AttributeValue<'a>,
+  traverse: &mut impl TraverseHtmlMut<'a>,
+) {
+  if traverse.enter_attribute_value(attribute_value) != TraverseOperate::Skip {
+    traverse.exit_attribute_value(attribute_value);
+  }
+}
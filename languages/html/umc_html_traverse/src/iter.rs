@@ -0,0 +1,136 @@
+//! Iterators over the nodes of a tree, for queries too simple to write a visitor for.
+//!
+//! Like [`Node::children`], they look into elements, conditional comments and Handlebars
+//! blocks up to their `{{else}}`.
+
+use std::{collections::VecDeque, slice};
+
+use umc_html_ast::Node;
+
+/// Iterators over nodes and their descendants, implemented for
+/// [`Program`](umc_html_ast::Program) and the children of nodes.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_ast::Node;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::iter::WalkNodes;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<ul><li>a</li><li>b</li></ul><p>c</p>");
+/// let program = parser.parse().program;
+///
+/// fn name<'a>(node: &Node<'a>) -> &'a str {
+///   match node {
+///     Node::Element(element) => element.name(),
+///     Node::Text(text) => text.value,
+///     _ => "",
+///   }
+/// }
+/// let names: Vec<_> = program.descendants().map(name).collect();
+/// assert_eq!(names, ["ul", "li", "a", "li", "b", "p", "c"]);
+///
+/// let names: Vec<_> = program.descendants_post().map(name).collect();
+/// assert_eq!(names, ["a", "li", "b", "li", "ul", "c", "p"]);
+///
+/// let names: Vec<_> = program.bfs().map(|(node, depth)| (name(node), depth)).collect();
+/// assert_eq!(
+///   names,
+///   [("ul", 0), ("p", 0), ("li", 1), ("li", 1), ("c", 1), ("a", 2), ("b", 2)]
+/// );
+/// ```
+pub trait WalkNodes<'a> {
+  /// The nodes and their descendants in pre-order, a node before its children.
+  fn descendants(&self) -> Descendants<'_, 'a>;
+
+  /// The nodes and their descendants in post-order, a node after its children.
+  fn descendants_post(&self) -> DescendantsPost<'_, 'a>;
+
+  /// The nodes and their descendants breadth-first, with their depth, 0 for these nodes.
+  fn bfs(&self) -> Bfs<'_, 'a>;
+}
+
+impl<'a> WalkNodes<'a> for [Node<'a>] {
+  fn descendants(&self) -> Descendants<'_, 'a> {
+    Descendants {
+      stack: vec![self.iter()],
+    }
+  }
+
+  fn descendants_post(&self) -> DescendantsPost<'_, 'a> {
+    DescendantsPost {
+      stack: vec![(None, self.iter())],
+    }
+  }
+
+  fn bfs(&self) -> Bfs<'_, 'a> {
+    Bfs {
+      queue: self.iter().map(|node| (node, 0)).collect(),
+    }
+  }
+}
+
+/// Pre-order iterator, see [`WalkNodes::descendants`].
+#[derive(Debug, Clone)]
+pub struct Descendants<'n, 'a> {
+  stack: Vec<slice::Iter<'n, Node<'a>>>,
+}
+
+impl<'n, 'a> Iterator for Descendants<'n, 'a> {
+  type Item = &'n Node<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let siblings = self.stack.last_mut()?;
+      if let Some(node) = siblings.next() {
+        self.stack.push(node.children().iter());
+        return Some(node);
+      }
+      self.stack.pop();
+    }
+  }
+}
+
+/// Post-order iterator, see [`WalkNodes::descendants_post`].
+#[derive(Debug, Clone)]
+pub struct DescendantsPost<'n, 'a> {
+  /// Nodes whose children are being iterated, `None` for the nodes iterated from
+  stack: Vec<(Option<&'n Node<'a>>, slice::Iter<'n, Node<'a>>)>,
+}
+
+impl<'n, 'a> Iterator for DescendantsPost<'n, 'a> {
+  type Item = &'n Node<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let (_, children) = self.stack.last_mut()?;
+      if let Some(node) = children.next() {
+        self.stack.push((Some(node), node.children().iter()));
+      } else {
+        let (node, _) = self.stack.pop()?;
+        return node;
+      }
+    }
+  }
+}
+
+/// Breadth-first iterator, see [`WalkNodes::bfs`].
+#[derive(Debug, Clone)]
+pub struct Bfs<'n, 'a> {
+  queue: VecDeque<(&'n Node<'a>, usize)>,
+}
+
+impl<'n, 'a> Iterator for Bfs<'n, 'a> {
+  type Item = (&'n Node<'a>, usize);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (node, depth) = self.queue.pop_front()?;
+    self
+      .queue
+      .extend(node.children().iter().map(|child| (child, depth + 1)));
+    Some((node, depth))
+  }
+}
@@ -0,0 +1,84 @@
+//! Lookups of nodes by position, for editor features like hover and completion.
+
+use umc_html_ast::{Attribute, Element, Node, child_at_offset};
+use umc_span::GetSpan;
+
+/// The nodes at an offset of the source text, see [`hit_test`].
+#[derive(Debug, Clone)]
+pub struct Hit<'n, 'a> {
+  /// The deepest node covering the offset
+  pub node: &'n Node<'a>,
+  /// The ancestors of the node, from the root to its parent
+  pub ancestors: Vec<&'n Node<'a>>,
+  /// The attribute covering the offset, for an element or a script
+  pub attribute: Option<&'n Attribute<'a>>,
+}
+
+impl<'n, 'a> Hit<'n, 'a> {
+  /// The parent of the node, `None` for a root node.
+  pub fn parent(&self) -> Option<&'n Node<'a>> {
+    self.ancestors.last().copied()
+  }
+
+  /// The innermost element covering the offset, the node itself if it is an element.
+  pub fn element(&self) -> Option<&'n Element<'a>> {
+    std::iter::once(self.node)
+      .chain(self.ancestors.iter().rev().copied())
+      .find_map(|node| match node {
+        Node::Element(element) => Some(&**element),
+        _ => None,
+      })
+  }
+}
+
+/// Get the deepest node covering `offset`, with its ancestors, and the attribute covering
+/// it if any.
+///
+/// Nodes cover the offsets from their start, included, to their end, excluded. Each level
+/// is a binary search over the spans of the siblings, so a lookup takes O(depth · log n).
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_ast::Node;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::query::hit_test;
+/// use umc_parser::Parser;
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, r#"<ul><li class="a">One</li></ul>"#);
+/// let program = parser.parse().program;
+///
+/// let hit = hit_test(&program, 19).unwrap();
+/// assert!(matches!(hit.node, Node::Text(text) if text.value == "One"));
+/// assert_eq!(hit.element().map(|element| element.name()), Some("li"));
+/// assert_eq!(hit.ancestors.len(), 2);
+///
+/// let hit = hit_test(&program, 12).unwrap();
+/// assert_eq!(hit.attribute.map(|attribute| attribute.key.name()), Some("class"));
+/// ```
+pub fn hit_test<'n, 'a>(nodes: &'n [Node<'a>], offset: u32) -> Option<Hit<'n, 'a>> {
+  let mut ancestors = Vec::new();
+  let mut node = &nodes[child_at_offset(nodes, offset)?];
+  while let Some(index) = child_at_offset(node.children(), offset) {
+    ancestors.push(node);
+    node = &node.children()[index];
+  }
+
+  let attributes: &[Attribute] = match node {
+    Node::Element(element) => &element.attributes,
+    Node::Script(script) => &script.attributes,
+    _ => &[],
+  };
+  let index = attributes.partition_point(|attribute| attribute.span().end <= offset);
+  let attribute = attributes
+    .get(index)
+    .filter(|attribute| attribute.span().start <= offset);
+
+  Some(Hit {
+    node,
+    ancestors,
+    attribute,
+  })
+}
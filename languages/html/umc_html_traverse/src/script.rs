@@ -0,0 +1,139 @@
+//! Visiting the JavaScript programs of scripts, with `oxc_ast_visit`.
+//!
+//! The walk of [`TraverseHtml`] stops at scripts. A [`VisitScript`] continues into their
+//! programs, and gets a [`ScriptContext`] to map the spans of a program, relative to the
+//! content of its script, to the document.
+
+use oxc_ast_visit::Visit;
+use umc_html_ast::Script;
+use umc_span::Span;
+use umc_traverse::TraverseOperate;
+
+use crate::TraverseHtml;
+
+/// Where the program of a script is in the document.
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptContext {
+  /// Source location of the script element
+  pub span: Span,
+  /// Offset of the content of the script, `None` for a script loaded from its `src`, whose
+  /// program is not in the document
+  pub offset: Option<u32>,
+}
+
+impl ScriptContext {
+  /// Locate the program of `script` in the document it was parsed from.
+  pub fn new(source_text: &str, script: &Script) -> Self {
+    let offset = (!script.synthetic).then(|| content_start(source_text, script));
+    Self {
+      span: script.span,
+      offset,
+    }
+  }
+
+  /// Map a span of the program to the document.
+  pub fn span(&self, span: oxc_span::Span) -> Option<Span> {
+    self
+      .offset
+      .map(|offset| Span::new(offset + span.start, offset + span.end))
+  }
+}
+
+/// Get the offset right after the start tag of a script, where its content starts.
+fn content_start(source_text: &str, script: &Script) -> u32 {
+  // after the last attribute, only whitespace and a slash are left in the start tag
+  let from = script
+    .attributes
+    .last()
+    .map_or(script.span.start, |attribute| attribute.span.end);
+  source_text
+    .get(from as usize..script.span.end as usize)
+    .and_then(|tag| tag.find('>'))
+    .map_or(script.span.end, |index| from + index as u32 + 1)
+}
+
+/// A visitor of the programs of scripts.
+#[expect(unused_variables)]
+pub trait VisitScript<'a>: Visit<'a> {
+  fn enter_script_program(&mut self, ctx: ScriptContext) {}
+  fn exit_script_program(&mut self, ctx: ScriptContext) {}
+}
+
+/// Visit the program of a script, parsing it first if the script is parsed lazily.
+///
+/// Call it from [`TraverseHtml::enter_script`] to analyze the markup and the scripts of a
+/// document in a single pass.
+pub fn visit_script<'a>(
+  source_text: &str,
+  script: &Script<'a>,
+  visitor: &mut impl VisitScript<'a>,
+) {
+  let ctx = ScriptContext::new(source_text, script);
+  visitor.enter_script_program(ctx);
+  visitor.visit_program(script.program());
+  visitor.exit_script_program(ctx);
+}
+
+/// An adapter walking the programs of the scripts of a tree with a [`VisitScript`].
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use oxc_ast::ast::IdentifierReference;
+/// use oxc_ast_visit::Visit;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::script::{ScriptBridge, ScriptContext, VisitScript};
+/// use umc_html_traverse::traverse_program;
+/// use umc_parser::Parser;
+/// use umc_span::Span;
+///
+/// #[derive(Default)]
+/// struct References {
+///   ctx: Option<ScriptContext>,
+///   spans: Vec<Span>,
+/// }
+///
+/// impl<'a> Visit<'a> for References {
+///   fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+///     self.spans.extend(self.ctx.and_then(|ctx| ctx.span(it.span)));
+///   }
+/// }
+///
+/// impl VisitScript<'_> for References {
+///   fn enter_script_program(&mut self, ctx: ScriptContext) {
+///     self.ctx = Some(ctx);
+///   }
+/// }
+///
+/// let allocator = Allocator::default();
+/// let source_text = "<p>a</p><script type=module>foo()</script>";
+/// let parser = Parser::html(&allocator, source_text);
+/// let program = parser.parse().program;
+///
+/// let mut bridge = ScriptBridge::new(source_text, References::default());
+/// traverse_program(&program, &mut bridge);
+/// let span = bridge.visitor.spans[0];
+/// assert_eq!(&source_text[span.start as usize..span.end as usize], "foo");
+/// ```
+pub struct ScriptBridge<'s, V> {
+  source_text: &'s str,
+  pub visitor: V,
+}
+
+impl<'s, V> ScriptBridge<'s, V> {
+  /// Create an adapter for a tree parsed from `source_text`.
+  pub const fn new(source_text: &'s str, visitor: V) -> Self {
+    Self {
+      source_text,
+      visitor,
+    }
+  }
+}
+
+impl<'a, V: VisitScript<'a>> TraverseHtml<'a> for ScriptBridge<'_, V> {
+  fn enter_script(&mut self, script: &Script<'a>) -> TraverseOperate {
+    visit_script(self.source_text, script, &mut self.visitor);
+    TraverseOperate::Continue
+  }
+}
@@ -0,0 +1,112 @@
+use umc_html_ast::Node;
+use umc_traverse::TraverseOperate;
+
+use crate::{TraverseHtml, traverse_node};
+
+/// Walk the subtree of `node`, entering its `ancestors` first, from the root to its parent.
+///
+/// The ancestors are entered and exited as the walk of the whole tree would, without
+/// walking their attributes or their other children, so visitors keeping a stack of the
+/// elements they are in can re-run on a subtree, e.g. after an incremental reparse. If the
+/// visitor skips an ancestor, the subtree is not walked. The ancestors can come from
+/// [`hit_test`](crate::query::hit_test).
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_html_ast::Element;
+/// use umc_html_parser::CreateHtml;
+/// use umc_html_traverse::query::hit_test;
+/// use umc_html_traverse::{TraverseHtml, traverse_subtree};
+/// use umc_parser::Parser;
+/// use umc_traverse::TraverseOperate;
+///
+/// #[derive(Default)]
+/// struct Paths {
+///   stack: Vec<String>,
+///   paths: Vec<String>,
+/// }
+///
+/// impl<'a> TraverseHtml<'a> for Paths {
+///   fn enter_element(&mut self, element: &Element<'a>) -> TraverseOperate {
+///     self.stack.push(element.name().to_string());
+///     self.paths.push(self.stack.join(" > "));
+///     TraverseOperate::Continue
+///   }
+///
+///   fn exit_element(&mut self, _element: &Element<'a>) {
+///     self.stack.pop();
+///   }
+/// }
+///
+/// let allocator = Allocator::default();
+/// let parser = Parser::html(&allocator, "<nav><ul><li><a>Home</a></li></ul></nav>");
+/// let program = parser.parse().program;
+///
+/// let hit = hit_test(&program, 13).unwrap();
+/// let mut paths = Paths::default();
+/// traverse_subtree(hit.node, &hit.ancestors, &mut paths);
+///
+/// assert_eq!(paths.paths[2..], ["nav > ul > li", "nav > ul > li > a"]);
+/// assert!(paths.stack.is_empty());
+/// ```
+pub fn traverse_subtree<'a>(
+  node: &Node<'a>,
+  ancestors: &[&Node<'a>],
+  traverse: &mut impl TraverseHtml<'a>,
+) {
+  let entered = ancestors
+    .iter()
+    .take_while(|ancestor| enter_ancestor(ancestor, traverse))
+    .count();
+  if entered == ancestors.len() {
+    traverse_node(node, traverse);
+  }
+  for ancestor in ancestors[..entered].iter().rev() {
+    exit_ancestor(ancestor, traverse);
+  }
+}
+
+/// Enter an ancestor, `false` if the visitor skipped it, having exited what it entered.
+fn enter_ancestor<'a>(node: &Node<'a>, traverse: &mut impl TraverseHtml<'a>) -> bool {
+  if traverse.enter_node(node) == TraverseOperate::Skip {
+    return false;
+  }
+  let entered = match node {
+    Node::Element(element) => traverse.enter_element(element) != TraverseOperate::Skip,
+    Node::Mustache(mustache) => traverse.enter_mustache(mustache) != TraverseOperate::Skip,
+    Node::Comment(comment) => {
+      if traverse.enter_comment(comment) == TraverseOperate::Skip {
+        false
+      } else if let Some(conditional) = &comment.conditional
+        && traverse.enter_conditional_comment(conditional) == TraverseOperate::Skip
+      {
+        traverse.exit_comment(comment);
+        false
+      } else {
+        true
+      }
+    }
+    _ => true,
+  };
+  if !entered {
+    traverse.exit_node(node);
+  }
+  entered
+}
+
+fn exit_ancestor<'a>(node: &Node<'a>, traverse: &mut impl TraverseHtml<'a>) {
+  match node {
+    Node::Element(element) => traverse.exit_element(element),
+    Node::Mustache(mustache) => traverse.exit_mustache(mustache),
+    Node::Comment(comment) => {
+      if let Some(conditional) = &comment.conditional {
+        traverse.exit_conditional_comment(conditional);
+      }
+      traverse.exit_comment(comment);
+    }
+    _ => {}
+  }
+  traverse.exit_node(node);
+}
@@ -0,0 +1,83 @@
+//! Pug parser implementation for the Universal Markup-language Compiler.
+//!
+//! This crate parses Pug (formerly Jade) templates into the AST defined in
+//! [`umc_html_ast`], so the transforms, linters and printers written for HTML work on the
+//! HTML structure of the template unchanged.
+//!
+//! Tags become [`Element`](umc_html_ast::Element) nodes, with the `.class` and `#id`
+//! shorthands as `class` and `id` attributes, and `script` tags become
+//! [`Script`](umc_html_ast::Script) nodes parsed by `oxc_parser`. The template language
+//! itself is not interpreted: code like `= name` or `- let a = 1`, interpolations like
+//! `#{name}`, filters, and lines starting with a keyword like `if`, `each` or `include`
+//! are [`TemplateTag`](umc_html_ast::TemplateTag) nodes, and the content nested under a
+//! keyword stays its sibling, as for the template tags of the HTML parser.
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_html_ast::Node;
+//! use umc_parser::Parser;
+//! use umc_pug_parser::CreatePug;
+//!
+//! let allocator = Allocator::default();
+//! let source_text = "ul#menu\n  li.item: a(href=\"/\") Home\n  li.item= user.name";
+//! let result = Parser::pug(&allocator, source_text).parse();
+//!
+//! assert!(result.errors.is_empty());
+//! let Node::Element(ul) = &result.program[0] else { unreachable!() };
+//! assert_eq!(ul.name(), "ul");
+//! assert_eq!(ul.attributes[0].value.as_ref().unwrap().value, "menu");
+//! assert_eq!(ul.children.len(), 2);
+//! ```
+
+use oxc_allocator::Allocator;
+use umc_html_ast::Program;
+use umc_parser::{LanguageParser, Parser};
+
+use crate::{option::PugParserOption, parse::PugParserImpl};
+
+mod parse;
+
+/// Pug language parser marker type.
+///
+/// This zero-sized type implements [`LanguageParser`] for Pug parsing.
+/// Use [`Parser::pug()`](CreatePug::pug) to create a Pug parser instance.
+pub struct Pug;
+
+impl LanguageParser for Pug {
+  const NAME: &'static str = "pug";
+  const EXTENSIONS: &'static [&'static str] = &["pug", "jade"];
+
+  type Result<'a> = Program<'a>;
+  type Option = PugParserOption;
+  type Parser<'a> = PugParserImpl<'a>;
+}
+
+/// Convenience trait for creating Pug parsers.
+pub trait CreatePug<'a> {
+  /// Create a parser for Pug parsing.
+  ///
+  /// # Parameters
+  /// - `allocator`: Memory arena for allocating AST nodes
+  /// - `source_text`: Pug template to parse
+  fn pug(allocator: &'a Allocator, source_text: &'a str) -> Self;
+}
+
+impl<'a> CreatePug<'a> for Parser<'a, Pug> {
+  fn pug(allocator: &'a Allocator, source_text: &'a str) -> Self {
+    Parser::<Pug>::new(allocator, source_text)
+  }
+}
+
+/// Pug parser configuration options.
+pub mod option {
+  use oxc_parser::ParseOptions;
+
+  /// Pug parser configuration options.
+  #[derive(Debug, Clone, Default)]
+  pub struct PugParserOption {
+    /// The oxc_parser options for the content of `script` tags.
+    pub script: ParseOptions,
+  }
+}
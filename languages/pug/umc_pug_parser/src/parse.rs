@@ -0,0 +1,881 @@
+use std::borrow::Cow;
+
+use oxc_allocator::{Allocator, Box, Vec};
+use oxc_diagnostics::{LabeledSpan, OxcDiagnostic};
+use oxc_parser::Parser as JsParser;
+use umc_html_ast::{
+  Attribute, AttributeKey, AttributeValue, Comment, Doctype, Element, Node, Program, RawUnknown,
+  Script, ScriptInfo, TemplateTag, Text,
+};
+use umc_parser::{LanguageParser, ParseResult, ParserImpl};
+use umc_span::Span;
+
+use crate::{Pug, option::PugParserOption};
+
+/// Words starting a line of template logic rather than a tag.
+const KEYWORDS: &[&str] = &[
+  "append", "block", "case", "default", "each", "else", "extends", "for", "if", "include", "mixin",
+  "prepend", "unless", "when", "while", "yield",
+];
+
+/// A line of the template, without its line break.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+  /// Offset of the start of the line
+  start: usize,
+  /// Offset of the content, after the indentation
+  content: usize,
+  /// Offset of the end of the line
+  end: usize,
+}
+
+impl Line {
+  /// Width of the indentation, a tab counting as one space.
+  const fn indent(&self) -> usize {
+    self.content - self.start
+  }
+
+  const fn is_blank(&self) -> bool {
+    self.content == self.end
+  }
+}
+
+/// The tag name and attributes at the start of a tag, see [`PugParserImpl::parse_tag_head`].
+struct TagHead<'a> {
+  name: &'a str,
+  attributes: Vec<'a, Attribute<'a>>,
+  /// Offset after the head
+  end: usize,
+  /// Offset of the end of the line, after an attribute list over several lines
+  line_end: usize,
+}
+
+/// Pug parser implementation.
+///
+/// Parses the template line by line, nesting the lines indented under a tag into its
+/// children.
+pub struct PugParserImpl<'a> {
+  allocator: &'a Allocator,
+  source_text: &'a str,
+  options: Cow<'a, PugParserOption>,
+  lines: std::vec::Vec<Line>,
+  /// Index of the next line to parse
+  pos: usize,
+  /// Offset of the end of the last line parsed
+  end: usize,
+  errors: std::vec::Vec<OxcDiagnostic>,
+}
+
+impl<'a> ParserImpl<'a, Pug> for PugParserImpl<'a> {
+  fn new(
+    allocator: &'a Allocator,
+    source_text: &'a str,
+    options: Cow<'a, <Pug as LanguageParser>::Option>,
+  ) -> Self {
+    PugParserImpl {
+      allocator,
+      source_text,
+      options,
+      lines: split_lines(source_text),
+      pos: 0,
+      end: 0,
+      errors: std::vec::Vec::new(),
+    }
+  }
+
+  fn parse(mut self) -> ParseResult<Program<'a>> {
+    let program = self.parse_block(None);
+    ParseResult {
+      program,
+      errors: self.errors,
+      metrics: None,
+    }
+  }
+}
+
+impl<'a> PugParserImpl<'a> {
+  /// Parse the lines indented more than `parent`, or every line without parent.
+  fn parse_block(&mut self, parent: Option<usize>) -> Vec<'a, Node<'a>> {
+    let mut nodes = Vec::new_in(self.allocator);
+    let mut level = None;
+    while let Some(line) = self.peek_line() {
+      let indent = line.indent();
+      if parent.is_some_and(|parent| indent <= parent) {
+        break;
+      }
+      match level {
+        None => level = Some(indent),
+        Some(level) if level != indent => self.errors.push(
+          OxcDiagnostic::error(format!(
+            "Inconsistent indentation, expected {level} spaces but found {indent}"
+          ))
+          .with_label(span(line.start, line.content)),
+        ),
+        Some(_) => {}
+      }
+      self.next_line(line);
+      self.parse_line(line, &mut nodes);
+    }
+    nodes
+  }
+
+  /// Parse a line, and the lines nested under it.
+  fn parse_line(&mut self, line: Line, nodes: &mut Vec<'a, Node<'a>>) {
+    let (start, end, indent) = (line.content, line.end, line.indent());
+    let text = &self.source_text[start..end];
+    let word = text.split([' ', '\t', '(', ':']).next().unwrap_or_default();
+
+    if text.starts_with("//-") {
+      self.block_range(indent);
+    } else if text.starts_with("//") {
+      let end = self.block_range(indent).map_or(end, |(_, end)| end);
+      let comment = Comment {
+        span: span(start, end),
+        bogus: false,
+        value: &self.source_text[start + 2..end],
+        raw: &self.source_text[start..end],
+        conditional: None,
+      };
+      nodes.push(Node::Comment(Box::new_in(comment, self.allocator)));
+    } else if let Some(value) = text.strip_prefix('|') {
+      let skip = usize::from(value.starts_with(' '));
+      self.parse_text(start + 1 + skip, end, nodes);
+    } else if text.starts_with('<') {
+      let raw = RawUnknown {
+        span: span(start, end),
+        raw: text,
+      };
+      nodes.push(Node::RawUnknown(Box::new_in(raw, self.allocator)));
+      nodes.extend(self.parse_block(Some(indent)));
+    } else if let Some(open) = ["!=", "="].into_iter().find(|open| text.starts_with(open)) {
+      nodes.push(self.template_tag(start, open, start + open.len(), end));
+    } else if let Some(code) = text.strip_prefix('-') {
+      if code.trim().is_empty() {
+        let end = self.block_range(indent).map_or(end, |(_, end)| end);
+        nodes.push(self.template_tag(start, "-", start + 1, end));
+      } else {
+        nodes.push(self.template_tag(start, "-", start + 1, end));
+        nodes.extend(self.parse_block(Some(indent)));
+      }
+    } else if text.starts_with(':') {
+      let end = self.block_range(indent).map_or(end, |(_, end)| end);
+      nodes.push(self.template_tag(start, ":", start + 1, end));
+    } else if text.starts_with('+') || KEYWORDS.contains(&word) {
+      let open = if text.starts_with('+') { "+" } else { "" };
+      nodes.push(self.template_tag(start, open, start + open.len(), end));
+      nodes.extend(self.parse_block(Some(indent)));
+    } else if word == "doctype" {
+      nodes.push(self.parse_doctype(start, end));
+      nodes.extend(self.parse_block(Some(indent)));
+    } else {
+      self.parse_tag(start, end, Some(indent), nodes);
+    }
+  }
+
+  /// Parse `doctype html`, with each word after `doctype` as an attribute.
+  fn parse_doctype(&self, start: usize, end: usize) -> Node<'a> {
+    let mut attributes = Vec::new_in(self.allocator);
+    let mut offset = start + "doctype".len();
+    for word in self.source_text[offset..end].split_ascii_whitespace() {
+      let word_start = offset + self.source_text[offset..end].find(word).unwrap_or_default();
+      offset = word_start + word.len();
+      attributes.push(Attribute {
+        span: span(word_start, offset),
+        key: AttributeKey {
+          span: span(word_start, offset),
+          value: word,
+          normalized: word,
+        },
+        value: None,
+        style: None,
+      });
+    }
+    let name = attributes.first().map(|attribute| attribute.key.value);
+    let doctype = Doctype {
+      span: span(start, end),
+      attributes,
+      name,
+      public_id: None,
+      system_id: None,
+    };
+    Node::Doctype(Box::new_in(doctype, self.allocator))
+  }
+
+  /// Parse a tag from `start` to `end`, with the lines indented more than `indent` as its
+  /// children, or inline, like the `#[strong bold]` interpolation, without `indent`.
+  fn parse_tag(
+    &mut self,
+    start: usize,
+    end: usize,
+    indent: Option<usize>,
+    nodes: &mut Vec<'a, Node<'a>>,
+  ) {
+    let Some(head) = self.parse_tag_head(start, end, indent.is_some()) else {
+      self.errors.push(
+        OxcDiagnostic::error("Expected a tag, `|` before text or `//` before a comment")
+          .with_label(span(start, end)),
+      );
+      self.parse_text(start, end, nodes);
+      return;
+    };
+    let (end, rest) = (head.line_end, &self.source_text[head.end..head.line_end]);
+    let mut children = Vec::new_in(self.allocator);
+    let mut text = None;
+    let mut text_block = head.name == "script";
+    let mut nested = indent;
+
+    if let Some(content) = rest.strip_prefix('/') {
+      if !content.trim().is_empty() {
+        self.errors.push(
+          OxcDiagnostic::error("Self-closing tags cannot have content")
+            .with_label(span(head.end + 1, end)),
+        );
+      }
+    } else if rest.trim_end() == "." {
+      text_block = true;
+    } else if let Some(expansion) = rest.strip_prefix(':') {
+      let expansion_start = end - expansion.trim_start().len();
+      self.parse_tag(expansion_start, end, indent, &mut children);
+      nested = None;
+    } else if let Some(open) = ["!=", "="].into_iter().find(|open| rest.starts_with(open)) {
+      children.push(self.template_tag(head.end, open, head.end + open.len(), end));
+    } else if let Some(inline) = rest.strip_prefix(' ') {
+      text = Some((end - inline.len(), end));
+    } else if !rest.is_empty() {
+      self.errors.push(
+        OxcDiagnostic::error("Expected a space between a tag and its text")
+          .with_label(span(head.end, end)),
+      );
+      text = Some((head.end, end));
+    }
+
+    if let Some(indent) = nested {
+      if text_block {
+        if let Some(block) = self.block_range(indent) {
+          text = Some(text.map_or(block, |(start, _)| (start, block.1)));
+        }
+      } else {
+        children.extend(self.parse_block(Some(indent)));
+      }
+    }
+    let end = if indent.is_some() {
+      self.end.max(end)
+    } else {
+      end
+    };
+
+    if head.name == "script" && children.is_empty() {
+      let content = text.unwrap_or((head.end, head.end));
+      nodes.push(self.parse_script(span(start, end), head.attributes, content));
+      return;
+    }
+    if let Some((text_start, text_end)) = text {
+      self.parse_text(text_start, text_end, &mut children);
+    }
+    let element = Element {
+      span: span(start, end),
+      tag_name: head.name,
+      normalized_name: head.name,
+      attributes: head.attributes,
+      children,
+    };
+    nodes.push(Node::Element(Box::new_in(element, self.allocator)));
+  }
+
+  /// Parse the name, `.class` and `#id` shorthands and attribute lists at the start of a
+  /// tag, `None` if it does not start with a name or shorthand.
+  fn parse_tag_head(&mut self, start: usize, end: usize, multiline: bool) -> Option<TagHead<'a>> {
+    let bytes = self.source_text.as_bytes();
+    let head_end = name_end(bytes, start, end);
+    let name = if head_end > start {
+      &self.source_text[start..head_end]
+    } else if matches!(bytes.get(start), Some(b'.' | b'#'))
+      && name_end(bytes, start + 1, end) > start + 1
+    {
+      "div"
+    } else {
+      return None;
+    };
+
+    let mut head = TagHead {
+      name,
+      attributes: Vec::new_in(self.allocator),
+      end: head_end,
+      line_end: end,
+    };
+    let mut classes = std::vec::Vec::new();
+    let mut class_index = None;
+    while head.end < head.line_end {
+      let i = head.end;
+      match bytes[i] {
+        sigil @ (b'.' | b'#') if name_end(bytes, i + 1, head.line_end) > i + 1 => {
+          head.end = name_end(bytes, i + 1, head.line_end);
+          if sigil == b'.' {
+            class_index.get_or_insert(head.attributes.len());
+            classes.push((i + 1, head.end));
+          } else {
+            head
+              .attributes
+              .push(self.shorthand("id", i, (i + 1, head.end)));
+          }
+        }
+        b'(' => self.parse_attribute_list(&mut head, multiline),
+        b'&' if self.source_text[i..].starts_with("&attributes(") => {
+          let open = i + "&attributes".len();
+          let close = find_closing(bytes, open + 1, head.line_end).unwrap_or(head.line_end);
+          head.end = (close + 1).min(head.line_end);
+          head.attributes.push(self.attribute((i, head.end), None));
+        }
+        _ => break,
+      }
+    }
+
+    if let (Some(index), Some(&(first, _)), Some(&(_, last))) =
+      (class_index, classes.first(), classes.last())
+    {
+      let mut class = self.shorthand("class", first - 1, (first, last));
+      if classes.len() > 1 {
+        let names: std::vec::Vec<_> = classes
+          .iter()
+          .map(|&(start, end)| &self.source_text[start..end])
+          .collect();
+        let value = self.allocator.alloc_str(&names.join(" "));
+        class.value = Some(AttributeValue {
+          span: span(first, last),
+          value_span: span(first, last),
+          value,
+          raw: value,
+        });
+      }
+      head.attributes.insert(index, class);
+    }
+    Some(head)
+  }
+
+  /// Parse the attribute list at `head.end`, like `(href="/" target="_blank")`, over
+  /// several lines if `multiline`.
+  fn parse_attribute_list(&mut self, head: &mut TagHead<'a>, multiline: bool) {
+    let bytes = self.source_text.as_bytes();
+    let open = head.end;
+    let limit = if multiline {
+      bytes.len()
+    } else {
+      head.line_end
+    };
+    let close = if let Some(close) = find_closing(bytes, open + 1, limit) {
+      head.end = close + 1;
+      close
+    } else {
+      self.errors.push(
+        OxcDiagnostic::error("Expected a closing `)` for the attribute list")
+          .with_label(span(open, open + 1)),
+      );
+      head.end = head.line_end;
+      head.line_end
+    };
+
+    // the attribute list continues on the following lines
+    while close > head.line_end && self.pos < self.lines.len() {
+      let line = self.lines[self.pos];
+      self.next_line(line);
+      head.line_end = line.end;
+    }
+
+    let mut i = open + 1;
+    loop {
+      while i < close && (bytes[i].is_ascii_whitespace() || bytes[i] == b',') {
+        i += 1;
+      }
+      if i >= close {
+        break;
+      }
+      let key_start = i;
+      while i < close
+        && !bytes[i].is_ascii_whitespace()
+        && !matches!(bytes[i], b',' | b'=')
+        && !self.source_text[i..].starts_with("!=")
+      {
+        i += 1;
+      }
+      let key = (key_start, i);
+      while i < close && bytes[i].is_ascii_whitespace() {
+        i += 1;
+      }
+      let value = ["!=", "="]
+        .into_iter()
+        .find(|eq| self.source_text[i..close].starts_with(eq))
+        .map(|eq| {
+          i += eq.len();
+          while i < close && bytes[i].is_ascii_whitespace() {
+            i += 1;
+          }
+          let value_start = i;
+          i = value_end(bytes, i, close);
+          (value_start, i)
+        });
+      head.attributes.push(self.attribute(key, value));
+    }
+  }
+
+  /// Parse text, with `#{expression}` and `!{expression}` interpolations as template tags
+  /// and `#[tag text]` interpolations as elements.
+  fn parse_text(&mut self, start: usize, end: usize, nodes: &mut Vec<'a, Node<'a>>) {
+    let bytes = self.source_text.as_bytes();
+    let mut text_start = start;
+    let mut i = start;
+    while i + 1 < end {
+      let interpolation = matches!((bytes[i], bytes[i + 1]), (b'#' | b'!', b'{') | (b'#', b'['));
+      if !interpolation || (i > start && bytes[i - 1] == b'\\') {
+        i += 1;
+        continue;
+      }
+      let Some(close) = find_closing(bytes, i + 2, end) else {
+        self.errors.push(
+          OxcDiagnostic::error(format!(
+            "Expected a closing `{}` for the interpolation",
+            if bytes[i + 1] == b'{' { '}' } else { ']' }
+          ))
+          .with_label(span(i, i + 2)),
+        );
+        break;
+      };
+      self.push_text(text_start, i, nodes);
+      if bytes[i + 1] == b'[' {
+        self.parse_tag(i + 2, close, None, nodes);
+      } else {
+        let tag = TemplateTag {
+          span: span(i, close + 1),
+          open: &self.source_text[i..i + 2],
+          close: "}",
+          value: &self.source_text[i + 2..close],
+        };
+        nodes.push(Node::TemplateTag(Box::new_in(tag, self.allocator)));
+      }
+      i = close + 1;
+      text_start = i;
+    }
+    self.push_text(text_start, end, nodes);
+  }
+
+  fn push_text(&self, start: usize, end: usize, nodes: &mut Vec<'a, Node<'a>>) {
+    if start < end {
+      let text = Text {
+        span: span(start, end),
+        value: &self.source_text[start..end],
+      };
+      nodes.push(Node::Text(Box::new_in(text, self.allocator)));
+    }
+  }
+
+  /// Parse the content of a `script` tag, from `content.0` to `content.1`, as JavaScript.
+  fn parse_script(
+    &mut self,
+    span: Span,
+    attributes: Vec<'a, Attribute<'a>>,
+    (start, end): (usize, usize),
+  ) -> Node<'a> {
+    let info = ScriptInfo::from_attributes(&attributes);
+    let source_type = info.kind.source_type();
+    let ret = JsParser::new(self.allocator, &self.source_text[start..end], source_type)
+      .with_options(self.options.script)
+      .parse();
+    self.errors.extend(
+      ret
+        .errors
+        .into_iter()
+        .map(|error| shift_diagnostic(error, start)),
+    );
+
+    let script = Script {
+      span,
+      tag_name: "script",
+      attributes,
+      info,
+      source_type,
+      program: ret.program,
+      lazy: None,
+      synthetic: false,
+      module: None,
+    };
+    Node::Script(Box::new_in(script, self.allocator))
+  }
+
+  /// A template tag from `start` to `end`, opened by `open` and without closing delimiter,
+  /// like `= name`.
+  fn template_tag(&self, start: usize, open: &'a str, value_start: usize, end: usize) -> Node<'a> {
+    let tag = TemplateTag {
+      span: span(start, end),
+      open,
+      close: "",
+      value: &self.source_text[value_start..end],
+    };
+    Node::TemplateTag(Box::new_in(tag, self.allocator))
+  }
+
+  fn attribute(&self, key: (usize, usize), value: Option<(usize, usize)>) -> Attribute<'a> {
+    let key_text = &self.source_text[key.0..key.1];
+    Attribute {
+      span: span(key.0, value.map_or(key.1, |(_, end)| end)),
+      key: AttributeKey {
+        span: span(key.0, key.1),
+        value: key_text,
+        normalized: key_text,
+      },
+      value: value.map(|(start, end)| attribute_value(&self.source_text[start..end], start)),
+      style: None,
+    }
+  }
+
+  /// The attribute of a `#id` or `.class` shorthand, keyed by its sigil at `sigil`.
+  fn shorthand(&self, key: &'a str, sigil: usize, (start, end): (usize, usize)) -> Attribute<'a> {
+    let value = &self.source_text[start..end];
+    Attribute {
+      span: span(sigil, end),
+      key: AttributeKey {
+        span: span(sigil, sigil + 1),
+        value: key,
+        normalized: key,
+      },
+      value: Some(AttributeValue {
+        span: span(start, end),
+        value_span: span(start, end),
+        value,
+        raw: value,
+      }),
+      style: None,
+    }
+  }
+
+  /// Skip the lines indented more than `indent`, returning the offsets of the content of
+  /// the first and of the end of the last, `None` without such lines.
+  fn block_range(&mut self, indent: usize) -> Option<(usize, usize)> {
+    let mut range = None;
+    while let Some(line) = self.peek_line() {
+      if line.indent() <= indent {
+        break;
+      }
+      self.next_line(line);
+      range = Some((range.map_or(line.content, |(start, _)| start), line.end));
+    }
+    range
+  }
+
+  /// The next line that is not blank, without parsing it.
+  fn peek_line(&mut self) -> Option<Line> {
+    while self.lines.get(self.pos).is_some_and(Line::is_blank) {
+      self.pos += 1;
+    }
+    self.lines.get(self.pos).copied()
+  }
+
+  /// Move past `line`, the next line.
+  const fn next_line(&mut self, line: Line) {
+    self.pos += 1;
+    self.end = line.end;
+  }
+}
+
+fn split_lines(source_text: &str) -> std::vec::Vec<Line> {
+  let mut offset = 0;
+  source_text
+    .split_inclusive('\n')
+    .map(|line| {
+      let text = line.trim_end_matches(['\n', '\r']);
+      let content = text.trim_start_matches([' ', '\t']);
+      let text_line = Line {
+        start: offset,
+        content: offset + text.len() - content.len(),
+        end: offset + text.len(),
+      };
+      offset += line.len();
+      text_line
+    })
+    .collect()
+}
+
+/// The end of the name of a tag, class or id starting at `start`.
+fn name_end(bytes: &[u8], start: usize, end: usize) -> usize {
+  let mut i = start;
+  while i < end && (bytes[i].is_ascii_alphanumeric() || matches!(bytes[i], b'-' | b'_' | b':')) {
+    i += 1;
+  }
+  // `a: img` nests `img` into `a`
+  while i > start && bytes[i - 1] == b':' {
+    i -= 1;
+  }
+  i
+}
+
+/// The end of an attribute value starting at `start`: a string, or a JavaScript expression
+/// ending at a comma, or at a space that is not around an operator.
+fn value_end(bytes: &[u8], start: usize, end: usize) -> usize {
+  const OPERATORS: &[u8] = b"+-*/%?:|&<>=!.";
+  let mut i = start;
+  while i < end {
+    match bytes[i] {
+      b'"' | b'\'' | b'`' => i = string_end(bytes, i, end),
+      b'(' | b'[' | b'{' => i = find_closing(bytes, i + 1, end).map_or(end, |close| close + 1),
+      b',' => break,
+      byte if byte.is_ascii_whitespace() => {
+        let next = (i..end)
+          .find(|&next| !bytes[next].is_ascii_whitespace())
+          .unwrap_or(end);
+        let around_operator =
+          OPERATORS.contains(&bytes[i - 1]) || (next < end && OPERATORS.contains(&bytes[next]));
+        if !around_operator || next == end {
+          break;
+        }
+        i = next;
+      }
+      _ => i += 1,
+    }
+  }
+  i
+}
+
+/// The offset of the bracket closing the one before `start`, skipping nested brackets and
+/// strings, `None` if it is not closed before `end`.
+fn find_closing(bytes: &[u8], start: usize, end: usize) -> Option<usize> {
+  let mut depth = 0usize;
+  let mut i = start;
+  while i < end {
+    match bytes[i] {
+      b'"' | b'\'' | b'`' => {
+        i = string_end(bytes, i, end);
+        continue;
+      }
+      b'(' | b'[' | b'{' => depth += 1,
+      b')' | b']' | b'}' => {
+        if depth == 0 {
+          return Some(i);
+        }
+        depth -= 1;
+      }
+      _ => {}
+    }
+    i += 1;
+  }
+  None
+}
+
+/// The offset after the string starting with a quote at `start`.
+fn string_end(bytes: &[u8], start: usize, end: usize) -> usize {
+  let quote = bytes[start];
+  let mut i = start + 1;
+  while i < end && bytes[i] != quote {
+    i += if bytes[i] == b'\\' { 2 } else { 1 };
+  }
+  (i + 1).min(end)
+}
+
+/// An attribute value, without its quotes if it is a string. Other values are JavaScript
+/// expressions, kept as written.
+fn attribute_value(raw: &str, start: usize) -> AttributeValue<'_> {
+  let quoted = raw.len() >= 2
+    && matches!(raw.as_bytes()[0], b'"' | b'\'' | b'`')
+    && raw.as_bytes()[raw.len() - 1] == raw.as_bytes()[0];
+  let (value, value_span) = if quoted {
+    (
+      &raw[1..raw.len() - 1],
+      span(start + 1, start + raw.len() - 1),
+    )
+  } else {
+    (raw, span(start, start + raw.len()))
+  };
+  AttributeValue {
+    span: span(start, start + raw.len()),
+    value_span,
+    value,
+    raw,
+  }
+}
+
+const fn span(start: usize, end: usize) -> Span {
+  Span::new(start as u32, end as u32)
+}
+
+/// Move the labels of a diagnostic of a script to their offset in the template.
+fn shift_diagnostic(mut error: OxcDiagnostic, offset: usize) -> OxcDiagnostic {
+  if let Some(labels) = &mut error.labels {
+    for label in labels {
+      let span = (label.offset() + offset, label.len());
+      let text = label.label().map(ToString::to_string);
+      *label = if label.primary() {
+        LabeledSpan::new_primary_with_span(text, span)
+      } else {
+        LabeledSpan::new_with_span(text, span)
+      };
+    }
+  }
+  error
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_ast::{Node, Program};
+  use umc_parser::Parser;
+  use umc_span::Span;
+
+  use crate::CreatePug;
+
+  fn attributes<'a>(node: &Node<'a>) -> Vec<(&'a str, Option<&'a str>)> {
+    let Node::Element(element) = node else {
+      unreachable!()
+    };
+    element
+      .attributes
+      .iter()
+      .map(|attribute| {
+        (
+          attribute.key.value,
+          attribute.value.as_ref().map(|v| v.value),
+        )
+      })
+      .collect()
+  }
+
+  fn children<'b>(node: &'b Node<'b>) -> &'b Program<'b> {
+    match node {
+      Node::Element(element) => &element.children,
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn tags() {
+    const PUG: &str = "doctype html
+html(lang=\"en\")
+  body
+    a.btn.primary#go(href=\"/\", target='_blank' data-n=count + 1)/
+    input(
+      type=\"checkbox\"
+      checked
+    )
+    ul: li: a Home
+    .card&attributes(props) Hello";
+
+    let allocator = Allocator::default();
+    let result = Parser::pug(&allocator, PUG).parse();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    assert!(
+      matches!(&result.program[0], Node::Doctype(doctype) if doctype.attributes[0].key.value == "html")
+    );
+
+    let html = &result.program[1];
+    assert_eq!(attributes(html), [("lang", Some("en"))]);
+    let body = &children(html)[0];
+    let [a, input, ul, card] = &children(body)[..] else {
+      unreachable!()
+    };
+    assert_eq!(
+      attributes(a),
+      [
+        ("class", Some("btn primary")),
+        ("id", Some("go")),
+        ("href", Some("/")),
+        ("target", Some("_blank")),
+        ("data-n", Some("count + 1"))
+      ]
+    );
+    assert!(children(a).is_empty());
+    assert_eq!(
+      attributes(input),
+      [("type", Some("checkbox")), ("checked", None)]
+    );
+
+    let li = &children(ul)[0];
+    let Node::Element(link) = &children(li)[0] else {
+      unreachable!()
+    };
+    assert_eq!(link.name(), "a");
+    assert!(matches!(&link.children[..], [Node::Text(text)] if text.value == "Home"));
+
+    let Node::Element(div) = card else {
+      unreachable!()
+    };
+    assert_eq!(div.name(), "div");
+    assert_eq!(
+      attributes(card),
+      [("class", Some("card")), ("&attributes(props)", None)]
+    );
+    assert_eq!(div.span, Span::new(178, 207));
+  }
+
+  #[test]
+  fn text_and_code() {
+    const PUG: &str = "p Hello #{user.name}, #[strong welcome]!
+p.
+  Block text
+  on two lines
+p
+  | Piped
+  = greeting
+// note
+//- hidden
+- const a = 1
+if user
+  span= user.name
+script.
+  const b = 2;";
+
+    let allocator = Allocator::default();
+    let result = Parser::pug(&allocator, PUG).parse();
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let program = &result.program;
+
+    let [
+      Node::Text(hello),
+      Node::TemplateTag(name),
+      Node::Text(_),
+      Node::Element(strong),
+      Node::Text(_),
+    ] = &children(&program[0])[..]
+    else {
+      unreachable!()
+    };
+    assert_eq!(hello.value, "Hello ");
+    assert_eq!(
+      (name.open, name.value, name.close),
+      ("#{", "user.name", "}")
+    );
+    assert_eq!(strong.name(), "strong");
+    assert!(
+      matches!(&children(&program[1])[..], [Node::Text(text)] if text.value == "Block text\n  on two lines")
+    );
+    assert!(matches!(
+      &children(&program[2])[..],
+      [Node::Text(text), Node::TemplateTag(code)] if text.value == "Piped" && code.open == "="
+    ));
+
+    assert!(matches!(&program[3], Node::Comment(comment) if comment.value == " note"));
+    assert!(matches!(&program[4], Node::TemplateTag(code) if code.value == " const a = 1"));
+    assert!(matches!(&program[5], Node::TemplateTag(tag) if tag.value == "if user"));
+    assert!(matches!(&program[6], Node::Element(span) if span.name() == "span"));
+    let Node::Script(script) = &program[7] else {
+      unreachable!()
+    };
+    assert_eq!(script.program.body.len(), 1);
+  }
+
+  #[test]
+  fn errors() {
+    let allocator = Allocator::default();
+    let result = Parser::pug(&allocator, "div\n    p\n  p\na(href=\"/\"\n$ text #{a").parse();
+    let messages: Vec<_> = result
+      .errors
+      .iter()
+      .map(|error| error.message.as_ref())
+      .collect();
+    assert_eq!(
+      messages,
+      [
+        "Inconsistent indentation, expected 4 spaces but found 2",
+        "Expected a closing `)` for the attribute list",
+        "Expected a tag, `|` before text or `//` before a comment",
+        "Expected a closing `}` for the interpolation",
+      ]
+    );
+    assert_eq!(result.program.len(), 3);
+  }
+}
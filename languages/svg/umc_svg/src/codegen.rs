@@ -0,0 +1,154 @@
+use umc_html_ast::{Attribute, Element, MustacheKind, Node, Program};
+
+/// Print a tree back to markup, e.g. after [optimizing](crate::optimize::optimize) it.
+///
+/// Elements without children are printed self-closed, as SVG allows, and names keep the
+/// case they are written in, e.g. `viewBox`. Text, attribute values and other nodes are
+/// printed as written in the source, so entities stay escaped.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_svg::{codegen::codegen, parse_svg};
+///
+/// let allocator = Allocator::default();
+/// let result = parse_svg(&allocator, "<svg viewBox='0 0 1 1'>\n  <g><path d=\"M0 0\"></path></g>\n</svg>");
+///
+/// assert_eq!(
+///   codegen(&result.program),
+///   "<svg viewBox=\"0 0 1 1\">\n  <g><path d=\"M0 0\"/></g>\n</svg>"
+/// );
+/// ```
+pub fn codegen(program: &Program) -> String {
+  let mut output = String::new();
+  print_nodes(program, &mut output);
+  output
+}
+
+fn print_nodes(nodes: &[Node], output: &mut String) {
+  for node in nodes {
+    print_node(node, output);
+  }
+}
+
+fn print_node(node: &Node, output: &mut String) {
+  match node {
+    Node::Doctype(doctype) => {
+      output.push_str("<!DOCTYPE");
+      for attribute in &doctype.attributes {
+        output.push(' ');
+        output.push_str(attribute.key.raw_name());
+        if let Some(value) = &attribute.value {
+          output.push_str(value.raw);
+        }
+      }
+      output.push('>');
+    }
+    Node::Element(element) => print_element(element, output),
+    Node::Text(text) => output.push_str(text.value),
+    Node::Comment(comment) => {
+      if let Some(conditional) = &comment.conditional {
+        output.push_str("<!--[if ");
+        output.push_str(conditional.condition);
+        output.push_str("]>");
+        print_nodes(&conditional.children, output);
+        output.push_str("<![endif]-->");
+      } else if comment.bogus {
+        output.push('<');
+        if !comment.value.starts_with('?') {
+          output.push('!');
+        }
+        output.push_str(comment.value);
+        output.push('>');
+      } else {
+        output.push_str("<!--");
+        output.push_str(comment.value);
+        output.push_str("-->");
+      }
+    }
+    Node::Script(script) => {
+      output.push('<');
+      output.push_str(script.tag_name);
+      print_attributes(&script.attributes, output);
+      output.push('>');
+      output.push_str(script.program().source_text);
+      output.push_str("</");
+      output.push_str(script.tag_name);
+      output.push('>');
+    }
+    Node::RawUnknown(raw) => output.push_str(raw.raw),
+    Node::Directive(directive) => output.push_str(directive.raw),
+    Node::Mustache(mustache) => {
+      let (open, close) = match mustache.kind {
+        MustacheKind::Expression | MustacheKind::Else => ("{{", "}}"),
+        MustacheKind::Unescaped => ("{{{", "}}}"),
+        MustacheKind::Block => ("{{#", "}}"),
+        MustacheKind::InvertedBlock => ("{{^", "}}"),
+        MustacheKind::Partial => ("{{> ", "}}"),
+        MustacheKind::Comment => ("{{!", "}}"),
+        MustacheKind::CloseBlock => ("{{/", "}}"),
+      };
+      output.push_str(open);
+      output.push_str(mustache.expression);
+      output.push_str(close);
+      if matches!(
+        mustache.kind,
+        MustacheKind::Block | MustacheKind::InvertedBlock
+      ) {
+        print_nodes(&mustache.children, output);
+        if !mustache.inverse.is_empty() {
+          output.push_str("{{else}}");
+          print_nodes(&mustache.inverse, output);
+        }
+        output.push_str("{{/");
+        output.push_str(mustache.name());
+        output.push_str("}}");
+      }
+    }
+    Node::TemplateTag(tag) => {
+      output.push_str(tag.open);
+      output.push_str(tag.value);
+      output.push_str(tag.close);
+    }
+  }
+}
+
+fn print_element(element: &Element, output: &mut String) {
+  output.push('<');
+  output.push_str(element.raw_name());
+  print_attributes(&element.attributes, output);
+  if element.children.is_empty() {
+    output.push_str("/>");
+    return;
+  }
+  output.push('>');
+  print_nodes(&element.children, output);
+  output.push_str("</");
+  output.push_str(element.raw_name());
+  output.push('>');
+}
+
+fn print_attributes(attributes: &[Attribute], output: &mut String) {
+  for attribute in attributes {
+    output.push(' ');
+    output.push_str(attribute.key.raw_name());
+    let Some(value) = &attribute.value else {
+      continue;
+    };
+    output.push('=');
+    if !value.value.contains('"') {
+      output.push('"');
+      output.push_str(value.value);
+      output.push('"');
+    } else if !value.value.contains('\'') {
+      output.push('\'');
+      output.push_str(value.value);
+      output.push('\'');
+    } else {
+      output.push('"');
+      output.push_str(&value.value.replace('"', "&quot;"));
+      output.push('"');
+    }
+  }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use umc_html_ast::{Attribute, Element, Node, Program, Text};
+use umc_html_traverse::{TraverseHtml, traverse_program};
+use umc_traverse::TraverseOperate;
+
+use crate::is_named;
+
+/// The ids referenced by a document: by `href` and `xlink:href` attributes like
+/// `<use href="#icon">`, and by `url(#id)` in attribute values and `<style>` elements.
+#[derive(Debug, Default)]
+pub struct References<'a> {
+  ids: HashSet<&'a str>,
+  in_style: bool,
+}
+
+impl<'a> References<'a> {
+  /// Collect the references of a document.
+  pub fn collect(program: &Program<'a>) -> Self {
+    let mut references = Self::default();
+    traverse_program(program, &mut references);
+    references
+  }
+
+  /// Whether an id is referenced.
+  pub fn contains(&self, id: &str) -> bool {
+    self.ids.contains(id)
+  }
+
+  fn add_urls(&mut self, text: &'a str) {
+    let mut rest = text;
+    while let Some(start) = rest.find("url(") {
+      rest = rest[start + 4..].trim_start_matches([' ', '"', '\'']);
+      if let Some(id) = rest.strip_prefix('#') {
+        let end = id.find([')', '"', '\'', ' ']).unwrap_or(id.len());
+        self.ids.insert(&id[..end]);
+      }
+    }
+  }
+}
+
+impl<'a> TraverseHtml<'a> for References<'a> {
+  fn enter_element(&mut self, element: &Element<'a>) -> TraverseOperate {
+    self.in_style = is_named(element, "style");
+    TraverseOperate::Continue
+  }
+
+  fn exit_element(&mut self, _element: &Element<'a>) {
+    self.in_style = false;
+  }
+
+  fn enter_attribute(&mut self, attribute: &Attribute<'a>) -> TraverseOperate {
+    let Some(value) = &attribute.value else {
+      return TraverseOperate::Continue;
+    };
+    let name = attribute.key.name();
+    if name.eq_ignore_ascii_case("href") || name.eq_ignore_ascii_case("xlink:href") {
+      if let Some(id) = value.value.trim().strip_prefix('#') {
+        self.ids.insert(id);
+      }
+    } else {
+      self.add_urls(value.value);
+    }
+    TraverseOperate::Continue
+  }
+
+  fn enter_text(&mut self, text: &Text<'a>) -> TraverseOperate {
+    if self.in_style {
+      self.add_urls(text.value);
+    }
+    TraverseOperate::Continue
+  }
+}
+
+/// Whether a child element of `<defs>` is unused: it has no id, or no reference to its id.
+///
+/// `<style>` and `<script>` apply wherever they are, so they are never unused.
+pub fn is_unused_def(element: &Element, references: &References) -> bool {
+  if is_named(element, "style") || is_named(element, "script") {
+    return false;
+  }
+  element
+    .attribute("id")
+    .is_none_or(|id| !references.contains(id))
+}
+
+/// Find the child elements of the `<defs>` of a document which are not used, see
+/// [`is_unused_def`].
+///
+/// References are collected from the whole document, including from other definitions,
+/// even unused ones.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_svg::{defs::unused_defs, parse_svg};
+///
+/// let allocator = Allocator::default();
+/// let result = parse_svg(
+///   &allocator,
+///   r##"<svg><defs><circle id="a" r="1"/><rect id="b"/></defs><use href="#a"/></svg>"##,
+/// );
+///
+/// let unused = unused_defs(&result.program);
+/// assert_eq!(unused.len(), 1);
+/// assert_eq!(unused[0].name(), "rect");
+/// ```
+pub fn unused_defs<'b, 'a>(program: &'b Program<'a>) -> Vec<&'b Element<'a>> {
+  let references = References::collect(program);
+  let mut unused = Vec::new();
+  collect_unused(program, &references, &mut unused);
+  unused
+}
+
+fn collect_unused<'b, 'a>(
+  nodes: &'b [Node<'a>],
+  references: &References,
+  unused: &mut Vec<&'b Element<'a>>,
+) {
+  for node in nodes {
+    let Node::Element(element) = node else {
+      continue;
+    };
+    if is_named(element, "defs") {
+      for child in &element.children {
+        if let Node::Element(child) = child
+          && is_unused_def(child, references)
+        {
+          unused.push(child);
+        }
+      }
+    } else {
+      collect_unused(&element.children, references, unused);
+    }
+  }
+}
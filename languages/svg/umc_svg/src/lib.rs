@@ -0,0 +1,114 @@
+//! Analysis and optimization of SVG documents.
+//!
+//! Works on trees of the HTML parser: standalone SVG files parsed with the
+//! [XML dialect](umc_xml_formats::xml_options), see [`parse_svg`], and `<svg>` elements
+//! inline in HTML documents.
+//!
+//! - [`path`]: parsing and minifying path data, the `d` attribute of `<path>`.
+//! - [`view_box`]: parsing and validating `viewBox` attributes.
+//! - [`defs`]: the references of a document and the unused children of `<defs>`.
+//! - [`optimize`]: an [SVGO](https://github.com/svg/svgo) style optimizer, made of
+//!   [`TraverseHtmlMut`](umc_html_traverse::TraverseHtmlMut) passes.
+//! - [`codegen`]: printing a tree back to markup.
+//!
+//! [`check`] reports the problems found by the analyses as diagnostics.
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_svg::{check, parse_svg};
+//!
+//! let allocator = Allocator::default();
+//! let result = parse_svg(&allocator, r#"<svg viewBox="0 0 -24 24"><path d="M0 0 L"/></svg>"#);
+//!
+//! let diagnostics = check(&result.program);
+//! assert_eq!(diagnostics.len(), 2);
+//! assert_eq!(diagnostics[0].to_string(), "The width of a `viewBox` must not be negative");
+//! ```
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Element, Program};
+use umc_html_traverse::{TraverseHtml, traverse_program};
+use umc_parser::ParseResult;
+use umc_traverse::TraverseOperate;
+
+pub mod codegen;
+pub mod defs;
+pub mod optimize;
+pub mod path;
+pub mod view_box;
+
+use defs::{References, is_unused_def};
+use path::PathData;
+use view_box::ViewBox;
+
+/// Parse a standalone SVG document, with [`umc_xml_formats::parse_xml`].
+pub fn parse_svg<'a>(allocator: &'a Allocator, source_text: &'a str) -> ParseResult<Program<'a>> {
+  umc_xml_formats::parse_xml(allocator, source_text)
+}
+
+/// Report invalid path data and `viewBox` attributes, and unused definitions, see
+/// [`defs::unused_defs`].
+///
+/// Invalid values are errors, while empty `viewBox`es, which disable rendering, and unused
+/// definitions are warnings.
+pub fn check(program: &Program) -> Vec<OxcDiagnostic> {
+  let mut checker = Checker {
+    references: References::collect(program),
+    parents: Vec::new(),
+    diagnostics: Vec::new(),
+  };
+  traverse_program(program, &mut checker);
+  checker.diagnostics
+}
+
+struct Checker<'a> {
+  references: References<'a>,
+  /// Whether each ancestor of the current element is a `<defs>`
+  parents: Vec<bool>,
+  diagnostics: Vec<OxcDiagnostic>,
+}
+
+impl<'a> TraverseHtml<'a> for Checker<'a> {
+  fn enter_element(&mut self, element: &Element<'a>) -> TraverseOperate {
+    if self.parents.last() == Some(&true) && is_unused_def(element, &self.references) {
+      self.diagnostics.push(
+        OxcDiagnostic::warn(format!("Unused definition `<{}>`", element.raw_name()))
+          .with_label(element.span)
+          .with_help("Give it an id referenced by the document, or remove it"),
+      );
+    }
+    self.parents.push(is_named(element, "defs"));
+
+    for attribute in &element.attributes {
+      let Some(value) = &attribute.value else {
+        continue;
+      };
+      if attribute.key.name().eq_ignore_ascii_case("viewBox") {
+        match ViewBox::parse(value) {
+          Ok(view_box) if view_box.is_empty() => self.diagnostics.push(
+            OxcDiagnostic::warn("A `viewBox` with a zero width or height disables rendering")
+              .with_label(view_box.span),
+          ),
+          Ok(_) => {}
+          Err(error) => self.diagnostics.push(error),
+        }
+      } else if attribute.key.name() == "d" && is_named(element, "path") {
+        self.diagnostics.extend(PathData::parse(value).errors);
+      }
+    }
+    TraverseOperate::Continue
+  }
+
+  fn exit_element(&mut self, _element: &Element<'a>) {
+    self.parents.pop();
+  }
+}
+
+/// Whether an element has a name, compared case-insensitively since the HTML parser
+/// lowercases the names of inline SVG, e.g. `lineargradient`.
+const fn is_named(element: &Element, name: &str) -> bool {
+  element.name().eq_ignore_ascii_case(name)
+}
@@ -0,0 +1,372 @@
+//! Passes of the optimizer, each a [`TraverseHtmlMut`] which can also be run on its own with
+//! [`traverse_program_mut`].
+
+use oxc_allocator::{Allocator, Vec};
+use umc_html_ast::{AttributeValue, Element, Node, Program};
+use umc_html_traverse::{TraverseHtmlMut, traverse_program_mut};
+use umc_traverse::TraverseOperate;
+
+use crate::{
+  defs::{References, is_unused_def},
+  is_named,
+  path::PathData,
+};
+
+/// Which passes [`optimize`] runs. Every pass is enabled by default.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct OptimizeOptions {
+  /// Run [`RemoveComments`]
+  pub remove_comments: bool,
+  /// Run [`RemoveMetadata`]
+  pub remove_metadata: bool,
+  /// Run [`RemoveEditorData`]
+  pub remove_editor_data: bool,
+  /// Run [`RemoveUnusedDefs`]
+  pub remove_unused_defs: bool,
+  /// Run [`RemoveWhitespace`]
+  pub remove_whitespace: bool,
+  /// Run [`MinifyPathData`] with this precision, the number of decimals kept. `3` by default.
+  pub path_precision: Option<u8>,
+}
+
+impl Default for OptimizeOptions {
+  fn default() -> Self {
+    Self {
+      remove_comments: true,
+      remove_metadata: true,
+      remove_editor_data: true,
+      remove_unused_defs: true,
+      remove_whitespace: true,
+      path_precision: Some(3),
+    }
+  }
+}
+
+/// Optimize an SVG document in place, like [SVGO](https://github.com/svg/svgo) does, with
+/// the passes enabled in `options`. Print the result with [`codegen`](crate::codegen::codegen).
+///
+/// New attribute values are allocated in `allocator`.
+///
+/// ## Example
+///
+/// ```
+/// use oxc_allocator::Allocator;
+/// use umc_svg::{
+///   codegen::codegen,
+///   optimize::{OptimizeOptions, optimize},
+///   parse_svg,
+/// };
+///
+/// const SVG: &str = r#"<svg xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" viewBox="0 0 24 24">
+///   <!-- Generator: Inkscape -->
+///   <metadata>...</metadata>
+///   <path inkscape:label="Layer" d="M 2.0000 2.0000 L 22.0000 22.0000"/>
+/// </svg>"#;
+///
+/// let allocator = Allocator::default();
+/// let mut program = parse_svg(&allocator, SVG).program;
+/// optimize(&allocator, &mut program, &OptimizeOptions::default());
+///
+/// assert_eq!(
+///   codegen(&program),
+///   r#"<svg viewBox="0 0 24 24"><path d="M2 2 22 22"/></svg>"#
+/// );
+/// ```
+pub fn optimize<'a>(
+  allocator: &'a Allocator,
+  program: &mut Program<'a>,
+  options: &OptimizeOptions,
+) {
+  if options.remove_comments {
+    traverse_program_mut(program, &mut RemoveComments);
+  }
+  if options.remove_metadata {
+    traverse_program_mut(program, &mut RemoveMetadata);
+  }
+  if options.remove_editor_data {
+    traverse_program_mut(program, &mut RemoveEditorData);
+  }
+  if options.remove_unused_defs {
+    let mut pass = RemoveUnusedDefs::new(program);
+    traverse_program_mut(program, &mut pass);
+  }
+  if options.remove_whitespace {
+    traverse_program_mut(program, &mut RemoveWhitespace);
+  }
+  if let Some(precision) = options.path_precision {
+    traverse_program_mut(program, &mut MinifyPathData::new(allocator, precision));
+  }
+}
+
+/// Remove comments, except the ones starting with `!`, like `<!--! License -->`, which are
+/// kept as legal comments.
+///
+/// CDATA sections, which are bogus comments in the tree, are kept, as are XML declarations,
+/// which are [directives](umc_html_ast::Directive).
+pub struct RemoveComments;
+
+impl RemoveComments {
+  fn retain(nodes: &mut Vec<'_, Node<'_>>) {
+    nodes.retain(|node| match node {
+      Node::Comment(comment) => comment.bogus || comment.value.starts_with('!'),
+      _ => true,
+    });
+  }
+}
+
+impl<'a> TraverseHtmlMut<'a> for RemoveComments {
+  fn enter_program(&mut self, program: &mut Program<'a>) -> TraverseOperate {
+    Self::retain(program);
+    TraverseOperate::Continue
+  }
+
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    Self::retain(&mut element.children);
+    TraverseOperate::Continue
+  }
+}
+
+/// Remove `<metadata>` elements, e.g. the RDF licensing data written by editors, which is
+/// not rendered.
+pub struct RemoveMetadata;
+
+impl<'a> TraverseHtmlMut<'a> for RemoveMetadata {
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    element
+      .children
+      .retain(|node| !matches!(node, Node::Element(child) if is_named(child, "metadata")));
+    TraverseOperate::Continue
+  }
+}
+
+/// Namespace prefixes of the private data of editors.
+const EDITOR_PREFIXES: &[&str] = &["inkscape", "sodipodi", "sketch", "serif", "i", "graph"];
+
+/// Whether a name is in the namespace of an editor, e.g. `inkscape:label`, or declares one,
+/// e.g. `xmlns:inkscape`.
+fn is_editor_name(name: &str) -> bool {
+  let Some((prefix, local)) = name.split_once(':') else {
+    return false;
+  };
+  let prefix = if prefix.eq_ignore_ascii_case("xmlns") {
+    local
+  } else {
+    prefix
+  };
+  EDITOR_PREFIXES
+    .iter()
+    .any(|editor| prefix.eq_ignore_ascii_case(editor))
+}
+
+/// Remove the elements and attributes editors like Inkscape, Sketch or Illustrator add for
+/// themselves, like `<sodipodi:namedview>` and `inkscape:label`, with their namespace
+/// declarations.
+pub struct RemoveEditorData;
+
+impl<'a> TraverseHtmlMut<'a> for RemoveEditorData {
+  fn enter_program(&mut self, program: &mut Program<'a>) -> TraverseOperate {
+    program
+      .retain(|node| !matches!(node, Node::Element(element) if is_editor_name(element.name())));
+    TraverseOperate::Continue
+  }
+
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    element
+      .attributes
+      .retain(|attribute| !is_editor_name(attribute.key.name()));
+    element
+      .children
+      .retain(|node| !matches!(node, Node::Element(child) if is_editor_name(child.name())));
+    TraverseOperate::Continue
+  }
+}
+
+/// Remove the unused children of `<defs>`, see [`is_unused_def`], and the `<defs>` left
+/// empty.
+pub struct RemoveUnusedDefs<'a> {
+  references: References<'a>,
+}
+
+impl<'a> RemoveUnusedDefs<'a> {
+  /// Create the pass for a document, collecting its references.
+  pub fn new(program: &Program<'a>) -> Self {
+    Self {
+      references: References::collect(program),
+    }
+  }
+
+  /// Remove the `<defs>` without definitions left.
+  fn retain(nodes: &mut Vec<'a, Node<'a>>) {
+    nodes.retain(|node| match node {
+      Node::Element(element) if is_named(element, "defs") => {
+        !element.children.iter().all(is_whitespace)
+      }
+      _ => true,
+    });
+  }
+}
+
+impl<'a> TraverseHtmlMut<'a> for RemoveUnusedDefs<'a> {
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    if is_named(element, "defs") {
+      let references = &self.references;
+      element
+        .children
+        .retain(|node| !matches!(node, Node::Element(child) if is_unused_def(child, references)));
+    }
+    TraverseOperate::Continue
+  }
+
+  fn exit_element(&mut self, element: &mut Element<'a>) {
+    Self::retain(&mut element.children);
+  }
+
+  fn exit_program(&mut self, program: &mut Program<'a>) {
+    Self::retain(program);
+  }
+}
+
+/// Elements whose whitespace is rendered or meaningful.
+const WHITESPACE_ELEMENTS: &[&str] = &[
+  "text",
+  "tspan",
+  "textPath",
+  "title",
+  "desc",
+  "style",
+  "script",
+  "foreignObject",
+];
+
+fn is_whitespace(node: &Node) -> bool {
+  matches!(node, Node::Text(text) if text.value.trim().is_empty())
+}
+
+/// Remove the text made of whitespace only, e.g. indentation, except in elements where it is
+/// rendered like `<text>`.
+pub struct RemoveWhitespace;
+
+impl<'a> TraverseHtmlMut<'a> for RemoveWhitespace {
+  fn enter_program(&mut self, program: &mut Program<'a>) -> TraverseOperate {
+    program.retain(|node| !is_whitespace(node));
+    TraverseOperate::Continue
+  }
+
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    if WHITESPACE_ELEMENTS
+      .iter()
+      .any(|name| is_named(element, name))
+    {
+      return TraverseOperate::Skip;
+    }
+    element.children.retain(|node| !is_whitespace(node));
+    TraverseOperate::Continue
+  }
+}
+
+/// Rewrite the `d` attribute of `<path>` elements in its [shortest form](PathData::minify),
+/// keeping path data with errors as written.
+pub struct MinifyPathData<'a> {
+  allocator: &'a Allocator,
+  precision: u8,
+}
+
+impl<'a> MinifyPathData<'a> {
+  /// Create the pass, rounding numbers to `precision` decimals.
+  pub const fn new(allocator: &'a Allocator, precision: u8) -> Self {
+    Self {
+      allocator,
+      precision,
+    }
+  }
+}
+
+impl<'a> TraverseHtmlMut<'a> for MinifyPathData<'a> {
+  fn enter_element(&mut self, element: &mut Element<'a>) -> TraverseOperate {
+    if !is_named(element, "path") {
+      return TraverseOperate::Continue;
+    }
+    for attribute in &mut element.attributes {
+      if attribute.key.name() != "d" {
+        continue;
+      }
+      let Some(value) = &mut attribute.value else {
+        continue;
+      };
+      let path = PathData::parse(value);
+      if !path.errors.is_empty() {
+        continue;
+      }
+      let minified = path.minify(self.precision);
+      if minified.len() < value.value.len() {
+        let minified = self.allocator.alloc_str(&minified);
+        *value = AttributeValue {
+          span: value.span,
+          value_span: value.value_span,
+          value: minified,
+          raw: minified,
+        };
+      }
+    }
+    TraverseOperate::Continue
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use super::{OptimizeOptions, optimize};
+  use crate::{codegen::codegen, parse_svg};
+
+  fn optimized(source_text: &str) -> String {
+    let allocator = Allocator::default();
+    let mut program = parse_svg(&allocator, source_text).program;
+    optimize(&allocator, &mut program, &OptimizeOptions::default());
+    codegen(&program)
+  }
+
+  #[test]
+  fn unused_defs() {
+    assert_eq!(
+      optimized(
+        r#"<svg>
+  <defs>
+    <linearGradient id="used"/>
+    <linearGradient id="unused"/>
+    <style>.a { fill: url(#styled) }</style>
+    <clipPath id="styled"/>
+  </defs>
+  <defs><circle r="1"/></defs>
+  <rect fill="url(#used)"/>
+</svg>"#
+      ),
+      r#"<svg><defs><linearGradient id="used"/><style>.a { fill: url(#styled) }</style><clipPath id="styled"/></defs><rect fill="url(#used)"/></svg>"#
+    );
+  }
+
+  #[test]
+  fn keep() {
+    assert_eq!(
+      optimized(
+        "<?xml version=\"1.0\"?>\n<!--! MIT License -->\n<svg><text> a <tspan> b </tspan></text>\n<path d=\"M 0 0 L\"/></svg>"
+      ),
+      "<?xml version=\"1.0\"?><!--! MIT License --><svg><text> a <tspan> b </tspan></text><path d=\"M 0 0 L\"/></svg>"
+    );
+  }
+
+  #[test]
+  fn inline_svg() {
+    const HTML: &str = r#"<p>Icon: <svg viewBox="0 0 8 8"><sodipodi:namedview/><path d="M 0.50 0.50 H 7.50"></path></svg></p>"#;
+    let allocator = Allocator::default();
+    let mut program = Parser::html(&allocator, HTML).parse().program;
+    optimize(&allocator, &mut program, &OptimizeOptions::default());
+    assert_eq!(
+      codegen(&program),
+      r#"<p>Icon: <svg viewBox="0 0 8 8"><path d="M.5.5H7.5"/></svg></p>"#
+    );
+  }
+}
@@ -0,0 +1,378 @@
+use std::fmt::Write;
+
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::AttributeValue;
+use umc_span::Span;
+
+/// A command of path data with its arguments, e.g. `L 10 20`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathSegment {
+  /// Span of the command and its arguments. Starts at the first argument for a command
+  /// repeated implicitly, like the second pair of `L 1 2 3 4`.
+  pub span: Span,
+  /// The command letter, uppercase for absolute coordinates. A repeated command gets the
+  /// letter of its command, except after a moveto, where it is a lineto: `L` for `M`, `l`
+  /// for `m`.
+  pub command: char,
+  /// The arguments, with the flags of arcs as `0.0` or `1.0`
+  pub args: Vec<f64>,
+}
+
+/// Parsed path data, the `d` attribute of a `<path>`.
+///
+/// Follows the [path data grammar](https://www.w3.org/TR/SVG2/paths.html#PathDataBNF).
+/// Like browsers, parsing stops at the first error, which is reported in `errors`, and
+/// the segments before it are kept.
+#[derive(Debug, Default)]
+pub struct PathData {
+  /// The segments, in source order
+  pub segments: Vec<PathSegment>,
+  /// The error parsing stopped at, if any
+  pub errors: Vec<OxcDiagnostic>,
+}
+
+impl PathData {
+  /// Parse the value of a `d` attribute.
+  pub fn parse(value: &AttributeValue) -> Self {
+    Self::parse_str(value.value, value.value_span.start)
+  }
+
+  /// Parse `input`, which starts at offset `start` of the source text.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_svg::path::PathData;
+  ///
+  /// let path = PathData::parse_str("M10-20l.5.5 1e1,0z", 0);
+  /// assert!(path.errors.is_empty());
+  /// assert_eq!(path.segments[1].args, [0.5, 0.5]);
+  /// assert_eq!(path.segments[2].command, 'l');
+  /// assert_eq!(path.segments[2].args, [10.0, 0.0]);
+  /// ```
+  pub fn parse_str(input: &str, start: u32) -> Self {
+    let mut parser = PathParser {
+      input,
+      start,
+      pos: 0,
+      path: Self::default(),
+    };
+    parser.parse();
+    parser.path
+  }
+
+  /// Print the segments in their shortest form: numbers rounded to `precision` decimals
+  /// without redundant zeros, and no command letter nor separator where the grammar allows
+  /// to omit them.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_svg::path::PathData;
+  ///
+  /// let path = PathData::parse_str("M 10.0 20.0 L 0.5 -0.25 L 30.123456 40 Z", 0);
+  /// assert_eq!(path.minify(3), "M10 20 .5-.25 30.123 40z");
+  /// ```
+  pub fn minify(&self, precision: u8) -> String {
+    let mut output = String::new();
+    let mut previous: Option<char> = None;
+    for segment in &self.segments {
+      let implicit = previous.is_some_and(|previous| {
+        (previous == segment.command && !matches!(previous, 'M' | 'm'))
+          || (previous == 'M' && segment.command == 'L')
+          || (previous == 'm' && segment.command == 'l')
+      });
+      let command = if matches!(segment.command, 'Z' | 'z') {
+        'z'
+      } else {
+        segment.command
+      };
+      if !implicit || segment.args.is_empty() {
+        output.push(command);
+      }
+      for arg in &segment.args {
+        let number = format_number(*arg, precision);
+        if needs_separator(&output, &number) {
+          output.push(' ');
+        }
+        output.push_str(&number);
+      }
+      previous = Some(command);
+    }
+    output
+  }
+}
+
+/// Print a number with at most `precision` decimals, without trailing zeros nor leading
+/// zero, e.g. `.5` for `0.5`.
+pub fn format_number(number: f64, precision: u8) -> String {
+  let mut text = String::new();
+  let _ = write!(text, "{:.*}", usize::from(precision), number);
+  if text.contains('.') {
+    text.truncate(text.trim_end_matches('0').trim_end_matches('.').len());
+  }
+  if text == "-0" {
+    return "0".to_string();
+  }
+  if let Some(rest) = text.strip_prefix("0.") {
+    return format!(".{rest}");
+  }
+  if let Some(rest) = text.strip_prefix("-0.") {
+    return format!("-.{rest}");
+  }
+  text
+}
+
+/// Whether a space is needed between the text printed so far and the next number.
+fn needs_separator(previous: &str, number: &str) -> bool {
+  let Some(last) = previous.chars().last() else {
+    return false;
+  };
+  if !last.is_ascii_digit() && last != '.' {
+    return false;
+  }
+  if number.starts_with('-') {
+    return false;
+  }
+  if number.starts_with('.') {
+    // `.5.5` reads as two numbers, when the previous one already has a decimal point
+    let previous_number = previous
+      .rsplit(|c: char| !c.is_ascii_digit() && c != '.')
+      .next()
+      .unwrap_or_default();
+    return !previous_number.contains('.');
+  }
+  true
+}
+
+struct PathParser<'s> {
+  input: &'s str,
+  start: u32,
+  pos: usize,
+  path: PathData,
+}
+
+impl PathParser<'_> {
+  #[expect(clippy::cast_possible_truncation)]
+  const fn span(&self, start: usize, end: usize) -> Span {
+    Span::new(self.start + start as u32, self.start + end as u32)
+  }
+
+  fn peek(&self) -> Option<u8> {
+    self.input.as_bytes().get(self.pos).copied()
+  }
+
+  fn skip_whitespace(&mut self) {
+    while self.peek().is_some_and(|c| c.is_ascii_whitespace()) {
+      self.pos += 1;
+    }
+  }
+
+  /// Skip whitespace and at most one comma between two arguments.
+  fn skip_separator(&mut self) {
+    self.skip_whitespace();
+    if self.peek() == Some(b',') {
+      self.pos += 1;
+      self.skip_whitespace();
+    }
+  }
+
+  fn error(&mut self, message: impl Into<String>, start: usize, end: usize) {
+    let span = self.span(start, end.min(self.input.len()));
+    self
+      .path
+      .errors
+      .push(OxcDiagnostic::error(message.into()).with_label(span));
+  }
+
+  fn parse(&mut self) {
+    self.skip_whitespace();
+    let mut command: Option<u8> = None;
+    while self.pos < self.input.len() {
+      let segment_start = self.pos;
+      let current = match self.peek() {
+        Some(c) if b"MmZzLlHhVvCcSsQqTtAa".contains(&c) => {
+          self.pos += 1;
+          self.skip_whitespace();
+          c
+        }
+        Some(c) if c.is_ascii_digit() || b"+-.".contains(&c) => match command {
+          Some(b'M') => b'L',
+          Some(b'm') => b'l',
+          Some(command) if !matches!(command, b'Z' | b'z') => command,
+          _ => {
+            self.error("Expected a path command", self.pos, self.pos + 1);
+            return;
+          }
+        },
+        _ => {
+          let end = self.input[self.pos..]
+            .chars()
+            .next()
+            .map_or(self.pos, |c| self.pos + c.len_utf8());
+          self.error("Expected a path command", self.pos, end);
+          return;
+        }
+      };
+      if command.is_none() && !matches!(current, b'M' | b'm') {
+        self.error(
+          "Path data must start with a moveto command",
+          segment_start,
+          segment_start + 1,
+        );
+        return;
+      }
+      let Some(args) = self.parse_args(current) else {
+        return;
+      };
+      let end = if args.is_empty() {
+        segment_start + 1
+      } else {
+        self.pos
+      };
+      self.path.segments.push(PathSegment {
+        span: self.span(segment_start, end),
+        command: char::from(current),
+        args,
+      });
+      self.skip_separator();
+      command = Some(current);
+    }
+  }
+
+  /// Parse the arguments of a command, `None` after reporting an error.
+  fn parse_args(&mut self, command: u8) -> Option<Vec<f64>> {
+    let count = match command.to_ascii_uppercase() {
+      b'Z' => 0,
+      b'H' | b'V' => 1,
+      b'M' | b'L' | b'T' => 2,
+      b'S' | b'Q' => 4,
+      b'C' => 6,
+      _ => 7,
+    };
+    let mut args = Vec::with_capacity(count);
+    for index in 0..count {
+      if index > 0 {
+        self.skip_separator();
+      }
+      let arg = if command.eq_ignore_ascii_case(&b'A') && matches!(index, 3 | 4) {
+        self.parse_flag()
+      } else {
+        self.parse_number()
+      };
+      args.push(arg?);
+    }
+    Some(args)
+  }
+
+  fn parse_flag(&mut self) -> Option<f64> {
+    match self.peek() {
+      Some(b'0') => {
+        self.pos += 1;
+        Some(0.0)
+      }
+      Some(b'1') => {
+        self.pos += 1;
+        Some(1.0)
+      }
+      _ => {
+        self.error("Expected an arc flag, `0` or `1`", self.pos, self.pos + 1);
+        None
+      }
+    }
+  }
+
+  fn parse_number(&mut self) -> Option<f64> {
+    let bytes = self.input.as_bytes();
+    let start = self.pos;
+    let mut end = start;
+    if matches!(bytes.get(end), Some(b'+' | b'-')) {
+      end += 1;
+    }
+    let integer_start = end;
+    while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+      end += 1;
+    }
+    let mut digits = end > integer_start;
+    if bytes.get(end) == Some(&b'.') {
+      let fraction_start = end + 1;
+      let mut fraction_end = fraction_start;
+      while bytes.get(fraction_end).is_some_and(u8::is_ascii_digit) {
+        fraction_end += 1;
+      }
+      if fraction_end > fraction_start {
+        digits = true;
+        end = fraction_end;
+      } else if digits {
+        end = fraction_start;
+      }
+    }
+    if !digits {
+      self.error("Expected a number", start, start + 1);
+      return None;
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+      let mut exponent_end = end + 1;
+      if matches!(bytes.get(exponent_end), Some(b'+' | b'-')) {
+        exponent_end += 1;
+      }
+      let exponent_digits = exponent_end;
+      while bytes.get(exponent_end).is_some_and(u8::is_ascii_digit) {
+        exponent_end += 1;
+      }
+      if exponent_end > exponent_digits {
+        end = exponent_end;
+      }
+    }
+    self.pos = end;
+    self.input[start..end].parse().ok()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use umc_span::Span;
+
+  use super::{PathData, format_number};
+
+  #[test]
+  fn parse() {
+    let path = PathData::parse_str("M 0,0 10 10 h5 a5 5 0 1010 10 Z m1 1", 10);
+    assert!(path.errors.is_empty(), "{:?}", path.errors);
+    let commands: String = path
+      .segments
+      .iter()
+      .map(|segment| segment.command)
+      .collect();
+    assert_eq!(commands, "MLhaZm");
+    assert_eq!(path.segments[1].span, Span::new(16, 21));
+    assert_eq!(path.segments[3].args, [5.0, 5.0, 0.0, 1.0, 0.0, 10.0, 10.0]);
+    assert_eq!(path.segments[4].span, Span::new(40, 41));
+  }
+
+  #[test]
+  fn errors() {
+    let path = PathData::parse_str("L 0 0", 0);
+    assert!(path.segments.is_empty());
+    assert_eq!(path.errors.len(), 1);
+
+    let path = PathData::parse_str("M 0 0 L 1 x", 0);
+    assert_eq!(path.segments.len(), 1);
+    assert_eq!(path.errors[0].to_string(), "Expected a number");
+
+    let path = PathData::parse_str("M 0 0 A 1 1 0 2 0 1 1", 0);
+    assert_eq!(
+      path.errors[0].to_string(),
+      "Expected an arc flag, `0` or `1`"
+    );
+  }
+
+  #[test]
+  fn minify() {
+    let path = PathData::parse_str("M 0 0 L 1 1 L 2 2 M 3 3 C 0.1 0.2 .3 .4 -0.5 -0.6 z", 0);
+    assert_eq!(path.minify(3), "M0 0 1 1 2 2M3 3C.1.2.3.4-.5-.6z");
+    assert_eq!(format_number(-0.0001, 3), "0");
+    assert_eq!(format_number(12.5, 0), "12");
+    assert_eq!(format_number(1.0e3, 2), "1000");
+  }
+}
@@ -0,0 +1,138 @@
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::AttributeValue;
+use umc_span::Span;
+
+/// A parsed `viewBox` attribute, the rectangle of user space mapped to the viewport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewBox {
+  /// Span of the value, without quotes
+  pub span: Span,
+  pub min_x: f64,
+  pub min_y: f64,
+  pub width: f64,
+  pub height: f64,
+}
+
+impl ViewBox {
+  /// Parse and validate the value of a `viewBox` attribute.
+  pub fn parse(value: &AttributeValue) -> Result<Self, OxcDiagnostic> {
+    Self::parse_str(value.value, value.value_span.start)
+  }
+
+  /// Parse and validate `input`, which starts at offset `start` of the source text.
+  ///
+  /// The value must be four numbers separated by whitespace or a comma, and the width and
+  /// height must not be negative, see
+  /// <https://www.w3.org/TR/SVG2/coords.html#ViewBoxAttribute>.
+  ///
+  /// ## Example
+  ///
+  /// ```
+  /// use umc_svg::view_box::ViewBox;
+  ///
+  /// let view_box = ViewBox::parse_str("0,0 24 24", 0).unwrap();
+  /// assert_eq!(view_box.width, 24.0);
+  ///
+  /// assert!(ViewBox::parse_str("0 0 -1 24", 0).is_err());
+  /// assert!(ViewBox::parse_str("0 0 24", 0).is_err());
+  /// ```
+  #[expect(clippy::cast_possible_truncation)]
+  pub fn parse_str(input: &str, start: u32) -> Result<Self, OxcDiagnostic> {
+    let span = |from: usize, to: usize| Span::new(start + from as u32, start + to as u32);
+    let whole = span(0, input.len());
+
+    let mut numbers = Vec::with_capacity(4);
+    for (offset, part) in split_list(input) {
+      let Some(number) = part.parse::<f64>().ok().filter(|number| number.is_finite()) else {
+        return Err(
+          OxcDiagnostic::error(format!("Invalid number `{part}` in `viewBox`"))
+            .with_label(span(offset, offset + part.len())),
+        );
+      };
+      numbers.push((number, span(offset, offset + part.len())));
+    }
+    let [
+      (min_x, _),
+      (min_y, _),
+      (width, width_span),
+      (height, height_span),
+    ] = numbers[..]
+    else {
+      return Err(
+        OxcDiagnostic::error(format!(
+          "`viewBox` must have 4 numbers, found {}",
+          numbers.len()
+        ))
+        .with_label(whole),
+      );
+    };
+    for (value, span, name) in [
+      (width, width_span, "width"),
+      (height, height_span, "height"),
+    ] {
+      if value < 0.0 {
+        return Err(
+          OxcDiagnostic::error(format!("The {name} of a `viewBox` must not be negative"))
+            .with_label(span),
+        );
+      }
+    }
+    Ok(Self {
+      span: whole,
+      min_x,
+      min_y,
+      width,
+      height,
+    })
+  }
+
+  /// Whether the width or height is zero, which disables the rendering of the element.
+  pub fn is_empty(&self) -> bool {
+    self.width == 0.0 || self.height == 0.0
+  }
+}
+
+/// Split a list of numbers separated by whitespace and commas, with the offset of each
+/// item.
+fn split_list(input: &str) -> impl Iterator<Item = (usize, &str)> {
+  input
+    .split(|c: char| c.is_ascii_whitespace() || c == ',')
+    .scan(0, |offset, part| {
+      let start = *offset;
+      *offset += part.len() + 1;
+      Some((start, part))
+    })
+    .filter(|(_, part)| !part.is_empty())
+}
+
+#[cfg(test)]
+mod test {
+  use super::ViewBox;
+
+  #[test]
+  fn view_box() {
+    let view_box = ViewBox::parse_str(" -1.5, 2 1e2\t50 ", 10).unwrap();
+    assert_eq!(
+      (
+        view_box.min_x,
+        view_box.min_y,
+        view_box.width,
+        view_box.height
+      ),
+      (-1.5, 2.0, 100.0, 50.0)
+    );
+    assert!(!view_box.is_empty());
+    assert!(ViewBox::parse_str("0 0 0 10", 0).unwrap().is_empty());
+
+    let error = ViewBox::parse_str("0 0 10 -5", 10).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "The height of a `viewBox` must not be negative"
+    );
+    let label = &error.labels.as_ref().unwrap()[0];
+    assert_eq!((label.offset(), label.len()), (17, 2));
+
+    let error = ViewBox::parse_str("0 0 10 auto", 0).unwrap_err();
+    assert_eq!(error.to_string(), "Invalid number `auto` in `viewBox`");
+  }
+}
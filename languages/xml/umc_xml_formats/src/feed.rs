@@ -0,0 +1,229 @@
+use umc_html_ast::{Element, Program};
+use umc_span::Span;
+
+use crate::{Field, child_text, children, local_name, root};
+
+/// The format of a [`Feed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedKind {
+  /// RSS 2.0, or RSS 0.9x: `<rss><channel>` with `<item>` elements
+  Rss,
+  /// RSS 1.0, RDF: `<rdf:RDF>` with a `<channel>` and `<item>` elements
+  Rdf,
+  /// Atom: `<feed>` with `<entry>` elements
+  Atom,
+}
+
+/// An RSS or Atom feed.
+#[derive(Debug, Clone)]
+pub struct Feed<'a> {
+  /// The format of the feed
+  pub kind: FeedKind,
+  /// Source location of the channel, or of the `<feed>` of Atom
+  pub span: Span,
+  /// The title of the feed
+  pub title: Option<Field<'a>>,
+  /// The link to the website of the feed, the `href` of the alternate `<link>` in Atom
+  pub link: Option<Field<'a>>,
+  /// The description of the feed, its `<subtitle>` in Atom
+  pub description: Option<Field<'a>>,
+  /// The items, or entries, of the feed, in document order
+  pub items: Vec<FeedItem<'a>>,
+}
+
+/// An item of an RSS feed, or an entry of an Atom feed.
+#[derive(Debug, Clone)]
+pub struct FeedItem<'a> {
+  /// Source location of the item
+  pub span: Span,
+  /// The title of the item
+  pub title: Option<Field<'a>>,
+  /// The link to the item
+  pub link: Option<Field<'a>>,
+  /// The identifier of the item, its `<guid>` in RSS
+  pub id: Option<Field<'a>>,
+  /// The publication date, as written: `<pubDate>` or `<dc:date>` in RSS, `<published>` or
+  /// else `<updated>` in Atom
+  pub published: Option<Field<'a>>,
+  /// The summary of the item: `<description>` in RSS, `<summary>` or else `<content>` in
+  /// Atom
+  pub summary: Option<Field<'a>>,
+}
+
+impl<'a> Feed<'a> {
+  /// Read the feed of a document, `None` if its root element is not the one of a feed.
+  pub fn from_program(program: &Program<'a>) -> Option<Self> {
+    let root = root(program)?;
+    match local_name(root) {
+      "rss" => {
+        let channel = children(root).find(|child| local_name(child) == "channel")?;
+        Some(Self::rss(FeedKind::Rss, channel, channel))
+      }
+      "RDF" => {
+        let channel = children(root).find(|child| local_name(child) == "channel")?;
+        Some(Self::rss(FeedKind::Rdf, channel, root))
+      }
+      "feed" => Some(Self::atom(root)),
+      _ => None,
+    }
+  }
+
+  /// Read an RSS feed, whose items are in `items`, the channel in RSS 2.0 and the root in
+  /// RSS 1.0.
+  fn rss(kind: FeedKind, channel: &Element<'a>, items: &Element<'a>) -> Self {
+    Self {
+      kind,
+      span: channel.span,
+      title: child_text(channel, &["title"]),
+      link: child_text(channel, &["link"]),
+      description: child_text(channel, &["description"]),
+      items: children(items)
+        .filter(|child| local_name(child) == "item")
+        .map(|item| FeedItem {
+          span: item.span,
+          title: child_text(item, &["title"]),
+          link: child_text(item, &["link"]),
+          id: child_text(item, &["guid"]),
+          published: child_text(item, &["pubDate", "date"]),
+          summary: child_text(item, &["description"]),
+        })
+        .collect(),
+    }
+  }
+
+  fn atom(feed: &Element<'a>) -> Self {
+    Self {
+      kind: FeedKind::Atom,
+      span: feed.span,
+      title: child_text(feed, &["title"]),
+      link: atom_link(feed),
+      description: child_text(feed, &["subtitle"]),
+      items: children(feed)
+        .filter(|child| local_name(child) == "entry")
+        .map(|entry| FeedItem {
+          span: entry.span,
+          title: child_text(entry, &["title"]),
+          link: atom_link(entry),
+          id: child_text(entry, &["id"]),
+          published: child_text(entry, &["published", "updated"]),
+          summary: child_text(entry, &["summary", "content"]),
+        })
+        .collect(),
+    }
+  }
+}
+
+/// The `href` of the alternate `<link>` of an Atom feed or entry, the one without `rel` or
+/// with `rel="alternate"`.
+fn atom_link<'a>(element: &Element<'a>) -> Option<Field<'a>> {
+  children(element)
+    .filter(|child| local_name(child) == "link")
+    .find(|link| Field::attribute(link, "rel").is_none_or(|rel| rel.value == "alternate"))
+    .and_then(|link| Field::attribute(link, "href"))
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+
+  use crate::{Feed, FeedKind, parse_xml};
+
+  #[test]
+  fn rss() {
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Blog</title>
+    <link>https://example.com/</link>
+    <description>Posts &amp; notes</description>
+    <item>
+      <title>First</title>
+      <link>https://example.com/1</link>
+      <guid isPermaLink="false">1</guid>
+      <pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>
+      <description><![CDATA[<p>Hello <b>world</b></p>]]></description>
+    </item>
+    <item><title>Second</title><dc:date>2024-01-02</dc:date></item>
+  </channel>
+</rss>"#;
+
+    let allocator = Allocator::default();
+    let result = parse_xml(&allocator, RSS);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let feed = Feed::from_program(&result.program).unwrap();
+
+    assert_eq!(feed.kind, FeedKind::Rss);
+    assert_eq!(feed.title.unwrap().value, "Blog");
+    assert_eq!(feed.link.unwrap().value, "https://example.com/");
+    assert_eq!(feed.description.unwrap().value, "Posts & notes");
+    let [first, second] = &feed.items[..] else {
+      unreachable!()
+    };
+    assert_eq!(first.id.as_ref().unwrap().value, "1");
+    assert_eq!(
+      first.published.as_ref().unwrap().value,
+      "Mon, 01 Jan 2024 00:00:00 GMT"
+    );
+    assert_eq!(
+      first.summary.as_ref().unwrap().value,
+      "<p>Hello <b>world</b></p>"
+    );
+    let title = second.title.as_ref().unwrap();
+    assert_eq!(
+      &RSS[title.span.start as usize..title.span.end as usize],
+      "<title>Second</title>"
+    );
+    assert_eq!(second.published.as_ref().unwrap().value, "2024-01-02");
+    assert!(second.link.is_none());
+  }
+
+  #[test]
+  fn atom() {
+    const ATOM: &str = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">Example</title>
+  <subtitle>All the news</subtitle>
+  <link rel="self" href="https://example.com/feed.xml"/>
+  <link href="https://example.com/"/>
+  <entry>
+    <title>Post</title>
+    <link rel="alternate" href="https://example.com/post"/>
+    <id>urn:uuid:1</id>
+    <updated>2024-01-01T00:00:00Z</updated>
+    <content type="html">&lt;p&gt;Hi&lt;/p&gt;</content>
+  </entry>
+</feed>"#;
+
+    let allocator = Allocator::default();
+    let result = parse_xml(&allocator, ATOM);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let feed = Feed::from_program(&result.program).unwrap();
+
+    assert_eq!(feed.kind, FeedKind::Atom);
+    assert_eq!(feed.title.unwrap().value, "Example");
+    assert_eq!(feed.description.unwrap().value, "All the news");
+    let link = feed.link.unwrap();
+    assert_eq!(link.value, "https://example.com/");
+    assert_eq!(
+      &ATOM[link.span.start as usize..link.span.end as usize],
+      "\"https://example.com/\""
+    );
+    let entry = &feed.items[0];
+    assert_eq!(
+      entry.link.as_ref().unwrap().value,
+      "https://example.com/post"
+    );
+    assert_eq!(entry.id.as_ref().unwrap().value, "urn:uuid:1");
+    assert_eq!(
+      entry.published.as_ref().unwrap().value,
+      "2024-01-01T00:00:00Z"
+    );
+    assert_eq!(entry.summary.as_ref().unwrap().value, "<p>Hi</p>");
+  }
+
+  #[test]
+  fn not_a_feed() {
+    let allocator = Allocator::default();
+    let result = parse_xml(&allocator, "<urlset></urlset>");
+    assert!(Feed::from_program(&result.program).is_none());
+  }
+}
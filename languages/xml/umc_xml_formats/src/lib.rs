@@ -0,0 +1,209 @@
+//! Typed extraction of common XML formats, for crawlers and aggregators.
+//!
+//! Documents are parsed by the HTML parser with the [XML dialect](DialectSpec::XML), see
+//! [`parse_xml`], then [`Feed::from_program`] reads RSS and Atom feeds, and
+//! [`Sitemap::from_program`] reads sitemaps, with the span of every value.
+//!
+//! # Example
+//!
+//! ```
+//! use oxc_allocator::Allocator;
+//! use umc_xml_formats::{Feed, FeedKind, parse_xml};
+//!
+//! let allocator = Allocator::default();
+//! let source_text = r#"<?xml version="1.0"?>
+//! <rss version="2.0"><channel>
+//!   <title>News</title>
+//!   <item><title>Tom &amp; Jerry</title><link>https://example.com/1</link></item>
+//! </channel></rss>"#;
+//! let result = parse_xml(&allocator, source_text);
+//!
+//! let feed = Feed::from_program(&result.program).unwrap();
+//! assert_eq!(feed.kind, FeedKind::Rss);
+//! assert_eq!(feed.items[0].title.as_ref().unwrap().value, "Tom & Jerry");
+//! ```
+
+use std::borrow::Cow;
+
+use oxc_allocator::Allocator;
+use umc_html_ast::{Element, Node, Program};
+use umc_html_parser::{CreateHtml, dialect::DialectSpec, option::HtmlParserOption};
+use umc_parser::{ParseResult, Parser};
+use umc_span::Span;
+
+mod feed;
+mod sitemap;
+
+pub use feed::{Feed, FeedItem, FeedKind};
+pub use sitemap::{Sitemap, SitemapUrl};
+
+/// Options of the HTML parser for XML documents: the [XML dialect](DialectSpec::XML),
+/// without parsing scripts.
+pub fn xml_options() -> HtmlParserOption {
+  HtmlParserOption::builder()
+    .dialect_spec(DialectSpec::XML)
+    .parse_script(None)
+    .build()
+}
+
+/// Parse an XML document with [`xml_options`].
+pub fn parse_xml<'a>(allocator: &'a Allocator, source_text: &'a str) -> ParseResult<Program<'a>> {
+  Parser::html(allocator, source_text)
+    .with_options(xml_options())
+    .parse()
+}
+
+/// A value read from a document, e.g. the title of a feed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field<'a> {
+  /// Source location of the element, or of the attribute value, the value is read from
+  pub span: Span,
+  /// The text, with CDATA sections unwrapped, entities decoded and surrounding whitespace
+  /// trimmed. Borrows the source text when it can.
+  pub value: Cow<'a, str>,
+}
+
+impl<'a> Field<'a> {
+  /// The text content of an element.
+  fn text(element: &Element<'a>) -> Self {
+    let mut parts = Vec::new();
+    text_parts(&element.children, &mut parts);
+    let value = match parts.as_slice() {
+      [] => Cow::Borrowed(""),
+      [part] => trim(part.clone()),
+      _ => Cow::Owned(parts.concat().trim().to_string()),
+    };
+    Self {
+      span: element.span,
+      value,
+    }
+  }
+
+  /// The value of an attribute of an element, `None` if it has none.
+  fn attribute(element: &Element<'a>, name: &str) -> Option<Self> {
+    let value = element
+      .attributes
+      .iter()
+      .find(|attribute| attribute.key.name() == name)?
+      .value
+      .as_ref()?;
+    Some(Self {
+      span: value.span,
+      value: trim(decode_entities(value.value)),
+    })
+  }
+}
+
+/// Collect the text of nodes and of their descendants.
+fn text_parts<'a>(nodes: &[Node<'a>], parts: &mut Vec<Cow<'a, str>>) {
+  for node in nodes {
+    match node {
+      Node::Text(text) => parts.push(decode_entities(text.value)),
+      Node::Comment(comment) if comment.bogus => {
+        if let Some(cdata) = comment
+          .value
+          .strip_prefix("[CDATA[")
+          .and_then(|value| value.strip_suffix("]]"))
+        {
+          parts.push(Cow::Borrowed(cdata));
+        }
+      }
+      Node::Element(element) => text_parts(&element.children, parts),
+      _ => {}
+    }
+  }
+}
+
+fn trim(text: Cow<'_, str>) -> Cow<'_, str> {
+  match text {
+    Cow::Borrowed(text) => Cow::Borrowed(text.trim()),
+    Cow::Owned(text) => Cow::Owned(text.trim().to_string()),
+  }
+}
+
+/// Decode the predefined entities of XML and character references, keeping unknown
+/// entities as written.
+fn decode_entities(text: &str) -> Cow<'_, str> {
+  if !text.contains('&') {
+    return Cow::Borrowed(text);
+  }
+  let mut decoded = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find('&') {
+    decoded.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let entity = rest.find(';').map(|end| (&rest[1..end], end));
+    let character = entity.and_then(|(name, _)| match name {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      _ => name
+        .strip_prefix("#x")
+        .or_else(|| name.strip_prefix("#X"))
+        .map_or_else(
+          || name.strip_prefix('#')?.parse().ok(),
+          |hex| u32::from_str_radix(hex, 16).ok(),
+        )
+        .and_then(char::from_u32),
+    });
+    if let (Some(character), Some((_, end))) = (character, entity) {
+      decoded.push(character);
+      rest = &rest[end + 1..];
+    } else {
+      decoded.push('&');
+      rest = &rest[1..];
+    }
+  }
+  decoded.push_str(rest);
+  Cow::Owned(decoded)
+}
+
+/// The name of an element without its namespace prefix, e.g. `date` for `dc:date`.
+fn local_name<'a>(element: &Element<'a>) -> &'a str {
+  let name = element.name();
+  name.rsplit(':').next().unwrap_or(name)
+}
+
+/// The root element of a document.
+fn root<'b, 'a>(program: &'b Program<'a>) -> Option<&'b Element<'a>> {
+  program.iter().find_map(|node| match node {
+    Node::Element(element) => Some(&**element),
+    _ => None,
+  })
+}
+
+/// The child elements of an element.
+fn children<'b, 'a>(element: &'b Element<'a>) -> impl Iterator<Item = &'b Element<'a>> {
+  element.children.iter().filter_map(|node| match node {
+    Node::Element(element) => Some(&**element),
+    _ => None,
+  })
+}
+
+/// The text of the first child element with one of the local names, in the order of the
+/// names.
+fn child_text<'a>(element: &Element<'a>, names: &[&str]) -> Option<Field<'a>> {
+  names.iter().find_map(|name| {
+    children(element)
+      .find(|child| local_name(child) == *name)
+      .map(Field::text)
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use std::borrow::Cow;
+
+  use super::decode_entities;
+
+  #[test]
+  fn entities() {
+    assert!(matches!(decode_entities("a b"), Cow::Borrowed("a b")));
+    assert_eq!(
+      decode_entities("&lt;b&gt; &amp;amp; &#65;&#x42; &nbsp; & x"),
+      "<b> &amp; AB &nbsp; & x"
+    );
+  }
+}
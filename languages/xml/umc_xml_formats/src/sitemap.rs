@@ -0,0 +1,119 @@
+use umc_html_ast::Program;
+use umc_span::Span;
+
+use crate::{Field, child_text, children, local_name, root};
+
+/// A sitemap, or a sitemap index listing other sitemaps, see
+/// <https://www.sitemaps.org/protocol.html>.
+#[derive(Debug, Clone)]
+pub struct Sitemap<'a> {
+  /// Source location of the `<urlset>` or `<sitemapindex>`
+  pub span: Span,
+  /// Whether the sitemap is a `<sitemapindex>`, whose URLs are the ones of sitemaps
+  pub index: bool,
+  /// The `<url>` elements, or the `<sitemap>` elements of an index, in document order
+  pub urls: Vec<SitemapUrl<'a>>,
+}
+
+/// A URL of a [`Sitemap`].
+#[derive(Debug, Clone)]
+pub struct SitemapUrl<'a> {
+  /// Source location of the `<url>` or `<sitemap>`
+  pub span: Span,
+  /// The URL, `None` if the required `<loc>` is missing
+  pub loc: Option<Field<'a>>,
+  /// The date of last modification, as written
+  pub lastmod: Option<Field<'a>>,
+  /// How often the page changes, e.g. `daily`. Not used by indexes.
+  pub changefreq: Option<Field<'a>>,
+  /// The priority of the page relative to the others of the site, e.g. `0.8`. Not used by
+  /// indexes.
+  pub priority: Option<Field<'a>>,
+}
+
+impl<'a> Sitemap<'a> {
+  /// Read the sitemap of a document, `None` if its root element is not a `<urlset>` nor a
+  /// `<sitemapindex>`.
+  pub fn from_program(program: &Program<'a>) -> Option<Self> {
+    let root = root(program)?;
+    let (index, url) = match local_name(root) {
+      "urlset" => (false, "url"),
+      "sitemapindex" => (true, "sitemap"),
+      _ => return None,
+    };
+    Some(Self {
+      span: root.span,
+      index,
+      urls: children(root)
+        .filter(|child| local_name(child) == url)
+        .map(|url| SitemapUrl {
+          span: url.span,
+          loc: child_text(url, &["loc"]),
+          lastmod: child_text(url, &["lastmod"]),
+          changefreq: child_text(url, &["changefreq"]),
+          priority: child_text(url, &["priority"]),
+        })
+        .collect(),
+    })
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+
+  use crate::{Sitemap, parse_xml};
+
+  #[test]
+  fn sitemap() {
+    const SITEMAP: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url>
+    <loc>https://example.com/?a=1&amp;b=2</loc>
+    <lastmod>2024-01-01</lastmod>
+    <changefreq>daily</changefreq>
+    <priority>0.8</priority>
+  </url>
+  <url><loc> https://example.com/about </loc></url>
+</urlset>"#;
+
+    let allocator = Allocator::default();
+    let result = parse_xml(&allocator, SITEMAP);
+    assert!(result.errors.is_empty(), "{:?}", result.errors);
+    let sitemap = Sitemap::from_program(&result.program).unwrap();
+
+    assert!(!sitemap.index);
+    let [first, second] = &sitemap.urls[..] else {
+      unreachable!()
+    };
+    assert_eq!(
+      first.loc.as_ref().unwrap().value,
+      "https://example.com/?a=1&b=2"
+    );
+    assert_eq!(first.lastmod.as_ref().unwrap().value, "2024-01-01");
+    assert_eq!(first.changefreq.as_ref().unwrap().value, "daily");
+    assert_eq!(first.priority.as_ref().unwrap().value, "0.8");
+    let loc = second.loc.as_ref().unwrap();
+    assert_eq!(loc.value, "https://example.com/about");
+    assert_eq!(
+      &SITEMAP[loc.span.start as usize..loc.span.end as usize],
+      "<loc> https://example.com/about </loc>"
+    );
+    assert!(second.priority.is_none());
+  }
+
+  #[test]
+  fn sitemap_index() {
+    let allocator = Allocator::default();
+    let result = parse_xml(
+      &allocator,
+      "<sitemapindex><sitemap><loc>https://example.com/a.xml</loc></sitemap></sitemapindex>",
+    );
+    let sitemap = Sitemap::from_program(&result.program).unwrap();
+    assert!(sitemap.index);
+    assert_eq!(
+      sitemap.urls[0].loc.as_ref().unwrap().value,
+      "https://example.com/a.xml"
+    );
+  }
+}
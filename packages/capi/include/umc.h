@@ -0,0 +1,84 @@
+/*
+ * C API of the UMC HTML parser.
+ *
+ * umc_parse_html returns an opaque document owning every node of the AST. Nodes are
+ * borrowed pointers into the document, valid until it is released with umc_free.
+ *
+ * Strings are returned as UmcStr, a pointer and a length in UTF-8 bytes, without a
+ * terminating NUL. A missing string has a NULL pointer. Spans are UTF-8 byte offsets
+ * into the source.
+ */
+
+#ifndef UMC_H
+#define UMC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct UmcDocument UmcDocument;
+typedef struct UmcNode UmcNode;
+
+typedef struct UmcStr {
+  const uint8_t *ptr;
+  size_t len;
+} UmcStr;
+
+typedef struct UmcSpan {
+  uint32_t start;
+  uint32_t end;
+} UmcSpan;
+
+typedef enum UmcNodeKind {
+  UMC_NODE_DOCTYPE,
+  UMC_NODE_ELEMENT,
+  UMC_NODE_TEXT,
+  UMC_NODE_COMMENT,
+  UMC_NODE_SCRIPT,
+  UMC_NODE_RAW_UNKNOWN,
+  UMC_NODE_MUSTACHE,
+  UMC_NODE_TEMPLATE_TAG,
+  UMC_NODE_DIRECTIVE,
+} UmcNodeKind;
+
+/* Parse a document of `length` bytes. Returns NULL if it is not valid UTF-8. */
+UmcDocument *umc_parse_html(const uint8_t *source, size_t length);
+
+/* Release a document and every node of it. Accepts NULL. */
+void umc_free(UmcDocument *document);
+
+size_t umc_document_child_count(const UmcDocument *document);
+/* NULL if out of range */
+const UmcNode *umc_document_child(const UmcDocument *document, size_t index);
+
+size_t umc_error_count(const UmcDocument *document);
+UmcStr umc_error_message(const UmcDocument *document, size_t index);
+/* Span of the first label of the error, empty if it has none */
+UmcSpan umc_error_span(const UmcDocument *document, size_t index);
+
+UmcNodeKind umc_node_kind(const UmcNode *node);
+UmcSpan umc_node_span(const UmcNode *node);
+/* Elements and scripts only */
+UmcStr umc_node_tag_name(const UmcNode *node);
+/* Value of texts and comments, content of scripts, source of raw unknown constructs
+   and directives, expression of Handlebars tags, content of template tags */
+UmcStr umc_node_text(const UmcNode *node);
+
+/* Elements only */
+size_t umc_node_child_count(const UmcNode *node);
+const UmcNode *umc_node_child(const UmcNode *node, size_t index);
+
+/* Elements, scripts and doctypes only */
+size_t umc_node_attribute_count(const UmcNode *node);
+UmcStr umc_node_attribute_name(const UmcNode *node, size_t index);
+/* Without quotes, NULL for an attribute without value */
+UmcStr umc_node_attribute_value(const UmcNode *node, size_t index);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* UMC_H */
@@ -0,0 +1,471 @@
+//! C API of the UMC HTML parser, declared in `include/umc.h`.
+//!
+//! [`umc_parse_html`] returns an opaque document owning the arena of the AST. Nodes are
+//! borrowed pointers into that arena, valid until the document is released with
+//! [`umc_free`], which frees every node at once.
+//!
+//! Strings are returned as [`UmcStr`], a pointer and a length in UTF-8 bytes, without a
+//! terminating NUL. A missing string, like the value of `<input disabled>`, has a null
+//! pointer. Spans are UTF-8 byte offsets into the source.
+
+#![allow(unsafe_code)]
+// exported functions are only called through FFI
+#![allow(clippy::missing_const_for_fn)]
+
+use std::{ptr, slice};
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::OxcDiagnostic;
+use umc_html_ast::{Attribute, Node, Program};
+use umc_html_parser::CreateHtml;
+use umc_parser::Parser;
+use umc_span::Span;
+
+/// A parsed document.
+///
+/// The fields borrow from the ones declared after them, and are dropped first.
+pub struct UmcDocument {
+  program: Program<'static>,
+  errors: Vec<OxcDiagnostic>,
+  _source: Box<str>,
+  _allocator: Box<Allocator>,
+}
+
+/// A node of a document, see [`UmcNodeKind`].
+pub type UmcNode = Node<'static>;
+
+/// A string borrowed from a document.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct UmcStr {
+  pub ptr: *const u8,
+  pub len: usize,
+}
+
+/// A range of the source, in UTF-8 byte offsets.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UmcSpan {
+  pub start: u32,
+  pub end: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UmcNodeKind {
+  Doctype,
+  Element,
+  Text,
+  Comment,
+  Script,
+  RawUnknown,
+  Mustache,
+  TemplateTag,
+  Directive,
+}
+
+impl UmcStr {
+  const NULL: Self = Self {
+    ptr: ptr::null(),
+    len: 0,
+  };
+
+  const fn new(text: &str) -> Self {
+    Self {
+      ptr: text.as_ptr(),
+      len: text.len(),
+    }
+  }
+}
+
+impl From<Span> for UmcSpan {
+  fn from(span: Span) -> Self {
+    Self {
+      start: span.start,
+      end: span.end,
+    }
+  }
+}
+
+/// Parse an HTML document of `length` bytes.
+///
+/// Returns null if the source is not valid UTF-8. The document must be released with
+/// [`umc_free`].
+///
+/// # Safety
+///
+/// `source` must point to `length` readable bytes, or be null when `length` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_parse_html(source: *const u8, length: usize) -> *mut UmcDocument {
+  let bytes = if length == 0 {
+    &[]
+  } else {
+    // SAFETY: guaranteed by the caller
+    unsafe { slice::from_raw_parts(source, length) }
+  };
+  let Ok(source) = std::str::from_utf8(bytes) else {
+    return ptr::null_mut();
+  };
+
+  let allocator = Box::new(Allocator::default());
+  let source: Box<str> = source.into();
+  // SAFETY: the allocator and the source are boxed, so they don't move with the document,
+  // and they outlive the AST, see the field order of `UmcDocument`.
+  let (allocator_ref, source_ref) = unsafe {
+    (
+      &*ptr::from_ref::<Allocator>(&*allocator),
+      &*ptr::from_ref::<str>(&*source),
+    )
+  };
+
+  let result = Parser::html(allocator_ref, source_ref).parse();
+
+  Box::into_raw(Box::new(UmcDocument {
+    program: result.program,
+    errors: result.errors,
+    _source: source,
+    _allocator: allocator,
+  }))
+}
+
+/// Release a document and every node of it.
+///
+/// # Safety
+///
+/// `document` must be returned by [`umc_parse_html`] and not released yet, or be null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_free(document: *mut UmcDocument) {
+  if !document.is_null() {
+    // SAFETY: guaranteed by the caller
+    drop(unsafe { Box::from_raw(document) });
+  }
+}
+
+/// Number of top level nodes of a document.
+///
+/// # Safety
+///
+/// `document` must be a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_document_child_count(document: *const UmcDocument) -> usize {
+  // SAFETY: guaranteed by the caller
+  unsafe { &*document }.program.len()
+}
+
+/// Top level node at `index`, or null if out of range.
+///
+/// # Safety
+///
+/// `document` must be a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_document_child(
+  document: *const UmcDocument,
+  index: usize,
+) -> *const UmcNode {
+  // SAFETY: guaranteed by the caller
+  let program = &unsafe { &*document }.program;
+  program.get(index).map_or(ptr::null(), ptr::from_ref)
+}
+
+/// Number of parse errors of a document.
+///
+/// # Safety
+///
+/// `document` must be a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_error_count(document: *const UmcDocument) -> usize {
+  // SAFETY: guaranteed by the caller
+  unsafe { &*document }.errors.len()
+}
+
+/// Message of the parse error at `index`, or a null string if out of range.
+///
+/// # Safety
+///
+/// `document` must be a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_error_message(document: *const UmcDocument, index: usize) -> UmcStr {
+  // SAFETY: guaranteed by the caller
+  let errors = &unsafe { &*document }.errors;
+  errors
+    .get(index)
+    .map_or(UmcStr::NULL, |error| UmcStr::new(&error.message))
+}
+
+/// Span of the first label of the parse error at `index`, empty if it has none.
+///
+/// # Safety
+///
+/// `document` must be a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_error_span(document: *const UmcDocument, index: usize) -> UmcSpan {
+  // SAFETY: guaranteed by the caller
+  let errors = &unsafe { &*document }.errors;
+  let label = errors
+    .get(index)
+    .and_then(|error| error.labels.as_ref()?.first());
+  label.map_or(UmcSpan { start: 0, end: 0 }, |label| UmcSpan {
+    start: label.offset() as u32,
+    end: (label.offset() + label.len()) as u32,
+  })
+}
+
+/// Kind of a node.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_kind(node: *const UmcNode) -> UmcNodeKind {
+  // SAFETY: guaranteed by the caller
+  match unsafe { &*node } {
+    Node::Doctype(_) => UmcNodeKind::Doctype,
+    Node::Element(_) => UmcNodeKind::Element,
+    Node::Text(_) => UmcNodeKind::Text,
+    Node::Comment(_) => UmcNodeKind::Comment,
+    Node::Script(_) => UmcNodeKind::Script,
+    Node::RawUnknown(_) => UmcNodeKind::RawUnknown,
+    Node::Mustache(_) => UmcNodeKind::Mustache,
+    Node::TemplateTag(_) => UmcNodeKind::TemplateTag,
+    Node::Directive(_) => UmcNodeKind::Directive,
+  }
+}
+
+/// Span of a node in the source.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_span(node: *const UmcNode) -> UmcSpan {
+  // SAFETY: guaranteed by the caller
+  let span = match unsafe { &*node } {
+    Node::Doctype(doctype) => doctype.span,
+    Node::Element(element) => element.span,
+    Node::Text(text) => text.span,
+    Node::Comment(comment) => comment.span,
+    Node::Script(script) => script.span,
+    Node::RawUnknown(raw) => raw.span,
+    Node::Mustache(mustache) => mustache.span,
+    Node::TemplateTag(tag) => tag.span,
+    Node::Directive(directive) => directive.span,
+  };
+  span.into()
+}
+
+/// Tag name of an element or a script, as written in the source, a null string otherwise.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_tag_name(node: *const UmcNode) -> UmcStr {
+  // SAFETY: guaranteed by the caller
+  match unsafe { &*node } {
+    Node::Element(element) => UmcStr::new(element.tag_name),
+    Node::Script(script) => UmcStr::new(script.tag_name),
+    _ => UmcStr::NULL,
+  }
+}
+
+/// Text of a node, a null string for elements and doctypes.
+///
+/// The value of a text or a comment, the content of a script, the source of a raw unknown
+/// construct or a directive, the expression of a Handlebars tag, or the content of a template
+/// tag.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_text(node: *const UmcNode) -> UmcStr {
+  // SAFETY: guaranteed by the caller
+  match unsafe { &*node } {
+    Node::Text(text) => UmcStr::new(text.value),
+    Node::Comment(comment) => UmcStr::new(comment.value),
+    Node::Script(script) => UmcStr::new(script.program.source_text),
+    Node::RawUnknown(raw) => UmcStr::new(raw.raw),
+    Node::Directive(directive) => UmcStr::new(directive.raw),
+    Node::Mustache(mustache) => UmcStr::new(mustache.expression),
+    Node::TemplateTag(tag) => UmcStr::new(tag.value),
+    Node::Doctype(_) | Node::Element(_) => UmcStr::NULL,
+  }
+}
+
+/// Number of children of a node, 0 for nodes other than elements.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_child_count(node: *const UmcNode) -> usize {
+  // SAFETY: guaranteed by the caller
+  match unsafe { &*node } {
+    Node::Element(element) => element.children.len(),
+    _ => 0,
+  }
+}
+
+/// Child of a node at `index`, or null if out of range.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_child(node: *const UmcNode, index: usize) -> *const UmcNode {
+  // SAFETY: guaranteed by the caller
+  match unsafe { &*node } {
+    Node::Element(element) => element
+      .children
+      .get(index)
+      .map_or(ptr::null(), ptr::from_ref),
+    _ => ptr::null(),
+  }
+}
+
+/// Number of attributes of an element, a script or a doctype, 0 for other nodes.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_attribute_count(node: *const UmcNode) -> usize {
+  // SAFETY: guaranteed by the caller
+  unsafe { attributes(node) }.len()
+}
+
+/// Name of the attribute at `index`, or a null string if out of range.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_attribute_name(node: *const UmcNode, index: usize) -> UmcStr {
+  // SAFETY: guaranteed by the caller
+  let attributes = unsafe { attributes(node) };
+  attributes
+    .get(index)
+    .map_or(UmcStr::NULL, |attribute| UmcStr::new(attribute.key.value))
+}
+
+/// Value of the attribute at `index` without quotes, or a null string if out of range or
+/// if the attribute has no value.
+///
+/// # Safety
+///
+/// `node` must be a node of a live document.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn umc_node_attribute_value(node: *const UmcNode, index: usize) -> UmcStr {
+  // SAFETY: guaranteed by the caller
+  let attributes = unsafe { attributes(node) };
+  attributes
+    .get(index)
+    .and_then(|attribute| attribute.value.as_ref())
+    .map_or(UmcStr::NULL, |value| UmcStr::new(value.value))
+}
+
+/// # Safety
+///
+/// `node` must be a node of a live document.
+unsafe fn attributes<'n>(node: *const UmcNode) -> &'n [Attribute<'static>] {
+  // SAFETY: guaranteed by the caller
+  match unsafe { &*node } {
+    Node::Doctype(doctype) => &doctype.attributes,
+    Node::Element(element) => &element.attributes,
+    Node::Script(script) => &script.attributes,
+    _ => &[],
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::{ptr, slice};
+
+  use crate::{
+    UmcNodeKind, UmcSpan, UmcStr, umc_document_child, umc_document_child_count, umc_error_count,
+    umc_error_message, umc_error_span, umc_free, umc_node_attribute_count, umc_node_attribute_name,
+    umc_node_attribute_value, umc_node_child, umc_node_child_count, umc_node_kind, umc_node_span,
+    umc_node_tag_name, umc_node_text, umc_parse_html,
+  };
+
+  fn string(text: UmcStr) -> Option<String> {
+    (!text.ptr.is_null()).then(|| {
+      // SAFETY: strings returned by the API are valid UTF-8 of `len` bytes
+      let bytes = unsafe { slice::from_raw_parts(text.ptr, text.len) };
+      String::from_utf8(bytes.to_vec()).unwrap()
+    })
+  }
+
+  #[test]
+  fn walk_document() {
+    let source = "<!DOCTYPE html><p class=a hidden>Hi<!--c--></p><script>1</script><b>";
+    // SAFETY: the source is valid for its length, and the document is used before freed
+    unsafe {
+      let document = umc_parse_html(source.as_ptr(), source.len());
+      assert!(!document.is_null());
+      assert_eq!(umc_document_child_count(document), 4);
+      assert!(umc_document_child(document, 4).is_null());
+
+      let doctype = umc_document_child(document, 0);
+      assert_eq!(umc_node_kind(doctype), UmcNodeKind::Doctype);
+      assert_eq!(string(umc_node_attribute_name(doctype, 0)).unwrap(), "html");
+
+      let p = umc_document_child(document, 1);
+      assert_eq!(umc_node_kind(p), UmcNodeKind::Element);
+      assert_eq!(string(umc_node_tag_name(p)).unwrap(), "p");
+      assert_eq!(umc_node_span(p), UmcSpan { start: 15, end: 47 });
+      assert_eq!(umc_node_attribute_count(p), 2);
+      assert_eq!(string(umc_node_attribute_value(p, 0)).unwrap(), "a");
+      assert_eq!(string(umc_node_attribute_value(p, 1)), None);
+      assert_eq!(string(umc_node_attribute_name(p, 2)), None);
+
+      assert_eq!(umc_node_child_count(p), 2);
+      assert_eq!(string(umc_node_text(umc_node_child(p, 0))).unwrap(), "Hi");
+      let comment = umc_node_child(p, 1);
+      assert_eq!(umc_node_kind(comment), UmcNodeKind::Comment);
+      assert_eq!(string(umc_node_text(comment)).unwrap(), "c");
+      assert!(umc_node_child(p, 2).is_null());
+
+      let script = umc_document_child(document, 2);
+      assert_eq!(umc_node_kind(script), UmcNodeKind::Script);
+      assert_eq!(string(umc_node_text(script)).unwrap(), "1");
+
+      assert_eq!(umc_error_count(document), 1);
+      assert!(string(umc_error_message(document, 0)).is_some());
+      assert_eq!(umc_error_span(document, 0).start, 65);
+      assert_eq!(string(umc_error_message(document, 1)), None);
+
+      umc_free(document);
+    }
+  }
+
+  #[test]
+  fn invalid_input() {
+    // SAFETY: the sources are valid for their length
+    unsafe {
+      assert!(umc_parse_html(b"\xFF".as_ptr(), 1).is_null());
+
+      let document = umc_parse_html(ptr::null(), 0);
+      assert_eq!(umc_document_child_count(document), 0);
+      umc_free(document);
+      umc_free(ptr::null_mut());
+    }
+  }
+
+  #[test]
+  fn header_declares_every_function() {
+    let header = include_str!("../include/umc.h");
+    let source = include_str!("lib.rs");
+
+    for line in source.lines() {
+      let Some(rest) = line.strip_prefix("pub unsafe extern \"C\" fn ") else {
+        continue;
+      };
+      let name = &rest[..rest.find('(').unwrap()];
+      assert!(
+        header.contains(&format!("{name}(")),
+        "`{name}` is not declared in include/umc.h"
+      );
+    }
+  }
+}
@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand, ValueEnum};
+
+/// Parse, lint, format and minify HTML files.
+#[derive(Debug, Parser)]
+#[command(name = "umc", version)]
+pub struct Cli {
+  #[command(subcommand)]
+  pub command: Command,
+  /// Parser options, as a JSON or TOML file with camelCase keys
+  #[arg(long, global = true, value_name = "FILE")]
+  pub config: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+  /// Parse files and report parse errors
+  Parse {
+    /// Print the AST and the diagnostics of every file as JSON
+    #[arg(long)]
+    json: bool,
+    /// Files, directories or glob patterns
+    #[arg(required = true)]
+    inputs: Vec<String>,
+  },
+  /// Report parse errors and lint diagnostics
+  Lint {
+    /// Output format of the diagnostics
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+    /// Severity of a rule, e.g. `img-alt=error`
+    #[arg(long = "rule", value_name = "NAME=LEVEL")]
+    rules: Vec<String>,
+    /// Files, directories or glob patterns
+    #[arg(required = true)]
+    inputs: Vec<String>,
+  },
+  /// Reindent files in place
+  Fmt {
+    /// Only report the files which are not formatted
+    #[arg(long)]
+    check: bool,
+    /// Files, directories or glob patterns
+    #[arg(required = true)]
+    inputs: Vec<String>,
+  },
+  /// Remove comments and collapse whitespace
+  Minify {
+    /// Write each minified file to this directory under its file name, instead of stdout
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+    /// Files, directories or glob patterns
+    #[arg(required = true)]
+    inputs: Vec<String>,
+  },
+}
+
+/// Output format of diagnostics.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  /// Rendered with the source code, on stderr
+  #[default]
+  Pretty,
+  /// A JSON array on stdout, see `umc_diagnostics::to_json`
+  Json,
+  /// A SARIF log on stdout, see `umc_diagnostics::to_sarif`
+  Sarif,
+}
@@ -0,0 +1,57 @@
+use std::{
+  collections::HashSet,
+  path::{Path, PathBuf},
+};
+
+/// Extensions of the files found in directories.
+const EXTENSIONS: [&str; 2] = ["html", "htm"];
+
+/// Expand the inputs to the files to process, in order and without duplicates.
+///
+/// An input is a glob pattern if it contains `*`, `?` or `[`, and a directory is searched
+/// recursively for `.html` and `.htm` files. Other inputs are kept as is, and fail when read
+/// if they do not exist.
+pub fn expand(inputs: &[String]) -> Result<Vec<PathBuf>, String> {
+  let mut seen = HashSet::new();
+  let mut files = Vec::new();
+
+  for input in inputs {
+    let path = Path::new(input);
+    let matches = if input.contains(['*', '?', '[']) {
+      let matches = glob(input)?;
+      if matches.is_empty() {
+        return Err(format!("No file matches `{input}`"));
+      }
+      matches
+    } else if path.is_dir() {
+      let mut matches = Vec::new();
+      for extension in EXTENSIONS {
+        let pattern = path.join("**").join(format!("*.{extension}"));
+        matches.extend(glob(&pattern.to_string_lossy())?);
+      }
+      matches.sort();
+      matches
+    } else {
+      vec![path.to_path_buf()]
+    };
+
+    for file in matches {
+      if seen.insert(file.clone()) {
+        files.push(file);
+      }
+    }
+  }
+
+  Ok(files)
+}
+
+fn glob(pattern: &str) -> Result<Vec<PathBuf>, String> {
+  let paths =
+    glob::glob(pattern).map_err(|error| format!("Invalid pattern `{pattern}`: {error}"))?;
+  Ok(
+    paths
+      .filter_map(Result::ok)
+      .filter(|path| path.is_file())
+      .collect(),
+  )
+}
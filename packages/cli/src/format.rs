@@ -0,0 +1,68 @@
+use umc_html_ast::{Node, Program};
+
+use crate::{Edit, apply_edits};
+
+/// Elements whose content is kept as written.
+const VERBATIM: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+/// Reindent a document.
+///
+/// Only whitespace between nodes spanning several lines is changed: it becomes its line
+/// breaks, at most two to keep a blank line, followed by two spaces for each level of
+/// nesting. Other text, tags and attributes are kept as written, so the rendering of the
+/// document does not change.
+pub fn format(source_text: &str, program: &Program) -> String {
+  let mut edits = Vec::new();
+  reindent(program, 0, &mut edits);
+  apply_edits(source_text, edits)
+}
+
+fn reindent(nodes: &[Node], depth: usize, edits: &mut Vec<Edit>) {
+  for (index, node) in nodes.iter().enumerate() {
+    match node {
+      Node::Text(text) => {
+        let line_breaks = text.value.bytes().filter(|&byte| byte == b'\n').count();
+        if line_breaks == 0 || !text.value.bytes().all(|byte| byte.is_ascii_whitespace()) {
+          continue;
+        }
+        // the last child is followed by the closing tag of its parent
+        let indent = if index + 1 == nodes.len() {
+          depth.saturating_sub(1)
+        } else {
+          depth
+        };
+        let replacement = "\n".repeat(line_breaks.min(2)) + &"  ".repeat(indent);
+        if replacement != text.value {
+          edits.push((text.span, replacement));
+        }
+      }
+      Node::Element(element) if !VERBATIM.contains(&element.name()) => {
+        reindent(&element.children, depth + 1, edits);
+      }
+      _ => {}
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use crate::format::format;
+
+  #[test]
+  fn reindent() {
+    const HTML: &str =
+      "<ul>\n<li>One</li>\n\n\n\n      <li>Two <b>x</b></li>\n    </ul>\n<pre>\n x\n</pre>\n";
+
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, HTML);
+    let program = parser.parse().program;
+    assert_eq!(
+      format(HTML, &program),
+      "<ul>\n  <li>One</li>\n\n  <li>Two <b>x</b></li>\n</ul>\n<pre>\n x\n</pre>\n"
+    );
+  }
+}
@@ -0,0 +1,544 @@
+//! Command line interface of UMC, the `umc` binary.
+//!
+//! ```sh
+//! umc parse index.html --json        # print the AST and parse errors as JSON
+//! umc lint 'src/**/*.html'           # report parse errors and lint diagnostics
+//! umc lint src --format sarif        # as a SARIF log, for code scanning
+//! umc fmt src                        # reindent files in place
+//! umc fmt src --check                # list the files which are not formatted
+//! umc minify index.html --out-dir dist
+//! ```
+//!
+//! Inputs are files, directories searched for `.html` and `.htm` files, or glob patterns.
+//! Files are processed in parallel, and reported in the order of the inputs. Parser options
+//! are read from the JSON or TOML file given with `--config`, in the format of
+//! `HtmlParserOption`'s `Deserialize` implementation.
+//!
+//! # Exit codes
+//!
+//! - `0`: no error
+//! - `1`: an error was reported, e.g. a parse error, a lint error, or a file which is not
+//!   formatted with `fmt --check`. Warnings do not fail. `fmt` and `minify` leave files with
+//!   parse errors untouched
+//! - `2`: the command could not run, e.g. an unreadable file or an invalid configuration
+
+mod cli;
+mod files;
+mod format;
+mod minify;
+
+use std::{
+  fmt::Write,
+  fs,
+  io::IsTerminal,
+  path::{Path, PathBuf},
+};
+
+use oxc_allocator::Allocator;
+use oxc_diagnostics::{
+  GraphicalReportHandler, GraphicalTheme, NamedSource, OxcDiagnostic, Severity,
+};
+use rayon::prelude::*;
+use serde_json::{Value, json};
+use umc_diagnostics::{FileDiagnostics, Tool, to_json, to_sarif};
+use umc_html_ast::Program;
+use umc_html_lint::{Linter, RuleSeverity};
+use umc_html_parser::{CreateHtml, option::HtmlParserOption};
+use umc_parser::Parser;
+use umc_span::Span;
+
+pub use crate::cli::{Cli, Command, Format};
+
+/// Exit code when no error was reported.
+pub const EXIT_SUCCESS: u8 = 0;
+/// Exit code when an error was reported.
+pub const EXIT_FAILURE: u8 = 1;
+/// Exit code when the command could not run.
+pub const EXIT_ERROR: u8 = 2;
+
+/// What a command printed, and its exit code.
+#[derive(Debug, Default)]
+pub struct Output {
+  pub stdout: String,
+  pub stderr: String,
+  pub code: u8,
+}
+
+/// Run a command.
+pub fn run(cli: &Cli) -> Output {
+  let mut output = Output::default();
+  output.code = match execute(cli, &mut output) {
+    Ok(code) => code,
+    Err(message) => {
+      writeln!(output.stderr, "error: {message}").unwrap();
+      EXIT_ERROR
+    }
+  };
+  output
+}
+
+fn execute(cli: &Cli, output: &mut Output) -> Result<u8, String> {
+  let options = load_options(cli.config.as_deref())?;
+
+  match &cli.command {
+    Command::Parse { json, inputs } => parse(inputs, *json, &options, output),
+    Command::Lint {
+      format,
+      rules,
+      inputs,
+    } => lint(inputs, *format, rules, &options, output),
+    Command::Fmt { check, inputs } => fmt(inputs, *check, &options, output),
+    Command::Minify { out_dir, inputs } => minify(inputs, out_dir.as_deref(), &options, output),
+  }
+}
+
+fn parse(
+  inputs: &[String],
+  json: bool,
+  options: &HtmlParserOption,
+  output: &mut Output,
+) -> Result<u8, String> {
+  let files = read_files(inputs)?;
+  let results = process(&files, options, |_, program| {
+    json.then(|| serde_json::to_value(program).unwrap())
+  });
+  if !json {
+    let diagnostics: Vec<_> = results.into_iter().map(|(d, _)| d).collect();
+    return Ok(report(&files, &diagnostics, Format::Pretty, output));
+  }
+
+  let output_files: Vec<Value> = files
+    .iter()
+    .zip(&results)
+    .map(|(file, (diagnostics, ast))| {
+      json!({
+        "path": file.path,
+        "ast": ast,
+        "diagnostics": to_json(&[file.diagnostics(diagnostics)]),
+      })
+    })
+    .collect();
+  writeln!(output.stdout, "{}", Value::Array(output_files)).unwrap();
+  Ok(exit_code(
+    results
+      .iter()
+      .any(|(diagnostics, _)| has_errors(diagnostics)),
+  ))
+}
+
+fn lint(
+  inputs: &[String],
+  format: Format,
+  rules: &[String],
+  options: &HtmlParserOption,
+  output: &mut Output,
+) -> Result<u8, String> {
+  let linter = linter(rules)?;
+  let files = read_files(inputs)?;
+  let results = process(&files, options, |_, program| linter.lint(program));
+  let diagnostics: Vec<_> = results
+    .into_iter()
+    .map(|(mut diagnostics, lint)| {
+      diagnostics.extend(lint);
+      diagnostics
+    })
+    .collect();
+  Ok(report(&files, &diagnostics, format, output))
+}
+
+fn fmt(
+  inputs: &[String],
+  check: bool,
+  options: &HtmlParserOption,
+  output: &mut Output,
+) -> Result<u8, String> {
+  let files = read_files(inputs)?;
+  let results = process(&files, options, |file, program| {
+    format::format(&file.source_text, program)
+  });
+  let (diagnostics, formatted) = skip_errors(results);
+  let mut code = report(&files, &diagnostics, Format::Pretty, output);
+
+  let mut changed = 0;
+  for (file, formatted) in files.iter().zip(formatted) {
+    let Some(formatted) = formatted.filter(|formatted| *formatted != file.source_text) else {
+      continue;
+    };
+    changed += 1;
+    if check {
+      writeln!(output.stderr, "{} is not formatted", file.path).unwrap();
+    } else {
+      write_file(&file.path_buf, &formatted)?;
+    }
+  }
+  if check {
+    if changed > 0 {
+      code = EXIT_FAILURE;
+    }
+  } else {
+    writeln!(
+      output.stderr,
+      "Formatted {changed} of {} files",
+      files.len()
+    )
+    .unwrap();
+  }
+  Ok(code)
+}
+
+fn minify(
+  inputs: &[String],
+  out_dir: Option<&Path>,
+  options: &HtmlParserOption,
+  output: &mut Output,
+) -> Result<u8, String> {
+  let files = read_files(inputs)?;
+  let results = process(&files, options, |file, program| {
+    minify::minify(&file.source_text, program)
+  });
+  let (diagnostics, minified) = skip_errors(results);
+  let code = report(&files, &diagnostics, Format::Pretty, output);
+
+  if let Some(out_dir) = out_dir {
+    fs::create_dir_all(out_dir)
+      .map_err(|error| format!("Cannot create {}: {error}", out_dir.display()))?;
+  }
+  for (file, minified) in files.iter().zip(minified) {
+    let Some(minified) = minified else {
+      continue;
+    };
+    match out_dir {
+      Some(out_dir) => {
+        let name = file.path_buf.file_name().unwrap_or_default();
+        write_file(&out_dir.join(name), &minified)?;
+      }
+      None => output.stdout.push_str(&minified),
+    }
+  }
+  Ok(code)
+}
+
+/// A file to process.
+struct File {
+  path_buf: PathBuf,
+  /// The path, as shown to the user
+  path: String,
+  source_text: String,
+}
+
+impl File {
+  fn diagnostics<'a>(&'a self, diagnostics: &'a [OxcDiagnostic]) -> FileDiagnostics<'a> {
+    FileDiagnostics::new(&self.path, &self.source_text, diagnostics)
+  }
+}
+
+/// A replacement of a span of the source text.
+type Edit = (Span, String);
+
+/// Apply edits which do not overlap, in any order.
+fn apply_edits(source_text: &str, mut edits: Vec<Edit>) -> String {
+  edits.sort_by_key(|(span, _)| span.start);
+
+  let mut output = String::with_capacity(source_text.len());
+  let mut end = 0;
+  for (span, replacement) in edits {
+    output.push_str(&source_text[end..span.start as usize]);
+    output.push_str(&replacement);
+    end = span.end as usize;
+  }
+  output.push_str(&source_text[end..]);
+  output
+}
+
+fn load_options(path: Option<&Path>) -> Result<HtmlParserOption, String> {
+  let Some(path) = path else {
+    return Ok(HtmlParserOption::default());
+  };
+  let text =
+    fs::read_to_string(path).map_err(|error| format!("Cannot read {}: {error}", path.display()))?;
+
+  let options = if path
+    .extension()
+    .is_some_and(|extension| extension == "toml")
+  {
+    toml::from_str(&text).map_err(|error| error.to_string())
+  } else {
+    serde_json::from_str(&text).map_err(|error| error.to_string())
+  };
+  options.map_err(|error| format!("Invalid configuration {}: {error}", path.display()))
+}
+
+fn linter(rules: &[String]) -> Result<Linter, String> {
+  let mut linter = Linter::default();
+  for rule in rules {
+    let (name, level) = rule
+      .split_once('=')
+      .ok_or_else(|| format!("Expected a rule as NAME=LEVEL, got `{rule}`"))?;
+    if linter.registry().get(name).is_none() {
+      return Err(format!("Unknown rule `{name}`"));
+    }
+    let severity = match level {
+      "off" => RuleSeverity::Off,
+      "warn" => RuleSeverity::Warn,
+      "error" => RuleSeverity::Error,
+      _ => {
+        return Err(format!(
+          "Unknown level `{level}`, expected `off`, `warn` or `error`"
+        ));
+      }
+    };
+    linter = linter.with_severity(name, severity);
+  }
+  Ok(linter)
+}
+
+fn read_files(inputs: &[String]) -> Result<Vec<File>, String> {
+  files::expand(inputs)?
+    .into_iter()
+    .map(|path_buf| {
+      let path = path_buf.to_string_lossy().into_owned();
+      let source_text =
+        fs::read_to_string(&path_buf).map_err(|error| format!("Cannot read {path}: {error}"))?;
+      Ok(File {
+        path_buf,
+        path,
+        source_text,
+      })
+    })
+    .collect()
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+  fs::write(path, content).map_err(|error| format!("Cannot write {}: {error}", path.display()))
+}
+
+/// Parse the files in parallel, and call `f` with each program.
+///
+/// Returns the parse errors and the result of `f` of every file, in order.
+fn process<T: Send>(
+  files: &[File],
+  options: &HtmlParserOption,
+  f: impl Fn(&File, &Program) -> T + Sync,
+) -> Vec<(Vec<OxcDiagnostic>, T)> {
+  files
+    .par_iter()
+    .map(|file| {
+      let allocator = Allocator::default();
+      let parser = Parser::html(&allocator, &file.source_text).with_shared_options(options);
+      let result = parser.parse();
+      let output = f(file, &result.program);
+      (result.errors, output)
+    })
+    .collect()
+}
+
+/// Drop the output of the files with parse errors.
+fn skip_errors<T>(
+  results: Vec<(Vec<OxcDiagnostic>, T)>,
+) -> (Vec<Vec<OxcDiagnostic>>, Vec<Option<T>>) {
+  results
+    .into_iter()
+    .map(|(diagnostics, output)| {
+      let output = (!has_errors(&diagnostics)).then_some(output);
+      (diagnostics, output)
+    })
+    .unzip()
+}
+
+fn has_errors(diagnostics: &[OxcDiagnostic]) -> bool {
+  diagnostics
+    .iter()
+    .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+const fn exit_code(has_errors: bool) -> u8 {
+  if has_errors {
+    EXIT_FAILURE
+  } else {
+    EXIT_SUCCESS
+  }
+}
+
+/// Report the diagnostics of the files, returning the exit code.
+fn report(
+  files: &[File],
+  diagnostics: &[Vec<OxcDiagnostic>],
+  format: Format,
+  output: &mut Output,
+) -> u8 {
+  let file_diagnostics: Vec<_> = files
+    .iter()
+    .zip(diagnostics)
+    .map(|(file, diagnostics)| file.diagnostics(diagnostics))
+    .collect();
+
+  match format {
+    Format::Pretty => {
+      let theme = if std::io::stderr().is_terminal() {
+        GraphicalTheme::unicode()
+      } else {
+        GraphicalTheme::unicode_nocolor()
+      };
+      let handler = GraphicalReportHandler::new_themed(theme);
+      let (mut errors, mut warnings) = (0, 0);
+      for (file, diagnostics) in files.iter().zip(diagnostics) {
+        for diagnostic in diagnostics {
+          if diagnostic.severity == Severity::Error {
+            errors += 1;
+          } else {
+            warnings += 1;
+          }
+          let report = diagnostic
+            .clone()
+            .with_source_code(NamedSource::new(&file.path, file.source_text.clone()));
+          handler
+            .render_report(&mut output.stderr, report.as_ref())
+            .unwrap();
+        }
+      }
+      if errors + warnings > 0 {
+        writeln!(
+          output.stderr,
+          "Found {errors} errors and {warnings} warnings in {} files",
+          files.len()
+        )
+        .unwrap();
+      }
+    }
+    Format::Json => {
+      writeln!(output.stdout, "{:#}", to_json(&file_diagnostics)).unwrap();
+    }
+    Format::Sarif => {
+      let tool = Tool::new("umc", env!("CARGO_PKG_VERSION"));
+      writeln!(output.stdout, "{:#}", to_sarif(&tool, &file_diagnostics)).unwrap();
+    }
+  }
+
+  exit_code(
+    diagnostics
+      .iter()
+      .any(|diagnostics| has_errors(diagnostics)),
+  )
+}
+
+#[cfg(test)]
+mod test {
+  use std::{
+    fs,
+    path::{Path, PathBuf},
+  };
+
+  use clap::Parser;
+  use insta::assert_snapshot;
+
+  use crate::{Cli, EXIT_ERROR, EXIT_FAILURE, EXIT_SUCCESS, Output, run};
+
+  /// A directory with `a.html`, which has errors, and `b.html`.
+  fn fixture(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("umc_cli_{name}_{}", std::process::id()));
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(
+      dir.join("src/a.html"),
+      "<ul>\n<li>One\n<img src=a.png>\n</ul></span>\n",
+    )
+    .unwrap();
+    fs::write(
+      dir.join("src/b.html"),
+      "<div>\n<p>\n  Hi  <!-- c -->\n</p>\n</div>\n",
+    )
+    .unwrap();
+    dir
+  }
+
+  fn umc(dir: &Path, args: &[&str]) -> Output {
+    let args = args.iter().map(|arg| {
+      arg
+        .replace("src", &dir.join("src").to_string_lossy())
+        .replace("umc.", &dir.join("umc.").to_string_lossy())
+    });
+    let mut output = run(&Cli::parse_from(
+      std::iter::once("umc".to_string()).chain(args),
+    ));
+    let prefix = format!("{}/", dir.to_string_lossy());
+    output.stdout = output.stdout.replace(&prefix, "");
+    output.stderr = output.stderr.replace(&prefix, "");
+    output
+  }
+
+  #[test]
+  fn lint() {
+    let dir = fixture("lint");
+
+    let output = umc(&dir, &["lint", "src"]);
+    assert_eq!(output.code, EXIT_FAILURE);
+    assert_snapshot!(output.stderr);
+
+    fs::write(
+      dir.join("umc.toml"),
+      "[errors]\nimplicitly-closed-element = \"off\"\norphan-end-tag = \"warn\"\n",
+    )
+    .unwrap();
+    let output = umc(
+      &dir,
+      &[
+        "lint",
+        "src/*.html",
+        "--config",
+        "umc.toml",
+        "--rule",
+        "img-alt=off",
+      ],
+    );
+    assert_eq!(output.code, EXIT_SUCCESS);
+    assert!(
+      output
+        .stderr
+        .ends_with("Found 0 errors and 1 warnings in 2 files\n")
+    );
+
+    let output = umc(&dir, &["lint", "src/b.html", "--format", "json"]);
+    assert_eq!(
+      (output.code, output.stdout.as_str()),
+      (EXIT_SUCCESS, "[]\n")
+    );
+
+    let output = umc(&dir, &["lint", "src/*.xml"]);
+    assert_eq!(output.code, EXIT_ERROR);
+    let output = umc(&dir, &["lint", "src", "--rule", "img-alt=loud"]);
+    assert_eq!(output.code, EXIT_ERROR);
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+
+  #[test]
+  fn fmt_and_minify() {
+    let dir = fixture("fmt");
+
+    let output = umc(&dir, &["fmt", "--check", "src/b.html"]);
+    assert_eq!(output.code, EXIT_FAILURE);
+    assert_eq!(output.stderr, "src/b.html is not formatted\n");
+
+    let output = umc(&dir, &["fmt", "src"]);
+    assert_eq!(output.code, EXIT_FAILURE);
+    assert!(output.stderr.ends_with("Formatted 1 of 2 files\n"));
+    assert_eq!(
+      fs::read_to_string(dir.join("src/b.html")).unwrap(),
+      "<div>\n  <p>\n  Hi  <!-- c -->\n  </p>\n</div>\n"
+    );
+
+    let output = umc(&dir, &["minify", "src/b.html"]);
+    assert_eq!(output.code, EXIT_SUCCESS);
+    assert_eq!(output.stdout, "<div> <p> Hi </p> </div>");
+
+    let output = umc(&dir, &["minify", "src/b.html", "--out-dir", "src/dist"]);
+    assert_eq!(output.code, EXIT_SUCCESS);
+    assert!(dir.join("src/dist/b.html").exists());
+
+    let output = umc(&dir, &["parse", "--json", "src/b.html"]);
+    let json: serde_json::Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(json[0]["path"], "src/b.html");
+    assert_eq!(json[0]["ast"][0]["tagName"], "div");
+
+    fs::remove_dir_all(dir).unwrap();
+  }
+}
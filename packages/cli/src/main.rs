@@ -0,0 +1,12 @@
+use std::{io::Write, process::ExitCode};
+
+use clap::Parser;
+use umc_cli::{Cli, run};
+
+fn main() -> ExitCode {
+  let output = run(&Cli::parse());
+  // a closed pipe, e.g. `umc parse --json | head`, is not an error
+  std::io::stdout().write_all(output.stdout.as_bytes()).ok();
+  std::io::stderr().write_all(output.stderr.as_bytes()).ok();
+  ExitCode::from(output.code)
+}
@@ -0,0 +1,94 @@
+use umc_html_ast::{Node, Program};
+
+use crate::{Edit, apply_edits};
+
+/// Elements whose content is kept as written.
+const VERBATIM: [&str; 4] = ["pre", "textarea", "script", "style"];
+
+/// Minify a document.
+///
+/// Comments are removed, except IE conditional comments and bogus comments like
+/// `<?xml ... ?>`, and runs of whitespace in text collapse to a single space, outside of
+/// `<pre>`, `<textarea>`, `<script>` and `<style>`. Whitespace between top level nodes is
+/// removed. Tags and attributes are kept as written.
+pub fn minify(source_text: &str, program: &Program) -> String {
+  let mut edits = Vec::new();
+  collapse(program, true, &mut edits);
+  apply_edits(source_text, edits)
+}
+
+fn collapse(nodes: &[Node], top_level: bool, edits: &mut Vec<Edit>) {
+  // whether the output so far ends with a space, as texts around a removed comment meet
+  let mut after_space = false;
+
+  for node in nodes {
+    match node {
+      Node::Comment(comment) => {
+        let conditional = comment.conditional.is_some() || comment.value.starts_with("[if");
+        if !comment.bogus && !conditional {
+          edits.push((comment.span, String::new()));
+          continue;
+        }
+      }
+      Node::Text(text) => {
+        let mut collapsed = if top_level && text.value.trim_ascii().is_empty() {
+          String::new()
+        } else {
+          collapse_whitespace(text.value)
+        };
+        if after_space && collapsed.starts_with(' ') {
+          collapsed.remove(0);
+        }
+        after_space = after_space && collapsed.is_empty() || collapsed.ends_with(' ');
+        if collapsed != text.value {
+          edits.push((text.span, collapsed));
+        }
+        continue;
+      }
+      Node::Element(element) if !VERBATIM.contains(&element.name()) => {
+        collapse(&element.children, false, edits);
+      }
+      _ => {}
+    }
+    after_space = false;
+  }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+  let mut output = String::with_capacity(text.len());
+  let mut in_whitespace = false;
+  for char in text.chars() {
+    if char.is_ascii_whitespace() {
+      if !in_whitespace {
+        output.push(' ');
+      }
+      in_whitespace = true;
+    } else {
+      output.push(char);
+      in_whitespace = false;
+    }
+  }
+  output
+}
+
+#[cfg(test)]
+mod test {
+  use oxc_allocator::Allocator;
+  use umc_html_parser::CreateHtml;
+  use umc_parser::Parser;
+
+  use crate::minify::minify;
+
+  #[test]
+  fn minify_document() {
+    const HTML: &str = "<!DOCTYPE html>\n<!-- a -->\n<p class=\"a  b\">\n  Hello   <b>world</b> <!-- b -->\n</p>\n<pre>  x  </pre>\n<!--[if IE]><p>IE</p><![endif]-->\n";
+
+    let allocator = Allocator::default();
+    let parser = Parser::html(&allocator, HTML);
+    let program = parser.parse().program;
+    assert_eq!(
+      minify(HTML, &program),
+      "<!DOCTYPE html><p class=\"a  b\"> Hello <b>world</b> </p><pre>  x  </pre><!--[if IE]><p>IE</p><![endif]-->"
+    );
+  }
+}
@@ -1,3 +1,46 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
-export declare function plus100(input: number): number
+export interface Diagnostic {
+  message: string
+  /** `"error"`, `"warning"` or `"advice"` */
+  severity: string
+  help?: string
+  labels: Array<Label>
+}
+
+/** A labeled range of the source, in UTF-8 byte offsets. */
+export interface Label {
+  start: number
+  end: number
+  message?: string
+}
+
+/** Parse an HTML document on the libuv worker pool. */
+export declare function parse(sourceText: string, options?: ParseOptions | undefined | null): Promise<ParseResult>
+
+/** Parser options, all optional. */
+export interface ParseOptions {
+  /** Parse the content of `<script>` tags, default `true` */
+  parseScript?: boolean
+  /** Keep constructs the parser cannot understand as `RawUnknown` nodes, default `false` */
+  preserveUnknown?: boolean
+  /** Tags whose content is not parsed as HTML, default `["script", "style"]` */
+  embeddedLanguageTags?: Array<string>
+  /** Tags which never have content, default the void elements of HTML */
+  voidTags?: Array<string>
+}
+
+/** The result of parsing a document. */
+export interface ParseResult {
+  /**
+   * The AST as JSON, to be read with `JSON.parse` when needed.
+   *
+   * Passing a single string is much cheaper than building the JS objects of every node
+   * on the Rust side, and consumers which only need the errors never pay for it.
+   */
+  program: string
+  errors: Array<Diagnostic>
+}
+
+/** Parse an HTML document on the calling thread. */
+export declare function parseSync(sourceText: string, options?: ParseOptions | undefined | null): ParseResult
@@ -572,4 +572,5 @@ if (!nativeBinding) {
 }
 
 module.exports = nativeBinding
-module.exports.plus100 = nativeBinding.plus100
+module.exports.parse = nativeBinding.parse
+module.exports.parseSync = nativeBinding.parseSync
@@ -56,4 +56,5 @@ const {
   },
 })
 export default __napiModule.exports
-export const plus100 = __napiModule.exports.plus100
+export const parse = __napiModule.exports.parse
+export const parseSync = __napiModule.exports.parseSync
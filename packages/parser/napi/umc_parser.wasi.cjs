@@ -108,4 +108,5 @@ const { instance: __napiInstance, module: __wasiModule, napiModule: __napiModule
   },
 })
 module.exports = __napiModule.exports
-module.exports.plus100 = __napiModule.exports.plus100
+module.exports.parse = __napiModule.exports.parse
+module.exports.parseSync = __napiModule.exports.parseSync
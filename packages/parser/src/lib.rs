@@ -1,8 +1,138 @@
 #![deny(clippy::all)]
 
+use napi::{Env, Task, bindgen_prelude::AsyncTask};
 use napi_derive::napi;
+use oxc_allocator::Allocator;
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use umc_html_parser::{
+  CreateHtml,
+  option::{HtmlParserOption, TagSet},
+};
+use umc_parser::Parser;
 
+/// Parser options, all optional.
+#[napi(object)]
+#[derive(Default)]
+pub struct ParseOptions {
+  /// Parse the content of `<script>` tags, default `true`
+  pub parse_script: Option<bool>,
+  /// Keep constructs the parser cannot understand as `RawUnknown` nodes, default `false`
+  pub preserve_unknown: Option<bool>,
+  /// Tags whose content is not parsed as HTML, default `["script", "style"]`
+  pub embedded_language_tags: Option<Vec<String>>,
+  /// Tags which never have content, default the void elements of HTML
+  pub void_tags: Option<Vec<String>>,
+}
+
+/// The result of parsing a document.
+#[napi(object)]
+pub struct ParseResult {
+  /// The AST as JSON, to be read with `JSON.parse` when needed.
+  ///
+  /// Passing a single string is much cheaper than building the JS objects of every node
+  /// on the Rust side, and consumers which only need the errors never pay for it.
+  pub program: String,
+  pub errors: Vec<Diagnostic>,
+}
+
+#[napi(object)]
+pub struct Diagnostic {
+  pub message: String,
+  /// `"error"`, `"warning"` or `"advice"`
+  pub severity: String,
+  pub help: Option<String>,
+  pub labels: Vec<Label>,
+}
+
+/// A labeled range of the source, in UTF-8 byte offsets.
+#[napi(object)]
+pub struct Label {
+  pub start: u32,
+  pub end: u32,
+  pub message: Option<String>,
+}
+
+/// Parse an HTML document on the calling thread.
+#[napi]
+#[allow(clippy::needless_pass_by_value)]
+pub fn parse_sync(source_text: String, options: Option<ParseOptions>) -> ParseResult {
+  parse_html(&source_text, options.unwrap_or_default())
+}
+
+/// Parse an HTML document on the libuv worker pool.
 #[napi]
-pub fn plus_100(input: u32) -> u32 {
-  input + 100
+pub fn parse(source_text: String, options: Option<ParseOptions>) -> AsyncTask<ParseTask> {
+  AsyncTask::new(ParseTask {
+    source_text,
+    options: options.unwrap_or_default(),
+  })
+}
+
+pub struct ParseTask {
+  source_text: String,
+  options: ParseOptions,
+}
+
+impl Task for ParseTask {
+  type Output = ParseResult;
+  type JsValue = ParseResult;
+
+  fn compute(&mut self) -> napi::Result<Self::Output> {
+    let options = std::mem::take(&mut self.options);
+    Ok(parse_html(&self.source_text, options))
+  }
+
+  fn resolve(&mut self, _env: Env, output: Self::Output) -> napi::Result<Self::JsValue> {
+    Ok(output)
+  }
+}
+
+fn parse_html(source_text: &str, options: ParseOptions) -> ParseResult {
+  let allocator = Allocator::default();
+  let parser = Parser::html(&allocator, source_text).with_options(parser_option(options));
+  let result = parser.parse();
+
+  ParseResult {
+    program: serde_json::to_string(&result.program).unwrap(),
+    errors: result.errors.iter().map(diagnostic).collect(),
+  }
+}
+
+fn parser_option(options: ParseOptions) -> HtmlParserOption {
+  let mut option = HtmlParserOption {
+    preserve_unknown: options.preserve_unknown.unwrap_or(false),
+    ..HtmlParserOption::default()
+  };
+  if options.parse_script == Some(false) {
+    option.parse_script = None;
+  }
+  if let Some(tags) = options.embedded_language_tags {
+    option.dialect_spec.raw_text_tags = TagSet::from_names(tags);
+  }
+  if let Some(tags) = options.void_tags {
+    option.dialect_spec.void_tags = TagSet::from_names(tags);
+  }
+  option
+}
+
+fn diagnostic(diagnostic: &OxcDiagnostic) -> Diagnostic {
+  let labels = diagnostic.labels.as_deref().unwrap_or_default();
+  Diagnostic {
+    message: diagnostic.message.to_string(),
+    severity: match diagnostic.severity {
+      Severity::Error => "error",
+      Severity::Warning => "warning",
+      Severity::Advice => "advice",
+    }
+    .to_string(),
+    help: diagnostic.help.as_ref().map(ToString::to_string),
+    labels: labels
+      .iter()
+      .map(|label| Label {
+        start: label.offset() as u32,
+        end: (label.offset() + label.len()) as u32,
+        message: label.label().map(ToString::to_string),
+      })
+      .collect(),
+  }
 }
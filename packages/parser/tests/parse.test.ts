@@ -0,0 +1,41 @@
+import { describe, expect, it } from 'vitest';
+import { parse, parseSync } from '../napi';
+
+describe('parseSync', () => {
+  it('returns the AST as JSON', () => {
+    const { program, errors } = parseSync('<p class=a>Hello</p>');
+
+    expect(errors).toEqual([]);
+    expect(JSON.parse(program)).toMatchObject([
+      {
+        type: 'Element',
+        tagName: 'p',
+        attributes: [{ key: { value: 'class' }, value: { value: 'a' } }],
+        children: [{ type: 'Text', value: 'Hello' }],
+      },
+    ]);
+  });
+
+  it('reports errors with spans', () => {
+    const { errors } = parseSync('<div>');
+
+    expect(errors).toMatchObject([
+      { severity: 'error', labels: [{ start: 0, end: 5 }] },
+    ]);
+  });
+
+  it('accepts options', () => {
+    const { program } = parseSync('<x-icon><br>', { voidTags: ['x-icon'] });
+
+    expect(JSON.parse(program)).toMatchObject([
+      { tagName: 'x-icon', children: [] },
+      { tagName: 'br' },
+    ]);
+  });
+});
+
+it('parse runs on the worker pool', async () => {
+  const [sync, async] = [parseSync('<p>a</p>'), await parse('<p>a</p>')];
+
+  expect(async).toEqual(sync);
+});
@@ -0,0 +1,43 @@
+use oxc_diagnostics::{OxcDiagnostic, Severity};
+use serde::Serialize;
+
+/// The JSON form of a diagnostic.
+#[derive(Debug, Serialize)]
+pub struct Diagnostic<'a> {
+  message: &'a str,
+  severity: &'static str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  help: Option<&'a str>,
+  labels: Vec<Label<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Label<'a> {
+  start: usize,
+  end: usize,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  message: Option<&'a str>,
+}
+
+impl<'a> From<&'a OxcDiagnostic> for Diagnostic<'a> {
+  fn from(diagnostic: &'a OxcDiagnostic) -> Self {
+    let labels = diagnostic.labels.as_deref().unwrap_or_default();
+    Self {
+      message: &diagnostic.message,
+      severity: match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "advice",
+      },
+      help: diagnostic.help.as_deref(),
+      labels: labels
+        .iter()
+        .map(|label| Label {
+          start: label.offset(),
+          end: label.offset() + label.len(),
+          message: label.label(),
+        })
+        .collect(),
+    }
+  }
+}
@@ -0,0 +1,174 @@
+//! WebAssembly bindings of the UMC HTML parser.
+//!
+//! Build with `wasm-pack build packages/wasm`. The package exports:
+//!
+//! - `parse(html, options?)`, returning `{ ast, errors }`, where `ast` is the JSON form of
+//!   the [`Program`](umc_html_ast::Program) and `errors` the parse diagnostics
+//! - `Tokenizer`, a streaming tokenizer: `feed(chunk)` and `end()` return the events
+//!   completed so far, and `errors()` the diagnostics found until now
+//!
+//! See [`ParseOptions`] for the accepted options. Spans are UTF-8 byte offsets.
+
+mod diagnostic;
+mod options;
+
+use oxc_allocator::Allocator;
+use umc_html_parser::{CreateHtml, stream::HtmlStreamParser};
+use umc_parser::Parser;
+use wasm_bindgen::prelude::*;
+
+use crate::diagnostic::Diagnostic;
+pub use crate::options::ParseOptions;
+
+/// Parse an HTML document.
+#[wasm_bindgen]
+pub fn parse(html: &str, options: &JsValue) -> Result<JsValue, JsError> {
+  let options = ParseOptions::from_json(&stringify(options)?)?;
+  to_js(&parse_to_json(html, options))
+}
+
+/// Parse `html`, returning `{ ast, errors }` as JSON.
+fn parse_to_json(html: &str, options: ParseOptions) -> String {
+  #[derive(serde::Serialize)]
+  struct Output<'a> {
+    ast: &'a umc_html_ast::Program<'a>,
+    errors: Vec<Diagnostic<'a>>,
+  }
+
+  let allocator = Allocator::default();
+  let parser = Parser::html(&allocator, html).with_options(options.into());
+  let result = parser.parse();
+
+  let output = Output {
+    ast: &result.program,
+    errors: result.errors.iter().map(Diagnostic::from).collect(),
+  };
+  serde_json::to_string(&output).unwrap()
+}
+
+/// A streaming tokenizer, fed with chunks of the document.
+#[wasm_bindgen]
+pub struct Tokenizer {
+  parser: HtmlStreamParser,
+}
+
+#[wasm_bindgen]
+impl Tokenizer {
+  #[wasm_bindgen(constructor)]
+  pub fn new(options: &JsValue) -> Result<Self, JsError> {
+    let options = ParseOptions::from_json(&stringify(options)?)?;
+    Ok(Self::with_options(options))
+  }
+
+  /// Append a chunk of text, returning the events it completes.
+  pub fn feed(&mut self, chunk: &str) -> Result<JsValue, JsError> {
+    to_js(&self.feed_to_json(chunk))
+  }
+
+  /// Mark the end of the input, returning the remaining events.
+  pub fn end(&mut self) -> Result<JsValue, JsError> {
+    to_js(&self.end_to_json())
+  }
+
+  /// The errors found so far.
+  pub fn errors(&self) -> Result<JsValue, JsError> {
+    to_js(&self.errors_to_json())
+  }
+}
+
+impl Tokenizer {
+  fn with_options(options: ParseOptions) -> Self {
+    Self {
+      parser: HtmlStreamParser::new(options.into()),
+    }
+  }
+
+  /// Like [`feed`](Self::feed), returning the events as JSON.
+  fn feed_to_json(&mut self, chunk: &str) -> String {
+    self.parser.feed(chunk);
+    self.events_to_json()
+  }
+
+  /// Like [`end`](Self::end), returning the events as JSON.
+  fn end_to_json(&mut self) -> String {
+    self.parser.end();
+    self.events_to_json()
+  }
+
+  /// Like [`errors`](Self::errors), returning the errors as JSON.
+  fn errors_to_json(&self) -> String {
+    let errors: Vec<_> = self.parser.errors.iter().map(Diagnostic::from).collect();
+    serde_json::to_string(&errors).unwrap()
+  }
+
+  fn events_to_json(&mut self) -> String {
+    let mut json = String::from("[");
+    while let Some(event) = self.parser.next_event() {
+      if json.len() > 1 {
+        json.push(',');
+      }
+      json.push_str(&serde_json::to_string(&event).unwrap());
+    }
+    json.push(']');
+    json
+  }
+}
+
+fn stringify(value: &JsValue) -> Result<String, JsError> {
+  if value.is_undefined() || value.is_null() {
+    return Ok(String::new());
+  }
+  js_sys::JSON::stringify(value)
+    .map(String::from)
+    .map_err(|_| JsError::new("Options must be serializable as JSON"))
+}
+
+fn to_js(json: &str) -> Result<JsValue, JsError> {
+  js_sys::JSON::parse(json).map_err(|_| JsError::new("Failed to convert the output"))
+}
+
+#[cfg(test)]
+mod test {
+  use insta::assert_snapshot;
+
+  use crate::{ParseOptions, Tokenizer, parse_to_json};
+
+  #[test]
+  fn parse() {
+    let options = ParseOptions::from_json(r#"{ "parseScript": false }"#).unwrap();
+    let json = parse_to_json(
+      "<!DOCTYPE html><p class=a>Hi<!-- c --><script>1</script></b>",
+      options,
+    );
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_snapshot!(serde_json::to_string_pretty(&value).unwrap());
+  }
+
+  #[test]
+  fn options() {
+    assert!(
+      ParseOptions::from_json("null")
+        .unwrap()
+        .0
+        .parse_script
+        .is_some()
+    );
+    assert!(ParseOptions::from_json(r#"{ "parse_script": false }"#).is_err());
+
+    let options = ParseOptions::from_json(r#"{ "voidTags": ["x-icon"] }"#).unwrap();
+    let json = parse_to_json("<x-icon><br>", options);
+    assert!(json.contains(r#""normalizedName":"x-icon","attributes":[],"children":[]"#));
+  }
+
+  #[test]
+  fn tokenizer() {
+    let mut tokenizer = Tokenizer::with_options(ParseOptions::default());
+    let events = [
+      tokenizer.feed_to_json("<a hr"),
+      tokenizer.feed_to_json("ef=x>te"),
+      tokenizer.end_to_json(),
+      tokenizer.errors_to_json(),
+    ];
+    assert_snapshot!(events.join("\n"));
+  }
+}
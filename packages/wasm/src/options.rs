@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use umc_html_parser::option::HtmlParserOption;
+
+/// Parser options accepted from JavaScript, all optional.
+///
+/// ```ts
+/// interface ParseOptions {
+///   parseScript?: boolean;            // default: true
+///   preserveUnknown?: boolean;        // default: false
+///   embeddedLanguageTags?: string[];  // default: ["script", "style"]
+///   voidTags?: string[];              // default: the void elements of HTML
+///   maxDepth?: number;                // default: no limit
+///   maxAttributes?: number;           // default: no limit
+///   maxTokens?: number;               // default: no limit
+///   budget?: { maxBytes?: number; maxNodes?: number; maxDepth?: number; maxErrors?: number };
+///   errors?: Record<string, "off" | "warn" | "error">; // by error code, default: "error"
+///   strict?: boolean;                 // default: false
+///   whitespace?: "preserve" | "trim" | "skip"; // default: "preserve"
+///   preserveComments?: boolean;       // default: true
+///   parseConditionalComments?: boolean; // default: false
+///   normalizeCase?: boolean; // default: true
+///   parseStyleAttributes?: boolean; // default: false
+/// }
+/// ```
+///
+/// These are the configuration file options of [`HtmlParserOption`].
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct ParseOptions(pub HtmlParserOption);
+
+impl ParseOptions {
+  /// Read options from their JSON form, `null` or an empty input for the defaults.
+  pub fn from_json(json: &str) -> serde_json::Result<Self> {
+    match json.trim() {
+      "" | "null" => Ok(Self::default()),
+      json => serde_json::from_str(json),
+    }
+  }
+}
+
+impl From<ParseOptions> for HtmlParserOption {
+  fn from(options: ParseOptions) -> Self {
+    options.0
+  }
+}
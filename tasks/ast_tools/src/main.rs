@@ -0,0 +1,381 @@
+//! Code generation from the definitions of `umc_html_ast`.
+//!
+//! Run `just codegen` after changing the AST: the visitor traits and walk functions of
+//! `umc_html_traverse` are generated from the node types, so they cannot drift from them.
+//!
+//! Every type of the AST whose fields are all public gets an `enter_*` and an `exit_*`
+//! method, and its walk function walks its fields of AST types, through `Box`, `Option` and
+//! `Vec`, in the order they are declared. Types from other crates, like the programs of
+//! scripts, are not walked.
+
+use std::{
+  collections::HashSet,
+  fmt::Write as _,
+  fs,
+  io::Write as _,
+  path::{Path, PathBuf},
+  process::{Command, Stdio},
+};
+
+use syn::{Fields, GenericArgument, Item, PathArguments, Type, Visibility};
+
+/// The AST definitions, from the root of the repository.
+const AST_PATH: &str = "languages/html/umc_html_ast/src/lib.rs";
+/// The generated traversal, from the root of the repository.
+const TRAVERSE_PATH: &str = "languages/html/umc_html_traverse/src/generated.rs";
+
+fn main() {
+  let root = root();
+  let code = generate(&root);
+  fs::write(root.join(TRAVERSE_PATH), code).expect("failed to write the generated traversal");
+  println!("Generated {TRAVERSE_PATH}");
+}
+
+fn root() -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+/// Generate the traversal, formatted with the configuration of the repository.
+fn generate(root: &Path) -> String {
+  let source = fs::read_to_string(root.join(AST_PATH)).expect("failed to read the AST");
+  let types = ast_types(&source);
+  let mut code = String::from(
+    "// Generated by `just codegen` from the definitions of `umc_html_ast`, do not edit.\n\n",
+  );
+  let mut names: Vec<_> = types.iter().map(|ty| ty.name.as_str()).collect();
+  names.sort_unstable();
+  writeln!(code, "use umc_html_ast::{{{}}};", names.join(", ")).unwrap();
+  code.push_str("use umc_traverse::TraverseOperate;\n");
+  for mutable in [false, true] {
+    Generator {
+      code: &mut code,
+      mutable,
+    }
+    .traverse(&types);
+  }
+  format(root, &code)
+}
+
+/// A type of the AST, with a visitor method.
+struct AstType {
+  name: String,
+  has_lifetime: bool,
+  kind: Kind,
+}
+
+enum Kind {
+  /// A struct, with its fields of AST types
+  Struct(Vec<(String, Shape)>),
+  /// An enum, with its variants and the type each wraps
+  Enum(Vec<(String, String)>),
+  /// A type alias, like `Program`
+  Alias(Shape),
+}
+
+/// How a field holds AST types.
+enum Shape {
+  Node(String),
+  Option(Box<Self>),
+  Vec(Box<Self>),
+}
+
+impl Shape {
+  /// The name of the AST type held.
+  fn name(&self) -> &str {
+    match self {
+      Self::Node(name) => name,
+      Self::Option(shape) | Self::Vec(shape) => shape.name(),
+    }
+  }
+}
+
+fn ast_types(source: &str) -> Vec<AstType> {
+  let file = syn::parse_file(source).expect("failed to parse the AST");
+  let names: HashSet<String> = file
+    .items
+    .iter()
+    .filter_map(|item| match item {
+      Item::Struct(item) if matches!(item.vis, Visibility::Public(_)) => {
+        let public = item
+          .fields
+          .iter()
+          .all(|field| matches!(field.vis, Visibility::Public(_)));
+        (public && matches!(item.fields, Fields::Named(_))).then(|| item.ident.to_string())
+      }
+      // enums of data, like the kind of a node, are not walked
+      Item::Enum(item) if matches!(item.vis, Visibility::Public(_)) => {
+        let wraps_nodes = item.variants.iter().all(
+          |variant| matches!(&variant.fields, Fields::Unnamed(fields) if fields.unnamed.len() == 1),
+        );
+        wraps_nodes.then(|| item.ident.to_string())
+      }
+      Item::Type(item) if matches!(item.vis, Visibility::Public(_)) => Some(item.ident.to_string()),
+      _ => None,
+    })
+    .collect();
+
+  let mut types = Vec::new();
+  for item in &file.items {
+    let (ident, generics, kind) = match item {
+      Item::Struct(item) if names.contains(&item.ident.to_string()) => {
+        let fields = item
+          .fields
+          .iter()
+          .filter_map(|field| {
+            let name = field.ident.as_ref()?.to_string();
+            Some((name, shape(&field.ty, &names)?))
+          })
+          .collect();
+        (&item.ident, &item.generics, Kind::Struct(fields))
+      }
+      Item::Enum(item) if names.contains(&item.ident.to_string()) => {
+        let variants: Option<Vec<_>> = item
+          .variants
+          .iter()
+          .map(|variant| {
+            let Fields::Unnamed(fields) = &variant.fields else {
+              return None;
+            };
+            let [field] = fields.unnamed.iter().collect::<Vec<_>>()[..] else {
+              return None;
+            };
+            let Some(Shape::Node(name)) = shape(&field.ty, &names) else {
+              return None;
+            };
+            Some((variant.ident.to_string(), name))
+          })
+          .collect();
+        let Some(variants) = variants else {
+          continue;
+        };
+        (&item.ident, &item.generics, Kind::Enum(variants))
+      }
+      Item::Type(item) if names.contains(&item.ident.to_string()) => {
+        let Some(shape) = shape(&item.ty, &names) else {
+          continue;
+        };
+        (&item.ident, &item.generics, Kind::Alias(shape))
+      }
+      _ => continue,
+    };
+    types.push(AstType {
+      name: ident.to_string(),
+      has_lifetime: generics.lifetimes().next().is_some(),
+      kind,
+    });
+  }
+  // the root first
+  types.sort_by_key(|ty| !matches!(ty.kind, Kind::Alias(_)));
+  types
+}
+
+/// Get how a type holds AST types, `None` if it holds none.
+fn shape(ty: &Type, names: &HashSet<String>) -> Option<Shape> {
+  let Type::Path(path) = ty else {
+    return None;
+  };
+  // types of other crates are written with their path, like `oxc_ast::ast::Program`
+  let [segment] = path.path.segments.iter().collect::<Vec<_>>()[..] else {
+    return None;
+  };
+  let inner = || {
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+      return None;
+    };
+    arguments.args.iter().find_map(|argument| match argument {
+      GenericArgument::Type(ty) => shape(ty, names),
+      _ => None,
+    })
+  };
+  let name = segment.ident.to_string();
+  match name.as_str() {
+    "Box" => inner(),
+    "Option" => Some(Shape::Option(Box::new(inner()?))),
+    "Vec" => Some(Shape::Vec(Box::new(inner()?))),
+    _ if names.contains(&name) => Some(Shape::Node(name)),
+    _ => None,
+  }
+}
+
+struct Generator<'c> {
+  code: &'c mut String,
+  mutable: bool,
+}
+
+impl Generator<'_> {
+  const fn reference(&self) -> &'static str {
+    if self.mutable { "&mut " } else { "&" }
+  }
+
+  const fn suffix(&self) -> &'static str {
+    if self.mutable { "_mut" } else { "" }
+  }
+
+  const fn trait_name(&self) -> &'static str {
+    if self.mutable {
+      "TraverseHtmlMut"
+    } else {
+      "TraverseHtml"
+    }
+  }
+
+  /// The type of a reference to an AST type, e.g. `&mut Element<'a>`.
+  fn reference_type(&self, ty: &AstType) -> String {
+    let lifetime = if ty.has_lifetime { "<'a>" } else { "" };
+    format!("{}{}{lifetime}", self.reference(), ty.name)
+  }
+
+  fn traverse(&mut self, types: &[AstType]) {
+    let trait_name = self.trait_name();
+    writeln!(
+      self.code,
+      "\n#[expect(unused_variables)]\npub trait {trait_name}<'a> {{"
+    )
+    .unwrap();
+    for ty in types {
+      let name = snake_case(&ty.name);
+      writeln!(
+        self.code,
+        "fn enter_{name}(&mut self, {name}: {}) -> TraverseOperate {{ TraverseOperate::Continue }}",
+        self.reference_type(ty)
+      )
+      .unwrap();
+    }
+    for ty in types {
+      let name = snake_case(&ty.name);
+      writeln!(
+        self.code,
+        "fn exit_{name}(&mut self, {name}: {}) {{}}",
+        self.reference_type(ty)
+      )
+      .unwrap();
+    }
+    self.code.push_str("}\n");
+
+    for ty in types {
+      self.walk_function(ty);
+    }
+  }
+
+  fn walk_function(&mut self, ty: &AstType) {
+    let name = snake_case(&ty.name);
+    let suffix = self.suffix();
+    writeln!(
+      self.code,
+      "\npub fn traverse_{name}{suffix}<'a>({name}: {}, traverse: &mut impl {}<'a>) {{",
+      self.reference_type(ty),
+      self.trait_name()
+    )
+    .unwrap();
+    writeln!(
+      self.code,
+      "if traverse.enter_{name}({name}) != TraverseOperate::Skip {{"
+    )
+    .unwrap();
+    match &ty.kind {
+      Kind::Struct(fields) => {
+        for (field, shape) in fields {
+          self.walk(shape, &format!("{name}.{field}"), false, field);
+        }
+      }
+      Kind::Enum(variants) => {
+        writeln!(self.code, "match {name} {{").unwrap();
+        for (variant, inner) in variants {
+          let binding = snake_case(inner);
+          writeln!(
+            self.code,
+            "{}::{variant}({binding}) => traverse_{binding}{suffix}({binding}, traverse),",
+            ty.name
+          )
+          .unwrap();
+        }
+        self.code.push_str("}\n");
+      }
+      Kind::Alias(shape) => self.walk(shape, &name, true, &name),
+    }
+    writeln!(self.code, "traverse.exit_{name}({name});\n}}\n}}").unwrap();
+  }
+
+  /// Walk the AST types held by `place`, a reference already when `borrowed`.
+  fn walk(&mut self, shape: &Shape, place: &str, borrowed: bool, binding: &str) {
+    let place = match (borrowed, self.mutable) {
+      (false, _) => format!("{}{place}", self.reference()),
+      // reborrow, the reference is used again to exit
+      (true, true) => format!("&mut *{place}"),
+      (true, false) => place.to_string(),
+    };
+    match shape {
+      Shape::Node(name) => {
+        writeln!(
+          self.code,
+          "traverse_{}{}({place}, traverse);",
+          snake_case(name),
+          self.suffix()
+        )
+        .unwrap();
+      }
+      Shape::Option(inner) => {
+        writeln!(self.code, "if let Some({binding}) = {place} {{").unwrap();
+        self.walk(inner, binding, true, binding);
+        self.code.push_str("}\n");
+      }
+      Shape::Vec(inner) => {
+        let item = snake_case(inner.name());
+        writeln!(self.code, "for {item} in {place} {{").unwrap();
+        self.walk(inner, &item, true, &item);
+        self.code.push_str("}\n");
+      }
+    }
+  }
+}
+
+fn snake_case(name: &str) -> String {
+  let mut snake = String::new();
+  for (index, c) in name.char_indices() {
+    if c.is_ascii_uppercase() && index > 0 {
+      snake.push('_');
+    }
+    snake.push(c.to_ascii_lowercase());
+  }
+  snake
+}
+
+/// Format code with rustfmt and the configuration of the repository.
+fn format(root: &Path, code: &str) -> String {
+  let mut rustfmt = Command::new("rustfmt")
+    .args(["--edition", "2024", "--config-path"])
+    .arg(root.join(".rustfmt.toml"))
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()
+    .expect("failed to run rustfmt");
+  rustfmt
+    .stdin
+    .take()
+    .expect("rustfmt has a stdin")
+    .write_all(code.as_bytes())
+    .expect("failed to write to rustfmt");
+  let output = rustfmt.wait_with_output().expect("failed to run rustfmt");
+  assert!(
+    output.status.success(),
+    "rustfmt failed on the generated code"
+  );
+  String::from_utf8(output.stdout).expect("rustfmt outputs UTF-8")
+}
+
+#[cfg(test)]
+mod test {
+  use std::fs;
+
+  use super::{TRAVERSE_PATH, generate, root};
+
+  #[test]
+  fn traverse_is_up_to_date() {
+    let root = root();
+    let generated = fs::read_to_string(root.join(TRAVERSE_PATH)).unwrap();
+    assert!(
+      generated == generate(&root),
+      "`{TRAVERSE_PATH}` is out of date with the AST, run `just codegen`"
+    );
+  }
+}